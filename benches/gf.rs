@@ -4,6 +4,7 @@ use criterion::criterion_group;
 use criterion::criterion_main;
 use criterion::Criterion;
 use criterion::BatchSize;
+use criterion::Throughput;
 use std::iter;
 use std::convert::TryFrom;
 use ::gf256::gf::gf;
@@ -159,5 +160,34 @@ fn bench_gfmul(c: &mut Criterion) {
     bench_div!(group, "gf2p64_barret_div",          gf2p64_barret);
 }
 
-criterion_group!(benches, bench_gfmul);
+macro_rules! bench_slice_mul_acc {
+    ($group:expr, $name:expr, $gf:ident) => {
+        let mut xs = xorshift64(42).map(|x| $gf(x as _));
+        let mut ys = xorshift64(42*42).map(|y| $gf(y as _));
+        let c = $gf(0x1234567890abcdef_u64 as _);
+        $group.bench_function($name, |b| b.iter_batched_ref(
+            || (
+                (&mut xs).take(SIZE).collect::<Vec<_>>(),
+                (&mut ys).take(SIZE).collect::<Vec<_>>(),
+            ),
+            |(x, y)| $gf::slice_mul_acc(x, y, c),
+            BatchSize::SmallInput
+        ));
+    }
+}
+
+fn bench_gfslice(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gfslice");
+
+    // size to bench, in elements
+    const SIZE: usize = 64*1024;
+    group.throughput(Throughput::Elements(SIZE as u64));
+
+    bench_slice_mul_acc!(group, "gf256_table_slice_mul_acc",   gf256_table);
+    bench_slice_mul_acc!(group, "gf2p16_barret_slice_mul_acc", gf2p16_barret);
+    bench_slice_mul_acc!(group, "gf2p32_barret_slice_mul_acc", gf2p32_barret);
+    bench_slice_mul_acc!(group, "gf2p64_barret_slice_mul_acc", gf2p64_barret);
+}
+
+criterion_group!(benches, bench_gfmul, bench_gfslice);
 criterion_main!(benches);
@@ -10,6 +10,8 @@ use flate2::Compression;
 use flate2::write::DeflateEncoder;
 use std::io::Write;
 use std::mem::size_of;
+use rand_core::RngCore;
+use rand_core::block::BlockRng;
 
 
 #[allow(dead_code)]
@@ -152,6 +154,17 @@ fn bench_lfsr(c: &mut Criterion) {
     group.bench_function("lfsr64_small_table_barret", |b| b.iter(
         || buffer.fill_with(|| lfs64_small_table_barret.next(64))
     ));
+
+    // rng timings, unbuffered vs buffered with BlockRng
+    let mut lfs64_rng = gf256::lfsr::Lfsr64::new(0x123456789abcdef0);
+    group.bench_function("lfsr64_rng", |b| b.iter(
+        || buffer.fill_with(|| lfs64_rng.next_u64())
+    ));
+
+    let mut lfs64_block_rng = BlockRng::new(gf256::lfsr::Lfsr64::new(0x123456789abcdef0));
+    group.bench_function("lfsr64_block_rng", |b| b.iter(
+        || buffer.fill_with(|| lfs64_block_rng.next_u64())
+    ));
 }
 
 fn bench_lfsr_compressability(c: &mut Criterion<Compressability>) {
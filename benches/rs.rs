@@ -7,6 +7,7 @@ use criterion::BatchSize;
 use criterion::Throughput;
 use std::iter;
 use std::collections::HashSet;
+use ::gf256::rs::rs255w223;
 
 #[allow(dead_code)]
 #[allow(unused_attributes)]
@@ -184,6 +185,83 @@ fn bench_rs(c: &mut Criterion) {
         },
         BatchSize::SmallInput
     ));
+
+    // the above use the example implementation, also bench gf256's
+    // Reed-Solomon (255, 223), which precomputes products of its generator
+    // polynomial
+    let mut xs = xorshift64(42);
+    group.bench_function("rs255w223_encode", |b| b.iter_batched_ref(
+        || (&mut xs).take(SIZE).map(|x| x as u8).collect::<Vec<u8>>(),
+        |data| {
+            data.chunks(rs255w223::DATA_SIZE)
+                .map(|chunk| {
+                    let mut chunk = Vec::from(chunk);
+                    chunk.resize(chunk.len() + rs255w223::ECC_SIZE, 0);
+                    rs255w223::encode(&mut chunk);
+                    chunk
+                })
+                .collect::<Vec<_>>()
+        },
+        BatchSize::SmallInput
+    ));
+
+    let mut xs = xorshift64(42);
+    group.bench_function("rs255w223_correct_none", |b| b.iter_batched_ref(
+        || {
+            let data = (&mut xs).take(SIZE).map(|x| x as u8).collect::<Vec<u8>>();
+            data.chunks(rs255w223::DATA_SIZE)
+                .map(|chunk| {
+                    let mut chunk = Vec::from(chunk);
+                    chunk.resize(chunk.len() + rs255w223::ECC_SIZE, 0);
+                    rs255w223::encode(&mut chunk);
+                    chunk
+                })
+                .collect::<Vec<_>>()
+        },
+        |data| {
+            for chunk in data.iter_mut() {
+                assert!(rs255w223::is_correct(chunk));
+            }
+        },
+        BatchSize::SmallInput
+    ));
+
+    // encode/check many codewords side-by-side, which lets us use SIMD
+    let mut xs = xorshift64(42);
+    group.bench_function("rs255w223_encode_blocks", |b| b.iter_batched_ref(
+        || {
+            let data = (&mut xs).take(SIZE).map(|x| x as u8).collect::<Vec<u8>>();
+            data.chunks(rs255w223::DATA_SIZE)
+                .map(|chunk| {
+                    let mut chunk = Vec::from(chunk);
+                    chunk.resize(chunk.len() + rs255w223::ECC_SIZE, 0);
+                    chunk
+                })
+                .collect::<Vec<_>>()
+        },
+        |data| rs255w223::encode_blocks(data),
+        BatchSize::SmallInput
+    ));
+
+    let mut xs = xorshift64(42);
+    group.bench_function("rs255w223_correct_none_blocks", |b| b.iter_batched_ref(
+        || {
+            let data = (&mut xs).take(SIZE).map(|x| x as u8).collect::<Vec<u8>>();
+            let mut data = data.chunks(rs255w223::DATA_SIZE)
+                .map(|chunk| {
+                    let mut chunk = Vec::from(chunk);
+                    chunk.resize(chunk.len() + rs255w223::ECC_SIZE, 0);
+                    chunk
+                })
+                .collect::<Vec<_>>();
+            rs255w223::encode_blocks(&mut data);
+            data
+        },
+        |data| {
+            assert!(rs255w223::is_correct_blocks(data).iter().all(|x| *x));
+        },
+        BatchSize::SmallInput
+    ));
 }
 
 criterion_group!(benches, bench_rs);
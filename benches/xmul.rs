@@ -39,6 +39,18 @@ fn bench_xmul(c: &mut Criterion) {
         |(x, y)| x.wrapping_mul(y),
         BatchSize::SmallInput
     ));
+
+    // batched hardware accelerated xmul (leveraging vpclmulqdq, etc)
+    let mut xs = xorshift64(42).map(p64);
+    let mut ys = xorshift64(42*42).map(p64);
+    group.bench_function("hardware_xmul_x4", |b| b.iter_batched(
+        || (
+            [xs.next().unwrap(), xs.next().unwrap(), xs.next().unwrap(), xs.next().unwrap()],
+            [ys.next().unwrap(), ys.next().unwrap(), ys.next().unwrap(), ys.next().unwrap()],
+        ),
+        |(x, y)| p64::widening_mul_x4(x, y),
+        BatchSize::SmallInput
+    ));
 }
 
 criterion_group!(benches, bench_xmul);
@@ -44,6 +44,29 @@ pub(crate) fn xmul_predicate() -> TokenStream {
     }
 }
 
+/// Predicate for when hardware xmul may be found at runtime, see
+/// gf256::internal::xmul::detect_xmul
+#[cfg(feature="crc")]
+pub(crate) fn runtime_xmul_predicate() -> TokenStream {
+    // runtime detection requires std
+    if cfg!(feature="no-xmul") || !cfg!(feature="std") {
+        quote! { any() }
+    } else {
+        quote! {
+            any(
+                all(
+                    target_arch="x86_64",
+                    not(target_feature="pclmulqdq")
+                ),
+                all(
+                    target_arch="aarch64",
+                    not(target_feature="neon")
+                )
+            )
+        }
+    }
+}
+
 /// Guess width of u type
 pub(crate) fn guess_width(u: &syn::Path) -> Option<usize> {
     if u.segments.len() == 1 {
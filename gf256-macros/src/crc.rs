@@ -16,11 +16,20 @@ use crate::common::*;
 
 // template files are relative to the current file
 const CRC_TEMPLATE: &'static str = include_str!("../templates/crc.rs");
+const CRC_KERNEL_TEMPLATE: &'static str = include_str!("../templates/crc_kernel.rs");
+
+// the most entries we're willing to generate for a correct table
+const CORRECT_TABLE_LIMIT: u128 = 1 << 16;
 
 
 #[derive(Debug, FromMeta)]
 struct CrcArgs {
-    polynomial: U128Wrapper,
+    #[darling(default)]
+    polynomial: Option<U128Wrapper>,
+    #[darling(default)]
+    polynomial_reversed: Option<U128Wrapper>,
+    #[darling(default)]
+    polynomial_koopman: Option<U128Wrapper>,
 
     #[darling(default)]
     u: Option<syn::Path>,
@@ -35,6 +44,16 @@ struct CrcArgs {
     reflected: Option<bool>,
     #[darling(default)]
     xor: Option<U128Wrapper>,
+    #[darling(default)]
+    init: Option<U128Wrapper>,
+
+    #[darling(default)]
+    check: Option<U128Wrapper>,
+
+    #[darling(default)]
+    correct: Option<usize>,
+    #[darling(default)]
+    burst: Option<usize>,
 
     #[darling(default)]
     naive: bool,
@@ -44,6 +63,20 @@ struct CrcArgs {
     small_table: bool,
     #[darling(default)]
     barret: bool,
+    #[darling(default)]
+    slice_by_8: bool,
+    #[darling(default)]
+    slice_by_16: bool,
+    #[darling(default)]
+    folding: bool,
+
+    #[darling(default)]
+    adaptive: bool,
+    #[darling(default)]
+    threshold: Option<usize>,
+
+    #[darling(default)]
+    lazy: bool,
 }
 
 pub fn crc(
@@ -61,31 +94,123 @@ pub fn crc(
         }
     };
 
-    let width = {
-        // default to 1 less than the width of the given polynomial, this
-        // is the only width that would really work
-        let polynomial = args.polynomial.0;
-        (128-usize::try_from(polynomial.leading_zeros()).unwrap()) - 1
+    // normalize polynomial notations, the width of a polynomial's full
+    // notation is always 1 more than the width of the CRC
+    let mut polynomials = vec![];
+    if let Some(polynomial) = args.polynomial.as_ref() {
+        polynomials.push(("polynomial", polynomial.0));
+    }
+    if let Some(polynomial) = args.polynomial_reversed.as_ref() {
+        // reversed notation drops the leading term and reverses the rest,
+        // so the constant term ends up as the most-significant bit
+        let polynomial = polynomial.0;
+        if polynomial == 0 {
+            panic!("invalid polynomial_reversed for macro crc, must be non-zero");
+        }
+        let width = 128-usize::try_from(polynomial.leading_zeros()).unwrap();
+        if width >= 128 {
+            panic!("invalid polynomial_reversed for macro crc, width must be < 128");
+        }
+        let polynomial = (polynomial.reverse_bits() >> (128-width)) | (1u128 << width);
+        polynomials.push(("polynomial_reversed", polynomial));
+    }
+    if let Some(polynomial) = args.polynomial_koopman.as_ref() {
+        // Koopman notation drops the constant term, which must be 1
+        let polynomial = polynomial.0;
+        if polynomial >> 127 != 0 {
+            panic!("invalid polynomial_koopman for macro crc, width must be < 128");
+        }
+        polynomials.push(("polynomial_koopman", (polynomial << 1) | 1));
+    }
+
+    let polynomial = match polynomials.first() {
+        Some((_, polynomial)) => *polynomial,
+        None => panic!("missing polynomial for macro crc (polynomial, polynomial_reversed, polynomial_koopman?)"),
     };
 
+    let width_of = |polynomial: u128| -> usize {
+        (128-usize::try_from(polynomial.leading_zeros()).unwrap()).saturating_sub(1)
+    };
+
+    // multiple notations are allowed as a sanity check, but they must agree
+    for (name, other) in polynomials.iter().skip(1) {
+        if *other != polynomial {
+            panic!(
+                "conflicting polynomials for macro crc, {} implies 0x{:x} (width {}), \
+                but {} implies 0x{:x} (width {})",
+                polynomials[0].0, polynomial, width_of(polynomial),
+                name, other, width_of(*other)
+            );
+        }
+    }
+
+    // default to 1 less than the width of the given polynomial, this
+    // is the only width that would really work
+    let width = width_of(polynomial);
+
+    // init is given in the same bit-order as CRC catalogues, so reflected
+    // CRCs need init reflected to match their reflected state, note xor is
+    // applied after reflecting the output, so it's already in our bit-order
+    let xor = args.xor.as_ref().map(|xor| xor.0)
+        .unwrap_or_else(|| (1u128 << width) - 1);
+    let init = match args.init.as_ref() {
+        Some(init) if args.reflected.unwrap_or(true) => {
+            init.0.reverse_bits() >> (128-width)
+        }
+        Some(init) => init.0,
+        None => xor,
+    };
+    // the Checksum trait reports init as given, in the CRC catalogues'
+    // bit-order
+    let init_param = match args.init.as_ref() {
+        Some(init) => init.0,
+        None if args.reflected.unwrap_or(true) => {
+            xor.reverse_bits() >> (128-width)
+        }
+        None => xor,
+    };
+
+    // lazy only applies to table mode, so lazy implies table mode if no
+    // other mode is requested
+    let args_table = args.table || (args.lazy && !(
+        args.naive || args.small_table || args.barret
+            || args.slice_by_8 || args.slice_by_16 || args.folding
+            || args.adaptive
+    ));
+
+    if args.adaptive && (
+        args.naive || args_table || args.small_table || args.barret
+            || args.slice_by_8 || args.slice_by_16 || args.folding
+    ) {
+        panic!("invalid configuration of macro crc, adaptive chooses its own modes");
+    }
+
     // decide between implementations
-    let (naive, table, small_table, barret) = match
-        (args.naive, args.table, args.small_table, args.barret)
+    let (naive, table, small_table, barret, slice_by, folding) = match
+        (args.naive, args_table, args.small_table, args.barret, args.slice_by_8, args.slice_by_16, args.folding)
     {
         // choose mode if one is explicitly requested
-        (true,  false, false, false) => (true,  false, false, false),
-        (false, true,  false, false) => (false, true,  false, false),
-        (false, false, true,  false) => (false, false, true,  false),
-        (false, false, false, true ) => (false, false, false, true ),
+        (true,  false, false, false, false, false, false) => (true,  false, false, false, 0,  false),
+        (false, true,  false, false, false, false, false) => (false, true,  false, false, 0,  false),
+        (false, false, true,  false, false, false, false) => (false, false, true,  false, 0,  false),
+        (false, false, false, true,  false, false, false) => (false, false, false, true,  0,  false),
+        (false, false, false, false, true,  false, false) => (false, false, false, false, 8,  false),
+        (false, false, false, false, false, true,  false) => (false, false, false, false, 16, false),
+        (false, false, false, false, false, false, true ) => (false, false, false, false, 0,  true ),
+
+        // adaptive mode chooses between other modes at runtime, see below
+        (false, false, false, false, false, false, false)
+            if args.adaptive
+            => (false, false, false, false, 0, false),
 
         // if no-tables is enabled, stick to Barret reduction, it beats
         // a naive implementation even without hardware xmul
-        (false, false, false, false)
+        (false, false, false, false, false, false, false)
             if cfg!(feature="no-tables")
-            => (false, false, false, true),
+            => (false, false, false, true, 0, false),
 
         // if small-tables is enabled, we can use a smaller 16-element table
-        (false, false, false, false)
+        (false, false, false, false, false, false, false)
             if cfg!(feature="small-tables")
             => {
             // if xmul is available, Barret reduction is the fastest option for
@@ -100,7 +225,7 @@ pub fn crc(
             return output.into();
         }
 
-        (false, false, false, false) => {
+        (false, false, false, false, false, false, false) => {
             // if xmul is available, Barret reduction is the fastest option for
             // CRCs, otherwise a table-based approach wins
             let input = TokenStream::from(input);
@@ -114,7 +239,89 @@ pub fn crc(
         },
 
         // multiple modes selected?
-        _ => panic!("invalid configuration of macro crc (naive, table, small_table, barret?)"),
+        _ => panic!("invalid configuration of macro crc (naive, table, small_table, barret, slice_by_8, slice_by_16, folding?)"),
+    };
+
+    if args.lazy && !table {
+        panic!("invalid configuration of macro crc, lazy requires table mode");
+    }
+
+    // build a table of syndromes -> error patterns for correcting errors,
+    // note our syndromes are the remainder of the error pattern, which is
+    // why we can do this at compile time
+    let correct_table = match args.correct {
+        Some(max_len) => {
+            let burst = args.burst.unwrap_or(1);
+            if burst < 1 || burst > width {
+                panic!("invalid burst for macro crc, must be in the range 1..={}", width);
+            }
+            let reflect = |x: u128| -> u128 {
+                if args.reflected.unwrap_or(true) {
+                    x.reverse_bits() >> (128-width)
+                } else {
+                    x
+                }
+            };
+
+            // the table has an entry for every burst at every position, which
+            // grows quickly, so limit its size to keep compile times sane
+            let bits = 8*max_len;
+            let entries = (1..=burst)
+                .map(|len| {
+                    let patterns = if len == 1 { 1 } else { 1u128 << (len-2) };
+                    patterns.saturating_mul((bits+1).saturating_sub(len) as u128)
+                })
+                .fold(0u128, |a, b| a.saturating_add(b));
+            if entries > CORRECT_TABLE_LIMIT {
+                panic!(
+                    "macro crc can not correct bursts of up to {} bits in {} bytes, \
+                    the table would need {} entries, at most {} are supported",
+                    burst, max_len, entries, CORRECT_TABLE_LIMIT
+                );
+            }
+
+            // remainder of each bit position, counting from the last bit
+            let mut bit_syndromes = Vec::with_capacity(bits);
+            let mut r = polynomial & ((1u128 << width) - 1);
+            for _ in 0..bits {
+                bit_syndromes.push(r);
+                r <<= 1;
+                if r & (1u128 << width) != 0 {
+                    r ^= polynomial;
+                }
+            }
+
+            // all bursts, which must start and end with an error
+            let mut table = Vec::new();
+            for len in 1..=burst {
+                let patterns = if len == 1 { 1 } else { 1u128 << (len-2) };
+                for i in 0..patterns {
+                    let pattern = if len == 1 { 1 } else { (1u128 << (len-1)) | (i << 1) | 1 };
+                    for pos in 0..(bits+1).saturating_sub(len) {
+                        let mut syndrome = 0;
+                        for j in 0..len {
+                            if pattern & (1 << j) != 0 {
+                                syndrome ^= bit_syndromes[pos+j];
+                            }
+                        }
+                        table.push((reflect(syndrome), pos, pattern));
+                    }
+                }
+            }
+
+            // syndromes must be unique, otherwise we can't tell errors apart
+            table.sort();
+            if table.windows(2).any(|w| w[0].0 == w[1].0) {
+                panic!(
+                    "macro crc can not correct bursts of up to {} bits in {} bytes, \
+                    syndromes are not unique",
+                    burst, max_len
+                );
+            }
+
+            table
+        }
+        None => vec![],
     };
 
     // parse type
@@ -123,6 +330,28 @@ pub fn crc(
     let vis = ty.vis;
     let crc = ty.sig.ident;
 
+    let digest = Ident::new(
+        &format!("{}Digest", crc.to_string()
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(c) => c.to_uppercase().chain(chars).collect::<String>(),
+                    None => String::new(),
+                }
+            })
+            .collect::<String>()),
+        crc.span()
+    );
+    let crc_vectored = Ident::new(&format!("{}_vectored", crc.to_string()), crc.span());
+    let crc_const = Ident::new(&format!("{}_const", crc.to_string()), crc.span());
+    let combine = Ident::new(&format!("{}_combine", crc.to_string()), crc.span());
+    let uncombine = Ident::new(&format!("{}_uncombine", crc.to_string()), crc.span());
+    let patch = Ident::new(&format!("{}_patch", crc.to_string()), crc.span());
+    let batch = Ident::new(&format!("{}_batch", crc.to_string()), crc.span());
+    let par_crc = Ident::new(&format!("par_{}", crc.to_string()), crc.span());
+    let verify = Ident::new(&format!("{}_verify", crc.to_string()), crc.span());
+    let correct = Ident::new(&format!("{}_correct", crc.to_string()), crc.span());
     let __mod = Ident::new(&format!("__{}_gen", crc.to_string()), Span::call_site());
     let __u   = Ident::new(&format!("__{}_u",   crc.to_string()), Span::call_site());
     let __u2  = Ident::new(&format!("__{}_u2",  crc.to_string()), Span::call_site());
@@ -187,8 +416,39 @@ pub fn crc(
     // keyword replacements
     let replacements = HashMap::from_iter([
         ("__crc".to_owned(), TokenTree::Ident(crc.clone())),
+        ("__digest".to_owned(), TokenTree::Ident(digest.clone())),
+        ("__crc_vectored".to_owned(), TokenTree::Ident(crc_vectored.clone())),
+        ("__crc_const".to_owned(), TokenTree::Ident(crc_const.clone())),
+        ("__combine".to_owned(), TokenTree::Ident(combine.clone())),
+        ("__uncombine".to_owned(), TokenTree::Ident(uncombine.clone())),
+        ("__patch".to_owned(), TokenTree::Ident(patch.clone())),
+        ("__batch".to_owned(), TokenTree::Ident(batch.clone())),
+        ("__par_crc".to_owned(), TokenTree::Ident(par_crc.clone())),
+        ("__verify".to_owned(), TokenTree::Ident(verify.clone())),
+        ("__correct".to_owned(), TokenTree::Ident(correct.clone())),
+        ("__has_check".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", args.check.is_some()), Span::call_site())
+        )),
+        ("__check".to_owned(), TokenTree::Literal(
+            Literal::u128_unsuffixed(args.check.as_ref().map(|check| check.0).unwrap_or(0))
+        )),
+        ("__has_correct".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", args.correct.is_some()), Span::call_site())
+        )),
+        ("__correct_len".to_owned(), TokenTree::Literal(
+            Literal::usize_unsuffixed(args.correct.unwrap_or(0))
+        )),
+        ("__correct_table".to_owned(), TokenTree::Group(Group::new(Delimiter::Bracket, {
+            let entries = correct_table.iter().map(|(syndrome, pos, pattern)| {
+                let syndrome = Literal::u128_unsuffixed(*syndrome);
+                let pos = Literal::usize_unsuffixed(*pos);
+                let pattern = Literal::u128_unsuffixed(*pattern);
+                quote! { (#syndrome, #pos, #pattern) }
+            });
+            quote! { #(#entries),* }
+        }))),
         ("__polynomial".to_owned(), TokenTree::Literal(
-            Literal::u128_unsuffixed(args.polynomial.0)
+            Literal::u128_unsuffixed(polynomial)
         )),
         ("__width".to_owned(), TokenTree::Literal(
             Literal::usize_unsuffixed(width)
@@ -212,10 +472,13 @@ pub fn crc(
             Ident::new(&format!("{}", args.reflected.unwrap_or(true)), Span::call_site())
         )),
         ("__xor".to_owned(), TokenTree::Literal(
-            Literal::u128_unsuffixed(
-                args.xor.map(|xor| xor.0)
-                    .unwrap_or_else(|| (1u128 << width) - 1)
-            )
+            Literal::u128_unsuffixed(xor)
+        )),
+        ("__init".to_owned(), TokenTree::Literal(
+            Literal::u128_unsuffixed(init)
+        )),
+        ("__init_param".to_owned(), TokenTree::Literal(
+            Literal::u128_unsuffixed(init_param)
         )),
         ("__naive".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", naive), Span::call_site())
@@ -229,7 +492,28 @@ pub fn crc(
         ("__barret".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", barret), Span::call_site())
         )),
-        ("__crate".to_owned(), __crate),
+        ("__slice_by".to_owned(), TokenTree::Literal(
+            Literal::usize_unsuffixed(slice_by)
+        )),
+        ("__folding".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", folding), Span::call_site())
+        )),
+        ("__adaptive".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", args.adaptive), Span::call_site())
+        )),
+        ("__threshold".to_owned(), TokenTree::Literal(
+            Literal::usize_unsuffixed(args.threshold.unwrap_or(512))
+        )),
+        ("__lazy".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", args.lazy), Span::call_site())
+        )),
+        ("__std".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="std")), Span::call_site())
+        )),
+        ("__rayon".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="rayon")), Span::call_site())
+        )),
+        ("__crate".to_owned(), __crate.clone()),
     ]);
 
     // parse template
@@ -240,10 +524,237 @@ pub fn crc(
         }
     };
 
+    // compile the CRC calculation itself with a given mode, adaptive mode
+    // compiles several of these and chooses between them
+    let compile_kernel = |
+        name: &str,
+        (table, small_table, barret, slice_by, folding): (bool, bool, bool, usize, bool),
+        p: Option<TokenStream>,
+    | {
+        let mut replacements = replacements.clone();
+        replacements.insert("__crc_kernel".to_owned(), TokenTree::Ident(
+            Ident::new(name, Span::call_site())
+        ));
+        if args.adaptive {
+            for (flag, value) in [
+                ("__naive", false),
+                ("__table", table),
+                ("__small_table", small_table),
+                ("__barret", barret),
+                ("__folding", folding),
+            ] {
+                replacements.insert(flag.to_owned(), TokenTree::Ident(
+                    Ident::new(&format!("{}", value), Span::call_site())
+                ));
+            }
+            replacements.insert("__slice_by".to_owned(), TokenTree::Literal(
+                Literal::usize_unsuffixed(slice_by)
+            ));
+        }
+        if let Some(p) = p {
+            replacements.insert("__p".to_owned(), TokenTree::Group(
+                Group::new(Delimiter::None, p)
+            ));
+        }
+        compile_template(CRC_KERNEL_TEMPLATE, &replacements)
+    };
+
+    let kernels = if !args.adaptive {
+        match compile_kernel("crc_kernel", (table, small_table, barret, slice_by, folding), None) {
+            Ok(kernel) => quote! {
+                #[inline(always)]
+                #kernel
+            },
+            Err(err) => {
+                return err.to_compile_error().into();
+            }
+        }
+    } else {
+        // in adaptive mode, generate a CRC for short data and a CRC for long
+        // data, choosing modes the same way we choose a default mode
+        let xmul = xmul_predicate();
+        let barret_mode  = (false, false, true,  0, false);
+        let folding_mode = (false, false, false, 0, true );
+        let (short_mode, long_mode) = if cfg!(feature="no-tables") {
+            (barret_mode, barret_mode)
+        } else if cfg!(feature="small-tables") {
+            ((false, true, false, 0, false), (false, true, false, 0, false))
+        } else {
+            ((true, false, false, 0, false), (false, false, false, 8, false))
+        };
+
+        // if xmul isn't available at compile time, we may still find it at
+        // runtime, but this needs a polynomial type that uses it
+        let runtime_width = match (args.p.as_ref(), max(width.next_power_of_two(), 8)) {
+            (None, w) if w <= 64 => Some(w),
+            _ => None,
+        };
+        let runtime_p = runtime_width.map(|w| {
+            let p = Ident::new(&format!("runtime_p{}", w), Span::call_site());
+            quote! { #p }
+        });
+        let runtime = match runtime_p {
+            Some(_) => runtime_xmul_predicate(),
+            None => quote! { any() },
+        };
+
+        let kernels = vec![
+            compile_kernel("short_crc", barret_mode, None),
+            compile_kernel("long_crc", folding_mode, None),
+            compile_kernel("short_table_crc", short_mode, None),
+            compile_kernel("long_table_crc", long_mode, None),
+            compile_kernel("short_xmul_crc_kernel", barret_mode, runtime_p.clone()),
+            compile_kernel("long_xmul_crc_kernel", folding_mode, runtime_p.clone()),
+        ];
+        let [
+            short_crc,
+            long_crc,
+            short_table_crc,
+            long_table_crc,
+            short_xmul_crc,
+            long_xmul_crc,
+        ] = match kernels.into_iter().collect::<Result<Vec<_>, _>>() {
+            Ok(kernels) => <[TokenStream; 6]>::try_from(kernels).unwrap(),
+            Err(err) => {
+                return err.to_compile_error().into();
+            }
+        };
+
+        let runtime_dispatch = if let Some(w) = runtime_width {
+            let u = Ident::new(&format!("u{}", w), Span::call_site());
+            let runtime_p = Ident::new(&format!("runtime_p{}", w), Span::call_site());
+            let runtime_xmul = Ident::new(&format!("runtime_xmul{}", w), Span::call_site());
+            quote! {
+                // a private polynomial type using hardware xmul, this must
+                // only be used in the target_feature kernels below, which
+                // are only called after detect_xmul
+                #[cfg(all(not(#xmul), #runtime))]
+                #[inline(always)]
+                fn #runtime_xmul(a: #u, b: #u) -> (#u, #u) {
+                    // safety: only reachable from the kernels below
+                    unsafe { #__crate::internal::xmul::#runtime_xmul(a, b) }
+                }
+
+                #[cfg(all(not(#xmul), #runtime))]
+                #[#__crate::p::p(u=#u, xmul=#runtime_xmul)]
+                type #runtime_p;
+
+                #[cfg(all(not(#xmul), #runtime))]
+                #[inline(always)]
+                #short_xmul_crc
+
+                #[cfg(all(not(#xmul), #runtime))]
+                #[inline(always)]
+                #long_xmul_crc
+
+                #[cfg(all(not(#xmul), #runtime, target_arch="x86_64"))]
+                #[target_feature(enable="pclmulqdq")]
+                unsafe fn short_xmul_crc(data: &[u8], crc: super::#__u) -> super::#__u {
+                    short_xmul_crc_kernel(data, crc)
+                }
+
+                #[cfg(all(not(#xmul), #runtime, target_arch="x86_64"))]
+                #[target_feature(enable="pclmulqdq")]
+                unsafe fn long_xmul_crc(data: &[u8], crc: super::#__u) -> super::#__u {
+                    long_xmul_crc_kernel(data, crc)
+                }
+
+                #[cfg(all(not(#xmul), #runtime, target_arch="aarch64"))]
+                #[target_feature(enable="neon,aes")]
+                unsafe fn short_xmul_crc(data: &[u8], crc: super::#__u) -> super::#__u {
+                    short_xmul_crc_kernel(data, crc)
+                }
+
+                #[cfg(all(not(#xmul), #runtime, target_arch="aarch64"))]
+                #[target_feature(enable="neon,aes")]
+                unsafe fn long_xmul_crc(data: &[u8], crc: super::#__u) -> super::#__u {
+                    long_xmul_crc_kernel(data, crc)
+                }
+
+                #[cfg(all(not(#xmul), #runtime))]
+                #[inline]
+                fn short_crc(data: &[u8], crc: super::#__u) -> super::#__u {
+                    if #__crate::internal::xmul::detect_xmul() {
+                        // safety: we just checked that xmul is available
+                        unsafe { short_xmul_crc(data, crc) }
+                    } else {
+                        short_table_crc(data, crc)
+                    }
+                }
+
+                #[cfg(all(not(#xmul), #runtime))]
+                #[inline]
+                fn long_crc(data: &[u8], crc: super::#__u) -> super::#__u {
+                    if #__crate::internal::xmul::detect_xmul() {
+                        // safety: we just checked that xmul is available
+                        unsafe { long_xmul_crc(data, crc) }
+                    } else {
+                        long_table_crc(data, crc)
+                    }
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        quote! {
+            // if xmul is available, Barret reduction is the fastest option
+            // for short data, and folding the fastest for long data,
+            // otherwise a table-based approach wins
+            #[cfg(#xmul)]
+            #[inline]
+            #short_crc
+
+            #[cfg(#xmul)]
+            #[inline]
+            #long_crc
+
+            #[cfg(not(#xmul))]
+            #[inline]
+            #short_table_crc
+
+            #[cfg(not(#xmul))]
+            #[inline]
+            #long_table_crc
+
+            #[cfg(all(not(#xmul), not(#runtime)))]
+            use short_table_crc as short_crc;
+
+            #[cfg(all(not(#xmul), not(#runtime)))]
+            use long_table_crc as long_crc;
+
+            // otherwise we may still find xmul at runtime
+            #runtime_dispatch
+        }
+    };
+
+    let correct_use = if args.correct.is_some() {
+        quote! { #vis use #__mod::#correct; }
+    } else {
+        quote! {}
+    };
+
+    let par_crc_use = if cfg!(feature="rayon") {
+        quote! { #vis use #__mod::#par_crc; }
+    } else {
+        quote! {}
+    };
+
     let output = quote! {
         #(#attrs)* #vis use #__mod::#crc;
+        #vis use #__mod::#crc_vectored;
+        #vis use #__mod::#crc_const;
+        #vis use #__mod::#combine;
+        #vis use #__mod::#uncombine;
+        #vis use #__mod::#patch;
+        #vis use #__mod::#batch;
+        #vis use #__mod::#verify;
+        #correct_use
+        #par_crc_use
+        #vis use #__mod::#digest;
         mod #__mod {
             #template
+            #kernels
         }
 
         // overrides in parent's namespace
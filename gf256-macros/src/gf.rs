@@ -196,6 +196,9 @@ pub fn gf(
         ("__is_pw2ge8".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", width.is_power_of_two() && width >= 8), Span::call_site())
         )),
+        ("__zeroize".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="zeroize")), Span::call_site())
+        )),
         ("__is_usize".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", is_usize), Span::call_site())
         )),
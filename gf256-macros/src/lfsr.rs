@@ -11,6 +11,7 @@ use std::collections::HashMap;
 use quote::quote;
 use std::iter::FromIterator;
 use std::cmp::max;
+use std::cmp::min;
 use std::convert::TryFrom;
 use crate::common::*;
 
@@ -18,6 +19,127 @@ use crate::common::*;
 const LFSR_TEMPLATE: &'static str = include_str!("../templates/lfsr.rs");
 
 
+// Find the distinct prime factors of n, this is used to find the prime
+// factors of 2^width-1, which are needed to check if an LFSR is maximal.
+//
+fn prime_factors(n: u128) -> Vec<u128> {
+    // a + b mod n, without overflow, a and b must be < n
+    fn add_mod(a: u128, b: u128, n: u128) -> u128 {
+        if a >= n - b {
+            a - (n - b)
+        } else {
+            a + b
+        }
+    }
+
+    // a * b mod n, falling back to double-and-add if a*b overflows, which
+    // is possible when n >= 2^64
+    fn mul_mod(a: u128, b: u128, n: u128) -> u128 {
+        let (mut a, mut b) = (a % n, b % n);
+        if let Some(x) = a.checked_mul(b) {
+            return x % n;
+        }
+
+        let mut x = 0;
+        while b > 0 {
+            if b & 1 != 0 {
+                x = add_mod(x, a, n);
+            }
+            a = add_mod(a, a, n);
+            b >>= 1;
+        }
+        x
+    }
+
+    fn pow_mod(mut a: u128, mut e: u128, n: u128) -> u128 {
+        let mut x = 1 % n;
+        a %= n;
+        while e > 0 {
+            if e & 1 != 0 {
+                x = mul_mod(x, a, n);
+            }
+            a = mul_mod(a, a, n);
+            e >>= 1;
+        }
+        x
+    }
+
+    // Miller-Rabin, these bases are deterministic for n < 2^64, and
+    // extremely unlikely to be fooled beyond that
+    fn is_prime(n: u128) -> bool {
+        if n < 2 {
+            return false;
+        }
+        let bases = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+        for b in bases {
+            if n.is_multiple_of(b) {
+                return n == b;
+            }
+        }
+        let s = (n-1).trailing_zeros();
+        let d = (n-1) >> s;
+        'outer: for b in bases {
+            let mut x = pow_mod(b, d, n);
+            if x == 1 || x == n-1 {
+                continue;
+            }
+            for _ in 1..s {
+                x = mul_mod(x, x, n);
+                if x == n-1 {
+                    continue 'outer;
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    // Pollard's rho, n must be composite
+    fn find_factor(n: u128) -> u128 {
+        if n.is_multiple_of(2) {
+            return 2;
+        }
+        for c in 1.. {
+            let f = |x: u128| add_mod(mul_mod(x, x, n), c % n, n);
+            let (mut x, mut y, mut d) = (2, 2, 1);
+            while d == 1 {
+                x = f(x);
+                y = f(f(y));
+                d = gcd(x.abs_diff(y), n);
+            }
+            if d != n {
+                return d;
+            }
+        }
+        unreachable!()
+    }
+
+    fn gcd(mut a: u128, mut b: u128) -> u128 {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+
+    let mut factors = vec![];
+    let mut stack = vec![n];
+    while let Some(n) = stack.pop() {
+        if n == 1 {
+            continue;
+        } else if is_prime(n) {
+            factors.push(n);
+        } else {
+            let d = find_factor(n);
+            stack.push(d);
+            stack.push(n / d);
+        }
+    }
+    factors.sort();
+    factors.dedup();
+    factors
+}
+
+
 #[derive(Debug, FromMeta)]
 struct LfsrArgs {
     polynomial: U128Wrapper,
@@ -38,10 +160,24 @@ struct LfsrArgs {
     #[darling(default)]
     reflected: Option<bool>,
 
+    // LFSR configurations
+    #[darling(default)]
+    galois: bool,
+    #[darling(default)]
+    fibonacci: bool,
+
+    // output bit-order
+    #[darling(default)]
+    msb_first: bool,
+    #[darling(default)]
+    lsb_first: bool,
+
     // div/rem modes
     #[darling(default)]
     naive: bool,
     #[darling(default)]
+    sparse: bool,
+    #[darling(default)]
     table: bool,
     #[darling(default)]
     small_table: bool,
@@ -61,6 +197,10 @@ struct LfsrArgs {
     small_table_skip: bool,
     #[darling(default)]
     barret_skip: bool,
+
+    // generate verify_maximal
+    #[darling(default)]
+    maximal: bool,
 }
 
 pub fn lfsr(
@@ -85,36 +225,88 @@ pub fn lfsr(
         (128-usize::try_from(polynomial.leading_zeros()).unwrap()) - 1
     };
 
+    // sparse polynomials can be stepped multiple bits at a time with only
+    // a shift and xor per term, as long as the steps don't overlap the
+    // polynomial's terms
+    //
+    // note these may be as large as width, the template limits them further
+    // to avoid overflowing shifts in __u
+    let sparse_taps = (0..width)
+        .filter(|i| args.polynomial.0 & (1 << i) != 0)
+        .collect::<Vec<_>>();
+    let sparse_chunk = width - sparse_taps.iter().copied().max().unwrap_or(0);
+    let sparse_back_chunk = sparse_taps.iter().copied()
+        .filter(|t| *t > 0)
+        .min()
+        .unwrap_or(width);
+
+    // sparse mode is only worth it if we can step at least a byte at a
+    // time, after the template's limit of one less than the bits in __u,
+    // a custom __u is at least width bits
+    let u_width = match args.u {
+        Some(_) => width,
+        None => max(width.next_power_of_two(), 8),
+    };
+    let is_sparse = sparse_taps.len() <= 4 && min(sparse_chunk, u_width-1) >= 8;
+
     // decide between div/rem modes
-    let (naive, table, small_table, barret, table_barret, small_table_barret) = match
-        (args.naive, args.table, args.small_table, args.barret, args.table_barret, args.small_table_barret)
+    let (naive, sparse, table, small_table, barret, table_barret, small_table_barret) = match
+        (args.naive, args.sparse, args.table, args.small_table, args.barret, args.table_barret, args.small_table_barret)
     {
         // choose mode if one is explicitly requested
-        (true,  false, false, false, false, false) => (true,  false, false, false, false, false),
-        (false, true,  false, false, false, false) => (false, true,  false, false, false, false),
-        (false, false, true,  false, false, false) => (false, false, true,  false, false, false),
-        (false, false, false, true,  false, false) => (false, false, false, true,  false, false),
-        (false, false, false, false, true,  false) => (false, false, false, false, true,  false),
-        (false, false, false, false, false, true ) => (false, false, false, false, false, true ),
+        (true,  false, false, false, false, false, false) => (true,  false, false, false, false, false, false),
+        (false, true,  false, false, false, false, false) => (false, true,  false, false, false, false, false),
+        (false, false, true,  false, false, false, false) => (false, false, true,  false, false, false, false),
+        (false, false, false, true,  false, false, false) => (false, false, false, true,  false, false, false),
+        (false, false, false, false, true,  false, false) => (false, false, false, false, true,  false, false),
+        (false, false, false, false, false, true,  false) => (false, false, false, false, false, true,  false),
+        (false, false, false, false, false, false, true ) => (false, false, false, false, false, false, true ),
+
+        // if the polynomial is sparse, a handful of shifts and xors beats
+        // any table lookup
+        (false, false, false, false, false, false, false)
+            if is_sparse
+            => (false, true,  false, false, false, false, false),
 
         // if no-tables is enabled, naive is actually the fastest (Barret
         // reduction behaves uniquely terrible for LFSRs for some reason,
         // though Barret reduction for skipping is still the fastest)
-        (false, false, false, false, false, false)
+        (false, false, false, false, false, false, false)
             if cfg!(feature="no-tables")
-            => (true,  false, false, false, false, false),
+            => (true,  false, false, false, false, false, false),
 
         // if small-tables is enabled, we can use a smaller 16-element table
-        (false, false, false, false, false, false)
+        (false, false, false, false, false, false, false)
             if cfg!(feature="small-tables")
-            => (false, false, true,  false, false, false),
+            => (false, false, false, true,  false, false, false),
 
         // otherwise tables is the fastest
-        (false, false, false, false, false, false)
-            => (false, true,  false, false, false, false),
+        (false, false, false, false, false, false, false)
+            => (false, false, true,  false, false, false, false),
 
         // multiple modes selected?
-        _ => panic!("invalid configuration of macro lfsr (naive, table, small_table, barret, table_barret, small_table_barret?)"),
+        _ => panic!("invalid configuration of macro lfsr (naive, sparse, table, small_table, barret, table_barret, small_table_barret?)"),
+    };
+
+    // decide between LFSR configurations, note these generate the same
+    // sequence, they only differ in how the state maps to the output
+    let fibonacci = match (args.galois, args.fibonacci) {
+        (false, false) => false,
+        (true,  false) => false,
+        (false, true ) => true,
+
+        // multiple configurations selected?
+        _ => panic!("invalid configuration of macro lfsr (galois, fibonacci?)"),
+    };
+
+    // decide output bit-order, defaults to lsb-first if reflected
+    let lsb_first = match (args.msb_first, args.lsb_first) {
+        (false, false) => args.reflected.unwrap_or(false),
+        (true,  false) => false,
+        (false, true ) => true,
+
+        // multiple bit-orders selected?
+        _ => panic!("invalid configuration of macro lfsr (msb_first, lsb_first?)"),
     };
 
     // decide between skip modes
@@ -249,6 +441,20 @@ pub fn lfsr(
         ("__nonzeros".to_owned(), TokenTree::Literal(
             Literal::u128_unsuffixed((1u128 << width) - 1)
         )),
+        ("__maximal".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", args.maximal), Span::call_site())
+        )),
+        ("__period_factors".to_owned(), TokenTree::Group(Group::new(Delimiter::Bracket, {
+            // factoring 2^width-1 can be expensive, so only do this if
+            // we need to
+            let factors = if args.maximal {
+                prime_factors((1u128 << width) - 1)
+            } else {
+                vec![]
+            };
+            let factors = factors.into_iter().map(Literal::u128_unsuffixed);
+            quote! { #(#factors),* }
+        }))),
         ("__u".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
             quote! { super::#__u }
         }))),
@@ -270,9 +476,28 @@ pub fn lfsr(
         ("__reflected".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", args.reflected.unwrap_or(false)), Span::call_site())
         )),
+        ("__lsb_first".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", lsb_first), Span::call_site())
+        )),
+        ("__fibonacci".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", fibonacci), Span::call_site())
+        )),
         ("__naive".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", naive), Span::call_site())
         )),
+        ("__sparse".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", sparse), Span::call_site())
+        )),
+        ("__sparse_taps".to_owned(), TokenTree::Group(Group::new(Delimiter::Bracket, {
+            let taps = sparse_taps.iter().copied().map(Literal::usize_unsuffixed);
+            quote! { #(#taps),* }
+        }))),
+        ("__sparse_chunk".to_owned(), TokenTree::Literal(
+            Literal::usize_unsuffixed(sparse_chunk)
+        )),
+        ("__sparse_back_chunk".to_owned(), TokenTree::Literal(
+            Literal::usize_unsuffixed(sparse_back_chunk)
+        )),
         ("__table".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", table), Span::call_site())
         )),
@@ -300,6 +525,9 @@ pub fn lfsr(
         ("__barret_skip".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", barret_skip), Span::call_site())
         )),
+        ("__serde".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="serde")), Span::call_site())
+        )),
         ("__crate".to_owned(), __crate.clone()),
     ]);
 
@@ -95,9 +95,26 @@ pub fn p(
             // query target configuration and recurse back into our proc_macro
             let input = TokenStream::from(input);
             let xmul = xmul_predicate();
+
+            // without hardware xmul, we can still use a faster software xmul
+            // if our width matches one of the builtin types, unless tables are
+            // disabled
+            let fallback = if
+                !cfg!(feature="no-tables")
+                    && [8, 16, 32, 64, 128].contains(&width)
+                    && (is_usize || args.u.as_ref()
+                        .map(|u| guess_width(u) == Some(width))
+                        .unwrap_or(true))
+            {
+                let soft_xmul = Ident::new(&format!("soft_xmul{}", width), Span::call_site());
+                quote! { xmul=#__crate::internal::xmul::#soft_xmul }
+            } else {
+                quote! { naive }
+            };
+
             let output = quote! {
-                #[cfg_attr(#xmul,      #__crate::p::p(xmul,  #(#raw_args),*))]
-                #[cfg_attr(not(#xmul), #__crate::p::p(naive, #(#raw_args),*))]
+                #[cfg_attr(#xmul,      #__crate::p::p(xmul,      #(#raw_args),*))]
+                #[cfg_attr(not(#xmul), #__crate::p::p(#fallback, #(#raw_args),*))]
                 #input
             };
             return output.into();
@@ -117,6 +134,8 @@ pub fn p(
     let __u    = Ident::new(&format!("__{}_u",    p.to_string()), Span::call_site());
     let __i    = Ident::new(&format!("__{}_i",    p.to_string()), Span::call_site());
     let __xmul = Ident::new(&format!("__{}_xmul", p.to_string()), Span::call_site());
+    let __xmul4 = Ident::new(&format!("__{}_xmul4", p.to_string()), Span::call_site());
+    let __xmul_acc = Ident::new(&format!("__{}_xmul_acc", p.to_string()), Span::call_site());
 
     // overrides in paren't namespace
     let mut overrides = vec![];
@@ -154,8 +173,12 @@ pub fn p(
         }
         Some(darling::util::Override::Inherit) => {
             let xmul = TokenTree::Ident(Ident::new(&format!("xmul{}", width), Span::call_site()));
+            let xmul4 = TokenTree::Ident(Ident::new(&format!("xmul{}x4", width), Span::call_site()));
+            let xmul_acc = TokenTree::Ident(Ident::new(&format!("xmul{}_acc", width), Span::call_site()));
             overrides.push(quote! {
                 use #__crate::internal::xmul::#xmul as #__xmul;
+                use #__crate::internal::xmul::#xmul4 as #__xmul4;
+                use #__crate::internal::xmul::#xmul_acc as #__xmul_acc;
             })
         }
         None => {
@@ -163,6 +186,11 @@ pub fn p(
         }
     };
 
+    // batched xmul and fused multiply-accumulate are only available with our
+    // builtin xmul, custom xmul functions fall back to multiple operations
+    let has_xmul4 = matches!(args.xmul, Some(darling::util::Override::Inherit));
+    let has_xmul_acc = has_xmul4;
+
     // keyword replacements
     let replacements = HashMap::from_iter([
         ("__p".to_owned(), TokenTree::Ident(p.clone())),
@@ -181,9 +209,21 @@ pub fn p(
         ("__i".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
             quote! { super::#__i }
         }))),
+        ("__has_xmul4".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", has_xmul4), Span::call_site())
+        )),
         ("__xmul".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
             quote! { super::#__xmul }
         }))),
+        ("__xmul4".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
+            quote! { super::#__xmul4 }
+        }))),
+        ("__has_xmul_acc".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", has_xmul_acc), Span::call_site())
+        )),
+        ("__xmul_acc".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
+            quote! { super::#__xmul_acc }
+        }))),
         ("__crate".to_owned(), __crate),
     ]);
 
@@ -23,6 +23,11 @@ struct RaidArgs {
     gf: Option<syn::Path>,
     #[darling(default)]
     u: Option<syn::Path>,
+    #[darling(default)]
+    width: Option<usize>,
+
+    #[darling(default)]
+    md: bool,
 }
 
 pub fn raid(
@@ -40,9 +45,6 @@ pub fn raid(
         }
     };
 
-    // only up to 2 parity blocks are currently supported
-    assert!(args.parity <= 3);
-
     // parse type
     let ty = parse_macro_input!(input as syn::ItemMod);
     let attrs = ty.attrs;
@@ -52,6 +54,28 @@ pub fn raid(
     let __gf = Ident::new(&format!("__{}_gf", raid.to_string()), Span::call_site());
     let __u  = Ident::new(&format!("__{}_u",  raid.to_string()), Span::call_site());
 
+    // Linux md compatibility requires GF(2^8) with polynomial 0x11d and
+    // generator 2 over bytes, which is our default field, so we just need
+    // to make sure nothing else is overridden
+    if args.md && (args.gf.is_some() || args.u.is_some() || args.width.is_some()) {
+        panic!("invalid configuration of macro raid, md is incompatible with gf, u, and width");
+    }
+    if args.md && args.parity > 2 {
+        panic!("invalid configuration of macro raid, md only supports up to 2 parity blocks");
+    }
+
+    // find the width of our default field, wider fields allow more blocks
+    if args.width.is_some() && args.gf.is_some() {
+        panic!("invalid configuration of macro raid, can't specify both gf and width");
+    }
+    let (gf, u) = match args.width.unwrap_or(8) {
+        8  => (Ident::new("gf256",  Span::call_site()), Ident::new("u8",  Span::call_site())),
+        16 => (Ident::new("gf2p16", Span::call_site()), Ident::new("u16", Span::call_site())),
+        32 => (Ident::new("gf2p32", Span::call_site()), Ident::new("u32", Span::call_site())),
+        64 => (Ident::new("gf2p64", Span::call_site()), Ident::new("u64", Span::call_site())),
+        _  => panic!("invalid width for macro raid, must be 8, 16, 32, or 64"),
+    };
+
     // overrides in parent's namespace
     let mut overrides = vec![];
     match args.gf.as_ref() {
@@ -62,7 +86,7 @@ pub fn raid(
         }
         None => {
             overrides.push(quote! {
-                use #__crate::gf::gf256 as #__gf;
+                use #__crate::gf::#gf as #__gf;
             });
         }
     }
@@ -72,7 +96,7 @@ pub fn raid(
                 use #u as #__u;
             })
         }
-        None => {
+        None if args.gf.is_some() => {
             // default to u8, we can't do any better since we don't really have
             // a way to infer the underlying u-type of __gf
             //
@@ -80,10 +104,19 @@ pub fn raid(
             // currently not supported
             // https://github.com/rust-lang/rust/issues/8995
             //
+            // note an alias of <__gf as Field>::U doesn't work either, rustc
+            // has trouble normalizing it in where clauses
+            //
             overrides.push(quote! {
                 use u8 as #__u;
             });
         }
+        None => {
+            // default to the underlying unsigned type of our field
+            overrides.push(quote! {
+                use #u as #__u;
+            });
+        }
     }
 
     // keyword replacements
@@ -98,6 +131,15 @@ pub fn raid(
         ("__u".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
             quote! { super::#__u }
         }))),
+        ("__alloc".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="alloc")), Span::call_site())
+        )),
+        ("__rayon".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="rayon")), Span::call_site())
+        )),
+        ("__crc".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="crc")), Span::call_site())
+        )),
         ("__crate".to_owned(), __crate.clone()),
     ]);
 
@@ -25,6 +25,11 @@ struct RsArgs {
     gf: Option<syn::Path>,
     #[darling(default)]
     u: Option<syn::Path>,
+
+    #[darling(default)]
+    euclidean: bool,
+    #[darling(default)]
+    list: bool,
 }
 
 pub fn rs(
@@ -42,8 +47,9 @@ pub fn rs(
         }
     };
 
-    // gf256 is limited to 255 elements
-    assert!(args.block <= 255);
+    // note the block size is limited by the number of non-zero elements
+    // in the field, this is checked at compile-time in the template since
+    // we don't know the field's size here
     assert!(args.data <= args.block);
 
     // parse type
@@ -107,6 +113,21 @@ pub fn rs(
         ("__u".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
             quote! { super::#__u }
         }))),
+        ("__euclidean".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", args.euclidean), Span::call_site())
+        )),
+        ("__list".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", args.list), Span::call_site())
+        )),
+        ("__no_tables".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="no-tables")), Span::call_site())
+        )),
+        ("__alloc".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="alloc")), Span::call_site())
+        )),
+        ("__rayon".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="rayon")), Span::call_site())
+        )),
         ("__crate".to_owned(), __crate.clone()),
     ]);
 
@@ -23,6 +23,8 @@ struct ShamirArgs {
     #[darling(default)]
     u: Option<syn::Path>,
     #[darling(default)]
+    width: Option<usize>,
+    #[darling(default)]
     rng: Option<ExprWrapper>,
 }
 
@@ -51,6 +53,20 @@ pub fn shamir(
     let __u   = Ident::new(&format!("__{}_u",   shamir.to_string()), Span::call_site());
     let __rng = Ident::new(&format!("__{}_rng", shamir.to_string()), Span::call_site());
 
+    // find the width of our default field, wider fields allow more shares
+    if args.width.is_some() && args.gf.is_some() {
+        panic!("invalid configuration of macro shamir, can't specify both gf and width");
+    }
+    let width = args.width.unwrap_or(8);
+    let polynomial = match width {
+        8  => 0x11d,
+        16 => 0x1002d,
+        32 => 0x1000000af,
+        64 => 0x1000000000000001b,
+        _  => panic!("invalid width for macro shamir, must be 8, 16, 32, or 64"),
+    };
+    let polynomial = Literal::u128_unsuffixed(polynomial);
+
     // overrides in parent's namespace
     let mut overrides = vec![];
     match args.gf.as_ref() {
@@ -63,7 +79,7 @@ pub fn shamir(
             overrides.push(quote! {
                 // If not overridden, we need to create our own type in Barret mode here
                 // in order to ensure the finite-field operations are constant-time
-                #[#__crate::gf::gf(polynomial=0x11d, generator=0x02, barret)]
+                #[#__crate::gf::gf(polynomial=#polynomial, generator=0x02, barret)]
                 type #__gf;
             })
         }
@@ -75,15 +91,10 @@ pub fn shamir(
             })
         }
         None => {
-            // default to u8, we can't do any better since we don't really have
-            // a way to infer the underlying u-type of __gf
-            //
-            // we could use an inherent associated type in __gf, except they are
-            // currently not supported
-            // https://github.com/rust-lang/rust/issues/8995
-            //
+            // default to the underlying unsigned type of our field
             overrides.push(quote! {
-                use u8 as #__u;
+                #[allow(non_camel_case_types)]
+                type #__u = <#__gf as #__crate::traits::Field>::U;
             });
         }
     }
@@ -118,6 +129,15 @@ pub fn shamir(
         ("__rng".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
             quote! { super::#__rng }
         }))),
+        ("__std".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="std")), Span::call_site())
+        )),
+        ("__zeroize".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="zeroize")), Span::call_site())
+        )),
+        ("__alloc".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="alloc")), Span::call_site())
+        )),
         ("__crate".to_owned(), __crate.clone()),
     ]);
 
@@ -204,15 +204,41 @@
 //!   This is the most common implementation of CRCs you will find, due to its speed and
 //!   portability.
 //!
+//!   With the `lazy` option, the remainder table is built at runtime on first use
+//!   instead of being stored in the binary, trading a bit of startup time for flash.
+//!
 //! - In `small_table` mode, the same strategy as `table` mode is used, but with a 16
 //!   element  remainder table computer the remainder a nibble at a time.
 //!
+//! - In `slice_by_8` and `slice_by_16` modes, CRCs use 8 or 16 remainder tables,
+//!   where each table contains the remainder of a byte followed by some number of
+//!   zero bytes. This allows 8 or 16 bytes to be processed with independent table
+//!   lookups, which can be several times faster than `table` mode for large inputs,
+//!   at the cost of 8 or 16 times the table size.
+//!
 //! - In `barret` mode, CRCs use [Barret-reduction][barret-reduction] to efficiently
 //!   compute the remainder using only multiplication by precomputed constants.
 //!
 //!   This mode is especially effective when hardware carry-less multiplication
 //!   instructions are available.
 //!
+//! - In `folding` mode, CRCs fold blocks of the input into 4 independent lanes,
+//!   each two words wide, by multiplying each lane by precomputed powers of `x`
+//!   modulo the polynomial. This is the same technique used by Intel's and the
+//!   Linux kernel's carry-less multiplication based CRCs, and keeps several
+//!   multiplications in flight at once. Once the input is exhausted, the lanes
+//!   are folded together and reduced with Barret-reduction, which also handles
+//!   any remaining words/bytes.
+//!
+//!   This mode is only worth it for large inputs, and only if hardware carry-less
+//!   multiplication instructions are available.
+//!
+//! - In `adaptive` mode, CRCs choose between the above modes at runtime based on the
+//!   length of the input, using `barret` or `table` mode for short inputs, and
+//!   `folding` or `slice_by_8` mode for long inputs, depending on if hardware
+//!   carry-less multiplication is available. With the `std` feature, hardware
+//!   carry-less multiplication is also detected at runtime.
+//!
 //! If hardware carry-less multiplication is available, `barret` mode is the fastest
 //! option for CRCs, so CRC implementations will use `barret` by default.
 //!
@@ -244,6 +270,11 @@
 //! Philip Koopman also has a list of good CRC polynomials and their effective
 //! Hamming distances at various message lengths [here][crc-polynomials].
 //!
+//! If the `std` feature is enabled, gf256 can also compute the Hamming distance
+//! of a polynomial at a given message length with
+//! [`hamming_distance`](crate::crc::hamming_distance), though this is an
+//! exhaustive search and can be slow.
+//!
 //! Note you may see several different formats for CRC polynomials! Where the
 //! mathematically correct polynomial may be `0x104c11db7`, you may see a truncated
 //! `0x04c11db7` or `0x82608edb` representation to fit into 32-bits, or a
@@ -270,6 +301,25 @@
 //! [`crc32`](crate::crc::crc32) and [`crc32c`](crate::crc::crc32c).
 //! It's suggested to use [`crc32c`](crate::crc::crc32) for new applications.
 //!
+//! ## Catalog CRCs
+//!
+//! In addition to the above CRCs, gf256 provides a handful of CRCs commonly
+//! found in the wild, such as [`crc16_modbus`](crate::crc::crc16_modbus)
+//! and [`crc64_ecma`](crate::crc::crc64_ecma). These match the parameters
+//! and check values in the [catalogue of parametrised CRC
+//! algorithms][crc-catalogue]:
+//!
+//! ``` rust
+//! use gf256::crc::*;
+//!
+//! assert_eq!(crc5_usb(b"123456789", 0), 0x19);
+//! assert_eq!(crc8_maxim(b"123456789", 0), 0xa1);
+//! assert_eq!(crc16_ccitt_false(b"123456789", 0), 0x29b1);
+//! assert_eq!(crc24_openpgp(b"123456789", 0), 0x21cf02);
+//! assert_eq!(crc32k(b"123456789", 0), 0x2d3dd0ae);
+//! assert_eq!(crc64_xz(b"123456789", 0), 0x995dc9bbdf1939fa);
+//! ```
+//!
 //!
 //! [crc-wiki]: https://en.wikipedia.org/wiki/Cyclic_redundancy_check
 //! [remainder]: https://en.wikipedia.org/wiki/Modulo_operation
@@ -278,6 +328,630 @@
 //! [koopman]: http://users.ece.cmu.edu/~koopman/roses/dsn04/koopman04_crc_poly_embedded.pdf
 //! [crc-polynomials]: https://users.ece.cmu.edu/~koopman/crc
 //! [crc-example]: https://github.com/geky/gf256/blob/master/examples/crc.rs
+//! [crc-catalogue]: https://reveng.sourceforge.io/crc-catalogue
+
+
+#[cfg(feature="std")]
+extern crate std;
+
+use core::fmt;
+#[cfg(feature="async")]
+use core::pin::Pin;
+#[cfg(feature="async")]
+use core::task::{Context, Poll, ready};
+
+
+/// Error codes for CRCs
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// A CRC can only correct errors if the resulting syndrome
+    /// uniquely identifies the error
+    ///
+    TooManyErrors,
+
+    /// The data is shorter than the CRC, or longer than the `correct` option
+    /// provided to the [`crc`] macro
+    ///
+    UnsupportedLength,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooManyErrors => write!(f, "Too many errors to correct"),
+            Error::UnsupportedLength => write!(f, "Unsupported length for correction"),
+        }
+    }
+}
+
+#[cfg(feature="std")]
+impl std::error::Error for Error {}
+
+
+/// A trait for CRC digests
+///
+/// This is implemented by all digests generated by the [`crc`] macro, and
+/// allows generic code, such as [`CrcWriter`] and [`CrcReader`], to be
+/// written over any CRC.
+///
+/// Note that the digests' inherent methods are usually more convenient
+/// when the type is known, this trait is only needed for generic code.
+///
+pub trait Digest {
+    /// The underlying unsigned type
+    type U: Copy;
+
+    /// Feed more data into the digest
+    fn update(&mut self, data: &[u8]);
+
+    /// Get the CRC of all data fed into the digest so far
+    fn finalize(&self) -> Self::U;
+
+    /// Reset the digest to its initial state
+    fn reset(&mut self);
+}
+
+/// A trait describing a CRC
+///
+/// This is implemented by all digests generated by the [`crc`] macro, and
+/// extends [`Digest`] with the CRC's parameters, so generic code can accept
+/// any CRC, and test CRCs uniformly:
+///
+/// ``` rust
+/// use gf256::crc::{Checksum, Crc32cDigest, Crc16CcittFalseDigest};
+///
+/// fn describe<C: Checksum>() -> (usize, u128, C::U) {
+///     (C::WIDTH, C::POLYNOMIAL, C::checksum(b"123456789"))
+/// }
+///
+/// assert_eq!(describe::<Crc32cDigest>(), (32, 0x11edc6f41, 0xe3069283));
+/// assert_eq!(describe::<Crc16CcittFalseDigest>(), (16, 0x11021, 0x29b1));
+/// ```
+///
+/// Note the parameters are stored as they are provided to the [`crc`] macro,
+/// in the same bit-order as CRC catalogues, even for reflected CRCs:
+///
+/// ``` rust
+/// use gf256::crc::{Checksum, Crc24BleDigest};
+///
+/// assert_eq!(Crc24BleDigest::REFLECTED, true);
+/// assert_eq!(Crc24BleDigest::INIT, 0x555555);
+/// assert_eq!(Crc24BleDigest::CHECK, 0xc25a56);
+/// ```
+///
+pub trait Checksum: Digest + Default {
+    /// The width of the CRC in bits
+    const WIDTH: usize;
+
+    /// The polynomial that defines the CRC, including the leading term
+    const POLYNOMIAL: u128;
+
+    /// Indicates if the CRC's bits are reversed
+    const REFLECTED: bool;
+
+    /// The bit-mask xored into the input CRC, in the same bit-order as the
+    /// `init` option, even for reflected CRCs
+    const INIT: Self::U;
+
+    /// The bit-mask xored into the output CRC
+    const XOR: Self::U;
+
+    /// The CRC of the ASCII string "123456789", the standard check value
+    /// used to identify CRCs
+    const CHECK: Self::U;
+
+    /// Calculate the CRC of a piece of data
+    #[inline]
+    fn checksum(data: &[u8]) -> Self::U {
+        let mut digest = Self::default();
+        digest.update(data);
+        digest.finalize()
+    }
+}
+
+
+/// A [`Write`](std::io::Write) adapter that computes a CRC of all data
+/// written through it.
+///
+/// This makes it easy to attach a checksum while writing, without needing
+/// to read the data back:
+///
+/// ``` rust
+/// use gf256::crc::{Crc32cDigest, CrcWriter};
+/// use std::io::Write;
+///
+/// let mut writer = CrcWriter::new(Vec::new(), Crc32cDigest::new());
+/// writer.write_all(b"Hello World!").unwrap();
+/// assert_eq!(writer.finalize(), 0xfe6cf1dc);
+/// assert_eq!(writer.into_inner(), b"Hello World!");
+/// ```
+///
+/// Note this requires the `std` feature.
+///
+#[cfg(feature="std")]
+#[derive(Debug, Clone)]
+pub struct CrcWriter<W, D> {
+    inner: W,
+    digest: D,
+}
+
+#[cfg(feature="std")]
+impl<W, D: Digest> CrcWriter<W, D> {
+    /// Create a writer that passes data through to `inner`, feeding
+    /// the digest as it goes.
+    #[inline]
+    pub fn new(inner: W, digest: D) -> Self {
+        Self { inner, digest }
+    }
+
+    /// Get the CRC of all data written so far.
+    #[inline]
+    pub fn finalize(&self) -> D::U {
+        self.digest.finalize()
+    }
+
+    /// Get a reference to the underlying digest.
+    #[inline]
+    pub fn digest(&self) -> &D {
+        &self.digest
+    }
+
+    /// Get a reference to the underlying writer.
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    ///
+    /// Note data written directly to the underlying writer does not
+    /// contribute to the CRC.
+    ///
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Get the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Get the underlying writer and digest.
+    #[inline]
+    pub fn into_parts(self) -> (W, D) {
+        (self.inner, self.digest)
+    }
+}
+
+#[cfg(feature="std")]
+impl<W: std::io::Write, D: Digest> std::io::Write for CrcWriter<W, D> {
+    /// Write to the underlying writer, only the bytes actually written
+    /// are fed into the digest.
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.digest.update(&buf[..n]);
+        Ok(n)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+
+/// A [`Read`](std::io::Read) adapter that computes a CRC of all data
+/// read through it.
+///
+/// This makes it easy to check a checksum while reading, without needing
+/// to read the data twice:
+///
+/// ``` rust
+/// use gf256::crc::{Crc32cDigest, CrcReader};
+/// use std::io::Read;
+///
+/// let mut reader = CrcReader::new(&b"Hello World!"[..], Crc32cDigest::new());
+/// let mut buf = Vec::new();
+/// reader.read_to_end(&mut buf).unwrap();
+/// assert_eq!(buf, b"Hello World!");
+/// assert_eq!(reader.finalize(), 0xfe6cf1dc);
+/// ```
+///
+/// Note this requires the `std` feature.
+///
+#[cfg(feature="std")]
+#[derive(Debug, Clone)]
+pub struct CrcReader<R, D> {
+    inner: R,
+    digest: D,
+}
+
+#[cfg(feature="std")]
+impl<R, D: Digest> CrcReader<R, D> {
+    /// Create a reader that passes data through from `inner`, feeding
+    /// the digest as it goes.
+    #[inline]
+    pub fn new(inner: R, digest: D) -> Self {
+        Self { inner, digest }
+    }
+
+    /// Get the CRC of all data read so far.
+    #[inline]
+    pub fn finalize(&self) -> D::U {
+        self.digest.finalize()
+    }
+
+    /// Get a reference to the underlying digest.
+    #[inline]
+    pub fn digest(&self) -> &D {
+        &self.digest
+    }
+
+    /// Get a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    ///
+    /// Note data read directly from the underlying reader does not
+    /// contribute to the CRC.
+    ///
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Get the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Get the underlying reader and digest.
+    #[inline]
+    pub fn into_parts(self) -> (R, D) {
+        (self.inner, self.digest)
+    }
+}
+
+#[cfg(feature="std")]
+impl<R: std::io::Read, D: Digest> std::io::Read for CrcReader<R, D> {
+    /// Read from the underlying reader, feeding any bytes read into
+    /// the digest.
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.digest.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+
+/// An [`AsyncWrite`](futures_io::AsyncWrite) adapter that computes a CRC
+/// of all data written through it.
+///
+/// This is the async equivalent of [`CrcWriter`]:
+///
+/// ``` rust
+/// # futures::executor::block_on(async {
+/// use gf256::crc::{Crc32cDigest, CrcAsyncWriter};
+/// use futures::io::AsyncWriteExt;
+///
+/// let mut writer = CrcAsyncWriter::new(Vec::new(), Crc32cDigest::new());
+/// writer.write_all(b"Hello World!").await.unwrap();
+/// assert_eq!(writer.finalize(), 0xfe6cf1dc);
+/// assert_eq!(writer.into_inner(), b"Hello World!");
+/// # });
+/// ```
+///
+/// Note this uses the `AsyncWrite` trait from the `futures-io` crate, tokio's
+/// `AsyncWrite` can be adapted with `tokio-util`'s compat module.
+///
+/// Note this requires the `async` feature.
+///
+#[cfg(feature="async")]
+#[derive(Debug, Clone)]
+pub struct CrcAsyncWriter<W, D> {
+    inner: W,
+    digest: D,
+}
+
+#[cfg(feature="async")]
+impl<W, D: Digest> CrcAsyncWriter<W, D> {
+    /// Create a writer that passes data through to `inner`, feeding
+    /// the digest as it goes.
+    #[inline]
+    pub fn new(inner: W, digest: D) -> Self {
+        Self { inner, digest }
+    }
+
+    /// Get the CRC of all data written so far.
+    #[inline]
+    pub fn finalize(&self) -> D::U {
+        self.digest.finalize()
+    }
+
+    /// Get a reference to the underlying digest.
+    #[inline]
+    pub fn digest(&self) -> &D {
+        &self.digest
+    }
+
+    /// Get a reference to the underlying writer.
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    ///
+    /// Note data written directly to the underlying writer does not
+    /// contribute to the CRC.
+    ///
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Get the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Get the underlying writer and digest.
+    #[inline]
+    pub fn into_parts(self) -> (W, D) {
+        (self.inner, self.digest)
+    }
+}
+
+#[cfg(feature="async")]
+impl<W, D> futures_io::AsyncWrite for CrcAsyncWriter<W, D>
+where
+    W: futures_io::AsyncWrite + Unpin,
+    D: Digest + Unpin,
+{
+    /// Write to the underlying writer, only the bytes actually written
+    /// are fed into the digest.
+    #[inline]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        let n = ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+        this.digest.update(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    #[inline]
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
+
+
+/// An [`AsyncRead`](futures_io::AsyncRead) adapter that computes a CRC
+/// of all data read through it.
+///
+/// This is the async equivalent of [`CrcReader`]:
+///
+/// ``` rust
+/// # futures::executor::block_on(async {
+/// use gf256::crc::{Crc32cDigest, CrcAsyncReader};
+/// use futures::io::AsyncReadExt;
+///
+/// let mut reader = CrcAsyncReader::new(&b"Hello World!"[..], Crc32cDigest::new());
+/// let mut buf = Vec::new();
+/// reader.read_to_end(&mut buf).await.unwrap();
+/// assert_eq!(buf, b"Hello World!");
+/// assert_eq!(reader.finalize(), 0xfe6cf1dc);
+/// # });
+/// ```
+///
+/// Note this uses the `AsyncRead` trait from the `futures-io` crate, tokio's
+/// `AsyncRead` can be adapted with `tokio-util`'s compat module.
+///
+/// Note this requires the `async` feature.
+///
+#[cfg(feature="async")]
+#[derive(Debug, Clone)]
+pub struct CrcAsyncReader<R, D> {
+    inner: R,
+    digest: D,
+}
+
+#[cfg(feature="async")]
+impl<R, D: Digest> CrcAsyncReader<R, D> {
+    /// Create a reader that passes data through from `inner`, feeding
+    /// the digest as it goes.
+    #[inline]
+    pub fn new(inner: R, digest: D) -> Self {
+        Self { inner, digest }
+    }
+
+    /// Get the CRC of all data read so far.
+    #[inline]
+    pub fn finalize(&self) -> D::U {
+        self.digest.finalize()
+    }
+
+    /// Get a reference to the underlying digest.
+    #[inline]
+    pub fn digest(&self) -> &D {
+        &self.digest
+    }
+
+    /// Get a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    ///
+    /// Note data read directly from the underlying reader does not
+    /// contribute to the CRC.
+    ///
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Get the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Get the underlying reader and digest.
+    #[inline]
+    pub fn into_parts(self) -> (R, D) {
+        (self.inner, self.digest)
+    }
+}
+
+#[cfg(feature="async")]
+impl<R, D> futures_io::AsyncRead for CrcAsyncReader<R, D>
+where
+    R: futures_io::AsyncRead + Unpin,
+    D: Digest + Unpin,
+{
+    /// Read from the underlying reader, feeding any bytes read into
+    /// the digest.
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        let n = ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.digest.update(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+}
+
+
+/// Find the minimum Hamming distance of a CRC polynomial for messages of
+/// a given length.
+///
+/// The Hamming distance is the smallest number of bit errors, anywhere in
+/// the message or trailing CRC, that can go undetected. `bits` is the length
+/// of the message, not including the CRC, in bits, and `polynomial` is the
+/// full polynomial, including the leading term, as accepted by the [`crc`]
+/// macro.
+///
+/// This returns `None` if no undetected errors exist with up to `max` bit
+/// errors, so the Hamming distance is greater than `max`:
+///
+/// ``` rust
+/// use gf256::crc::hamming_distance;
+///
+/// // HD=4 up to 119 bits, HD=2 after that
+/// assert_eq!(hamming_distance(0x107, 119, 4), Some(4));
+/// assert_eq!(hamming_distance(0x107, 120, 4), Some(2));
+/// assert_eq!(hamming_distance(0x107, 8, 3), None);
+/// ```
+///
+/// This is an exhaustive search, using a meet-in-the-middle strategy over
+/// the remainders of each bit error. It takes `O(n^ceil(max/2))` time and
+/// memory, where `n` is the length of the message plus CRC in bits, so it
+/// is only practical for small `max` or short messages. Philip Koopman's
+/// [CRC polynomial zoo][crc-polynomials] has precomputed results for many
+/// polynomials.
+///
+/// Note this requires the `std` feature.
+///
+/// [crc-polynomials]: https://users.ece.cmu.edu/~koopman/crc
+///
+#[cfg(feature="std")]
+pub fn hamming_distance(polynomial: u128, bits: usize, max: usize) -> Option<usize> {
+    use std::collections::HashSet;
+    use std::vec::Vec;
+
+    assert!(polynomial > 1, "crc polynomial must have degree >= 1");
+    let width = 127 - usize::try_from(polynomial.leading_zeros()).unwrap();
+    let n = bits + width;
+
+    // remainder of each bit error, x^i mod p
+    let mut remainders = Vec::with_capacity(n);
+    let mut r = 1u128;
+    for _ in 0..n {
+        remainders.push(r);
+        r <<= 1;
+        if r & (1 << width) != 0 {
+            r ^= polynomial;
+        }
+    }
+
+    // xor every combination of k remainders, stopping early if f
+    // returns true
+    fn combinations(
+        remainders: &[u128],
+        k: usize,
+        x: u128,
+        f: &mut impl FnMut(u128) -> bool
+    ) -> bool {
+        if k == 0 {
+            return f(x);
+        }
+
+        for i in k-1..remainders.len() {
+            if combinations(&remainders[..i], k-1, x ^ remainders[i], f) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    // an undetected error is a set of bit errors whose remainders xor to
+    // zero, we can find these by splitting the set in half and looking for
+    // collisions
+    //
+    // note that since we search in order of weight, any collision between
+    // overlapping halves would imply a smaller undetected error, which we
+    // would have already found
+    for w in 2..=max {
+        let a = w.div_ceil(2);
+        let b = w/2;
+        let mut seen = HashSet::new();
+        if a == b {
+            if combinations(&remainders, a, 0, &mut |x| !seen.insert(x)) {
+                return Some(w);
+            }
+        } else {
+            combinations(&remainders, a, 0, &mut |x| {
+                seen.insert(x);
+                false
+            });
+            if combinations(&remainders, b, 0, &mut |x| seen.contains(&x)) {
+                return Some(w);
+            }
+        }
+    }
+
+    None
+}
 
 
 /// A macro for generating custom CRC functions.
@@ -296,6 +970,12 @@
 /// The `crc` macro accepts a number of configuration options:
 ///
 /// - `polynomial` - The irreducible polynomial that defines the CRC.
+/// - `polynomial_reversed` - The polynomial in reversed notation, with the
+///   leading term dropped and the remaining bits reversed, for example
+///   `0xedb88320` for CRC32. This can be used instead of `polynomial`.
+/// - `polynomial_koopman` - The polynomial in Koopman notation, with the
+///   constant term dropped, for example `0x82608edb` for CRC32. This can
+///   be used instead of `polynomial`.
 /// - `u` - The underlying unsigned type, defaults to the minimum sized
 ///   unsigned type that fits the CRC state space.
 /// - `u2` - An unsigned type with twice the width, used as an intermediary type
@@ -308,12 +988,46 @@
 ///   defaults to true.
 /// - `xor` - A bit-mask to xor the input and output CRC with, defaults to
 ///   all ones.
+/// - `init` - A bit-mask to xor the input CRC with, defaults to `xor`. This
+///   can be used for CRCs where the initial value differs from the final
+///   xor, though note the output of these CRCs can no longer be passed
+///   back in as the input.
+/// - `check` - The expected CRC of the ASCII string "123456789", the
+///   macro will fail to compile if the CRC does not match. This is the
+///   standard check value found in CRC catalogs, and is useful for making
+///   sure the CRC's parameters are correct.
+/// - `correct` - Generate a `{name}_correct` function that can correct
+///   single-bit errors in data up to this many bytes, including the
+///   trailing CRC.
+/// - `burst` - The longest burst of errors, in bits, `{name}_correct` should
+///   be able to correct, defaults to 1. Note not all CRCs can correct all
+///   bursts, the macro will fail if errors can't be uniquely identified.
+///   The table needs about `8*correct*2^(burst-2)` entries, the macro will
+///   also fail if this exceeds 2^16 entries.
 /// - `naive` - Use a naive bitwise implementation.
 /// - `table` - Use precomputed CRC table. This is the default if hardware
 ///   polynomial multiplication is not available.
 /// - `small_table` - Use a small, 16-element CRC table.
 /// - `barret` - Use Barret-reduction with polynomial multiplication. This is
 ///   the default if hardware polynomial multiplication is available.
+/// - `slice_by_8` - Use 8 precomputed CRC tables to process 8 bytes at a time.
+/// - `slice_by_16` - Use 16 precomputed CRC tables to process 16 bytes at a time.
+/// - `folding` - Fold large inputs with polynomial multiplication, falling
+///   back to Barret-reduction for the tail. This is intended for large inputs
+///   when hardware polynomial multiplication is available.
+/// - `adaptive` - Choose between modes at runtime based on the length of the
+///   data. Short data uses `barret` mode if hardware polynomial multiplication
+///   is available and `table` mode otherwise, long data uses `folding` mode if
+///   hardware polynomial multiplication is available and `slice_by_8` mode
+///   otherwise. If hardware polynomial multiplication isn't enabled at compile
+///   time, the `std` feature allows adaptive mode to detect it at runtime.
+/// - `threshold` - The length of data, in bytes, at which `adaptive` mode
+///   switches to the mode for long data, defaults to 512.
+/// - `lazy` - Build the CRC table in RAM on first use, rather than storing it
+///   in the binary, this implies `table` mode. This may be useful on devices
+///   where flash is more limited than RAM. Note this requires atomic
+///   compare-and-swap instructions. If memory is limited in general, the
+///   `small_table` mode, or the `small-tables` feature, may be a better option.
 ///
 /// ``` rust,ignore
 /// # use ::gf256::*;
@@ -326,10 +1040,17 @@
 ///     p2=p64,
 ///     reflected=true,
 ///     xor=0xffffffff,
+///     init=0xffffffff,
 ///     // naive,
 ///     // table,
 ///     // small_table,
 ///     // barret,
+///     // slice_by_8,
+///     // slice_by_16,
+///     // folding,
+///     // adaptive,
+///     // threshold=512,
+///     // lazy,
 /// )]
 /// pub fn my_crc32() {}
 ///
@@ -338,6 +1059,152 @@
 /// # }
 /// ```
 ///
+/// Multiple notations can be provided as a sanity check, in which case the
+/// macro will fail to compile if they disagree:
+///
+/// ``` rust,ignore
+/// # use ::gf256::*;
+/// # use ::gf256::crc::crc;
+/// #[crc(polynomial=0x104c11db7, polynomial_reversed=0xedb88320, polynomial_koopman=0x82608edb)]
+/// pub fn my_crc32() {}
+///
+/// # fn main() {
+/// assert_eq!(my_crc32(b"Hello World!", 0), 0x1c291ca3);
+/// # }
+/// ```
+///
+/// The `crc` macro also generates a stateful digest struct alongside the
+/// function, named after the function in CamelCase, for computing the CRC
+/// over data that arrives in chunks. This digest implements the [`Digest`]
+/// and [`Checksum`] traits, and if the `std` feature is enabled,
+/// `std::io::Write`. The digest
+/// can also be used with [`CrcWriter`] and [`CrcReader`] to compute a CRC
+/// inline while writing or reading:
+///
+/// ``` rust,ignore
+/// # use ::gf256::*;
+/// # use ::gf256::crc::crc;
+/// #[crc(polynomial=0x11edc6f41)]
+/// pub fn my_crc32() {}
+///
+/// # fn main() {
+/// let mut digest = MyCrc32Digest::new();
+/// digest.update(b"Hello ");
+/// digest.update(b"World!");
+/// assert_eq!(digest.finalize(), 0xfe6cf1dc);
+/// # }
+/// ```
+///
+/// A `{name}_vectored` function is also generated, which accepts data
+/// split into multiple chunks, such as scatter-gather buffers, without
+/// needing to copy the data into one contiguous slice:
+///
+/// ``` rust,ignore
+/// let chunks: [&[u8]; 2] = [b"Hello ", b"World!"];
+/// assert_eq!(my_crc32_vectored(chunks, 0), 0xfe6cf1dc);
+/// ```
+///
+/// A `{name}_const` function is also generated, which is a bitwise
+/// implementation of the CRC that can be evaluated at compile time:
+///
+/// ``` rust,ignore
+/// const CRC: u32 = my_crc32_const(b"Hello World!", 0);
+/// assert_eq!(CRC, 0xfe6cf1dc);
+/// ```
+///
+/// The digest also provides the CRC's check value, the CRC of the ASCII
+/// string "123456789", as the associated constant `CHECK`. Passing the
+/// expected check value with the `check` option turns this into a
+/// compile-time self-test:
+///
+/// ``` rust,ignore
+/// #[crc(polynomial=0x11edc6f41, check=0xe3069283)]
+/// pub fn my_crc32() {}
+///
+/// assert_eq!(MyCrc32Digest::CHECK, 0xe3069283);
+/// ```
+///
+/// A `{name}_verify` function is also generated, which checks data with a
+/// trailing CRC against the CRC's residue:
+///
+/// ``` rust,ignore
+/// let mut data = [0u8; 16];
+/// data[..12].copy_from_slice(b"Hello World!");
+/// data[12..].copy_from_slice(&my_crc32(&data[..12], 0).to_le_bytes());
+/// assert!(my_crc32_verify(&data));
+/// ```
+///
+/// And a `{name}_combine` function is generated, which computes the CRC of
+/// two concatenated messages given only their CRCs and the length of the
+/// second message, in `O(log n)` time:
+///
+/// ``` rust,ignore
+/// let a = my_crc32(b"Hello ", 0);
+/// let b = my_crc32(b"World!", 0);
+/// assert_eq!(my_crc32_combine(a, b, 6), 0xfe6cf1dc);
+/// ```
+///
+/// Its inverse, `{name}_uncombine`, recovers the CRC of the first message
+/// given the CRC of the concatenation and the CRC of the second message:
+///
+/// ``` rust,ignore
+/// let ab = my_crc32(b"Hello World!", 0);
+/// let b = my_crc32(b"World!", 0);
+/// assert_eq!(my_crc32_uncombine(ab, b, 6), my_crc32(b"Hello ", 0));
+/// ```
+///
+/// A `{name}_patch` function is generated, which updates a CRC after a
+/// range of bytes in the data changes, given the old and new bytes, the
+/// offset of the change, and the total length of the data:
+///
+/// ``` rust,ignore
+/// let crc = my_crc32(b"Hello World!", 0);
+/// assert_eq!(my_crc32_patch(crc, b"World", b"Earth", 6, 12), my_crc32(b"Hello Earth!", 0));
+/// ```
+///
+/// A `{name}_batch` function is generated, which computes the CRCs of many
+/// independent messages at once, interleaving their computation to make
+/// better use of the CPU when checksumming many small messages:
+///
+/// ``` rust,ignore
+/// let data: [&[u8]; 2] = [b"Hello", b"World!"];
+/// let mut crcs = [0; 2];
+/// my_crc32_batch(&data, &mut crcs);
+/// assert_eq!(crcs, [my_crc32(b"Hello", 0), my_crc32(b"World!", 0)]);
+/// ```
+///
+/// If the `rayon` feature is enabled, a `par_{name}` function is also
+/// generated, which splits large inputs into chunks, computes their CRCs
+/// in parallel, and merges the results with `{name}_combine`:
+///
+/// ``` rust,ignore
+/// let data = vec![0x55u8; 1024*1024];
+/// assert_eq!(par_my_crc32(&data, 0), my_crc32(&data, 0));
+/// ```
+///
+/// If the `correct` option is provided, a `{name}_correct` function is
+/// generated, which can correct single-bit errors, or bursts of errors if
+/// `burst` is provided, by looking up the error's syndrome in a table
+/// built at compile time:
+///
+/// ``` rust,ignore
+/// #[crc(polynomial=0x11edc6f41, correct=64)]
+/// pub fn my_crc32() {}
+///
+/// let mut data = [0u8; 16];
+/// data[..12].copy_from_slice(b"Hello World!");
+/// data[12..].copy_from_slice(&my_crc32(&data[..12], 0).to_le_bytes());
+///
+/// data[1] ^= 0x04;
+/// assert_eq!(my_crc32_correct(&mut data), Ok(1));
+/// assert_eq!(&data[..12], b"Hello World!");
+/// ```
+///
+/// Note this uses up some of the CRC's ability to detect errors, and the
+/// table grows with both `correct` and `burst`, so this is best suited for
+/// short messages. Data shorter than the CRC, or longer than `correct`,
+/// returns `Error::UnsupportedLength`.
+///
 
 pub use gf256_macros::crc;
 
@@ -352,7 +1219,7 @@ pub use gf256_macros::crc;
 pub fn crc8() {}
 
 // HD=3,4, up to 32751+16 bits
-#[crc(polynomial=0x11021)]
+#[crc(polynomial=0x11021, check=0x906e)]
 pub fn crc16() {}
 
 // HD=3, up to 4294967263+32 bits
@@ -361,22 +1228,248 @@ pub fn crc16() {}
 // HD=6, up to 268+32 bits
 // HD=7, up to 171+32 bits
 // HD=8, up to 91+32 bits
-#[crc(polynomial=0x104c11db7)]
+#[crc(polynomial=0x104c11db7, check=0xcbf43926)]
 pub fn crc32() {}
 
 // HD=3,4, up to 2147483615+32 bits
 // HD=5,6, up to 5243+32 bits
 // HD=7,8, up to 177+32 bits
-#[crc(polynomial=0x11edc6f41)]
+#[crc(polynomial=0x11edc6f41, check=0xe3069283)]
 pub fn crc32c() {}
 
 // HD=3,4, up to 8589606850+64 bits
 // HD=5,6, up to 126701+64 bits
 // HD=7,7, up to ~33710+64 bits
-#[crc(polynomial=0x142f0e1eba9ea3693)]
+#[crc(polynomial=0x142f0e1eba9ea3693, check=0x995dc9bbdf1939fa)]
 pub fn crc64() {}
 
 
+// Catalog CRC functions
+//
+// These match common CRCs found in the wild, parameters and check values
+// from the catalogue of parametrised CRC algorithms:
+// https://reveng.sourceforge.io/crc-catalogue
+//
+// Note check values are the CRC of the ASCII string "123456789", these
+// are verified at compile time with the check option
+
+/// CRC-5/USB, used in USB token packets.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc5_usb(b"123456789", 0), 0x19);
+/// ```
+///
+#[crc(polynomial=0x25, reflected=true, xor=0x1f, check=0x19)]
+pub fn crc5_usb() {}
+
+/// CRC-8/AUTOSAR, used in AUTOSAR's E2E protection.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc8_autosar(b"123456789", 0), 0xdf);
+/// ```
+///
+#[crc(polynomial=0x12f, reflected=false, xor=0xff, check=0xdf)]
+pub fn crc8_autosar() {}
+
+/// CRC-8/MAXIM, also known as CRC-8/MAXIM-DOW, used in 1-Wire devices.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc8_maxim(b"123456789", 0), 0xa1);
+/// ```
+///
+#[crc(polynomial=0x131, reflected=true, xor=0, check=0xa1)]
+pub fn crc8_maxim() {}
+
+/// CRC-8/SMBUS, used in SMBus's packet error checking.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc8_smbus(b"123456789", 0), 0xf4);
+/// ```
+///
+#[crc(polynomial=0x107, reflected=false, xor=0, check=0xf4)]
+pub fn crc8_smbus() {}
+
+/// CRC-11/FLEXRAY, used in FlexRay frame headers.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc11_flexray(b"123456789", 0), 0x5a3);
+/// ```
+///
+/// Note this CRC's initial value differs from its final xor, so the
+/// output can't be passed back in as the input.
+///
+#[crc(polynomial=0xb85, reflected=false, xor=0, init=0x01a, check=0x5a3)]
+pub fn crc11_flexray() {}
+
+/// CRC-12/DECT, used in DECT cordless phones.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc12_dect(b"123456789", 0), 0xf5b);
+/// ```
+///
+#[crc(polynomial=0x180f, reflected=false, xor=0, check=0xf5b)]
+pub fn crc12_dect() {}
+
+/// CRC-16/ARC, also known as CRC-16/IBM.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc16_arc(b"123456789", 0), 0xbb3d);
+/// ```
+///
+#[crc(polynomial=0x18005, reflected=true, xor=0, check=0xbb3d)]
+pub fn crc16_arc() {}
+
+/// CRC-16/CCITT-FALSE, also known as CRC-16/IBM-3740.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc16_ccitt_false(b"123456789", 0), 0x29b1);
+/// ```
+///
+/// Note this CRC's initial value differs from its final xor, so the
+/// output can't be passed back in as the input.
+///
+#[crc(polynomial=0x11021, reflected=false, xor=0, init=0xffff, check=0x29b1)]
+pub fn crc16_ccitt_false() {}
+
+/// CRC-16/KERMIT, also known as CRC-16/CCITT.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc16_kermit(b"123456789", 0), 0x2189);
+/// ```
+///
+#[crc(polynomial=0x11021, reflected=true, xor=0, check=0x2189)]
+pub fn crc16_kermit() {}
+
+/// CRC-16/MODBUS, used in the Modbus protocol.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc16_modbus(b"123456789", 0), 0x4b37);
+/// ```
+///
+/// Note this CRC's initial value differs from its final xor, so the
+/// output can't be passed back in as the input.
+///
+#[crc(polynomial=0x18005, reflected=true, xor=0, init=0xffff, check=0x4b37)]
+pub fn crc16_modbus() {}
+
+/// CRC-16/XMODEM, used in the XMODEM protocol.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc16_xmodem(b"123456789", 0), 0x31c3);
+/// ```
+///
+#[crc(polynomial=0x11021, reflected=false, xor=0, check=0x31c3)]
+pub fn crc16_xmodem() {}
+
+/// CRC-21/CAN-FD, used in CAN FD frames with more than 16 bytes of data.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc21_can_fd(b"123456789", 0), 0x0ed841);
+/// ```
+///
+#[crc(polynomial=0x302899, reflected=false, xor=0, check=0x0ed841)]
+pub fn crc21_can_fd() {}
+
+/// CRC-24/BLE, used in Bluetooth Low Energy link-layer packets.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc24_ble(b"123456789", 0), 0xc25a56);
+/// ```
+///
+/// Note this CRC's initial value differs from its final xor, so the
+/// output can't be passed back in as the input.
+///
+#[crc(polynomial=0x100065b, reflected=true, xor=0, init=0x555555, check=0xc25a56)]
+pub fn crc24_ble() {}
+
+/// CRC-24/OPENPGP, used in OpenPGP's ASCII armor.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc24_openpgp(b"123456789", 0), 0x21cf02);
+/// ```
+///
+/// Note this CRC's initial value differs from its final xor, so the
+/// output can't be passed back in as the input.
+///
+#[crc(polynomial=0x1864cfb, reflected=false, xor=0, init=0xb704ce, check=0x21cf02)]
+pub fn crc24_openpgp() {}
+
+/// CRC-32/BZIP2, the unreflected variant of CRC-32.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc32_bzip2(b"123456789", 0), 0xfc891918);
+/// ```
+///
+#[crc(polynomial=0x104c11db7, reflected=false, check=0xfc891918)]
+pub fn crc32_bzip2() {}
+
+/// CRC-32/MPEG-2, used in MPEG transport streams.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc32_mpeg2(b"123456789", 0), 0x0376e6e7);
+/// ```
+///
+/// Note this CRC's initial value differs from its final xor, so the
+/// output can't be passed back in as the input.
+///
+#[crc(polynomial=0x104c11db7, reflected=false, xor=0, init=0xffffffff, check=0x0376e6e7)]
+pub fn crc32_mpeg2() {}
+
+/// CRC-32K, Koopman's 32-bit polynomial.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc32k(b"123456789", 0), 0x2d3dd0ae);
+/// ```
+///
+#[crc(polynomial=0x1741b8cd7, check=0x2d3dd0ae)]
+pub fn crc32k() {}
+
+/// CRC-64/ECMA-182, the unreflected variant of CRC-64.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc64_ecma(b"123456789", 0), 0x6c40df5f0b497347);
+/// ```
+///
+#[crc(polynomial=0x142f0e1eba9ea3693, reflected=false, xor=0, check=0x6c40df5f0b497347)]
+pub fn crc64_ecma() {}
+
+/// CRC-32C (Castagnoli), the same as [`crc32c`].
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc32_castagnoli(b"123456789", 0), 0xe3069283);
+/// ```
+///
+pub use crc32c as crc32_castagnoli;
+
+/// CRC-64/XZ, the same as [`crc64`].
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc64_xz(b"123456789", 0), 0x995dc9bbdf1939fa);
+/// ```
+///
+pub use crc64 as crc64_xz;
+
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -416,6 +1509,60 @@ mod test {
     #[crc(polynomial=0x11edc6f41, barret)] fn crc32c_barret() {}
     #[crc(polynomial=0x142f0e1eba9ea3693, barret)] fn crc64_barret() {}
 
+    #[crc(polynomial=0x107, slice_by_8)] fn crc8_slice_by_8() {}
+    #[crc(polynomial=0x11021, slice_by_8)] fn crc16_slice_by_8() {}
+    #[crc(polynomial=0x104c11db7, slice_by_8)] fn crc32_slice_by_8() {}
+    #[crc(polynomial=0x11edc6f41, slice_by_8)] fn crc32c_slice_by_8() {}
+    #[crc(polynomial=0x142f0e1eba9ea3693, slice_by_8)] fn crc64_slice_by_8() {}
+
+    #[crc(polynomial=0x107, slice_by_16)] fn crc8_slice_by_16() {}
+    #[crc(polynomial=0x11021, slice_by_16)] fn crc16_slice_by_16() {}
+    #[crc(polynomial=0x104c11db7, slice_by_16)] fn crc32_slice_by_16() {}
+    #[crc(polynomial=0x11edc6f41, slice_by_16)] fn crc32c_slice_by_16() {}
+    #[crc(polynomial=0x142f0e1eba9ea3693, slice_by_16)] fn crc64_slice_by_16() {}
+
+    #[crc(polynomial=0x107, folding)] fn crc8_folding() {}
+    #[crc(polynomial=0x11021, folding)] fn crc16_folding() {}
+    #[crc(polynomial=0x104c11db7, folding)] fn crc32_folding() {}
+    #[crc(polynomial=0x11edc6f41, folding)] fn crc32c_folding() {}
+    #[crc(polynomial=0x142f0e1eba9ea3693, folding)] fn crc64_folding() {}
+
+    #[crc(polynomial=0x107, adaptive)] fn crc8_adaptive() {}
+    #[crc(polynomial=0x11021, adaptive)] fn crc16_adaptive() {}
+    #[crc(polynomial=0x104c11db7, adaptive, threshold=64)] fn crc32_adaptive() {}
+    #[crc(polynomial=0x11edc6f41, adaptive)] fn crc32c_adaptive() {}
+    #[crc(polynomial=0x142f0e1eba9ea3693, adaptive, threshold=16)] fn crc64_adaptive() {}
+    #[crc(polynomial=0x11021, reflected=false, xor=0, init=0xffff, adaptive, threshold=32)] fn crc16_adaptive_ccitt_false() {}
+    #[crc(polynomial=0x11021, xor=0, init=0xb2aa, adaptive, threshold=4, check=0x63d0)] fn crc16_adaptive_riello() {}
+
+    #[crc(polynomial=0x107, lazy)] fn crc8_lazy() {}
+    #[crc(polynomial=0x11021, lazy)] fn crc16_lazy() {}
+    #[crc(polynomial=0x104c11db7, lazy)] fn crc32_lazy() {}
+    #[crc(polynomial=0x11edc6f41, table, lazy)] fn crc32c_lazy() {}
+    #[crc(polynomial=0x142f0e1eba9ea3693, table, lazy)] fn crc64_lazy() {}
+    #[crc(polynomial=0x104c11db7, reflected=false, lazy)] fn crc32_lazy_unreflected() {}
+
+    // alternative polynomial notations
+    #[crc(polynomial_reversed=0x14)] fn crc5_usb_reversed() {}
+    #[crc(polynomial_koopman=0x12)] fn crc5_usb_koopman() {}
+    #[crc(polynomial_reversed=0xedb88320)] fn crc32_reversed() {}
+    #[crc(polynomial_koopman=0x82608edb)] fn crc32_koopman() {}
+    #[crc(polynomial_reversed=0x82f63b78, polynomial_koopman=0x8f6e37a0)] fn crc32c_reversed_koopman() {}
+    #[crc(polynomial=0x142f0e1eba9ea3693, polynomial_reversed=0xc96c5795d7870f42)] fn crc64_checked() {}
+
+    #[test]
+    fn crc_notations() {
+        assert_eq!(crc5_usb_reversed(b"123456789", 0), crc5_usb(b"123456789", 0));
+        assert_eq!(crc5_usb_koopman(b"123456789", 0),  crc5_usb(b"123456789", 0));
+        assert_eq!(crc32_reversed(b"Hello World!", 0),  0x1c291ca3);
+        assert_eq!(crc32_koopman(b"Hello World!", 0),   0x1c291ca3);
+        assert_eq!(crc32c_reversed_koopman(b"Hello World!", 0), 0xfe6cf1dc);
+        assert_eq!(crc64_checked(b"Hello World!", 0),   0x75045245c9ea6fe2);
+
+        assert_eq!(<Crc32ReversedDigest as Checksum>::POLYNOMIAL, 0x104c11db7);
+        assert_eq!(<Crc32KoopmanDigest as Checksum>::POLYNOMIAL, 0x104c11db7);
+    }
+
     #[test]
     fn crc_naive() {
         assert_eq!(crc8_naive(b"Hello World!", 0),   0xb3);
@@ -434,6 +1581,51 @@ mod test {
         assert_eq!(crc64_table(b"Hello World!", 0),  0x75045245c9ea6fe2);
     }
 
+    #[test]
+    fn crc_adaptive() {
+        assert_eq!(crc8_adaptive(b"Hello World!", 0),   0xb3);
+        assert_eq!(crc16_adaptive(b"Hello World!", 0),  0x0bbb);
+        assert_eq!(crc32_adaptive(b"Hello World!", 0),  0x1c291ca3);
+        assert_eq!(crc32c_adaptive(b"Hello World!", 0), 0xfe6cf1dc);
+        assert_eq!(crc64_adaptive(b"Hello World!", 0),  0x75045245c9ea6fe2);
+
+        // make sure we exercise both sides of the threshold
+        let mut data = [0u8; 1000];
+        let mut x = 1u32;
+        for b in data.iter_mut() {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            *b = x as u8;
+        }
+        for len in [0, 1, 15, 16, 17, 31, 32, 33, 63, 64, 65, 511, 512, 513, 1000] {
+            let data = &data[..len];
+            assert_eq!(crc8_adaptive(data, 0),   crc8_naive(data, 0));
+            assert_eq!(crc16_adaptive(data, 0),  crc16_naive(data, 0));
+            assert_eq!(crc32_adaptive(data, 0),  crc32_naive(data, 0));
+            assert_eq!(crc32c_adaptive(data, 0), crc32c_naive(data, 0));
+            assert_eq!(crc64_adaptive(data, 0),  crc64_naive(data, 0));
+            assert_eq!(crc16_adaptive_ccitt_false(data, 0), crc16_naive_ccitt_false(data, 0));
+        }
+
+        // reflected init should be reflected in both short and long CRCs
+        assert_eq!(crc16_adaptive_riello(b"123", 0), 0x0e3e);
+        assert_eq!(crc16_adaptive_riello(b"123456789", 0), 0x63d0);
+    }
+
+    #[test]
+    fn crc_lazy() {
+        // first use builds the table, second use should find it
+        for _ in 0..2 {
+            assert_eq!(crc8_lazy(b"Hello World!", 0),   0xb3);
+            assert_eq!(crc16_lazy(b"Hello World!", 0),  0x0bbb);
+            assert_eq!(crc32_lazy(b"Hello World!", 0),  0x1c291ca3);
+            assert_eq!(crc32c_lazy(b"Hello World!", 0), 0xfe6cf1dc);
+            assert_eq!(crc64_lazy(b"Hello World!", 0),  0x75045245c9ea6fe2);
+            assert_eq!(crc32_lazy_unreflected(b"Hello World!", 0), crc32_naive_unreflected(b"Hello World!", 0));
+        }
+    }
+
     #[test]
     fn crc_small_table() {
         assert_eq!(crc8_small_table(b"Hello World!", 0),   0xb3);
@@ -452,6 +1644,141 @@ mod test {
         assert_eq!(crc64_barret(b"Hello World!", 0),  0x75045245c9ea6fe2);
     }
 
+    #[test]
+    fn crc_slice_by() {
+        assert_eq!(crc8_slice_by_8(b"Hello World!", 0),   0xb3);
+        assert_eq!(crc16_slice_by_8(b"Hello World!", 0),  0x0bbb);
+        assert_eq!(crc32_slice_by_8(b"Hello World!", 0),  0x1c291ca3);
+        assert_eq!(crc32c_slice_by_8(b"Hello World!", 0), 0xfe6cf1dc);
+        assert_eq!(crc64_slice_by_8(b"Hello World!", 0),  0x75045245c9ea6fe2);
+
+        assert_eq!(crc8_slice_by_16(b"Hello World!", 0),   0xb3);
+        assert_eq!(crc16_slice_by_16(b"Hello World!", 0),  0x0bbb);
+        assert_eq!(crc32_slice_by_16(b"Hello World!", 0),  0x1c291ca3);
+        assert_eq!(crc32c_slice_by_16(b"Hello World!", 0), 0xfe6cf1dc);
+        assert_eq!(crc64_slice_by_16(b"Hello World!", 0),  0x75045245c9ea6fe2);
+
+        // make sure we exercise multiple slices + remainders
+        let mut data = [0u8; 1000];
+        let mut x = 1u32;
+        for b in data.iter_mut() {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            *b = x as u8;
+        }
+        for len in [0, 1, 7, 8, 9, 15, 16, 17, 100, 1000] {
+            let data = &data[..len];
+            assert_eq!(crc8_slice_by_8(data, 0),    crc8_naive(data, 0));
+            assert_eq!(crc16_slice_by_8(data, 0),   crc16_naive(data, 0));
+            assert_eq!(crc32_slice_by_8(data, 0),   crc32_naive(data, 0));
+            assert_eq!(crc32c_slice_by_8(data, 0),  crc32c_naive(data, 0));
+            assert_eq!(crc64_slice_by_8(data, 0),   crc64_naive(data, 0));
+            assert_eq!(crc8_slice_by_16(data, 0),   crc8_naive(data, 0));
+            assert_eq!(crc16_slice_by_16(data, 0),  crc16_naive(data, 0));
+            assert_eq!(crc32_slice_by_16(data, 0),  crc32_naive(data, 0));
+            assert_eq!(crc32c_slice_by_16(data, 0), crc32c_naive(data, 0));
+            assert_eq!(crc64_slice_by_16(data, 0),  crc64_naive(data, 0));
+
+            assert_eq!(crc4_slice_by_8(data, 0),   crc4_naive(data, 0));
+            assert_eq!(crc12_slice_by_8(data, 0),  crc12_naive(data, 0));
+            assert_eq!(crc23_slice_by_16(data, 0), crc23_naive(data, 0));
+            assert_eq!(crc32_slice_by_8_unreflected(data, 0), crc32_naive_unreflected(data, 0));
+            assert_eq!(crc16_slice_by_16_ccitt_false(data, 0), crc16_naive_ccitt_false(data, 0));
+        }
+    }
+
+    #[test]
+    fn crc_folding() {
+        assert_eq!(crc8_folding(b"Hello World!", 0),   0xb3);
+        assert_eq!(crc16_folding(b"Hello World!", 0),  0x0bbb);
+        assert_eq!(crc32_folding(b"Hello World!", 0),  0x1c291ca3);
+        assert_eq!(crc32c_folding(b"Hello World!", 0), 0xfe6cf1dc);
+        assert_eq!(crc64_folding(b"Hello World!", 0),  0x75045245c9ea6fe2);
+
+        // make sure we exercise multiple folds + remainders
+        let mut data = [0u8; 1000];
+        let mut x = 1u32;
+        for b in data.iter_mut() {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            *b = x as u8;
+        }
+        for len in [0, 1, 8, 15, 16, 17, 32, 63, 64, 65, 100, 128, 200, 1000] {
+            let data = &data[..len];
+            assert_eq!(crc8_folding(data, 0),   crc8_naive(data, 0));
+            assert_eq!(crc16_folding(data, 0),  crc16_naive(data, 0));
+            assert_eq!(crc32_folding(data, 0),  crc32_naive(data, 0));
+            assert_eq!(crc32c_folding(data, 0), crc32c_naive(data, 0));
+            assert_eq!(crc64_folding(data, 0),  crc64_naive(data, 0));
+
+            assert_eq!(crc4_folding(data, 0),  crc4_naive(data, 0));
+            assert_eq!(crc12_folding(data, 0), crc12_naive(data, 0));
+            assert_eq!(crc23_folding(data, 0), crc23_naive(data, 0));
+            assert_eq!(crc32_folding_unreflected(data, 0), crc32_naive_unreflected(data, 0));
+            assert_eq!(crc16_folding_ccitt_false(data, 0), crc16_naive_ccitt_false(data, 0));
+            assert_eq!(crc16_folding_modbus(data, 0), crc16_naive_modbus(data, 0));
+        }
+    }
+
+    #[test]
+    fn crc_vectored() {
+        let data = b"Hello World! This is a longer message to split up.";
+        for i in 0..=data.len() {
+            for j in i..=data.len() {
+                let chunks = [&data[..i], &data[i..j], &data[j..]];
+                assert_eq!(crc8_naive_vectored(chunks, 0),     crc8_naive(data, 0));
+                assert_eq!(crc16_table_vectored(chunks, 0),    crc16_table(data, 0));
+                assert_eq!(crc32c_barret_vectored(chunks, 0),  crc32c_barret(data, 0));
+                assert_eq!(crc12_table_vectored(chunks, 0),    crc12_table(data, 0));
+                assert_eq!(crc16_ccitt_false_vectored(chunks, 0), crc16_ccitt_false(data, 0));
+                assert_eq!(crc32_mpeg2_vectored(chunks.iter().copied(), 0), crc32_mpeg2(data, 0));
+
+                let mut digest = Crc16CcittFalseDigest::new();
+                digest.update_vectored(chunks);
+                assert_eq!(digest.finalize(), crc16_ccitt_false(data, 0));
+            }
+        }
+
+        // previous state should carry through
+        let a = crc32c(b"Hello ", 0);
+        assert_eq!(crc32c_vectored([&b"World"[..], b"!"], a), 0xfe6cf1dc);
+        assert_eq!(crc32c_vectored([], 0), crc32c(b"", 0));
+    }
+
+    #[test]
+    fn crc_const() {
+        const CRC8: u8 = crc8_const(b"Hello World!", 0);
+        const CRC16: u16 = crc16_const(b"Hello World!", 0);
+        const CRC32: u32 = crc32_const(b"Hello World!", 0);
+        const CRC32C: u32 = crc32c_const(b"Hello World!", 0);
+        const CRC64: u64 = crc64_const(b"Hello World!", 0);
+        assert_eq!(CRC8,   0xb3);
+        assert_eq!(CRC16,  0x0bbb);
+        assert_eq!(CRC32,  0x1c291ca3);
+        assert_eq!(CRC32C, 0xfe6cf1dc);
+        assert_eq!(CRC64,  0x75045245c9ea6fe2);
+
+        // catalog check values, including odd widths and init != xor
+        const CRC5_USB: u8 = crc5_usb_const(b"123456789", 0);
+        const CRC11_FLEXRAY: u16 = crc11_flexray_const(b"123456789", 0);
+        const CRC16_CCITT_FALSE: u16 = crc16_ccitt_false_const(b"123456789", 0);
+        const CRC24_BLE: u32 = crc24_ble_const(b"123456789", 0);
+        const CRC64_ECMA: u64 = crc64_ecma_const(b"123456789", 0);
+        assert_eq!(CRC5_USB,          0x19);
+        assert_eq!(CRC11_FLEXRAY,     0x5a3);
+        assert_eq!(CRC16_CCITT_FALSE, 0x29b1);
+        assert_eq!(CRC24_BLE,         0xc25a56);
+        assert_eq!(CRC64_ECMA,        0x6c40df5f0b497347);
+
+        // and incremental
+        assert_eq!(crc32c_const(b"rld!", crc32c_const(b"Hello Wo", 0)), 0xfe6cf1dc);
+        assert_eq!(crc4_naive_const(b"Hello World!", 0), crc4_naive(b"Hello World!", 0));
+        assert_eq!(crc12_table_const(b"Hello World!", 0), crc12_table(b"Hello World!", 0));
+        assert_eq!(crc32_naive_unreflected_const(b"Hello World!", 0), crc32_naive_unreflected(b"Hello World!", 0));
+    }
+
     #[test]
     fn crc_unaligned() {
         assert_eq!(crc8_naive(b"Hello World!!", 0),   0x2f);
@@ -477,6 +1804,18 @@ mod test {
         assert_eq!(crc32_barret(b"Hello World!!", 0),  0xd1a8249d);
         assert_eq!(crc32c_barret(b"Hello World!!", 0), 0x1ec51c06);
         assert_eq!(crc64_barret(b"Hello World!!", 0),  0xf5a8a397b60da2e1);
+
+        assert_eq!(crc8_slice_by_8(b"Hello World!!", 0),   0x2f);
+        assert_eq!(crc16_slice_by_8(b"Hello World!!", 0),  0xcba0);
+        assert_eq!(crc32_slice_by_8(b"Hello World!!", 0),  0xd1a8249d);
+        assert_eq!(crc32c_slice_by_8(b"Hello World!!", 0), 0x1ec51c06);
+        assert_eq!(crc64_slice_by_8(b"Hello World!!", 0),  0xf5a8a397b60da2e1);
+
+        assert_eq!(crc8_folding(b"Hello World!!", 0),   0x2f);
+        assert_eq!(crc16_folding(b"Hello World!!", 0),  0xcba0);
+        assert_eq!(crc32_folding(b"Hello World!!", 0),  0xd1a8249d);
+        assert_eq!(crc32c_folding(b"Hello World!!", 0), 0x1ec51c06);
+        assert_eq!(crc64_folding(b"Hello World!!", 0),  0xf5a8a397b60da2e1);
     }
 
     #[test]
@@ -504,6 +1843,18 @@ mod test {
         assert_eq!(crc32_barret(b"World!", crc32_barret(b"Hello ", 0)),   0x1c291ca3);
         assert_eq!(crc32c_barret(b"World!", crc32c_barret(b"Hello ", 0)), 0xfe6cf1dc);
         assert_eq!(crc64_barret(b"World!", crc64_barret(b"Hello ", 0)),   0x75045245c9ea6fe2);
+
+        assert_eq!(crc8_slice_by_8(b"World!", crc8_slice_by_8(b"Hello ", 0)),     0xb3);
+        assert_eq!(crc16_slice_by_8(b"World!", crc16_slice_by_8(b"Hello ", 0)),   0x0bbb);
+        assert_eq!(crc32_slice_by_8(b"World!", crc32_slice_by_8(b"Hello ", 0)),   0x1c291ca3);
+        assert_eq!(crc32c_slice_by_8(b"World!", crc32c_slice_by_8(b"Hello ", 0)), 0xfe6cf1dc);
+        assert_eq!(crc64_slice_by_8(b"World!", crc64_slice_by_8(b"Hello ", 0)),   0x75045245c9ea6fe2);
+
+        assert_eq!(crc8_folding(b"World!", crc8_folding(b"Hello ", 0)),     0xb3);
+        assert_eq!(crc16_folding(b"World!", crc16_folding(b"Hello ", 0)),   0x0bbb);
+        assert_eq!(crc32_folding(b"World!", crc32_folding(b"Hello ", 0)),   0x1c291ca3);
+        assert_eq!(crc32c_folding(b"World!", crc32c_folding(b"Hello ", 0)), 0xfe6cf1dc);
+        assert_eq!(crc64_folding(b"World!", crc64_folding(b"Hello ", 0)),   0x75045245c9ea6fe2);
     }
 
     // odd-sized crcs
@@ -511,16 +1862,25 @@ mod test {
     #[crc(polynomial=0x13, table)] fn crc4_table() {}
     #[crc(polynomial=0x13, small_table)] fn crc4_small_table() {}
     #[crc(polynomial=0x13, barret)] fn crc4_barret() {}
+    #[crc(polynomial=0x13, slice_by_8)] fn crc4_slice_by_8() {}
+    #[crc(polynomial=0x13, slice_by_16)] fn crc4_slice_by_16() {}
+    #[crc(polynomial=0x13, folding)] fn crc4_folding() {}
 
     #[crc(polynomial=0x11e7, naive)] fn crc12_naive() {}
     #[crc(polynomial=0x11e7, table)] fn crc12_table() {}
     #[crc(polynomial=0x11e7, small_table)] fn crc12_small_table() {}
     #[crc(polynomial=0x11e7, barret)] fn crc12_barret() {}
+    #[crc(polynomial=0x11e7, slice_by_8)] fn crc12_slice_by_8() {}
+    #[crc(polynomial=0x11e7, slice_by_16)] fn crc12_slice_by_16() {}
+    #[crc(polynomial=0x11e7, folding)] fn crc12_folding() {}
 
     #[crc(polynomial=0x8002a9, naive)] fn crc23_naive() {}
     #[crc(polynomial=0x8002a9, table)] fn crc23_table() {}
     #[crc(polynomial=0x8002a9, small_table)] fn crc23_small_table() {}
     #[crc(polynomial=0x8002a9, barret)] fn crc23_barret() {}
+    #[crc(polynomial=0x8002a9, slice_by_8)] fn crc23_slice_by_8() {}
+    #[crc(polynomial=0x8002a9, slice_by_16)] fn crc23_slice_by_16() {}
+    #[crc(polynomial=0x8002a9, folding)] fn crc23_folding() {}
 
     #[test]
     fn crc_odd_sizes() {
@@ -528,31 +1888,49 @@ mod test {
         assert_eq!(crc4_table(b"Hello World!", 0),       0x7);
         assert_eq!(crc4_small_table(b"Hello World!", 0), 0x7);
         assert_eq!(crc4_barret(b"Hello World!", 0),      0x7);
+        assert_eq!(crc4_slice_by_8(b"Hello World!", 0),  0x7);
+        assert_eq!(crc4_slice_by_16(b"Hello World!", 0), 0x7);
+        assert_eq!(crc4_folding(b"Hello World!", 0),     0x7);
 
         assert_eq!(crc12_naive(b"Hello World!", 0),       0x1d4);
         assert_eq!(crc12_table(b"Hello World!", 0),       0x1d4);
         assert_eq!(crc12_small_table(b"Hello World!", 0), 0x1d4);
         assert_eq!(crc12_barret(b"Hello World!", 0),      0x1d4);
+        assert_eq!(crc12_slice_by_8(b"Hello World!", 0),  0x1d4);
+        assert_eq!(crc12_slice_by_16(b"Hello World!", 0), 0x1d4);
+        assert_eq!(crc12_folding(b"Hello World!", 0),     0x1d4);
 
         assert_eq!(crc23_naive(b"Hello World!", 0),       0x32da1c);
         assert_eq!(crc23_table(b"Hello World!", 0),       0x32da1c);
         assert_eq!(crc23_small_table(b"Hello World!", 0), 0x32da1c);
         assert_eq!(crc23_barret(b"Hello World!", 0),      0x32da1c);
+        assert_eq!(crc23_slice_by_8(b"Hello World!", 0),  0x32da1c);
+        assert_eq!(crc23_slice_by_16(b"Hello World!", 0), 0x32da1c);
+        assert_eq!(crc23_folding(b"Hello World!", 0),     0x32da1c);
 
         assert_eq!(crc4_naive(b"Hello World!!", 0),       0x1);
         assert_eq!(crc4_table(b"Hello World!!", 0),       0x1);
         assert_eq!(crc4_small_table(b"Hello World!!", 0), 0x1);
         assert_eq!(crc4_barret(b"Hello World!!", 0),      0x1);
+        assert_eq!(crc4_slice_by_8(b"Hello World!!", 0),  0x1);
+        assert_eq!(crc4_slice_by_16(b"Hello World!!", 0), 0x1);
+        assert_eq!(crc4_folding(b"Hello World!!", 0),     0x1);
 
         assert_eq!(crc12_naive(b"Hello World!!", 0),       0xb8d);
         assert_eq!(crc12_table(b"Hello World!!", 0),       0xb8d);
         assert_eq!(crc12_small_table(b"Hello World!!", 0), 0xb8d);
         assert_eq!(crc12_barret(b"Hello World!!", 0),      0xb8d);
+        assert_eq!(crc12_slice_by_8(b"Hello World!!", 0),  0xb8d);
+        assert_eq!(crc12_slice_by_16(b"Hello World!!", 0), 0xb8d);
+        assert_eq!(crc12_folding(b"Hello World!!", 0),     0xb8d);
 
         assert_eq!(crc23_naive(b"Hello World!!", 0),       0x11685a);
         assert_eq!(crc23_table(b"Hello World!!", 0),       0x11685a);
         assert_eq!(crc23_small_table(b"Hello World!!", 0), 0x11685a);
         assert_eq!(crc23_barret(b"Hello World!!", 0),      0x11685a);
+        assert_eq!(crc23_slice_by_8(b"Hello World!!", 0),  0x11685a);
+        assert_eq!(crc23_slice_by_16(b"Hello World!!", 0), 0x11685a);
+        assert_eq!(crc23_folding(b"Hello World!!", 0),     0x11685a);
     }
 
     // bit reflected 
@@ -560,6 +1938,8 @@ mod test {
     #[crc(polynomial=0x104c11db7, table, reflected=false)] fn crc32_table_unreflected() {}
     #[crc(polynomial=0x104c11db7, small_table, reflected=false)] fn crc32_small_table_unreflected() {}
     #[crc(polynomial=0x104c11db7, barret, reflected=false)] fn crc32_barret_unreflected() {}
+    #[crc(polynomial=0x104c11db7, slice_by_8, reflected=false)] fn crc32_slice_by_8_unreflected() {}
+    #[crc(polynomial=0x104c11db7, folding, reflected=false)] fn crc32_folding_unreflected() {}
 
     #[test]
     fn crc_unreflected() {
@@ -567,6 +1947,8 @@ mod test {
         assert_eq!(crc32_table_unreflected(b"Hello World!", 0),       0x6b1a7cae);
         assert_eq!(crc32_small_table_unreflected(b"Hello World!", 0), 0x6b1a7cae);
         assert_eq!(crc32_barret_unreflected(b"Hello World!", 0),      0x6b1a7cae);
+        assert_eq!(crc32_slice_by_8_unreflected(b"Hello World!", 0),  0x6b1a7cae);
+        assert_eq!(crc32_folding_unreflected(b"Hello World!", 0),     0x6b1a7cae);
     }
 
     // bit inverted 
@@ -583,6 +1965,716 @@ mod test {
         assert_eq!(crc32_barret_uninverted(b"Hello World!", 0),      0x67fcdacc);
     }
 
+    // init differing from xor
+    #[crc(polynomial=0x11021, naive, reflected=false, xor=0, init=0xffff)] fn crc16_naive_ccitt_false() {}
+    #[crc(polynomial=0x11021, table, reflected=false, xor=0, init=0xffff)] fn crc16_table_ccitt_false() {}
+    #[crc(polynomial=0x11021, small_table, reflected=false, xor=0, init=0xffff)] fn crc16_small_table_ccitt_false() {}
+    #[crc(polynomial=0x11021, barret, reflected=false, xor=0, init=0xffff)] fn crc16_barret_ccitt_false() {}
+    #[crc(polynomial=0x11021, slice_by_16, reflected=false, xor=0, init=0xffff)] fn crc16_slice_by_16_ccitt_false() {}
+    #[crc(polynomial=0x11021, folding, reflected=false, xor=0, init=0xffff)] fn crc16_folding_ccitt_false() {}
+
+    #[crc(polynomial=0x18005, naive, xor=0, init=0xffff)] fn crc16_naive_modbus() {}
+    #[crc(polynomial=0x18005, table, xor=0, init=0xffff)] fn crc16_table_modbus() {}
+    #[crc(polynomial=0x18005, small_table, xor=0, init=0xffff)] fn crc16_small_table_modbus() {}
+    #[crc(polynomial=0x18005, barret, xor=0, init=0xffff)] fn crc16_barret_modbus() {}
+    #[crc(polynomial=0x18005, folding, xor=0, init=0xffff)] fn crc16_folding_modbus() {}
+
+    #[test]
+    fn crc_init() {
+        assert_eq!(crc16_naive_ccitt_false(b"Hello World!", 0),       0x882a);
+        assert_eq!(crc16_table_ccitt_false(b"Hello World!", 0),       0x882a);
+        assert_eq!(crc16_small_table_ccitt_false(b"Hello World!", 0), 0x882a);
+        assert_eq!(crc16_barret_ccitt_false(b"Hello World!", 0),      0x882a);
+        assert_eq!(crc16_slice_by_16_ccitt_false(b"Hello World!", 0), 0x882a);
+        assert_eq!(crc16_folding_ccitt_false(b"Hello World!", 0),     0x882a);
+
+        assert_eq!(crc16_naive_modbus(b"Hello World!", 0),       0x55da);
+        assert_eq!(crc16_table_modbus(b"Hello World!", 0),       0x55da);
+        assert_eq!(crc16_small_table_modbus(b"Hello World!", 0), 0x55da);
+        assert_eq!(crc16_barret_modbus(b"Hello World!", 0),      0x55da);
+        assert_eq!(crc16_folding_modbus(b"Hello World!", 0),     0x55da);
+    }
+
+    // catalog CRCs with odd widths
+    #[crc(polynomial=0x25, naive, xor=0x1f)] fn crc5_naive_usb() {}
+    #[crc(polynomial=0x25, table, xor=0x1f)] fn crc5_table_usb() {}
+    #[crc(polynomial=0x25, small_table, xor=0x1f)] fn crc5_small_table_usb() {}
+    #[crc(polynomial=0x25, barret, xor=0x1f)] fn crc5_barret_usb() {}
+    #[crc(polynomial=0x25, slice_by_8, xor=0x1f)] fn crc5_slice_by_8_usb() {}
+    #[crc(polynomial=0x25, folding, xor=0x1f)] fn crc5_folding_usb() {}
+
+    #[crc(polynomial=0x100065b, naive, xor=0, init=0x555555)] fn crc24_naive_ble() {}
+    #[crc(polynomial=0x100065b, table, xor=0, init=0x555555)] fn crc24_table_ble() {}
+    #[crc(polynomial=0x100065b, small_table, xor=0, init=0x555555)] fn crc24_small_table_ble() {}
+    #[crc(polynomial=0x100065b, barret, xor=0, init=0x555555)] fn crc24_barret_ble() {}
+    #[crc(polynomial=0x100065b, slice_by_8, xor=0, init=0x555555)] fn crc24_slice_by_8_ble() {}
+    #[crc(polynomial=0x100065b, folding, xor=0, init=0x555555)] fn crc24_folding_ble() {}
+
+    #[crc(polynomial=0x1864cfb, naive, reflected=false, xor=0, init=0xb704ce)] fn crc24_naive_openpgp() {}
+    #[crc(polynomial=0x1864cfb, table, reflected=false, xor=0, init=0xb704ce)] fn crc24_table_openpgp() {}
+    #[crc(polynomial=0x1864cfb, small_table, reflected=false, xor=0, init=0xb704ce)] fn crc24_small_table_openpgp() {}
+    #[crc(polynomial=0x1864cfb, barret, reflected=false, xor=0, init=0xb704ce)] fn crc24_barret_openpgp() {}
+    #[crc(polynomial=0x1864cfb, slice_by_8, reflected=false, xor=0, init=0xb704ce)] fn crc24_slice_by_8_openpgp() {}
+    #[crc(polynomial=0x1864cfb, folding, reflected=false, xor=0, init=0xb704ce)] fn crc24_folding_openpgp() {}
+
+    #[test]
+    fn crc_catalog_odd_sizes() {
+        assert_eq!(crc5_naive_usb(b"123456789", 0),        0x19);
+        assert_eq!(crc5_table_usb(b"123456789", 0),        0x19);
+        assert_eq!(crc5_small_table_usb(b"123456789", 0),  0x19);
+        assert_eq!(crc5_barret_usb(b"123456789", 0),       0x19);
+        assert_eq!(crc5_slice_by_8_usb(b"123456789", 0),   0x19);
+        assert_eq!(crc5_folding_usb(b"123456789", 0),      0x19);
+
+        assert_eq!(crc24_naive_ble(b"123456789", 0),       0xc25a56);
+        assert_eq!(crc24_table_ble(b"123456789", 0),       0xc25a56);
+        assert_eq!(crc24_small_table_ble(b"123456789", 0), 0xc25a56);
+        assert_eq!(crc24_barret_ble(b"123456789", 0),      0xc25a56);
+        assert_eq!(crc24_slice_by_8_ble(b"123456789", 0),  0xc25a56);
+        assert_eq!(crc24_folding_ble(b"123456789", 0),     0xc25a56);
+
+        assert_eq!(crc24_naive_openpgp(b"123456789", 0),       0x21cf02);
+        assert_eq!(crc24_table_openpgp(b"123456789", 0),       0x21cf02);
+        assert_eq!(crc24_small_table_openpgp(b"123456789", 0), 0x21cf02);
+        assert_eq!(crc24_barret_openpgp(b"123456789", 0),      0x21cf02);
+        assert_eq!(crc24_slice_by_8_openpgp(b"123456789", 0),  0x21cf02);
+        assert_eq!(crc24_folding_openpgp(b"123456789", 0),     0x21cf02);
+
+        // longer inputs to exercise slicing/folding
+        let mut data = [0u8; 9*16];
+        for chunk in data.chunks_mut(9) {
+            chunk.copy_from_slice(b"123456789");
+        }
+        assert_eq!(crc5_table_usb(&data, 0),          crc5_naive_usb(&data, 0));
+        assert_eq!(crc5_small_table_usb(&data, 0),    crc5_naive_usb(&data, 0));
+        assert_eq!(crc5_barret_usb(&data, 0),         crc5_naive_usb(&data, 0));
+        assert_eq!(crc5_slice_by_8_usb(&data, 0),     crc5_naive_usb(&data, 0));
+        assert_eq!(crc5_folding_usb(&data, 0),        crc5_naive_usb(&data, 0));
+        assert_eq!(crc24_table_ble(&data, 0),         crc24_naive_ble(&data, 0));
+        assert_eq!(crc24_small_table_ble(&data, 0),   crc24_naive_ble(&data, 0));
+        assert_eq!(crc24_barret_ble(&data, 0),        crc24_naive_ble(&data, 0));
+        assert_eq!(crc24_slice_by_8_ble(&data, 0),    crc24_naive_ble(&data, 0));
+        assert_eq!(crc24_folding_ble(&data, 0),       crc24_naive_ble(&data, 0));
+        assert_eq!(crc24_table_openpgp(&data, 0),       crc24_naive_openpgp(&data, 0));
+        assert_eq!(crc24_small_table_openpgp(&data, 0), crc24_naive_openpgp(&data, 0));
+        assert_eq!(crc24_barret_openpgp(&data, 0),      crc24_naive_openpgp(&data, 0));
+        assert_eq!(crc24_slice_by_8_openpgp(&data, 0),  crc24_naive_openpgp(&data, 0));
+        assert_eq!(crc24_folding_openpgp(&data, 0),     crc24_naive_openpgp(&data, 0));
+    }
+
+    #[test]
+    fn crc_catalog() {
+        assert_eq!(crc5_usb(b"123456789", 0),          0x19);
+        assert_eq!(crc8_autosar(b"123456789", 0),      0xdf);
+        assert_eq!(crc8_maxim(b"123456789", 0),        0xa1);
+        assert_eq!(crc8_smbus(b"123456789", 0),        0xf4);
+        assert_eq!(crc11_flexray(b"123456789", 0),     0x5a3);
+        assert_eq!(crc12_dect(b"123456789", 0),        0xf5b);
+        assert_eq!(crc16_arc(b"123456789", 0),         0xbb3d);
+        assert_eq!(crc16_ccitt_false(b"123456789", 0), 0x29b1);
+        assert_eq!(crc16_kermit(b"123456789", 0),      0x2189);
+        assert_eq!(crc16_modbus(b"123456789", 0),      0x4b37);
+        assert_eq!(crc16_xmodem(b"123456789", 0),      0x31c3);
+        assert_eq!(crc21_can_fd(b"123456789", 0),      0x0ed841);
+        assert_eq!(crc24_ble(b"123456789", 0),         0xc25a56);
+        assert_eq!(crc24_openpgp(b"123456789", 0),     0x21cf02);
+        assert_eq!(crc32(b"123456789", 0),             0xcbf43926);
+        assert_eq!(crc32_bzip2(b"123456789", 0),       0xfc891918);
+        assert_eq!(crc32_mpeg2(b"123456789", 0),       0x0376e6e7);
+        assert_eq!(crc32c(b"123456789", 0),            0xe3069283);
+        assert_eq!(crc32_castagnoli(b"123456789", 0),  0xe3069283);
+        assert_eq!(crc32k(b"123456789", 0),            0x2d3dd0ae);
+        assert_eq!(crc64_xz(b"123456789", 0),          0x995dc9bbdf1939fa);
+        assert_eq!(crc64_ecma(b"123456789", 0),        0x6c40df5f0b497347);
+
+        assert_eq!(crc8_autosar(b"Hello World!", 0),      0x37);
+        assert_eq!(crc8_maxim(b"Hello World!", 0),        0x9e);
+        assert_eq!(crc8_smbus(b"Hello World!", 0),        0x1c);
+        assert_eq!(crc16_arc(b"Hello World!", 0),         0x57be);
+        assert_eq!(crc16_ccitt_false(b"Hello World!", 0), 0x882a);
+        assert_eq!(crc16_kermit(b"Hello World!", 0),      0x6b65);
+        assert_eq!(crc16_modbus(b"Hello World!", 0),      0x55da);
+        assert_eq!(crc16_xmodem(b"Hello World!", 0),      0x0cd3);
+        assert_eq!(crc32_bzip2(b"Hello World!", 0),       0x6b1a7cae);
+        assert_eq!(crc32_mpeg2(b"Hello World!", 0),       0x94e58351);
+        assert_eq!(crc32k(b"Hello World!", 0),            0x681a32b8);
+        assert_eq!(crc64_ecma(b"Hello World!", 0),        0xfad9a77c67077205);
+    }
+
+    #[test]
+    fn crc_digest() {
+        macro_rules! digest {
+            ($digest:ident, $crc:expr) => {{
+                let mut digest = $digest::new();
+                for chunk in b"Hello World!".chunks(5) {
+                    digest.update(chunk);
+                }
+                assert_eq!(digest.finalize(), $crc);
+                digest.update(b"");
+                assert_eq!(digest.finalize(), $crc);
+                digest.reset();
+                digest.update(b"Hello World!");
+                assert_eq!(digest.finalize(), $crc);
+            }}
+        }
+
+        digest!(Crc8NaiveDigest,       0xb3);
+        digest!(Crc16TableDigest,      0x0bbb);
+        digest!(Crc32SmallTableDigest, 0x1c291ca3);
+        digest!(Crc32cBarretDigest,    0xfe6cf1dc);
+        digest!(Crc64Digest,           0x75045245c9ea6fe2);
+        digest!(Crc12NaiveDigest,      0x1d4);
+        digest!(Crc23BarretDigest,     0x32da1c);
+
+        // init differing from xor
+        digest!(Crc16NaiveCcittFalseDigest,      0x882a);
+        digest!(Crc16TableCcittFalseDigest,      0x882a);
+        digest!(Crc16SmallTableCcittFalseDigest, 0x882a);
+        digest!(Crc16BarretCcittFalseDigest,     0x882a);
+        digest!(Crc16NaiveModbusDigest,          0x55da);
+        digest!(Crc16TableModbusDigest,          0x55da);
+        digest!(Crc16SmallTableModbusDigest,     0x55da);
+        digest!(Crc16BarretModbusDigest,         0x55da);
+
+        // note the empty message is not always zero
+        assert_eq!(Crc16CcittFalseDigest::new().finalize(), 0xffff);
+        assert_eq!(Crc32Mpeg2Digest::new().finalize(), 0xffffffff);
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn crc_digest_write() {
+        extern crate std;
+        use std::io::Write;
+
+        let mut digest = Crc32cDigest::default();
+        write!(digest, "Hello {}!", "World").unwrap();
+        assert_eq!(digest.finalize(), 0xfe6cf1dc);
+
+        let mut digest = Crc16ModbusDigest::default();
+        std::io::copy(&mut &b"123456789"[..], &mut digest).unwrap();
+        assert_eq!(digest.finalize(), 0x4b37);
+    }
+
+    #[test]
+    fn crc_combine() {
+        macro_rules! combine {
+            ($crc:ident, $combine:ident) => {{
+                let data = b"Hello World! This is a longer message to split up.";
+                for i in 0..=data.len() {
+                    let (a, b) = data.split_at(i);
+                    assert_eq!(
+                        $combine($crc(a, 0), $crc(b, 0), b.len()),
+                        $crc(data, 0)
+                    );
+                }
+            }}
+        }
+
+        combine!(crc8_naive,         crc8_naive_combine);
+        combine!(crc16_table,        crc16_table_combine);
+        combine!(crc32_small_table,  crc32_small_table_combine);
+        combine!(crc32c_barret,      crc32c_barret_combine);
+        combine!(crc64,              crc64_combine);
+        combine!(crc4_naive,         crc4_naive_combine);
+        combine!(crc12_table,        crc12_table_combine);
+        combine!(crc23_barret,       crc23_barret_combine);
+        combine!(crc32_barret_unreflected, crc32_barret_unreflected_combine);
+        combine!(crc32_naive_uninverted,   crc32_naive_uninverted_combine);
+
+        // init differing from xor
+        combine!(crc16_naive_ccitt_false,  crc16_naive_ccitt_false_combine);
+        combine!(crc16_barret_ccitt_false, crc16_barret_ccitt_false_combine);
+        combine!(crc16_table_modbus,       crc16_table_modbus_combine);
+        combine!(crc32_mpeg2,              crc32_mpeg2_combine);
+        combine!(crc64_ecma,               crc64_ecma_combine);
+
+        // combining large lengths should still be fast
+        let mut data = [0u8; 10000];
+        data[..5].copy_from_slice(b"Hello");
+        assert_eq!(
+            crc32c_combine(crc32c(&data[..5], 0), crc32c(&data[5..], 0), data.len()-5),
+            crc32c(&data, 0)
+        );
+    }
+
+    #[test]
+    fn crc_batch() {
+        let mut data = [0u8; 1000];
+        let mut x = 1u32;
+        for b in data.iter_mut() {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            *b = x as u8;
+        }
+
+        // messages of many lengths, enough for more than one group
+        let lens = [0, 1, 7, 8, 9, 15, 16, 17, 100, 1000, 64, 64, 64, 3, 200, 999, 12, 33, 500, 2];
+        let mut messages: [&[u8]; 20] = [&[]; 20];
+        for i in 0..messages.len() {
+            let off = (37*i) % (data.len()-lens[i]+1);
+            messages[i] = &data[off..off+lens[i]];
+        }
+
+        macro_rules! batch {
+            ($crc:ident, $batch:ident) => {{
+                let mut crcs = [0; 20];
+                $batch(&messages, &mut crcs);
+                for i in 0..crcs.len() {
+                    assert_eq!(crcs[i], $crc(messages[i], 0));
+                }
+            }}
+        }
+
+        batch!(crc8_naive,         crc8_naive_batch);
+        batch!(crc16_table,        crc16_table_batch);
+        batch!(crc32_small_table,  crc32_small_table_batch);
+        batch!(crc32c_barret,      crc32c_barret_batch);
+        batch!(crc64,              crc64_batch);
+        batch!(crc4_naive,         crc4_naive_batch);
+        batch!(crc12_table,        crc12_table_batch);
+        batch!(crc23_barret,       crc23_barret_batch);
+        batch!(crc32_barret_unreflected, crc32_barret_unreflected_batch);
+
+        // init differing from xor
+        batch!(crc16_naive_ccitt_false, crc16_naive_ccitt_false_batch);
+        batch!(crc32_mpeg2,        crc32_mpeg2_batch);
+        batch!(crc64_ecma,         crc64_ecma_batch);
+
+        // previous state should carry through
+        let mut crcs = [0; 20];
+        for i in 0..crcs.len() {
+            crcs[i] = crc32c(&messages[i][..lens[i]/2], 0);
+        }
+        let mut rest: [&[u8]; 20] = [&[]; 20];
+        for i in 0..rest.len() {
+            rest[i] = &messages[i][lens[i]/2..];
+        }
+        crc32c_batch(&rest, &mut crcs);
+        for i in 0..crcs.len() {
+            assert_eq!(crcs[i], crc32c(messages[i], 0));
+        }
+    }
+
+    #[cfg(feature="rayon")]
+    #[test]
+    fn crc_par() {
+        extern crate std;
+        use std::vec::Vec;
+
+        // large enough to split into several chunks, with an uneven tail
+        let mut x = 1u32;
+        let data = (0..1000003).map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x as u8
+        }).collect::<Vec<u8>>();
+
+        for len in [0, 1, 100, 65536, 65537, 300000, 1000003] {
+            let data = &data[..len];
+            assert_eq!(par_crc8(data, 0),   crc8(data, 0));
+            assert_eq!(par_crc16(data, 0),  crc16(data, 0));
+            assert_eq!(par_crc32(data, 0),  crc32(data, 0));
+            assert_eq!(par_crc32c(data, 0), crc32c(data, 0));
+            assert_eq!(par_crc64(data, 0),  crc64(data, 0));
+
+            assert_eq!(par_crc12_table(data, 0), crc12_table(data, 0));
+            assert_eq!(par_crc32_barret_unreflected(data, 0), crc32_barret_unreflected(data, 0));
+            assert_eq!(par_crc16_ccitt_false(data, 0), crc16_ccitt_false(data, 0));
+            assert_eq!(par_crc64_ecma(data, 0), crc64_ecma(data, 0));
+        }
+
+        // previous state should carry through
+        let a = crc32c(&data[..1000], 0);
+        assert_eq!(par_crc32c(&data[1000..], a), crc32c(&data, 0));
+    }
+
+    #[test]
+    fn crc_uncombine() {
+        macro_rules! uncombine {
+            ($crc:ident, $combine:ident, $uncombine:ident) => {{
+                let data = b"Hello World! This is a longer message to split up.";
+                for i in 0..=data.len() {
+                    let (a, b) = data.split_at(i);
+                    assert_eq!(
+                        $uncombine($crc(data, 0), $crc(b, 0), b.len()),
+                        $crc(a, 0)
+                    );
+                    // removing a prefix is just combine
+                    assert_eq!(
+                        $combine($crc(a, 0), $crc(data, 0), b.len()),
+                        $crc(b, 0)
+                    );
+                }
+            }}
+        }
+
+        uncombine!(crc8_naive,         crc8_naive_combine,         crc8_naive_uncombine);
+        uncombine!(crc16_table,        crc16_table_combine,        crc16_table_uncombine);
+        uncombine!(crc32_small_table,  crc32_small_table_combine,  crc32_small_table_uncombine);
+        uncombine!(crc32c_barret,      crc32c_barret_combine,      crc32c_barret_uncombine);
+        uncombine!(crc64,              crc64_combine,              crc64_uncombine);
+        uncombine!(crc4_naive,         crc4_naive_combine,         crc4_naive_uncombine);
+        uncombine!(crc12_table,        crc12_table_combine,        crc12_table_uncombine);
+        uncombine!(crc23_barret,       crc23_barret_combine,       crc23_barret_uncombine);
+        uncombine!(crc32_barret_unreflected, crc32_barret_unreflected_combine, crc32_barret_unreflected_uncombine);
+
+        // init differing from xor
+        uncombine!(crc16_naive_ccitt_false, crc16_naive_ccitt_false_combine, crc16_naive_ccitt_false_uncombine);
+        uncombine!(crc32_mpeg2,        crc32_mpeg2_combine,        crc32_mpeg2_uncombine);
+        uncombine!(crc64_ecma,         crc64_ecma_combine,         crc64_ecma_uncombine);
+
+        // uncombining large lengths should still be fast
+        let mut data = [0u8; 10000];
+        data[..5].copy_from_slice(b"Hello");
+        assert_eq!(
+            crc32c_uncombine(crc32c(&data, 0), crc32c(&data[5..], 0), data.len()-5),
+            crc32c(&data[..5], 0)
+        );
+    }
+
+    #[test]
+    fn crc_patch() {
+        macro_rules! patch {
+            ($crc:ident, $patch:ident) => {{
+                let data = b"Hello World! This is a longer message to patch up.";
+                for i in 0..=data.len() {
+                    for j in i..=data.len() {
+                        let mut patched = *data;
+                        for b in patched[i..j].iter_mut() {
+                            *b = b.wrapping_mul(3) ^ 0x5a;
+                        }
+                        assert_eq!(
+                            $patch($crc(data, 0), &data[i..j], &patched[i..j], i, data.len()),
+                            $crc(&patched, 0)
+                        );
+                    }
+                }
+            }}
+        }
+
+        patch!(crc8_naive,         crc8_naive_patch);
+        patch!(crc16_table,        crc16_table_patch);
+        patch!(crc32_small_table,  crc32_small_table_patch);
+        patch!(crc32c_barret,      crc32c_barret_patch);
+        patch!(crc64,              crc64_patch);
+        patch!(crc4_naive,         crc4_naive_patch);
+        patch!(crc12_table,        crc12_table_patch);
+        patch!(crc23_barret,       crc23_barret_patch);
+        patch!(crc32_barret_unreflected, crc32_barret_unreflected_patch);
+
+        // init differing from xor
+        patch!(crc16_table_modbus, crc16_table_modbus_patch);
+        patch!(crc32_mpeg2,        crc32_mpeg2_patch);
+
+        // patching a small range in a large message
+        let mut data = [0u8; 10000];
+        let crc = crc32c(&data, 0);
+        data[16..32].copy_from_slice(b"Hello World!!!!!");
+        assert_eq!(
+            crc32c_patch(crc, &[0u8; 16], b"Hello World!!!!!", 16, data.len()),
+            crc32c(&data, 0)
+        );
+    }
+
+    #[test]
+    fn crc_verify() {
+        macro_rules! verify {
+            ($crc:ident, $verify:ident, $width:expr, $reflected:expr) => {{
+                let k = usize::div_ceil($width, 8);
+                let mut data = [0u8; 12+16];
+                data[..12].copy_from_slice(b"Hello World!");
+                let crc = $crc(&data[..12], 0);
+                if $reflected {
+                    data[12..12+k].copy_from_slice(&crc.to_le_bytes()[..k]);
+                } else {
+                    let bytes = (crc << (8*k-$width)).to_be_bytes();
+                    data[12..12+k].copy_from_slice(&bytes[bytes.len()-k..]);
+                }
+                assert!($verify(&data[..12+k]));
+
+                // any single-bit error should be caught
+                for i in 0..8*(12+k) {
+                    data[i/8] ^= 1 << (i%8);
+                    assert!(!$verify(&data[..12+k]));
+                    data[i/8] ^= 1 << (i%8);
+                }
+
+                // too short for a CRC
+                assert!(!$verify(&data[..k-1]));
+            }}
+        }
+
+        verify!(crc8_naive,         crc8_naive_verify,         8,  true);
+        verify!(crc16_table,        crc16_table_verify,        16, true);
+        verify!(crc32_small_table,  crc32_small_table_verify,  32, true);
+        verify!(crc32c_barret,      crc32c_barret_verify,      32, true);
+        verify!(crc64_slice_by_8,   crc64_slice_by_8_verify,   64, true);
+        verify!(crc32_folding,      crc32_folding_verify,      32, true);
+        verify!(crc32,              crc32_verify,              32, true);
+        verify!(crc64,              crc64_verify,              64, true);
+
+        // odd sizes
+        verify!(crc4_naive,         crc4_naive_verify,         4,  true);
+        verify!(crc12_table,        crc12_table_verify,        12, true);
+        verify!(crc23_barret,       crc23_barret_verify,       23, true);
+        verify!(crc5_usb,           crc5_usb_verify,           5,  true);
+        verify!(crc24_ble,          crc24_ble_verify,          24, true);
+
+        // unreflected
+        verify!(crc32_naive_unreflected,  crc32_naive_unreflected_verify,  32, false);
+        verify!(crc32_barret_unreflected, crc32_barret_unreflected_verify, 32, false);
+        verify!(crc8_autosar,             crc8_autosar_verify,             8,  false);
+        verify!(crc11_flexray,            crc11_flexray_verify,            11, false);
+        verify!(crc12_dect,               crc12_dect_verify,               12, false);
+        verify!(crc21_can_fd,             crc21_can_fd_verify,             21, false);
+        verify!(crc24_openpgp,            crc24_openpgp_verify,            24, false);
+        verify!(crc64_ecma,               crc64_ecma_verify,               64, false);
+
+        // init differing from xor
+        verify!(crc16_naive_ccitt_false, crc16_naive_ccitt_false_verify, 16, false);
+        verify!(crc16_table_modbus,      crc16_table_modbus_verify,      16, true);
+        verify!(crc32_mpeg2,             crc32_mpeg2_verify,             32, false);
+    }
+
+    // error correction
+    #[crc(polynomial=0x11edc6f41, correct=32)] fn crc32c_ecc() {}
+    #[crc(polynomial=0x11edc6f41, barret, correct=32, burst=4)] fn crc32c_barret_ecc() {}
+    #[crc(polynomial=0x11021, reflected=false, xor=0, init=0xffff, correct=16)] fn crc16_ccitt_false_ecc() {}
+    #[crc(polynomial=0x11e7, table, correct=8, burst=3)] fn crc12_table_ecc() {}
+    #[crc(polynomial=0x1864cfb, reflected=false, xor=0, init=0xb704ce, correct=32, burst=8)] fn crc24_openpgp_ecc() {}
+
+    #[test]
+    fn crc_correct() {
+        macro_rules! correct {
+            ($crc:ident, $correct:ident, $width:expr, $reflected:expr, $len:expr, $burst:expr) => {{
+                let k = usize::div_ceil($width, 8);
+                let mut data = [0u8; $len];
+                for (i, b) in data[..$len-k].iter_mut().enumerate() {
+                    *b = b"Hello World!"[i % 12];
+                }
+                let crc = $crc(&data[..$len-k], 0);
+                if $reflected {
+                    data[$len-k..].copy_from_slice(&crc.to_le_bytes()[..k]);
+                } else {
+                    let bytes = (crc << (8*k-$width)).to_be_bytes();
+                    data[$len-k..].copy_from_slice(&bytes[bytes.len()-k..]);
+                }
+                let expected = data;
+                assert_eq!($correct(&mut data), Ok(0));
+
+                // any burst starting and ending with an error should be
+                // correctable
+                for len in 1..=$burst {
+                    let patterns = if len == 1 { 1 } else { 1 << (len-2) };
+                    for i in 0..patterns {
+                        let pattern: u32 = if len == 1 { 1 } else { (1 << (len-1)) | (i << 1) | 1 };
+                        for pos in 0..8*$len+1-len {
+                            for j in 0..len {
+                                if pattern & (1 << j) != 0 {
+                                    if $reflected {
+                                        data[(pos+j)/8] ^= 1 << ((pos+j)%8);
+                                    } else {
+                                        data[(pos+j)/8] ^= 0x80 >> ((pos+j)%8);
+                                    }
+                                }
+                            }
+                            assert_ne!(data, expected);
+                            assert_eq!($correct(&mut data), Ok(pattern.count_ones() as usize));
+                            assert_eq!(data, expected);
+                        }
+                    }
+                }
+            }}
+        }
+
+        correct!(crc32c_ecc,            crc32c_ecc_correct,            32, true,  32, 1);
+        correct!(crc32c_barret_ecc,     crc32c_barret_ecc_correct,     32, true,  32, 4);
+        correct!(crc16_ccitt_false_ecc, crc16_ccitt_false_ecc_correct, 16, false, 16, 1);
+        correct!(crc12_table_ecc,       crc12_table_ecc_correct,       12, true,  8,  3);
+        correct!(crc24_openpgp_ecc,     crc24_openpgp_ecc_correct,     24, false, 32, 8);
+
+        // shorter data
+        let mut data = [0u8; 16];
+        data[..12].copy_from_slice(b"Hello World!");
+        let crc = crc32c_ecc(&data[..12], 0);
+        data[12..].copy_from_slice(&crc.to_le_bytes());
+        let expected = data;
+        data[3] ^= 0x02;
+        assert_eq!(crc32c_ecc_correct(&mut data), Ok(1));
+        assert_eq!(data, expected);
+
+        // too many errors
+        data[0] ^= 0x01;
+        data[5] ^= 0x10;
+        data[9] ^= 0x80;
+        assert_eq!(crc32c_ecc_correct(&mut data), Err(Error::TooManyErrors));
+
+        // unsupported lengths
+        assert_eq!(crc32c_ecc_correct(&mut data[..3]), Err(Error::UnsupportedLength));
+        assert_eq!(crc32c_ecc_correct(&mut [0u8; 33]), Err(Error::UnsupportedLength));
+    }
+
+    #[test]
+    fn crc_digest_trait() {
+        fn crc<D: Digest>(mut digest: D, data: &[u8]) -> D::U {
+            for chunk in data.chunks(5) {
+                digest.update(chunk);
+            }
+            digest.finalize()
+        }
+
+        assert_eq!(crc(Crc8NaiveDigest::new(), b"Hello World!"),       0xb3);
+        assert_eq!(crc(Crc16TableDigest::new(), b"Hello World!"),      0x0bbb);
+        assert_eq!(crc(Crc32SmallTableDigest::new(), b"Hello World!"), 0x1c291ca3);
+        assert_eq!(crc(Crc32cBarretDigest::new(), b"Hello World!"),    0xfe6cf1dc);
+        assert_eq!(crc(Crc64Digest::new(), b"Hello World!"),           0x75045245c9ea6fe2);
+        assert_eq!(crc(Crc16CcittFalseDigest::new(), b"Hello World!"), 0x882a);
+
+        let mut digest = Crc32cDigest::new();
+        Digest::update(&mut digest, b"Hello World!");
+        Digest::reset(&mut digest);
+        assert_eq!(Digest::finalize(&digest), Crc32cDigest::new().finalize());
+    }
+
+    #[test]
+    fn crc_checksum_trait() {
+        fn check<C: Checksum>(width: usize, polynomial: u128, reflected: bool)
+        where
+            C::U: core::fmt::Debug + PartialEq
+        {
+            assert_eq!(C::WIDTH, width);
+            assert_eq!(C::POLYNOMIAL, polynomial);
+            assert_eq!(C::REFLECTED, reflected);
+
+            let mut digest = C::default();
+            digest.update(b"Hello ");
+            digest.update(b"World!");
+            assert_eq!(digest.finalize(), C::checksum(b"Hello World!"));
+        }
+
+        check::<Crc8Digest>(8, 0x107, true);
+        check::<Crc16TableDigest>(16, 0x11021, true);
+        check::<Crc32BarretUnreflectedDigest>(32, 0x104c11db7, false);
+        check::<Crc64Digest>(64, 0x142f0e1eba9ea3693, true);
+        check::<Crc4NaiveDigest>(4, 0x13, true);
+        check::<Crc23BarretDigest>(23, 0x8002a9, true);
+
+        assert_eq!(Crc32cDigest::checksum(b"Hello World!"), 0xfe6cf1dc);
+        assert_eq!(Crc16CcittFalseDigest::INIT, 0xffff);
+        assert_eq!(Crc24BleDigest::INIT, 0x555555);
+        assert_eq!(Crc16CcittFalseDigest::XOR, 0);
+        assert_eq!(Crc16CcittFalseDigest::checksum(b"123456789"), 0x29b1);
+        assert_eq!(<Crc16CcittFalseDigest as Checksum>::CHECK, 0x29b1);
+    }
+
+    #[test]
+    fn crc_check() {
+        assert_eq!(Crc32Digest::CHECK,           0xcbf43926);
+        assert_eq!(Crc32cDigest::CHECK,          0xe3069283);
+        assert_eq!(Crc5UsbDigest::CHECK,         0x19);
+        assert_eq!(Crc11FlexrayDigest::CHECK,    0x5a3);
+        assert_eq!(Crc24BleDigest::CHECK,        0xc25a56);
+        assert_eq!(Crc64EcmaDigest::CHECK,       0x6c40df5f0b497347);
+
+        // check values should match every mode
+        assert_eq!(Crc32NaiveDigest::CHECK,      crc32_naive(b"123456789", 0));
+        assert_eq!(Crc32cTableDigest::CHECK,     crc32c_table(b"123456789", 0));
+        assert_eq!(Crc64BarretDigest::CHECK,     crc64_barret(b"123456789", 0));
+        assert_eq!(Crc12SliceBy8Digest::CHECK,   crc12_slice_by_8(b"123456789", 0));
+        assert_eq!(Crc23FoldingDigest::CHECK,    crc23_folding(b"123456789", 0));
+    }
+
+    #[cfg(feature="async")]
+    #[test]
+    fn crc_async_writer_reader() {
+        extern crate std;
+        use std::vec::Vec;
+        use futures::io::AsyncWriteExt;
+        use futures::io::AsyncReadExt;
+        use futures::executor::block_on;
+
+        block_on(async {
+            let mut writer = CrcAsyncWriter::new(Vec::new(), Crc16CcittFalseDigest::new());
+            writer.write_all(b"Hello ").await.unwrap();
+            writer.write_all(b"World!").await.unwrap();
+            writer.flush().await.unwrap();
+            writer.close().await.unwrap();
+            assert_eq!(writer.finalize(), 0x882a);
+            let (inner, digest) = writer.into_parts();
+            assert_eq!(inner, b"Hello World!");
+            assert_eq!(digest.finalize(), 0x882a);
+
+            let mut reader = CrcAsyncReader::new(&b"Hello World!"[..], Crc32Digest::new());
+            let mut buf = [0u8; 5];
+            reader.read_exact(&mut buf).await.unwrap();
+            assert_eq!(reader.finalize(), crc32(b"Hello", 0));
+            let mut rest = Vec::new();
+            reader.read_to_end(&mut rest).await.unwrap();
+            assert_eq!(rest, b" World!");
+            assert_eq!(reader.finalize(), 0x1c291ca3);
+        });
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn crc_hamming_distance() {
+        // x^4+x+1 is primitive, so HD=3 up to 2^4-1 bits, including the CRC
+        assert_eq!(hamming_distance(0x13, 11, 4), Some(3));
+        assert_eq!(hamming_distance(0x13, 12, 4), Some(2));
+
+        // HD=4 up to 119 bits
+        assert_eq!(hamming_distance(0x107, 8, 6),   Some(4));
+        assert_eq!(hamming_distance(0x107, 119, 4), Some(4));
+        assert_eq!(hamming_distance(0x107, 120, 4), Some(2));
+        assert_eq!(hamming_distance(0x107, 119, 3), None);
+
+        // CRC32 has a HD > 6 for short messages
+        assert_eq!(hamming_distance(0x104c11db7, 50, 6), None);
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn crc_writer_reader() {
+        extern crate std;
+        use std::io::Write;
+        use std::io::Read;
+        use std::vec::Vec;
+
+        // a writer that only accepts a few bytes at a time
+        struct Slow(Vec<u8>);
+        impl Write for Slow {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                let n = buf.len().min(3);
+                self.0.extend_from_slice(&buf[..n]);
+                Ok(n)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer = CrcWriter::new(Slow(Vec::new()), Crc16CcittFalseDigest::new());
+        write!(writer, "Hello {}!", "World").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.finalize(), 0x882a);
+        let (inner, digest) = writer.into_parts();
+        assert_eq!(inner.0, b"Hello World!");
+        assert_eq!(digest.finalize(), 0x882a);
+
+        let mut reader = CrcReader::new(&b"Hello World!"[..], Crc32Digest::new());
+        let mut buf = [0u8; 5];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(reader.finalize(), crc32(b"Hello", 0));
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b" World!");
+        assert_eq!(reader.finalize(), 0x1c291ca3);
+        assert_eq!(reader.digest().finalize(), 0x1c291ca3);
+    }
+
     // all CRC params
     #[crc(
         polynomial=0x104c11db7,
@@ -934,6 +934,146 @@ mod test {
         }
     }
 
+    #[test]
+    fn slice_mul_acc() {
+        // test a number of lengths to make sure we hit all code paths
+        let mut a = [0u8; 100];
+        let mut b = [0u8; 100];
+        for i in 0..100 {
+            a[i] = (i as u8).wrapping_mul(0x45);
+            b[i] = (i as u8).wrapping_mul(0x9b) ^ 0x5a;
+        }
+        for c in [0x00, 0x01, 0x02, 0x53, 0xff] {
+            for len in 0..100 {
+                let mut x = a.map(gf256);
+                gf256::slice_mul_acc(&mut x[..len], &b.map(gf256)[..len], gf256(c));
+                for i in 0..len {
+                    assert_eq!(x[i], gf256(a[i]) + gf256(b[i])*gf256(c));
+                }
+
+                // different polynomials share the same kernel
+                let mut x = a.map(gf256_rijndael);
+                gf256_rijndael::slice_mul_acc(&mut x[..len], &b.map(gf256_rijndael)[..len], gf256_rijndael(c));
+                for i in 0..len {
+                    assert_eq!(x[i], gf256_rijndael(a[i]) + gf256_rijndael(b[i])*gf256_rijndael(c));
+                }
+            }
+        }
+
+        // smaller and larger fields
+        let mut x = [gf16::new(0x1), gf16::new(0x2), gf16::new(0x3)];
+        gf16::slice_mul_acc(&mut x, &[gf16::new(0x4), gf16::new(0x5), gf16::new(0x6)], gf16::new(0x7));
+        assert_eq!(x, [
+            gf16::new(0x1) + gf16::new(0x4)*gf16::new(0x7),
+            gf16::new(0x2) + gf16::new(0x5)*gf16::new(0x7),
+            gf16::new(0x3) + gf16::new(0x6)*gf16::new(0x7),
+        ]);
+
+        let mut x = [gf2p16(0x1234), gf2p16(0x5678)];
+        gf2p16::slice_mul_acc(&mut x, &[gf2p16(0x9abc), gf2p16(0xdef0)], gf2p16(0x1357));
+        assert_eq!(x, [
+            gf2p16(0x1234) + gf2p16(0x9abc)*gf2p16(0x1357),
+            gf2p16(0x5678) + gf2p16(0xdef0)*gf2p16(0x1357),
+        ]);
+
+        // Barret mode multiplies 4 elements at a time, so test a number
+        // of lengths in wider fields
+        macro_rules! test_slice_mul_acc {
+            ($gf:ty, $new:expr) => {
+                let a: [$gf; 10] = core::array::from_fn(|i| $new(0x9e3779b97f4a7c15u64.wrapping_mul(i as u64 + 1)));
+                let b: [$gf; 10] = core::array::from_fn(|i| $new(0xc2b2ae3d27d4eb4fu64.wrapping_mul(i as u64 + 7)));
+                let c: $gf = $new(0x165667b19e3779f9);
+                for len in 0..10 {
+                    let mut x = a;
+                    <$gf>::slice_mul_acc(&mut x[..len], &b[..len], c);
+                    for i in 0..10 {
+                        assert_eq!(x[i], if i < len { a[i] + b[i]*c } else { a[i] });
+                    }
+                }
+            }
+        }
+        test_slice_mul_acc!(gf4096,        |x: u64| gf4096::new((x & 0xfff) as u16));
+        test_slice_mul_acc!(gf2p16,        |x: u64| gf2p16(x as u16));
+        test_slice_mul_acc!(gf2p32,        |x: u64| gf2p32(x as u32));
+        test_slice_mul_acc!(gf2p64,        |x: u64| gf2p64(x));
+        test_slice_mul_acc!(gf4096_barret, |x: u64| gf4096_barret::new((x & 0xfff) as u16));
+        test_slice_mul_acc!(gf2p23_barret, |x: u64| gf2p23_barret::new((x & 0x7fffff) as u32));
+        test_slice_mul_acc!(gf2p64_barret, |x: u64| gf2p64_barret(x));
+    }
+
+    #[test]
+    fn mul_acc8_rows() {
+        use crate::internal::simd::*;
+
+        // each row is multiplied by its own constant, test a number of
+        // lengths to make sure we hit all code paths in each kernel
+        let cs = [0x00, 0x01, 0x02, 0x53, 0xff];
+        let kernels = [Kernel::Table, Kernel::Ssse3, Kernel::Avx2, Kernel::Gfni, Kernel::Neon];
+        for kernel in kernels.iter().filter(|k| k.is_available()) {
+            let prepared = cs.map(|c| {
+                Prepared8::new(*kernel, core::array::from_fn(|j| (gf256(c)*gf256(1 << j)).get()))
+            });
+            for len in 0..100 {
+                let src: [u8; 100] = core::array::from_fn(|i| (i as u8).wrapping_mul(0x9b) ^ 0x5a);
+                let mut dst: [u8; 500] = core::array::from_fn(|i| (i as u8).wrapping_mul(0x45));
+                let orig = dst;
+                mul_acc8_rows(&mut dst[..5*len], &src[..len], &prepared);
+                for (j, c) in cs.iter().enumerate() {
+                    for i in 0..len {
+                        assert_eq!(
+                            gf256(dst[j*len+i]),
+                            gf256(orig[j*len+i]) + gf256(src[i])*gf256(*c)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn field_trait() {
+        use crate::traits::Field;
+
+        // field properties, written once for all fields
+        fn test<F: Field>(a: F, b: F) {
+            assert_eq!(F::try_from_u(a.to_u()), Some(a));
+            assert_eq!(Field::checked_div(a*b, b), Some(a));
+            assert_eq!(Field::checked_div(a, F::ZERO), None);
+            assert_eq!(Field::checked_recip(F::ZERO), None);
+            assert_eq!(Field::checked_recip(a).map(|x| x*a), Some(F::ONE));
+            assert_eq!(Field::pow(a, F::NONZEROS), F::ONE);
+            assert_eq!(Field::pow(F::GENERATOR, F::NONZEROS), F::ONE);
+        }
+
+        test(gf16::new(0x6), gf16::new(0x3));
+        test(gf256(0x69), gf256(0x34));
+        test(gf4096::new(0x696), gf4096::new(0x123));
+        test(gf2p16(0x6969), gf2p16(0x1234));
+        test(gf2p32(0x69696969), gf2p32(0x12345));
+        test(gf2p64(0x6969696969696969), gf2p64(0x123456789));
+
+        // non-power-of-two fields reject unrepresentable values
+        assert_eq!(gf16::try_from_u(0xf), Some(gf16::new(0xf)));
+        assert_eq!(gf16::try_from_u(0x10), None);
+        assert_eq!(gf16::WIDTH, 4);
+        assert_eq!(gf256::WIDTH, 8);
+        assert_eq!(gf2p64::WIDTH, 64);
+    }
+
+    #[cfg(feature="zeroize")]
+    #[test]
+    fn gf_zeroize() {
+        use zeroize::Zeroize;
+
+        let mut a = gf256(0x12);
+        a.zeroize();
+        assert_eq!(a, gf256(0));
+
+        let mut xs = [gf2p64(0x1234), gf2p64(0x5678)];
+        xs.zeroize();
+        assert_eq!(xs, [gf2p64(0); 2]);
+    }
+
     // Test higher/lower order fields
     //
     // These polynomials/generators were all found using the find-p
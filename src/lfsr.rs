@@ -399,6 +399,48 @@
 //! # }
 //! ```
 //!
+//! The LFSR structs also keep track of their seed and position, so
+//! [`seek`](crate::lfsr::Lfsr8::seek) can jump to an absolute position in the stream,
+//! regardless of where the LFSR currently is:
+//!
+//! ``` rust
+//! # pub use ::gf256::*;
+//! use ::gf256::lfsr::lfsr;
+//!
+//! #[lfsr(polynomial=0x11d)]
+//! struct Lfsr {}
+//!
+//! # fn main() {
+//! let mut lfsr = Lfsr::new(0b11001100);
+//! lfsr.skip(50);
+//! lfsr.seek(100);
+//! assert_eq!(lfsr.position(), 100);
+//! assert_eq!(lfsr.next(8), 0b10011111);
+//! # }
+//! ```
+//!
+//! ## `const fn` support
+//!
+//! Like the Galois-field types, the LFSR structs provide a couple "naive"
+//! functions, [`naive_next`](crate::lfsr::Lfsr8::naive_next) and
+//! [`naive_skip`](crate::lfsr::Lfsr8::naive_skip), that can be used in const fns. These
+//! take and return the LFSR by value, which is useful for deriving whitening
+//! tables or interleaver permutations at compile-time:
+//!
+//! ``` rust
+//! # pub use ::gf256::*;
+//! use ::gf256::lfsr::lfsr;
+//!
+//! #[lfsr(polynomial=0x11d)]
+//! struct Lfsr {}
+//!
+//! # fn main() {
+//! const LFSR: Lfsr = Lfsr::new(0b11001100).naive_skip(100);
+//! const OUTPUT: u8 = LFSR.naive_next(8).1;
+//! assert_eq!(OUTPUT, 0b10011111);
+//! # }
+//! ```
+//!
 //! ## Optimizations
 //!
 //! Since LFSRs are equivalent to Galois-fields, they share a lot of the same
@@ -409,6 +451,14 @@
 //! uses the shift-and-xor implementation, since this is much faster than full
 //! polynomial division/remainder without other optimizations. Additional modes:
 //!
+//! - In `sparse` mode, LFSRs take advantage of polynomials with only a handful
+//!   of terms, such as trinomials and pentanomials. If the polynomial's terms
+//!   leave a gap below x^width, multiple bits can be shifted out at once, with
+//!   only one shift and xor per term to apply the remainder.
+//!
+//!   This is selected automatically when the polynomial has at most 4 terms
+//!   below x^width and allows at least 8 bits to be shifted at once.
+//!
 //! - In `table` mode, LFSRs use a precomputed division and remainder table to
 //!   compute both the quotient and remainder a byte at a time.
 //!
@@ -444,17 +494,39 @@
 //! ## The `Rng` trait
 //!
 //! In addition to the above APIs, the LFSR structs in this module satisfy the
-//! [`RngCore`](rand::RngCore) and [`SeedableRng`](rand::SeedableRng) traits found
-//! in the [`rand`] crate. This allows custom LFSRs to act as drop-in replacements
-//! for other pseudo-random number generators with the additional ability to seek and
+//! [`RngCore`](rand_core::RngCore) and [`SeedableRng`](rand_core::SeedableRng) traits
+//! found in the [`rand_core`] crate, and so work with anything built on the
+//! [`rand`](https://docs.rs/rand) crate's [`Rng`](https://docs.rs/rand/0.8/rand/trait.Rng.html)
+//! trait. This allows custom LFSRs to act as drop-in replacements for other
+//! pseudo-random number generators with the additional ability to seek and
 //! rewind, allowing perfect replayability.
 //!
+//! Note these traits are implemented against [`rand_core`] directly, so the
+//! LFSR structs don't require the full [`rand`](https://docs.rs/rand) crate.
+//!
 //! Note! If you're just looking for a pseudo-random number generator, the
 //! randomness generated by these LFSRs is equivalent to the same-sized, naive
 //! [Xorshift generators][xorshift], with the same limitations and cycle-length.
 //! However, Xorshift generators are much more efficient, using only a handful of
 //! shifts and xors.
 //!
+//! ## Scramblers
+//!
+//! This module also provides a
+//! [`SelfSyncScrambler`](crate::lfsr::SelfSyncScrambler), a multiplicative
+//! scrambler that feeds the scrambled data back into its shift register. This
+//! allows the descrambler to synchronize without a shared seed, as required by
+//! SONET/SDH and many serial links.
+//!
+//! For standards that xor the data with a free-running LFSR, there is also an
+//! [`AdditiveScrambler`](crate::lfsr::AdditiveScrambler), along with presets
+//! for DVB-S2, PCIe, IEEE 802.3, and Bluetooth Low Energy.
+//!
+//! The PRBS test patterns from ITU-T O.150, [`Prbs7`](crate::lfsr::Prbs7)
+//! through [`Prbs31`](crate::lfsr::Prbs31), are provided as additive
+//! scramblers, and [`PrbsChecker`](crate::lfsr::PrbsChecker) can lock onto
+//! these patterns and count bit errors.
+//!
 //!
 //! [lfsr-wiki]: https://en.wikipedia.org/wiki/Linear-feedback_shift_register
 //! [exp-by-squaring]: https://en.wikipedia.org/wiki/Exponentiation_by_squaring
@@ -464,6 +536,37 @@
 //! [benchmarks]: https://github.com/geky/gf256/blob/master/BENCHMARKS.md
 
 
+#[cfg(feature="std")]
+extern crate std;
+
+use core::ops::Add;
+use core::ops::Sub;
+use core::ops::Mul;
+use core::ops::Div;
+use core::fmt;
+
+
+/// Error codes for LFSRs
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// An LFSR's state can't be zero, since zero is a fixed point and
+    /// would only ever output zeros
+    ///
+    ZeroSeed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ZeroSeed => write!(f, "LFSR seed can't be zero"),
+        }
+    }
+}
+
+#[cfg(feature="std")]
+impl std::error::Error for Error {}
+
+
 /// A macro for generating custom LFSR structs.
 ///
 /// ``` rust
@@ -503,8 +606,23 @@
 ///   for computations, defaults to the correct type based on `p`.
 /// - `reflected` - Indicate if the LFSR should have its bits reversed,
 ///   defaults to false.
+/// - `msb_first` - Output bits msb-first, so the first bit generated is in
+///   the msb of `next`'s result. This is the default, unless `reflected`.
+/// - `lsb_first` - Output bits lsb-first, so the first bit generated is in
+///   the lsb of `next`'s result. This is the default if `reflected`. This
+///   allows the output order to differ from the state's bit-order, which
+///   `reflected` controls.
+/// - `galois` - Model a Galois LFSR, where the state is the remainder
+///   after feeding back into each tap. This is the default.
+/// - `fibonacci` - Model a Fibonacci LFSR, where the state is a shift
+///   register holding the next width-bits of output, and feedback is the
+///   xor of the taps. This generates the same sequence as `galois`, but
+///   seeds and states match hardware that uses a Fibonacci LFSR.
 /// - `naive` - Use a naive bitwise implementation.
-/// - `table` - Use precomputed quotient and remainder tables. This is the default.
+/// - `sparse` - Use a shift and xor per term of the polynomial. This is the
+///   default for sparse polynomials.
+/// - `table` - Use precomputed quotient and remainder tables. This is the default
+///   otherwise.
 /// - `small_table` - Use small, 16-element division and remainder tables.
 /// - `barret` - Use Barret-reduction with polynomial multiplication.
 /// - `table_barret` - Use Barret-reduction for the remainder, and a
@@ -516,6 +634,9 @@
 /// - `small_table_skip` - Use a small, 16-element remainder table to calculate skips.
 /// - `barret_skip` - Use Barret-reduction with polynomial multiplication to
 ///   calculate skips. This is the default.
+/// - `maximal` - Generate a `verify_maximal` function, which checks that the
+///   LFSR is maximal-length. This requires factoring `2^width-1` at compile
+///   time, which can be slow for large widths.
 ///
 /// ``` rust
 /// # use ::gf256::*;
@@ -530,7 +651,12 @@
 ///     p=p16,
 ///     p2=p32,
 ///     reflected=false,
+///     // msb_first,
+///     // lsb_first,
+///     // galois,
+///     // fibonacci,
 ///     // naive,
+///     // sparse,
 ///     // table,
 ///     // small_table,
 ///     // barret,
@@ -540,6 +666,7 @@
 ///     // table_skip,
 ///     // small_table_skip,
 ///     // barret_skip,
+///     // maximal,
 /// )]
 /// pub struct MyLfsr16 {}
 ///
@@ -561,19 +688,927 @@ pub use gf256_macros::lfsr;
 
 // Default LFSR structs
 //
-#[lfsr(polynomial=0x11d)]
+#[lfsr(polynomial=0x11d, maximal)]
 pub struct Lfsr8 {}
-#[lfsr(polynomial=0x1002d)]
+#[lfsr(polynomial=0x1002d, maximal)]
 pub struct Lfsr16 {}
-#[lfsr(polynomial=0x1000000af)]
+#[lfsr(polynomial=0x1000000af, maximal)]
 pub struct Lfsr32 {}
-#[lfsr(polynomial=0x1000000000000001b)]
+#[lfsr(polynomial=0x1000000000000001b, maximal)]
 pub struct Lfsr64 {}
 
 
+/// A trait for LFSR structs
+///
+/// This is implemented by all LFSR structs, including those created with
+/// the [`lfsr`] macro, and allows generic code, such as [`GoldCode`], to
+/// be written over any LFSR.
+///
+/// Note that the LFSR structs' inherent methods are usually more convenient
+/// when the type is known, this trait is only needed for generic code.
+///
+pub trait Lfsr: Clone {
+    /// The underlying unsigned type
+    type U: Copy;
+
+    /// Width of the LFSR in bits
+    const WIDTH: usize;
+
+    /// Create an LFSR with the given seed, returning an error if the seed
+    /// is zero
+    fn try_new(seed: Self::U) -> Result<Self, Error>;
+
+    /// Generate the next n-bits of pseudo-random data
+    fn next(&mut self, bits: Self::U) -> Self::U;
+
+    /// Generate the previous n-bits of pseudo-random data
+    fn prev(&mut self, bits: Self::U) -> Self::U;
+
+    /// Skip n-bits of pseudo-random data
+    fn skip(&mut self, bits: Self::U);
+
+    /// Skip n-bits of pseudo-random data backwards
+    fn skip_backwards(&mut self, bits: Self::U);
+
+    /// Skip n-bits of pseudo-random data, where n may be larger than the
+    /// state's width
+    fn skip_u128(&mut self, bits: u128);
+
+    /// Skip n-bits of pseudo-random data backwards, where n may be larger
+    /// than the state's width
+    fn skip_backwards_u128(&mut self, bits: u128);
+
+    /// Get the current position of the LFSR in bits, relative to its seed
+    fn position(&self) -> Self::U;
+
+    /// Seek to an absolute position in bits, relative to the LFSR's seed
+    fn seek(&mut self, position: Self::U);
+
+    /// Get the current state of the LFSR
+    fn state(&self) -> Self::U;
+}
+
+
+/// A Gold code generator.
+///
+/// A Gold code is the xor of two maximal-length LFSRs with a "preferred
+/// pair" of polynomials. Different code offsets between the two LFSRs give
+/// a family of sequences with bounded cross-correlation, which is useful for
+/// sharing a channel between multiple transmitters, such as in GPS and CDMA.
+///
+/// For example, the 1023-chip codes used by GPS are built on the preferred
+/// pair `x^10 + x^3 + 1` and `x^10 + x^9 + x^8 + x^6 + x^3 + x^2 + 1`:
+///
+/// ``` rust
+/// # use ::gf256::*;
+/// use gf256::lfsr::{lfsr, GoldCode};
+///
+/// #[lfsr(polynomial=0x409)]
+/// pub struct G1 {}
+/// #[lfsr(polynomial=0x74d)]
+/// pub struct G2 {}
+///
+/// # fn main() {
+/// // each offset gives a different code in the family
+/// let mut code = GoldCode::with_offset(G1::new(0x3ff), G2::new(0x3ff), 5);
+/// let chips = (0..1023).map(|_| code.next(1)).collect::<Vec<_>>();
+///
+/// // codes repeat every 2^10-1 chips
+/// assert_eq!(code.next(10), (0..10).fold(0, |x, i| (x << 1) | chips[i]));
+/// # }
+/// ```
+///
+/// This relies on the LFSRs' seeking machinery, so offsets, skips, and
+/// stepping backwards are all efficient.
+///
+#[derive(Debug, Clone)]
+pub struct GoldCode<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Lfsr, B: Lfsr<U=A::U>> GoldCode<A, B> {
+    /// Create a Gold code generator from a pair of LFSRs.
+    ///
+    /// Note the LFSRs should be maximal-length LFSRs with the same width
+    /// and a preferred pair of polynomials, otherwise the resulting code
+    /// will not have the expected correlation properties.
+    ///
+    #[inline]
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+
+    /// Create a Gold code generator from a pair of LFSRs, with the second
+    /// LFSR advanced by a code offset.
+    ///
+    /// This selects one of the codes in the Gold code family.
+    ///
+    #[inline]
+    pub fn with_offset(a: A, mut b: B, offset: A::U) -> Self {
+        b.skip(offset);
+        Self { a, b }
+    }
+
+    /// Get the underlying pair of LFSRs.
+    #[inline]
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A, B> GoldCode<A, B>
+where
+    A: Lfsr,
+    B: Lfsr<U=A::U>,
+    A::U: core::ops::BitXor<Output=A::U>,
+{
+    /// Generate the next n-chips of the code.
+    #[inline]
+    pub fn next(&mut self, bits: A::U) -> A::U {
+        self.a.next(bits) ^ self.b.next(bits)
+    }
+
+    /// Generate the previous n-chips of the code.
+    #[inline]
+    pub fn prev(&mut self, bits: A::U) -> A::U {
+        self.a.prev(bits) ^ self.b.prev(bits)
+    }
+
+    /// Skip n-chips of the code.
+    #[inline]
+    pub fn skip(&mut self, bits: A::U) {
+        self.a.skip(bits);
+        self.b.skip(bits);
+    }
+
+    /// Skip n-chips of the code backwards.
+    #[inline]
+    pub fn skip_backwards(&mut self, bits: A::U) {
+        self.a.skip_backwards(bits);
+        self.b.skip_backwards(bits);
+    }
+}
+
+
+/// A leapfrogging LFSR, which produces every n-th output of another LFSR.
+///
+/// This is created by an LFSR's `split` method, which divides a single
+/// pseudo-random stream into n interleaved streams. Each Leapfrog is an
+/// endless iterator over its outputs. This is useful for
+/// parallelizing a simulation across threads or SIMD lanes while keeping
+/// the results identical to a single-threaded run:
+///
+/// ``` rust
+/// use gf256::lfsr::Lfsr64;
+///
+/// let mut lfsr = Lfsr64::new(1);
+/// let mut lanes = lfsr.split(4, 64).collect::<Vec<_>>();
+/// for _ in 0..100 {
+///     for lane in lanes.iter_mut() {
+///         assert_eq!(lane.next(), Some(lfsr.next(64)));
+///     }
+/// }
+/// ```
+///
+/// Each output jumps over the outputs of the other n-1 LFSRs using the
+/// LFSR's seeking machinery, so this costs one skip per output.
+///
+#[derive(Debug, Clone)]
+pub struct Leapfrog<L: Lfsr> {
+    lfsr: L,
+    bits: L::U,
+    stride: u128,
+}
+
+impl<L: Lfsr> Leapfrog<L>
+where
+    L::U: Into<u128>
+{
+    /// Split an LFSR into n interleaved LFSRs, each producing every n-th
+    /// `bits`-sized output.
+    ///
+    /// This is the same as the LFSR's `split` method.
+    ///
+    pub fn split(lfsr: L, n: usize, bits: L::U) -> impl Iterator<Item=Self> {
+        assert!(n > 0);
+        let stride = (n as u128 - 1) * bits.into();
+        (0..n).map(move |k| {
+            let mut lfsr = lfsr.clone();
+            lfsr.skip_u128(k as u128 * bits.into());
+            Self { lfsr, bits, stride }
+        })
+    }
+
+    /// Get the underlying LFSR.
+    ///
+    /// Note the underlying LFSR is positioned at this LFSR's next output.
+    ///
+    #[inline]
+    pub fn into_inner(self) -> L {
+        self.lfsr
+    }
+}
+
+impl<L: Lfsr> Iterator for Leapfrog<L>
+where
+    L::U: Into<u128>
+{
+    type Item = L::U;
+
+    /// Generate the next output of this LFSR, this never returns `None`.
+    #[inline]
+    fn next(&mut self) -> Option<L::U> {
+        let x = self.lfsr.next(self.bits);
+        self.lfsr.skip_u128(self.stride);
+        Some(x)
+    }
+}
+
+
+/// A [`Read`](std::io::Read) adapter for an LFSR's byte stream.
+///
+/// This endlessly yields the same bytes as the LFSR's
+/// [`fill_bytes`](rand_core::RngCore::fill_bytes), which makes it easy to
+/// pipe the stream into anything that expects a reader, such as a
+/// statistical test suite:
+///
+/// ``` rust
+/// use gf256::lfsr::{Lfsr16, LfsrReader};
+/// use std::io::Read;
+///
+/// let mut reader = LfsrReader::new(Lfsr16::new(1));
+/// let mut buf = [0; 8];
+/// reader.read_exact(&mut buf).unwrap();
+/// assert_eq!(&buf, &[0x00, 0x01, 0x00, 0x2d, 0x04, 0x51, 0xbd, 0xad]);
+///
+/// // take can limit the stream to a finite length
+/// let mut buf = Vec::new();
+/// reader.by_ref().take(1024).read_to_end(&mut buf).unwrap();
+/// assert_eq!(buf.len(), 1024);
+/// ```
+///
+/// Note this requires the `std` feature.
+///
+#[cfg(feature="std")]
+#[derive(Debug, Clone)]
+pub struct LfsrReader<L> {
+    lfsr: L,
+}
+
+#[cfg(feature="std")]
+impl<L: rand_core::RngCore> LfsrReader<L> {
+    /// Create a reader from an LFSR.
+    #[inline]
+    pub fn new(lfsr: L) -> Self {
+        Self { lfsr }
+    }
+
+    /// Get a reference to the underlying LFSR.
+    #[inline]
+    pub fn get_ref(&self) -> &L {
+        &self.lfsr
+    }
+
+    /// Get a mutable reference to the underlying LFSR.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut L {
+        &mut self.lfsr
+    }
+
+    /// Get the underlying LFSR.
+    #[inline]
+    pub fn into_inner(self) -> L {
+        self.lfsr
+    }
+}
+
+#[cfg(feature="std")]
+impl<L: rand_core::RngCore> std::io::Read for LfsrReader<L> {
+    /// Read bytes from the LFSR's stream, this always fills the buffer.
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.lfsr.fill_bytes(buf);
+        Ok(buf.len())
+    }
+}
+
+
+/// A word-oriented LFSR, where each cell is a symbol in a finite-field.
+///
+/// Unlike the bitwise LFSRs in this module, a word-oriented LFSR, sometimes
+/// called a σ-LFSR, shifts out one field symbol at a time, with feedback
+/// defined by a polynomial over the field. This generates byte-oriented
+/// sequences directly, and is the basis of several stream ciphers.
+///
+/// The LFSR follows the recurrence `s[t+N] = sum(taps[i]*s[t+i])`, which
+/// is defined by the monic feedback polynomial
+/// `x^N + taps[N-1]*x^(N-1) + ... + taps[1]*x + taps[0]`. If this
+/// polynomial is primitive over the field, the LFSR will have the maximal
+/// period of `q^N-1`, where q is the size of the field:
+///
+/// ``` rust
+/// # use ::gf256::*;
+/// use ::gf256::lfsr::WordLfsr;
+///
+/// // x^2 + x + 0x22 is primitive over gf256, giving a period of 65535
+/// let mut lfsr = WordLfsr::new(
+///     [gf256(0x22), gf256(0x01)],
+///     [gf256(0x01), gf256(0x00)],
+/// );
+/// let bytes = lfsr.by_ref().take(8).map(|x| x.get()).collect::<Vec<_>>();
+/// assert_eq!(&bytes, &[0x01, 0x00, 0x22, 0x22, 0x52, 0x22, 0x14, 0x64]);
+/// assert_eq!(lfsr.prev(), gf256(0x64));
+/// ```
+///
+/// This works with any of the finite-field types in [`gf`](mod@crate::gf),
+/// including custom fields created with the [`gf`](crate::gf::gf) macro.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordLfsr<G, const N: usize> {
+    taps: [G; N],
+    state: [G; N],
+}
+
+impl<G, const N: usize> WordLfsr<G, N>
+where
+    G: Copy + Default + PartialEq + Add<Output=G> + Mul<Output=G>
+{
+    /// Create a word-oriented LFSR from its feedback taps and a seed.
+    ///
+    /// The seed is the next N symbols of output. Note that an all-zero seed
+    /// is a fixed point of the LFSR, so this will panic if the seed is all
+    /// zero.
+    ///
+    pub fn new(taps: [G; N], seed: [G; N]) -> Self {
+        assert!(N > 0);
+        assert!(seed.iter().any(|x| *x != G::default()), "seed must be non-zero");
+        Self { taps, state: seed }
+    }
+
+    /// Get the feedback taps of the LFSR.
+    #[inline]
+    pub fn taps(&self) -> [G; N] {
+        self.taps
+    }
+
+    /// Get the current state of the LFSR, this is the next N symbols of
+    /// output.
+    #[inline]
+    pub fn state(&self) -> [G; N] {
+        self.state
+    }
+
+    /// Fill a buffer with the next symbols of output.
+    pub fn fill(&mut self, buf: &mut [G]) {
+        for x in buf {
+            *x = self.step();
+        }
+    }
+
+    #[inline]
+    fn step(&mut self) -> G {
+        let x = self.state[0];
+        let feedback = self.taps.iter()
+            .zip(self.state.iter())
+            .fold(G::default(), |s, (t, x)| s + (*t * *x));
+        self.state.copy_within(1.., 0);
+        self.state[N-1] = feedback;
+        x
+    }
+}
+
+impl<G, const N: usize> WordLfsr<G, N>
+where
+    G: Copy + Default + PartialEq + Add<Output=G> + Mul<Output=G>
+        + Sub<Output=G> + Div<Output=G>
+{
+    /// Generate the previous symbol of output.
+    ///
+    /// Note this requires `taps[0]` to be non-zero, otherwise the LFSR
+    /// isn't reversible and this will panic.
+    ///
+    pub fn prev(&mut self) -> G {
+        assert!(self.taps[0] != G::default(), "taps[0] must be non-zero");
+        let feedback = self.taps[1..].iter()
+            .zip(self.state[..N-1].iter())
+            .fold(self.state[N-1], |s, (t, x)| s - (*t * *x));
+        self.state.copy_within(..N-1, 1);
+        self.state[0] = feedback / self.taps[0];
+        self.state[0]
+    }
+}
+
+impl<G, const N: usize> Iterator for WordLfsr<G, N>
+where
+    G: Copy + Default + PartialEq + Add<Output=G> + Mul<Output=G>
+{
+    type Item = G;
+
+    /// Generate the next symbol of output, this never returns `None`.
+    #[inline]
+    fn next(&mut self) -> Option<G> {
+        Some(self.step())
+    }
+}
+
+
+/// A self-synchronizing, or multiplicative, scrambler.
+///
+/// Unlike an additive scrambler, which xors the data with the output of a
+/// free-running LFSR, a multiplicative scrambler feeds the scrambled data
+/// itself back into the shift register. This divides the data stream by the
+/// polynomial, and the descrambler recovers the data by multiplying by the
+/// polynomial.
+///
+/// Since the descrambler's state is just the last width-bits of scrambled
+/// data, the descrambler synchronizes automatically after width-bits,
+/// without any shared seed or framing. This is the scrambler used by
+/// SONET/SDH payloads (`x^43 + 1`) and 64b/66b encoding (`x^58 + x^39 + 1`):
+///
+/// ``` rust
+/// use gf256::lfsr::SelfSyncScrambler;
+///
+/// // 64b/66b scrambler, bits are transmitted lsb-first
+/// type Scrambler = SelfSyncScrambler<0x400008000000001, true>;
+///
+/// let mut data = *b"Hello World!";
+/// Scrambler::new(0).scramble(&mut data);
+/// assert_ne!(&data, b"Hello World!");
+///
+/// // the descrambler doesn't need to know the initial state, it only
+/// // loses the first 58-bits
+/// Scrambler::new(0x123456789).descramble(&mut data);
+/// assert_eq!(&data[8..], b"rld!");
+/// ```
+///
+/// Each term `x^k` in the polynomial is a tap with a delay of k-bits, and
+/// the constant term must be 1. `REFLECTED` controls the bit-order, with
+/// bits processed msb-first by default, or lsb-first if `REFLECTED` is true.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfSyncScrambler<const POLYNOMIAL: u64, const REFLECTED: bool=false> {
+    state: u64,
+}
+
+impl<const POLYNOMIAL: u64, const REFLECTED: bool> SelfSyncScrambler<POLYNOMIAL, REFLECTED> {
+    /// Width of the scrambler's shift register in bits
+    pub const WIDTH: usize = {
+        assert!(POLYNOMIAL & 1 == 1 && POLYNOMIAL > 1);
+        (64-1 - POLYNOMIAL.leading_zeros()) as usize
+    };
+
+    const TAPS: u64 = POLYNOMIAL >> 1;
+    const MASK: u64 = u64::MAX >> (64 - Self::WIDTH);
+
+    /// Create a scrambler with the given shift register state.
+    ///
+    /// The state is the last width-bits of scrambled data, with the most
+    /// recent bit in the lsb. Extra bits are ignored.
+    ///
+    #[inline]
+    pub const fn new(state: u64) -> Self {
+        Self { state: state & Self::MASK }
+    }
+
+    /// Get the current state of the scrambler's shift register.
+    #[inline]
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+
+    #[inline]
+    fn feedback(&self) -> u8 {
+        ((self.state & Self::TAPS).count_ones() & 1) as u8
+    }
+
+    #[inline]
+    fn shift(&mut self, bit: u8) {
+        self.state = ((self.state << 1) | u64::from(bit)) & Self::MASK;
+    }
+
+    /// Scramble a buffer of data in-place.
+    pub fn scramble(&mut self, data: &mut [u8]) {
+        for byte in data {
+            let mut scrambled = 0;
+            for i in 0..8 {
+                let i = if REFLECTED { i } else { 7-i };
+                let bit = ((*byte >> i) & 1) ^ self.feedback();
+                self.shift(bit);
+                scrambled |= bit << i;
+            }
+            *byte = scrambled;
+        }
+    }
+
+    /// Descramble a buffer of data in-place.
+    ///
+    /// Note the first width-bits are only correct if the scrambler's state
+    /// matches the state used to scramble the data.
+    ///
+    pub fn descramble(&mut self, data: &mut [u8]) {
+        for byte in data {
+            let mut descrambled = 0;
+            for i in 0..8 {
+                let i = if REFLECTED { i } else { 7-i };
+                let bit = (*byte >> i) & 1;
+                descrambled |= (bit ^ self.feedback()) << i;
+                self.shift(bit);
+            }
+            *byte = descrambled;
+        }
+    }
+}
+
+impl<const POLYNOMIAL: u64, const REFLECTED: bool> Default for SelfSyncScrambler<POLYNOMIAL, REFLECTED> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+
+/// An additive scrambler.
+///
+/// An additive scrambler xors the data with the output of a free-running
+/// Fibonacci LFSR, so scrambling and descrambling are the same operation,
+/// but both sides need to agree on the seed. The shift register holds the
+/// last width-bits of output, with the most recent bit in the lsb.
+///
+/// This uses the same polynomial convention as [`SelfSyncScrambler`], each
+/// term `x^k` is a tap with a delay of k-bits. Note that standards that
+/// describe their scrambler as a Galois LFSR end up with the reciprocal
+/// polynomial in this convention.
+///
+/// ``` rust
+/// use gf256::lfsr::{AdditiveScrambler, DvbS2Scrambler};
+///
+/// let mut data = *b"Hello World!";
+/// DvbS2Scrambler::new().scramble(&mut data);
+/// assert_eq!(&data, &[0x4b, 0x93, 0x64, 0x58, 0x5f, 0x98, 0xf4, 0xfc, 0xbb, 0x04, 0xd3, 0x52]);
+/// DvbS2Scrambler::new().descramble(&mut data);
+/// assert_eq!(&data, b"Hello World!");
+///
+/// // custom scramblers are just type parameters
+/// type MyScrambler = AdditiveScrambler<0xc001, 0x7fff>;
+/// ```
+///
+/// `REFLECTED` controls the bit-order, with bits processed msb-first by
+/// default, or lsb-first if `REFLECTED` is true.
+///
+/// Several presets for common standards are provided, see
+/// [`DvbS2Scrambler`], [`PcieScrambler`], [`Ieee8023Scrambler`], and
+/// [`BleWhitening`].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdditiveScrambler<const POLYNOMIAL: u64, const SEED: u64, const REFLECTED: bool=false> {
+    seed: u64,
+    state: u64,
+}
+
+impl<const POLYNOMIAL: u64, const SEED: u64, const REFLECTED: bool> AdditiveScrambler<POLYNOMIAL, SEED, REFLECTED> {
+    /// Width of the scrambler's shift register in bits
+    pub const WIDTH: usize = {
+        assert!(POLYNOMIAL & 1 == 1 && POLYNOMIAL > 1);
+        (64-1 - POLYNOMIAL.leading_zeros()) as usize
+    };
+
+    const TAPS: u64 = POLYNOMIAL >> 1;
+    const MASK: u64 = u64::MAX >> (64 - Self::WIDTH);
+
+    /// Create a scrambler with the default seed.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with_state(SEED)
+    }
+
+    /// Create a scrambler with the given shift register state.
+    ///
+    /// The state is the last width-bits of output, with the most recent bit
+    /// in the lsb. Extra bits are ignored. Note that [`reset`](Self::reset)
+    /// returns to this state.
+    ///
+    #[inline]
+    pub const fn with_state(state: u64) -> Self {
+        let state = state & Self::MASK;
+        Self { seed: state, state }
+    }
+
+    /// Create a scrambler that will output the given width-bits next.
+    ///
+    /// This is useful for matching a standard's seed, or for recovering the
+    /// state of a scrambler from width-bits of known data, such as idle
+    /// symbols.
+    ///
+    pub const fn from_output(output: u64) -> Self {
+        // after outputting these bits, the shift register will contain
+        // exactly these bits, so we just need to run the LFSR backwards
+        let mut state = if REFLECTED {
+            output.reverse_bits() >> (64 - Self::WIDTH)
+        } else {
+            output & Self::MASK
+        };
+        let mut i = 0;
+        while i < Self::WIDTH {
+            let bit = (state & 1) ^ (((state >> 1) & Self::TAPS).count_ones() & 1) as u64;
+            state = (state >> 1) | (bit << (Self::WIDTH-1));
+            i += 1;
+        }
+        Self::with_state(state)
+    }
+
+    /// Get the current state of the scrambler's shift register.
+    #[inline]
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Reset the scrambler to its seed.
+    ///
+    /// Most standards reset the scrambler at the start of each frame.
+    ///
+    #[inline]
+    pub fn reset(&mut self) {
+        self.state = self.seed;
+    }
+
+    /// Generate the next n-bits of the scrambler's output.
+    ///
+    /// This is the sequence xored with the data, and can also be used to
+    /// advance the scrambler without scrambling any data.
+    ///
+    pub fn next(&mut self, bits: usize) -> u64 {
+        assert!(bits <= 64);
+        let mut out = 0;
+        for i in 0..bits {
+            let bit = (self.state & Self::TAPS).count_ones() as u64 & 1;
+            self.state = ((self.state << 1) | bit) & Self::MASK;
+            if REFLECTED {
+                out |= bit << i;
+            } else {
+                out = (out << 1) | bit;
+            }
+        }
+        out
+    }
+
+    /// Scramble a buffer of data in-place.
+    pub fn scramble(&mut self, data: &mut [u8]) {
+        for byte in data {
+            *byte ^= self.next(8) as u8;
+        }
+    }
+
+    /// Descramble a buffer of data in-place.
+    ///
+    /// For an additive scrambler this is the same as scrambling.
+    ///
+    #[inline]
+    pub fn descramble(&mut self, data: &mut [u8]) {
+        self.scramble(data)
+    }
+}
+
+impl<const POLYNOMIAL: u64, const SEED: u64, const REFLECTED: bool> Default for AdditiveScrambler<POLYNOMIAL, SEED, REFLECTED> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// DVB-S/DVB-S2 energy dispersal scrambler, `x^15 + x^14 + 1`.
+///
+/// Seeded with `100101010000000` and reset at the start of each frame
+/// (BBFRAME in DVB-S2). Bits are processed msb-first.
+///
+pub type DvbS2Scrambler = AdditiveScrambler<0xc001, 0x00a9>;
+
+/// PCI Express Gen1/Gen2 scrambler, `x^16 + x^5 + x^4 + x^3 + 1`.
+///
+/// PCIe describes this as a Galois LFSR, seeded with `0xffff` on every lane
+/// and reset on each COM symbol, which is equivalent to this state. Bits are
+/// processed lsb-first.
+///
+/// Note the LFSR advances on K-symbols without scrambling them, use
+/// [`next(8)`](AdditiveScrambler::next) to advance the scrambler, and
+/// doesn't advance on SKP symbols at all.
+///
+pub type PcieScrambler = AdditiveScrambler<0x13801, 0x3ed7, true>;
+
+/// IEEE 802.3 100BASE-TX stream cipher scrambler, `x^11 + x^9 + 1`.
+///
+/// 100BASE-TX allows any non-zero seed, the descrambler instead locks onto
+/// the scrambler by observing the idle stream. This can be done with
+/// [`from_output`](AdditiveScrambler::from_output), since idle symbols are
+/// all ones. This preset defaults to a seed of all ones.
+///
+pub type Ieee8023Scrambler = AdditiveScrambler<0xa01, 0x7ff>;
+
+/// Bluetooth Low Energy data whitening, `x^7 + x^4 + 1`.
+///
+/// BLE describes this as a Galois LFSR, seeded with the channel index, see
+/// [`with_channel`](AdditiveScrambler::with_channel). Bits are processed
+/// lsb-first. This preset defaults to channel 0.
+///
+pub type BleWhitening = AdditiveScrambler<0x89, 0x0011, true>;
+
+impl BleWhitening {
+    /// Create a whitening sequence for the given channel index.
+    ///
+    /// The whitening sequence is reset at the start of each packet.
+    ///
+    pub const fn with_channel(channel: u8) -> Self {
+        // run BLE's Galois LFSR, position 0 set to 1 and positions 1-6
+        // set to the channel index msb-first
+        let mut reg = (channel & 0x3f).reverse_bits() | 0x02;
+        let mut output = 0;
+        let mut i = 0;
+        while i < 7 {
+            let bit = reg >> 7;
+            output |= (bit as u64) << i;
+            if bit != 0 {
+                reg ^= 0x11;
+            }
+            reg <<= 1;
+            i += 1;
+        }
+        Self::from_output(output)
+    }
+}
+
+
+/// PRBS7 test pattern, `x^7 + x^6 + 1`, as defined in ITU-T O.150.
+pub type Prbs7 = AdditiveScrambler<0xc1, 0x7f>;
+/// PRBS9 test pattern, `x^9 + x^5 + 1`, as defined in ITU-T O.150.
+pub type Prbs9 = AdditiveScrambler<0x221, 0x1ff>;
+/// PRBS15 test pattern, `x^15 + x^14 + 1`, as defined in ITU-T O.150.
+///
+/// Note O.150 inverts the output of PRBS15, this pattern is not inverted,
+/// matching most serdes hardware.
+///
+pub type Prbs15 = AdditiveScrambler<0xc001, 0x7fff>;
+/// PRBS23 test pattern, `x^23 + x^18 + 1`, as defined in ITU-T O.150.
+///
+/// Note O.150 inverts the output of PRBS23, this pattern is not inverted,
+/// matching most serdes hardware.
+///
+pub type Prbs23 = AdditiveScrambler<0x840001, 0x7fffff>;
+/// PRBS31 test pattern, `x^31 + x^28 + 1`, as defined in ITU-T O.150.
+///
+/// Note O.150 inverts the output of PRBS31, this pattern is not inverted,
+/// matching most serdes hardware.
+///
+pub type Prbs31 = AdditiveScrambler<0x90000001, 0x7fffffff>;
+
+/// A checker for PRBS test patterns.
+///
+/// The checker locks onto an incoming PRBS stream, without needing to know
+/// the stream's offset, and then counts bit errors. This is the receiving
+/// half of a bit error rate tester:
+///
+/// ``` rust
+/// use gf256::lfsr::{Prbs31, Prbs31Checker};
+///
+/// // start somewhere in the middle of the stream
+/// let mut prbs = Prbs31::new();
+/// prbs.next(13);
+/// let mut data = [0u8; 1024];
+/// prbs.scramble(&mut data);
+///
+/// // inject some errors
+/// data[100] ^= 0x01;
+/// data[200] ^= 0x81;
+///
+/// let mut checker = Prbs31Checker::new();
+/// checker.check(&data);
+/// assert!(checker.is_locked());
+/// assert_eq!(checker.errors(), 3);
+/// ```
+///
+/// While unlocked, the checker loads its shift register from the incoming
+/// stream, like a [`SelfSyncScrambler`], and locks after 64-bits of
+/// consecutive matches. Once locked the checker runs freely, so each bit
+/// error is counted exactly once.
+///
+/// Note the checker never unlocks on its own, if the stream is interrupted
+/// you can call [`reset`](PrbsChecker::reset) to resynchronize.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrbsChecker<const POLYNOMIAL: u64, const REFLECTED: bool=false> {
+    state: u64,
+    matches: usize,
+    locked: bool,
+    bits: u64,
+    errors: u64,
+}
+
+impl<const POLYNOMIAL: u64, const REFLECTED: bool> PrbsChecker<POLYNOMIAL, REFLECTED> {
+    /// Width of the checker's shift register in bits
+    pub const WIDTH: usize = {
+        assert!(POLYNOMIAL & 1 == 1 && POLYNOMIAL > 1);
+        (64-1 - POLYNOMIAL.leading_zeros()) as usize
+    };
+
+    const TAPS: u64 = POLYNOMIAL >> 1;
+    const MASK: u64 = u64::MAX >> (64 - Self::WIDTH);
+
+    // short patterns match random data surprisingly often, so require
+    // a minimum number of matches before locking
+    const LOCK: usize = 64;
+
+    /// Create a new, unlocked, PRBS checker.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            state: 0,
+            matches: 0,
+            locked: false,
+            bits: 0,
+            errors: 0,
+        }
+    }
+
+    /// Reset the checker, unlocking and clearing any counts.
+    #[inline]
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns true if the checker has locked onto the PRBS stream.
+    #[inline]
+    pub const fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Number of bits checked since locking.
+    #[inline]
+    pub const fn bits(&self) -> u64 {
+        self.bits
+    }
+
+    /// Number of bit errors found since locking.
+    #[inline]
+    pub const fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    /// Check a single bit of the PRBS stream.
+    pub fn check_bit(&mut self, bit: bool) {
+        let expected = (self.state & Self::TAPS).count_ones() & 1 == 1;
+        if self.locked {
+            self.bits += 1;
+            if bit != expected {
+                self.errors += 1;
+            }
+            self.state = ((self.state << 1) | u64::from(expected)) & Self::MASK;
+        } else {
+            // an all-zero state can't lock, since it's a fixed point
+            if bit == expected && self.state != 0 {
+                self.matches += 1;
+            } else {
+                self.matches = 0;
+            }
+            self.state = ((self.state << 1) | u64::from(bit)) & Self::MASK;
+            if self.matches >= Self::LOCK {
+                self.locked = true;
+            }
+        }
+    }
+
+    /// Check a buffer of the PRBS stream.
+    pub fn check(&mut self, data: &[u8]) {
+        for byte in data {
+            for i in 0..8 {
+                let i = if REFLECTED { i } else { 7-i };
+                self.check_bit((byte >> i) & 1 == 1);
+            }
+        }
+    }
+}
+
+impl<const POLYNOMIAL: u64, const REFLECTED: bool> Default for PrbsChecker<POLYNOMIAL, REFLECTED> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A checker for [`Prbs7`] test patterns.
+pub type Prbs7Checker = PrbsChecker<0xc1>;
+/// A checker for [`Prbs9`] test patterns.
+pub type Prbs9Checker = PrbsChecker<0x221>;
+/// A checker for [`Prbs15`] test patterns.
+pub type Prbs15Checker = PrbsChecker<0xc001>;
+/// A checker for [`Prbs23`] test patterns.
+pub type Prbs23Checker = PrbsChecker<0x840001>;
+/// A checker for [`Prbs31`] test patterns.
+pub type Prbs31Checker = PrbsChecker<0x90000001>;
+
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::gf::*;
     use crate::p::p64;
     use crate::p::p128;
     use core::num::NonZeroU64;
@@ -668,6 +1703,7 @@ mod test {
 
     // explicit modes
     #[lfsr(polynomial=0x11d, naive, naive_skip)]               pub struct Lfsr8Naive {}
+    #[lfsr(polynomial=0x11d, sparse, naive_skip)]              pub struct Lfsr8Sparse {}
     #[lfsr(polynomial=0x11d, table, table_skip)]               pub struct Lfsr8Table {}
     #[lfsr(polynomial=0x11d, small_table, small_table_skip)]   pub struct Lfsr8SmallTable {}
     #[lfsr(polynomial=0x11d, barret, barret_skip)]             pub struct Lfsr8Barret {}
@@ -675,6 +1711,7 @@ mod test {
     #[lfsr(polynomial=0x11d, small_table_barret, barret_skip)] pub struct Lfsr8SmallTableBarret {}
 
     #[lfsr(polynomial=0x1002d, naive, naive_skip)]               pub struct Lfsr16Naive {}
+    #[lfsr(polynomial=0x1002d, sparse, naive_skip)]              pub struct Lfsr16Sparse {}
     #[lfsr(polynomial=0x1002d, table, table_skip)]               pub struct Lfsr16Table {}
     #[lfsr(polynomial=0x1002d, small_table, small_table_skip)]   pub struct Lfsr16SmallTable {}
     #[lfsr(polynomial=0x1002d, barret, barret_skip)]             pub struct Lfsr16Barret {}
@@ -682,6 +1719,7 @@ mod test {
     #[lfsr(polynomial=0x1002d, small_table_barret, barret_skip)] pub struct Lfsr16SmallTableBarret {}
 
     #[lfsr(polynomial=0x1000000af, naive, naive_skip)]               pub struct Lfsr32Naive {}
+    #[lfsr(polynomial=0x1000000af, sparse, naive_skip)]              pub struct Lfsr32Sparse {}
     #[lfsr(polynomial=0x1000000af, table, table_skip)]               pub struct Lfsr32Table {}
     #[lfsr(polynomial=0x1000000af, small_table, small_table_skip)]   pub struct Lfsr32SmallTable {}
     #[lfsr(polynomial=0x1000000af, barret, barret_skip)]             pub struct Lfsr32Barret {}
@@ -689,6 +1727,7 @@ mod test {
     #[lfsr(polynomial=0x1000000af, small_table_barret, barret_skip)] pub struct Lfsr32SmallTableBarret {}
 
     #[lfsr(polynomial=0x1000000000000001b, naive, naive_skip)]               pub struct Lfsr64Naive {}
+    #[lfsr(polynomial=0x1000000000000001b, sparse, naive_skip)]              pub struct Lfsr64Sparse {}
     #[lfsr(polynomial=0x1000000000000001b, table, table_skip)]               pub struct Lfsr64Table {}
     #[lfsr(polynomial=0x1000000000000001b, small_table, small_table_skip)]   pub struct Lfsr64SmallTable {}
     #[lfsr(polynomial=0x1000000000000001b, barret, barret_skip)]             pub struct Lfsr64Barret {}
@@ -723,6 +1762,61 @@ mod test {
         assert_eq!(buf, &[0x000000001c6db6c7,0x0000000001514515,0x00000000001ab1ab,0x0000000000011011,0x0000000000001db7,0x0000000000000145,0x000000000000001b,0x0000000000000001]);
     }
 
+    #[test]
+    fn lfsr_sparse() {
+        let mut lfsr8_sparse = Lfsr8Sparse::new(1);
+        let buf = iter::repeat_with(|| lfsr8_sparse.next(8)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x01,0x1c,0x4b,0x81,0x92,0x6e,0x41,0x5b]);
+        let buf = iter::repeat_with(|| lfsr8_sparse.prev(8)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x5b,0x41,0x6e,0x92,0x81,0x4b,0x1c,0x01]);
+
+        let mut lfsr16_sparse = Lfsr16Sparse::new(1);
+        let buf = iter::repeat_with(|| lfsr16_sparse.next(16)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x0001,0x002d,0x0451,0xbdad,0x13d3,0xb877,0x94e7,0xfcb8]);
+        let buf = iter::repeat_with(|| lfsr16_sparse.prev(16)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0xfcb8,0x94e7,0xb877,0x13d3,0xbdad,0x0451,0x002d,0x0001]);
+
+        let mut lfsr32_sparse = Lfsr32Sparse::new(1);
+        let buf = iter::repeat_with(|| lfsr32_sparse.next(32)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x00000001,0x000000af,0x00004455,0x00295f23,0x1010111b,0xfafa511e,0x11579360,0x7d21bf13]);
+        let buf = iter::repeat_with(|| lfsr32_sparse.prev(32)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x7d21bf13,0x11579360,0xfafa511e,0x1010111b,0x00295f23,0x00004455,0x000000af,0x00000001]);
+
+        let mut lfsr64_sparse = Lfsr64Sparse::new(1);
+        let buf = iter::repeat_with(|| lfsr64_sparse.next(64)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x0000000000000001,0x000000000000001b,0x0000000000000145,0x0000000000001db7,0x0000000000011011,0x00000000001ab1ab,0x0000000001514515,0x000000001c6db6c7]);
+        let buf = iter::repeat_with(|| lfsr64_sparse.prev(64)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x000000001c6db6c7,0x0000000001514515,0x00000000001ab1ab,0x0000000000011011,0x0000000000001db7,0x0000000000000145,0x000000000000001b,0x0000000000000001]);
+    }
+
+    // x^width + 1 leaves no gap below x^width, so sparse mode must limit
+    // its steps to avoid overflowing shifts
+    #[lfsr(polynomial=0x101, naive, naive_skip)]                 pub struct Lfsr8BinomialNaive {}
+    #[lfsr(polynomial=0x101, sparse, naive_skip)]                pub struct Lfsr8BinomialSparse {}
+    #[lfsr(polynomial=0x10000000000000001, naive, naive_skip)]   pub struct Lfsr64BinomialNaive {}
+    #[lfsr(polynomial=0x10000000000000001, sparse, naive_skip)]  pub struct Lfsr64BinomialSparse {}
+
+    #[test]
+    fn lfsr_sparse_binomial() {
+        let mut naive = Lfsr8BinomialNaive::new(0x35);
+        let mut sparse = Lfsr8BinomialSparse::new(0x35);
+        for bits in [8, 7, 1, 8, 3] {
+            assert_eq!(sparse.next(bits), naive.next(bits));
+        }
+        for bits in [8, 7, 1, 8, 3] {
+            assert_eq!(sparse.prev(bits), naive.prev(bits));
+        }
+
+        let mut naive = Lfsr64BinomialNaive::new(0x123456789abcdef1);
+        let mut sparse = Lfsr64BinomialSparse::new(0x123456789abcdef1);
+        for bits in [64, 63, 1, 64, 17] {
+            assert_eq!(sparse.next(bits), naive.next(bits));
+        }
+        for bits in [64, 63, 1, 64, 17] {
+            assert_eq!(sparse.prev(bits), naive.prev(bits));
+        }
+    }
+
     #[test]
     fn lfsr_table() {
         let mut lfsr8_table = Lfsr8Table::new(1);
@@ -859,6 +1953,77 @@ mod test {
     }
 
     // test explicit skip modes
+    #[test]
+    fn lfsr_skip_u128() {
+        // skipping a full period should be a noop
+        let mut lfsr8 = Lfsr8::new(1);
+        lfsr8.skip_u128(255*1000 + 8*8);
+        let buf = iter::repeat_with(|| lfsr8.prev(8)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x5b,0x41,0x6e,0x92,0x81,0x4b,0x1c,0x01]);
+
+        let mut lfsr16 = Lfsr16::new(1);
+        lfsr16.skip_u128(65535*(1 << 40) + 16*8);
+        let buf = iter::repeat_with(|| lfsr16.prev(16)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0xfcb8,0x94e7,0xb877,0x13d3,0xbdad,0x0451,0x002d,0x0001]);
+
+        let mut lfsr32 = Lfsr32::new(1);
+        lfsr32.skip_u128(u128::from(u32::MAX)*(1 << 90) + 32*16);
+        lfsr32.skip_backwards_u128(u128::from(u32::MAX)*3 + 32*8);
+        let buf = iter::repeat_with(|| lfsr32.prev(32)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x7d21bf13,0x11579360,0xfafa511e,0x1010111b,0x00295f23,0x00004455,0x000000af,0x00000001]);
+
+        // large skips should be consistent with smaller skips
+        let mut a = Lfsr32::new(1);
+        a.skip_u128(1 << 40);
+        let mut b = Lfsr32::new(1);
+        for _ in 0..(1 << 16) {
+            b.skip(1 << 24);
+        }
+        assert_eq!(a.next(32), b.next(32));
+        a.skip_backwards_u128((1 << 40) + 32);
+        assert_eq!(a.next(32), Lfsr32::new(1).next(32));
+
+        let mut a = Lfsr64::new(1);
+        a.skip_u128(u128::MAX);
+        let mut b = Lfsr64::new(1);
+        b.skip((u128::MAX % u128::from(u64::MAX)) as u64);
+        assert_eq!(a.next(64), b.next(64));
+    }
+
+    #[test]
+    fn lfsr_split() {
+        // interleaving split LFSRs should give the original stream
+        for n in 1..10 {
+            let mut lfsr = Lfsr8::new(1);
+            let mut split = lfsr.split(n, 1).collect::<Vec<_>>();
+            assert_eq!(split.len(), n);
+            for _ in 0..100 {
+                for lfsr_ in split.iter_mut() {
+                    assert_eq!(lfsr_.next(), Some(lfsr.next(1)));
+                }
+            }
+        }
+
+        let mut lfsr = Lfsr32::new(0x12345678);
+        let mut split = lfsr.split(7, 13).collect::<Vec<_>>();
+        for _ in 0..100 {
+            for lfsr_ in split.iter_mut() {
+                assert_eq!(lfsr_.next(), Some(lfsr.next(13)));
+            }
+        }
+        let lfsr_ = split.remove(0).into_inner();
+        assert_eq!(lfsr_.state(), lfsr.state());
+
+        // splits larger than the period should still work
+        let mut lfsr = Lfsr8::new(1);
+        let mut split = lfsr.split(1000, 8).collect::<Vec<_>>();
+        for _ in 0..10 {
+            for lfsr_ in split.iter_mut() {
+                assert_eq!(lfsr_.next(), Some(lfsr.next(8)));
+            }
+        }
+    }
+
     #[test]
     fn lfsr_naive_skip() {
         let mut lfsr8_naive = Lfsr8Naive::new(1);
@@ -970,6 +2135,22 @@ mod test {
         let buf = iter::repeat_with(|| lfsr32.prev(23)).take(8).collect::<Vec<_>>();
         assert_eq!(buf, &[0x7afa51,0x202237,0x7c8c40,0x28014a,0x000445,0x0015e0,0x004000,0x000000]);
 
+        let mut lfsr8 = Lfsr8Sparse::new(1);
+        let buf = iter::repeat_with(|| lfsr8.next(4)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x0,0x1,0x1,0xc,0x4,0xb,0x8,0x1]);
+        let buf = iter::repeat_with(|| lfsr8.prev(4)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x1,0x8,0xb,0x4,0xc,0x1,0x1,0x0]);
+        let mut lfsr16 = Lfsr16Sparse::new(1);
+        let buf = iter::repeat_with(|| lfsr16.next(12)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x000,0x100,0x2d0,0x451,0xbda,0xd13,0xd3b,0x877]);
+        let buf = iter::repeat_with(|| lfsr16.prev(12)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x877,0xd3b,0xd13,0xbda,0x451,0x2d0,0x100,0x000]);
+        let mut lfsr32 = Lfsr32Sparse::new(1);
+        let buf = iter::repeat_with(|| lfsr32.next(23)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x000000,0x004000,0x0015e0,0x000445,0x28014a,0x7c8c40,0x202237,0x7afa51]);
+        let buf = iter::repeat_with(|| lfsr32.prev(23)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x7afa51,0x202237,0x7c8c40,0x28014a,0x000445,0x0015e0,0x004000,0x000000]);
+
         let mut lfsr8 = Lfsr8Table::new(1);
         let buf = iter::repeat_with(|| lfsr8.next(4)).take(8).collect::<Vec<_>>();
         assert_eq!(buf, &[0x0,0x1,0x1,0xc,0x4,0xb,0x8,0x1]);
@@ -1053,21 +2234,24 @@ mod test {
 
     // odd LFSR sizes
     #[lfsr(polynomial=0x13, naive, naive_skip)]               pub struct Lfsr4Naive {}
-    #[lfsr(polynomial=0x13, table, table_skip)]               pub struct Lfsr4Table {}
+    #[lfsr(polynomial=0x13, sparse, naive_skip)]              pub struct Lfsr4Sparse {}
+    #[lfsr(polynomial=0x13, table, table_skip, maximal)]      pub struct Lfsr4Table {}
     #[lfsr(polynomial=0x13, small_table, small_table_skip)]   pub struct Lfsr4SmallTable {}
     #[lfsr(polynomial=0x13, barret, barret_skip)]             pub struct Lfsr4Barret {}
     #[lfsr(polynomial=0x13, table_barret, barret_skip)]       pub struct Lfsr4TableBarret {}
     #[lfsr(polynomial=0x13, small_table_barret, barret_skip)] pub struct Lfsr4SmallTableBarret {}
 
     #[lfsr(polynomial=0x1053, naive, naive_skip)]               pub struct Lfsr12Naive {}
-    #[lfsr(polynomial=0x1053, table, table_skip)]               pub struct Lfsr12Table {}
+    #[lfsr(polynomial=0x1053, sparse, naive_skip)]              pub struct Lfsr12Sparse {}
+    #[lfsr(polynomial=0x1053, table, table_skip, maximal)]      pub struct Lfsr12Table {}
     #[lfsr(polynomial=0x1053, small_table, small_table_skip)]   pub struct Lfsr12SmallTable {}
     #[lfsr(polynomial=0x1053, barret, barret_skip)]             pub struct Lfsr12Barret {}
     #[lfsr(polynomial=0x1053, table_barret, barret_skip)]       pub struct Lfsr12TableBarret {}
     #[lfsr(polynomial=0x1053, small_table_barret, barret_skip)] pub struct Lfsr12SmallTableBarret {}
 
     #[lfsr(polynomial=0x800021, naive, naive_skip)]               pub struct Lfsr23Naive {}
-    #[lfsr(polynomial=0x800021, table, table_skip)]               pub struct Lfsr23Table {}
+    #[lfsr(polynomial=0x800021, sparse, naive_skip)]              pub struct Lfsr23Sparse {}
+    #[lfsr(polynomial=0x800021, table, table_skip, maximal)]      pub struct Lfsr23Table {}
     #[lfsr(polynomial=0x800021, small_table, small_table_skip)]   pub struct Lfsr23SmallTable {}
     #[lfsr(polynomial=0x800021, barret, barret_skip)]             pub struct Lfsr23Barret {}
     #[lfsr(polynomial=0x800021, table_barret, barret_skip)]       pub struct Lfsr23TableBarret {}
@@ -1091,6 +2275,22 @@ mod test {
         let buf = iter::repeat_with(|| lfsr23.prev(23)).take(8).collect::<Vec<_>>();
         assert_eq!(buf, &[0x128421,0x101485,0x1000a1,0x100005,0x008421,0x000401,0x000021,0x000001]);
 
+        let mut lfsr4 = Lfsr4Sparse::new(1);
+        let buf = iter::repeat_with(|| lfsr4.next(4)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x1,0x3,0x5,0xe,0x2,0x6,0xb,0xc]);
+        let buf = iter::repeat_with(|| lfsr4.prev(4)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0xc,0xb,0x6,0x2,0xe,0x5,0x3,0x1]);
+        let mut lfsr12 = Lfsr12Sparse::new(1);
+        let buf = iter::repeat_with(|| lfsr12.next(12)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x001,0x052,0x152,0x25d,0x462,0x20c,0x5c6,0x3a7]);
+        let buf = iter::repeat_with(|| lfsr12.prev(12)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x3a7,0x5c6,0x20c,0x462,0x25d,0x152,0x052,0x001]);
+        let mut lfsr23 = Lfsr23Sparse::new(1);
+        let buf = iter::repeat_with(|| lfsr23.next(23)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x000001,0x000021,0x000401,0x008421,0x100005,0x1000a1,0x101485,0x128421]);
+        let buf = iter::repeat_with(|| lfsr23.prev(23)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x128421,0x101485,0x1000a1,0x100005,0x008421,0x000401,0x000021,0x000001]);
+
         let mut lfsr4 = Lfsr4Table::new(1);
         let buf = iter::repeat_with(|| lfsr4.next(4)).take(8).collect::<Vec<_>>();
         assert_eq!(buf, &[0x1,0x3,0x5,0xe,0x2,0x6,0xb,0xc]);
@@ -1229,6 +2429,7 @@ mod test {
 
     // bit-reflected LFSRs
     #[lfsr(polynomial=0x1000000000000001b, naive, naive_skip, reflected=true)]               pub struct Lfsr64NaiveReflected {}
+    #[lfsr(polynomial=0x1000000000000001b, sparse, naive_skip, reflected=true)]              pub struct Lfsr64SparseReflected {}
     #[lfsr(polynomial=0x1000000000000001b, table, table_skip, reflected=true)]               pub struct Lfsr64TableReflected {}
     #[lfsr(polynomial=0x1000000000000001b, small_table, small_table_skip, reflected=true)]   pub struct Lfsr64SmallTableReflected {}
     #[lfsr(polynomial=0x1000000000000001b, barret, barret_skip, reflected=true)]             pub struct Lfsr64BarretReflected {}
@@ -1243,6 +2444,12 @@ mod test {
         let buf = iter::repeat_with(|| lfsr64.prev(64)).take(8).collect::<Vec<_>>();
         assert_eq!(buf, &[0xe36db63800000000,0xa8a28a8000000000,0xd58d580000000000,0x8808800000000000,0xedb8000000000000,0xa280000000000000,0xd800000000000000,0x8000000000000000]);
 
+        let mut lfsr64 = Lfsr64SparseReflected::new(0x8000000000000000);
+        let buf = iter::repeat_with(|| lfsr64.next(64)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x8000000000000000,0xd800000000000000,0xa280000000000000,0xedb8000000000000,0x8808800000000000,0xd58d580000000000,0xa8a28a8000000000,0xe36db63800000000]);
+        let buf = iter::repeat_with(|| lfsr64.prev(64)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0xe36db63800000000,0xa8a28a8000000000,0xd58d580000000000,0x8808800000000000,0xedb8000000000000,0xa280000000000000,0xd800000000000000,0x8000000000000000]);
+
         let mut lfsr64 = Lfsr64TableReflected::new(0x8000000000000000);
         let buf = iter::repeat_with(|| lfsr64.next(64)).take(8).collect::<Vec<_>>();
         assert_eq!(buf, &[0x8000000000000000,0xd800000000000000,0xa280000000000000,0xedb8000000000000,0x8808800000000000,0xd58d580000000000,0xa8a28a8000000000,0xe36db63800000000]);
@@ -1382,6 +2589,803 @@ mod test {
         assert_eq!(&next_bytes, &rng_bytes);
     }
 
+    #[test]
+    fn lfsr_block_rng_consistency() {
+        use rand_core::RngCore;
+        use rand_core::block::BlockRng;
+
+        // BlockRng should generate the same stream as fill_bytes, across
+        // multiple blocks
+        let mut lfsr = Lfsr8::new(1);
+        let mut fill_bytes = vec![0u8; 1000];
+        lfsr.fill_bytes(&mut fill_bytes);
+        let mut rng = BlockRng::new(Lfsr8::new(1));
+        let mut block_bytes = vec![0u8; 1000];
+        rng.fill_bytes(&mut block_bytes);
+        assert_eq!(&fill_bytes, &block_bytes);
+
+        let mut lfsr = Lfsr32::new(1);
+        let next_u32s = iter::repeat_with(|| lfsr.next_u32()).take(100).collect::<Vec<_>>();
+        let mut rng = BlockRng::new(Lfsr32::new(1));
+        let block_u32s = iter::repeat_with(|| rng.next_u32()).take(100).collect::<Vec<_>>();
+        assert_eq!(&next_u32s, &block_u32s);
+
+        let mut lfsr = Lfsr64::new(1);
+        let next_u64s = iter::repeat_with(|| lfsr.next_u64()).take(100).collect::<Vec<_>>();
+        let mut rng = BlockRng::new(Lfsr64::new(1));
+        let block_u64s = iter::repeat_with(|| rng.next_u64()).take(100).collect::<Vec<_>>();
+        assert_eq!(&next_u64s, &block_u64s);
+
+        let mut lfsr = Lfsr23Table::new(1);
+        let mut fill_bytes = vec![0u8; 1000];
+        lfsr.fill_bytes(&mut fill_bytes);
+        let mut rng = BlockRng::new(Lfsr23Table::new(1));
+        let mut block_bytes = vec![0u8; 1000];
+        rng.fill_bytes(&mut block_bytes);
+        assert_eq!(&fill_bytes, &block_bytes);
+
+        let mut lfsr = Lfsr4Table::new(1);
+        let mut fill_bytes = vec![0u8; 1000];
+        lfsr.fill_bytes(&mut fill_bytes);
+        let mut rng = BlockRng::new(Lfsr4Table::new(1));
+        let mut block_bytes = vec![0u8; 1000];
+        rng.fill_bytes(&mut block_bytes);
+        assert_eq!(&fill_bytes, &block_bytes);
+
+        let mut lfsr = Lfsr16LsbFirst::new(1);
+        let mut fill_bytes = vec![0u8; 1000];
+        lfsr.fill_bytes(&mut fill_bytes);
+        let mut rng = BlockRng::new(Lfsr16LsbFirst::new(1));
+        let mut block_bytes = vec![0u8; 1000];
+        rng.fill_bytes(&mut block_bytes);
+        assert_eq!(&fill_bytes, &block_bytes);
+
+        // reflected order
+        let mut lfsr = Lfsr64TableReflected::new(1);
+        let next_u64s = iter::repeat_with(|| lfsr.next_u64()).take(100).collect::<Vec<_>>();
+        let mut rng = BlockRng::new(Lfsr64TableReflected::new(1));
+        let block_u64s = iter::repeat_with(|| rng.next_u64()).take(100).collect::<Vec<_>>();
+        assert_eq!(&next_u64s, &block_u64s);
+    }
+
+    #[test]
+    fn lfsr_state() {
+        use rand_core::SeedableRng;
+
+        // resuming from state should continue the same stream
+        let mut lfsr = Lfsr64::new(0x123456789abcdef0);
+        lfsr.next(23);
+        let mut resumed = Lfsr64::new(lfsr.state());
+        let next = iter::repeat_with(|| lfsr.next(64)).take(100).collect::<Vec<_>>();
+        let resumed_next = iter::repeat_with(|| resumed.next(64)).take(100).collect::<Vec<_>>();
+        assert_eq!(&next, &resumed_next);
+
+        let mut lfsr = Lfsr23Table::new(1);
+        lfsr.next(7);
+        let mut resumed = Lfsr23Table::from_bytes(lfsr.to_bytes());
+        let next = iter::repeat_with(|| lfsr.next(8)).take(100).collect::<Vec<_>>();
+        let resumed_next = iter::repeat_with(|| resumed.next(8)).take(100).collect::<Vec<_>>();
+        assert_eq!(&next, &resumed_next);
+
+        // reflected order
+        let mut lfsr = Lfsr64TableReflected::new(0x123456789abcdef0);
+        assert_eq!(lfsr.state(), 0x123456789abcdef0);
+        lfsr.next(23);
+        let mut resumed = Lfsr64TableReflected::new(1);
+        resumed.set_state(lfsr.state(), lfsr.seed(), lfsr.position());
+        let next = iter::repeat_with(|| lfsr.next(64)).take(100).collect::<Vec<_>>();
+        let resumed_next = iter::repeat_with(|| resumed.next(64)).take(100).collect::<Vec<_>>();
+        assert_eq!(&next, &resumed_next);
+
+        // bytes should contain state, seed, and position
+        let mut lfsr = Lfsr32::new(0x12345678);
+        assert_eq!(lfsr.to_bytes(), [0x78,0x56,0x34,0x12, 0x78,0x56,0x34,0x12, 0,0,0,0]);
+        assert_eq!(Lfsr32::from_seed(lfsr.state().to_le_bytes()).state(), 0x12345678);
+        lfsr.next(17);
+        assert_eq!(&lfsr.to_bytes()[4..], [0x78,0x56,0x34,0x12, 17,0,0,0]);
+
+        // seeking should still work after a round-trip
+        let mut lfsr = Lfsr64TableReflected::new(0x123456789abcdef0);
+        let first = iter::repeat_with(|| lfsr.next(64)).take(10).collect::<Vec<_>>();
+        let mut resumed = Lfsr64TableReflected::from_bytes(lfsr.to_bytes());
+        assert_eq!(resumed.seed(), 0x123456789abcdef0);
+        assert_eq!(resumed.position(), 640);
+        assert_eq!(resumed.next(64), lfsr.next(64));
+        resumed.seek(0);
+        let replayed = iter::repeat_with(|| resumed.next(64)).take(10).collect::<Vec<_>>();
+        assert_eq!(&first, &replayed);
+
+        let mut lfsr = Lfsr23Table::new(1);
+        lfsr.skip_backwards(100);
+        let mut resumed = Lfsr23Table::new(0x1234);
+        resumed.set_state(lfsr.state(), lfsr.seed(), lfsr.position());
+        resumed.seek(0);
+        assert_eq!(resumed.state(), 1);
+    }
+
+    #[test]
+    fn lfsr_from_output() {
+        // recover from output at a number of offsets
+        for off in 0..100 {
+            let mut lfsr = Lfsr64::new(0x123456789abcdef0);
+            lfsr.skip(off);
+            let mut recovered = Lfsr64::from_output(lfsr.next(64)).unwrap();
+            let next = iter::repeat_with(|| lfsr.next(64)).take(10).collect::<Vec<_>>();
+            let recovered_next = iter::repeat_with(|| recovered.next(64)).take(10).collect::<Vec<_>>();
+            assert_eq!(&next, &recovered_next);
+
+            let mut lfsr = Lfsr23Table::new(1);
+            lfsr.skip(off as u32);
+            let mut recovered = Lfsr23Table::new(1);
+            assert!(recovered.synchronize(lfsr.next(23)));
+            let next = iter::repeat_with(|| lfsr.next(8)).take(10).collect::<Vec<_>>();
+            let recovered_next = iter::repeat_with(|| recovered.next(8)).take(10).collect::<Vec<_>>();
+            assert_eq!(&next, &recovered_next);
+
+            // reflected order
+            let mut lfsr = Lfsr64TableReflected::new(0x123456789abcdef0);
+            lfsr.skip(off);
+            let mut recovered = Lfsr64TableReflected::from_output(lfsr.next(64)).unwrap();
+            let next = iter::repeat_with(|| lfsr.next(64)).take(10).collect::<Vec<_>>();
+            let recovered_next = iter::repeat_with(|| recovered.next(64)).take(10).collect::<Vec<_>>();
+            assert_eq!(&next, &recovered_next);
+        }
+
+        // invalid outputs
+        assert!(Lfsr64::from_output(0).is_none());
+        assert!(Lfsr23Table::from_output(0x800000).is_none());
+        let mut lfsr = Lfsr23Table::new(1);
+        assert!(!lfsr.synchronize(0));
+        assert_eq!(lfsr.state(), 1);
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn lfsr_serde() {
+        let mut lfsr = Lfsr64::new(0x123456789abcdef0);
+        lfsr.next(23);
+        let json = serde_json::to_string(&lfsr).unwrap();
+        let mut resumed: Lfsr64 = serde_json::from_str(&json).unwrap();
+        let next = iter::repeat_with(|| lfsr.next(64)).take(100).collect::<Vec<_>>();
+        let resumed_next = iter::repeat_with(|| resumed.next(64)).take(100).collect::<Vec<_>>();
+        assert_eq!(&next, &resumed_next);
+
+        let mut lfsr = Lfsr23Table::new(1);
+        lfsr.next(7);
+        let json = serde_json::to_string(&lfsr).unwrap();
+        let resumed: Lfsr23Table = serde_json::from_str(&json).unwrap();
+        assert_eq!(resumed.state(), lfsr.state());
+
+        // seed and position should survive a round-trip
+        let mut lfsr = Lfsr64TableReflected::new(0x123456789abcdef0);
+        let first = iter::repeat_with(|| lfsr.next(64)).take(10).collect::<Vec<_>>();
+        let json = serde_json::to_string(&lfsr).unwrap();
+        let mut resumed: Lfsr64TableReflected = serde_json::from_str(&json).unwrap();
+        assert_eq!(resumed.seed(), lfsr.seed());
+        assert_eq!(resumed.position(), lfsr.position());
+        resumed.seek(0);
+        let replayed = iter::repeat_with(|| resumed.next(64)).take(10).collect::<Vec<_>>();
+        assert_eq!(&first, &replayed);
+
+        // invalid states should be rejected
+        assert!(serde_json::from_str::<Lfsr64>("0").is_err());
+        assert!(serde_json::from_str::<Lfsr64>("[0,1,0]").is_err());
+        assert!(serde_json::from_str::<Lfsr64>("[1,0,0]").is_err());
+        assert!(serde_json::from_str::<Lfsr23Table>("[16777215,1,0]").is_err());
+        assert!(serde_json::from_str::<Lfsr23Table>("[1,1,8388607]").is_err());
+        // state doesn't match seed+position
+        assert!(serde_json::from_str::<Lfsr23Table>("[1,1,1]").is_err());
+        assert!(serde_json::from_str::<Lfsr23Table>("[1,1,0]").is_ok());
+    }
+
+    #[lfsr(polynomial=0x409, maximal)] pub struct LfsrG1 {}
+    #[lfsr(polynomial=0x74d, maximal)] pub struct LfsrG2 {}
+
+    #[test]
+    fn gold_code() {
+        fn chips(offset: u16) -> Vec<i32> {
+            let mut code = GoldCode::with_offset(LfsrG1::new(1), LfsrG2::new(1), offset);
+            iter::repeat_with(|| if code.next(1) == 1 { -1 } else { 1 })
+                .take(1023)
+                .collect()
+        }
+
+        fn correlate(a: &[i32], b: &[i32], shift: usize) -> i32 {
+            (0..a.len()).map(|i| a[i]*b[(i+shift) % b.len()]).sum()
+        }
+
+        // cross-correlation of a Gold code family should be three-valued,
+        // with t(10) = 2^((10+2)/2)+1 = 65
+        let codes = [0, 1, 5, 100, 1000].iter().map(|o| chips(*o)).collect::<Vec<_>>();
+        for i in 0..codes.len() {
+            for j in i+1..codes.len() {
+                for shift in 0..1023 {
+                    let x = correlate(&codes[i], &codes[j], shift);
+                    assert!(x == -1 || x == -65 || x == 63, "{}", x);
+                }
+            }
+        }
+
+        // stepping and seeking should be consistent
+        let mut code = GoldCode::new(LfsrG1::new(1), LfsrG2::new(1));
+        let next = iter::repeat_with(|| code.next(8)).take(100).collect::<Vec<_>>();
+        let prev = iter::repeat_with(|| code.prev(8)).take(100).collect::<Vec<_>>();
+        assert_eq!(next, prev.into_iter().rev().collect::<Vec<_>>());
+        code.skip(23);
+        let x = code.next(10);
+        code.skip_backwards(33);
+        code.skip(23);
+        assert_eq!(code.next(10), x);
+        let (a, b) = code.into_inner();
+        assert_eq!(a.state(), { let mut g1 = LfsrG1::new(1); g1.skip(33); g1.state() });
+        assert_eq!(b.state(), { let mut g2 = LfsrG2::new(1); g2.skip(33); g2.state() });
+    }
+
+    #[lfsr(polynomial=0x1002d, fibonacci)]                 pub struct Lfsr16Fibonacci {}
+    #[lfsr(polynomial=0x1002d, reflected=true, fibonacci, maximal)] pub struct Lfsr16FibonacciReflected {}
+    #[lfsr(polynomial=0x1000000000000001b, fibonacci)]     pub struct Lfsr64Fibonacci {}
+
+    #[test]
+    fn lfsr_fibonacci() {
+        // compare against a bit-serial fibonacci LFSR, taps 16,14,13,11
+        let mut reg: u16 = 0xace1;
+        let mut lfsr = Lfsr16FibonacciReflected::new(0xace1);
+        for _ in 0..1000 {
+            assert_eq!(lfsr.state(), reg);
+            assert_eq!(lfsr.next(1), reg & 1);
+            let bit = (reg ^ (reg >> 2) ^ (reg >> 3) ^ (reg >> 5)) & 1;
+            reg = (reg >> 1) | (bit << 15);
+        }
+
+        let mut reg: u16 = 0xace1;
+        let mut lfsr = Lfsr16Fibonacci::new(0xace1);
+        for _ in 0..1000 {
+            assert_eq!(lfsr.state(), reg);
+            assert_eq!(lfsr.next(1), reg >> 15);
+            let bit = ((reg >> 15) ^ (reg >> 13) ^ (reg >> 12) ^ (reg >> 10)) & 1;
+            reg = (reg << 1) | bit;
+        }
+
+        // the register of a fibonacci LFSR is just its next width bits
+        // of output, otherwise it should match the galois sequence
+        let mut lfsr = Lfsr64Fibonacci::new(0x123456789abcdef0);
+        assert_eq!(lfsr.clone().next(64), 0x123456789abcdef0);
+        let mut galois = Lfsr64::from_output(0x123456789abcdef0).unwrap();
+        lfsr.skip(64);
+        for _ in 0..100 {
+            assert_eq!(lfsr.next(64), galois.next(64));
+        }
+        let state = lfsr.state();
+        assert_eq!(Lfsr64Fibonacci::new(state).state(), state);
+        let x = lfsr.next(64);
+        lfsr.skip_backwards(64);
+        assert_eq!(lfsr.state(), x);
+    }
+
+    #[test]
+    fn self_sync_scrambler() {
+        type Scrambler7 = SelfSyncScrambler<0xc1>;
+        type Scrambler58 = SelfSyncScrambler<0x400008000000001, true>;
+        assert_eq!(Scrambler7::WIDTH, 7);
+        assert_eq!(Scrambler58::WIDTH, 58);
+
+        // descrambling should undo scrambling
+        let data = (0..1000).map(|i| (i*i) as u8).collect::<Vec<_>>();
+        let mut buf = data.clone();
+        Scrambler58::new(0x123).scramble(&mut buf);
+        assert_ne!(buf, data);
+        Scrambler58::new(0x123).descramble(&mut buf);
+        assert_eq!(buf, data);
+
+        // scrambling in chunks should be equivalent
+        let mut buf1 = data.clone();
+        let mut buf2 = data.clone();
+        Scrambler7::new(0x55).scramble(&mut buf1);
+        let mut scrambler = Scrambler7::new(0x55);
+        for chunk in buf2.chunks_mut(7) {
+            scrambler.scramble(chunk);
+        }
+        assert_eq!(buf1, buf2);
+
+        // descrambler should synchronize after width-bits
+        let mut buf = data.clone();
+        Scrambler7::default().scramble(&mut buf);
+        Scrambler7::new(0x7f).descramble(&mut buf);
+        assert_eq!(buf[0] & 0x01, data[0] & 0x01);
+        assert_eq!(&buf[1..], &data[1..]);
+
+        // scrambling zeros with a primitive polynomial should give a
+        // maximal-length sequence
+        let mut scrambler = Scrambler7::new(1);
+        let mut zeros = [0u8; 127];
+        scrambler.scramble(&mut zeros);
+        assert_eq!(scrambler.state(), 1);
+        let unique = BTreeSet::from_iter(
+            (0..127*8).map(|i| {
+                let mut s = Scrambler7::new(1);
+                s.scramble(&mut [0u8; 127][..i/8]);
+                s.state()
+            })
+        );
+        assert_eq!(unique.len(), 127);
+    }
+
+    #[test]
+    fn additive_scrambler() {
+        // DVB-S energy dispersal sequence
+        let mut buf = [0u8; 8];
+        DvbS2Scrambler::new().scramble(&mut buf);
+        assert_eq!(buf, [0x03, 0xf6, 0x08, 0x34, 0x30, 0xb8, 0xa3, 0x93]);
+
+        // PCIe's scrambler, compared against PCIe's reference Galois LFSR
+        let mut reg: u16 = 0xffff;
+        let mut scrambler = PcieScrambler::new();
+        for _ in 0..1000 {
+            let mut expected = 0;
+            for i in 0..8 {
+                let bit = reg >> 15;
+                expected |= bit << i;
+                reg = (reg << 1) ^ if bit != 0 { 0x0039 } else { 0 };
+            }
+            assert_eq!(scrambler.next(8), u64::from(expected));
+        }
+        let mut buf = [0u8; 8];
+        scrambler.reset();
+        scrambler.scramble(&mut buf);
+        assert_eq!(buf, [0xff, 0x17, 0xc0, 0x14, 0xb2, 0xe7, 0x02, 0x82]);
+
+        // 100BASE-TX, X[n] = X[n-11] ^ X[n-9]
+        let mut bits = vec![1u64; 11];
+        let mut scrambler = Ieee8023Scrambler::new();
+        for n in 11..1000 {
+            bits.push(bits[n-11] ^ bits[n-9]);
+            assert_eq!(scrambler.next(1), bits[n]);
+        }
+
+        // BLE whitening, compared against BLE's reference Galois LFSR
+        for channel in 0..40u8 {
+            let mut reg = channel.reverse_bits() | 0x02;
+            let mut whitening = BleWhitening::with_channel(channel);
+            for _ in 0..1000 {
+                let bit = reg >> 7;
+                if bit != 0 {
+                    reg ^= 0x11;
+                }
+                reg <<= 1;
+                assert_eq!(whitening.next(1), u64::from(bit));
+            }
+        }
+        assert_eq!(BleWhitening::with_channel(0), BleWhitening::new());
+        let mut buf = [0u8; 4];
+        BleWhitening::with_channel(37).scramble(&mut buf);
+        assert_eq!(buf, [0x8d, 0xd2, 0x57, 0xa1]);
+
+        // recover state from output
+        let mut scrambler = DvbS2Scrambler::new();
+        scrambler.next(50);
+        scrambler.next(50);
+        let state = scrambler.state();
+        let output = scrambler.clone().next(15);
+        assert_eq!(DvbS2Scrambler::from_output(output).state(), state);
+        let mut scrambler = PcieScrambler::new();
+        scrambler.next(50);
+        scrambler.next(50);
+        let state = scrambler.state();
+        let output = scrambler.clone().next(16);
+        assert_eq!(PcieScrambler::from_output(output).state(), state);
+
+        // scrambling twice should give the original data, and reset
+        // should return to the original seed
+        let data = (0..1000).map(|i| (i*i) as u8).collect::<Vec<_>>();
+        let mut buf = data.clone();
+        let mut scrambler = BleWhitening::with_channel(12);
+        scrambler.scramble(&mut buf);
+        assert_ne!(buf, data);
+        scrambler.reset();
+        scrambler.descramble(&mut buf);
+        assert_eq!(buf, data);
+    }
+
+    #[test]
+    fn prbs() {
+        // compare against the recurrences in ITU-T O.150
+        fn check<const P: u64, const S: u64>(a: usize, b: usize) {
+            let mut bits = vec![1u64; a];
+            let mut prbs = AdditiveScrambler::<P, S>::new();
+            for n in a..a+1000 {
+                bits.push(bits[n-a] ^ bits[n-b]);
+                assert_eq!(prbs.next(1), bits[n]);
+            }
+        }
+        check::<0xc1, 0x7f>(7, 6);
+        check::<0x221, 0x1ff>(9, 5);
+        check::<0xc001, 0x7fff>(15, 14);
+        check::<0x840001, 0x7fffff>(23, 18);
+        check::<0x90000001, 0x7fffffff>(31, 28);
+
+        // these should be maximal-length
+        let mut prbs = Prbs7::new();
+        let unique = BTreeSet::from_iter(iter::repeat_with(|| { prbs.next(1); prbs.state() }).take(127));
+        assert_eq!(unique.len(), 127);
+        assert_eq!(prbs.state(), Prbs7::new().state());
+        let mut prbs = Prbs15::new();
+        let unique = BTreeSet::from_iter(iter::repeat_with(|| { prbs.next(1); prbs.state() }).take(32767));
+        assert_eq!(unique.len(), 32767);
+        assert_eq!(prbs.state(), Prbs15::new().state());
+    }
+
+    #[test]
+    fn prbs_checker() {
+        let mut prbs = Prbs23::new();
+        prbs.next(42);
+        let mut data = vec![0u8; 1000];
+        prbs.scramble(&mut data);
+
+        // no errors
+        let mut checker = Prbs23Checker::new();
+        assert!(!checker.is_locked());
+        checker.check(&data);
+        assert!(checker.is_locked());
+        assert_eq!(checker.errors(), 0);
+        assert!(checker.bits() >= 8*1000 - 23 - 64);
+
+        // each bit error should be counted exactly once
+        let mut corrupted = data.clone();
+        for i in (100..1000).step_by(37) {
+            corrupted[i] ^= 1 << (i % 8);
+        }
+        corrupted[500] ^= 0xff;
+        let mut checker = Prbs23Checker::new();
+        checker.check(&corrupted);
+        assert_eq!(checker.errors(), 25 + 8);
+
+        // checking in chunks should be equivalent
+        let mut chunked = Prbs23Checker::new();
+        for chunk in corrupted.chunks(3) {
+            chunked.check(chunk);
+        }
+        assert_eq!(chunked, checker);
+
+        // zeros should never lock
+        let mut checker = Prbs7Checker::new();
+        checker.check(&[0; 100]);
+        assert!(!checker.is_locked());
+
+        // the wrong pattern should never lock
+        let mut data = vec![0u8; 100];
+        Prbs9::new().scramble(&mut data);
+        let mut checker = Prbs7Checker::new();
+        checker.check(&data);
+        assert!(!checker.is_locked());
+        checker.reset();
+        assert_eq!(checker, Prbs7Checker::new());
+    }
+
+    #[gf(polynomial=0x13, generator=0x2)] type gf16;
+
+    #[test]
+    fn word_lfsr() {
+        // x^2 + x + 9 is primitive over gf16, so this should have a period
+        // of 16^2-1 = 255
+        let mut lfsr = WordLfsr::new([gf16::new(0x9), gf16::new(0x1)], [gf16::new(0x1), gf16::new(0x0)]);
+        let unique = BTreeSet::from_iter(iter::repeat_with(|| { lfsr.next(); lfsr.state().map(|x| x.get()) }).take(255));
+        assert_eq!(unique.len(), 255);
+        assert_eq!(lfsr.state(), [gf16::new(0x1), gf16::new(0x0)]);
+
+        // same with x^2 + x + 0x22 over gf256, with a period of 65535
+        let mut lfsr = WordLfsr::new([gf256(0x22), gf256(0x01)], [gf256(0x01), gf256(0x00)]);
+        let unique = BTreeSet::from_iter(iter::repeat_with(|| { lfsr.next(); lfsr.state().map(|x| x.get()) }).take(65535));
+        assert_eq!(unique.len(), 65535);
+        assert_eq!(lfsr.state(), [gf256(0x01), gf256(0x00)]);
+
+        // compare against the recurrence directly
+        let taps = [gf2p16(0x1234), gf2p16(0x5678), gf2p16(0x9abc)];
+        let mut lfsr = WordLfsr::new(taps, [gf2p16(1), gf2p16(2), gf2p16(3)]);
+        let mut s = vec![gf2p16(1), gf2p16(2), gf2p16(3)];
+        for t in 0..1000 {
+            s.push(taps[0]*s[t] + taps[1]*s[t+1] + taps[2]*s[t+2]);
+            assert_eq!(lfsr.next(), Some(s[t]));
+        }
+
+        // prev should undo next
+        let mut buf = vec![gf2p16(0); 100];
+        lfsr.fill(&mut buf);
+        let prev = iter::repeat_with(|| lfsr.prev()).take(100).collect::<Vec<_>>();
+        assert_eq!(buf, prev.into_iter().rev().collect::<Vec<_>>());
+        assert_eq!(lfsr.next(), Some(s[1000]));
+    }
+
+    #[lfsr(polynomial=0x11b, maximal)]                pub struct Lfsr8NotPrimitive {}
+    #[lfsr(polynomial=0x105, maximal)]                pub struct Lfsr8Reducible {}
+    #[lfsr(polynomial=0x1000000000000001a, maximal)]  pub struct Lfsr64Reducible {}
+    #[lfsr(polynomial=0x3, maximal)]                  pub struct Lfsr1 {}
+    #[lfsr(polynomial=0x12b, maximal)]                pub struct Lfsr8Primitive {}
+    #[lfsr(polynomial=0x139, maximal)]                pub struct Lfsr8NotPrimitive2 {}
+
+    #[test]
+    fn lfsr_verify_maximal() {
+        assert_eq!(Lfsr8::PERIOD, 255);
+        assert_eq!(Lfsr16::PERIOD, 65535);
+        assert_eq!(Lfsr64::PERIOD, u64::MAX);
+
+        assert!(Lfsr1::verify_maximal());
+        assert!(Lfsr4Table::verify_maximal());
+        assert!(Lfsr8::verify_maximal());
+        assert!(Lfsr12Table::verify_maximal());
+        assert!(Lfsr16::verify_maximal());
+        assert!(Lfsr23Table::verify_maximal());
+        assert!(Lfsr32::verify_maximal());
+        assert!(Lfsr64::verify_maximal());
+        assert!(Lfsr16FibonacciReflected::verify_maximal());
+        assert!(LfsrG1::verify_maximal());
+        assert!(LfsrG2::verify_maximal());
+        assert!(Lfsr8Primitive::verify_maximal());
+
+        // irreducible but not primitive, x has order 51 and 17
+        assert!(!Lfsr8NotPrimitive::verify_maximal());
+        assert!(!Lfsr8NotPrimitive2::verify_maximal());
+        let mut lfsr = Lfsr8NotPrimitive::new(1);
+        lfsr.skip(51);
+        assert_eq!(lfsr.state(), 1);
+
+        // reducible
+        assert!(!Lfsr8Reducible::verify_maximal());
+        assert!(!Lfsr64Reducible::verify_maximal());
+    }
+
+    #[lfsr(polynomial=0x1002d, lsb_first)]                  pub struct Lfsr16LsbFirst {}
+    #[lfsr(polynomial=0x1002d, reflected=true, msb_first)]  pub struct Lfsr16ReflectedMsbFirst {}
+    #[lfsr(polynomial=0x1002d, reflected=true)]             pub struct Lfsr16Reflected {}
+
+    #[test]
+    fn lfsr_bit_order() {
+        use rand_core::RngCore;
+
+        // lsb-first should be the same stream in reverse bit-order
+        let mut a = Lfsr16::new(0x1234);
+        let mut b = Lfsr16LsbFirst::new(0x1234);
+        for n in [1, 3, 8, 16, 5, 7] {
+            assert_eq!(b.next(n), a.next(n).reverse_bits() >> (16-n));
+            assert_eq!(b.state(), a.state());
+        }
+        for n in [1, 3, 8, 16, 5, 7] {
+            assert_eq!(b.prev(n), a.prev(n).reverse_bits() >> (16-n));
+        }
+
+        // reflected + msb-first, the state is reflected, but not the output
+        let mut a = Lfsr16Reflected::new(0x1234);
+        let mut b = Lfsr16ReflectedMsbFirst::new(0x1234);
+        for n in [1, 3, 8, 16, 5, 7] {
+            assert_eq!(b.next(n), a.next(n).reverse_bits() >> (16-n));
+            assert_eq!(b.state(), a.state());
+        }
+
+        // rng bytes should follow the output bit-order
+        let mut a = Lfsr16::new(0x1234);
+        let mut b = Lfsr16LsbFirst::new(0x1234);
+        let mut a_bytes = [0u8; 33];
+        let mut b_bytes = [0u8; 33];
+        a.fill_bytes(&mut a_bytes);
+        b.fill_bytes(&mut b_bytes);
+        assert_eq!(a_bytes.map(|x| x.reverse_bits()), b_bytes);
+
+        // from_output should also follow the output bit-order
+        let mut a = Lfsr16LsbFirst::new(0x1234);
+        a.skip(7);
+        let output = a.next(16);
+        let mut b = Lfsr16LsbFirst::from_output(output).unwrap();
+        assert_eq!(b.next(16), a.next(16));
+    }
+
+    #[test]
+    fn lfsr_naive_const() {
+        const LFSR16: Lfsr16 = Lfsr16::new(1).naive_skip(48);
+        assert_eq!(LFSR16.clone().next(16), 0xbdad);
+
+        const OUTPUT16: u16 = Lfsr16::new(1).naive_next(16).0.naive_next(16).1;
+        assert_eq!(OUTPUT16, 0x002d);
+
+        macro_rules! naive_const {
+            ($lfsr:ty, $width:expr) => {{
+                let mut a = <$lfsr>::new(0x12);
+                let mut b = <$lfsr>::new(0x12);
+                for n in [1, 3, 4, $width, 2, $width-1] {
+                    let (b_, x) = b.naive_next(n);
+                    b = b_;
+                    assert_eq!(x, a.next(n));
+                    assert_eq!(b.state(), a.state());
+                }
+                for n in [0, 1, 100, $width, 123] {
+                    a.skip(n);
+                    b = b.naive_skip(n);
+                    assert_eq!(b.state(), a.state());
+                }
+            }}
+        }
+
+        naive_const!(Lfsr8, 8);
+        naive_const!(Lfsr16, 16);
+        naive_const!(Lfsr32, 32);
+        naive_const!(Lfsr64, 64);
+        naive_const!(Lfsr4Table, 4);
+        naive_const!(Lfsr23Table, 23);
+        naive_const!(Lfsr64TableReflected, 64);
+        naive_const!(Lfsr16Fibonacci, 16);
+        naive_const!(Lfsr16FibonacciReflected, 16);
+        naive_const!(Lfsr16LsbFirst, 16);
+        naive_const!(Lfsr16ReflectedMsbFirst, 16);
+    }
+
+    #[test]
+    fn lfsr_seek() {
+        macro_rules! seek {
+            ($lfsr:ty, $width:expr) => {{
+                let mut lfsr = <$lfsr>::new(0x12);
+                assert_eq!(lfsr.position(), 0);
+                lfsr.next($width);
+                lfsr.next(3);
+                assert_eq!(lfsr.position(), $width+3);
+                lfsr.prev(2);
+                assert_eq!(lfsr.position(), $width+1);
+                lfsr.skip(100);
+                assert_eq!(lfsr.position(), ($width+101) % <$lfsr>::PERIOD);
+                lfsr.skip_backwards(100);
+                assert_eq!(lfsr.position(), $width+1);
+                lfsr.prev($width);
+                lfsr.prev(1);
+                assert_eq!(lfsr.position(), 0);
+                lfsr.prev(1);
+                assert_eq!(lfsr.position(), <$lfsr>::PERIOD-1);
+                lfsr.next(1);
+                assert_eq!(lfsr.position(), 0);
+
+                // seeking should match skipping from the seed
+                for pos in [100, 0, 1, $width, 123, <$lfsr>::PERIOD-1] {
+                    let mut a = <$lfsr>::new(0x12);
+                    a.skip(pos);
+                    lfsr.seek(pos);
+                    assert_eq!(lfsr.position(), pos % <$lfsr>::PERIOD);
+                    assert_eq!(lfsr.next($width), a.next($width));
+                }
+            }}
+        }
+
+        seek!(Lfsr8, 8);
+        seek!(Lfsr16, 16);
+        seek!(Lfsr32, 32);
+        seek!(Lfsr64, 64);
+        seek!(Lfsr4Table, 4);
+        seek!(Lfsr23Table, 23);
+        seek!(Lfsr64TableReflected, 64);
+        seek!(Lfsr16Fibonacci, 16);
+        seek!(Lfsr16LsbFirst, 16);
+
+        // the seed of a recovered LFSR is the state before its output
+        let mut lfsr = Lfsr16::new(0x1234);
+        lfsr.skip(5);
+        let mut recovered = Lfsr16::from_output(lfsr.next(16)).unwrap();
+        assert_eq!(recovered.position(), 16);
+        recovered.seek(0);
+        lfsr.prev(16);
+        assert_eq!(recovered.next(16), lfsr.next(16));
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn lfsr_reader() {
+        use rand_core::RngCore;
+        use std::io::Read;
+
+        let mut lfsr = Lfsr64::new(0x123456789abcdef0);
+        let mut reader = LfsrReader::new(lfsr.clone());
+        let mut a = vec![0u8; 1001];
+        let mut b = vec![0u8; 1001];
+        lfsr.fill_bytes(&mut a);
+        for chunk in b.chunks_mut(13) {
+            reader.read_exact(chunk).unwrap();
+        }
+        assert_eq!(a, b);
+        assert_eq!(reader.get_ref().state(), lfsr.state());
+
+        let mut reader = LfsrReader::new(Lfsr8::new(1));
+        let mut buf = Vec::new();
+        reader.by_ref().take(255).read_to_end(&mut buf).unwrap();
+        assert_eq!(BTreeSet::from_iter(buf.iter().copied()).len(), 255);
+        assert_eq!(reader.into_inner().position(), 0);
+    }
+
+    #[test]
+    fn lfsr_iter_words() {
+        macro_rules! iter_words {
+            ($lfsr:ty, $lsb_first:expr) => {{
+                // compare against generating a bit at a time
+                let mut a = <$lfsr>::new(0x12);
+                let mut b = <$lfsr>::new(0x12);
+                for _ in 0..8 {
+                    let mut x = 0u64;
+                    for i in 0..64 {
+                        let bit = a.next(1) as u64;
+                        if $lsb_first {
+                            x |= bit << i;
+                        } else {
+                            x = (x << 1) | bit;
+                        }
+                    }
+                    assert_eq!(b.iter_u64().next(), Some(x));
+
+                    let mut x = 0u32;
+                    for i in 0..32 {
+                        let bit = a.next(1) as u32;
+                        if $lsb_first {
+                            x |= bit << i;
+                        } else {
+                            x = (x << 1) | bit;
+                        }
+                    }
+                    assert_eq!(b.iter_u32().next(), Some(x));
+                }
+                assert_eq!(b.state(), a.state());
+            }}
+        }
+
+        iter_words!(Lfsr8, false);
+        iter_words!(Lfsr16, false);
+        iter_words!(Lfsr32, false);
+        iter_words!(Lfsr64, false);
+        iter_words!(Lfsr4Table, false);
+        iter_words!(Lfsr23Table, false);
+        iter_words!(Lfsr64TableReflected, true);
+        iter_words!(Lfsr16LsbFirst, true);
+        iter_words!(Lfsr16ReflectedMsbFirst, false);
+
+        let mut lfsr = Lfsr64::new(1);
+        let buf = lfsr.iter_u64().take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x0000000000000001,0x000000000000001b,0x0000000000000145,0x0000000000001db7,0x0000000000011011,0x00000000001ab1ab,0x0000000001514515,0x000000001c6db6c7]);
+    }
+
+    #[test]
+    fn lfsr_try_new() {
+        use rand_core::SeedableRng;
+
+        assert_eq!(Lfsr16::try_new(0x1234).unwrap().state(), 0x1234);
+        assert_eq!(Lfsr16::try_new(0).unwrap_err(), Error::ZeroSeed);
+        assert_eq!(Lfsr64::try_new(0).unwrap_err(), Error::ZeroSeed);
+
+        // bits outside the LFSR's width are ignored
+        assert_eq!(Lfsr4Table::try_new(0x13).unwrap().state(), 0x3);
+        assert_eq!(Lfsr4Table::try_new(0x10).unwrap_err(), Error::ZeroSeed);
+        assert_eq!(Lfsr23Table::try_new(0xff800000).unwrap_err(), Error::ZeroSeed);
+        assert_eq!(Lfsr23Table::new(0xff800000).state(), 1);
+
+        // from_seed uses the full width of the state
+        let lfsr = Lfsr64::from_seed([0xf0,0xde,0xbc,0x9a,0x78,0x56,0x34,0x12]);
+        assert_eq!(lfsr.state(), 0x123456789abcdef0);
+        assert_eq!(Lfsr64::from_seed(lfsr.state().to_le_bytes()).state(), lfsr.state());
+        let lfsr = Lfsr64::from_seed([0; 8]);
+        assert_eq!(lfsr.state(), 1);
+    }
+
+    #[test]
+    fn lfsr_try_new_generic() {
+        fn validate<L: Lfsr>(seed: L::U) -> Result<L::U, Error> {
+            Ok(L::try_new(seed)?.state())
+        }
+
+        assert_eq!(validate::<Lfsr16>(0x1234), Ok(0x1234));
+        assert_eq!(validate::<Lfsr16>(0), Err(Error::ZeroSeed));
+        assert_eq!(validate::<Lfsr4Table>(0x10), Err(Error::ZeroSeed));
+        assert_eq!(validate::<Lfsr64>(0), Err(Error::ZeroSeed));
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn lfsr_error() {
+        use std::string::ToString;
+
+        let err: std::boxed::Box<dyn std::error::Error> = Lfsr16::try_new(0).unwrap_err().into();
+        assert_eq!(err.to_string(), "LFSR seed can't be zero");
+    }
+
     #[test]
     fn lfsr_uniqueness() {
         let mut lfsr = Lfsr8::new(1);
@@ -46,9 +46,18 @@ pub mod rs;
 #[path="."]
 pub mod internal {
     pub mod xmul;
+    pub mod simd;
     pub use cfg_if;
-    #[cfg(any(feature="lfsr", feature="shamir"))]
+    #[cfg(feature="shamir")]
     pub use rand;
+    #[cfg(feature="rand_core")]
+    pub use rand_core;
+    #[cfg(feature="serde")]
+    pub use serde;
+    #[cfg(feature="rayon")]
+    pub use rayon;
+    #[cfg(feature="zeroize")]
+    pub use zeroize;
 }
 
 /// A flag indicating if hardware carry-less multiplication
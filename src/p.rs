@@ -92,8 +92,10 @@
 //! ## Hardware support
 //!
 //! The polynomial types leverage [carry-less multiplication][xmul] instructions
-//! when available, otherwise falling back to a more expensive, branch-less naive
-//! implementation.
+//! when available, otherwise falling back to a more expensive software
+//! implementation that multiplies 4-bits at a time using a small 16-element
+//! table. If the feature `no-tables` is enabled, this falls back to an even
+//! more expensive, branch-less naive implementation.
 //!
 //! Note that at the time of writing, aarch64 [`pmull`][pmull] support is only
 //! available on a [nightly][nightly] compiler.
@@ -153,6 +155,11 @@
 //! For polynomial types, addition (xor), subtraction (xor), and multiplication
 //! should always be constant-time.
 //!
+//! Note that the software fallback for multiplication, used when hardware
+//! carry-less multiplication isn't available, indexes a small 16-element table,
+//! and may be susceptible to cache-timing attacks on some hardware. The feature
+//! `no-tables` can be used to force a naive, branch-less implementation.
+//!
 //! Note that division and remainder are NOT constant-time. These are expensive,
 //! branching, loop-based implementations, which should generally be avoided for
 //! performance reasons anyway (outside of constant generation).
@@ -223,6 +230,14 @@
 ///
 pub use gf256_macros::p;
 
+use cfg_if::cfg_if;
+#[allow(unused)]
+use core::mem::size_of;
+#[allow(unused)]
+use core::convert::TryFrom;
+use core::ops::*;
+use core::fmt;
+
 // polynomial types
 #[p(u=u8)]    pub type p8;
 #[p(u=u16)]   pub type p16;
@@ -232,6 +247,588 @@ pub use gf256_macros::p;
 #[p(u=usize)] pub type psize;
 
 
+/// Xor a slice of bytes into another slice of bytes.
+///
+/// This is equivalent to polynomial addition over each byte, and is a common
+/// building block for parity and erasure codes. This uses explicit wide
+/// registers when available (`avx2`/`sse2` on x86_64, `neon` on aarch64),
+/// otherwise falling back to xoring a word at a time, so it remains fast
+/// even when the compiler fails to vectorize a naive loop.
+///
+/// ``` rust
+/// # use ::gf256::*;
+/// let mut a = [0x12, 0x34, 0x56, 0x78];
+/// let b = [0x9a, 0xbc, 0xde, 0xf1];
+/// slice_xor(&mut a, &b);
+/// assert_eq!(a, [0x88, 0x88, 0x88, 0x89]);
+/// ```
+///
+/// This panics if `dst` and `src` have different lengths. See also
+/// [`p8::slice_xor`] and friends for xoring slices of polynomial types.
+///
+pub fn slice_xor(dst: &mut [u8], src: &[u8]) {
+    assert_eq!(dst.len(), src.len());
+    let len = dst.len();
+    let mut i = 0;
+
+    cfg_if! {
+        if #[cfg(all(target_arch="x86_64", target_feature="avx2"))] {
+            // x86_64 with avx2 provides 256-bit xors
+            use core::arch::x86_64::*;
+            while i + 32 <= len {
+                unsafe {
+                    let a = _mm256_loadu_si256(dst.as_ptr().add(i) as *const __m256i);
+                    let b = _mm256_loadu_si256(src.as_ptr().add(i) as *const __m256i);
+                    _mm256_storeu_si256(dst.as_mut_ptr().add(i) as *mut __m256i, _mm256_xor_si256(a, b));
+                }
+                i += 32;
+            }
+        } else if #[cfg(all(target_arch="x86_64", target_feature="sse2"))] {
+            // x86_64 with sse2 provides 128-bit xors
+            use core::arch::x86_64::*;
+            while i + 16 <= len {
+                unsafe {
+                    let a = _mm_loadu_si128(dst.as_ptr().add(i) as *const __m128i);
+                    let b = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+                    _mm_storeu_si128(dst.as_mut_ptr().add(i) as *mut __m128i, _mm_xor_si128(a, b));
+                }
+                i += 16;
+            }
+        } else if #[cfg(all(target_arch="aarch64", target_feature="neon"))] {
+            // aarch64 with neon provides 128-bit xors
+            use core::arch::aarch64::*;
+            while i + 16 <= len {
+                unsafe {
+                    let a = vld1q_u8(dst.as_ptr().add(i));
+                    let b = vld1q_u8(src.as_ptr().add(i));
+                    vst1q_u8(dst.as_mut_ptr().add(i), veorq_u8(a, b));
+                }
+                i += 16;
+            }
+        } else {
+            // fall back to xoring a word at a time
+            const W: usize = size_of::<usize>();
+            while i + W <= len {
+                let a = usize::from_ne_bytes(<[u8; W]>::try_from(&dst[i..i+W]).unwrap());
+                let b = usize::from_ne_bytes(<[u8; W]>::try_from(&src[i..i+W]).unwrap());
+                dst[i..i+W].copy_from_slice(&(a ^ b).to_ne_bytes());
+                i += W;
+            }
+        }
+    }
+
+    // handle remainder
+    while i < len {
+        dst[i] ^= src[i];
+        i += 1;
+    }
+}
+
+
+/// A fixed-size, multi-limb polynomial type.
+///
+/// This is a polynomial made out of `LIMBS` 64-bit limbs, stored
+/// little-endian (the lowest limb first), for when a polynomial doesn't
+/// fit in any of the builtin polynomial types. This doesn't allocate,
+/// so it remains usable in `no_std` environments without `alloc`.
+///
+/// ``` rust
+/// # use ::gf256::*;
+/// let a = PArr::<4>::new([0x123456789abcdef1, 0x23456789abcdef12, 0, 0]);
+/// let b = PArr::<4>::new([0x3456789abcdef123, 0, 0, 0]);
+/// assert_eq!((a*b) / b, a);
+/// assert_eq!((a*b + PArr::new([1, 0, 0, 0])) % b, PArr::new([1, 0, 0, 0]));
+/// ```
+///
+/// Multiplication is built out of 64-bit polynomial multiplication, and
+/// so uses hardware carry-less multiplication when available. Division
+/// and remainder have no hardware support, and use relatively expensive
+/// bitwise long division, similar to the naive implementations in the
+/// other polynomial types.
+///
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PArr<const LIMBS: usize>(pub [u64; LIMBS]);
+
+impl<const LIMBS: usize> PArr<LIMBS> {
+    /// Width of the polynomial in bits.
+    pub const WIDTH: usize = 64*LIMBS;
+
+    /// Create a polynomial from its limbs, lowest limb first.
+    #[inline]
+    pub const fn new(limbs: [u64; LIMBS]) -> PArr<LIMBS> {
+        PArr(limbs)
+    }
+
+    /// Get the limbs of a polynomial, lowest limb first.
+    #[inline]
+    pub const fn get(self) -> [u64; LIMBS] {
+        self.0
+    }
+
+    /// The zero polynomial.
+    #[inline]
+    pub const fn zero() -> PArr<LIMBS> {
+        PArr([0; LIMBS])
+    }
+
+    /// The polynomial `1`.
+    ///
+    /// Note this is zero if `LIMBS == 0`.
+    ///
+    #[inline]
+    pub const fn one() -> PArr<LIMBS> {
+        let mut x = [0; LIMBS];
+        if LIMBS > 0 {
+            x[0] = 1;
+        }
+        PArr(x)
+    }
+
+    /// Returns true if this is the zero polynomial.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|x| *x == 0)
+    }
+
+    /// Get the coefficient of `x^i`.
+    ///
+    /// This will panic if `i` is out of bounds.
+    ///
+    #[inline]
+    pub const fn bit(&self, i: usize) -> bool {
+        (self.0[i/64] >> (i%64)) & 1 != 0
+    }
+
+    /// Number of non-zero coefficients.
+    #[inline]
+    pub fn count_ones(&self) -> u32 {
+        self.0.iter().map(|x| x.count_ones()).sum()
+    }
+
+    /// Number of leading zero bits, counting from the highest limb.
+    ///
+    /// This is [`WIDTH`](Self::WIDTH) for the zero polynomial.
+    ///
+    #[inline]
+    pub fn leading_zeros(&self) -> u32 {
+        let mut n = 0;
+        for x in self.0.iter().rev() {
+            n += x.leading_zeros();
+            if *x != 0 {
+                break;
+            }
+        }
+        n
+    }
+
+    /// Number of trailing zero bits, counting from the lowest limb.
+    ///
+    /// This is [`WIDTH`](Self::WIDTH) for the zero polynomial.
+    ///
+    #[inline]
+    pub fn trailing_zeros(&self) -> u32 {
+        let mut n = 0;
+        for x in self.0.iter() {
+            n += x.trailing_zeros();
+            if *x != 0 {
+                break;
+            }
+        }
+        n
+    }
+
+    /// Polynomial addition, aka xor.
+    ///
+    /// Naive versions are built out of simple bitwise operations,
+    /// these are more expensive, but also allowed in const contexts.
+    ///
+    #[inline]
+    pub const fn naive_add(self, other: PArr<LIMBS>) -> PArr<LIMBS> {
+        let mut x = self.0;
+        let mut i = 0;
+        while i < LIMBS {
+            x[i] ^= other.0[i];
+            i += 1;
+        }
+        PArr(x)
+    }
+
+    /// Polynomial subtraction, aka xor.
+    ///
+    /// Naive versions are built out of simple bitwise operations,
+    /// these are more expensive, but also allowed in const contexts.
+    ///
+    #[inline]
+    pub const fn naive_sub(self, other: PArr<LIMBS>) -> PArr<LIMBS> {
+        self.naive_add(other)
+    }
+
+    /// Polynomial multiplication.
+    ///
+    /// This returns a tuple containing the low and high parts in that order.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let a = PArr::<2>::new([0, 0x8000000000000000]);
+    /// assert_eq!(a.widening_mul(a), (PArr::new([0, 0]), PArr::new([0, 0x4000000000000000])));
+    /// ```
+    ///
+    #[inline]
+    pub fn widening_mul(self, other: PArr<LIMBS>) -> (PArr<LIMBS>, PArr<LIMBS>) {
+        let mut lo = [0; LIMBS];
+        let mut hi = [0; LIMBS];
+        for i in 0..LIMBS {
+            if self.0[i] == 0 {
+                continue;
+            }
+            for j in 0..LIMBS {
+                let (x_lo, x_hi) = p64(self.0[i]).widening_mul(p64(other.0[j]));
+                let k = i+j;
+                if k < LIMBS { lo[k] ^= x_lo.0; } else { hi[k-LIMBS] ^= x_lo.0; }
+                if k+1 < LIMBS { lo[k+1] ^= x_hi.0; } else { hi[k+1-LIMBS] ^= x_hi.0; }
+            }
+        }
+        (PArr(lo), PArr(hi))
+    }
+
+    /// Polynomial multiplication.
+    ///
+    /// Note this wraps around the boundary of the type, and returns
+    /// a flag indicating of overflow occured.
+    ///
+    #[inline]
+    pub fn overflowing_mul(self, other: PArr<LIMBS>) -> (PArr<LIMBS>, bool) {
+        let (lo, hi) = self.widening_mul(other);
+        (lo, !hi.is_zero())
+    }
+
+    /// Polynomial multiplication.
+    ///
+    /// Returns [`None`] if the result would overflow.
+    ///
+    #[inline]
+    pub fn checked_mul(self, other: PArr<LIMBS>) -> Option<PArr<LIMBS>> {
+        match self.overflowing_mul(other) {
+            (_, true ) => None,
+            (x, false) => Some(x),
+        }
+    }
+
+    /// Polynomial multiplication.
+    ///
+    /// Note this wraps around the boundary of the type.
+    ///
+    #[inline]
+    pub fn wrapping_mul(self, other: PArr<LIMBS>) -> PArr<LIMBS> {
+        let mut x = [0; LIMBS];
+        for i in 0..LIMBS {
+            if self.0[i] == 0 {
+                continue;
+            }
+            for j in 0..LIMBS-i {
+                let (x_lo, x_hi) = p64(self.0[i]).widening_mul(p64(other.0[j]));
+                x[i+j] ^= x_lo.0;
+                if i+j+1 < LIMBS {
+                    x[i+j+1] ^= x_hi.0;
+                }
+            }
+        }
+        PArr(x)
+    }
+
+    /// Polynomial division and remainder.
+    ///
+    /// Returns [`None`] if `other == 0`.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let a = PArr::<2>::new([0x69, 0]);
+    /// let b = PArr::<2>::new([0x34, 0]);
+    /// assert_eq!(a.checked_div_rem(b), Some((PArr::new([0x02, 0]), PArr::new([0x01, 0]))));
+    /// assert_eq!(a.checked_div_rem(PArr::zero()), None);
+    /// ```
+    ///
+    pub fn checked_div_rem(self, other: PArr<LIMBS>) -> Option<(PArr<LIMBS>, PArr<LIMBS>)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let mut a = self;
+        let b = other;
+        let mut x = PArr::zero();
+        while a.leading_zeros() <= b.leading_zeros() {
+            let shift = b.leading_zeros() - a.leading_zeros();
+            x.0[(shift/64) as usize] ^= 1 << (shift%64);
+            a += b << shift;
+        }
+        Some((x, a))
+    }
+
+    /// Polynomial division.
+    ///
+    /// Returns [`None`] if `other == 0`.
+    ///
+    #[inline]
+    pub fn checked_div(self, other: PArr<LIMBS>) -> Option<PArr<LIMBS>> {
+        self.checked_div_rem(other).map(|(x, _)| x)
+    }
+
+    /// Polynomial remainder.
+    ///
+    /// Returns [`None`] if `other == 0`.
+    ///
+    #[inline]
+    pub fn checked_rem(self, other: PArr<LIMBS>) -> Option<PArr<LIMBS>> {
+        self.checked_div_rem(other).map(|(_, x)| x)
+    }
+
+    /// Polynomial exponentiation.
+    ///
+    /// Note this wraps around the boundary of the type.
+    ///
+    pub fn wrapping_pow(self, exp: u32) -> PArr<LIMBS> {
+        let mut a = self;
+        let mut exp = exp;
+        let mut x = PArr::one();
+        loop {
+            if exp & 1 != 0 {
+                x = x.wrapping_mul(a);
+            }
+
+            exp >>= 1;
+            if exp == 0 {
+                return x;
+            }
+            a = a.wrapping_mul(a);
+        }
+    }
+}
+
+impl<const LIMBS: usize> Default for PArr<LIMBS> {
+    #[inline]
+    fn default() -> PArr<LIMBS> {
+        PArr::zero()
+    }
+}
+
+impl<const LIMBS: usize> From<[u64; LIMBS]> for PArr<LIMBS> {
+    #[inline]
+    fn from(x: [u64; LIMBS]) -> PArr<LIMBS> {
+        PArr(x)
+    }
+}
+
+impl<const LIMBS: usize> From<PArr<LIMBS>> for [u64; LIMBS] {
+    #[inline]
+    fn from(x: PArr<LIMBS>) -> [u64; LIMBS] {
+        x.0
+    }
+}
+
+impl<const LIMBS: usize> Add for PArr<LIMBS> {
+    type Output = PArr<LIMBS>;
+    #[inline]
+    fn add(self, other: PArr<LIMBS>) -> PArr<LIMBS> {
+        PArr::naive_add(self, other)
+    }
+}
+
+impl<const LIMBS: usize> AddAssign for PArr<LIMBS> {
+    #[inline]
+    fn add_assign(&mut self, other: PArr<LIMBS>) {
+        *self = PArr::naive_add(*self, other);
+    }
+}
+
+impl<const LIMBS: usize> Sub for PArr<LIMBS> {
+    type Output = PArr<LIMBS>;
+    #[inline]
+    fn sub(self, other: PArr<LIMBS>) -> PArr<LIMBS> {
+        PArr::naive_sub(self, other)
+    }
+}
+
+impl<const LIMBS: usize> SubAssign for PArr<LIMBS> {
+    #[inline]
+    fn sub_assign(&mut self, other: PArr<LIMBS>) {
+        *self = PArr::naive_sub(*self, other);
+    }
+}
+
+impl<const LIMBS: usize> Mul for PArr<LIMBS> {
+    type Output = PArr<LIMBS>;
+    #[inline]
+    fn mul(self, other: PArr<LIMBS>) -> PArr<LIMBS> {
+        cfg_if! {
+            if #[cfg(debug_assertions)] {
+                match self.checked_mul(other) {
+                    Some(x) => x,
+                    None => panic!("overflow in polynomial multiply"),
+                }
+            } else {
+                self.wrapping_mul(other)
+            }
+        }
+    }
+}
+
+impl<const LIMBS: usize> MulAssign for PArr<LIMBS> {
+    #[inline]
+    fn mul_assign(&mut self, other: PArr<LIMBS>) {
+        *self = *self * other;
+    }
+}
+
+impl<const LIMBS: usize> Div for PArr<LIMBS> {
+    type Output = PArr<LIMBS>;
+    #[inline]
+    fn div(self, other: PArr<LIMBS>) -> PArr<LIMBS> {
+        match self.checked_div(other) {
+            Some(x) => x,
+            None => panic!("attempt to divide by zero"),
+        }
+    }
+}
+
+impl<const LIMBS: usize> DivAssign for PArr<LIMBS> {
+    #[inline]
+    fn div_assign(&mut self, other: PArr<LIMBS>) {
+        *self = *self / other;
+    }
+}
+
+impl<const LIMBS: usize> Rem for PArr<LIMBS> {
+    type Output = PArr<LIMBS>;
+    #[inline]
+    fn rem(self, other: PArr<LIMBS>) -> PArr<LIMBS> {
+        match self.checked_rem(other) {
+            Some(x) => x,
+            None => panic!("attempt to calculate the remainder with a divisor of zero"),
+        }
+    }
+}
+
+impl<const LIMBS: usize> RemAssign for PArr<LIMBS> {
+    #[inline]
+    fn rem_assign(&mut self, other: PArr<LIMBS>) {
+        *self = *self % other;
+    }
+}
+
+impl<const LIMBS: usize> Shl<usize> for PArr<LIMBS> {
+    type Output = PArr<LIMBS>;
+    /// Note that unlike the builtin polynomial types, bits shifted past
+    /// the boundary of the type are simply discarded.
+    #[inline]
+    fn shl(self, other: usize) -> PArr<LIMBS> {
+        let limbs = other/64;
+        let bits = other%64;
+        let mut x = [0; LIMBS];
+        for (i, x) in x.iter_mut().enumerate().skip(limbs) {
+            *x = self.0[i-limbs] << bits;
+            if bits != 0 && i > limbs {
+                *x |= self.0[i-limbs-1] >> (64-bits);
+            }
+        }
+        PArr(x)
+    }
+}
+
+impl<const LIMBS: usize> ShlAssign<usize> for PArr<LIMBS> {
+    #[inline]
+    fn shl_assign(&mut self, other: usize) {
+        *self = *self << other;
+    }
+}
+
+impl<const LIMBS: usize> Shl<u32> for PArr<LIMBS> {
+    type Output = PArr<LIMBS>;
+    #[inline]
+    fn shl(self, other: u32) -> PArr<LIMBS> {
+        self << usize::try_from(other).unwrap()
+    }
+}
+
+impl<const LIMBS: usize> ShlAssign<u32> for PArr<LIMBS> {
+    #[inline]
+    fn shl_assign(&mut self, other: u32) {
+        *self = *self << other;
+    }
+}
+
+impl<const LIMBS: usize> Shr<usize> for PArr<LIMBS> {
+    type Output = PArr<LIMBS>;
+    /// Note that unlike the builtin polynomial types, bits shifted past
+    /// the boundary of the type are simply discarded.
+    #[inline]
+    fn shr(self, other: usize) -> PArr<LIMBS> {
+        let limbs = other/64;
+        let bits = other%64;
+        let mut x = [0; LIMBS];
+        for (i, x) in x.iter_mut().enumerate().take(LIMBS.saturating_sub(limbs)) {
+            *x = self.0[i+limbs] >> bits;
+            if bits != 0 && i+limbs+1 < LIMBS {
+                *x |= self.0[i+limbs+1] << (64-bits);
+            }
+        }
+        PArr(x)
+    }
+}
+
+impl<const LIMBS: usize> ShrAssign<usize> for PArr<LIMBS> {
+    #[inline]
+    fn shr_assign(&mut self, other: usize) {
+        *self = *self >> other;
+    }
+}
+
+impl<const LIMBS: usize> Shr<u32> for PArr<LIMBS> {
+    type Output = PArr<LIMBS>;
+    #[inline]
+    fn shr(self, other: u32) -> PArr<LIMBS> {
+        self >> usize::try_from(other).unwrap()
+    }
+}
+
+impl<const LIMBS: usize> ShrAssign<u32> for PArr<LIMBS> {
+    #[inline]
+    fn shr_assign(&mut self, other: u32) {
+        *self = *self >> other;
+    }
+}
+
+impl<const LIMBS: usize> fmt::Debug for PArr<LIMBS> {
+    /// We use LowerHex for Debug, since this is a more useful representation
+    /// of binary polynomials.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "PArr(0x{:x})", self)
+    }
+}
+
+impl<const LIMBS: usize> fmt::Display for PArr<LIMBS> {
+    /// We use LowerHex for Display since this is a more useful representation
+    /// of binary polynomials.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "0x{:x}", self)
+    }
+}
+
+impl<const LIMBS: usize> fmt::LowerHex for PArr<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        // skip leading zero limbs, and pad the rest
+        let mut limbs = self.0.iter().rev().skip_while(|x| **x == 0);
+        match limbs.next() {
+            Some(x) => write!(f, "{:x}", x)?,
+            None => write!(f, "0")?,
+        }
+        for x in limbs {
+            write!(f, "{:016x}", x)?;
+        }
+        Ok(())
+    }
+}
+
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -373,6 +970,149 @@ mod test {
         }
     }
 
+    #[test]
+    fn widening_mul128() {
+        // full 128x128 -> 256-bit products
+        assert_eq!(
+            p128(0x123456789abcdef123456789abcdef12).widening_mul(p128(0x3456789abcdef123456789abcdef1234)),
+            (p128(0x280ee06c83e50bb694bf46dd3f55ab28), p128(0x0328db698aa112b13699e7d8bf102e03))
+        );
+        assert_eq!(
+            p128(u128::MAX).widening_mul(p128(u128::MAX)),
+            (p128(0x55555555555555555555555555555555), p128(0x55555555555555555555555555555555))
+        );
+
+        // compare against naive, with asymmetric limbs
+        let mut x = 0x123456789abcdef123456789abcdef12u128;
+        for _ in 0..100 {
+            let a = p128(x);
+            x = x.rotate_left(23).wrapping_mul(0x9e3779b97f4a7c15f39cc0605cedc835);
+            let b = p128(x);
+            x = x.rotate_left(23).wrapping_mul(0x9e3779b97f4a7c15f39cc0605cedc835);
+            assert_eq!(a.widening_mul(b), a.naive_widening_mul(b));
+            assert_eq!(b.widening_mul(a), a.naive_widening_mul(b));
+        }
+    }
+
+    #[test]
+    fn mul_acc() {
+        for a in (0..=255).map(p8) {
+            for b in (0..=255).map(p8) {
+                let acc = (a+p8(0x5a), b+p8(0xa5));
+                let (lo, hi) = a.widening_mul(b);
+                assert_eq!(a.widening_mul_acc(b, acc), (acc.0+lo, acc.1+hi));
+                assert_eq!(a.wrapping_mul_acc(b, acc.0), acc.0+lo);
+            }
+        }
+
+        let a = p64(0x123456789abcdef1);
+        let b = p64(0xfedcba9876543210);
+        let (lo, hi) = a.naive_widening_mul(b);
+        assert_eq!(a.widening_mul_acc(b, (b, a)), (b+lo, a+hi));
+        assert_eq!(a.wrapping_mul_acc(b, b), b+lo);
+
+        let a = p128(0x123456789abcdef123456789abcdef12);
+        let (lo, hi) = a.naive_widening_mul(a);
+        assert_eq!(a.widening_mul_acc(a, (a, a)), (a+lo, a+hi));
+    }
+
+    #[test]
+    fn widening_mul_x4() {
+        for a in (0..=255).map(p8) {
+            for b in (0..=255).step_by(4).map(p8) {
+                let bs = [b, b+p8(1), b+p8(2), b+p8(3)];
+                let (lo, hi) = p8::widening_mul_x4([a; 4], bs);
+                for i in 0..4 {
+                    assert_eq!((lo[i], hi[i]), a.widening_mul(bs[i]));
+                }
+            }
+        }
+
+        let a = p64(0x123456789abcdef1);
+        let b = p64(0xfedcba9876543210);
+        let (lo, hi) = p64::widening_mul_x4([a, b, a, b], [a, a, b, b]);
+        assert_eq!((lo[0], hi[0]), a.naive_widening_mul(a));
+        assert_eq!((lo[1], hi[1]), b.naive_widening_mul(a));
+        assert_eq!((lo[2], hi[2]), a.naive_widening_mul(b));
+        assert_eq!((lo[3], hi[3]), b.naive_widening_mul(b));
+
+        let a = p128(0x123456789abcdef123456789abcdef12);
+        let (lo, hi) = p128::widening_mul_x4([a; 4], [a; 4]);
+        assert_eq!((lo[3], hi[3]), a.naive_widening_mul(a));
+    }
+
+    #[test]
+    fn rev_mul() {
+        for a in (0..=255).map(p8) {
+            for b in (0..=255).map(p8) {
+                // multiplying bit-reversed polynomials should give us the
+                // bit-reversed product
+                let x = (p16::from(a) * p16::from(b)).reverse_bits();
+                let lo = p8::try_from(x & 0xff).unwrap();
+                let hi = p8::try_from(x >> 8).unwrap();
+                let a = a.reverse_bits();
+                let b = b.reverse_bits();
+
+                assert_eq!(a.naive_rev_widening_mul(b), (lo, hi));
+                assert_eq!(a.rev_widening_mul(b), (lo, hi));
+                assert_eq!(a.naive_rev_wrapping_mul(b), hi);
+                assert_eq!(a.rev_wrapping_mul(b), hi);
+            }
+        }
+
+        let a = p64(0x123456789abcdef1);
+        let b = p64(0xfedcba9876543210);
+        let x = (p128::from(a) * p128::from(b)).reverse_bits();
+        assert_eq!(
+            a.reverse_bits().rev_widening_mul(b.reverse_bits()),
+            (p64::try_from(x & 0xffffffffffffffff).unwrap(), p64::try_from(x >> 64).unwrap())
+        );
+        assert_eq!(p64(0x8000000000000000).rev_mul(b), b);
+    }
+
+    #[test]
+    fn rev_mul_widths() {
+        let mut x = 0x123456789abcdef123456789abcdef12u128;
+        for _ in 0..100 {
+            let a = x;
+            x = x.rotate_left(23).wrapping_mul(0x9e3779b97f4a7c15f39cc0605cedc835);
+            let b = x;
+            x = x.rotate_left(23).wrapping_mul(0x9e3779b97f4a7c15f39cc0605cedc835);
+
+            // multiplying bit-reversed polynomials should give us the
+            // bit-reversed product
+            let (a16, b16) = (p16(a as u16), p16(b as u16));
+            let y = (p32::from(a16) * p32::from(b16)).reverse_bits();
+            let (lo, hi) = (p16::try_from(y & 0xffff).unwrap(), p16::try_from(y >> 16).unwrap());
+            assert_eq!(a16.reverse_bits().rev_widening_mul(b16.reverse_bits()), (lo, hi));
+            assert_eq!(a16.reverse_bits().rev_wrapping_mul(b16.reverse_bits()), hi);
+
+            let (a32, b32) = (p32(a as u32), p32(b as u32));
+            let y = (p64::from(a32) * p64::from(b32)).reverse_bits();
+            let (lo, hi) = (p32::try_from(y & 0xffffffff).unwrap(), p32::try_from(y >> 32).unwrap());
+            assert_eq!(a32.reverse_bits().rev_widening_mul(b32.reverse_bits()), (lo, hi));
+            assert_eq!(a32.reverse_bits().rev_wrapping_mul(b32.reverse_bits()), hi);
+
+            let (a64, b64) = (p64(a as u64), p64(b as u64));
+            let y = (p128::from(a64) * p128::from(b64)).reverse_bits();
+            let (lo, hi) = (p64::try_from(y & 0xffffffffffffffff).unwrap(), p64::try_from(y >> 64).unwrap());
+            assert_eq!(a64.reverse_bits().rev_widening_mul(b64.reverse_bits()), (lo, hi));
+            assert_eq!(a64.reverse_bits().rev_wrapping_mul(b64.reverse_bits()), hi);
+
+            // no wider type, but reversing a (lo, hi) product swaps
+            // and reverses each half
+            let (a128, b128) = (p128(a), p128(b));
+            let (lo, hi) = a128.widening_mul(b128);
+            assert_eq!(a128.reverse_bits().rev_widening_mul(b128.reverse_bits()), (hi.reverse_bits(), lo.reverse_bits()));
+            assert_eq!(a128.reverse_bits().rev_wrapping_mul(b128.reverse_bits()), lo.reverse_bits());
+
+            let (asize, bsize) = (psize(a as usize), psize(b as usize));
+            let (lo, hi) = asize.widening_mul(bsize);
+            assert_eq!(asize.reverse_bits().rev_widening_mul(bsize.reverse_bits()), (hi.reverse_bits(), lo.reverse_bits()));
+            assert_eq!(asize.reverse_bits().rev_wrapping_mul(bsize.reverse_bits()), lo.reverse_bits());
+        }
+    }
+
     #[test]
     fn mul_div() {
         for a in (1..=255).map(p16) {
@@ -422,6 +1162,171 @@ mod test {
         }
     }
 
+    #[test]
+    fn slice_xor() {
+        // test a number of lengths to make sure we hit all code paths
+        let mut a = [0u8; 100];
+        let mut b = [0u8; 100];
+        for i in 0..100 {
+            a[i] = (i as u8).wrapping_mul(0x45);
+            b[i] = (i as u8).wrapping_mul(0x9b) ^ 0x5a;
+        }
+        for len in 0..100 {
+            let mut x = a;
+            super::slice_xor(&mut x[..len], &b[..len]);
+            for i in 0..len {
+                assert_eq!(x[i], a[i] ^ b[i]);
+            }
+        }
+
+        let mut x = [p16(0x1234), p16(0x5678), p16(0x9abc)];
+        p16::slice_xor(&mut x, &[p16(0x5678), p16(0x5678), p16(0x5678)]);
+        assert_eq!(x, [p16(0x444c), p16(0x0000), p16(0xccc4)]);
+    }
+
+    #[test]
+    fn byte_slice() {
+        let bytes = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0];
+        for len in 0..=8 {
+            let mut le = [0u8; 8];
+            le[..len].copy_from_slice(&bytes[..len]);
+            assert_eq!(p64::from_le_byte_slice(&bytes[..len]), p64::from_le_bytes(le));
+            let mut be = [0u8; 8];
+            be[8-len..].copy_from_slice(&bytes[..len]);
+            assert_eq!(p64::from_be_byte_slice(&bytes[..len]), p64::from_be_bytes(be));
+
+            // round trip
+            let mut x = [0u8; 8];
+            p64::from_le_byte_slice(&bytes[..len]).to_le_byte_slice(&mut x[..len]);
+            assert_eq!(&x[..len], &bytes[..len]);
+            let mut x = [0u8; 8];
+            p64::from_be_byte_slice(&bytes[..len]).to_be_byte_slice(&mut x[..len]);
+            assert_eq!(&x[..len], &bytes[..len]);
+        }
+    }
+
+    #[test]
+    fn bit_slice() {
+        let bytes = [0x12, 0x34, 0x56, 0x78, 0x9a];
+        let le = u64::from_le_bytes([0x12, 0x34, 0x56, 0x78, 0x9a, 0, 0, 0]);
+        let be = u64::from_be_bytes([0, 0, 0, 0x12, 0x34, 0x56, 0x78, 0x9a]);
+        for off in 0..8 {
+            for len in 0..=32 {
+                let mask = ((1u64 << len) - 1) as u32;
+                assert_eq!(
+                    p32::from_le_bit_slice(&bytes, off, len),
+                    p32(((le >> off) as u32) & mask)
+                );
+                assert_eq!(
+                    p32::from_be_bit_slice(&bytes, off, len),
+                    p32(((be >> (40-off-len)) as u32) & mask)
+                );
+
+                // writing should only change the selected bits
+                let mut x = bytes;
+                p32(!0).to_le_bit_slice(&mut x, off, len);
+                let y = u64::from_le_bytes([x[0], x[1], x[2], x[3], x[4], 0, 0, 0]);
+                assert_eq!(y, le | ((mask as u64) << off));
+                let mut x = bytes;
+                p32(0).to_be_bit_slice(&mut x, off, len);
+                let y = u64::from_be_bytes([0, 0, 0, x[0], x[1], x[2], x[3], x[4]]);
+                assert_eq!(y, be & !((mask as u64) << (40-off-len)));
+            }
+        }
+    }
+
+    #[test]
+    fn parr() {
+        fn to_parr(x: p128) -> PArr<2> {
+            PArr([x.0 as u64, (x.0 >> 64) as u64])
+        }
+
+        // compare against p128
+        let mut x = 0x123456789abcdef123456789abcdef12u128;
+        for _ in 0..100 {
+            let a = p128(x);
+            x = x.rotate_left(23).wrapping_mul(0x9e3779b97f4a7c15f39cc0605cedc835);
+            let b = p128((x >> (x % 128)) | 1);
+            x = x.rotate_left(23).wrapping_mul(0x9e3779b97f4a7c15f39cc0605cedc835);
+
+            assert_eq!(to_parr(a) + to_parr(b), to_parr(a + b));
+            let (lo, hi) = a.widening_mul(b);
+            assert_eq!(to_parr(a).widening_mul(to_parr(b)), (to_parr(lo), to_parr(hi)));
+            assert_eq!(to_parr(a).wrapping_mul(to_parr(b)), to_parr(a.wrapping_mul(b)));
+            assert_eq!(to_parr(a) / to_parr(b), to_parr(a / b));
+            assert_eq!(to_parr(a) % to_parr(b), to_parr(a % b));
+            for shift in [0u32, 1, 63, 64, 65, 127] {
+                assert_eq!(to_parr(a) << shift, to_parr(a << shift));
+                assert_eq!(to_parr(a) >> shift, to_parr(a >> shift));
+            }
+            for shift in [0usize, 1, 63, 64, 65, 127] {
+                assert_eq!(to_parr(a) << shift, to_parr(a << shift));
+                assert_eq!(to_parr(a) >> shift, to_parr(a >> shift));
+                let mut x = to_parr(a);
+                x <<= shift;
+                assert_eq!(x, to_parr(a << shift));
+                let mut x = to_parr(a);
+                x >>= shift;
+                assert_eq!(x, to_parr(a >> shift));
+            }
+        }
+
+        // larger polynomials
+        let a = PArr::<4>::new([0x123456789abcdef1, 0x23456789abcdef12, 0x3456789abcdef123, 0]);
+        let b = PArr::<4>::new([0x456789abcdef1234, 0x1, 0, 0]);
+        let c = PArr::<4>::new([0x56789abcdef12345, 0, 0, 0]);
+        assert_eq!((a*b) / b, a);
+        assert_eq!((a*b) % b, PArr::zero());
+        assert_eq!((a*b + c) / b, a);
+        assert_eq!((a*b + c) % b, c);
+        assert_eq!(a.wrapping_pow(3), a.wrapping_mul(a).wrapping_mul(a));
+        assert_eq!((a << 200u32) >> 200u32, PArr::new([a.0[0] & 0x00ffffffffffffff, 0, 0, 0]));
+        assert_eq!((a << 200usize) >> 200usize, PArr::new([a.0[0] & 0x00ffffffffffffff, 0, 0, 0]));
+        assert_eq!(a.leading_zeros(), 66);
+        assert_eq!(c.trailing_zeros(), 0);
+        assert_eq!(PArr::<4>::zero().leading_zeros(), 256);
+    }
+
+    #[test]
+    fn poly_trait() {
+        use crate::traits::Poly;
+
+        // a generic bitwise remainder, written once for all widths
+        fn bitwise_rem<P: Poly>(a: P, b: P) -> P {
+            let mut a = a;
+            while a.leading_zeros() <= b.leading_zeros() {
+                let shift = (b.leading_zeros() - a.leading_zeros()) as usize;
+                a ^= b << shift;
+            }
+            a
+        }
+
+        fn test<P: Poly>(a: P, b: P) {
+            assert_eq!(P::from_u(a.to_u()), a);
+            assert_eq!(Poly::checked_rem(a, b), Some(a % b));
+            assert_eq!(Poly::checked_div(a, b), Some(a / b));
+            assert_eq!(Poly::checked_div(a, P::ZERO), None);
+            assert_eq!(bitwise_rem(a, b), a % b);
+            assert_eq!(Poly::wrapping_mul(a, P::ONE), a);
+            assert_eq!(Poly::wrapping_pow(a, 2), Poly::wrapping_mul(a, a));
+            let (lo, _) = Poly::widening_mul(a, b);
+            assert_eq!(lo, Poly::wrapping_mul(a, b));
+            assert_eq!(Poly::reverse_bits(Poly::reverse_bits(a)), a);
+            assert_eq!(
+                Poly::leading_zeros(P::ONE) as usize,
+                P::WIDTH-1
+            );
+        }
+
+        test(p8(0x69), p8(0x34));
+        test(p16(0x6969), p16(0x1234));
+        test(p32(0x69696969), p32(0x12345));
+        test(p64(0x6969696969696969), p64(0x123456789));
+        test(p128(0x69696969696969696969696969696969), p128(0x123456789abcdef12));
+        test(psize(0x6969), psize(0x1234));
+        test(p8_all_params(0x69), p8_all_params(0x34));
+    }
+
     // all polynomial-type params
     #[p(
         width=8,
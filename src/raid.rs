@@ -34,6 +34,29 @@
 //! to any array of blocks as long as there is some mechanism to detect failures,
 //! such as CRCs or other checksums.
 //!
+//! Data blocks don't need to be the same length. Blocks shorter than the parity
+//! blocks are treated as though they were padded with zeros, which is useful for
+//! the last stripe of a file:
+//!
+//! ``` rust
+//! use gf256::raid::raid6;
+//!
+//! // format
+//! let mut buf = b"Hello World!".to_vec();
+//! let mut parity1 = vec![0u8; 5];
+//! let mut parity2 = vec![0u8; 5];
+//! let slices = buf.chunks(5).collect::<Vec<_>>();
+//! raid6::format(&slices, &mut parity1, &mut parity2);
+//!
+//! // corrupt
+//! buf[5..12].fill(b'x');
+//!
+//! // repair
+//! let mut slices = buf.chunks_mut(5).collect::<Vec<_>>();
+//! raid6::repair(&mut slices, &mut parity1, &mut parity2, &[1, 2]).unwrap();
+//! assert_eq!(&buf, b"Hello World!");
+//! ```
+//!
 //! Compared to other, more general [Reed-Solomon](../rs) schemes, RAID-parity has
 //! the nice feature that it is cheap to update a single block, requiring only extra
 //! read and writes for each parity block.
@@ -961,22 +984,316 @@
 //! limited to the number of non-zero elements in the field. In the case of `GF(256)`,
 //! this limits RAID 6 and RAID 7 to 255 blocks.
 //!
+//! If you need more blocks, the `raid` macro accepts a `width` option for
+//! building the parity math over a larger field. In `GF(2^16)`, each symbol of
+//! each block is a [`u16`], and we can have up to 65535 blocks:
+//!
+//! ``` rust
+//! # pub use ::gf256::*;
+//! # use ::gf256::raid::raid;
+//! #[raid(parity=2, width=16)]
+//! mod raid6_16 {}
+//!
+//! # fn main() {
+//! // format
+//! let mut blocks = (0..1000u16).map(|i| vec![i; 4]).collect::<Vec<_>>();
+//! let mut parity1 = vec![0u16; 4];
+//! let mut parity2 = vec![0u16; 4];
+//! raid6_16::format(&blocks, &mut parity1, &mut parity2);
+//!
+//! // corrupt
+//! blocks[300].fill(0xffff);
+//! blocks[900].fill(0xffff);
+//!
+//! // repair
+//! raid6_16::repair(&mut blocks, &mut parity1, &mut parity2, &[300, 900]).unwrap();
+//! assert_eq!(blocks[300], vec![300u16; 4]);
+//! assert_eq!(blocks[900], vec![900u16; 4]);
+//! # }
+//! ```
+//!
 //! Each scheme can repair any block up to the number of parity blocks, however
 //! they don't actually provide the detection of block failures. One way to do this
 //! is attach a CRC or other checksum to each block.
 //!
+//! With at least 2 parity blocks, `scrub` can locate a single silently corrupted
+//! block by comparing the parity blocks against the data blocks, though this
+//! requires reading the entire array and can be fooled by multiple bad blocks if
+//! there are only 2 parity blocks.
+//!
 //! ## RAID8? >3 parity blocks?
 //!
-//! As it is, the current scheme only supports up to 3 parity blocks. But it is
-//! actually possible to use a different scheme that works beyond 3 parity blocks.
+//! The `raid` macro supports any number of parity blocks, though with more
+//! than 3 parity blocks the parity blocks are passed as a slice, and repair
+//! falls back to solving the system of equations with Gauss-Jordan
+//! elimination:
+//!
+//! ``` rust
+//! # pub use ::gf256::*;
+//! use gf256::raid::raid;
+//!
+//! #[raid(parity=4)]
+//! pub mod raid8 {}
+//!
+//! # fn main() {
+//! // format
+//! let mut buf = b"Hello World!".to_vec();
+//! let mut parity = vec![vec![0u8; 2]; 4];
+//! let slices = buf.chunks(2).collect::<Vec<_>>();
+//! raid8::format(&slices, &mut parity);
+//!
+//! // corrupt
+//! buf[0..8].fill(b'x');
+//!
+//! // repair
+//! let mut slices = buf.chunks_mut(2).collect::<Vec<_>>();
+//! raid8::repair(&mut slices, &mut parity, &[0, 1, 2, 3]).unwrap();
+//! assert_eq!(&buf, b"Hello World!");
+//! # }
+//! ```
+//!
+//! Note the scheme used for RAID 6 and RAID 7 doesn't generalize beyond 3
+//! parity blocks. If parity block `k` contained the sum of each data block `i`
+//! multiplied by `g^(i*k)`, the equations from an arbitrary subset of parity
+//! blocks would no longer be guaranteed to be linearly independent, and some
+//! combinations of failed data and parity blocks couldn't be repaired.
+//!
+//! Instead, with more than 3 parity blocks, data block `i` is multiplied by
+//! `y_i/(x_k + y_i)` in parity block `k`, where `x_k = k` and `y_i = parity+i`.
+//! Without the `y_i` numerator, this is a [Cauchy matrix][cauchy-matrix], which
+//! has the nice property that every square submatrix is invertible, so any
+//! combination of failed data and parity blocks can be repaired. Scaling each
+//! column by `y_i` doesn't change this, and keeps the first parity block a
+//! simple xor of the data blocks.
+//!
+//! The catch is that every `x_k` and `y_i` must be unique, so in `GF(256)`,
+//! `raid8` is limited to 252 blocks. As outlined in James S. Plank’s paper,
+//! [Note: Correction to the 1997 Tutorial on Reed-Solomon Coding][plank], the
+//! naive [Vandermonde matrix][vandermonde-matrix] construction is a common
+//! pitfall here.
+//!
+//! ## Streaming repair
+//!
+//! `repair` needs every block in memory at once, which isn't always practical
+//! when the blocks are entire disks. `repair_stream` instead pulls the surviving
+//! blocks through the [`BlockSource`](crate::raid::BlockSource) trait and pushes the rebuilt blocks through
+//! the [`BlockSink`](crate::raid::BlockSink) trait, a fixed-size window at a time, so memory usage only
+//! depends on the number of blocks:
+//!
+//! ``` rust
+//! # use ::gf256::raid::*;
+//! // format
+//! let buf = b"Hello World!".to_vec();
+//! let mut parity1 = vec![0u8; 4];
+//! let mut parity2 = vec![0u8; 4];
+//! let slices = buf.chunks(4).collect::<Vec<_>>();
+//! raid6::format(&slices, &mut parity1, &mut parity2);
+//!
+//! // lose two blocks, lost blocks are never read but still need a length
+//! let mut blocks = [&buf[0..4], &b"xxxx"[..], &b"xxxx"[..], &parity1[..], &parity2[..]];
+//!
+//! // rebuild into new blocks
+//! let mut rebuilt = [vec![0u8; 4], vec![0u8; 4]];
+//! raid6::repair_stream(&mut blocks, &mut rebuilt, &[1, 2]).unwrap();
+//! assert_eq!(&rebuilt[0], b"o Wo");
+//! assert_eq!(&rebuilt[1], b"rld!");
+//! ```
+//!
+//! Implementations are provided for slices and `Vec`s, but the traits are
+//! intended to be implemented for files, disks, network volumes, etc.
+//!
+//! Note `repair_stream` requires alloc for its window buffers.
 //!
-//! As outlined in James S. Plank’s paper, [Note: Correction to the 1997 Tutorial
-//! on Reed-Solomon Coding][plank], you can construct a modified [Vandermonde matrix
-//! ][vandermonde-matrix] that allows you to solve the linear system of equations for
-//! any number of parity blocks.
+//! If the blocks live on something that can be both read and written, such as
+//! files or disks, `format_devices` and `repair_devices` operate directly on an
+//! array of [`BlockDevice`](crate::raid::BlockDevice)s, repairing bad devices in place. [`MemBlockDevice`](crate::raid::MemBlockDevice)
+//! provides an in-memory implementation:
 //!
-//! The downside Plank's approach is that you need to store an array of unique constants
-//! for each block of data, for each parity block.
+//! ``` rust
+//! # use ::gf256::raid::*;
+//! let mut devices = b"Hello World!".chunks(4)
+//!     .map(|b| MemBlockDevice::new(b.to_vec()))
+//!     .chain([MemBlockDevice::new(vec![0u8; 4]), MemBlockDevice::new(vec![0u8; 4])])
+//!     .collect::<Vec<_>>();
+//! raid6::format_devices(&mut devices).unwrap();
+//!
+//! // corrupt
+//! devices[1].get_mut().fill(b'x');
+//! devices[2].get_mut().fill(b'x');
+//!
+//! // repair
+//! raid6::repair_devices(&mut devices, &[1, 2]).unwrap();
+//! assert_eq!(devices[1].get_ref(), b"o Wo");
+//! assert_eq!(devices[2].get_ref(), b"rld!");
+//! ```
+//!
+//! Rebuilding an entire disk can take a while, so `repair_stream_with_progress`
+//! and `repair_devices_with_progress` also accept a callback that is passed the
+//! current [`Progress`](crate::raid::Progress) after each window is written. Returning false from this
+//! callback cancels the repair.
+//!
+//! ## The write hole
+//!
+//! Updating a block in a RAID array requires writing both the data block and
+//! every parity block. If power is lost between these writes, the stripe is
+//! left inconsistent, and a later repair will quietly reconstruct garbage.
+//! This is known as the RAID "write hole".
+//!
+//! `update_devices` avoids this by first recording the new data and parity in
+//! a [`Journal`](crate::raid::Journal), only clearing the journal once every device has been written.
+//! After a crash, `recover_devices` replays any update left in the journal. The
+//! lower-level `prepare_update_devices` and `apply_update_devices` are also
+//! available if you need more control over the journaling.
+//!
+//! ## Parallel format/repair
+//!
+//! Computing the Q and R parity blocks is CPU-bound. If the `rayon` feature
+//! is enabled, `par_format` and `par_repair` split the blocks into stripe chunks
+//! and format/repair each chunk on rayon's thread pool. These take the same
+//! arguments as `format` and `repair`.
+//!
+//! Independently of rayon, for 8-bit fields, the inner multiply-accumulate
+//! loops, `parity += g^i * data`, use explicit SIMD instructions when available
+//! (`gfni`/`avx2`/`ssse3` on x86_64, `neon` on aarch64). Note these are selected
+//! at compile time, so you may need to enable the relevant target features,
+//! for example with `RUSTFLAGS="-C target-cpu=native"`.
+//!
+//! If you can't know the target CPU at compile time, `format_with` and
+//! `repair_with` take a [`Backend`](crate::raid::Backend), which picks the best SIMD [`Kernel`](crate::raid::Kernel) and a
+//! cache-friendly chunk size at runtime. With the `std` feature, this probes
+//! the CPU for instructions that weren't enabled at compile time:
+//!
+//! ``` rust
+//! # use ::gf256::raid::*;
+//! let backend = Backend::detect();
+//!
+//! let data = (0..64*1024).map(|x| x as u8).collect::<Vec<u8>>();
+//! let blocks = data.chunks(16*1024).collect::<Vec<_>>();
+//! let mut parity = vec![vec![0u8; 16*1024]; 2];
+//! raid6::format_with(&backend, &blocks, &mut parity);
+//!
+//! let mut expected1 = vec![0u8; 16*1024];
+//! let mut expected2 = vec![0u8; 16*1024];
+//! raid6::format(&blocks, &mut expected1, &mut expected2);
+//! assert_eq!(parity[0], expected1);
+//! assert_eq!(parity[1], expected2);
+//! ```
+//!
+//! ## Planning repairs
+//!
+//! Sometimes it's useful to know if a set of bad blocks can be repaired before
+//! attempting a repair, for example to report the health of an array, or to
+//! test which failures a given configuration can survive. `plan` takes the
+//! number of data blocks and the bad blocks, and returns a `RepairPlan`
+//! describing which parity blocks' equations will be used to solve for the bad
+//! data blocks, and which parity blocks will be regenerated:
+//!
+//! ``` rust
+//! # use gf256::raid::raid7;
+//! #
+//! // 4 data blocks, where data block 1 and p (block 4) are bad
+//! let plan = raid7::plan(4, &[1, 4]).unwrap();
+//! assert_eq!(plan.equations, [false, true, false]);
+//! assert_eq!(plan.regenerated, [true, false, false]);
+//!
+//! // but we can't survive 4 bad blocks
+//! assert!(raid7::plan(4, &[0, 1, 2, 3]).is_err());
+//! ```
+//!
+//! ## Degraded reads
+//!
+//! While a rebuild is pending, `read_degraded` and `read_degraded_into` can
+//! reconstruct a single bad block, or part of a bad block, on the fly, without
+//! modifying any blocks. `read_degraded_into` only needs a small window on the
+//! stack, so it's available even without alloc.
+//!
+//! Similarly, `repair_into` reconstructs every bad block directly into
+//! caller-provided buffers, without modifying the surviving blocks and without
+//! allocating, which is useful for rebuilding onto a replacement device.
+//!
+//! ## Checksums
+//!
+//! RAID-parity can only repair bad blocks it knows about. `scrub` can detect
+//! silent corruption, and sometimes locate it, but with the `crc` feature
+//! enabled, `checksum` and `repair_checksummed` can store a CRC for each block,
+//! using any CRC from the [`crc`](crate::crc) module, and use these to decide
+//! which blocks are bad before repairing.
+//!
+//! ## Const-generic arrays
+//!
+//! For embedded systems, where the geometry of the array is known at compile
+//! time, each RAID module also provides a `Raid<N, L>` type, which stores `N`
+//! data blocks of `L` symbols, and the module's parity blocks, in fixed-size
+//! arrays. This avoids slices of slices and never allocates:
+//!
+//! ``` rust
+//! # use ::gf256::raid::*;
+//! let mut array = raid6::Raid::new([*b"Hell", *b"o Wo", *b"rld!"]);
+//! array.update(1, 0, b"0 W0");
+//!
+//! // corrupt
+//! array.blocks_mut()[1] = *b"xxxx";
+//! array.blocks_mut()[2] = *b"xxxx";
+//!
+//! // repair
+//! array.repair(&[1, 2]).unwrap();
+//! assert_eq!(array.blocks(), &[*b"Hell", *b"0 W0", *b"rld!"]);
+//! ```
+//!
+//! ## Linux md compatibility
+//!
+//! With the default configuration, [`raid5`](crate::raid::raid5) and [`raid6`](crate::raid::raid6) produce the same
+//! parity as the Linux kernel's md driver. md's RAID 6 uses `GF(256)` with the
+//! polynomial `0x11d` and generator `2`, operates on bytes, and calculates Q as
+//! `Σ 2^i * d_i`, where `i` is the data block's index in the stripe, which is
+//! exactly the math described above.
+//!
+//! The `md` option on the `raid` macro makes this guarantee explicit, rejecting
+//! any options that would break compatibility. Combined with the [`layout`](crate::raid::layout)
+//! module, which provides md's left/right-(a)symmetric layouts, this can be
+//! used to validate or reconstruct the contents of an md array:
+//!
+//! ``` rust
+//! # pub use ::gf256::*;
+//! # use ::gf256::raid::raid;
+//! # use ::gf256::raid::layout::*;
+//! #[raid(parity=2, md)]
+//! mod md_raid6 {}
+//!
+//! # fn main() {
+//! // 4 members, md's default left-symmetric layout
+//! let geometry = Geometry::new(Layout::LeftSymmetric, 4, 2, 4);
+//! let mut members = vec![vec![0u8; 4]; 4];
+//!
+//! // write stripe 1
+//! let stripe = geometry.stripe(1).collect::<Vec<_>>();
+//! members[stripe[0]].copy_from_slice(b"Hell");
+//! members[stripe[1]].copy_from_slice(b"o Wo");
+//! let (p, q) = (stripe[2], stripe[3]);
+//! let mut parity = [vec![0u8; 4], vec![0u8; 4]];
+//! let datas = [&members[stripe[0]], &members[stripe[1]]];
+//! let [parity1, parity2] = &mut parity;
+//! md_raid6::format(&datas, parity1, parity2);
+//! members[p].copy_from_slice(&parity[0]);
+//! members[q].copy_from_slice(&parity[1]);
+//! # }
+//! ```
+//!
+//! ## Row-diagonal parity
+//!
+//! As an alternative to RAID 6, the [`rdp`](crate::raid::rdp) module provides row-diagonal
+//! parity, the scheme behind NetApp's RAID-DP. RDP can also repair any two bad
+//! blocks, but uses only xors, no finite-field multiplication. In exchange, all
+//! blocks must be the same length, a multiple of [`rdp::rows`](crate::raid::rdp::rows) bytes, and RDP
+//! is limited to 2 parity blocks.
+//!
+//! ## Rotating parity
+//!
+//! Storing parity on the same members in every stripe makes those members a
+//! bottleneck for writes. Most arrays rotate parity across the members instead,
+//! and the [`layout`](crate::raid::layout) module provides the standard left/right-(a)symmetric
+//! layouts for mapping logical offsets to members, and for finding the members
+//! that make up each stripe.
 //!
 //!
 //! [raid-wiki]: https://en.wikipedia.org/wiki/Standard_RAID_levels
@@ -987,9 +1304,18 @@
 //! [A051179]: https://oeis.org/A051179
 //! [leventhal-blog]: http://dtrace.org/blogs/ahl/2009/07/21/triple-parity-raid-z
 //! [plank]: http://web.eecs.utk.edu/~jplank/plank/papers/CS-03-504.pdf
+//! [cauchy-matrix]: https://en.wikipedia.org/wiki/Cauchy_matrix
 //! [raid-example]: https://github.com/geky/gf256/blob/master/examples/raid.rs
 
 
+use core::convert::Infallible;
+
+#[cfg(feature="alloc")]
+extern crate alloc;
+#[cfg(feature="alloc")]
+use alloc::vec::Vec;
+
+
 /// A macro for generating custom RAID-parity modules.
 ///
 /// ``` rust,ignore
@@ -1019,10 +1345,19 @@
 ///
 /// The `raid` macro accepts a number of configuration options:
 ///
-/// - `parity` - The number of parity blocks to use for redundancy.
+/// - `parity` - The number of parity blocks to use for redundancy. With more
+///   than 3 parity blocks, the parity blocks are passed as a slice.
 /// - `gf` - The finite-field we are implemented over, defaults to
 ///   [`gf256`](crate::gf256).
-/// - `u` - The unsigned type to operate on, defaults to [`u8`].
+/// - `u` - The unsigned type to operate on, defaults to the underlying
+///   unsigned type of `gf`.
+/// - `width` - Width of the default finite-field in bits, this can be 8, 16,
+///   32, or 64 and determines the maximum number of blocks. Defaults to 8,
+///   which limits RAID 6 and RAID 7 to 255 blocks. Can't be combined with `gf`.
+/// - `md` - Guarantee parity is compatible with Linux md's RAID 5/6, see
+///   [Linux md compatibility](crate::raid#linux-md-compatibility). This is
+///   already true for the default configuration, but `md` makes it explicit
+///   and rejects any options that would break compatibility.
 ///
 /// ``` rust,ignore
 /// # use ::gf256::*;
@@ -1058,6 +1393,418 @@
 pub use gf256_macros::raid;
 
 
+/// A source of symbols that can be read in arbitrary windows, see
+/// `repair_stream`
+pub trait BlockSource<U> {
+    /// The error returned when a read fails
+    type Error;
+
+    /// The length of the block in symbols
+    fn len(&self) -> usize;
+
+    /// Returns true if the block contains no symbols
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read `buf.len()` symbols starting at `off`
+    ///
+    /// `off+buf.len()` is always <= `len()`.
+    ///
+    fn read(&mut self, off: usize, buf: &mut [U]) -> Result<(), Self::Error>;
+}
+
+/// A sink of symbols that can be written in arbitrary windows, see
+/// `repair_stream`
+pub trait BlockSink<U> {
+    /// The error returned when a write fails
+    type Error;
+
+    /// Write `buf` starting at `off`
+    fn write(&mut self, off: usize, buf: &[U]) -> Result<(), Self::Error>;
+}
+
+impl<U: Copy> BlockSource<U> for &[U] {
+    type Error = Infallible;
+
+    fn len(&self) -> usize {
+        <[U]>::len(self)
+    }
+
+    fn read(&mut self, off: usize, buf: &mut [U]) -> Result<(), Infallible> {
+        buf.copy_from_slice(&self[off..off+buf.len()]);
+        Ok(())
+    }
+}
+
+impl<U: Copy> BlockSource<U> for &mut [U] {
+    type Error = Infallible;
+
+    fn len(&self) -> usize {
+        <[U]>::len(self)
+    }
+
+    fn read(&mut self, off: usize, buf: &mut [U]) -> Result<(), Infallible> {
+        buf.copy_from_slice(&self[off..off+buf.len()]);
+        Ok(())
+    }
+}
+
+impl<U: Copy> BlockSink<U> for &mut [U] {
+    type Error = Infallible;
+
+    fn write(&mut self, off: usize, buf: &[U]) -> Result<(), Infallible> {
+        self[off..off+buf.len()].copy_from_slice(buf);
+        Ok(())
+    }
+}
+
+#[cfg(feature="alloc")]
+impl<U: Copy> BlockSource<U> for Vec<U> {
+    type Error = Infallible;
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn read(&mut self, off: usize, buf: &mut [U]) -> Result<(), Infallible> {
+        buf.copy_from_slice(&self[off..off+buf.len()]);
+        Ok(())
+    }
+}
+
+/// Writing past the end of a `Vec` extends it with `U::default()`
+#[cfg(feature="alloc")]
+impl<U: Copy + Default> BlockSink<U> for Vec<U> {
+    type Error = Infallible;
+
+    fn write(&mut self, off: usize, buf: &[U]) -> Result<(), Infallible> {
+        if self.len() < off+buf.len() {
+            self.resize(off+buf.len(), U::default());
+        }
+        self[off..off+buf.len()].copy_from_slice(buf);
+        Ok(())
+    }
+}
+
+
+/// Progress of a long-running repair, passed to the progress callback of
+/// `repair_stream_with_progress` and `repair_devices_with_progress`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Progress {
+    /// The number of symbols of the current block that have been repaired
+    pub processed: usize,
+
+    /// The total number of symbols to repair in each block, the length of
+    /// the longest block
+    pub total: usize,
+
+    /// The index of the block currently being repaired
+    pub block: usize,
+}
+
+/// A readable and writable block device, see `format_devices` and
+/// `repair_devices`
+///
+/// This can be implemented for files, mmaps, network volumes, etc, to let
+/// the RAID-parity functions operate on them directly. Reads and writes are
+/// done in fixed-size windows, so the entire device never needs to be in
+/// memory.
+///
+pub trait BlockDevice<U> {
+    /// The error returned when a read or write fails
+    type Error;
+
+    /// The length of the device in symbols
+    fn len(&self) -> usize;
+
+    /// Returns true if the device contains no symbols
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read `buf.len()` symbols starting at `off`
+    ///
+    /// `off+buf.len()` is always <= `len()`.
+    ///
+    fn read_block(&mut self, off: usize, buf: &mut [U]) -> Result<(), Self::Error>;
+
+    /// Write `buf` starting at `off`
+    ///
+    /// `off+buf.len()` is always <= `len()`.
+    ///
+    fn write_block(&mut self, off: usize, buf: &[U]) -> Result<(), Self::Error>;
+}
+
+impl<U, D: BlockDevice<U> + ?Sized> BlockDevice<U> for &mut D {
+    type Error = D::Error;
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn read_block(&mut self, off: usize, buf: &mut [U]) -> Result<(), D::Error> {
+        (**self).read_block(off, buf)
+    }
+
+    fn write_block(&mut self, off: usize, buf: &[U]) -> Result<(), D::Error> {
+        (**self).write_block(off, buf)
+    }
+}
+
+/// An in-memory [`BlockDevice`] backed by anything that can be viewed as a
+/// slice, such as a `Vec`, array, or `&mut [u8]`
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let mut device = MemBlockDevice::new(b"Hello World!".to_vec());
+/// let mut buf = [0u8; 5];
+/// device.read_block(6, &mut buf[..]).unwrap();
+/// assert_eq!(&buf, b"World");
+/// ```
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemBlockDevice<B> {
+    buf: B,
+}
+
+impl<B> MemBlockDevice<B> {
+    /// Create a block device from an in-memory buffer
+    pub const fn new(buf: B) -> Self {
+        Self { buf }
+    }
+
+    /// Get a reference to the underlying buffer
+    pub fn get_ref(&self) -> &B {
+        &self.buf
+    }
+
+    /// Get a mutable reference to the underlying buffer
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.buf
+    }
+
+    /// Consume the block device, returning the underlying buffer
+    pub fn into_inner(self) -> B {
+        self.buf
+    }
+}
+
+impl<U: Copy, B: AsRef<[U]> + AsMut<[U]>> BlockDevice<U> for MemBlockDevice<B> {
+    type Error = Infallible;
+
+    fn len(&self) -> usize {
+        self.buf.as_ref().len()
+    }
+
+    fn read_block(&mut self, off: usize, buf: &mut [U]) -> Result<(), Infallible> {
+        buf.copy_from_slice(&self.buf.as_ref()[off..off+buf.len()]);
+        Ok(())
+    }
+
+    fn write_block(&mut self, off: usize, buf: &[U]) -> Result<(), Infallible> {
+        self.buf.as_mut()[off..off+buf.len()].copy_from_slice(buf);
+        Ok(())
+    }
+}
+
+
+/// A record of an in-progress update to a RAID array, see `update_devices`
+///
+/// This contains the new data and the new parity over the affected range,
+/// so applying it is idempotent.
+///
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UpdateIntent<U> {
+    /// Index of the data block being updated
+    pub block: usize,
+
+    /// Offset of the update in the block
+    pub off: usize,
+
+    /// The new data
+    pub data: Vec<U>,
+
+    /// The new parity for each parity block, over the same range as `data`
+    pub parity: Vec<Vec<U>>,
+}
+
+/// Durable storage for [`UpdateIntent`]s, see `update_devices`
+///
+/// This can be implemented for a reserved region of a disk, a separate log
+/// file, NVRAM, etc. For crash safety, `record` and `clear` must not return
+/// until the intent is durably written or removed.
+///
+#[cfg(feature="alloc")]
+pub trait Journal<U> {
+    /// The error returned when accessing the journal fails
+    type Error;
+
+    /// Durably record an intent before it is applied
+    fn record(&mut self, intent: &UpdateIntent<U>) -> Result<(), Self::Error>;
+
+    /// Return the recorded intent, if there is one
+    fn pending(&mut self) -> Result<Option<UpdateIntent<U>>, Self::Error>;
+
+    /// Durably clear the recorded intent after it is applied
+    fn clear(&mut self) -> Result<(), Self::Error>;
+}
+
+/// An in-memory [`Journal`]
+///
+/// This doesn't survive crashes, but is useful for testing, or when the
+/// journal is persisted by some other mechanism.
+///
+#[cfg(feature="alloc")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemJournal<U> {
+    intent: Option<UpdateIntent<U>>,
+}
+
+#[cfg(feature="alloc")]
+impl<U> MemJournal<U> {
+    /// Create an empty journal
+    pub const fn new() -> Self {
+        Self { intent: None }
+    }
+}
+
+#[cfg(feature="alloc")]
+impl<U> Default for MemJournal<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature="alloc")]
+impl<U: Clone> Journal<U> for MemJournal<U> {
+    type Error = Infallible;
+
+    fn record(&mut self, intent: &UpdateIntent<U>) -> Result<(), Infallible> {
+        self.intent = Some(intent.clone());
+        Ok(())
+    }
+
+    fn pending(&mut self) -> Result<Option<UpdateIntent<U>>, Infallible> {
+        Ok(self.intent.clone())
+    }
+
+    fn clear(&mut self) -> Result<(), Infallible> {
+        self.intent = None;
+        Ok(())
+    }
+}
+
+
+/// The SIMD kernels available to the RAID-parity functions, see [`Backend`]
+pub use crate::internal::simd::Kernel;
+
+/// A RAID-parity backend selected at runtime, see `format_with` and
+/// `repair_with`
+///
+/// This determines the SIMD kernel used for multiply-accumulate, which only
+/// applies to 8-bit fields, and the size of the chunks the blocks are
+/// processed in, which should be small enough to keep a chunk of each parity
+/// block in L1 cache while streaming through the data blocks.
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Backend {
+    kernel: Kernel,
+    chunk_size: usize,
+}
+
+impl Backend {
+    /// Chunk size to use if we can't find the size of the L1 data cache
+    const DEFAULT_CHUNK_SIZE: usize = 8*1024;
+
+    /// Create a backend with a specific kernel and chunk size, in bytes.
+    ///
+    /// Panics if the kernel is not available on the current CPU, or the
+    /// chunk size is zero.
+    ///
+    pub fn new(kernel: Kernel, chunk_size: usize) -> Backend {
+        assert!(kernel.is_available());
+        assert!(chunk_size > 0);
+        Backend {
+            kernel,
+            chunk_size,
+        }
+    }
+
+    /// Probe the current CPU for the best kernel and chunk size.
+    ///
+    /// With the `std` feature, this finds kernels that weren't enabled at
+    /// compile time, see [`Kernel::detect`]. The chunk size is a quarter of
+    /// the L1 data cache, if its size can be found.
+    ///
+    pub fn detect() -> Backend {
+        let chunk_size = match l1d_cache_size() {
+            Some(size) => (size/4).clamp(1024, 64*1024),
+            None => Self::DEFAULT_CHUNK_SIZE,
+        };
+
+        Backend::new(Kernel::detect(), chunk_size)
+    }
+
+    /// The kernel used for multiply-accumulate
+    pub fn kernel(&self) -> Kernel {
+        self.kernel
+    }
+
+    /// The size of the chunks blocks are processed in, in bytes
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+}
+
+impl Default for Backend {
+    /// The default backend is probed at runtime, see [`Backend::detect`]
+    fn default() -> Backend {
+        Backend::detect()
+    }
+}
+
+/// Find the size of the L1 data cache, if we can
+///
+/// On x86_64 we can ask cpuid for the cache parameters, leaf 4 on Intel and
+/// leaf 0x8000001d on AMD.
+///
+// older versions of Rust require unsafe for cpuid
+#[allow(unused_unsafe)]
+fn l1d_cache_size() -> Option<usize> {
+    #[cfg(target_arch="x86_64")]
+    {
+        use core::arch::x86_64::*;
+        for leaf in [0x0000_0004, 0x8000_001d] {
+            // is this leaf supported?
+            let max_leaf = unsafe { __cpuid(leaf & 0x8000_0000) }.eax;
+            if max_leaf < leaf {
+                continue;
+            }
+
+            for i in 0..16 {
+                let cache = unsafe { __cpuid_count(leaf, i) };
+                let type_ = cache.eax & 0x1f;
+                let level = (cache.eax >> 5) & 0x7;
+                if type_ == 0 {
+                    // no more caches
+                    break;
+                } else if type_ == 1 && level == 1 {
+                    let ways = ((cache.ebx >> 22) & 0x3ff) as usize + 1;
+                    let partitions = ((cache.ebx >> 12) & 0x3ff) as usize + 1;
+                    let line_size = (cache.ebx & 0xfff) as usize + 1;
+                    let sets = cache.ecx as usize + 1;
+                    return Some(ways*partitions*line_size*sets);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+
 // RAID-parity functions
 //
 
@@ -1070,6 +1817,16 @@ pub mod raid6 {}
 #[raid(parity=3)]
 pub mod raid7 {}
 
+// Row-diagonal parity, an xor-only alternative to raid6
+//
+
+pub mod rdp;
+
+// Rotating-parity layouts
+//
+
+pub mod layout;
+
 
 #[cfg(test)]
 mod test {
@@ -1078,6 +1835,7 @@ mod test {
 
     extern crate alloc;
     use alloc::vec::Vec;
+    use alloc::vec;
 
     #[test]
     fn raid5() {
@@ -1322,6 +2080,719 @@ mod test {
         }
     }
 
+    // more than 3 parity blocks
+    #[raid(parity=4)]
+    pub mod raid8 {}
+
+    #[raid(parity=5)]
+    pub mod raid9 {}
+
+    #[test]
+    fn raid8() {
+        let mut blocks = [
+            (80..90).collect::<Vec<u8>>(),
+            (20..30).collect::<Vec<u8>>(),
+            (30..40).collect::<Vec<u8>>(),
+            (40..50).collect::<Vec<u8>>(),
+            (50..60).collect::<Vec<u8>>(),
+        ];
+        let mut parity = [
+            (60..70).collect::<Vec<u8>>(),
+            (70..80).collect::<Vec<u8>>(),
+            (80..90).collect::<Vec<u8>>(),
+            (90..100).collect::<Vec<u8>>(),
+        ];
+
+        // format
+        raid8::format(&mut blocks, &mut parity);
+
+        // update
+        raid8::update(0, &mut blocks[0], &(10..20).collect::<Vec<u8>>(), &mut parity);
+        blocks[0].copy_from_slice(&(10..20).collect::<Vec<u8>>());
+        let expected = blocks.clone();
+
+        // try every combination of 4 bad blocks, including parity blocks
+        let n = blocks.len()+4;
+        for i in 0..n {
+            for j in i+1..n {
+                for k in j+1..n {
+                    for l in k+1..n {
+                        // clobber
+                        for x in [i, j, k, l] {
+                            if x < blocks.len() {
+                                blocks[x].fill(b'x');
+                            } else {
+                                parity[x-blocks.len()].fill(b'x');
+                            }
+                        }
+                        // repair
+                        raid8::repair(&mut blocks, &mut parity, &[i, j, k, l]).unwrap();
+                        assert_eq!(blocks, expected);
+                    }
+                }
+            }
+        }
+
+        // remove and add
+        raid8::remove(4, &blocks[4], &mut parity);
+        blocks[4].fill(0);
+        blocks[3].fill(b'x');
+        raid8::repair(&mut blocks, &mut parity, &[3]).unwrap();
+        assert_eq!(&blocks[3], &expected[3]);
+        raid8::add(4, &expected[4], &mut parity);
+        blocks[4].copy_from_slice(&expected[4]);
+
+        // too many bad blocks
+        assert_eq!(
+            raid8::repair(&mut blocks, &mut parity, &[0, 1, 2, 3, 4]),
+            Err(raid8::Error::TooManyBadBlocks)
+        );
+    }
+
+    #[test]
+    fn raid8_large() {
+        let mut blocks = Vec::new();
+        for i in 0..252 {
+            blocks.push(((i+1)*10..(i+2)*10).map(|x| x as u8).collect::<Vec<u8>>());
+        }
+        let mut parity = vec![vec![0u8; 10]; 4];
+
+        // format
+        raid8::format(&mut blocks, &mut parity);
+
+        for i in (0..252-3).step_by(7) {
+            // clobber
+            blocks[i+0].fill(b'x');
+            blocks[i+1].fill(b'x');
+            blocks[i+2].fill(b'x');
+            blocks[i+3].fill(b'x');
+            // repair
+            raid8::repair(&mut blocks, &mut parity, &[i+0, i+1, i+2, i+3]).unwrap();
+
+            for i in 0..252 {
+                assert_eq!(&blocks[i], &((i+1)*10..(i+2)*10).map(|x| x as u8).collect::<Vec<u8>>());
+            }
+        }
+    }
+
+    #[test]
+    fn raid9() {
+        let mut blocks = (0..8)
+            .map(|i| (i*10..(i+1)*10).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut parity = vec![vec![0u8; 10]; 5];
+
+        // format
+        raid9::format(&blocks, &mut parity);
+        let expected = blocks.clone();
+
+        for i in 0..blocks.len()-4 {
+            // clobber 4 data blocks and a parity block
+            for x in i..i+4 {
+                blocks[x].fill(b'x');
+            }
+            parity[i % 5].fill(b'x');
+            // repair
+            raid9::repair(&mut blocks, &mut parity, &[i, i+1, i+2, i+3, 8+(i % 5)]).unwrap();
+            assert_eq!(blocks, expected);
+        }
+    }
+
+    #[raid(gf=gf16, u=u8, parity=4)]
+    pub mod gf16_raid8 {}
+
+    #[test]
+    fn raid8_every_combination() {
+        // GF(16) is small enough to check every combination of bad blocks
+        // in the largest array the field supports
+        let n = 12;
+        let blocks = (0..n)
+            .map(|i| (0..10).map(|x| ((i*10 + x) % 16) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut parity = vec![vec![0u8; 10]; 4];
+        gf16_raid8::format(&blocks, &mut parity);
+        assert_eq!(gf16_raid8::plan(n+1, &[0]), Err(gf16_raid8::Error::TooManyBlocks));
+
+        for mask in 0u32..(1 << (n+4)) {
+            if mask.count_ones() > 4 {
+                continue;
+            }
+            let bad_blocks = (0..n+4)
+                .filter(|x| mask & (1 << x) != 0)
+                .collect::<Vec<_>>();
+
+            let mut blocks_ = blocks.clone();
+            let mut parity_ = parity.clone();
+            for x in &bad_blocks {
+                if *x < n { blocks_[*x].fill(0xf); }
+                else { parity_[*x-n].fill(0xf); }
+            }
+            gf16_raid8::repair(&mut blocks_, &mut parity_, &bad_blocks).unwrap();
+            assert_eq!(blocks_, blocks);
+            assert_eq!(parity_, parity);
+        }
+    }
+
+    #[test]
+    fn raid8_large_every_combination() {
+        // check every combination of bad blocks from a sample of the blocks,
+        // including the last data blocks and all parity blocks
+        let n = 252;
+        let blocks = (0..n)
+            .map(|i| (0..10).map(|x| (i*10 + x) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut parity = vec![vec![0u8; 10]; 4];
+        raid8::format(&blocks, &mut parity);
+        assert_eq!(raid8::plan(n+1, &[0]), Err(raid8::Error::TooManyBlocks));
+
+        let sample = [0, 1, 2, 63, 127, 128, 200, 248, 249, 250, 251, n, n+1, n+2, n+3];
+        for mask in 0u32..(1 << sample.len()) {
+            if mask.count_ones() > 4 {
+                continue;
+            }
+            let bad_blocks = (0..sample.len())
+                .filter(|x| mask & (1 << x) != 0)
+                .map(|x| sample[x])
+                .collect::<Vec<_>>();
+
+            let mut blocks_ = blocks.clone();
+            let mut parity_ = parity.clone();
+            for x in &bad_blocks {
+                if *x < n { blocks_[*x].fill(b'x'); }
+                else { parity_[*x-n].fill(b'x'); }
+            }
+            raid8::repair(&mut blocks_, &mut parity_, &bad_blocks).unwrap();
+            assert_eq!(blocks_, blocks);
+            assert_eq!(parity_, parity);
+        }
+    }
+
+    #[test]
+    fn raid_update_delta() {
+        let mut blocks = (0..8)
+            .map(|i| (i*10..(i+1)*10).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut p = vec![0u8; 10];
+        let mut q = vec![0u8; 10];
+        let mut r = vec![0u8; 10];
+        let mut parity = vec![vec![0u8; 10]; 4];
+        raid5::format(&blocks, &mut p);
+        raid7::format(&blocks, &mut vec![0u8; 10], &mut q, &mut r);
+        raid8::format(&blocks, &mut parity);
+
+        // small writes
+        for (j, off, new) in [(0, 0, &b"abc"[..]), (3, 5, &b"de"[..]), (7, 9, &b"f"[..]), (3, 6, &b"ghij"[..])] {
+            let delta = blocks[j][off..off+new.len()].iter()
+                .zip(new)
+                .map(|(a, b)| a ^ b)
+                .collect::<Vec<u8>>();
+            raid5::update_delta(j, off, &delta, &mut p);
+            raid7::update_delta(j, off, &delta, &mut vec![0u8; 10], &mut q, &mut r);
+            raid8::update_delta(j, off, &delta, &mut parity);
+            blocks[j][off..off+new.len()].copy_from_slice(new);
+        }
+        let expected = blocks.clone();
+
+        // parity should still be consistent
+        blocks[3].fill(b'x');
+        raid5::repair(&mut blocks, &mut p, &[3]).unwrap();
+        assert_eq!(blocks, expected);
+
+        let mut p = vec![0u8; 10];
+        blocks[3].fill(b'x');
+        blocks[7].fill(b'x');
+        raid7::repair(&mut blocks, &mut p, &mut q, &mut r, &[3, 7, 8]).unwrap();
+        assert_eq!(blocks, expected);
+
+        blocks[0].fill(b'x');
+        blocks[3].fill(b'x');
+        blocks[7].fill(b'x');
+        raid8::repair(&mut blocks, &mut parity, &[0, 3, 7]).unwrap();
+        assert_eq!(blocks, expected);
+    }
+
+    #[test]
+    fn raid_scrub() {
+        let mut blocks = (0..8)
+            .map(|i| (i*10..(i+1)*10).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut p = vec![0u8; 10];
+        let mut q = vec![0u8; 10];
+        let mut r = vec![0u8; 10];
+        let mut parity = vec![vec![0u8; 10]; 4];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        raid8::format(&blocks, &mut parity);
+        let expected = blocks.clone();
+
+        // consistent
+        let report = raid7::scrub(&blocks, &p, &q, &r);
+        assert!(report.is_consistent());
+        assert_eq!(report.bad_block, None);
+        assert!(raid8::scrub(&blocks, &parity).is_consistent());
+
+        // silent corruption in every block
+        for x in 0..blocks.len() {
+            blocks[x][3] ^= 0x12;
+            blocks[x][7] ^= 0x34;
+
+            let report = raid5::scrub(&blocks, &p);
+            assert_eq!(report.mismatches, 2);
+            assert_eq!(report.bad_block, None);
+            let report = raid6::scrub(&blocks, &p, &q);
+            assert_eq!(report.mismatches, 2);
+            assert_eq!(report.bad_block, Some(x));
+            let report = raid7::scrub(&blocks, &p, &q, &r);
+            assert_eq!(report.mismatches, 2);
+            assert_eq!(report.bad_block, Some(x));
+            let report = raid8::scrub(&blocks, &parity);
+            assert_eq!(report.mismatches, 2);
+            assert_eq!(report.bad_block, Some(x));
+
+            // repair
+            raid7::repair(&mut blocks, &mut p, &mut q, &mut r, &[x]).unwrap();
+            assert_eq!(blocks, expected);
+        }
+
+        // silent corruption in parity blocks
+        q[5] ^= 0x56;
+        let report = raid7::scrub(&blocks, &p, &q, &r);
+        assert_eq!(report.mismatches, 1);
+        assert_eq!(report.bad_block, Some(blocks.len()+1));
+        let bad_block = blocks.len()+1;
+        raid7::repair(&mut blocks, &mut p, &mut q, &mut r, &[bad_block]).unwrap();
+        assert!(raid7::scrub(&blocks, &p, &q, &r).is_consistent());
+
+        parity[3][0] ^= 0x78;
+        let report = raid8::scrub(&blocks, &parity);
+        assert_eq!(report.mismatches, 1);
+        assert_eq!(report.bad_block, Some(blocks.len()+3));
+
+        // multiple bad blocks can't be located with enough parity
+        blocks[1][2] ^= 0x12;
+        blocks[4][2] ^= 0x34;
+        let report = raid7::scrub(&blocks, &p, &q, &r);
+        assert_eq!(report.mismatches, 1);
+        assert_eq!(report.bad_block, None);
+
+        blocks[1][2] ^= 0x12;
+        blocks[4][2] ^= 0x34;
+        blocks[4][8] ^= 0x34;
+        let report = raid7::scrub(&blocks, &p, &q, &r);
+        assert_eq!(report.mismatches, 1);
+        assert_eq!(report.bad_block, Some(4));
+        blocks[1][5] ^= 0x12;
+        let report = raid7::scrub(&blocks, &p, &q, &r);
+        assert_eq!(report.mismatches, 2);
+        assert_eq!(report.bad_block, None);
+    }
+
+    #[test]
+    fn raid_ragged() {
+        let mut blocks = (0..8)
+            .map(|i| (i*10..(i+1)*10-i).map(|x| x as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        blocks.push(vec![]);
+        let mut p = vec![0u8; 10];
+        let mut q = vec![0u8; 10];
+        let mut r = vec![0u8; 10];
+        let mut parity = vec![vec![0u8; 10]; 4];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        raid8::format(&blocks, &mut parity);
+        let expected = blocks.clone();
+
+        // padding with zeros gives the same parity
+        let mut padded = blocks.clone();
+        for b in padded.iter_mut() {
+            b.resize(10, 0);
+        }
+        let mut p_ = vec![0u8; 10];
+        let mut q_ = vec![0u8; 10];
+        let mut r_ = vec![0u8; 10];
+        raid7::format(&padded, &mut p_, &mut q_, &mut r_);
+        assert_eq!((&p, &q, &r), (&p_, &q_, &r_));
+        assert!(raid7::scrub(&blocks, &p, &q, &r).is_consistent());
+        assert!(raid8::scrub(&blocks, &parity).is_consistent());
+
+        // repair combinations of short and long blocks
+        let n = blocks.len()+3;
+        for i in 0..n {
+            for j in i+1..n {
+                for k in j+1..n {
+                    for x in [i, j, k] {
+                        if x < blocks.len() { blocks[x].fill(b'x'); }
+                    }
+                    raid7::repair(&mut blocks, &mut p, &mut q, &mut r, &[i, j, k]).unwrap();
+                    assert_eq!(blocks, expected);
+                    assert_eq!((&p, &q, &r), (&p_, &q_, &r_));
+                }
+            }
+        }
+
+        for i in 0..blocks.len()-3 {
+            for x in i..i+4 {
+                blocks[x].fill(b'x');
+            }
+            raid8::repair(&mut blocks, &mut parity, &[i, i+1, i+2, i+3]).unwrap();
+            assert_eq!(blocks, expected);
+        }
+
+        blocks[7].fill(b'x');
+        raid5::repair(&mut blocks, &mut p, &[7]).unwrap();
+        assert_eq!(blocks, expected);
+    }
+
+    #[test]
+    fn raid_repair_stream() {
+        // large enough to need multiple windows
+        let blocks = (0..4)
+            .map(|i| (0..10000-i*1000).map(|x| (x*(i+1)) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut p = vec![0u8; 10000];
+        let mut q = vec![0u8; 10000];
+        let mut r = vec![0u8; 10000];
+        let mut parity = vec![vec![0u8; 10000]; 4];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        raid8::format(&blocks, &mut parity);
+
+        let all = blocks.iter()
+            .chain([&p, &q, &r])
+            .map(|b| &b[..])
+            .collect::<Vec<_>>();
+        let all8 = blocks.iter()
+            .chain(&parity)
+            .map(|b| &b[..])
+            .collect::<Vec<_>>();
+        let garbage = all8.iter()
+            .map(|b| vec![b'x'; b.len()])
+            .collect::<Vec<_>>();
+
+        let n = blocks.len();
+        let mut bad_sets = vec![];
+        for i in 0..n+3 {
+            bad_sets.push(vec![i]);
+            for j in i+1..n+3 {
+                bad_sets.push(vec![i, j]);
+                for k in j+1..n+3 {
+                    bad_sets.push(vec![i, j, k]);
+                }
+            }
+        }
+
+        for bad_blocks in &bad_sets {
+            let mut sources = all.iter().enumerate()
+                .map(|(x, b)| if bad_blocks.contains(&x) { &garbage[x][..] } else { *b })
+                .collect::<Vec<_>>();
+            let mut rebuilt = vec![vec![]; bad_blocks.len()];
+            if bad_blocks.len() <= 1 && bad_blocks.iter().all(|x| *x < n+1) {
+                raid5::repair_stream(&mut sources[..n+1], &mut rebuilt, bad_blocks).unwrap();
+                for (b, x) in rebuilt.iter().zip(bad_blocks) {
+                    assert_eq!(b, &all[*x]);
+                }
+            }
+
+            let mut rebuilt = vec![vec![]; bad_blocks.len()];
+            if bad_blocks.len() <= 2 && bad_blocks.iter().all(|x| *x < n+2) {
+                raid6::repair_stream(&mut sources[..n+2], &mut rebuilt, bad_blocks).unwrap();
+                for (b, x) in rebuilt.iter().zip(bad_blocks) {
+                    assert_eq!(b, &all[*x]);
+                }
+            }
+
+            let mut rebuilt = vec![vec![]; bad_blocks.len()];
+            raid7::repair_stream(&mut sources, &mut rebuilt, bad_blocks).unwrap();
+            for (b, x) in rebuilt.iter().zip(bad_blocks) {
+                assert_eq!(b, &all[*x]);
+            }
+
+            let mut sources = all8.iter().enumerate()
+                .map(|(x, b)| if bad_blocks.contains(&x) { &garbage[x][..] } else { *b })
+                .collect::<Vec<_>>();
+            let mut rebuilt = vec![vec![]; bad_blocks.len()];
+            raid8::repair_stream(&mut sources, &mut rebuilt, bad_blocks).unwrap();
+            for (b, x) in rebuilt.iter().zip(bad_blocks) {
+                assert_eq!(b, &all8[*x]);
+            }
+        }
+
+        // too many bad blocks
+        let mut sources = all.clone();
+        let mut rebuilt = vec![vec![]; 4];
+        assert_eq!(
+            raid7::repair_stream(&mut sources, &mut rebuilt, &[0, 1, 2, 3]),
+            Err(raid7::StreamError::Raid(raid7::Error::TooManyBadBlocks))
+        );
+    }
+
+    #[test]
+    fn raid_devices() {
+        // large enough to need multiple windows
+        let blocks = (0..4)
+            .map(|i| (0..10000-i*1000).map(|x| (x*(i+1)) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut p = vec![0u8; 10000];
+        let mut q = vec![0u8; 10000];
+        let mut r = vec![0u8; 10000];
+        let mut parity = vec![vec![0u8; 10000]; 4];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        raid8::format(&blocks, &mut parity);
+
+        // format over devices matches format over slices
+        let expected = blocks.iter().cloned()
+            .chain([p, q, r])
+            .collect::<Vec<_>>();
+        let mut devices = blocks.iter().cloned()
+            .chain([vec![0u8; 10000], vec![0u8; 10000], vec![0u8; 10000]])
+            .map(MemBlockDevice::new)
+            .collect::<Vec<_>>();
+        raid7::format_devices(&mut devices).unwrap();
+        for (d, b) in devices.iter().zip(&expected) {
+            assert_eq!(d.get_ref(), b);
+        }
+
+        let expected8 = blocks.iter().cloned()
+            .chain(parity)
+            .collect::<Vec<_>>();
+        let mut devices8 = blocks.iter().cloned()
+            .chain(vec![vec![0u8; 10000]; 4])
+            .map(MemBlockDevice::new)
+            .collect::<Vec<_>>();
+        raid8::format_devices(&mut devices8).unwrap();
+        for (d, b) in devices8.iter().zip(&expected8) {
+            assert_eq!(d.get_ref(), b);
+        }
+
+        // repair in place
+        let n = blocks.len();
+        for i in 0..n+3 {
+            for j in i+1..n+3 {
+                for k in j+1..n+3 {
+                    for x in [i, j, k] {
+                        devices[x].get_mut().fill(b'x');
+                        devices8[x].get_mut().fill(b'x');
+                    }
+                    raid7::repair_devices(&mut devices, &[i, j, k]).unwrap();
+                    raid8::repair_devices(&mut devices8, &[i, j, k]).unwrap();
+                    for (d, b) in devices.iter().zip(&expected) {
+                        assert_eq!(d.get_ref(), b);
+                    }
+                    for (d, b) in devices8.iter().zip(&expected8) {
+                        assert_eq!(d.get_ref(), b);
+                    }
+                }
+            }
+        }
+
+        // devices can also be borrowed
+        let mut borrowed = devices[..n+1].iter_mut().collect::<Vec<_>>();
+        borrowed[2].get_mut().fill(b'x');
+        raid5::repair_devices(&mut borrowed, &[2]).unwrap();
+        assert_eq!(devices[2].get_ref(), &expected[2]);
+
+        // too many bad blocks
+        assert_eq!(
+            raid7::repair_devices(&mut devices, &[0, 1, 2, 3]),
+            Err(raid7::StreamError::Raid(raid7::Error::TooManyBadBlocks))
+        );
+    }
+
+    #[cfg(feature="rayon")]
+    #[test]
+    fn raid_par() {
+        // large enough to split into several chunks, with ragged blocks
+        let mut x = 1u32;
+        let blocks = (0..5)
+            .map(|i| (0..300000-i*12345).map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                x as u8
+            }).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+
+        let mut p = vec![0u8; 300000];
+        let mut q = vec![0u8; 300000];
+        let mut r = vec![0u8; 300000];
+        let mut parity = vec![vec![0u8; 300000]; 4];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        raid8::format(&blocks, &mut parity);
+
+        // par_format matches format
+        let mut p_ = vec![0u8; 300000];
+        let mut q_ = vec![0u8; 300000];
+        let mut r_ = vec![0u8; 300000];
+        let mut parity_ = vec![vec![0u8; 300000]; 4];
+        raid5::par_format(&blocks, &mut p_);
+        assert_eq!(p_, p);
+        raid6::par_format(&blocks, &mut p_, &mut q_);
+        assert_eq!((&p_, &q_), (&p, &q));
+        raid7::par_format(&blocks, &mut p_, &mut q_, &mut r_);
+        assert_eq!((&p_, &q_, &r_), (&p, &q, &r));
+        raid8::par_format(&blocks, &mut parity_);
+        assert_eq!(parity_, parity);
+
+        // par_repair matches repair
+        let expected = blocks.clone();
+        let mut blocks = blocks;
+        let n = blocks.len();
+        for (i, j, k) in [(0, 1, 2), (1, 4, 5), (2, n, n+2), (n, n+1, n+2)] {
+            for x in [i, j, k] {
+                if x < n { blocks[x].fill(b'x'); }
+                if x == n+2 { r_.fill(b'x'); }
+            }
+            raid7::par_repair(&mut blocks, &mut p_, &mut q_, &mut r_, &[i, j, k]).unwrap();
+            assert_eq!(blocks, expected);
+            assert_eq!((&p_, &q_, &r_), (&p, &q, &r));
+
+            for x in [i, j, k] {
+                if x < n { blocks[x].fill(b'x'); }
+            }
+            raid8::par_repair(&mut blocks, &mut parity_, &[i, j, k, n+3]).unwrap();
+            assert_eq!(blocks, expected);
+            assert_eq!(parity_, parity);
+        }
+
+        blocks[3].fill(b'x');
+        raid5::par_repair(&mut blocks, &mut p_, &[3]).unwrap();
+        assert_eq!(blocks, expected);
+
+        blocks[0].fill(b'x');
+        blocks[4].fill(b'x');
+        raid6::par_repair(&mut blocks, &mut p_, &mut q_, &[0, 4]).unwrap();
+        assert_eq!(blocks, expected);
+
+        assert_eq!(
+            raid6::par_repair(&mut blocks, &mut p_, &mut q_, &[0, 1, 2]),
+            Err(raid6::Error::TooManyBadBlocks)
+        );
+    }
+
+    // RAID-parity over wider fields
+    #[raid(parity=2, width=16)]
+    pub mod raid6_16 {}
+    #[raid(parity=3, width=16)]
+    pub mod raid7_16 {}
+    #[raid(parity=4, width=16)]
+    pub mod raid8_16 {}
+
+    #[test]
+    fn raid_width16() {
+        let expected = (0..3000u32)
+            .map(|i| (0..8).map(|x| (i*8 + x) as u16).collect::<Vec<u16>>())
+            .collect::<Vec<_>>();
+        let mut blocks = expected.clone();
+        let mut p = vec![0u16; 8];
+        let mut q = vec![0u16; 8];
+        let mut r = vec![0u16; 8];
+        raid7_16::format(&blocks, &mut p, &mut q, &mut r);
+
+        let mut p_ = vec![0u16; 8];
+        let mut q_ = vec![0u16; 8];
+        raid6_16::format(&blocks, &mut p_, &mut q_);
+        assert_eq!((&p_, &q_), (&p, &q));
+
+        // repair blocks beyond what GF(256) could index
+        for bad_blocks in [&[2999][..], &[0, 2999], &[256, 1000, 2999]] {
+            for x in bad_blocks {
+                blocks[*x].fill(0xffff);
+            }
+            raid7_16::repair(&mut blocks, &mut p, &mut q, &mut r, bad_blocks).unwrap();
+            assert_eq!(blocks, expected);
+        }
+
+        blocks[777].fill(0);
+        blocks[2000].fill(0);
+        raid6_16::repair(&mut blocks, &mut p_, &mut q_, &[777, 2000]).unwrap();
+        assert_eq!(blocks, expected);
+
+        // scrub can also locate bad blocks beyond 255
+        blocks[1234][3] ^= 0x1234;
+        let report = raid7_16::scrub(&blocks, &p, &q, &r);
+        assert_eq!(report.mismatches, 1);
+        assert_eq!(report.bad_block, Some(1234));
+        blocks[1234][3] ^= 0x1234;
+
+        let mut parity = vec![vec![0u16; 8]; 4];
+        raid8_16::format(&blocks, &mut parity);
+        for x in [10, 500, 1500, 2500] {
+            blocks[x].fill(0xffff);
+        }
+        raid8_16::repair(&mut blocks, &mut parity, &[10, 500, 1500, 2500]).unwrap();
+        assert_eq!(blocks, expected);
+    }
+
+    #[test]
+    fn raid_journal() {
+        let data = (0..40).collect::<Vec<u8>>();
+        let mut devices = data.chunks(10)
+            .map(|b| MemBlockDevice::new(b.to_vec()))
+            .chain(vec![MemBlockDevice::new(vec![0u8; 10]); 3])
+            .collect::<Vec<_>>();
+        raid7::format_devices(&mut devices).unwrap();
+
+        // journaled update
+        let mut journal = MemJournal::new();
+        raid7::update_devices(&mut devices, &mut journal, 2, 3, &[0xaa, 0xbb, 0xcc]).unwrap();
+        assert_eq!(journal.pending(), Ok(None));
+        assert_eq!(devices[2].get_ref(), &[20, 21, 22, 0xaa, 0xbb, 0xcc, 26, 27, 28, 29]);
+
+        let blocks = devices[..4].iter().map(|d| d.get_ref().clone()).collect::<Vec<_>>();
+        let mut p = vec![0u8; 10];
+        let mut q = vec![0u8; 10];
+        let mut r = vec![0u8; 10];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        assert_eq!(devices[4].get_ref(), &p);
+        assert_eq!(devices[5].get_ref(), &q);
+        assert_eq!(devices[6].get_ref(), &r);
+        let expected = devices.clone();
+
+        // crash after writing the data but before writing the parity
+        let intent = raid7::prepare_update_devices(&mut devices, 0, 5, &[1, 2, 3, 4, 5]).unwrap();
+        journal.record(&intent).unwrap();
+        devices[0].write_block(5, &[1u8, 2, 3, 4, 5][..]).unwrap();
+        assert!(!raid7::scrub(
+            &devices[..4].iter().map(|d| d.get_ref()).collect::<Vec<_>>(),
+            devices[4].get_ref(),
+            devices[5].get_ref(),
+            devices[6].get_ref(),
+        ).is_consistent());
+
+        // recover
+        assert_eq!(raid7::recover_devices(&mut devices, &mut journal), Ok(true));
+        assert_eq!(journal.pending(), Ok(None));
+        assert!(raid7::scrub(
+            &devices[..4].iter().map(|d| d.get_ref()).collect::<Vec<_>>(),
+            devices[4].get_ref(),
+            devices[5].get_ref(),
+            devices[6].get_ref(),
+        ).is_consistent());
+
+        // replaying is idempotent
+        raid7::apply_update_devices(&mut devices, &intent).unwrap();
+        raid7::apply_update_devices(&mut devices, &intent).unwrap();
+        assert_eq!(devices[0].get_ref(), &[0, 1, 2, 3, 4, 1, 2, 3, 4, 5]);
+        assert_eq!(raid7::recover_devices(&mut devices, &mut journal), Ok(false));
+
+        // and we can still repair
+        let mut repaired = devices.clone();
+        for x in [0, 2, 5] {
+            repaired[x].get_mut().fill(b'x');
+        }
+        raid7::repair_devices(&mut repaired, &[0, 2, 5]).unwrap();
+        assert_eq!(repaired, devices);
+        assert_ne!(devices, expected);
+
+        // N-parity
+        let mut devices = data.chunks(10)
+            .map(|b| MemBlockDevice::new(b.to_vec()))
+            .chain(vec![MemBlockDevice::new(vec![0u8; 10]); 4])
+            .collect::<Vec<_>>();
+        raid8::format_devices(&mut devices).unwrap();
+        raid8::update_devices(&mut devices, &mut journal, 3, 0, &[9; 10]).unwrap();
+        let blocks = devices[..4].iter().map(|d| d.get_ref()).collect::<Vec<_>>();
+        let parity = devices[4..].iter().map(|d| d.get_ref()).collect::<Vec<_>>();
+        assert!(raid8::scrub(&blocks, &parity).is_consistent());
+    }
+
     // why do we have this option?
     #[raid(parity=0)]
     pub mod raid0 {}
@@ -1681,4 +3152,746 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn raid_rdp() {
+        for n in 1..8 {
+            for c in [1, 5] {
+                let len = rdp::rows(n)*c;
+                let blocks = (0..n)
+                    .map(|i| (0..len).map(|j| (i*len+j) as u8).collect::<Vec<u8>>())
+                    .collect::<Vec<_>>();
+                let mut p = vec![0u8; len];
+                let mut q = vec![0u8; len];
+                rdp::format(&blocks, &mut p, &mut q);
+
+                for i in 0..n+2 {
+                    for j in i..n+2 {
+                        let mut blocks_ = blocks.clone();
+                        let mut p_ = p.clone();
+                        let mut q_ = q.clone();
+
+                        // clobber
+                        for x in [i, j] {
+                            if x < n { blocks_[x].fill(b'x'); }
+                            else if x == n { p_.fill(b'x'); }
+                            else { q_.fill(b'x'); }
+                        }
+
+                        // repair
+                        let bad_blocks = if i == j { &[i][..] } else { &[i, j][..] };
+                        rdp::repair(&mut blocks_, &mut p_, &mut q_, bad_blocks).unwrap();
+                        assert_eq!(blocks_, blocks);
+                        assert_eq!(p_, p);
+                        assert_eq!(q_, q);
+                    }
+                }
+
+                // too many bad blocks?
+                let mut blocks_ = blocks.clone();
+                assert_eq!(
+                    rdp::repair(&mut blocks_, &mut p, &mut q, &[0, n, n+1]),
+                    Err(rdp::Error::TooManyBadBlocks)
+                );
+
+                // duplicate bad blocks?
+                let mut blocks_ = blocks.clone();
+                let mut p_ = p.clone();
+                let mut q_ = q.clone();
+                blocks_[0].fill(b'x');
+                rdp::repair(&mut blocks_, &mut p_, &mut q_, &[0, 0]).unwrap();
+                assert_eq!(blocks_, blocks);
+                assert_eq!(
+                    rdp::repair(&mut blocks_, &mut p_, &mut q_, &[0, 0, n, n, n+1]),
+                    Err(rdp::Error::TooManyBadBlocks)
+                );
+
+                // bad block out of range?
+                assert_eq!(
+                    rdp::repair(&mut blocks_, &mut p_, &mut q_, &[n+2]),
+                    Err(rdp::Error::BadBlockOutOfRange)
+                );
+
+                // mismatched lengths?
+                assert_eq!(
+                    rdp::repair(&mut blocks_, &mut p_, &mut q_[..len-1], &[0]),
+                    Err(rdp::Error::LengthMismatch)
+                );
+                assert_eq!(
+                    rdp::repair(
+                        &mut blocks_.iter_mut().map(|b| &mut b[..len-1]).collect::<Vec<_>>(),
+                        &mut p_[..len-1],
+                        &mut q_[..len-1],
+                        &[0]
+                    ),
+                    Err(rdp::Error::LengthMismatch)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn raid_plan() {
+        let n = 5;
+        let blocks = (0..n)
+            .map(|i| (i*10..(i+1)*10).map(|x| x as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut p = vec![0u8; 10];
+        let mut q = vec![0u8; 10];
+        let mut r = vec![0u8; 10];
+        let mut parity = vec![vec![0u8; 10]; 4];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        raid8::format(&blocks, &mut parity);
+
+        // specific plans
+        let plan = raid7::plan(n, &[0, 1, n]).unwrap();
+        assert_eq!(plan.bad_datas, 2);
+        assert_eq!(plan.equations, [false, true, true]);
+        assert_eq!(plan.regenerated, [true, false, false]);
+        let plan = raid7::plan(n, &[2, n+1, 2]).unwrap();
+        assert_eq!(plan.bad_datas, 1);
+        assert_eq!(plan.equations, [true, false, false]);
+        assert_eq!(plan.regenerated, [false, true, false]);
+        assert_eq!(raid5::plan(n, &[]).unwrap().equations, [false]);
+        assert_eq!(raid5::plan(n, &[0, 1]), Err(raid5::Error::TooManyBadBlocks));
+
+        // plans should agree with repair for every combination of bad blocks
+        for mask in 0u32..(1 << (n+4)) {
+            let bad_blocks = (0..n+4)
+                .filter(|x| mask & (1 << x) != 0)
+                .collect::<Vec<_>>();
+
+            if bad_blocks.iter().all(|x| *x < n+3) {
+                let plan = raid7::plan(n, &bad_blocks);
+                assert_eq!(plan.is_ok(), bad_blocks.len() <= 3);
+                if let Ok(plan) = plan {
+                    let bad_datas = bad_blocks.iter().filter(|x| **x < n).count();
+                    assert_eq!(plan.bad_datas, bad_datas);
+                    assert_eq!(plan.equations.iter().filter(|e| **e).count(), bad_datas);
+                    for k in 0..3 {
+                        assert_eq!(plan.regenerated[k], bad_blocks.contains(&(n+k)));
+                        assert!(!(plan.equations[k] && plan.regenerated[k]));
+                    }
+
+                    let mut blocks_ = blocks.clone();
+                    let (mut p_, mut q_, mut r_) = (p.clone(), q.clone(), r.clone());
+                    for x in &bad_blocks {
+                        if *x < n { blocks_[*x].fill(b'x'); }
+                    }
+                    raid7::repair(&mut blocks_, &mut p_, &mut q_, &mut r_, &bad_blocks).unwrap();
+                    assert_eq!(blocks_, blocks);
+                }
+            }
+
+            let plan = raid8::plan(n, &bad_blocks);
+            assert_eq!(plan.is_ok(), bad_blocks.len() <= 4);
+            if let Ok(plan) = plan {
+                let bad_datas = bad_blocks.iter().filter(|x| **x < n).count();
+                assert_eq!(plan.bad_datas, bad_datas);
+                assert_eq!(plan.equations.iter().filter(|e| **e).count(), bad_datas);
+                for k in 0..4 {
+                    assert_eq!(plan.regenerated[k], bad_blocks.contains(&(n+k)));
+                    assert!(!(plan.equations[k] && plan.regenerated[k]));
+                }
+
+                let mut blocks_ = blocks.clone();
+                let mut parity_ = parity.clone();
+                for x in &bad_blocks {
+                    if *x < n { blocks_[*x].fill(b'x'); }
+                    else { parity_[*x-n].fill(b'x'); }
+                }
+                raid8::repair(&mut blocks_, &mut parity_, &bad_blocks).unwrap();
+                assert_eq!(blocks_, blocks);
+                assert_eq!(parity_, parity);
+            }
+        }
+    }
+
+    #[test]
+    fn raid_degraded() {
+        let n = 5;
+        let blocks = (0..n)
+            .map(|i| (0..200).map(|j| (i*200+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut p = vec![0u8; 200];
+        let mut q = vec![0u8; 200];
+        let mut r = vec![0u8; 200];
+        let mut parity = vec![vec![0u8; 200]; 4];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        raid8::format(&blocks, &mut parity);
+
+        let mut blocks7 = blocks.clone();
+        blocks7.extend([p, q, r]);
+        let mut blocks8 = blocks.clone();
+        blocks8.extend(parity);
+
+        for index in 0..n+3 {
+            for i in 0..n+3 {
+                for j in 0..n+3 {
+                    // clobber
+                    let mut blocks7_ = blocks7.clone();
+                    for x in [index, i, j] {
+                        blocks7_[x].fill(b'x');
+                    }
+
+                    // read
+                    let block = raid7::read_degraded(&blocks7_, &[i, j], index).unwrap();
+                    assert_eq!(block, blocks7[index]);
+
+                    let mut buf = [0u8; 100];
+                    raid7::read_degraded_into(&blocks7_, &[i, j], index, 77, &mut buf).unwrap();
+                    assert_eq!(&buf[..], &blocks7[index][77..177]);
+                }
+            }
+        }
+
+        for index in 0..n+4 {
+            for i in 0..n+4 {
+                for j in 0..n+4 {
+                    for k in 0..n+4 {
+                        // clobber
+                        let mut blocks8_ = blocks8.clone();
+                        for x in [index, i, j, k] {
+                            blocks8_[x].fill(b'x');
+                        }
+
+                        // read
+                        let block = raid8::read_degraded(&blocks8_, &[i, j, k], index).unwrap();
+                        assert_eq!(block, blocks8[index]);
+                    }
+                }
+            }
+        }
+
+        // too many bad blocks?
+        assert_eq!(
+            raid7::read_degraded(&blocks7, &[0, 1, 2], 3),
+            Err(raid7::Error::TooManyBadBlocks)
+        );
+    }
+
+    #[test]
+    fn raid_progress() {
+        let blocks = (0..4)
+            .map(|i| (0..10000).map(|j| (i*10000+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut devices = blocks.iter()
+            .cloned()
+            .chain([vec![0u8; 10000], vec![0u8; 10000]])
+            .map(MemBlockDevice::new)
+            .collect::<Vec<_>>();
+        raid6::format_devices(&mut devices).unwrap();
+        let expected = devices.clone();
+
+        // clobber
+        devices[1] = MemBlockDevice::new(vec![b'x'; 10000]);
+        devices[4] = MemBlockDevice::new(vec![b'x'; 10000]);
+
+        // repair, keeping track of progress
+        let mut calls = Vec::new();
+        raid6::repair_devices_with_progress(&mut devices, &[1, 4], |progress| {
+            calls.push(progress);
+            true
+        }).unwrap();
+        assert_eq!(devices, expected);
+        assert_eq!(calls.len(), 6);
+        assert_eq!(calls[0], Progress { processed: 4096, total: 10000, block: 1 });
+        assert_eq!(calls[1], Progress { processed: 4096, total: 10000, block: 4 });
+        assert_eq!(calls[5], Progress { processed: 10000, total: 10000, block: 4 });
+
+        // cancel after the first window
+        devices[1] = MemBlockDevice::new(vec![b'x'; 10000]);
+        let mut calls = 0;
+        assert_eq!(
+            raid6::repair_devices_with_progress(&mut devices, &[1], |_| {
+                calls += 1;
+                calls < 1
+            }),
+            Err(raid6::StreamError::Cancelled)
+        );
+        assert_eq!(calls, 1);
+        assert_eq!(&devices[1].get_ref()[..4096], &blocks[1][..4096]);
+        assert_eq!(&devices[1].get_ref()[4096..], &[b'x'; 10000-4096][..]);
+
+        // streaming
+        let mut blocks_ = expected.iter()
+            .map(|d| &d.get_ref()[..])
+            .collect::<Vec<_>>();
+        let mut rebuilt = [Vec::new()];
+        let mut calls = 0;
+        raid6::repair_stream_with_progress(&mut blocks_, &mut rebuilt, &[2], |progress| {
+            calls += 1;
+            assert_eq!(progress.block, 2);
+            true
+        }).unwrap();
+        assert_eq!(calls, 3);
+        assert_eq!(&rebuilt[0], &blocks[2]);
+    }
+
+    #[test]
+    fn raid_layout() {
+        use layout::*;
+
+        // compare against the tables in the layout docs, P = usize::MAX
+        let tables = [
+            (Layout::LeftAsymmetric,  [[0, 1, 2, !0], [3, 4, !0, 5], [6, !0, 7, 8], [!0, 9, 10, 11]]),
+            (Layout::LeftSymmetric,   [[0, 1, 2, !0], [4, 5, !0, 3], [8, !0, 6, 7], [!0, 9, 10, 11]]),
+            (Layout::RightAsymmetric, [[!0, 0, 1, 2], [3, !0, 4, 5], [6, 7, !0, 8], [9, 10, 11, !0]]),
+            (Layout::RightSymmetric,  [[!0, 0, 1, 2], [5, !0, 3, 4], [7, 8, !0, 6], [9, 10, 11, !0]]),
+        ];
+        for (layout, table) in tables {
+            let geometry = Geometry::new(layout, 4, 1, 1);
+            for (stripe, row) in table.iter().enumerate() {
+                for (member, chunk) in row.iter().enumerate() {
+                    let loc = Location { member, offset: stripe };
+                    if *chunk == !0 {
+                        assert_eq!(geometry.parity_member(stripe, 0), member);
+                        assert_eq!(geometry.logical(loc), None);
+                    } else {
+                        assert_eq!(geometry.locate(*chunk), loc);
+                        assert_eq!(geometry.logical(loc), Some(*chunk));
+                    }
+                }
+            }
+        }
+
+        // Linux md's RAID 6 layouts store Q after P
+        let geometry = Geometry::new(Layout::LeftAsymmetric, 5, 2, 1);
+        assert_eq!(geometry.stripe(0).collect::<Vec<_>>(), [1, 2, 3, 4, 0]);
+        assert_eq!(geometry.stripe(1).collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+        assert_eq!(geometry.stripe(2).collect::<Vec<_>>(), [0, 1, 4, 2, 3]);
+
+        // every member should appear exactly once in each stripe, and
+        // locate/logical/block should agree
+        for layout in [
+            Layout::LeftAsymmetric,
+            Layout::LeftSymmetric,
+            Layout::RightAsymmetric,
+            Layout::RightSymmetric,
+        ] {
+            for members in 1..8 {
+                for parity in 0..members {
+                    let geometry = Geometry::new(layout, members, parity, 3);
+                    for stripe in 0..2*members {
+                        let mut order = geometry.stripe(stripe).collect::<Vec<_>>();
+                        for (i, member) in order.iter().enumerate() {
+                            assert_eq!(geometry.block(stripe, *member), i);
+                        }
+                        order.sort_unstable();
+                        assert_eq!(order, (0..members).collect::<Vec<_>>());
+                    }
+
+                    for off in 0..100 {
+                        assert_eq!(geometry.logical(geometry.locate(off)), Some(off));
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(feature="crc")]
+    #[test]
+    fn raid_checksummed() {
+        use crate::crc::Crc32cDigest;
+
+        let mut blocks = (0..8)
+            .map(|i| (i*10..(i+1)*10).collect::<Vec<u8>>())
+            .chain((0..3).map(|_| vec![0u8; 10]))
+            .collect::<Vec<_>>();
+        let (datas, parity) = blocks.split_at_mut(8);
+        let (p, parity) = parity.split_at_mut(1);
+        let (q, r) = parity.split_at_mut(1);
+        raid7::format(datas, &mut p[0], &mut q[0], &mut r[0]);
+        let mut checksums = [0u32; 11];
+        raid7::checksum::<Crc32cDigest, _>(&blocks, &mut checksums);
+        let expected = blocks.clone();
+
+        // nothing to repair
+        assert_eq!(raid7::repair_checksummed::<Crc32cDigest, _>(&mut blocks, &checksums), Ok(0));
+
+        // silently corrupt up to 3 blocks, including parity
+        for i in 0..blocks.len() {
+            for j in i+1..blocks.len() {
+                for k in j+1..blocks.len() {
+                    blocks[i][3] ^= 0x01;
+                    blocks[j][0] ^= 0x10;
+                    blocks[k][9] ^= 0xff;
+                    assert_eq!(raid7::repair_checksummed::<Crc32cDigest, _>(&mut blocks, &checksums), Ok(3));
+                    assert_eq!(blocks, expected);
+                }
+            }
+        }
+
+        // too many bad blocks?
+        for i in 0..4 {
+            blocks[i][0] ^= 0x01;
+        }
+        assert_eq!(
+            raid7::repair_checksummed::<Crc32cDigest, _>(&mut blocks, &checksums),
+            Err(raid7::Error::TooManyBadBlocks)
+        );
+    }
+
+    #[test]
+    fn raid_const() {
+        let blocks = [
+            [80u8, 81, 82, 83, 84, 85],
+            [20, 21, 22, 23, 24, 25],
+            [30, 31, 32, 33, 34, 35],
+            [40, 41, 42, 43, 44, 45],
+        ];
+        let mut array = raid7::Raid::new(blocks);
+
+        // parity should match format
+        let mut p = [0u8; 6];
+        let mut q = [0u8; 6];
+        let mut r = [0u8; 6];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        assert_eq!(array.parity(), &[p, q, r]);
+
+        // update
+        array.update(0, 2, &[10, 11]);
+        let mut expected = array.clone();
+        expected.format();
+        assert_eq!(array, expected);
+        assert_eq!(array.blocks()[0], [80, 81, 10, 11, 84, 85]);
+
+        // repair up to 3 blocks
+        for i in 0..4+3 {
+            for j in i+1..4+3 {
+                for k in j+1..4+3 {
+                    for x in [i, j, k] {
+                        if x < 4 {
+                            array.blocks_mut()[x] = [b'x'; 6];
+                        } else {
+                            array.parity_mut()[x-4] = [b'x'; 6];
+                        }
+                    }
+
+                    array.repair(&[i, j, k]).unwrap();
+                    assert_eq!(array, expected);
+                }
+            }
+        }
+
+        // more than 3 parity blocks
+        let mut array = raid8::Raid::new(blocks);
+        array.blocks_mut()[0] = [b'x'; 6];
+        array.blocks_mut()[3] = [b'x'; 6];
+        array.parity_mut()[1] = [b'x'; 6];
+        array.parity_mut()[3] = [b'x'; 6];
+        array.repair(&[0, 3, 5, 7]).unwrap();
+        let (blocks_, _) = array.into_parts();
+        assert_eq!(blocks_, blocks);
+    }
+
+    #[raid(parity=2, md)]
+    pub mod raid6_md {}
+
+    #[test]
+    fn raid_md() {
+        // reference implementation of md's gen_syndrome, using Horner's
+        // method to multiply by 2 in GF(256) with the polynomial 0x11d
+        fn gen_syndrome(blocks: &[Vec<u8>], p: &mut [u8], q: &mut [u8]) {
+            for i in 0..p.len() {
+                let mut wp = 0u8;
+                let mut wq = 0u8;
+                for b in blocks.iter().rev() {
+                    wp ^= b[i];
+                    wq = (wq << 1) ^ if wq & 0x80 != 0 { 0x1d } else { 0 };
+                    wq ^= b[i];
+                }
+                p[i] = wp;
+                q[i] = wq;
+            }
+        }
+
+        let blocks = (0..20)
+            .map(|i| (0..64).map(|j| (i*37 + j*101) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut p = vec![0u8; 64];
+        let mut q = vec![0u8; 64];
+        gen_syndrome(&blocks, &mut p, &mut q);
+
+        let mut p_ = vec![0u8; 64];
+        let mut q_ = vec![0u8; 64];
+        raid6::format(&blocks, &mut p_, &mut q_);
+        assert_eq!(p_, p);
+        assert_eq!(q_, q);
+
+        let mut p_ = vec![0u8; 64];
+        let mut q_ = vec![0u8; 64];
+        raid6_md::format(&blocks, &mut p_, &mut q_);
+        assert_eq!(p_, p);
+        assert_eq!(q_, q);
+
+        // and we should be able to repair md's parity
+        let mut blocks_ = blocks.clone();
+        blocks_[3].fill(0);
+        blocks_[17].fill(0);
+        raid6_md::repair(&mut blocks_, &mut p, &mut q, &[3, 17]).unwrap();
+        assert_eq!(blocks_, blocks);
+    }
+
+    #[test]
+    fn raid_errors() {
+        let mut blocks = (0..4)
+            .map(|i| (i*10..(i+1)*10).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut p = vec![0u8; 10];
+        let mut q = vec![0u8; 10];
+        let mut r = vec![0u8; 10];
+        let mut parity = vec![vec![0u8; 10]; 4];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        raid8::format(&blocks, &mut parity);
+
+        // too many bad blocks
+        assert_eq!(
+            raid7::repair(&mut blocks, &mut p, &mut q, &mut r, &[0, 1, 2, 3]),
+            Err(raid7::Error::TooManyBadBlocks)
+        );
+
+        // bad block out of range
+        assert_eq!(
+            raid7::repair(&mut blocks, &mut p, &mut q, &mut r, &[7]),
+            Err(raid7::Error::BadBlockOutOfRange)
+        );
+        assert_eq!(
+            raid8::repair(&mut blocks, &mut parity, &[8]),
+            Err(raid8::Error::BadBlockOutOfRange)
+        );
+        assert_eq!(raid7::plan(4, &[7]), Err(raid7::Error::BadBlockOutOfRange));
+        assert_eq!(
+            raid5::read_degraded(&[&blocks[0], &blocks[1], &p], &[], 3),
+            Err(raid5::Error::BadBlockOutOfRange)
+        );
+
+        // mismatched lengths
+        assert_eq!(
+            raid7::repair(&mut blocks, &mut p, &mut q, &mut r[..9], &[0]),
+            Err(raid7::Error::LengthMismatch)
+        );
+        assert_eq!(
+            raid8::repair(&mut blocks, &mut parity[..3], &[0]),
+            Err(raid8::Error::LengthMismatch)
+        );
+        blocks[2].push(0);
+        assert_eq!(
+            raid7::repair(&mut blocks, &mut p, &mut q, &mut r, &[0]),
+            Err(raid7::Error::LengthMismatch)
+        );
+        blocks[2].pop();
+        let mut sinks = [Vec::new()];
+        assert_eq!(
+            raid7::repair_stream(&mut [&blocks[0][..], &p[..], &q[..], &r[..]], &mut sinks, &[0, 1]),
+            Err(raid7::StreamError::Raid(raid7::Error::LengthMismatch))
+        );
+
+        #[cfg(feature="rayon")] {
+            assert_eq!(
+                raid7::par_repair(&mut blocks, &mut p, &mut q, &mut r, &[7]),
+                Err(raid7::Error::BadBlockOutOfRange)
+            );
+            assert_eq!(
+                raid7::par_repair(&mut blocks, &mut p, &mut q, &mut r[..9], &[0]),
+                Err(raid7::Error::LengthMismatch)
+            );
+            assert_eq!(
+                raid8::par_repair(&mut blocks, &mut parity[..3], &[0]),
+                Err(raid8::Error::LengthMismatch)
+            );
+            blocks[2].push(0);
+            assert_eq!(
+                raid7::par_repair(&mut blocks, &mut p, &mut q, &mut r, &[0]),
+                Err(raid7::Error::LengthMismatch)
+            );
+            blocks[2].pop();
+        }
+
+        // devices
+        let mut devices = blocks.iter()
+            .chain([&p, &q, &r])
+            .map(|b| MemBlockDevice::new(b.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            raid7::format_devices(&mut devices[..3]),
+            Err(raid7::StreamError::Raid(raid7::Error::LengthMismatch))
+        );
+        devices[2].get_mut().push(0);
+        assert_eq!(
+            raid7::format_devices(&mut devices),
+            Err(raid7::StreamError::Raid(raid7::Error::LengthMismatch))
+        );
+        devices[2].get_mut().pop();
+        assert_eq!(
+            raid7::prepare_update_devices(&mut devices, 4, 0, &[1]),
+            Err(raid7::StreamError::Raid(raid7::Error::BadBlockOutOfRange))
+        );
+        assert_eq!(
+            raid7::prepare_update_devices(&mut devices, 0, 8, &[1, 2, 3]),
+            Err(raid7::StreamError::Raid(raid7::Error::LengthMismatch))
+        );
+        assert_eq!(
+            raid7::prepare_update_devices(&mut devices, 0, usize::MAX, &[1]),
+            Err(raid7::StreamError::Raid(raid7::Error::LengthMismatch))
+        );
+        let mut intent = raid7::prepare_update_devices(&mut devices, 0, 8, &[1, 2]).unwrap();
+        intent.parity.pop();
+        assert_eq!(
+            raid7::apply_update_devices(&mut devices, &intent),
+            Err(raid7::StreamError::Raid(raid7::Error::LengthMismatch))
+        );
+        let mut intent = raid7::prepare_update_devices(&mut devices, 0, 8, &[1, 2]).unwrap();
+        intent.block = 4;
+        assert_eq!(
+            raid7::apply_update_devices(&mut devices, &intent),
+            Err(raid7::StreamError::Raid(raid7::Error::BadBlockOutOfRange))
+        );
+        intent.block = 0;
+        intent.off = 9;
+        assert_eq!(
+            raid7::apply_update_devices(&mut devices, &intent),
+            Err(raid7::StreamError::Raid(raid7::Error::LengthMismatch))
+        );
+        for (d, b) in devices.iter().zip(blocks.iter().chain([&p, &q, &r])) {
+            assert_eq!(d.get_ref(), b);
+        }
+
+        // too many blocks for the field
+        assert_eq!(raid6::plan(256, &[0]), Err(raid6::Error::TooManyBlocks));
+        assert!(raid5::plan(256, &[0]).is_ok());
+        let mut devices = (0..258)
+            .map(|_| MemBlockDevice::new(vec![0u8; 1]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            raid6::format_devices(&mut devices),
+            Err(raid6::StreamError::Raid(raid6::Error::TooManyBlocks))
+        );
+        assert_eq!(
+            raid6::prepare_update_devices(&mut devices, 0, 0, &[1]),
+            Err(raid6::StreamError::Raid(raid6::Error::TooManyBlocks))
+        );
+
+        // none of this should have clobbered anything
+        let mut blocks_ = blocks.clone();
+        raid7::repair(&mut blocks_, &mut p, &mut q, &mut r, &[0, 1, 2]).unwrap();
+        assert_eq!(blocks_, blocks);
+    }
+    #[test]
+    fn raid_repair_into() {
+        let n = 5;
+        let blocks = (0..n)
+            .map(|i| (0..200).map(|j| (i*200+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut p = vec![0u8; 200];
+        let mut q = vec![0u8; 200];
+        let mut r = vec![0u8; 200];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+
+        let mut blocks7 = blocks.clone();
+        blocks7.extend([p, q, r]);
+
+        for i in 0..n+3 {
+            for j in 0..n+3 {
+                for k in 0..n+3 {
+                    if i == j || i == k || j == k {
+                        continue;
+                    }
+
+                    // clobber
+                    let mut blocks7_ = blocks7.clone();
+                    for x in [i, j, k] {
+                        blocks7_[x].fill(b'x');
+                    }
+
+                    // repair
+                    let mut outputs = [[0u8; 200]; 3];
+                    raid7::repair_into(&blocks7_, &[i, j, k], &mut outputs).unwrap();
+                    for (x, output) in [i, j, k].iter().zip(&outputs) {
+                        assert_eq!(&output[..], &blocks7[*x][..]);
+                    }
+                }
+            }
+        }
+
+        // shorter outputs only reconstruct a prefix
+        let mut short = [0u8; 100];
+        let mut long = [0u8; 200];
+        raid7::repair_into(&blocks7, &[1, 6], &mut [&mut short[..], &mut long[..]]).unwrap();
+        assert_eq!(&short[..], &blocks7[1][..100]);
+        assert_eq!(&long[..], &blocks7[6][..]);
+
+        // errors?
+        let mut outputs = [[0u8; 200]; 4];
+        assert_eq!(
+            raid7::repair_into(&blocks7, &[0, 1, 2, 3], &mut outputs),
+            Err(raid7::Error::TooManyBadBlocks)
+        );
+        assert_eq!(
+            raid7::repair_into(&blocks7, &[0, 1], &mut outputs),
+            Err(raid7::Error::LengthMismatch)
+        );
+    }
+
+    #[test]
+    fn raid_backend() {
+        let kernels = [Kernel::Table, Kernel::Ssse3, Kernel::Avx2, Kernel::Gfni, Kernel::Neon];
+        let mut backends = vec![Backend::detect()];
+        for kernel in kernels.iter().filter(|k| k.is_available()) {
+            for chunk_size in [1, 7, 4096] {
+                backends.push(Backend::new(*kernel, chunk_size));
+            }
+        }
+
+        // blocks of different lengths
+        let blocks = (0..5)
+            .map(|i| (0..200-i*10).map(|j| (i*200+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut p = vec![0u8; 200];
+        let mut q = vec![0u8; 200];
+        let mut r = vec![0u8; 200];
+        let mut expected8 = vec![vec![0u8; 200]; 4];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        raid8::format(&blocks, &mut expected8);
+        let expected7 = vec![p, q, r];
+
+        for backend in &backends {
+            let mut parity7 = vec![vec![0u8; 200]; 3];
+            let mut parity8 = vec![vec![0u8; 200]; 4];
+            raid7::format_with(backend, &blocks, &mut parity7);
+            raid8::format_with(backend, &blocks, &mut parity8);
+            assert_eq!(parity7, expected7);
+            assert_eq!(parity8, expected8);
+
+            for i in 0..5+3 {
+                for j in 0..5+3 {
+                    for k in 0..5+3 {
+                        // clobber
+                        let mut blocks_ = blocks.clone();
+                        let mut parity7_ = parity7.clone();
+                        for x in [i, j, k] {
+                            if x < 5 {
+                                blocks_[x].fill(b'x');
+                            } else {
+                                parity7_[x-5].fill(b'x');
+                            }
+                        }
+
+                        // repair
+                        raid7::repair_with(backend, &mut blocks_, &mut parity7_, &[i, j, k]).unwrap();
+                        assert_eq!(blocks_, blocks);
+                        assert_eq!(parity7_, expected7);
+                    }
+                }
+            }
+
+            // too many bad blocks?
+            let mut blocks_ = blocks.clone();
+            assert_eq!(
+                raid7::repair_with(backend, &mut blocks_, &mut parity7, &[0, 1, 2, 3]),
+                Err(raid7::Error::TooManyBadBlocks)
+            );
+        }
+    }
+
 }
@@ -0,0 +1,270 @@
+//! Distributed-parity layouts.
+//!
+//! The RAID-parity functions operate on a single stripe of blocks, but a
+//! real array usually rotates which members store parity from stripe to
+//! stripe, so parity writes are spread evenly across every member. This
+//! module maps logical offsets to physical members/offsets using the
+//! standard left/right-(a)symmetric layouts, the same layouts used by
+//! Linux's md driver.
+//!
+//! ``` rust
+//! # use ::gf256::raid::layout::*;
+//! // a RAID 6 array with 5 members and 4-byte chunks
+//! let geometry = Geometry::new(Layout::LeftSymmetric, 5, 2, 4);
+//!
+//! // stripe 0 stores P and Q on members 4 and 0
+//! assert_eq!(geometry.parity_member(0, 0), 4);
+//! assert_eq!(geometry.parity_member(0, 1), 0);
+//! // stripe 1 stores P and Q on members 3 and 4
+//! assert_eq!(geometry.parity_member(1, 0), 3);
+//! assert_eq!(geometry.parity_member(1, 1), 4);
+//!
+//! // logical offset 13 is in the second stripe, at offset 5 of member 0
+//! assert_eq!(geometry.locate(13), Location { member: 0, offset: 5 });
+//! assert_eq!(geometry.logical(Location { member: 0, offset: 5 }), Some(13));
+//! ```
+//!
+//! The four layouts differ in which direction parity rotates, and in where
+//! the data chunks start in each stripe:
+//!
+//! ``` text
+//! left-asymmetric    left-symmetric     right-asymmetric   right-symmetric
+//!  0  1  2  P         0  1  2  P         P  0  1  2         P  0  1  2
+//!  3  4  P  5         4  5  P  3         3  P  4  5         5  P  3  4
+//!  6  P  7  8         8  P  6  7         6  7  P  8         7  8  P  6
+//!  P  9 10 11         P  9 10 11         9 10 11  P         9 10 11  P
+//! ```
+//!
+//! With more than one parity block, the parity blocks are stored on
+//! consecutive members, wrapping around at the end of the stripe.
+//!
+
+/// Standard layouts for rotating parity across the members of an array
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Layout {
+    /// Parity rotates from the last member to the first, data fills the
+    /// remaining members in order
+    LeftAsymmetric,
+
+    /// Parity rotates from the last member to the first, data starts on
+    /// the member after the parity, wrapping around
+    LeftSymmetric,
+
+    /// Parity rotates from the first member to the last, data fills the
+    /// remaining members in order
+    RightAsymmetric,
+
+    /// Parity rotates from the first member to the last, data starts on
+    /// the member after the parity, wrapping around
+    RightSymmetric,
+}
+
+impl Default for Layout {
+    /// Left-symmetric is the default layout in Linux's md driver
+    fn default() -> Layout {
+        Layout::LeftSymmetric
+    }
+}
+
+/// A physical location in an array, see [`Geometry::locate`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Location {
+    /// The index of the member
+    pub member: usize,
+
+    /// The offset in the member, in symbols
+    pub offset: usize,
+}
+
+/// The geometry of an array using a rotating-parity [`Layout`]
+///
+/// Each member is split into chunks of `chunk_size` symbols, and stripe `s`
+/// is made up of chunk `s` of every member.
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Geometry {
+    layout: Layout,
+    members: usize,
+    parity: usize,
+    chunk_size: usize,
+}
+
+impl Geometry {
+    /// Create a new geometry with `members` members, `parity` of which store
+    /// parity in each stripe, and chunks of `chunk_size` symbols.
+    pub const fn new(
+        layout: Layout,
+        members: usize,
+        parity: usize,
+        chunk_size: usize
+    ) -> Geometry {
+        assert!(parity < members);
+        assert!(chunk_size > 0);
+        Geometry {
+            layout,
+            members,
+            parity,
+            chunk_size,
+        }
+    }
+
+    /// The layout of the array
+    pub const fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// The total number of members in the array
+    pub const fn members(&self) -> usize {
+        self.members
+    }
+
+    /// The number of parity chunks in each stripe
+    pub const fn parity(&self) -> usize {
+        self.parity
+    }
+
+    /// The number of data chunks in each stripe
+    pub const fn data(&self) -> usize {
+        self.members - self.parity
+    }
+
+    /// The size of each chunk, in symbols
+    pub const fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// The member storing the first parity chunk in a stripe
+    fn first_parity(&self, stripe: usize) -> usize {
+        match self.layout {
+            Layout::LeftAsymmetric | Layout::LeftSymmetric => {
+                self.members-1 - stripe % self.members
+            }
+            Layout::RightAsymmetric | Layout::RightSymmetric => {
+                stripe % self.members
+            }
+        }
+    }
+
+    /// The member storing parity chunk `k` in a stripe.
+    ///
+    /// This is the member that should be passed as parity block `k` to the
+    /// RAID-parity functions.
+    ///
+    pub fn parity_member(&self, stripe: usize, k: usize) -> usize {
+        assert!(k < self.parity);
+        (self.first_parity(stripe) + k) % self.members
+    }
+
+    /// The member storing data chunk `j` in a stripe.
+    ///
+    /// This is the member that should be passed as data block `j` to the
+    /// RAID-parity functions.
+    ///
+    pub fn data_member(&self, stripe: usize, j: usize) -> usize {
+        assert!(j < self.data());
+        let p = self.first_parity(stripe);
+        match self.layout {
+            Layout::LeftAsymmetric | Layout::RightAsymmetric => {
+                // skip the parity members, which may wrap around
+                let wrapped = (p + self.parity).saturating_sub(self.members);
+                if j + wrapped < p {
+                    j + wrapped
+                } else {
+                    j + wrapped + self.parity
+                }
+            }
+            Layout::LeftSymmetric | Layout::RightSymmetric => {
+                (p + self.parity + j) % self.members
+            }
+        }
+    }
+
+    /// The block index of a member in a stripe, using the same indices as
+    /// the RAID-parity functions, with the parity blocks indexed after the
+    /// data blocks.
+    ///
+    /// This is useful for mapping failed members to `bad_blocks`.
+    ///
+    /// ``` rust
+    /// # use ::gf256::raid::layout::*;
+    /// let geometry = Geometry::new(Layout::LeftSymmetric, 5, 2, 4);
+    ///
+    /// // member 4 is P in stripe 0, but data block 0 in stripe 2
+    /// assert_eq!(geometry.block(0, 4), 3);
+    /// assert_eq!(geometry.block(2, 4), 0);
+    /// ```
+    ///
+    pub fn block(&self, stripe: usize, member: usize) -> usize {
+        assert!(member < self.members);
+        let p = self.first_parity(stripe);
+        let rel = (member + self.members - p) % self.members;
+        if rel < self.parity {
+            return self.data() + rel;
+        }
+
+        match self.layout {
+            Layout::LeftAsymmetric | Layout::RightAsymmetric => {
+                // skip the parity members, which may wrap around
+                let wrapped = (p + self.parity).saturating_sub(self.members);
+                if member < p {
+                    member - wrapped
+                } else {
+                    member - wrapped - self.parity
+                }
+            }
+            Layout::LeftSymmetric | Layout::RightSymmetric => {
+                rel - self.parity
+            }
+        }
+    }
+
+    /// The members making up a stripe, in the order expected by the
+    /// RAID-parity functions, data blocks followed by parity blocks.
+    ///
+    /// ``` rust
+    /// # use ::gf256::raid::layout::*;
+    /// let geometry = Geometry::new(Layout::LeftSymmetric, 5, 2, 4);
+    /// assert_eq!(geometry.stripe(1).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    /// assert_eq!(geometry.stripe(2).collect::<Vec<_>>(), vec![4, 0, 1, 2, 3]);
+    /// ```
+    ///
+    pub fn stripe(&self, stripe: usize) -> impl Iterator<Item=usize> + '_ {
+        (0..self.members).map(move |i| {
+            if i < self.data() {
+                self.data_member(stripe, i)
+            } else {
+                self.parity_member(stripe, i - self.data())
+            }
+        })
+    }
+
+    /// Find the physical location of a logical offset, in symbols.
+    ///
+    /// Logical offsets only count data, so a logical offset always maps to
+    /// a data chunk.
+    ///
+    pub fn locate(&self, off: usize) -> Location {
+        let chunk = off / self.chunk_size;
+        let stripe = chunk / self.data();
+        Location {
+            member: self.data_member(stripe, chunk % self.data()),
+            offset: stripe*self.chunk_size + off % self.chunk_size,
+        }
+    }
+
+    /// Find the logical offset of a physical location, the inverse of
+    /// [`locate`](Geometry::locate).
+    ///
+    /// Returns None if the location stores parity.
+    ///
+    pub fn logical(&self, loc: Location) -> Option<usize> {
+        let stripe = loc.offset / self.chunk_size;
+        let j = self.block(stripe, loc.member);
+        if j >= self.data() {
+            return None;
+        }
+
+        Some((stripe*self.data() + j)*self.chunk_size
+            + loc.offset % self.chunk_size)
+    }
+}
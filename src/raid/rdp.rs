@@ -0,0 +1,389 @@
+//! Row-diagonal parity (RDP), an XOR-only alternative to RAID 6.
+//!
+//! RDP, described by Peter Corbett et al. in [Row-Diagonal Parity for Double
+//! Disk Failure Correction][rdp] and used in NetApp's RAID-DP, provides the
+//! same guarantees as [`raid6`](super::raid6), the repair of any two bad
+//! blocks, but uses only xor operations. This can be significantly faster on
+//! targets without carry-less multiplication or wide SIMD instructions.
+//!
+//! ``` rust
+//! # use ::gf256::raid::rdp;
+//! #
+//! // format, blocks must be a multiple of rdp::rows(n) bytes
+//! let mut buf = b"Hello World!".to_vec();
+//! assert_eq!(rdp::rows(3), 4);
+//! let mut parity1 = vec![0u8; 4];
+//! let mut parity2 = vec![0u8; 4];
+//! let slices = buf.chunks(4).collect::<Vec<_>>();
+//! rdp::format(&slices, &mut parity1, &mut parity2);
+//!
+//! // corrupt
+//! buf[0..8].fill(b'x');
+//!
+//! // repair
+//! let mut slices = buf.chunks_mut(4).collect::<Vec<_>>();
+//! rdp::repair(&mut slices, &mut parity1, &mut parity2, &[0, 1]).unwrap();
+//! assert_eq!(&buf, b"Hello World!");
+//! ```
+//!
+//! ## How does RDP work?
+//!
+//! RDP arranges the array as a grid, with the blocks as columns and each block
+//! split into `p-1` rows, where `p` is a prime larger than the number of data
+//! blocks. Missing data blocks, up to `p-1`, are treated as zeros.
+//!
+//! The first parity block is the xor of each row, the same as RAID 5. The second
+//! parity block is the xor of each diagonal, where cell `(r, d)`, in row `r` of
+//! block `d`, is on diagonal `(r+d) mod p`. The diagonals include the first
+//! parity block, and there are `p` diagonals, but we only store `p-1` of them.
+//!
+//! With one bad block, we can repair each row with the row parity. With two
+//! bad blocks, each diagonal skips exactly one block, so we can find a diagonal
+//! with only one unknown cell. Repairing that cell makes another row solvable,
+//! which makes another diagonal solvable, and so on. Because `p` is prime,
+//! these chains, starting from the two diagonals that skip each of the bad
+//! blocks, cover every cell.
+//!
+//! ## Limitations
+//!
+//! RDP trades the finite-field math of RAID 6 for layout constraints:
+//!
+//! - Blocks must be a multiple of [`rows(n)`](rows) bytes, where `n` is the
+//!   number of data blocks.
+//! - All blocks must be the same length.
+//! - RDP only supports 2 parity blocks, and can't be extended with `update`
+//!   as cheaply as RAID 6, so you may want to reformat when blocks are added.
+//!
+//! [rdp]: https://www.usenix.org/legacy/events/fast04/tech/corbett/corbett.pdf
+//!
+
+use core::fmt;
+use crate::p::slice_xor;
+
+
+/// Error codes for RDP arrays
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// RDP can fail to decode if there are more than 2 bad-blocks
+    TooManyBadBlocks,
+
+    /// A bad block's index is out of range, bad blocks must be less than
+    /// the number of data blocks plus 2
+    BadBlockOutOfRange,
+
+    /// The length of the blocks don't match, all blocks must be the same
+    /// length and a multiple of [`rows(n)`](rows) bytes
+    LengthMismatch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooManyBadBlocks => write!(f, "Too many bad-blocks to repair"),
+            Error::BadBlockOutOfRange => write!(f, "Bad-block out of range"),
+            Error::LengthMismatch => write!(f, "Block lengths don't match"),
+        }
+    }
+}
+
+/// Find the prime that defines an array with `n` data blocks
+fn prime(n: usize) -> usize {
+    let mut p = core::cmp::max(n+1, 3);
+    while (2..p).take_while(|x| x*x <= p).any(|x| p.is_multiple_of(x)) {
+        p += 1;
+    }
+    p
+}
+
+/// The number of rows each block is split into for `n` data blocks.
+///
+/// Blocks must be a multiple of this many bytes.
+///
+/// ``` rust
+/// # use ::gf256::raid::rdp;
+/// assert_eq!(rdp::rows(1), 2);
+/// assert_eq!(rdp::rows(4), 4);
+/// assert_eq!(rdp::rows(5), 6);
+/// ```
+///
+pub fn rows(n: usize) -> usize {
+    prime(n) - 1
+}
+
+/// The shape of the grid, `p` columns, with each block split into `p-1`
+/// rows of `c` byte cells
+#[derive(Debug, Clone, Copy)]
+struct Grid {
+    p: usize,
+    c: usize,
+}
+
+/// Recover a cell in block t as the xor of a set of other cells, `init` is
+/// either the cell's diagonal parity, or None if this is a row equation
+///
+/// Blocks >= n and < p-1 are implicitly zero, block p-1 is the row parity.
+///
+fn recover<B: AsMut<[u8]>>(
+    blocks: &mut [B],
+    rp: &mut [u8],
+    init: Option<&[u8]>,
+    (t, tr): (usize, usize),
+    cells: impl Iterator<Item=(usize, usize)>,
+    Grid { p, c }: Grid,
+) {
+    let n = blocks.len();
+    if t == p-1 {
+        let target = &mut rp[tr*c..(tr+1)*c];
+        match init {
+            Some(init) => target.copy_from_slice(init),
+            None => target.fill(0),
+        }
+
+        for (d, r) in cells {
+            if d < n {
+                slice_xor(target, &blocks[d].as_mut()[r*c..(r+1)*c]);
+            }
+        }
+    } else {
+        let (before, after) = blocks.split_at_mut(t);
+        let (target, after) = after.split_first_mut().unwrap();
+        let target = &mut target.as_mut()[tr*c..(tr+1)*c];
+        match init {
+            Some(init) => target.copy_from_slice(init),
+            None => target.fill(0),
+        }
+
+        for (d, r) in cells {
+            if d == p-1 {
+                slice_xor(target, &rp[r*c..(r+1)*c]);
+            } else if d < t {
+                slice_xor(target, &before[d].as_mut()[r*c..(r+1)*c]);
+            } else if d > t && d < n {
+                slice_xor(target, &after[d-t-1].as_mut()[r*c..(r+1)*c]);
+            }
+        }
+    }
+}
+
+/// Recover cell (r, t) using row r
+fn recover_row<B: AsMut<[u8]>>(
+    blocks: &mut [B],
+    rp: &mut [u8],
+    t: usize,
+    r: usize,
+    grid: Grid,
+) {
+    let cells = (0..grid.p).filter(|d| *d != t).map(|d| (d, r));
+    recover(blocks, rp, None, (t, r), cells, grid);
+}
+
+/// Recover the cell in block t using diagonal i
+fn recover_diagonal<B: AsMut<[u8]>>(
+    blocks: &mut [B],
+    rp: &mut [u8],
+    dp: &[u8],
+    t: usize,
+    i: usize,
+    grid: Grid,
+) {
+    let Grid { p, c } = grid;
+    let cells = (0..p)
+        .filter(|d| *d != t)
+        .map(|d| (d, (i+p-d) % p))
+        .filter(|(_, r)| *r != p-1);
+    recover(blocks, rp, Some(&dp[i*c..(i+1)*c]), (t, (i+p-t) % p), cells, grid);
+}
+
+/// Regenerate the diagonal parity from the data blocks and row parity
+fn format_diagonals<B: AsMut<[u8]>>(
+    blocks: &mut [B],
+    rp: &[u8],
+    dp: &mut [u8],
+    Grid { p, c }: Grid,
+) {
+    dp.fill(0);
+    for i in 0..p-1 {
+        let dp = &mut dp[i*c..(i+1)*c];
+        for d in 0..p {
+            let r = (i+p-d) % p;
+            if r == p-1 {
+                continue;
+            }
+
+            if d < blocks.len() {
+                slice_xor(dp, &blocks[d].as_mut()[r*c..(r+1)*c]);
+            } else if d == p-1 {
+                slice_xor(dp, &rp[r*c..(r+1)*c]);
+            }
+        }
+    }
+}
+
+/// Follow a chain of diagonals and rows, starting with the diagonal that
+/// skips block a, alternating between repairing block b with a diagonal and
+/// block a with a row, until we hit the diagonal we don't store
+fn recover_chain<B: AsMut<[u8]>>(
+    blocks: &mut [B],
+    rp: &mut [u8],
+    dp: &[u8],
+    a: usize,
+    b: usize,
+    grid: Grid,
+) {
+    let p = grid.p;
+    let mut i = (a+p-1) % p;
+    while i != p-1 {
+        let r = (i+p-b) % p;
+        recover_diagonal(blocks, rp, dp, b, i, grid);
+        recover_row(blocks, rp, a, r, grid);
+
+        // next diagonal passes through the cell we just repaired
+        i = (r+a) % p;
+    }
+}
+
+/// Format blocks as an RDP array.
+///
+/// This writes the row parity to `p` and the diagonal parity to `q`. All
+/// blocks must be the same length, and a multiple of [`rows(n)`](rows)
+/// bytes, where `n` is the number of data blocks.
+///
+/// ``` rust
+/// # use ::gf256::raid::rdp;
+/// let data = b"Hello World!";
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// let datas = data.chunks(4).collect::<Vec<_>>();
+/// rdp::format(&datas, &mut parity1, &mut parity2);
+///
+/// assert_eq!(&parity1, b"\x55\x29\x5f\x22");
+/// assert_eq!(&parity2, b"\x40\x55\x1c\x57");
+/// ```
+///
+pub fn format<B: AsRef<[u8]>>(blocks: &[B], p: &mut [u8], q: &mut [u8]) {
+    assert!(!blocks.is_empty());
+    let len = p.len();
+    let prime = prime(blocks.len());
+    assert!(len.is_multiple_of(prime-1));
+    assert!(q.len() == len);
+    assert!(blocks.iter().all(|b| b.as_ref().len() == len));
+    let c = len / (prime-1);
+
+    // row parity
+    p.fill(0);
+    for b in blocks {
+        slice_xor(p, b.as_ref());
+    }
+
+    // diagonal parity, note the diagonals include the row parity
+    q.fill(0);
+    for i in 0..prime-1 {
+        let q = &mut q[i*c..(i+1)*c];
+        for d in 0..prime {
+            let r = (i+prime-d) % prime;
+            if r == prime-1 {
+                continue;
+            }
+
+            if d < blocks.len() {
+                slice_xor(q, &blocks[d].as_ref()[r*c..(r+1)*c]);
+            } else if d == prime-1 {
+                slice_xor(q, &p[r*c..(r+1)*c]);
+            }
+        }
+    }
+}
+
+/// Repair up to 2 bad blocks.
+///
+/// This can include the parity blocks themselves, which are indexed after
+/// the data blocks. `bad_blocks` must be an array of indices indicating
+/// which blocks are bad, duplicate indices are ignored.
+///
+/// Returns [`Error::LengthMismatch`] if the blocks aren't all the same
+/// length, or aren't a multiple of [`rows(n)`](rows) bytes.
+///
+/// ``` rust
+/// # use ::gf256::raid::rdp;
+/// let mut data = b"Hellxxxxxxxx".to_vec();
+/// let mut datas = data.chunks_mut(4).collect::<Vec<_>>();
+/// let mut parity1 = b"\x55\x29\x5f\x22".to_vec();
+/// let mut parity2 = b"\x40\x55\x1c\x57".to_vec();
+///
+/// // repair
+/// rdp::repair(&mut datas, &mut parity1, &mut parity2, &[1, 2]).unwrap();
+/// assert_eq!(&data, b"Hello World!");
+/// ```
+///
+pub fn repair<B: AsMut<[u8]>>(
+    blocks: &mut [B],
+    p: &mut [u8],
+    q: &mut [u8],
+    bad_blocks: &[usize],
+) -> Result<(), Error> {
+    let n = blocks.len();
+    if bad_blocks.iter().any(|b| !(0..n+2).contains(b)) {
+        return Err(Error::BadBlockOutOfRange);
+    }
+
+    let len = p.len();
+    let prime = prime(n);
+    if n == 0
+        || !len.is_multiple_of(prime-1)
+        || q.len() != len
+        || blocks.iter_mut().any(|b| b.as_mut().len() != len)
+    {
+        return Err(Error::LengthMismatch);
+    }
+    let grid = Grid { p: prime, c: len / (prime-1) };
+
+    // map our block indices to columns in the grid, the row parity is
+    // column p-1, the diagonal parity is not part of the grid, note we
+    // need to skip duplicates here
+    let mut columns = [0usize; 2];
+    let mut count = 0;
+    let mut bad_q = false;
+    for b in bad_blocks {
+        let column = match *b {
+            b if b < n => b,
+            b if b == n => prime-1,
+            _ => { bad_q = true; continue; }
+        };
+
+        if !columns[..count].contains(&column) {
+            if count == columns.len() {
+                // can't repair
+                return Err(Error::TooManyBadBlocks);
+            }
+            columns[count] = column;
+            count += 1;
+        }
+    }
+
+    if count + usize::from(bad_q) > 2 {
+        // can't repair
+        return Err(Error::TooManyBadBlocks);
+    }
+
+    match count {
+        1 => {
+            // repair using the row parity
+            for r in 0..prime-1 {
+                recover_row(blocks, p, columns[0], r, grid);
+            }
+        }
+        2 => {
+            // repair using chains of diagonals and rows
+            let (a, b) = (columns[0], columns[1]);
+            recover_chain(blocks, p, q, a, b, grid);
+            recover_chain(blocks, p, q, b, a, grid);
+        }
+        _ => {}
+    }
+
+    if bad_q {
+        format_diagonals(blocks, p, q, grid);
+    }
+
+    Ok(())
+}
@@ -1263,6 +1263,71 @@
 //! provided by this crate as [`rs255w223`](crate::rs::rs255w223). This was the
 //! scheme famously used on the [Voyager missions][voyager].
 //!
+//! For larger codewords, Reed-Solomon can be built over a larger field. Over
+//! `GF(2^16)`, each symbol is a 16-bit [`gf2p16`](crate::gf::gf2p16), which
+//! allows codewords of up to 65535 symbols, and more than 255 symbols of ECC:
+//!
+//! ``` rust
+//! # use ::gf256::*;
+//! # use ::gf256::rs::rs;
+//! #[rs(gf=gf2p16, u=u16, block=65535, data=65503)]
+//! pub mod rs65535w65503 {}
+//!
+//! # fn main() -> Result<(), rs65535w65503::Error> {
+//! // encode
+//! let mut buf = (0..1000).collect::<Vec<u16>>();
+//! buf.resize(buf.len()+32, 0u16);
+//! rs65535w65503::encode(&mut buf);
+//!
+//! // corrupt
+//! buf[0..16].fill(0x7878);
+//!
+//! // correct
+//! rs65535w65503::correct_errors(&mut buf)?;
+//! assert_eq!(&buf[0..1000], &(0..1000).collect::<Vec<u16>>());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Decoding without a heap
+//!
+//! The decoding functions allocate a handful of polynomials on the heap. For
+//! devices without a heap, `correct_errors_with_scratch` and `correct_with_scratch`
+//! instead take a caller-provided scratch buffer of at least `SCRATCH_SIZE`
+//! symbols. This only depends on the number of ECC symbols, `5*(n-k) + 3`, so
+//! it can be a fixed-size array:
+//!
+//! ``` rust
+//! # use gf256::rs::rs255w223;
+//! let mut buf = b"Hello World!".to_vec();
+//! buf.resize(buf.len()+32, 0u8);
+//! rs255w223::encode(&mut buf);
+//! buf[0..16].fill(b'x');
+//!
+//! let mut scratch = [0u8; rs255w223::SCRATCH_SIZE];
+//! rs255w223::correct_errors_with_scratch(&mut buf, &mut scratch)?;
+//! assert_eq!(&buf[0..12], b"Hello World!");
+//! # Ok::<(), rs255w223::Error>(())
+//! ```
+//!
+//! Without the default `alloc` feature, these are the only decoding functions
+//! available.
+//!
+//! ## Batched and parallel encoding/correction
+//!
+//! Large files and objects are usually split into many independent codewords.
+//! `encode_blocks` and `is_correct_blocks` take a slice of codewords, and, for
+//! fields with byte-sized symbols, run each codeword's polynomial division
+//! side-by-side. This turns the inner loop into multiplying a row of symbols,
+//! one from each codeword, by a coefficient of the generator polynomial, which
+//! can use the same SIMD kernels (`gfni`/`avx2`/`ssse3`/`neon`) as
+//! `slice_mul_acc`. The streaming `Encoder`/`Decoder` use these internally.
+//!
+//! If the `rayon` feature is enabled, `par_encode_blocks` and `par_correct_blocks`
+//! also process batches of codewords on rayon's thread pool.
+//! `par_correct_blocks` returns the result of each codeword, so one
+//! uncorrectable codeword doesn't prevent correcting the others.
+//!
 //! ## Further reading
 //!
 //! Reed-Solomon error-correction, and error-correction in general, is a deep
@@ -1319,11 +1384,19 @@
 ///
 /// The `rs` macro accepts a number of configuration options:
 ///
-/// - `block` - Size of the codeword, data+ecc, in bytes.
-/// - `data` - Maximum size of the data in bytes.
+/// - `block` - Size of the codeword, data+ecc, in symbols. This must be <= the
+///   number of non-zero elements in the field, so at most 255 for `gf256`, or
+///   65535 for [`gf2p16`](crate::gf::gf2p16).
+/// - `data` - Maximum size of the data in symbols.
 /// - `gf` - The finite-field we are implemented over, defaults to
 ///   [`gf256`](crate::gf256).
 /// - `u` - The unsigned type to operate on, defaults to [`u8`].
+/// - `euclidean` - Find the error locator using the extended Euclidean
+///   algorithm (Sugiyama's algorithm) instead of Berlekamp-Massey. This
+///   finds the same errors, but may be easier to audit.
+/// - `list` - Provide an experimental list decoder, `correct_list`, which can
+///   find candidate codewords beyond `ECC_SIZE/2` errors. This is very slow
+///   and limited to fields with <= 2^16 elements.
 ///
 /// ``` rust,ignore
 /// # use ::gf256::*;
@@ -1333,6 +1406,8 @@
 ///     data=223,
 ///     gf=gf256,
 ///     u=u8,
+///     euclidean=false,
+///     list=false,
 /// )]
 /// pub mod my_rs255w223 {}
 ///
@@ -1424,6 +1499,385 @@ mod test {
         }
     }
 
+    #[test]
+    fn rs26w16_errors_and_erasures() {
+        let mut data = (0..26).collect::<Vec<u8>>();
+        rs26w16::encode(&mut data);
+        let orig = data.clone();
+
+        // try any mixture of errors and erasures with 2*errors+erasures <= k
+        for erasure_count in 0..=(26-16) {
+            for error_count in 0..=((26-16)-erasure_count)/2 {
+                for i in 0..26 {
+                    let erasures = (0..erasure_count)
+                        .map(|j| (i+j) % 26)
+                        .collect::<Vec<_>>();
+                    let errors = (0..error_count)
+                        .map(|j| (i+erasure_count+1+2*j) % 26)
+                        .collect::<Vec<_>>();
+                    for j in erasures.iter().chain(&errors) {
+                        data[*j] ^= b'x';
+                    }
+
+                    let res = rs26w16::correct_errors_and_erasures(&mut data, &erasures);
+                    assert_eq!(res.ok(), Some(erasure_count+error_count));
+                    assert_eq!(&data, &orig);
+                }
+            }
+        }
+
+        // duplicate erasures are ignored
+        data[0..2].fill(b'x');
+        let res = rs26w16::correct_errors_and_erasures(&mut data, &[0, 0, 1, 1]);
+        assert_eq!(res.ok(), Some(2));
+        assert_eq!(&data, &orig);
+
+        // too many errors?
+        data[0..6].fill(b'x');
+        let res = rs26w16::correct_errors_and_erasures(&mut data, &[0]);
+        assert_eq!(res, Err(rs26w16::Error::TooManyErrors));
+    }
+
+    #[test]
+    fn rs26w16_report() {
+        let mut data = (0..26).collect::<Vec<u8>>();
+        rs26w16::encode(&mut data);
+        let orig = data.clone();
+
+        // clean codewords need no corrections
+        let corrections = rs26w16::correct_with_report(&mut data, &[]).unwrap();
+        assert!(corrections.is_empty());
+        assert_eq!(corrections.margin(), 26-16);
+
+        // report errors and erasures
+        data[3] ^= b'x';
+        data[20] ^= b'x';
+        data[7] ^= b'x';
+        let corrections = rs26w16::correct_with_report(&mut data, &[7, 1]).unwrap();
+        assert_eq!(corrections.errors, &[3, 20]);
+        assert_eq!(corrections.erasures, &[1, 7]);
+        assert_eq!(corrections.len(), 4);
+        assert_eq!(corrections.margin(), (26-16) - 6);
+        assert_eq!(&data, &orig);
+
+        // at the limit of what we can correct
+        data[0..5].fill(b'x');
+        let corrections = rs26w16::correct_with_report(&mut data, &[]).unwrap();
+        assert_eq!(corrections.errors, &[0, 1, 2, 3, 4]);
+        assert_eq!(corrections.margin(), 0);
+        assert_eq!(&data, &orig);
+    }
+
+    #[test]
+    fn rs26w16_check() {
+        let mut data = (0..26).collect::<Vec<u8>>();
+        rs26w16::encode(&mut data);
+        let orig = data.clone();
+        assert_eq!(rs26w16::check(&data, &[]), rs26w16::Status::Clean);
+
+        // up to k/2 errors are correctable
+        for i in 1..=(26-16)/2 {
+            data[0..i].fill(b'x');
+            assert_eq!(rs26w16::check(&data, &[]), rs26w16::Status::Correctable);
+            data.copy_from_slice(&orig);
+        }
+
+        // up to k erasures are correctable
+        for i in 1..=(26-16) {
+            data[0..i].fill(b'x');
+            let erasures = (0..i).collect::<Vec<_>>();
+            assert_eq!(rs26w16::check(&data, &erasures), rs26w16::Status::Correctable);
+            data.copy_from_slice(&orig);
+        }
+
+        // check should always agree with correct
+        for i in 0..26 {
+            for j in 0..26 {
+                data[i..].fill(b'x');
+                data[..j].fill(b'x');
+                let status = rs26w16::check(&data, &[]);
+                let res = rs26w16::correct_errors(&mut data.clone());
+                match status {
+                    rs26w16::Status::Clean => assert_eq!(res, Ok(0)),
+                    rs26w16::Status::Correctable => assert!(res.is_ok()),
+                    rs26w16::Status::Uncorrectable => assert!(res.is_err()),
+                }
+                data.copy_from_slice(&orig);
+            }
+        }
+    }
+
+    #[test]
+    fn rs26w16_update_symbol() {
+        let mut data = (0..26).collect::<Vec<u8>>();
+        rs26w16::encode(&mut data);
+
+        // update each data symbol, including in shortened codewords
+        for len in [26, 20, 11] {
+            let mut data = data[26-len..].to_vec();
+            rs26w16::encode(&mut data);
+            for i in 0..len-(26-16) {
+                rs26w16::update_symbol(&mut data, i, b'x'+i as u8);
+
+                let mut expected = data.clone();
+                rs26w16::encode(&mut expected);
+                assert_eq!(data, expected);
+                assert!(rs26w16::is_correct(&data));
+            }
+        }
+    }
+
+    #[test]
+    fn rs26w16_stream() {
+        // try a number of message sizes, including exact multiples of
+        // the frame size
+        for len in [0, 1, 14, 15, 16, 30, 31, 100] {
+            let message = (0..len).map(|x| x as u8).collect::<Vec<u8>>();
+
+            // feed the encoder a bit at a time
+            let mut encoder = rs26w16::Encoder::new();
+            let mut codewords = Vec::new();
+            for chunk in message.chunks(7) {
+                codewords.extend(encoder.update(chunk));
+            }
+            codewords.extend(encoder.finish());
+            assert_eq!(codewords.len(), (len/15 + 1)*10 + len + (len/15 + 1));
+
+            // corrupt up to k/2 errors in each codeword
+            for i in (0..codewords.len()).step_by(26) {
+                for j in i..codewords.len().min(i+(26-16)/2) {
+                    codewords[j] ^= b'x';
+                }
+            }
+
+            // feed the decoder a bit at a time
+            let mut decoder = rs26w16::Decoder::new();
+            let mut data = Vec::new();
+            for chunk in codewords.chunks(11) {
+                data.extend(decoder.update(chunk).unwrap());
+            }
+            data.extend(decoder.finish().unwrap());
+            assert_eq!(data, message);
+        }
+
+        // truncated streams should fail to decode
+        let mut encoder = rs26w16::Encoder::new();
+        let mut codewords = encoder.update(&[b'x'; 100]);
+        codewords.extend(encoder.finish());
+
+        let mut decoder = rs26w16::Decoder::new();
+        decoder.update(&codewords[..26]).unwrap();
+        assert_eq!(decoder.finish(), Err(rs26w16::Error::InvalidFrame));
+
+        let mut decoder = rs26w16::Decoder::new();
+        decoder.update(&codewords[..5]).unwrap();
+        assert_eq!(decoder.finish(), Err(rs26w16::Error::InvalidFrame));
+    }
+
+    #[cfg(feature="rayon")]
+    #[test]
+    fn rs26w16_par() {
+        // many codewords, including shortened codewords
+        let mut codewords = (0..1000)
+            .map(|i| (0..(i%16)+11).map(|j| (i+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut expected = codewords.clone();
+        for codeword in expected.iter_mut() {
+            rs26w16::encode(codeword);
+        }
+
+        // par_encode_blocks matches encode
+        rs26w16::par_encode_blocks(&mut codewords);
+        assert_eq!(codewords, expected);
+
+        // corrupt up to k/2 errors, with one uncorrectable codeword
+        for (i, codeword) in codewords.iter_mut().enumerate() {
+            for x in codeword.iter_mut().take(i%6) {
+                *x ^= b'x';
+            }
+        }
+        codewords[500].fill(b'x');
+
+        // par_correct_blocks matches correct_errors
+        let results = rs26w16::par_correct_blocks(&mut codewords);
+        for (i, res) in results.iter().enumerate() {
+            if i == 500 {
+                assert_eq!(*res, Err(rs26w16::Error::TooManyErrors));
+            } else {
+                assert_eq!(*res, Ok(i%6));
+                assert_eq!(codewords[i], expected[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn rs26w16_blocks() {
+        // many codewords, including shortened codewords, more than fit
+        // in one batch
+        let mut codewords = (0..1000)
+            .map(|i| (0..(i%16)+11).map(|j| (i+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut expected = codewords.clone();
+        for codeword in expected.iter_mut() {
+            rs26w16::encode(codeword);
+        }
+
+        // encode_blocks matches encode
+        rs26w16::encode_blocks(&mut codewords);
+        assert_eq!(codewords, expected);
+        assert!(rs26w16::is_correct_blocks(&codewords).iter().all(|x| *x));
+
+        // is_correct_blocks matches is_correct, including codewords
+        // smaller than the error-correction
+        for (i, codeword) in codewords.iter_mut().enumerate() {
+            if i % 3 == 0 {
+                let len = codeword.len();
+                codeword[i % len] ^= b'x';
+            }
+        }
+        codewords.push(Vec::new());
+        codewords.push([0; 5].to_vec());
+        codewords.push([1; 5].to_vec());
+        let corrects = rs26w16::is_correct_blocks(&codewords);
+        for (codeword, correct) in codewords.iter().zip(corrects) {
+            assert_eq!(correct, rs26w16::is_correct(codeword));
+        }
+
+        // odd fields, gf16 fits in a byte, gf2p64 falls back to
+        // encoding each codeword
+        let mut codewords = (0..200)
+            .map(|i| (0..(i%8)+8).map(|j| ((i+j) % 16) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut expected = codewords.clone();
+        for codeword in expected.iter_mut() {
+            gf16_rs15w8::encode(codeword);
+        }
+        gf16_rs15w8::encode_blocks(&mut codewords);
+        assert_eq!(codewords, expected);
+        codewords[100][0] ^= 0x7;
+        let corrects = gf16_rs15w8::is_correct_blocks(&codewords);
+        assert!(corrects.iter().enumerate().all(|(i, x)| *x == (i != 100)));
+
+        let mut codewords = (0..200)
+            .map(|i| (0..(i%16)+11).map(|j| (i+j) as u64).collect::<Vec<u64>>())
+            .collect::<Vec<_>>();
+        let mut expected = codewords.clone();
+        for codeword in expected.iter_mut() {
+            gf2p64_rs26w16::encode(codeword);
+        }
+        gf2p64_rs26w16::encode_blocks(&mut codewords);
+        assert_eq!(codewords, expected);
+        codewords[100][0] ^= 0x7878787878787878;
+        let corrects = gf2p64_rs26w16::is_correct_blocks(&codewords);
+        assert!(corrects.iter().enumerate().all(|(i, x)| *x == (i != 100)));
+    }
+
+    #[test]
+    fn rs26w16_scratch() {
+        let mut scratch = [0u8; rs26w16::SCRATCH_SIZE];
+        assert_eq!(rs26w16::SCRATCH_SIZE, 5*10+3);
+
+        let mut data = (0..26).collect::<Vec<u8>>();
+        rs26w16::encode(&mut data);
+        let expected = data.clone();
+
+        // no errors
+        assert_eq!(rs26w16::correct_errors_with_scratch(&mut data, &mut scratch), Ok(0));
+        assert_eq!(data, expected);
+
+        // correct up to k/2 errors, and mixtures of errors and erasures,
+        // at various offsets, this should match the allocating decoder
+        for start in 0..26 {
+            for errors in 0..=5 {
+                let mut corrupted = expected.clone();
+                for i in 0..errors {
+                    corrupted[(start+i*5) % 26] ^= b'x';
+                }
+                let mut data = corrupted.clone();
+                assert_eq!(
+                    rs26w16::correct_errors_with_scratch(&mut data, &mut scratch),
+                    Ok(errors)
+                );
+                assert_eq!(data, expected);
+
+                for erasures in 0..=10-2*errors {
+                    let mut data = corrupted.clone();
+                    let erasures = (0..erasures)
+                        .map(|i| (start+i*5+1) % 26)
+                        .collect::<Vec<_>>();
+                    for j in erasures.iter() {
+                        data[*j] ^= b'x';
+                    }
+                    let mut data_ = data.clone();
+                    let res = rs26w16::correct_with_scratch(&mut data, &erasures, &mut scratch);
+                    assert_eq!(res, rs26w16::correct(&mut data_, &erasures));
+                    assert!(res.is_ok());
+                    assert_eq!(data, expected);
+                }
+            }
+        }
+
+        // duplicate erasures are ignored
+        let mut data = expected.clone();
+        data[0..10].fill(b'x');
+        assert_eq!(
+            rs26w16::correct_with_scratch(
+                &mut data,
+                &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 9, 0],
+                &mut scratch
+            ),
+            Ok(10)
+        );
+        assert_eq!(data, expected);
+
+        // too many errors
+        let mut data = expected.clone();
+        data[0..6].fill(b'x');
+        assert_eq!(
+            rs26w16::correct_errors_with_scratch(&mut data, &mut scratch),
+            Err(rs26w16::Error::TooManyErrors)
+        );
+    }
+
+    #[test]
+    fn rs26w16_shards() {
+        // 6 data shards, 10 parity shards
+        let mut shards = (0..16)
+            .map(|j| (0..8).map(|i| (j*8+i) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        rs26w16::encode_shards(&mut shards);
+        for (j, shard) in shards.iter().enumerate().take(6) {
+            assert!(shard.iter().enumerate().all(|(i, x)| *x == (j*8+i) as u8));
+        }
+
+        // each column should be a valid codeword
+        for i in 0..8 {
+            let column = shards.iter().map(|s| s[i]).collect::<Vec<u8>>();
+            assert!(rs26w16::is_correct(&column));
+        }
+
+        // lose up to 10 shards, in a number of patterns
+        for start in 0..16 {
+            for count in 0..=10 {
+                let mut lost = shards.iter().cloned().map(Some).collect::<Vec<_>>();
+                for j in 0..count {
+                    lost[(start+j*3) % 16] = None;
+                }
+                rs26w16::reconstruct(&mut lost).unwrap();
+                for j in 0..16 {
+                    assert_eq!(lost[j].as_ref(), Some(&shards[j]));
+                }
+            }
+        }
+
+        // lose too many shards
+        let mut lost = shards.iter().cloned().map(Some).collect::<Vec<_>>();
+        for shard in lost.iter_mut().take(11) {
+            *shard = None;
+        }
+        assert_eq!(rs26w16::reconstruct(&mut lost), Err(rs26w16::Error::TooManyErrors));
+    }
+
     #[test]
     fn rs255w223() {
         let mut data = (0..255).collect::<Vec<u8>>();
@@ -1553,6 +2007,42 @@ mod test {
         }
     }
 
+    // Reed-Solomon over a larger field, with more than 255 symbols
+    // and more than 255 symbols of ECC
+    #[rs(gf=gf2p16, u=u16, block=600, data=344)]
+    pub mod gf2p16_rs600w344 {}
+
+    #[test]
+    fn gf2p16_rs600w344() {
+        let mut data = (0..600).collect::<Vec<u16>>();
+        gf2p16_rs600w344::encode(&mut data);
+        assert!(gf2p16_rs600w344::is_correct(&data));
+        let orig = data.clone();
+
+        // correct up to k known erasures
+        for i in (0..=(600-344)).step_by(32) {
+            data[0..i].fill(0x7878);
+            let res = gf2p16_rs600w344::correct_erasures(&mut data, &(0..i).collect::<Vec<_>>());
+            assert_eq!(res.ok(), Some(i));
+            assert_eq!(&data, &orig);
+        }
+
+        // correct up to k/2 unknown errors
+        for i in (0..=(600-344)/2).step_by(16) {
+            data[600-i..].fill(0x7878);
+            let res = gf2p16_rs600w344::correct_errors(&mut data);
+            assert_eq!(res.ok(), Some(i));
+            assert_eq!(&data, &orig);
+        }
+
+        // correct a mixture of errors and erasures
+        data[0..100].fill(0x7878);
+        data[400..478].fill(0x7878);
+        let res = gf2p16_rs600w344::correct_errors_and_erasures(&mut data, &(0..100).collect::<Vec<_>>());
+        assert_eq!(res.ok(), Some(178));
+        assert_eq!(&data, &orig);
+    }
+
     // Reed-Solomon with very odd sizes
     #[gf(polynomial=0x13, generator=0x2)]
     type gf16;
@@ -1609,8 +2099,142 @@ mod test {
         }
     }
 
+    // Reed-Solomon using the extended Euclidean algorithm
+    #[rs(block=26, data=16, euclidean)]
+    pub mod rs26w16_euclidean {}
+    #[rs(gf=gf2p64, u=u64, block=64, data=8, euclidean)]
+    pub mod gf2p64_rs64w8_euclidean {}
+
+    #[test]
+    fn rs26w16_euclidean() {
+        let mut data = (0..26).collect::<Vec<u8>>();
+        rs26w16_euclidean::encode(&mut data);
+        assert!(rs26w16_euclidean::is_correct(&data));
+        let orig = data.clone();
+
+        // try any mixture of errors and erasures with 2*errors+erasures <= k
+        for erasure_count in 0..=(26-16) {
+            for error_count in 0..=((26-16)-erasure_count)/2 {
+                for i in 0..26 {
+                    let erasures = (0..erasure_count)
+                        .map(|j| (i+j) % 26)
+                        .collect::<Vec<_>>();
+                    let errors = (0..error_count)
+                        .map(|j| (i+erasure_count+1+2*j) % 26)
+                        .collect::<Vec<_>>();
+                    for j in erasures.iter().chain(&errors) {
+                        data[*j] ^= b'x';
+                    }
+
+                    let res = rs26w16_euclidean::correct_errors_and_erasures(&mut data, &erasures);
+                    assert_eq!(res.ok(), Some(erasure_count+error_count));
+                    assert_eq!(&data, &orig);
+                }
+            }
+        }
+
+        // should agree with Berlekamp-Massey, even when we can't correct
+        for i in 0..26 {
+            for j in 0..26 {
+                data[i..].fill(b'x');
+                data[..j].fill(b'x');
+                let mut a = data.clone();
+                let mut b = data.clone();
+                assert_eq!(
+                    rs26w16_euclidean::correct_errors(&mut a).ok(),
+                    rs26w16::correct_errors(&mut b).ok()
+                );
+                assert_eq!(a, b);
+                data.copy_from_slice(&orig);
+            }
+        }
+    }
+
+    #[test]
+    fn gf2p64_rs64w8_euclidean() {
+        let mut data = (0..64).collect::<Vec<u64>>();
+        gf2p64_rs64w8_euclidean::encode(&mut data);
+        assert!(gf2p64_rs64w8_euclidean::is_correct(&data));
+
+        // correct up to k known erasures
+        for i in 0..(64-8) {
+            data[0..i].fill(0x7878787878787878);
+            let res = gf2p64_rs64w8_euclidean::correct_erasures(&mut data, &(0..i).collect::<Vec<_>>());
+            assert_eq!(res.ok(), Some(i));
+            assert_eq!(&data[0..8], &(0..8).collect::<Vec<u64>>());
+        }
+
+        // correct up to k/2 unknown errors
+        for i in 0..(64-8)/2 {
+            data[0..i].fill(0x7878787878787878);
+            let res = gf2p64_rs64w8_euclidean::correct_errors(&mut data);
+            assert_eq!(res.ok(), Some(i));
+            assert_eq!(&data[0..8], &(0..8).collect::<Vec<u64>>());
+        }
+    }
+
+    // experimental list decoding, this only helps for low-rate codes
+    #[rs(block=32, data=4, list)]
+    pub mod rs32w4_list {}
+    #[rs(gf=gf16, u=u8, block=15, data=3, list)]
+    pub mod gf16_rs15w3_list {}
+
+    #[test]
+    fn rs32w4_list() {
+        let mut data = (0..32).collect::<Vec<u8>>();
+        rs32w4_list::encode(&mut data);
+        let orig = data.clone();
+
+        // clean codewords should only find themselves
+        let candidates = rs32w4_list::correct_list(&data, 1);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0], orig);
+
+        // up to k/2 errors, we should always find the unique codeword first
+        for i in 0..=(32-4)/2 {
+            data[0..i].fill(b'x');
+            let candidates = rs32w4_list::correct_list(&data, 1);
+            assert_eq!(candidates[0], orig);
+            data.copy_from_slice(&orig);
+        }
+
+        // beyond k/2 errors, the original codeword should be in the list
+        for i in (32-4)/2+1..=19 {
+            data[0..i].fill(b'x');
+            assert_eq!(rs32w4_list::correct_errors(&mut data.clone()).ok(), None);
+            let candidates = rs32w4_list::correct_list(&data, 1);
+            assert!(candidates.contains(&orig));
+            assert!(candidates.iter().all(|c| rs32w4_list::is_correct(c)));
+            data.copy_from_slice(&orig);
+        }
+
+        // with multiplicity we can go a bit further
+        data[0..20].fill(b'x');
+        let candidates = rs32w4_list::correct_list(&data, 2);
+        assert!(candidates.contains(&orig));
+    }
+
+    #[test]
+    fn gf16_rs15w3_list() {
+        let mut data = (0..15).collect::<Vec<u8>>();
+        gf16_rs15w3_list::encode(&mut data);
+        let orig = data.clone();
+
+        // beyond k/2 errors, the original codeword should be in the list
+        for i in 0..=8 {
+            for j in 0..15 {
+                for k in 0..i {
+                    data[(j+2*k) % 15] ^= 0x5;
+                }
+                let candidates = gf16_rs15w3_list::correct_list(&data, 1);
+                assert!(candidates.contains(&orig));
+                data.copy_from_slice(&orig);
+            }
+        }
+    }
+
     // all RS params
-    #[rs(gf=gf256, u=u8, block=26, data=16)]
+    #[rs(gf=gf256, u=u8, block=26, data=16, euclidean=false, list=false)]
     mod rs26w16_all_params {}
 
     #[test]
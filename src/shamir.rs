@@ -23,6 +23,27 @@
 //! Note this module requires feature `shamir`. You may also want to enable the
 //! feature `thread-rng`, which is required for the default rng.
 //!
+//! `reconstruct` always produces a result, even if the shares are garbage. To
+//! catch common mistakes, such as duplicate or mismatched shares, use
+//! `try_reconstruct`, which returns a `Result` instead.
+//!
+//! If you don't have a heap, `generate_into` and `reconstruct_into` operate on
+//! caller-provided buffers, and disabling the default feature `alloc` removes
+//! the functions that depend on alloc:
+//!
+//! ``` rust
+//! use gf256::shamir::shamir;
+//!
+//! // generate shares
+//! let mut shares = [[0u8; 22]; 5];
+//! shamir::generate_into(b"secret secret secret!", &mut shares, 4);
+//!
+//! // >=4 can reconstruct secret
+//! let mut secret = [0u8; 21];
+//! shamir::reconstruct_into(&shares[1..], &mut secret);
+//! assert_eq!(&secret, b"secret secret secret!");
+//! ```
+//!
 //! A fully featured implementation of Shamir's secret sharing can be found in
 //! [`examples/shamir.rs`][shamir-example]:
 //!
@@ -42,6 +63,118 @@
 //! reconstruct 5 shares => Hello World!  48656c6c6f20576f726c6421
 //! ```
 //!
+//! If the `zeroize` feature is enabled, intermediate secret material is wiped
+//! after use, and `generate_zeroizing`/`reconstruct_zeroizing` return shares
+//! and secrets that are wiped on drop.
+//!
+//! If the `std` feature is enabled, `generate_stream` and `reconstruct_stream`
+//! can split large secrets from a `Read` into share `Write`s in fixed-size
+//! chunks, without needing to hold the entire secret or shares in memory:
+//!
+//! ``` rust
+//! # use std::fs::File;
+//! use gf256::shamir::shamir;
+//!
+//! # let dir = std::env::temp_dir();
+//! # let backup_path = dir.join("gf256-shamir-backup");
+//! # std::fs::write(&backup_path, b"secret secret secret!")?;
+//! let backup = File::open(&backup_path)?;
+//! let mut shares = (0..5)
+//!     .map(|i| File::create(dir.join(format!("gf256-shamir-share{}", i))))
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! shamir::generate_stream(backup, &mut shares, 4)?;
+//!
+//! let mut shares = (1..5)
+//!     .map(|i| File::open(dir.join(format!("gf256-shamir-share{}", i))))
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! let mut secret = vec![];
+//! shamir::reconstruct_stream(&mut shares, &mut secret)?;
+//! assert_eq!(secret, b"secret secret secret!");
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! Shares can also carry an integrity tag, which lets us detect corrupted
+//! shares before reconstruction silently results in garbage:
+//!
+//! ``` rust
+//! use gf256::shamir::shamir;
+//!
+//! let mut shares = shamir::generate_tagged(b"secret secret secret!", 5, 4);
+//!
+//! // oh no, a cosmic ray
+//! shares[2][7] ^= 0x04;
+//! assert_eq!(shamir::invalid_shares(&shares).collect::<Vec<_>>(), vec![2]);
+//! assert_eq!(shamir::reconstruct_tagged(&shares), Err(shamir::Error::InvalidShare(2)));
+//!
+//! // leave out the bad share
+//! shares.remove(2);
+//! assert_eq!(shamir::reconstruct_tagged(&shares).unwrap(), b"secret secret secret!");
+//! ```
+//!
+//! Note the integrity tag is a checksum, not a MAC, and won't protect against
+//! deliberate tampering.
+//!
+//! If more than `k` shares are available, `reconstruct_robust` can use the
+//! redundant shares to find and correct invalid shares, at a cost:
+//!
+//! ``` rust
+//! use gf256::shamir::shamir;
+//!
+//! let mut shares = shamir::generate(b"secret secret secret!", 8, 4);
+//!
+//! // oh no, a malicious custodian
+//! shares[5][1] ^= 0x42;
+//! let (secret, invalid) = shamir::reconstruct_robust(&shares, 4)?;
+//! assert_eq!(secret, b"secret secret secret!");
+//! assert_eq!(invalid, vec![5]);
+//! # Ok::<(), shamir::Error>(())
+//! ```
+//!
+//! When reconstructing many chunks from the same set of shares, a
+//! `Reconstructor` can find the Lagrange basis once, reducing each chunk to a
+//! cheap matrix-vector product. The basis itself is available through
+//! `lagrange_basis_at_zero` for building custom reconstruction pipelines.
+//!
+//! Multiple secrets can be bundled into a single set of shares with
+//! `generate_multi`, which still uses independent polynomials for each secret:
+//!
+//! ``` rust
+//! use gf256::shamir::shamir;
+//!
+//! let shares = shamir::generate_multi(&[&b"key"[..], &b"iv"[..], &b"config"[..]], 5, 4);
+//! let secrets = shamir::reconstruct_multi(&shares[..4])?;
+//! assert_eq!(secrets, [&b"key"[..], &b"iv"[..], &b"config"[..]]);
+//! # Ok::<(), shamir::Error>(())
+//! ```
+//!
+//! Additional shares can be issued later with `issue_share`, given at least `k`
+//! existing shares, without ever reconstructing the secret:
+//!
+//! ``` rust
+//! use gf256::shamir::shamir;
+//!
+//! let shares = shamir::generate(b"secret secret secret!", 5, 4);
+//! let share = shamir::issue_share(&shares[..4], 6);
+//! assert_eq!(shamir::reconstruct(&[&share, &shares[0], &shares[1], &shares[2]]), b"secret secret secret!");
+//! ```
+//!
+//! For long-term storage, `Share` provides a versioned encoding
+//! that records the threshold, finite-field, and other metadata needed to
+//! reconstruct the secret later:
+//!
+//! ``` rust
+//! use gf256::shamir::shamir;
+//!
+//! let shares = shamir::generate_shares(b"secret secret secret!", 5, 4);
+//! let bytes = shares.iter().map(|share| share.to_bytes()).collect::<Vec<_>>();
+//!
+//! let shares = bytes[..3].iter()
+//!     .map(|bytes| shamir::Share::from_bytes(bytes))
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! assert_eq!(shamir::reconstruct_shares(&shares), Err(shamir::Error::TooFewShares));
+//! # Ok::<(), shamir::Error>(())
+//! ```
+//!
 //! ## How does Shamir's secret sharing scheme work?
 //!
 //! The underlying theory of Shamir's secret sharing is actually relatively easy
@@ -287,6 +420,64 @@
 //! Because of this, Shamir's secret sharing scheme is limited to the number of non-zero
 //! elements in our field. In the case of `GF(256)`, this limits us to 255 shares.
 //!
+//! If you need more shares, the `shamir` macro accepts a `width` option for
+//! sharing over a larger field, at the cost of larger shares. In `GF(2^16)`, each
+//! symbol of the secret and each share is a [`u16`], and we can create up to
+//! 65535 shares:
+//!
+//! ``` rust
+//! # pub use ::gf256::*;
+//! # pub use ::gf256::gf;
+//! # use ::gf256::shamir::shamir;
+//! #[shamir(width=16)]
+//! mod shamir16 {}
+//!
+//! # fn main() {
+//! let secret = [0x1234, 0x5678];
+//! let shares = shamir16::generate(&secret, 1000, 3);
+//! assert_eq!(shamir16::reconstruct(&shares[997..]), secret);
+//! # }
+//! ```
+//!
+//! Wider fields also reduce the number of field evaluations needed for large,
+//! aligned secrets. The modules `shamir16`, `shamir32`, and `shamir64` are
+//! provided for convenience, and `generate_bytes`/`reconstruct_bytes` interpret
+//! a byte secret as little-endian field symbols:
+//!
+//! ``` rust
+//! use gf256::shamir::shamir64;
+//!
+//! let shares = shamir64::generate_bytes(b"secret secret secret sec", 5, 4);
+//! assert_eq!(shamir64::reconstruct_bytes(&shares[1..]), b"secret secret secret sec");
+//! ```
+//!
+//! ## Interoperability
+//!
+//! Shares generated by the [`shamir`](macro@crate::shamir::shamir) macro are not
+//! directly compatible with other Shamir's secret-sharing tools. These tools
+//! agree on the underlying math, but differ in the details, such as the field,
+//! where the secret is placed, digests, and how shares are encoded.
+//!
+//! For migrating between tools, two compatible implementations are provided:
+//!
+//! - [`ssss`](crate::shamir::ssss) reads and writes shares for
+//!   [`ssss`][ssss], which shares the entire secret as a single element of a
+//!   field `GF(2^n)`, and by default applies an XTEA-based diffusion layer.
+//!
+//! - [`slip39`](crate::shamir::slip39) reads and writes [SLIP-0039][slip-0039]
+//!   mnemonics, which share over the Rijndael field `GF(256)` with a digest,
+//!   passphrase encryption, and two levels of groups.
+//!
+//! These require alloc, and `slip39` additionally requires the feature
+//! `slip39`, which pulls in the [`sha2`][sha2], [`hmac`][hmac], and
+//! [`pbkdf2`][pbkdf2] crates.
+//!
+//! [ssss]: http://point-at-infinity.org/ssss
+//! [slip-0039]: https://github.com/satoshilabs/slips/blob/master/slip-0039.md
+//! [sha2]: https://docs.rs/sha2
+//! [hmac]: https://docs.rs/hmac
+//! [pbkdf2]: https://docs.rs/pbkdf2
+//!
 //! ## Constant-time
 //!
 //! The default Shamir's secret-sharing implementation internally uses a custom
@@ -333,8 +524,15 @@
 /// The `shamir` macro accepts a number of configuration options:
 ///
 /// - `gf` - The finite-field we are implemented over, defaults to
-///   [`gf256`](crate::gf256) in Barret mode.
-/// - `u` - The unsigned type to operate on, defaults to [`u8`].
+///   [`gf256`](crate::gf256) in Barret mode. This can be any type implementing
+///   [`Field`](crate::traits::Field), including fields created with the
+///   [`gf`](crate::gf::gf) macro that aren't a multiple of a byte, in which
+///   case the secret is shared symbol-by-symbol in that field.
+/// - `u` - The unsigned type to operate on, defaults to the underlying
+///   unsigned type of `gf`.
+/// - `width` - Width of the default finite-field in bits, this can be 8, 16,
+///   32, or 64 and determines the maximum number of shares. Defaults to 8,
+///   which limits us to 255 shares. Can't be combined with `gf`.
 /// - `rng` - The random-number generator to use for generating shares, defaults
 ///   to [`ThreadRng`][thread-rng].
 ///
@@ -380,6 +578,30 @@ pub use gf256_macros::shamir;
 #[shamir]
 pub mod shamir {}
 
+// Shamir secret-sharing over wider fields, useful for sharing aligned data
+// with fewer field evaluations
+//
+#[cfg(feature="thread-rng")]
+#[shamir(width=16)]
+pub mod shamir16 {}
+
+#[cfg(feature="thread-rng")]
+#[shamir(width=32)]
+pub mod shamir32 {}
+
+#[cfg(feature="thread-rng")]
+#[shamir(width=64)]
+pub mod shamir64 {}
+
+// Shares compatible with other Shamir's secret-sharing tools
+//
+
+#[cfg(feature="alloc")]
+pub mod ssss;
+
+#[cfg(feature="slip39")]
+pub mod slip39;
+
 
 #[cfg(test)]
 mod test {
@@ -391,6 +613,7 @@ mod test {
 
     extern crate alloc;
     use alloc::vec::Vec;
+    use alloc::vec;
 
     #[cfg(feature="thread-rng")]
     #[test]
@@ -424,6 +647,397 @@ mod test {
         }
     }
 
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_into() {
+        let input = b"Hello World!";
+        let mut shares = [[0u8; 13]; 5];
+        gf256_shamir::generate_into(input, &mut shares, 4);
+        for i in 0..5 {
+            let mut output = [0u8; 12];
+            gf256_shamir::reconstruct_into(&shares[..i], &mut output);
+            if i < 4 {
+                assert_ne!(&output, input);
+            } else {
+                assert_eq!(&output, input);
+            }
+        }
+    }
+
+    #[cfg(all(feature="thread-rng", feature="std"))]
+    #[test]
+    fn shamir_stream() {
+        // large enough to span multiple chunks
+        let input = (0..5000).map(|i| (i*7) as u8).collect::<Vec<_>>();
+        let mut shares = vec![vec![]; 5];
+        gf256_shamir::generate_stream(&input[..], &mut shares, 4).unwrap();
+        assert!(shares.iter().all(|share| share.len() == input.len()+1));
+        for i in 0..5 {
+            let mut readers = shares[..i].iter().map(|s| &s[..]).collect::<Vec<_>>();
+            let mut output = vec![];
+            gf256_shamir::reconstruct_stream(&mut readers, &mut output).unwrap();
+            if i < 4 {
+                assert_ne!(output, input);
+            } else {
+                assert_eq!(output, input);
+            }
+        }
+
+        // streams are compatible with the non-streaming functions
+        assert_eq!(gf256_shamir::reconstruct(&shares[1..]), input);
+
+        // mismatched shares are an error
+        let mut readers = [&shares[0][..], &shares[1][..100], &shares[2][..]];
+        let mut output = vec![];
+        assert!(gf256_shamir::reconstruct_stream(&mut readers, &mut output).is_err());
+    }
+
+    #[cfg(all(feature="thread-rng", feature="std"))]
+    #[test]
+    fn shamir16_stream() {
+        let input = b"Hello World!";
+        let mut shares = vec![vec![]; 1000];
+        shamir16::generate_stream(&input[..], &mut shares, 100).unwrap();
+        let mut readers = shares[500..600].iter().map(|s| &s[..]).collect::<Vec<_>>();
+        let mut output = vec![];
+        shamir16::reconstruct_stream(&mut readers, &mut output).unwrap();
+        assert_eq!(output, input);
+
+        // secret must be a multiple of the symbol size
+        let mut shares = vec![vec![]; 10];
+        assert!(shamir16::generate_stream(&b"Hello World"[..], &mut shares, 5).is_err());
+    }
+
+    #[cfg(all(feature="thread-rng", feature="zeroize"))]
+    #[test]
+    fn shamir_zeroizing() {
+        let input = b"Hello World!";
+        let shares = gf256_shamir::generate_zeroizing(input, 5, 4);
+        assert_eq!(shares.len(), 5);
+        for i in 0..5 {
+            let output = gf256_shamir::reconstruct_zeroizing(&shares[..i]);
+            if i < 4 {
+                assert_ne!(&output[..], input);
+            } else {
+                assert_eq!(&output[..], input);
+            }
+        }
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_tagged() {
+        let input = b"Hello World!";
+        let mut shares = gf256_shamir::generate_tagged(input, 5, 4);
+        assert_eq!(gf256_shamir::TAG_SIZE, 4);
+        assert!(shares.iter().all(|share| share.len() == 1+input.len()+4));
+        assert!(shares.iter().all(|share| gf256_shamir::verify(share)));
+        assert_eq!(gf256_shamir::reconstruct_tagged(&shares[..4]), Ok(input.to_vec()));
+
+        // corrupt some shares
+        shares[1][0] ^= 0x01;
+        shares[3][5] ^= 0xff;
+        shares[4][14] ^= 0x80;
+        assert_eq!(
+            gf256_shamir::invalid_shares(&shares).collect::<Vec<_>>(),
+            vec![1, 3, 4]
+        );
+        assert_eq!(
+            gf256_shamir::reconstruct_tagged(&shares),
+            Err(gf256_shamir::Error::InvalidShare(1))
+        );
+
+        // truncated shares are also invalid
+        assert!(!gf256_shamir::verify(&shares[0][..3]));
+        assert!(!gf256_shamir::verify(&[]));
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_tagged_odd_sizes() {
+        // smaller fields need more symbols for the tag
+        assert_eq!(gf16_shamir::TAG_SIZE, 8);
+        let input = [0x1, 0x2, 0x3, 0x4];
+        let mut shares = gf16_shamir::generate_tagged(&input, 15, 10);
+        assert_eq!(gf16_shamir::reconstruct_tagged(&shares[5..]), Ok(input.to_vec()));
+        shares[7][2] ^= 0x4;
+        assert_eq!(
+            gf16_shamir::reconstruct_tagged(&shares[5..]),
+            Err(gf16_shamir::Error::InvalidShare(2))
+        );
+
+        // and larger fields need less
+        assert_eq!(gf2p64_shamir::TAG_SIZE, 1);
+        let input = [0x0123456789abcdef, 0xfedcba9876543210];
+        let mut shares = gf2p64_shamir::generate_tagged(&input, 300, 100);
+        assert_eq!(gf2p64_shamir::reconstruct_tagged(&shares[..100]), Ok(input.to_vec()));
+        shares[50][1] ^= 1;
+        assert_eq!(
+            gf2p64_shamir::invalid_shares(&shares).collect::<Vec<_>>(),
+            vec![50]
+        );
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_multi() {
+        let secrets = [&b"Hello"[..], &b""[..], &b"World!"[..]];
+        let shares = gf256_shamir::generate_multi(&secrets, 5, 4);
+        assert!(shares.iter().all(|share| share.len() == 1 + 4*4 + 11));
+        for i in 0..5 {
+            let output = gf256_shamir::reconstruct_multi(&shares[..i]).unwrap();
+            if i == 0 {
+                assert_eq!(output, Vec::<Vec<u8>>::new());
+            } else if i < 4 {
+                assert_eq!(output.len(), 3);
+                assert_ne!(output, secrets);
+            } else {
+                assert_eq!(output, secrets);
+            }
+        }
+
+        // mismatched or malformed headers
+        let mut bad = shares.clone();
+        bad[2][5] ^= 0x01;
+        assert_eq!(
+            gf256_shamir::reconstruct_multi(&bad),
+            Err(gf256_shamir::Error::MismatchedShares)
+        );
+        assert_eq!(
+            gf256_shamir::reconstruct_multi(&[&shares[0][..3]]),
+            Err(gf256_shamir::Error::InvalidEncoding)
+        );
+        assert_eq!(
+            gf256_shamir::reconstruct_multi(&[&shares[0][..20]]),
+            Err(gf256_shamir::Error::InvalidEncoding)
+        );
+
+        // wider fields
+        let secrets = [&[0x1234u64, 0x5678][..], &[0x9abc][..]];
+        let shares = gf2p64_shamir::generate_multi(&secrets, 300, 100);
+        assert_eq!(
+            gf2p64_shamir::reconstruct_multi(&shares[100..200]),
+            Ok(vec![vec![0x1234, 0x5678], vec![0x9abc]])
+        );
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_reconstructor() {
+        let input = (0..1000).map(|i| (i*7) as u8).collect::<Vec<_>>();
+        let shares = gf256_shamir::generate(&input, 10, 4);
+
+        let reconstructor = gf256_shamir::Reconstructor::from_shares(&shares[3..7]);
+        assert_eq!(reconstructor.xs(), &[4, 5, 6, 7]);
+        let mut output = vec![];
+        for j in (1..1001).step_by(64) {
+            let chunks = shares[3..7].iter()
+                .map(|share| &share[j..(j+64).min(1001)])
+                .collect::<Vec<_>>();
+            output.extend(reconstructor.reconstruct(&chunks));
+        }
+        assert_eq!(output, input);
+
+        // without alloc
+        let mut output = [0u8; 16];
+        let chunks = [&shares[3][1..17], &shares[4][1..17], &shares[5][1..17], &shares[6][1..17]];
+        reconstructor.reconstruct_into(&chunks, &mut output);
+        assert_eq!(&output[..], &input[..16]);
+
+        // too few x-coordinates gives garbage
+        let reconstructor = gf256_shamir::Reconstructor::new(&[4, 5, 6]);
+        let chunks = [&shares[3][1..], &shares[4][1..], &shares[5][1..]];
+        assert_ne!(reconstructor.reconstruct(&chunks), input);
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_lagrange_basis() {
+        let input = (0..100).map(|i| (i*7) as u8).collect::<Vec<_>>();
+        let shares = gf256_shamir::generate(&input, 10, 4);
+
+        let basis = gf256_shamir::lagrange_basis_at_zero(&[2, 5, 7, 8]);
+        let output = (1..101)
+            .map(|j| {
+                [&shares[1], &shares[4], &shares[6], &shares[7]].iter()
+                    .zip(&basis)
+                    .map(|(share, l)| gf256(*l)*gf256(share[j]))
+                    .fold(gf256(0), |a, b| a+b)
+            })
+            .map(u8::from)
+            .collect::<Vec<_>>();
+        assert_eq!(output, input);
+
+        // without alloc
+        let mut basis_ = [0u8; 4];
+        gf256_shamir::lagrange_basis_into(&[2, 5, 7, 8], &mut basis_);
+        assert_eq!(&basis_[..], &basis[..]);
+
+        // basis for a single x-coordinate is trivially 1
+        assert_eq!(gf256_shamir::lagrange_basis_at_zero(&[3]), &[1]);
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_issue_share() {
+        let input = b"Hello World!";
+        let shares = gf256_shamir::generate(input, 5, 4);
+
+        // issuing an existing share gives us the same share
+        assert_eq!(gf256_shamir::issue_share(&shares[1..], 1), shares[0]);
+
+        // new shares are interchangeable with the originals
+        let mut new_shares = (6..=10)
+            .map(|x| gf256_shamir::issue_share(&shares[..4], x))
+            .collect::<Vec<_>>();
+        assert_ne!(gf256_shamir::reconstruct(&new_shares[..3]), input);
+        assert_eq!(gf256_shamir::reconstruct(&new_shares[..4]), input);
+        new_shares.extend_from_slice(&shares[3..]);
+        assert_eq!(gf256_shamir::reconstruct(&new_shares[3..]), input);
+
+        // without alloc
+        let mut share = [0u8; 13];
+        gf256_shamir::issue_share_into(&shares[1..], 0xff, &mut share);
+        assert_eq!(share[0], 0xff);
+        assert_eq!(
+            gf256_shamir::reconstruct(&[&share[..], &shares[0][..], &shares[2][..], &shares[4][..]]),
+            input
+        );
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_robust() {
+        let input = b"Hello World!";
+        let mut shares = gf256_shamir::generate(input, 12, 4);
+
+        // no errors
+        assert_eq!(gf256_shamir::reconstruct_robust(&shares, 4), Ok((input.to_vec(), vec![])));
+        assert_eq!(gf256_shamir::reconstruct_robust(&shares[..4], 4), Ok((input.to_vec(), vec![])));
+        assert_eq!(
+            gf256_shamir::reconstruct_robust(&shares[..3], 4),
+            Err(gf256_shamir::Error::TooFewShares)
+        );
+
+        // we can correct up to (n-k)/2 errors
+        shares[2][1] ^= 0x01;
+        shares[7][5] ^= 0xff;
+        shares[9][1] ^= 0x80;
+        shares[9][12] ^= 0x80;
+        assert_ne!(gf256_shamir::reconstruct(&shares), input);
+        assert_eq!(
+            gf256_shamir::reconstruct_robust(&shares, 4),
+            Ok((input.to_vec(), vec![2, 7, 9]))
+        );
+        // including invalid x coords
+        shares[4][0] = 0x42;
+        assert_eq!(
+            gf256_shamir::reconstruct_robust(&shares, 4),
+            Ok((input.to_vec(), vec![2, 4, 7, 9]))
+        );
+        // but no more, note each symbol is corrected independently, so
+        // we need too many errors in the same symbol
+        shares[10][1] ^= 0x01;
+        assert_eq!(
+            gf256_shamir::reconstruct_robust(&shares[..11], 4),
+            Err(gf256_shamir::Error::TooManyErrors)
+        );
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir16_robust() {
+        let input = [0x1234, 0x5678, 0x9abc];
+        let mut shares = shamir16::generate(&input, 1000, 100);
+        for i in (0..1000).step_by(100) {
+            shares[i][2] ^= 0x1111;
+        }
+        assert_eq!(
+            shamir16::reconstruct_robust(&shares[..200], 100),
+            Ok((input.to_vec(), vec![0, 100]))
+        );
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[gf(polynomial=0x11b, generator=0x3)]
+    type gf256_rijndael;
+    #[cfg(feature="thread-rng")]
+    #[shamir(gf=gf256_rijndael)]
+    mod rijndael_shamir {}
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_share_encoding() {
+        let input = b"Hello World!";
+        let mut shares = gf256_shamir::generate_shares(input, 5, 4);
+        shares[0].label = b"backup".to_vec();
+        let bytes = shares[0].to_bytes();
+        assert_eq!(&bytes[..6], b"gfsh\x01\x08");
+        assert_eq!(bytes.len(), 32 + 6 + 1 + 12);
+        assert_eq!(gf256_shamir::Share::from_bytes(&bytes), Ok(shares[0].clone()));
+
+        // metadata is checked
+        assert_eq!(
+            gf256_shamir::reconstruct_shares(&shares[..4]),
+            Err(gf256_shamir::Error::MismatchedShares)
+        );
+        assert_eq!(
+            gf256_shamir::reconstruct_shares(&shares[1..4]),
+            Err(gf256_shamir::Error::TooFewShares)
+        );
+        assert_eq!(gf256_shamir::reconstruct_shares(&shares[1..]), Ok(input.to_vec()));
+
+        // invalid encodings
+        let mut bad = bytes.clone();
+        bad[4] = 2;
+        assert_eq!(
+            gf256_shamir::Share::from_bytes(&bad),
+            Err(gf256_shamir::Error::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            gf256_shamir::Share::from_bytes(&bytes[..20]),
+            Err(gf256_shamir::Error::InvalidEncoding)
+        );
+        assert_eq!(
+            gf256_shamir::Share::from_bytes(b"hello world"),
+            Err(gf256_shamir::Error::InvalidEncoding)
+        );
+
+        // shares from a different field are rejected
+        let bytes = gf2p64_shamir::generate_shares(&[1, 2, 3], 5, 4)[0].to_bytes();
+        assert_eq!(bytes.len(), 32 + 8*4);
+        assert!(gf2p64_shamir::Share::from_bytes(&bytes).is_ok());
+        assert_eq!(
+            gf256_shamir::Share::from_bytes(&bytes),
+            Err(gf256_shamir::Error::MismatchedField)
+        );
+        let bytes = rijndael_shamir::generate_shares(input, 5, 4)[0].to_bytes();
+        assert_eq!(
+            gf256_shamir::Share::from_bytes(&bytes),
+            Err(gf256_shamir::Error::MismatchedField)
+        );
+    }
+
+    // with the same rng, generate/generate_into should be equivalent
+    #[shamir(rng=<rand::rngs::StdRng as rand::SeedableRng>::seed_from_u64(42))]
+    mod seeded_shamir {}
+
+    #[test]
+    fn shamir_into_matches_alloc() {
+        let input = b"Hello World!";
+        let shares = seeded_shamir::generate(input, 5, 3);
+        let mut shares_ = [[0u8; 13]; 5];
+        seeded_shamir::generate_into(input, &mut shares_, 3);
+        for (share, share_) in shares.iter().zip(&shares_) {
+            assert_eq!(&share[..], &share_[..]);
+        }
+
+        let mut output = [0u8; 12];
+        seeded_shamir::reconstruct_into(&shares_[2..], &mut output);
+        assert_eq!(&output, input);
+        assert_eq!(seeded_shamir::reconstruct(&shares[2..]), input);
+    }
+
     // multi-byte Shamir secrets
     #[cfg(feature="thread-rng")]
     #[shamir(gf=gf2p64, u=u64)]
@@ -502,6 +1116,163 @@ mod test {
         }
     }
 
+    // Shamir inferring u from gf
+    #[cfg(feature="thread-rng")]
+    #[shamir(gf=gf16)]
+    mod gf16_inferred_shamir {}
+
+    #[cfg(feature="thread-rng")]
+    #[shamir(gf=gf2p23)]
+    mod gf2p23_inferred_shamir {}
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_inferred_u() {
+        // gf16 is shared nibble-by-nibble in u8s
+        let input = b"Hello World!"
+            .iter()
+            .map(|b| [(b >> 0) & 0xf, (b >> 4) & 0xf])
+            .flatten()
+            .collect::<Vec<u8>>();
+        let shares = gf16_inferred_shamir::generate(&input, 15, 10);
+        assert_eq!(gf16_inferred_shamir::reconstruct(&shares[..9]).len(), input.len());
+        assert_ne!(gf16_inferred_shamir::reconstruct(&shares[..9]), input);
+        assert_eq!(gf16_inferred_shamir::reconstruct(&shares[..10]), input);
+        assert_eq!(gf16_inferred_shamir::reconstruct(&shares[5..]), input);
+
+        // gf2p23 is shared in u32s
+        let input = b"Hello World!"
+            .chunks(2)
+            .map(|chunk| u32::from(u16::from_le_bytes(<_>::try_from(chunk).unwrap())))
+            .collect::<Vec<u32>>();
+        let shares = gf2p23_inferred_shamir::generate(&input, 1000, 100);
+        assert_ne!(gf2p23_inferred_shamir::reconstruct(&shares[..99]), input);
+        assert_eq!(gf2p23_inferred_shamir::reconstruct(&shares[..100]), input);
+        assert_eq!(gf2p23_inferred_shamir::reconstruct(&shares[900..]), input);
+    }
+
+    // Shamir over wider default fields, note shamir16/shamir32 are
+    // provided by the parent module
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir16_300w100() {
+        let input = b"Hello World!"
+            .chunks(2)
+            .map(|chunk| u16::from_le_bytes(<_>::try_from(chunk).unwrap()))
+            .collect::<Vec<_>>();
+        let shares = shamir16::generate(&input, 300, 100);
+        assert_eq!(shares.len(), 300);
+        assert!(shares.iter().all(|share| share.len() == 1+input.len()));
+        for i in (0..300).step_by(30) {
+            let output = shamir16::reconstruct(&shares[i..]);
+            if 300-i < 100 {
+                assert_ne!(output, input);
+            } else {
+                assert_eq!(output, input);
+            }
+        }
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir32_1000w10() {
+        let input = b"Hello World!"
+            .chunks(4)
+            .map(|chunk| u32::from_le_bytes(<_>::try_from(chunk).unwrap()))
+            .collect::<Vec<_>>();
+        let shares = shamir32::generate(&input, 1000, 10);
+        assert_eq!(shares.len(), 1000);
+        for i in (0..1000).step_by(99) {
+            let output = shamir32::reconstruct(&shares[i..i+10]);
+            assert_eq!(output, input);
+            let output = shamir32::reconstruct(&shares[i..i+9]);
+            assert_ne!(output, input);
+        }
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_try_reconstruct() {
+        let input = b"Hello World!";
+        let shares = gf256_shamir::generate(input, 5, 4);
+        assert_eq!(gf256_shamir::try_reconstruct(&shares[..4]).unwrap(), input);
+        assert_eq!(gf256_shamir::try_reconstruct(&shares[1..]).unwrap(), input);
+
+        let empty: [&[u8]; 0] = [];
+        assert_eq!(
+            gf256_shamir::try_reconstruct(&empty),
+            Err(gf256_shamir::Error::TooFewShares)
+        );
+        assert_eq!(
+            gf256_shamir::try_reconstruct(&[&shares[0][..], &shares[1][..], &shares[2][..12], &shares[3][..]]),
+            Err(gf256_shamir::Error::MismatchedShares)
+        );
+        assert_eq!(
+            gf256_shamir::try_reconstruct(&[&shares[0], &shares[1], &shares[3], &shares[1]]),
+            Err(gf256_shamir::Error::DuplicateShare(3))
+        );
+        let mut zero = shares[2].clone();
+        zero[0] = 0;
+        assert_eq!(
+            gf256_shamir::try_reconstruct(&[&shares[0], &shares[1], &zero, &shares[3]]),
+            Err(gf256_shamir::Error::InvalidShare(2))
+        );
+
+        // without alloc
+        let mut output = [0u8; 12];
+        gf256_shamir::try_reconstruct_into(&shares[..4], &mut output).unwrap();
+        assert_eq!(&output, input);
+        let mut output = [0u8; 11];
+        assert_eq!(
+            gf256_shamir::try_reconstruct_into(&shares[..4], &mut output),
+            Err(gf256_shamir::Error::MismatchedShares)
+        );
+
+        // duplicates are also caught with share metadata
+        let shares = gf256_shamir::generate_shares(input, 5, 4);
+        assert_eq!(
+            gf256_shamir::reconstruct_shares(&[shares[0].clone(), shares[1].clone(), shares[2].clone(), shares[2].clone()]),
+            Err(gf256_shamir::Error::DuplicateShare(3))
+        );
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_bytes() {
+        let input = (0..1024).map(|i| (i*7) as u8).collect::<Vec<_>>();
+
+        let shares = gf256_shamir::generate_bytes(&input, 10, 4);
+        assert!(shares.iter().all(|share| share.len() == 1+1024));
+        assert_eq!(gf256_shamir::reconstruct_bytes(&shares[..4]), input);
+
+        let shares = super::shamir16::generate_bytes(&input, 10, 4);
+        assert!(shares.iter().all(|share| share.len() == 2+1024));
+        assert_eq!(super::shamir16::reconstruct_bytes(&shares[..4]), input);
+        assert_ne!(super::shamir16::reconstruct_bytes(&shares[..3]), input);
+
+        let shares = super::shamir32::generate_bytes(&input, 10, 4);
+        assert!(shares.iter().all(|share| share.len() == 4+1024));
+        assert_eq!(super::shamir32::reconstruct_bytes(&shares[3..7]), input);
+
+        let shares = super::shamir64::generate_bytes(&input, 10, 4);
+        assert!(shares.iter().all(|share| share.len() == 8+1024));
+        assert_eq!(super::shamir64::reconstruct_bytes(&shares[6..]), input);
+        assert_ne!(super::shamir64::reconstruct_bytes(&shares[7..]), input);
+    }
+
+    #[cfg(all(feature="thread-rng", feature="std"))]
+    #[test]
+    fn shamir_bytes_stream() {
+        let input = (0..1024).map(|i| (i*7) as u8).collect::<Vec<_>>();
+
+        // compatible with the streaming format
+        let shares = super::shamir64::generate_bytes(&input, 5, 4);
+        let mut output = vec![];
+        let mut readers = shares.iter().map(|s| &s[..]).collect::<Vec<_>>();
+        super::shamir64::reconstruct_stream(&mut readers[1..], &mut output).unwrap();
+        assert_eq!(output, input);
+    }
+
     // TODO test this without ThreadRng?
 
     // all Shamir parameters 
@@ -0,0 +1,955 @@
+//! Shares compatible with [SLIP-0039][slip-0039] wallets.
+//!
+//! SLIP-0039 is a standard for sharing cryptocurrency master secrets as
+//! mnemonic phrases, supported by hardware wallets such as Trezor. This
+//! module generates and recovers SLIP-0039 mnemonics, so secrets can be
+//! migrated between this crate and SLIP-0039 wallets in either direction.
+//!
+//! ``` rust
+//! # use ::gf256::shamir::slip39;
+//! // test vector from SLIP-0039, a 2-of-3 share
+//! let mnemonics = [
+//!     "shadow pistol academic always adequate wildlife fancy gross oasis \
+//!      cylinder mustang wrist rescue view short owner flip making coding armed",
+//!     "shadow pistol academic acid actress prayer class unknown daughter \
+//!      sweater depict flip twice unkind craft early superior advocate guest smoking",
+//! ];
+//! assert_eq!(
+//!     slip39::reconstruct(&mnemonics, b"TREZOR").unwrap(),
+//!     b"\xb4\x3c\xeb\x7e\x57\xa0\xea\x87\x66\x22\x16\x24\xd0\x1b\x08\x64"
+//! );
+//!
+//! // and mnemonics for a wallet, 1 group of 3 shares, 2 of which
+//! // are needed to recover the secret
+//! # #[cfg(feature="thread-rng")]
+//! # {
+//! let secret = b"sixteen byte key";
+//! let groups = slip39::generate(secret, b"TREZOR", 1, &[(2, 3)]).unwrap();
+//! assert_eq!(groups[0].len(), 3);
+//! assert_eq!(slip39::reconstruct(&groups[0][1..], b"TREZOR").unwrap(), secret);
+//! # }
+//! ```
+//!
+//! ## How does SLIP-0039 work?
+//!
+//! SLIP-0039 shares byte-by-byte over the Rijndael field `GF(256)`, with the
+//! polynomial `0x11b`, like [`shamir`](macro@crate::shamir::shamir), but adds a few
+//! layers on top:
+//!
+//! 1. The master secret is first encrypted with a passphrase, using a 4-round
+//!    Feistel network with PBKDF2-HMAC-SHA256 as the round function. Any
+//!    passphrase decrypts to a valid secret, which allows plausible
+//!    deniability.
+//!
+//! 2. The encrypted secret is split in two levels: into groups, a threshold of
+//!    which are needed, and then each group into member shares, a threshold of
+//!    which are needed to recover the group's share.
+//!
+//! 3. At each level, the secret is placed at x=255, and a digest at x=254,
+//!    an HMAC-SHA256 of the secret keyed by random bytes. This lets
+//!    reconstruction detect invalid shares. The remaining shares are placed
+//!    at x=0, 1, 2, etc.
+//!
+//! 4. Each share is encoded as a mnemonic of 10-bit words, prefixed with a
+//!    random identifier and the share's parameters, and suffixed with an
+//!    RS1024 checksum, a Reed-Solomon code over `GF(1024)`.
+//!
+//! Master secrets must be at least 16 bytes and an even number of bytes.
+//!
+//! [slip-0039]: https://github.com/satoshilabs/slips/blob/master/slip-0039.md
+//!
+
+use core::fmt;
+use core::convert::TryFrom;
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::vec;
+use alloc::string::String;
+use alloc::collections::BTreeMap;
+use rand::Rng;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use pbkdf2::pbkdf2_hmac;
+use crate::gf::gf;
+
+
+/// Error codes for SLIP-0039 shares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Master secrets must be at least 16 bytes and an even number of bytes
+    InvalidSecret,
+
+    /// Passphrases must be printable ASCII
+    InvalidPassphrase,
+
+    /// The thresholds, share counts, or iteration exponent are out of range
+    InvalidParameters,
+
+    /// A mnemonic contained an unknown word
+    InvalidWord,
+
+    /// A mnemonic's checksum didn't match
+    InvalidChecksum,
+
+    /// A mnemonic had an invalid length or padding
+    InvalidEncoding,
+
+    /// Shares are from different secrets or have different parameters
+    MismatchedShares,
+
+    /// Not enough shares or groups to reach the threshold
+    TooFewShares,
+
+    /// The recovered digest didn't match, at least one share is invalid
+    InvalidDigest,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidSecret => write!(f, "Invalid master secret length"),
+            Error::InvalidPassphrase => write!(f, "Passphrase must be printable ASCII"),
+            Error::InvalidParameters => write!(f, "Invalid thresholds or share counts"),
+            Error::InvalidWord => write!(f, "Invalid mnemonic word"),
+            Error::InvalidChecksum => write!(f, "Invalid mnemonic checksum"),
+            Error::InvalidEncoding => write!(f, "Invalid mnemonic encoding"),
+            Error::MismatchedShares => write!(f, "Mismatched shares"),
+            Error::TooFewShares => write!(f, "Too few shares to reconstruct secret"),
+            Error::InvalidDigest => write!(f, "Invalid digest, shares may be corrupted"),
+        }
+    }
+}
+
+/// Options for generating SLIP-0039 shares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// Allow new shares to be added later with a different passphrase,
+    /// this omits the identifier from the encryption
+    pub extendable: bool,
+
+    /// The PBKDF2 iteration count is `10000 << iteration_exponent`, must
+    /// be less than 16
+    pub iteration_exponent: u8,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            extendable: true,
+            iteration_exponent: 1,
+        }
+    }
+}
+
+
+// HMAC-SHA256, as required by SLIP-0039
+//
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    // HMAC accepts keys of any length
+    let mut mac = HmacSha256::new_from_slice(key).unwrap();
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+
+// The passphrase encryption
+//
+
+fn salt_prefix(identifier: u16, extendable: bool) -> Vec<u8> {
+    if extendable {
+        Vec::new()
+    } else {
+        let mut salt = b"shamir".to_vec();
+        salt.extend_from_slice(&identifier.to_be_bytes());
+        salt
+    }
+}
+
+fn round_function(
+    i: u8,
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    salt: &[u8],
+    r: &[u8],
+) -> Vec<u8> {
+    let mut password = vec![i];
+    password.extend_from_slice(passphrase);
+    let mut salt = salt.to_vec();
+    salt.extend_from_slice(r);
+
+    let mut f = vec![0; r.len()];
+    pbkdf2_hmac::<Sha256>(
+        &password,
+        &salt,
+        (10000 << iteration_exponent) / 4,
+        &mut f,
+    );
+    f
+}
+
+fn crypt(
+    secret: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+    encrypt: bool,
+) -> Vec<u8> {
+    let salt = salt_prefix(identifier, extendable);
+    let (l, r) = secret.split_at(secret.len()/2);
+    let (mut l, mut r) = (l.to_vec(), r.to_vec());
+    for i in 0..4 {
+        let i = if encrypt { i } else { 3-i };
+        let f = round_function(i, passphrase, iteration_exponent, &salt, &r);
+        for (l, f) in l.iter_mut().zip(f) {
+            *l ^= f;
+        }
+        core::mem::swap(&mut l, &mut r);
+    }
+    r.extend_from_slice(&l);
+    r
+}
+
+
+// Sharing over GF(256)
+//
+
+#[gf(polynomial=0x11b, generator=0x3, barret)]
+type gf256_rijndael;
+
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const DIGEST_SIZE: usize = 4;
+
+/// Evaluate the polynomial defined by a set of points at x
+fn interpolate(points: &[(u8, &[u8])], x: u8) -> Vec<u8> {
+    if let Some((_, y)) = points.iter().find(|(x_, _)| *x_ == x) {
+        return y.to_vec();
+    }
+
+    let x = gf256_rijndael(x);
+    let mut value = vec![gf256_rijndael(0); points[0].1.len()];
+    for (i, (x0, y0)) in points.iter().enumerate() {
+        let x0 = gf256_rijndael(*x0);
+        let mut basis = gf256_rijndael(1);
+        for (j, (x1, _)) in points.iter().enumerate() {
+            if i != j {
+                let x1 = gf256_rijndael(*x1);
+                basis *= (x - x1) / (x0 - x1);
+            }
+        }
+        for (v, y) in value.iter_mut().zip(y0.iter()) {
+            *v += gf256_rijndael(*y) * basis;
+        }
+    }
+    value.into_iter().map(u8::from).collect()
+}
+
+fn digest(random: &[u8], secret: &[u8]) -> [u8; DIGEST_SIZE] {
+    let mac = hmac_sha256(random, secret);
+    <[u8; DIGEST_SIZE]>::try_from(&mac[..DIGEST_SIZE]).unwrap()
+}
+
+fn split_secret<R: Rng>(
+    rng: &mut R,
+    threshold: usize,
+    count: usize,
+    secret: &[u8],
+) -> Vec<Vec<u8>> {
+    if threshold == 1 {
+        return vec![secret.to_vec(); count];
+    }
+
+    let mut shares = Vec::with_capacity(count);
+    for _ in 0..threshold-2 {
+        let mut share = vec![0; secret.len()];
+        rng.fill_bytes(&mut share);
+        shares.push(share);
+    }
+
+    let mut digest_share = vec![0; secret.len()];
+    rng.fill_bytes(&mut digest_share[DIGEST_SIZE..]);
+    let d = digest(&digest_share[DIGEST_SIZE..], secret);
+    digest_share[..DIGEST_SIZE].copy_from_slice(&d);
+
+    let mut points = shares.iter()
+        .enumerate()
+        .map(|(i, y)| (u8::try_from(i).unwrap(), &y[..]))
+        .collect::<Vec<_>>();
+    points.push((DIGEST_INDEX, &digest_share));
+    points.push((SECRET_INDEX, secret));
+    let rest = (threshold-2..count)
+        .map(|i| interpolate(&points, u8::try_from(i).unwrap()))
+        .collect::<Vec<_>>();
+
+    shares.extend(rest);
+    shares
+}
+
+fn recover_secret(threshold: usize, points: &[(u8, &[u8])]) -> Result<Vec<u8>, Error> {
+    if threshold == 1 {
+        return Ok(points[0].1.to_vec());
+    }
+
+    let secret = interpolate(points, SECRET_INDEX);
+    let digest_share = interpolate(points, DIGEST_INDEX);
+    if digest(&digest_share[DIGEST_SIZE..], &secret) != digest_share[..DIGEST_SIZE] {
+        return Err(Error::InvalidDigest);
+    }
+    Ok(secret)
+}
+
+
+// Mnemonic encoding
+//
+
+const RADIX_BITS: usize = 10;
+const CHECKSUM_WORDS: usize = 3;
+const MIN_MNEMONIC_WORDS: usize = 20;
+const MAX_SHARES: usize = 16;
+
+const RS1024_GEN: [u32; 10] = [
+    0x00e0e040, 0x01c1c080, 0x03838100, 0x07070200, 0x0e0e0009,
+    0x1c0c2412, 0x38086c24, 0x3090fc48, 0x21b1f890, 0x03f3f120,
+];
+
+fn rs1024_polymod(values: impl Iterator<Item=u16>) -> u32 {
+    let mut chk = 1u32;
+    for v in values {
+        let b = chk >> 20;
+        chk = ((chk & 0xfffff) << 10) ^ u32::from(v);
+        for (i, g) in RS1024_GEN.iter().enumerate() {
+            if (b >> i) & 1 != 0 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+    if extendable {
+        b"shamir_extendable"
+    } else {
+        b"shamir"
+    }
+}
+
+/// A single SLIP-0039 share, as encoded in a mnemonic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    /// A random 15-bit identifier shared by all shares of a secret
+    pub identifier: u16,
+    /// Whether the identifier is omitted from the encryption
+    pub extendable: bool,
+    /// The PBKDF2 iteration count is `10000 << iteration_exponent`
+    pub iteration_exponent: u8,
+    /// The x-coordinate of this share's group
+    pub group_index: u8,
+    /// The number of groups needed to reconstruct the secret
+    pub group_threshold: u8,
+    /// The total number of groups
+    pub group_count: u8,
+    /// The x-coordinate of this share within its group
+    pub member_index: u8,
+    /// The number of shares needed to reconstruct this group's share
+    pub member_threshold: u8,
+    /// The y-coordinates of this share
+    pub value: Vec<u8>,
+}
+
+impl Share {
+    /// Decode a share from a mnemonic, checking its checksum.
+    ///
+    /// ``` rust
+    /// # use ::gf256::shamir::slip39;
+    /// let share = slip39::Share::from_mnemonic(
+    ///     "duckling enlarge academic academic agency result length solution \
+    ///      fridge kidney coal piece deal husband erode duke ajar critical \
+    ///      decision keyboard"
+    /// ).unwrap();
+    /// assert_eq!(share.identifier, 7945);
+    /// assert_eq!(share.group_threshold, 1);
+    /// assert_eq!(share.member_threshold, 1);
+    /// assert_eq!(share.value.len(), 16);
+    /// ```
+    ///
+    pub fn from_mnemonic(mnemonic: &str) -> Result<Share, Error> {
+        let words = mnemonic.split_whitespace()
+            .map(|w| {
+                WORDLIST.binary_search(&w)
+                    .map(|i| u16::try_from(i).unwrap())
+                    .map_err(|_| Error::InvalidWord)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if words.len() < MIN_MNEMONIC_WORDS {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let value_words = &words[4..words.len()-CHECKSUM_WORDS];
+        let padding = (RADIX_BITS*value_words.len()) % 16;
+        if padding > 8 {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let id_exp = (u32::from(words[0]) << 10) | u32::from(words[1]);
+        let extendable = (id_exp >> 4) & 1 != 0;
+        let chk = rs1024_polymod(
+            customization(extendable).iter().map(|b| u16::from(*b))
+                .chain(words.iter().copied())
+        );
+        if chk != 1 {
+            return Err(Error::InvalidChecksum);
+        }
+
+        // big-endian bits, with zero-padding at the front
+        let mut value = Vec::with_capacity((RADIX_BITS*value_words.len() - padding) / 8);
+        let mut acc = u32::from(value_words[0]);
+        let mut bits = RADIX_BITS - padding;
+        if acc >> bits != 0 {
+            return Err(Error::InvalidEncoding);
+        }
+        for w in &value_words[1..] {
+            acc = (acc << RADIX_BITS) | u32::from(*w);
+            bits += RADIX_BITS;
+            while bits >= 8 {
+                bits -= 8;
+                value.push((acc >> bits) as u8);
+                acc &= (1 << bits) - 1;
+            }
+        }
+
+        let params = (u32::from(words[2]) << 10) | u32::from(words[3]);
+        let share = Share {
+            identifier: (id_exp >> 5) as u16,
+            extendable,
+            iteration_exponent: (id_exp & 0xf) as u8,
+            group_index: (params >> 16) as u8,
+            group_threshold: ((params >> 12) & 0xf) as u8 + 1,
+            group_count: ((params >> 8) & 0xf) as u8 + 1,
+            member_index: ((params >> 4) & 0xf) as u8,
+            member_threshold: (params & 0xf) as u8 + 1,
+            value,
+        };
+        if share.group_count < share.group_threshold {
+            return Err(Error::InvalidEncoding);
+        }
+
+        Ok(share)
+    }
+
+    /// Encode a share as a mnemonic, including its checksum.
+    ///
+    /// ``` rust
+    /// # use ::gf256::shamir::slip39;
+    /// let mnemonic = "duckling enlarge academic academic agency result length \
+    ///     solution fridge kidney coal piece deal husband erode duke ajar \
+    ///     critical decision keyboard";
+    /// let share = slip39::Share::from_mnemonic(mnemonic).unwrap();
+    /// assert_eq!(
+    ///     share.to_mnemonic().split_whitespace().collect::<Vec<_>>(),
+    ///     mnemonic.split_whitespace().collect::<Vec<_>>(),
+    /// );
+    /// ```
+    ///
+    pub fn to_mnemonic(&self) -> String {
+        let id_exp = (u32::from(self.identifier & 0x7fff) << 5)
+            | (u32::from(self.extendable) << 4)
+            | u32::from(self.iteration_exponent & 0xf);
+        let params = (u32::from(self.group_index & 0xf) << 16)
+            | (u32::from((self.group_threshold-1) & 0xf) << 12)
+            | (u32::from((self.group_count-1) & 0xf) << 8)
+            | (u32::from(self.member_index & 0xf) << 4)
+            | u32::from((self.member_threshold-1) & 0xf);
+
+        let mut words = vec![
+            (id_exp >> 10) as u16, (id_exp & 0x3ff) as u16,
+            (params >> 10) as u16, (params & 0x3ff) as u16,
+        ];
+
+        // big-endian bits, with zero-padding at the front
+        let value_words = (8*self.value.len()).div_ceil(RADIX_BITS);
+        let mut bits = RADIX_BITS*value_words - 8*self.value.len();
+        let mut acc = 0u32;
+        for b in &self.value {
+            acc = (acc << 8) | u32::from(*b);
+            bits += 8;
+            if bits >= RADIX_BITS {
+                bits -= RADIX_BITS;
+                words.push((acc >> bits) as u16);
+                acc &= (1 << bits) - 1;
+            }
+        }
+
+        let chk = rs1024_polymod(
+            customization(self.extendable).iter().map(|b| u16::from(*b))
+                .chain(words.iter().copied())
+                .chain([0; CHECKSUM_WORDS])
+        ) ^ 1;
+        for i in (0..CHECKSUM_WORDS).rev() {
+            words.push(((chk >> (RADIX_BITS*i)) & 0x3ff) as u16);
+        }
+
+        words.iter()
+            .map(|w| WORDLIST[usize::from(*w)])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+
+// Share generation/reconstruction
+//
+
+/// Generate SLIP-0039 mnemonics for a master secret.
+///
+/// `groups` contains each group's member threshold and member count, and
+/// `group_threshold` groups are needed to reconstruct the secret. This uses
+/// the same defaults as the reference implementation, extendable shares with
+/// an iteration exponent of 1.
+///
+/// ``` rust
+/// # use ::gf256::shamir::slip39;
+/// // either the first group alone, or 2 of 3 shares in each of
+/// // the other groups
+/// let secret = b"sixteen byte key";
+/// let groups = slip39::generate(secret, b"", 2, &[(1, 1), (2, 3), (2, 3)]).unwrap();
+///
+/// let mut mnemonics = vec![groups[0][0].clone()];
+/// mnemonics.extend_from_slice(&groups[2][1..]);
+/// assert_eq!(slip39::reconstruct(&mnemonics, b"").unwrap(), secret);
+/// ```
+///
+#[cfg(feature="thread-rng")]
+pub fn generate(
+    master_secret: &[u8],
+    passphrase: &[u8],
+    group_threshold: usize,
+    groups: &[(usize, usize)],
+) -> Result<Vec<Vec<String>>, Error> {
+    generate_with(
+        &mut rand::thread_rng(),
+        master_secret,
+        passphrase,
+        group_threshold,
+        groups,
+        &Options::default(),
+    )
+}
+
+/// Generate SLIP-0039 mnemonics for a master secret with a custom Rng and
+/// options.
+///
+pub fn generate_with<R: Rng>(
+    rng: &mut R,
+    master_secret: &[u8],
+    passphrase: &[u8],
+    group_threshold: usize,
+    groups: &[(usize, usize)],
+    options: &Options,
+) -> Result<Vec<Vec<String>>, Error> {
+    if master_secret.len() < 16 || !master_secret.len().is_multiple_of(2) {
+        return Err(Error::InvalidSecret);
+    }
+    if !passphrase.iter().all(|c| (32..=126).contains(c)) {
+        return Err(Error::InvalidPassphrase);
+    }
+    if group_threshold < 1
+        || group_threshold > groups.len()
+        || groups.len() > MAX_SHARES
+        || options.iteration_exponent > 0xf
+        || groups.iter().any(|&(t, n)| {
+            t < 1 || t > n || n > MAX_SHARES || (t == 1 && n > 1)
+        })
+    {
+        return Err(Error::InvalidParameters);
+    }
+
+    let identifier = rng.gen::<u16>() & 0x7fff;
+    let encrypted = crypt(
+        master_secret,
+        passphrase,
+        options.iteration_exponent,
+        identifier,
+        options.extendable,
+        true,
+    );
+
+    let group_shares = split_secret(rng, group_threshold, groups.len(), &encrypted);
+    let mut mnemonics = Vec::with_capacity(groups.len());
+    for (i, (&(t, n), group_share)) in groups.iter().zip(&group_shares).enumerate() {
+        let member_shares = split_secret(rng, t, n, group_share);
+        mnemonics.push(
+            member_shares.into_iter()
+                .enumerate()
+                .map(|(j, value)| {
+                    Share {
+                        identifier,
+                        extendable: options.extendable,
+                        iteration_exponent: options.iteration_exponent,
+                        group_index: u8::try_from(i).unwrap(),
+                        group_threshold: u8::try_from(group_threshold).unwrap(),
+                        group_count: u8::try_from(groups.len()).unwrap(),
+                        member_index: u8::try_from(j).unwrap(),
+                        member_threshold: u8::try_from(t).unwrap(),
+                        value,
+                    }.to_mnemonic()
+                })
+                .collect()
+        );
+    }
+
+    Ok(mnemonics)
+}
+
+/// Reconstruct a master secret from SLIP-0039 mnemonics.
+///
+/// The mnemonics may come from any groups, extra shares beyond each
+/// threshold are ignored.
+///
+pub fn reconstruct<S: AsRef<str>>(
+    mnemonics: &[S],
+    passphrase: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let shares = mnemonics.iter()
+        .map(|m| Share::from_mnemonic(m.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let first = shares.first().ok_or(Error::TooFewShares)?;
+    if shares.iter().any(|s| {
+        s.identifier != first.identifier
+            || s.extendable != first.extendable
+            || s.iteration_exponent != first.iteration_exponent
+            || s.group_threshold != first.group_threshold
+            || s.group_count != first.group_count
+            || s.value.len() != first.value.len()
+    }) {
+        return Err(Error::MismatchedShares);
+    }
+
+    // sort shares into groups, ignoring duplicates
+    let mut groups = BTreeMap::<u8, Vec<&Share>>::new();
+    for share in &shares {
+        let group = groups.entry(share.group_index).or_default();
+        if group.iter().any(|s| s.member_threshold != share.member_threshold) {
+            return Err(Error::MismatchedShares);
+        }
+        match group.iter().find(|s| s.member_index == share.member_index) {
+            Some(s) if s.value != share.value => return Err(Error::MismatchedShares),
+            Some(_) => {}
+            None => group.push(share),
+        }
+    }
+
+    // recover each group's share
+    let mut group_shares = Vec::new();
+    for (group_index, members) in &groups {
+        let threshold = usize::from(members[0].member_threshold);
+        if members.len() >= threshold {
+            let points = members[..threshold].iter()
+                .map(|s| (s.member_index, &s.value[..]))
+                .collect::<Vec<_>>();
+            group_shares.push((*group_index, recover_secret(threshold, &points)?));
+        }
+    }
+
+    // recover the encrypted master secret
+    let threshold = usize::from(first.group_threshold);
+    if group_shares.len() < threshold {
+        return Err(Error::TooFewShares);
+    }
+    let points = group_shares[..threshold].iter()
+        .map(|(x, y)| (*x, &y[..]))
+        .collect::<Vec<_>>();
+    let encrypted = recover_secret(threshold, &points)?;
+
+    Ok(crypt(
+        &encrypted,
+        passphrase,
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+        false,
+    ))
+}
+
+
+/// The SLIP-0039 wordlist, sorted, each word has a unique 4-letter prefix
+#[rustfmt::skip]
+const WORDLIST: [&str; 1024] = [
+    "academic", "acid", "acne", "acquire", "acrobat", "activity", "actress", "adapt",
+    "adequate", "adjust", "admit", "adorn", "adult", "advance", "advocate", "afraid",
+    "again", "agency", "agree", "aide", "aircraft", "airline", "airport", "ajar",
+    "alarm", "album", "alcohol", "alien", "alive", "alpha", "already", "alto",
+    "aluminum", "always", "amazing", "ambition", "amount", "amuse", "analysis", "anatomy",
+    "ancestor", "ancient", "angel", "angry", "animal", "answer", "antenna", "anxiety",
+    "apart", "aquatic", "arcade", "arena", "argue", "armed", "artist", "artwork",
+    "aspect", "auction", "august", "aunt", "average", "aviation", "avoid", "award",
+    "away", "axis", "axle", "beam", "beard", "beaver", "become", "bedroom",
+    "behavior", "being", "believe", "belong", "benefit", "best", "beyond", "bike",
+    "biology", "birthday", "bishop", "black", "blanket", "blessing", "blimp", "blind",
+    "blue", "body", "bolt", "boring", "born", "both", "boundary", "bracelet",
+    "branch", "brave", "breathe", "briefing", "broken", "brother", "browser", "bucket",
+    "budget", "building", "bulb", "bulge", "bumpy", "bundle", "burden", "burning",
+    "busy", "buyer", "cage", "calcium", "camera", "campus", "canyon", "capacity",
+    "capital", "capture", "carbon", "cards", "careful", "cargo", "carpet", "carve",
+    "category", "cause", "ceiling", "center", "ceramic", "champion", "change", "charity",
+    "check", "chemical", "chest", "chew", "chubby", "cinema", "civil", "class",
+    "clay", "cleanup", "client", "climate", "clinic", "clock", "clogs", "closet",
+    "clothes", "club", "cluster", "coal", "coastal", "coding", "column", "company",
+    "corner", "costume", "counter", "course", "cover", "cowboy", "cradle", "craft",
+    "crazy", "credit", "cricket", "criminal", "crisis", "critical", "crowd", "crucial",
+    "crunch", "crush", "crystal", "cubic", "cultural", "curious", "curly", "custody",
+    "cylinder", "daisy", "damage", "dance", "darkness", "database", "daughter", "deadline",
+    "deal", "debris", "debut", "decent", "decision", "declare", "decorate", "decrease",
+    "deliver", "demand", "density", "deny", "depart", "depend", "depict", "deploy",
+    "describe", "desert", "desire", "desktop", "destroy", "detailed", "detect", "device",
+    "devote", "diagnose", "dictate", "diet", "dilemma", "diminish", "dining", "diploma",
+    "disaster", "discuss", "disease", "dish", "dismiss", "display", "distance", "dive",
+    "divorce", "document", "domain", "domestic", "dominant", "dough", "downtown", "dragon",
+    "dramatic", "dream", "dress", "drift", "drink", "drove", "drug", "dryer",
+    "duckling", "duke", "duration", "dwarf", "dynamic", "early", "earth", "easel",
+    "easy", "echo", "eclipse", "ecology", "edge", "editor", "educate", "either",
+    "elbow", "elder", "election", "elegant", "element", "elephant", "elevator", "elite",
+    "else", "email", "emerald", "emission", "emperor", "emphasis", "employer", "empty",
+    "ending", "endless", "endorse", "enemy", "energy", "enforce", "engage", "enjoy",
+    "enlarge", "entrance", "envelope", "envy", "epidemic", "episode", "equation", "equip",
+    "eraser", "erode", "escape", "estate", "estimate", "evaluate", "evening", "evidence",
+    "evil", "evoke", "exact", "example", "exceed", "exchange", "exclude", "excuse",
+    "execute", "exercise", "exhaust", "exotic", "expand", "expect", "explain", "express",
+    "extend", "extra", "eyebrow", "facility", "fact", "failure", "faint", "fake",
+    "false", "family", "famous", "fancy", "fangs", "fantasy", "fatal", "fatigue",
+    "favorite", "fawn", "fiber", "fiction", "filter", "finance", "findings", "finger",
+    "firefly", "firm", "fiscal", "fishing", "fitness", "flame", "flash", "flavor",
+    "flea", "flexible", "flip", "float", "floral", "fluff", "focus", "forbid",
+    "force", "forecast", "forget", "formal", "fortune", "forward", "founder", "fraction",
+    "fragment", "frequent", "freshman", "friar", "fridge", "friendly", "frost", "froth",
+    "frozen", "fumes", "funding", "furl", "fused", "galaxy", "game", "garbage",
+    "garden", "garlic", "gasoline", "gather", "general", "genius", "genre", "genuine",
+    "geology", "gesture", "glad", "glance", "glasses", "glen", "glimpse", "goat",
+    "golden", "graduate", "grant", "grasp", "gravity", "gray", "greatest", "grief",
+    "grill", "grin", "grocery", "gross", "group", "grownup", "grumpy", "guard",
+    "guest", "guilt", "guitar", "gums", "hairy", "hamster", "hand", "hanger",
+    "harvest", "have", "havoc", "hawk", "hazard", "headset", "health", "hearing",
+    "heat", "helpful", "herald", "herd", "hesitate", "hobo", "holiday", "holy",
+    "home", "hormone", "hospital", "hour", "huge", "human", "humidity", "hunting",
+    "husband", "hush", "husky", "hybrid", "idea", "identify", "idle", "image",
+    "impact", "imply", "improve", "impulse", "include", "income", "increase", "index",
+    "indicate", "industry", "infant", "inform", "inherit", "injury", "inmate", "insect",
+    "inside", "install", "intend", "intimate", "invasion", "involve", "iris", "island",
+    "isolate", "item", "ivory", "jacket", "jerky", "jewelry", "join", "judicial",
+    "juice", "jump", "junction", "junior", "junk", "jury", "justice", "kernel",
+    "keyboard", "kidney", "kind", "kitchen", "knife", "knit", "laden", "ladle",
+    "ladybug", "lair", "lamp", "language", "large", "laser", "laundry", "lawsuit",
+    "leader", "leaf", "learn", "leaves", "lecture", "legal", "legend", "legs",
+    "lend", "length", "level", "liberty", "library", "license", "lift", "likely",
+    "lilac", "lily", "lips", "liquid", "listen", "literary", "living", "lizard",
+    "loan", "lobe", "location", "losing", "loud", "loyalty", "luck", "lunar",
+    "lunch", "lungs", "luxury", "lying", "lyrics", "machine", "magazine", "maiden",
+    "mailman", "main", "makeup", "making", "mama", "manager", "mandate", "mansion",
+    "manual", "marathon", "march", "market", "marvel", "mason", "material", "math",
+    "maximum", "mayor", "meaning", "medal", "medical", "member", "memory", "mental",
+    "merchant", "merit", "method", "metric", "midst", "mild", "military", "mineral",
+    "minister", "miracle", "mixed", "mixture", "mobile", "modern", "modify", "moisture",
+    "moment", "morning", "mortgage", "mother", "mountain", "mouse", "move", "much",
+    "mule", "multiple", "muscle", "museum", "music", "mustang", "nail", "national",
+    "necklace", "negative", "nervous", "network", "news", "nuclear", "numb", "numerous",
+    "nylon", "oasis", "obesity", "object", "observe", "obtain", "ocean", "often",
+    "olympic", "omit", "oral", "orange", "orbit", "order", "ordinary", "organize",
+    "ounce", "oven", "overall", "owner", "paces", "pacific", "package", "paid",
+    "painting", "pajamas", "pancake", "pants", "papa", "paper", "parcel", "parking",
+    "party", "patent", "patrol", "payment", "payroll", "peaceful", "peanut", "peasant",
+    "pecan", "penalty", "pencil", "percent", "perfect", "permit", "petition", "phantom",
+    "pharmacy", "photo", "phrase", "physics", "pickup", "picture", "piece", "pile",
+    "pink", "pipeline", "pistol", "pitch", "plains", "plan", "plastic", "platform",
+    "playoff", "pleasure", "plot", "plunge", "practice", "prayer", "preach", "predator",
+    "pregnant", "premium", "prepare", "presence", "prevent", "priest", "primary", "priority",
+    "prisoner", "privacy", "prize", "problem", "process", "profile", "program", "promise",
+    "prospect", "provide", "prune", "public", "pulse", "pumps", "punish", "puny",
+    "pupal", "purchase", "purple", "python", "quantity", "quarter", "quick", "quiet",
+    "race", "racism", "radar", "railroad", "rainbow", "raisin", "random", "ranked",
+    "rapids", "raspy", "reaction", "realize", "rebound", "rebuild", "recall", "receiver",
+    "recover", "regret", "regular", "reject", "relate", "remember", "remind", "remove",
+    "render", "repair", "repeat", "replace", "require", "rescue", "research", "resident",
+    "response", "result", "retailer", "retreat", "reunion", "revenue", "review", "reward",
+    "rhyme", "rhythm", "rich", "rival", "river", "robin", "rocky", "romantic",
+    "romp", "roster", "round", "royal", "ruin", "ruler", "rumor", "sack",
+    "safari", "salary", "salon", "salt", "satisfy", "satoshi", "saver", "says",
+    "scandal", "scared", "scatter", "scene", "scholar", "science", "scout", "scramble",
+    "screw", "script", "scroll", "seafood", "season", "secret", "security", "segment",
+    "senior", "shadow", "shaft", "shame", "shaped", "sharp", "shelter", "sheriff",
+    "short", "should", "shrimp", "sidewalk", "silent", "silver", "similar", "simple",
+    "single", "sister", "skin", "skunk", "slap", "slavery", "sled", "slice",
+    "slim", "slow", "slush", "smart", "smear", "smell", "smirk", "smith",
+    "smoking", "smug", "snake", "snapshot", "sniff", "society", "software", "soldier",
+    "solution", "soul", "source", "space", "spark", "speak", "species", "spelling",
+    "spend", "spew", "spider", "spill", "spine", "spirit", "spit", "spray",
+    "sprinkle", "square", "squeeze", "stadium", "staff", "standard", "starting", "station",
+    "stay", "steady", "step", "stick", "stilt", "story", "strategy", "strike",
+    "style", "subject", "submit", "sugar", "suitable", "sunlight", "superior", "surface",
+    "surprise", "survive", "sweater", "swimming", "swing", "switch", "symbolic", "sympathy",
+    "syndrome", "system", "tackle", "tactics", "tadpole", "talent", "task", "taste",
+    "taught", "taxi", "teacher", "teammate", "teaspoon", "temple", "tenant", "tendency",
+    "tension", "terminal", "testify", "texture", "thank", "that", "theater", "theory",
+    "therapy", "thorn", "threaten", "thumb", "thunder", "ticket", "tidy", "timber",
+    "timely", "ting", "tofu", "together", "tolerate", "total", "toxic", "tracks",
+    "traffic", "training", "transfer", "trash", "traveler", "treat", "trend", "trial",
+    "tricycle", "trip", "triumph", "trouble", "true", "trust", "twice", "twin",
+    "type", "typical", "ugly", "ultimate", "umbrella", "uncover", "undergo", "unfair",
+    "unfold", "unhappy", "union", "universe", "unkind", "unknown", "unusual", "unwrap",
+    "upgrade", "upstairs", "username", "usher", "usual", "valid", "valuable", "vampire",
+    "vanish", "various", "vegan", "velvet", "venture", "verdict", "verify", "very",
+    "veteran", "vexed", "victim", "video", "view", "vintage", "violence", "viral",
+    "visitor", "visual", "vitamins", "vocal", "voice", "volume", "voter", "voting",
+    "walnut", "warmth", "warn", "watch", "wavy", "wealthy", "weapon", "webcam",
+    "welcome", "welfare", "western", "width", "wildlife", "window", "wine", "wireless",
+    "wisdom", "withdraw", "wits", "wolf", "woman", "work", "worthy", "wrap",
+    "wrist", "writing", "wrote", "year", "yelp", "yield", "yoga", "zero",
+];
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| alloc::format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn slip39_hashes() {
+        assert_eq!(
+            hex(&hmac_sha256(&[b'k'; 100], b"msg")),
+            "bd56a1782c2830e8abc6ed866a57a1230661e650b84c62f7ee3accc5fa5af491"
+        );
+    }
+
+    #[test]
+    fn slip39_wordlist() {
+        assert!(WORDLIST.windows(2).all(|w| w[0][..4] < w[1][..4]));
+        assert!(WORDLIST.iter().all(|w| (4..=8).contains(&w.len())));
+    }
+
+    #[test]
+    fn slip39_vectors() {
+        // test vectors from SLIP-0039
+        let mnemonic = "duckling enlarge academic academic agency result length \
+            solution fridge kidney coal piece deal husband erode duke ajar \
+            critical decision keyboard";
+        assert_eq!(
+            reconstruct(&[mnemonic], b"TREZOR").map(|s| hex(&s)),
+            Ok("bb54aac4b89dc868ba37d9cc21b2cece".into())
+        );
+        let share = Share::from_mnemonic(mnemonic).unwrap();
+        assert_eq!(
+            share.to_mnemonic(),
+            mnemonic.split_whitespace().collect::<Vec<_>>().join(" ")
+        );
+
+        let mnemonics = [
+            "shadow pistol academic always adequate wildlife fancy gross oasis \
+             cylinder mustang wrist rescue view short owner flip making coding armed",
+            "shadow pistol academic acid actress prayer class unknown daughter \
+             sweater depict flip twice unkind craft early superior advocate guest smoking",
+        ];
+        assert_eq!(
+            reconstruct(&mnemonics, b"TREZOR").map(|s| hex(&s)),
+            Ok("b43ceb7e57a0ea8766221624d01b0864".into())
+        );
+        assert_eq!(reconstruct(&mnemonics[..1], b"TREZOR"), Err(Error::TooFewShares));
+
+        // invalid checksum
+        let bad = mnemonic.replace("keyboard", "kidney");
+        assert_eq!(Share::from_mnemonic(&bad), Err(Error::InvalidChecksum));
+        let bad = mnemonic.replace("keyboard", "keybored");
+        assert_eq!(Share::from_mnemonic(&bad), Err(Error::InvalidWord));
+        let bad = mnemonic.replace("duckling ", "");
+        assert_eq!(Share::from_mnemonic(&bad), Err(Error::InvalidEncoding));
+    }
+
+    #[test]
+    fn slip39_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let secret = b"a 32-byte master secret, really!";
+        for extendable in [false, true] {
+            let options = Options { extendable, iteration_exponent: 0 };
+            let groups = generate_with(
+                &mut rng, secret, b"TREZOR", 2, &[(1, 1), (3, 5), (2, 6)], &options
+            ).unwrap();
+            assert_eq!(groups.iter().map(|g| g.len()).collect::<Vec<_>>(), [1, 5, 6]);
+
+            for mnemonic in groups.iter().flatten() {
+                let share = Share::from_mnemonic(mnemonic).unwrap();
+                assert_eq!(share.extendable, extendable);
+                assert_eq!(&share.to_mnemonic(), mnemonic);
+            }
+
+            let mut mnemonics = groups[1][2..].to_vec();
+            mnemonics.extend_from_slice(&groups[2][4..]);
+            assert_eq!(reconstruct(&mnemonics, b"TREZOR"), Ok(secret.to_vec()));
+            mnemonics.push(groups[0][0].clone());
+            assert_eq!(reconstruct(&mnemonics, b"TREZOR"), Ok(secret.to_vec()));
+
+            // any passphrase decrypts to something
+            assert_ne!(reconstruct(&mnemonics, b"trezor"), Ok(secret.to_vec()));
+
+            // not enough groups
+            assert_eq!(reconstruct(&groups[1][..3], b"TREZOR"), Err(Error::TooFewShares));
+            let mut mnemonics = groups[1][..2].to_vec();
+            mnemonics.extend_from_slice(&groups[2][..2]);
+            assert_eq!(reconstruct(&mnemonics, b"TREZOR"), Err(Error::TooFewShares));
+        }
+    }
+
+    #[test]
+    fn slip39_errors() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let options = Options::default();
+        let secret = b"sixteen byte key";
+        assert_eq!(
+            generate_with(&mut rng, b"short", b"", 1, &[(1, 1)], &options),
+            Err(Error::InvalidSecret)
+        );
+        assert_eq!(
+            generate_with(&mut rng, b"seventeen byte key!", b"", 1, &[(1, 1)], &options),
+            Err(Error::InvalidSecret)
+        );
+        assert_eq!(
+            generate_with(&mut rng, secret, b"\n", 1, &[(1, 1)], &options),
+            Err(Error::InvalidPassphrase)
+        );
+        for (t, groups) in [
+            (2, &[(1, 1)][..]),
+            (0, &[(1, 1)][..]),
+            (1, &[(1, 2)][..]),
+            (1, &[(3, 2)][..]),
+            (1, &[(2, 17)][..]),
+        ] {
+            assert_eq!(
+                generate_with(&mut rng, secret, b"", t, groups, &options),
+                Err(Error::InvalidParameters)
+            );
+        }
+
+        // shares from different secrets don't mix
+        let options = Options { extendable: false, iteration_exponent: 0 };
+        let a = generate_with(&mut rng, secret, b"", 1, &[(2, 3)], &options).unwrap();
+        let b = generate_with(&mut rng, secret, b"", 1, &[(2, 3)], &options).unwrap();
+        assert_eq!(
+            reconstruct(&[&a[0][0], &b[0][1]], b""),
+            Err(Error::MismatchedShares)
+        );
+
+        // corrupted shares are caught by the digest
+        let mut share = Share::from_mnemonic(&a[0][0]).unwrap();
+        share.value[0] ^= 1;
+        assert_eq!(
+            reconstruct(&[&share.to_mnemonic(), &a[0][1]], b""),
+            Err(Error::InvalidDigest)
+        );
+    }
+}
@@ -0,0 +1,680 @@
+//! Shares compatible with B. Poettering's [`ssss`][ssss] tool.
+//!
+//! `ssss` doesn't share a secret byte-by-byte. Instead it treats the entire
+//! secret as a single element of a large binary field `GF(2^n)`, where `n`,
+//! the "security level", is by default the size of the secret in bits. This
+//! module reproduces `ssss-split`/`ssss-combine` exactly, so shares can be
+//! migrated between this crate and `ssss` in either direction.
+//!
+//! ``` rust
+//! # use ::gf256::shamir::ssss;
+//! // shares generated by ssss-split -t 3 -n 5
+//! let shares = [
+//!     "3-fa1c3a9c6df8af0779c36de6c33f6e36e989d0e0b91309",
+//!     "5-4756974923c0dce0a55f4774d09ca7a4865f64f56a4ee0",
+//!     "2-fbc74a03a50e14ab406c225afb5f45c40ae11976d2b665",
+//! ];
+//! assert_eq!(ssss::reconstruct(&shares, 3).unwrap(), b"my secret root password");
+//!
+//! // and shares for ssss-combine -t 3
+//! # #[cfg(feature="thread-rng")]
+//! # {
+//! let shares = ssss::generate(b"my secret root password", 5, 3).unwrap();
+//! assert!(shares[0].starts_with("1-"));
+//! assert_eq!(ssss::reconstruct(&shares[2..], 3).unwrap(), b"my secret root password");
+//! # }
+//! ```
+//!
+//! ## The ssss format
+//!
+//! Each share is written as `[token-]index-value`, where the index is the
+//! share's x-coordinate in decimal, zero-padded to the width of the largest
+//! index, and the value is the share's y-coordinate in lowercase hex,
+//! zero-padded to `n/4` digits. The security level is implied by the length
+//! of the value.
+//!
+//! `ssss` differs from [`shamir`](macro@crate::shamir::shamir) in a few ways:
+//!
+//! 1. The field is defined by a fixed table of irreducible pentanomials
+//!    `x^n + x^a + x^b + x^c + 1`, one for each multiple of 8 bits up to
+//!    1024 bits.
+//!
+//! 2. The shared polynomial has an implicit leading coefficient of 1, so a
+//!    threshold of `k` uses a polynomial of degree `k`, not `k-1`. This means
+//!    reconstruction needs to know the threshold, and must use exactly `k`
+//!    shares.
+//!
+//! 3. Unless disabled, the secret is first passed through a diffusion layer,
+//!    a series of overlapping [XTEA][xtea] encryptions with an all-zero key,
+//!    so that every bit of the secret depends on every share. This is skipped
+//!    for security levels below 64 bits, where it wouldn't fit.
+//!
+//! Secrets shorter than the security level are left-padded with zeros, and are
+//! reconstructed with these zeros intact.
+//!
+//! [ssss]: http://point-at-infinity.org/ssss
+//! [xtea]: https://en.wikipedia.org/wiki/XTEA
+//!
+
+use core::fmt;
+use core::mem::swap;
+use core::convert::TryFrom;
+use core::fmt::Write;
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::string::ToString;
+use rand::Rng;
+
+
+/// Error codes for ssss shares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The threshold or number of shares is out of range, ssss needs at
+    /// least 2 shares, and no more shares than the field can index
+    InvalidParameters,
+
+    /// The security level must be a multiple of 8 bits, from 8 to 1024
+    /// bits, and large enough to hold the secret
+    InvalidSecurityLevel,
+
+    /// A share could not be parsed
+    InvalidEncoding,
+
+    /// Shares have different security levels
+    MismatchedShares,
+
+    /// The same x-coordinate appeared more than once
+    DuplicateShares,
+
+    /// Not enough shares to reach the threshold
+    TooFewShares,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidParameters => write!(f, "Invalid threshold or number of shares"),
+            Error::InvalidSecurityLevel => write!(f, "Invalid security level"),
+            Error::InvalidEncoding => write!(f, "Invalid share encoding"),
+            Error::MismatchedShares => write!(f, "Shares have different security levels"),
+            Error::DuplicateShares => write!(f, "Duplicate shares"),
+            Error::TooFewShares => write!(f, "Too few shares to reconstruct secret"),
+        }
+    }
+}
+
+/// Options matching ssss's command-line flags
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// A token prefixed to each share, `-w` in ssss
+    pub token: Option<String>,
+
+    /// The security level in bits, `-s` in ssss, defaults to the size of
+    /// the secret
+    pub bits: Option<usize>,
+
+    /// Apply the diffusion layer, disabled with `-D` in ssss
+    pub diffusion: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            token: None,
+            bits: None,
+            diffusion: true,
+        }
+    }
+}
+
+
+// Field arithmetic over GF(2^n), n <= 1024
+//
+
+/// Enough 64-bit limbs for a 1025-bit modulus
+const LIMBS: usize = 17;
+
+type Elem = [u64; LIMBS];
+
+/// The non-leading terms of ssss's irreducible pentanomials, for each
+/// multiple of 8 bits
+const IRRED_COEFFS: [u8; 384] = [
+      4,   3,   1,   5,   3,   1,   4,   3,   1,   7,   3,   2,   5,   4,   3,   5,   3,   2,
+      7,   4,   2,   4,   3,   1,  10,   9,   3,   9,   4,   2,   7,   6,   2,  10,   9,   6,
+      4,   3,   1,   5,   4,   3,   4,   3,   1,   7,   2,   1,   5,   3,   2,   7,   4,   2,
+      6,   3,   2,   5,   3,   2,  15,   3,   2,  11,   3,   2,   9,   8,   7,   7,   2,   1,
+      5,   3,   2,   9,   3,   1,   7,   3,   1,   9,   8,   3,   9,   4,   2,   8,   5,   3,
+     15,  14,  10,  10,   5,   2,   9,   6,   2,   9,   3,   2,   9,   5,   2,  11,  10,   1,
+      7,   3,   2,  11,   2,   1,   9,   7,   4,   4,   3,   1,   8,   3,   1,   7,   4,   1,
+      7,   2,   1,  13,  11,   6,   5,   3,   2,   7,   3,   2,   8,   7,   5,  12,   3,   2,
+     13,  10,   6,   5,   3,   2,   5,   3,   2,   9,   5,   2,   9,   7,   2,  13,   4,   3,
+      4,   3,   1,  11,   6,   4,  18,   9,   6,  19,  18,  13,  11,   3,   2,  15,   9,   6,
+      4,   3,   1,  16,   5,   2,  15,  14,   6,   8,   5,   2,  15,  11,   2,  11,   6,   2,
+      7,   5,   3,   8,   3,   1,  19,  16,   9,  11,   9,   6,  15,   7,   6,  13,   4,   3,
+     14,  13,   3,  13,   6,   3,   9,   5,   2,  19,  13,   6,  19,  10,   3,  11,   6,   5,
+      9,   2,   1,  14,   3,   2,  13,   3,   1,   7,   5,   4,  11,   9,   8,  11,   6,   5,
+     23,  16,   9,  19,  14,   6,  23,  10,   2,   8,   3,   2,   5,   4,   3,   9,   6,   4,
+      4,   3,   2,  13,   8,   6,  13,  11,   1,  13,  10,   3,  11,   6,   5,  19,  17,   4,
+     15,  14,   7,  13,   9,   6,   9,   7,   3,   9,   7,   1,  14,   3,   2,  11,   8,   2,
+     11,   6,   4,  13,   5,   2,  11,   5,   1,  11,   4,   1,  19,  10,   3,  21,  10,   6,
+     13,   3,   1,  15,   7,   5,  19,  18,  10,   7,   5,   3,  12,   7,   2,   7,   5,   1,
+     14,   9,   6,  10,   3,   2,  15,  13,  12,  12,  11,   9,  16,   9,   7,  12,   9,   3,
+      9,   5,   2,  17,  10,   6,  24,   9,   3,  17,  15,  13,   5,   4,   3,  19,  17,   8,
+     15,   6,   3,  19,   6,   1,
+];
+
+fn bit(a: &Elem, i: usize) -> bool {
+    (a[i/64] >> (i%64)) & 1 != 0
+}
+
+fn set_bit(a: &mut Elem, i: usize) {
+    a[i/64] |= 1 << (i%64);
+}
+
+fn degree(a: &Elem) -> Option<usize> {
+    a.iter().enumerate().rev()
+        .find(|(_, l)| **l != 0)
+        .map(|(i, l)| 64*i + 63 - usize::try_from(l.leading_zeros()).unwrap())
+}
+
+fn from_usize(x: usize) -> Elem {
+    let mut a = [0; LIMBS];
+    a[0] = u64::try_from(x).unwrap();
+    a
+}
+
+fn xor(a: &mut Elem, b: &Elem) {
+    for (a, b) in a.iter_mut().zip(b) {
+        *a ^= b;
+    }
+}
+
+/// a ^= b << j
+fn xor_shifted(a: &mut Elem, b: &Elem, j: usize) {
+    let (limbs, bits) = (j/64, j%64);
+    for i in (limbs..LIMBS).rev() {
+        let mut x = b[i-limbs] << bits;
+        if bits != 0 && i > limbs {
+            x |= b[i-limbs-1] >> (64-bits);
+        }
+        a[i] ^= x;
+    }
+}
+
+/// The field GF(2^n) used by ssss at a given security level
+struct Field {
+    bits: usize,
+    modulus: Elem,
+}
+
+impl Field {
+    fn new(bits: usize) -> Result<Field, Error> {
+        if bits == 0 || bits > 1024 || !bits.is_multiple_of(8) {
+            return Err(Error::InvalidSecurityLevel);
+        }
+
+        let mut modulus = [0; LIMBS];
+        set_bit(&mut modulus, bits);
+        for c in &IRRED_COEFFS[3*(bits/8-1) .. 3*(bits/8)] {
+            set_bit(&mut modulus, usize::from(*c));
+        }
+        set_bit(&mut modulus, 0);
+        Ok(Field { bits, modulus })
+    }
+
+    fn bytes(&self) -> usize {
+        self.bits / 8
+    }
+
+    fn mul(&self, a: &Elem, b: &Elem) -> Elem {
+        let mut r = [0; LIMBS];
+        let d = match degree(b) {
+            Some(d) => d,
+            None => return r,
+        };
+
+        for i in (0..=d).rev() {
+            let mut carry = 0;
+            for l in r.iter_mut() {
+                let next = *l >> 63;
+                *l = (*l << 1) | carry;
+                carry = next;
+            }
+            if bit(&r, self.bits) {
+                xor(&mut r, &self.modulus);
+            }
+            if bit(b, i) {
+                xor(&mut r, a);
+            }
+        }
+        r
+    }
+
+    /// Invert a non-zero element with the extended Euclidean algorithm
+    fn inv(&self, a: &Elem) -> Elem {
+        let (mut u, mut v) = (*a, self.modulus);
+        let (mut g1, mut g2) = (from_usize(1), [0; LIMBS]);
+        loop {
+            let du = degree(&u).unwrap();
+            if du == 0 {
+                return g1;
+            }
+            let dv = degree(&v).unwrap();
+            if du < dv {
+                swap(&mut u, &mut v);
+                swap(&mut g1, &mut g2);
+                continue;
+            }
+            xor_shifted(&mut u, &v, du-dv);
+            xor_shifted(&mut g1, &g2, du-dv);
+        }
+    }
+
+    /// Import big-endian bytes, left-padded with zeros
+    fn import(&self, bytes: &[u8]) -> Elem {
+        let mut a = [0; LIMBS];
+        for (i, b) in bytes.iter().rev().enumerate() {
+            a[i/8] |= u64::from(*b) << (8*(i%8));
+        }
+        a
+    }
+
+    /// Export as big-endian bytes
+    fn export(&self, a: &Elem) -> Vec<u8> {
+        (0..self.bytes()).rev()
+            .map(|i| (a[i/8] >> (8*(i%8))) as u8)
+            .collect()
+    }
+}
+
+
+// The diffusion layer
+//
+
+fn encipher(v: &mut [u32; 2]) {
+    let mut sum = 0u32;
+    for _ in 0..32 {
+        v[0] = v[0].wrapping_add(
+            (((v[1] << 4) ^ (v[1] >> 5)).wrapping_add(v[1])) ^ sum
+        );
+        sum = sum.wrapping_add(0x9e3779b9);
+        v[1] = v[1].wrapping_add(
+            (((v[0] << 4) ^ (v[0] >> 5)).wrapping_add(v[0])) ^ sum
+        );
+    }
+}
+
+fn decipher(v: &mut [u32; 2]) {
+    let mut sum = 0xc6ef3720u32;
+    for _ in 0..32 {
+        v[1] = v[1].wrapping_sub(
+            (((v[0] << 4) ^ (v[0] >> 5)).wrapping_add(v[0])) ^ sum
+        );
+        sum = sum.wrapping_sub(0x9e3779b9);
+        v[0] = v[0].wrapping_sub(
+            (((v[1] << 4) ^ (v[1] >> 5)).wrapping_add(v[1])) ^ sum
+        );
+    }
+}
+
+/// Apply a block cipher to 8 bytes starting at idx, wrapping around
+fn process_slice(data: &mut [u8], idx: usize, f: fn(&mut [u32; 2])) {
+    let len = data.len();
+    let mut v = [0u32; 2];
+    for (i, v) in v.iter_mut().enumerate() {
+        for j in 0..4 {
+            *v = (*v << 8) | u32::from(data[(idx+4*i+j) % len]);
+        }
+    }
+    f(&mut v);
+    for (i, v) in v.iter().enumerate() {
+        for j in 0..4 {
+            data[(idx+4*i+j) % len] = (v >> (24-8*j)) as u8;
+        }
+    }
+}
+
+/// ssss operates on the secret as 16-bit big-endian words, least-significant
+/// word first, with an odd trailing byte moved into the last position. This
+/// maps positions in that buffer to little-endian byte positions.
+fn diffusion_position(p: usize, len: usize) -> usize {
+    if len % 2 == 1 && p == len-1 {
+        p
+    } else {
+        p ^ 1
+    }
+}
+
+fn diffuse(field: &Field, a: &Elem, encode: bool) -> Elem {
+    let len = field.bytes();
+    let mut v = (0..len)
+        .map(|p| {
+            let i = diffusion_position(p, len);
+            (a[i/8] >> (8*(i%8))) as u8
+        })
+        .collect::<Vec<_>>();
+
+    if encode {
+        for i in (0..40*len).step_by(2) {
+            process_slice(&mut v, i, encipher);
+        }
+    } else {
+        for i in (0..40*len).step_by(2).rev() {
+            process_slice(&mut v, i, decipher);
+        }
+    }
+
+    let mut a = [0; LIMBS];
+    for (p, b) in v.iter().enumerate() {
+        let i = diffusion_position(p, len);
+        a[i/8] |= u64::from(*b) << (8*(i%8));
+    }
+    a
+}
+
+
+// Share generation/reconstruction
+//
+
+/// Generate `n` ssss shares of a secret, `k` of which are needed to
+/// reconstruct it.
+///
+/// This is equivalent to `ssss-split -t k -n n`, and uses the secret's size
+/// as the security level.
+///
+/// ``` rust
+/// # use ::gf256::shamir::ssss;
+/// let shares = ssss::generate(b"secret", 5, 3).unwrap();
+/// assert_eq!(shares.len(), 5);
+/// assert_eq!(ssss::reconstruct(&shares[..3], 3).unwrap(), b"secret");
+/// ```
+///
+#[cfg(feature="thread-rng")]
+pub fn generate(secret: &[u8], n: usize, k: usize) -> Result<Vec<String>, Error> {
+    generate_with(&mut rand::thread_rng(), secret, n, k, &Options::default())
+}
+
+/// Generate `n` ssss shares of a secret with a custom Rng and ssss's
+/// options.
+///
+/// ``` rust
+/// # use ::gf256::shamir::ssss;
+/// use rand::rngs::OsRng;
+///
+/// let options = ssss::Options {
+///     token: Some("backup".into()),
+///     bits: Some(128),
+///     ..Default::default()
+/// };
+/// let shares = ssss::generate_with(&mut OsRng, b"secret", 12, 3, &options).unwrap();
+/// assert!(shares[0].starts_with("backup-01-"));
+/// assert_eq!(shares[0].len(), 10+32);
+///
+/// let secret = ssss::reconstruct(&shares[..3], 3).unwrap();
+/// assert_eq!(&secret[10..], b"secret");
+/// ```
+///
+pub fn generate_with<R: Rng>(
+    rng: &mut R,
+    secret: &[u8],
+    n: usize,
+    k: usize,
+    options: &Options,
+) -> Result<Vec<String>, Error> {
+    let field = Field::new(options.bits.unwrap_or(8*secret.len()))?;
+    if secret.is_empty() || secret.len() > field.bytes() {
+        return Err(Error::InvalidSecurityLevel);
+    }
+    if k < 2 || k > n || (field.bits < 64 && n >> field.bits != 0) {
+        return Err(Error::InvalidParameters);
+    }
+
+    let mut coeffs = Vec::with_capacity(k);
+    let mut secret = field.import(secret);
+    if options.diffusion && field.bits >= 64 {
+        secret = diffuse(&field, &secret, true);
+    }
+    coeffs.push(secret);
+    let mut buf = alloc::vec![0u8; field.bytes()];
+    for _ in 1..k {
+        rng.fill_bytes(&mut buf);
+        coeffs.push(field.import(&buf));
+    }
+
+    let width = n.to_string().len();
+    let mut shares = Vec::with_capacity(n);
+    for i in 1..=n {
+        // evaluate x^k + c[k-1]x^(k-1) + ... + c[0] with Horner's method
+        let x = from_usize(i);
+        let mut y = x;
+        for c in coeffs[1..].iter().rev() {
+            xor(&mut y, c);
+            y = field.mul(&y, &x);
+        }
+        xor(&mut y, &coeffs[0]);
+
+        let mut share = String::new();
+        if let Some(token) = &options.token {
+            write!(share, "{}-", token).unwrap();
+        }
+        write!(share, "{:0width$}-", i, width=width).unwrap();
+        for b in field.export(&y) {
+            write!(share, "{:02x}", b).unwrap();
+        }
+        shares.push(share);
+    }
+
+    Ok(shares)
+}
+
+/// Reconstruct a secret from the first `k` ssss shares, where `k` is the
+/// threshold the shares were generated with.
+///
+/// This is equivalent to `ssss-combine -t k`. Note that because of the
+/// implicit leading coefficient, using the wrong threshold will produce
+/// garbage.
+///
+/// ``` rust
+/// # use ::gf256::shamir::ssss;
+/// let shares = [
+///     "1-1c41ef496eccfbeba439714085df8437236298da8dd824",
+///     "4-468de7d6eb36674c9cf008c8e8fc8c566537ad6301eb9e",
+///     "5-4756974923c0dce0a55f4774d09ca7a4865f64f56a4ee0",
+/// ];
+/// assert_eq!(ssss::reconstruct(&shares, 3).unwrap(), b"my secret root password");
+/// ```
+///
+pub fn reconstruct<S: AsRef<str>>(shares: &[S], k: usize) -> Result<Vec<u8>, Error> {
+    reconstruct_with(shares, k, &Options::default())
+}
+
+/// Reconstruct a secret from the first `k` ssss shares with ssss's options.
+///
+/// If `bits` is provided, the shares must match this security level. Tokens
+/// are ignored.
+///
+pub fn reconstruct_with<S: AsRef<str>>(
+    shares: &[S],
+    k: usize,
+    options: &Options,
+) -> Result<Vec<u8>, Error> {
+    if k < 2 {
+        return Err(Error::InvalidParameters);
+    }
+    if shares.len() < k {
+        return Err(Error::TooFewShares);
+    }
+
+    let mut field = None;
+    let mut points = Vec::with_capacity(k);
+    for share in &shares[..k] {
+        // [token-]index-value, the token may itself contain dashes
+        let share = share.as_ref().trim_end();
+        let (rest, value) = share.rsplit_once('-').ok_or(Error::InvalidEncoding)?;
+        let index = rest.rsplit('-').next().unwrap();
+        if index.is_empty()
+            || !index.bytes().all(|c| c.is_ascii_digit())
+            || value.is_empty()
+            || !value.bytes().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let bits = 4*value.len();
+        let field = match &field {
+            Some(field) => field,
+            None => {
+                if options.bits.unwrap_or(bits) != bits {
+                    return Err(Error::MismatchedShares);
+                }
+                field.insert(Field::new(bits).map_err(|_| Error::InvalidEncoding)?)
+            }
+        };
+        if bits != field.bits {
+            return Err(Error::MismatchedShares);
+        }
+
+        let index = index.parse::<usize>().map_err(|_| Error::InvalidEncoding)?;
+        if index == 0 || (field.bits < 64 && index >> field.bits != 0) {
+            return Err(Error::InvalidEncoding);
+        }
+        let bytes = (0..value.len()).step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i+2], 16).unwrap())
+            .collect::<Vec<_>>();
+
+        // remove the implicit x^k term
+        let x = from_usize(index);
+        let mut y = field.import(&bytes);
+        let mut xk = from_usize(1);
+        for _ in 0..k {
+            xk = field.mul(&xk, &x);
+        }
+        xor(&mut y, &xk);
+
+        if points.iter().any(|(x_, _)| *x_ == x) {
+            return Err(Error::DuplicateShares);
+        }
+        points.push((x, y));
+    }
+    let field = field.unwrap();
+
+    // interpolate at x=0
+    let mut secret = [0; LIMBS];
+    for (i, (x0, y0)) in points.iter().enumerate() {
+        let mut num = from_usize(1);
+        let mut den = from_usize(1);
+        for (j, (x1, _)) in points.iter().enumerate() {
+            if i != j {
+                num = field.mul(&num, x1);
+                let mut d = *x0;
+                xor(&mut d, x1);
+                den = field.mul(&den, &d);
+            }
+        }
+        let basis = field.mul(&num, &field.inv(&den));
+        xor(&mut secret, &field.mul(y0, &basis));
+    }
+
+    if options.diffusion && field.bits >= 64 {
+        secret = diffuse(&field, &secret, false);
+    }
+    Ok(field.export(&secret))
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn ssss_field() {
+        for bits in (8..=1024).step_by(8) {
+            let field = Field::new(bits).unwrap();
+            let a = field.import(&vec![0x5a; bits/8]);
+            let b = from_usize(bits);
+            let ab = field.mul(&a, &b);
+            assert_eq!(field.mul(&ab, &field.inv(&b)), a);
+            assert_eq!(field.mul(&field.inv(&a), &a), from_usize(1));
+        }
+    }
+
+    #[test]
+    fn ssss_diffusion() {
+        for bits in (64..=1024).step_by(8) {
+            let field = Field::new(bits).unwrap();
+            let a = field.import(&(0..bits/8).map(|i| i as u8).collect::<Vec<_>>());
+            let b = diffuse(&field, &a, true);
+            assert_ne!(a, b);
+            assert!(degree(&b).is_none_or(|d| d < bits));
+            assert_eq!(diffuse(&field, &b, false), a);
+        }
+    }
+
+    #[test]
+    fn ssss_known() {
+        // from the ssss documentation, generated with ssss-split -t 3 -n 5
+        let shares = [
+            "1-1c41ef496eccfbeba439714085df8437236298da8dd824",
+            "2-fbc74a03a50e14ab406c225afb5f45c40ae11976d2b665",
+            "3-fa1c3a9c6df8af0779c36de6c33f6e36e989d0e0b91309",
+            "4-468de7d6eb36674c9cf008c8e8fc8c566537ad6301eb9e",
+            "5-4756974923c0dce0a55f4774d09ca7a4865f64f56a4ee0",
+        ];
+        for i in 0..5 {
+            for j in i+1..5 {
+                for l in j+1..5 {
+                    assert_eq!(
+                        reconstruct(&[shares[i], shares[j], shares[l]], 3),
+                        Ok(b"my secret root password".to_vec())
+                    );
+                }
+            }
+        }
+
+        // the wrong threshold gives garbage
+        assert_ne!(reconstruct(&shares[..4], 4), Ok(b"my secret root password".to_vec()));
+        assert_eq!(reconstruct(&shares[..2], 3), Err(Error::TooFewShares));
+    }
+
+    #[test]
+    fn ssss_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for (bits, diffusion) in [(None, true), (None, false), (Some(8), true), (Some(104), true), (Some(1024), true)] {
+            let options = Options { token: Some("a-b".into()), bits, diffusion };
+            let secret: &[u8] = if bits == Some(8) { b"x" } else { b"Hello World!" };
+            let shares = generate_with(&mut rng, secret, 12, 4, &options).unwrap();
+            assert!(shares[0].starts_with("a-b-01-"));
+            let expected = reconstruct_with(&shares[8..], 4, &options).unwrap();
+            assert_eq!(&expected[expected.len()-secret.len()..], secret);
+            assert!(expected[..expected.len()-secret.len()].iter().all(|b| *b == 0));
+
+            // any k shares, but not fewer
+            assert_eq!(reconstruct_with(&shares[3..], 4, &options), Ok(expected.clone()));
+            assert_ne!(reconstruct_with(&shares[3..], 3, &options), Ok(expected.clone()));
+        }
+    }
+
+    #[test]
+    fn ssss_errors() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let options = Options::default();
+        assert_eq!(generate_with(&mut rng, b"", 5, 3, &options), Err(Error::InvalidSecurityLevel));
+        assert_eq!(generate_with(&mut rng, b"secret", 5, 1, &options), Err(Error::InvalidParameters));
+        assert_eq!(generate_with(&mut rng, b"secret", 2, 3, &options), Err(Error::InvalidParameters));
+        assert_eq!(generate_with(&mut rng, b"s", 256, 3, &options), Err(Error::InvalidParameters));
+        let options = Options { bits: Some(12), ..Default::default() };
+        assert_eq!(generate_with(&mut rng, b"s", 5, 3, &options), Err(Error::InvalidSecurityLevel));
+
+        assert_eq!(reconstruct(&["1-abcd", "2-abcd"], 2).map(|_| ()), Ok(()));
+        assert_eq!(reconstruct(&["1-abcd", "1-abcd"], 2), Err(Error::DuplicateShares));
+        assert_eq!(reconstruct(&["1-abcd", "2-abcdef"], 2), Err(Error::MismatchedShares));
+        assert_eq!(reconstruct(&["1-abcd", "2-abcg"], 2), Err(Error::InvalidEncoding));
+        assert_eq!(reconstruct(&["1-abc", "2-abc"], 2), Err(Error::InvalidEncoding));
+        assert_eq!(reconstruct(&["abcd", "2-abcd"], 2), Err(Error::InvalidEncoding));
+        assert_eq!(reconstruct(&["0-abcd", "2-abcd"], 2), Err(Error::InvalidEncoding));
+    }
+}
@@ -0,0 +1,365 @@
+//! Hardware SIMD multiply-accumulate kernels if available
+//!
+//! Multiplication by a constant in any finite-field GF(2^n) is linear over
+//! GF(2), so the multiplication can be described by the product of the
+//! constant with each bit, `c*x^i`, independent of the field's polynomial.
+//! This lets us provide a single set of kernels for all 8-bit fields.
+//!
+//! These functions are intended to only be used by gf256's proc_macros,
+//! these functions may change behavior depending on target_features, so
+//! they shouldn't be used directly.
+//!
+//! The exception is [`Kernel`], which lets callers pick a kernel at runtime,
+//! and is re-exported by the raid module.
+//!
+
+use cfg_if::cfg_if;
+use core::slice;
+
+
+#[cfg(feature="std")]
+extern crate std;
+
+// Runtime feature detection requires std
+#[cfg(all(target_arch="x86_64", feature="std"))]
+macro_rules! detect_x86 {
+    ($feature:tt) => { std::is_x86_feature_detected!($feature) };
+}
+#[cfg(all(target_arch="x86_64", not(feature="std")))]
+macro_rules! detect_x86 {
+    ($feature:tt) => { false };
+}
+#[cfg(all(target_arch="aarch64", feature="std"))]
+macro_rules! detect_aarch64 {
+    ($feature:tt) => { std::arch::is_aarch64_feature_detected!($feature) };
+}
+#[cfg(all(target_arch="aarch64", not(feature="std")))]
+macro_rules! detect_aarch64 {
+    ($feature:tt) => { false };
+}
+
+
+/// The SIMD kernels available for multiply-accumulate, see
+/// [`mul_acc8_with`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Kernel {
+    /// Portable 4-bit lookup tables, always available
+    Table,
+
+    /// 16 parallel 4-bit table lookups via x86_64's pshufb instruction
+    Ssse3,
+
+    /// 32 parallel 4-bit table lookups via x86_64's vpshufb instruction
+    Avx2,
+
+    /// 8x8 bit-matrix multiplication via x86_64's gf2p8affineqb instruction
+    Gfni,
+
+    /// 16 parallel 4-bit table lookups via aarch64's tbl instruction
+    Neon,
+}
+
+impl Kernel {
+    /// The best kernel enabled at compile time via target_features.
+    ///
+    /// This is the kernel used by [`mul_acc8`].
+    ///
+    pub const fn native() -> Kernel {
+        cfg_if! {
+            if #[cfg(all(
+                target_arch="x86_64",
+                target_feature="gfni",
+                target_feature="avx2"
+            ))] {
+                Kernel::Gfni
+            } else if #[cfg(all(target_arch="x86_64", target_feature="avx2"))] {
+                Kernel::Avx2
+            } else if #[cfg(all(target_arch="x86_64", target_feature="ssse3"))] {
+                Kernel::Ssse3
+            } else if #[cfg(all(target_arch="aarch64", target_feature="neon"))] {
+                Kernel::Neon
+            } else {
+                Kernel::Table
+            }
+        }
+    }
+
+    /// The best kernel available on the current CPU.
+    ///
+    /// With the `std` feature, this probes the CPU at runtime, so it may
+    /// find kernels that weren't enabled at compile time. Without `std`,
+    /// this is the same as [`native`](Kernel::native).
+    ///
+    pub fn detect() -> Kernel {
+        [Kernel::Gfni, Kernel::Avx2, Kernel::Ssse3, Kernel::Neon].into_iter()
+            .find(|kernel| kernel.is_available())
+            .unwrap_or(Kernel::Table)
+    }
+
+    /// Check if this kernel can run on the current CPU.
+    pub fn is_available(self) -> bool {
+        match self {
+            Kernel::Table => true,
+            #[cfg(target_arch="x86_64")]
+            Kernel::Ssse3 => {
+                cfg!(target_feature="ssse3") || detect_x86!("ssse3")
+            }
+            #[cfg(target_arch="x86_64")]
+            Kernel::Avx2 => {
+                cfg!(target_feature="avx2") || detect_x86!("avx2")
+            }
+            #[cfg(target_arch="x86_64")]
+            Kernel::Gfni => {
+                (cfg!(target_feature="gfni") || detect_x86!("gfni"))
+                    && Kernel::Avx2.is_available()
+            }
+            #[cfg(target_arch="aarch64")]
+            Kernel::Neon => {
+                cfg!(target_feature="neon") || detect_aarch64!("neon")
+            }
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+/// Multiply a slice of bytes by a constant and xor into another slice of
+/// bytes, `dst[i] ^= c*src[i]`.
+///
+/// Since multiplication by a constant is linear over GF(2), the constant is
+/// passed as the products of the constant with each bit, `basis[i] = c*x^i`.
+/// For fields with fewer than 8 bits, the extra bits in `basis` should be
+/// zero, which ignores any unused bits in `src`.
+///
+/// This uses explicit SIMD instructions when available (`gfni`/`avx2`/`ssse3`
+/// on x86_64, `neon` on aarch64), otherwise falling back to 4-bit lookup
+/// tables.
+///
+#[inline]
+pub fn mul_acc8(dst: &mut [u8], src: &[u8], basis: [u8; 8]) {
+    mul_acc8_with(Kernel::native(), dst, src, basis)
+}
+
+/// Multiply a slice of bytes by a constant and xor into another slice of
+/// bytes, `dst[i] ^= c*src[i]`, using a specific kernel.
+///
+/// This is the same as [`mul_acc8`], but lets the caller pick the kernel at
+/// runtime, see [`Kernel::detect`].
+///
+/// Panics if the kernel is not available on the current CPU.
+///
+#[inline]
+pub fn mul_acc8_with(kernel: Kernel, dst: &mut [u8], src: &[u8], basis: [u8; 8]) {
+    mul_acc8_prepared(dst, src, &Prepared8::new(kernel, basis))
+}
+
+/// A constant prepared for multiply-accumulate with a specific kernel, see
+/// [`mul_acc8_prepared`]
+///
+/// [`mul_acc8`] rebuilds its 4-bit lookup tables, or bit-matrix, on every
+/// call. This is negligible for long slices, but when the same constant is
+/// used with many short slices, it's worth building these once.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Prepared8 {
+    kernel: Kernel,
+    lo: [u8; 16],
+    hi: [u8; 16],
+    matrix: u64,
+}
+
+impl Prepared8 {
+    /// Prepare a constant, passed as the products of the constant with each
+    /// bit, `basis[i] = c*x^i`, the same as in [`mul_acc8`].
+    ///
+    /// Panics if the kernel is not available on the current CPU.
+    ///
+    pub fn new(kernel: Kernel, basis: [u8; 8]) -> Prepared8 {
+        assert!(kernel.is_available());
+
+        // 4-bit lookup tables, lo[x] = c*x, hi[x] = c*(x << 4)
+        let mut lo = [0u8; 16];
+        let mut hi = [0u8; 16];
+        for x in 0..16 {
+            for j in 0..4 {
+                if x & (1 << j) != 0 {
+                    lo[x] ^= basis[j];
+                    hi[x] ^= basis[j+4];
+                }
+            }
+        }
+
+        // row i of the matrix, stored in byte 7-i, selects the input bits
+        // that contribute to output bit i, this is only used by gfni
+        let mut matrix = 0u64;
+        if kernel == Kernel::Gfni {
+            for row in 0..8 {
+                for (j, b) in basis.iter().enumerate() {
+                    if b & (1 << row) != 0 {
+                        matrix |= 1 << (8*(7-row) + j);
+                    }
+                }
+            }
+        }
+
+        Prepared8 { kernel, lo, hi, matrix }
+    }
+}
+
+/// Multiply a slice of bytes by a prepared constant and xor into another
+/// slice of bytes, `dst[i] ^= c*src[i]`.
+///
+/// This is the same as [`mul_acc8_with`], but uses tables built once by
+/// [`Prepared8::new`].
+///
+#[inline]
+pub fn mul_acc8_prepared(dst: &mut [u8], src: &[u8], prepared: &Prepared8) {
+    mul_acc8_rows(dst, src, slice::from_ref(prepared))
+}
+
+/// Multiply a slice of bytes by many prepared constants, and xor each
+/// product into a row of another slice of bytes, `dst[j*n+i] ^= c_j*src[i]`,
+/// where `n = src.len()`.
+///
+/// This is the same as calling [`mul_acc8_prepared`] on each row, but only
+/// dispatches to a kernel once. All constants must be prepared for the same
+/// kernel.
+///
+#[inline]
+pub fn mul_acc8_rows(dst: &mut [u8], src: &[u8], prepared: &[Prepared8]) {
+    assert_eq!(dst.len(), src.len()*prepared.len());
+    let kernel = match prepared.first() {
+        Some(prepared) if !src.is_empty() => prepared.kernel,
+        _ => return,
+    };
+    assert!(prepared.iter().all(|prepared| prepared.kernel == kernel));
+
+    // kernel availability is checked in Prepared8::new
+    let i = unsafe {
+        match kernel {
+            #[cfg(target_arch="x86_64")]
+            Kernel::Gfni => mul_acc8_gfni(dst, src, prepared),
+            #[cfg(target_arch="x86_64")]
+            Kernel::Avx2 => mul_acc8_avx2(dst, src, prepared),
+            #[cfg(target_arch="x86_64")]
+            Kernel::Ssse3 => mul_acc8_ssse3(dst, src, prepared),
+            #[cfg(target_arch="aarch64")]
+            Kernel::Neon => mul_acc8_neon(dst, src, prepared),
+            _ => 0,
+        }
+    };
+
+    // handle remainder
+    if i < src.len() {
+        for (dst, prepared) in dst.chunks_exact_mut(src.len()).zip(prepared) {
+            let Prepared8 { lo, hi, .. } = prepared;
+            for i in i..src.len() {
+                dst[i] ^= lo[usize::from(src[i] & 0xf)] ^ hi[usize::from(src[i] >> 4)];
+            }
+        }
+    }
+}
+
+/// x86_64 with gfni provides arbitrary 8x8 bit-matrix multiplication via the
+/// gf2p8affineqb instruction, returns the number of bytes processed in
+/// each row
+#[cfg(target_arch="x86_64")]
+#[target_feature(enable="gfni,avx2")]
+unsafe fn mul_acc8_gfni(dst: &mut [u8], src: &[u8], prepared: &[Prepared8]) -> usize {
+    use core::arch::x86_64::*;
+    let len = src.len();
+    for (dst, prepared) in dst.chunks_exact_mut(len).zip(prepared) {
+        let mut i = 0;
+        let m = _mm256_set1_epi64x(prepared.matrix as i64);
+        while i + 32 <= len {
+            let a = _mm256_loadu_si256(dst.as_ptr().add(i) as *const __m256i);
+            let b = _mm256_loadu_si256(src.as_ptr().add(i) as *const __m256i);
+            let b = _mm256_gf2p8affine_epi64_epi8::<0>(b, m);
+            _mm256_storeu_si256(dst.as_mut_ptr().add(i) as *mut __m256i, _mm256_xor_si256(a, b));
+            i += 32;
+        }
+    }
+    len - len % 32
+}
+
+/// x86_64 with avx2 provides 32 parallel 4-bit table lookups via the vpshufb
+/// instruction, returns the number of bytes processed in each row
+#[cfg(target_arch="x86_64")]
+#[target_feature(enable="avx2")]
+unsafe fn mul_acc8_avx2(dst: &mut [u8], src: &[u8], prepared: &[Prepared8]) -> usize {
+    use core::arch::x86_64::*;
+    let len = src.len();
+    let mask = _mm256_set1_epi8(0x0f);
+    for (dst, prepared) in dst.chunks_exact_mut(len).zip(prepared) {
+        let mut i = 0;
+        let lo = _mm256_broadcastsi128_si256(_mm_loadu_si128(prepared.lo.as_ptr() as *const __m128i));
+        let hi = _mm256_broadcastsi128_si256(_mm_loadu_si128(prepared.hi.as_ptr() as *const __m128i));
+        while i + 32 <= len {
+            let a = _mm256_loadu_si256(dst.as_ptr().add(i) as *const __m256i);
+            let b = _mm256_loadu_si256(src.as_ptr().add(i) as *const __m256i);
+            let b_lo = _mm256_and_si256(b, mask);
+            let b_hi = _mm256_and_si256(_mm256_srli_epi64::<4>(b), mask);
+            let b = _mm256_xor_si256(
+                _mm256_shuffle_epi8(lo, b_lo),
+                _mm256_shuffle_epi8(hi, b_hi)
+            );
+            _mm256_storeu_si256(dst.as_mut_ptr().add(i) as *mut __m256i, _mm256_xor_si256(a, b));
+            i += 32;
+        }
+    }
+    len - len % 32
+}
+
+/// x86_64 with ssse3 provides 16 parallel 4-bit table lookups via the pshufb
+/// instruction, returns the number of bytes processed in each row
+#[cfg(target_arch="x86_64")]
+#[target_feature(enable="ssse3")]
+unsafe fn mul_acc8_ssse3(dst: &mut [u8], src: &[u8], prepared: &[Prepared8]) -> usize {
+    use core::arch::x86_64::*;
+    let len = src.len();
+    let mask = _mm_set1_epi8(0x0f);
+    for (dst, prepared) in dst.chunks_exact_mut(len).zip(prepared) {
+        let mut i = 0;
+        let lo = _mm_loadu_si128(prepared.lo.as_ptr() as *const __m128i);
+        let hi = _mm_loadu_si128(prepared.hi.as_ptr() as *const __m128i);
+        while i + 16 <= len {
+            let a = _mm_loadu_si128(dst.as_ptr().add(i) as *const __m128i);
+            let b = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+            let b_lo = _mm_and_si128(b, mask);
+            let b_hi = _mm_and_si128(_mm_srli_epi64::<4>(b), mask);
+            let b = _mm_xor_si128(
+                _mm_shuffle_epi8(lo, b_lo),
+                _mm_shuffle_epi8(hi, b_hi)
+            );
+            _mm_storeu_si128(dst.as_mut_ptr().add(i) as *mut __m128i, _mm_xor_si128(a, b));
+            i += 16;
+        }
+    }
+    len - len % 16
+}
+
+/// aarch64 with neon provides 16 parallel 4-bit table lookups via the tbl
+/// instruction, returns the number of bytes processed in each row
+#[cfg(target_arch="aarch64")]
+#[target_feature(enable="neon")]
+unsafe fn mul_acc8_neon(dst: &mut [u8], src: &[u8], prepared: &[Prepared8]) -> usize {
+    use core::arch::aarch64::*;
+    let len = src.len();
+    let mask = vdupq_n_u8(0x0f);
+    for (dst, prepared) in dst.chunks_exact_mut(len).zip(prepared) {
+        let mut i = 0;
+        let lo = vld1q_u8(prepared.lo.as_ptr());
+        let hi = vld1q_u8(prepared.hi.as_ptr());
+        while i + 16 <= len {
+            let a = vld1q_u8(dst.as_ptr().add(i));
+            let b = vld1q_u8(src.as_ptr().add(i));
+            let b = veorq_u8(
+                vqtbl1q_u8(lo, vandq_u8(b, mask)),
+                vqtbl1q_u8(hi, vshrq_n_u8::<4>(b))
+            );
+            vst1q_u8(dst.as_mut_ptr().add(i), veorq_u8(a, b));
+            i += 16;
+        }
+    }
+    len - len % 16
+}
@@ -1,12 +1,17 @@
 //! Common traits
 //!
-//! Currently just a workaround for FromLossy/IntoLossy traits
+//! Currently a workaround for FromLossy/IntoLossy traits, and traits
+//! for writing algorithms generic over polynomial and finite-field types
 //!
 
 // TryFrom/TryInto forwarded for convenience
 pub use core::convert::TryFrom;
 pub use core::convert::TryInto;
 
+use core::ops::*;
+use core::fmt::Debug;
+use core::hash::Hash;
+
 /// A From trait for conversions which may lose precision
 ///
 /// Note this is just a temporary solution. Once [RFC2484] is implemented
@@ -50,3 +55,155 @@ where
     }
 }
 
+
+
+/// A trait for binary polynomial types
+///
+/// This is implemented by all polynomial types, including those created
+/// with the [`p`](crate::p::p) macro, and allows algorithms to be written
+/// once over polynomials of any width:
+///
+/// ``` rust
+/// # use ::gf256::*;
+/// use ::gf256::traits::Poly;
+///
+/// fn gcd<P: Poly>(a: P, b: P) -> P {
+///     if b == P::ZERO { a } else { gcd(b, a % b) }
+/// }
+///
+/// assert_eq!(gcd(p8(0x1d), p8(0x31)), p8(0x0b));
+/// assert_eq!(gcd(p64(0x1d), p64(0x31)), p64(0x0b));
+/// ```
+///
+/// Note that the polynomial types' inherent methods are usually more
+/// convenient when the type is known, this trait is only needed for
+/// generic code.
+///
+pub trait Poly
+where
+    Self: Copy + Clone + Debug + Default + Eq + Hash
+        + Add<Output=Self> + AddAssign
+        + Sub<Output=Self> + SubAssign
+        + Mul<Output=Self> + MulAssign
+        + Div<Output=Self> + DivAssign
+        + Rem<Output=Self> + RemAssign
+        + BitAnd<Output=Self> + BitAndAssign
+        + BitOr<Output=Self> + BitOrAssign
+        + BitXor<Output=Self> + BitXorAssign
+        + Not<Output=Self>
+        + Shl<usize, Output=Self> + ShlAssign<usize>
+        + Shr<usize, Output=Self> + ShrAssign<usize>
+{
+    /// The underlying unsigned type
+    type U: Copy + Debug + Default + Eq + Hash;
+
+    /// Width of the polynomial type in bits
+    const WIDTH: usize;
+
+    /// The polynomial `0`
+    const ZERO: Self;
+
+    /// The polynomial `1`
+    const ONE: Self;
+
+    /// Create a polynomial from its underlying unsigned representation
+    fn from_u(x: Self::U) -> Self;
+
+    /// Get the underlying unsigned representation of a polynomial
+    fn to_u(self) -> Self::U;
+
+    /// Polynomial multiplication, returning a tuple containing the low
+    /// and high parts in that order
+    fn widening_mul(self, other: Self) -> (Self, Self);
+
+    /// Polynomial multiplication, wrapping around the boundary of the
+    /// type
+    fn wrapping_mul(self, other: Self) -> Self;
+
+    /// Polynomial exponentiation, wrapping around the boundary of the
+    /// type
+    fn wrapping_pow(self, exp: u32) -> Self;
+
+    /// Polynomial division, returning [`None`] if `other == 0`
+    fn checked_div(self, other: Self) -> Option<Self>;
+
+    /// Polynomial remainder, returning [`None`] if `other == 0`
+    fn checked_rem(self, other: Self) -> Option<Self>;
+
+    /// Reverse the order of the coefficients in the polynomial
+    fn reverse_bits(self) -> Self;
+
+    /// Number of leading zero coefficients
+    fn leading_zeros(self) -> u32;
+
+    /// Number of trailing zero coefficients
+    fn trailing_zeros(self) -> u32;
+
+    /// Number of non-zero coefficients
+    fn count_ones(self) -> u32;
+}
+
+/// A trait for finite-field types
+///
+/// This is implemented by all finite-field types, including those created
+/// with the [`gf`](crate::gf::gf) macro, and allows algorithms to be written
+/// once over finite-fields of any size:
+///
+/// ``` rust
+/// # use ::gf256::*;
+/// use ::gf256::traits::Field;
+///
+/// fn square_sum<F: Field>(xs: &[F]) -> F {
+///     xs.iter().fold(F::ZERO, |acc, &x| acc + x*x)
+/// }
+///
+/// assert_eq!(square_sum(&[gf256(0x12), gf256(0x34)]), gf256(0x60));
+/// assert_eq!(square_sum(&[gf2p64(0x12), gf2p64(0x34)]), gf2p64(0x414));
+/// ```
+///
+/// Note that the finite-field types' inherent methods are usually more
+/// convenient when the type is known, this trait is only needed for
+/// generic code.
+///
+pub trait Field
+where
+    Self: Copy + Clone + Debug + Default + Eq + Hash
+        + Add<Output=Self> + AddAssign
+        + Sub<Output=Self> + SubAssign
+        + Mul<Output=Self> + MulAssign
+        + Div<Output=Self> + DivAssign
+{
+    /// The underlying unsigned type
+    type U: Copy + Debug + Default + Eq + Hash;
+
+    /// Width of the finite-field in bits
+    const WIDTH: usize;
+
+    /// Number of non-zero elements in the finite-field
+    const NONZEROS: Self::U;
+
+    /// A generator, aka primitive element, in the finite-field
+    const GENERATOR: Self;
+
+    /// The element `0`
+    const ZERO: Self;
+
+    /// The element `1`
+    const ONE: Self;
+
+    /// Create an element from its underlying unsigned representation,
+    /// returning [`None`] if it does not fit in the finite-field
+    fn try_from_u(x: Self::U) -> Option<Self>;
+
+    /// Get the underlying unsigned representation of an element
+    fn to_u(self) -> Self::U;
+
+    /// Exponentiation
+    fn pow(self, exp: Self::U) -> Self;
+
+    /// Multiplicative inverse, returning [`None`] if `self == 0`
+    fn checked_recip(self) -> Option<Self>;
+
+    /// Division, returning [`None`] if `other == 0`
+    fn checked_div(self, other: Self) -> Option<Self>;
+}
@@ -13,6 +13,10 @@
 
 use cfg_if::cfg_if;
 
+// Runtime feature detection requires std
+#[cfg(feature="std")]
+extern crate std;
+
 
 /// A flag indicating if hardware carry-less multiplication
 /// instructions are available.
@@ -46,6 +50,27 @@ pub const HAS_XMUL: bool = {
     }
 };
 
+/// A flag indicating if batched carry-less multiplication, such as
+/// `xmul64x4`, is done with a single hardware instruction.
+///
+/// Otherwise the batched functions fall back to separate multiplications,
+/// which is only worth it if it keeps multiple multiplications in flight.
+///
+pub const HAS_XMUL4: bool = {
+    cfg_if! {
+        if #[cfg(all(
+            not(feature="no-xmul"),
+            target_arch="x86_64",
+            target_feature="vpclmulqdq",
+            target_feature="avx512f"
+        ))] {
+            true
+        } else {
+            false
+        }
+    }
+};
+
 
 /// Widening carry-less multiplication, if hardware instructions are available
 ///
@@ -264,22 +289,24 @@ pub fn xmul128(a: u128, b: u128) -> (u128, u128) {
             target_arch="x86_64",
             target_feature="pclmulqdq"
         ))] {
-            // x86_64 provides 64-bit xmul via the pclmulqdq instruction
+            // x86_64 provides 64-bit xmul via the pclmulqdq instruction,
+            // we can build a 128-bit xmul out of 3 64-bit xmuls with
+            // Karatsuba, since xor-ing the limbs can't carry
             use core::arch::x86_64::*;
             unsafe {
                 let a = _mm_set_epi64x((a >> 64) as i64, a as i64);
                 let b = _mm_set_epi64x((b >> 64) as i64, b as i64);
                 let x = _mm_clmulepi64_si128::<0x00>(a, b);
-                let y = _mm_clmulepi64_si128::<0x01>(a, b);
-                let z = _mm_clmulepi64_si128::<0x10>(a, b);
                 let w = _mm_clmulepi64_si128::<0x11>(a, b);
+                let m = _mm_clmulepi64_si128::<0x00>(
+                    _mm_xor_si128(a, _mm_unpackhi_epi64(a, a)),
+                    _mm_xor_si128(b, _mm_unpackhi_epi64(b, b)));
+                let m = _mm_xor_si128(m, _mm_xor_si128(x, w));
                 let lolo = _mm_extract_epi64::<0>(x) as u64;
                 let lohi = (_mm_extract_epi64::<1>(x) as u64)
-                    ^ (_mm_extract_epi64::<0>(y) as u64)
-                    ^ (_mm_extract_epi64::<0>(z) as u64);
+                    ^ (_mm_extract_epi64::<0>(m) as u64);
                 let hilo = (_mm_extract_epi64::<0>(w) as u64)
-                    ^ (_mm_extract_epi64::<1>(y) as u64)
-                    ^ (_mm_extract_epi64::<1>(z) as u64);
+                    ^ (_mm_extract_epi64::<1>(m) as u64);
                 let hihi = _mm_extract_epi64::<1>(w) as u64;
                 let lo = ((lohi as u128) << 64) | (lolo as u128);
                 let hi = ((hihi as u128) << 64) | (hilo as u128);
@@ -290,20 +317,811 @@ pub fn xmul128(a: u128, b: u128) -> (u128, u128) {
             target_arch="aarch64",
             target_feature="neon"
         ))] {
-            // aarch64 provides 64-bit xmul via the pmull instruction
+            // aarch64 provides 64-bit xmul via the pmull instruction,
+            // we can build a 128-bit xmul out of 3 64-bit xmuls with
+            // Karatsuba, since xor-ing the limbs can't carry
             use core::arch::aarch64::*;
             unsafe {
                 let x = vmull_p64(a as u64, b as u64);
-                let y = vmull_p64((a >> 64) as u64, (b >>  0) as u64);
-                let z = vmull_p64((a >>  0) as u64, (b >> 64) as u64);
                 let w = vmull_p64((a >> 64) as u64, (b >> 64) as u64);
-                (x ^ (y << 64) ^ (z << 64), w ^ (y >> 64) ^ (z >> 64))
+                let m = vmull_p64(
+                    ((a >> 64) ^ a) as u64,
+                    ((b >> 64) ^ b) as u64) ^ x ^ w;
+                (x ^ (m << 64), w ^ (m >> 64))
             }
         }
     }
 }
 
 
+/// Widening carry-less multiply-accumulate, if hardware instructions are
+/// available
+///
+/// This computes `acc ^ a*b`, keeping the accumulator in the same register
+/// as the multiplication result.
+///
+/// Result is a tuple (lo, hi)
+///
+#[cfg(any(
+    all(
+        not(feature="no-xmul"),
+        target_arch="x86_64",
+        target_feature="pclmulqdq"
+    ),
+    all(
+        not(feature="no-xmul"),
+        target_arch="aarch64",
+        target_feature="neon"
+    )
+))]
+#[inline]
+pub fn xmul8_acc(acc: (u8, u8), a: u8, b: u8) -> (u8, u8) {
+    cfg_if! {
+        if #[cfg(all(
+            not(feature="no-xmul"),
+            target_arch="x86_64",
+            target_feature="pclmulqdq"
+        ))] {
+            // x86_64 provides 64-bit xmul via the pclmulqdq instruction
+            use core::arch::x86_64::*;
+            unsafe {
+                let a = _mm_set_epi64x(0, a as i64);
+                let b = _mm_set_epi64x(0, b as i64);
+                let acc = _mm_set_epi64x(0, (((acc.1 as u64) << 8) | (acc.0 as u64)) as i64);
+                let x = _mm_xor_si128(_mm_clmulepi64_si128::<0>(a, b), acc);
+                let lo = _mm_extract_epi64::<0>(x) as u64;
+                (lo as u8, (lo >> 8) as u8)
+            }
+        } else if #[cfg(all(
+            not(feature="no-xmul"),
+            target_arch="aarch64",
+            target_feature="neon"
+        ))] {
+            // aarch64 provides 64-bit xmul via the pmull instruction
+            use core::arch::aarch64::*;
+            unsafe {
+                let x = vmull_p64(a as u64, b as u64)
+                    ^ (((acc.1 as u128) << 8) | (acc.0 as u128));
+                (x as u8, (x >> 8) as u8)
+            }
+        }
+    }
+}
+
+/// Widening carry-less multiply-accumulate, if hardware instructions are
+/// available
+///
+/// This computes `acc ^ a*b`, keeping the accumulator in the same register
+/// as the multiplication result.
+///
+/// Result is a tuple (lo, hi)
+///
+#[cfg(any(
+    all(
+        not(feature="no-xmul"),
+        target_arch="x86_64",
+        target_feature="pclmulqdq"
+    ),
+    all(
+        not(feature="no-xmul"),
+        target_arch="aarch64",
+        target_feature="neon"
+    )
+))]
+#[inline]
+pub fn xmul16_acc(acc: (u16, u16), a: u16, b: u16) -> (u16, u16) {
+    cfg_if! {
+        if #[cfg(all(
+            not(feature="no-xmul"),
+            target_arch="x86_64",
+            target_feature="pclmulqdq"
+        ))] {
+            // x86_64 provides 64-bit xmul via the pclmulqdq instruction
+            use core::arch::x86_64::*;
+            unsafe {
+                let a = _mm_set_epi64x(0, a as i64);
+                let b = _mm_set_epi64x(0, b as i64);
+                let acc = _mm_set_epi64x(0, (((acc.1 as u64) << 16) | (acc.0 as u64)) as i64);
+                let x = _mm_xor_si128(_mm_clmulepi64_si128::<0>(a, b), acc);
+                let lo = _mm_extract_epi64::<0>(x) as u64;
+                (lo as u16, (lo >> 16) as u16)
+            }
+        } else if #[cfg(all(
+            not(feature="no-xmul"),
+            target_arch="aarch64",
+            target_feature="neon"
+        ))] {
+            // aarch64 provides 64-bit xmul via the pmull instruction
+            use core::arch::aarch64::*;
+            unsafe {
+                let x = vmull_p64(a as u64, b as u64)
+                    ^ (((acc.1 as u128) << 16) | (acc.0 as u128));
+                (x as u16, (x >> 16) as u16)
+            }
+        }
+    }
+}
+
+/// Widening carry-less multiply-accumulate, if hardware instructions are
+/// available
+///
+/// This computes `acc ^ a*b`, keeping the accumulator in the same register
+/// as the multiplication result.
+///
+/// Result is a tuple (lo, hi)
+///
+#[cfg(any(
+    all(
+        not(feature="no-xmul"),
+        target_arch="x86_64",
+        target_feature="pclmulqdq"
+    ),
+    all(
+        not(feature="no-xmul"),
+        target_arch="aarch64",
+        target_feature="neon"
+    )
+))]
+#[inline]
+pub fn xmul32_acc(acc: (u32, u32), a: u32, b: u32) -> (u32, u32) {
+    cfg_if! {
+        if #[cfg(all(
+            not(feature="no-xmul"),
+            target_arch="x86_64",
+            target_feature="pclmulqdq"
+        ))] {
+            // x86_64 provides 64-bit xmul via the pclmulqdq instruction
+            use core::arch::x86_64::*;
+            unsafe {
+                let a = _mm_set_epi64x(0, a as i64);
+                let b = _mm_set_epi64x(0, b as i64);
+                let acc = _mm_set_epi64x(0, (((acc.1 as u64) << 32) | (acc.0 as u64)) as i64);
+                let x = _mm_xor_si128(_mm_clmulepi64_si128::<0>(a, b), acc);
+                let lo = _mm_extract_epi64::<0>(x) as u64;
+                (lo as u32, (lo >> 32) as u32)
+            }
+        } else if #[cfg(all(
+            not(feature="no-xmul"),
+            target_arch="aarch64",
+            target_feature="neon"
+        ))] {
+            // aarch64 provides 64-bit xmul via the pmull instruction
+            use core::arch::aarch64::*;
+            unsafe {
+                let x = vmull_p64(a as u64, b as u64)
+                    ^ (((acc.1 as u128) << 32) | (acc.0 as u128));
+                (x as u32, (x >> 32) as u32)
+            }
+        }
+    }
+}
+
+/// Widening carry-less multiply-accumulate, if hardware instructions are
+/// available
+///
+/// This computes `acc ^ a*b`, keeping the accumulator in the same register
+/// as the multiplication result.
+///
+/// Result is a tuple (lo, hi)
+///
+#[cfg(any(
+    all(
+        not(feature="no-xmul"),
+        target_arch="x86_64",
+        target_feature="pclmulqdq"
+    ),
+    all(
+        not(feature="no-xmul"),
+        target_arch="aarch64",
+        target_feature="neon"
+    )
+))]
+#[inline]
+pub fn xmul64_acc(acc: (u64, u64), a: u64, b: u64) -> (u64, u64) {
+    cfg_if! {
+        if #[cfg(all(
+            not(feature="no-xmul"),
+            target_arch="x86_64",
+            target_feature="pclmulqdq"
+        ))] {
+            // x86_64 provides 64-bit xmul via the pclmulqdq instruction
+            use core::arch::x86_64::*;
+            unsafe {
+                let a = _mm_set_epi64x(0, a as i64);
+                let b = _mm_set_epi64x(0, b as i64);
+                let acc = _mm_set_epi64x(acc.1 as i64, acc.0 as i64);
+                let x = _mm_xor_si128(_mm_clmulepi64_si128::<0>(a, b), acc);
+                let lo = _mm_extract_epi64::<0>(x) as u64;
+                let hi = _mm_extract_epi64::<1>(x) as u64;
+                (lo, hi)
+            }
+        } else if #[cfg(all(
+            not(feature="no-xmul"),
+            target_arch="aarch64",
+            target_feature="neon"
+        ))] {
+            // aarch64 provides 64-bit xmul via the pmull instruction
+            use core::arch::aarch64::*;
+            unsafe {
+                let x = vmull_p64(a as u64, b as u64)
+                    ^ (((acc.1 as u128) << 64) | (acc.0 as u128));
+                (x as u64, (x >> 64) as u64)
+            }
+        }
+    }
+}
+
+/// Widening carry-less multiply-accumulate, if hardware instructions are
+/// available
+///
+/// This computes `acc ^ a*b`.
+///
+/// Result is a tuple (lo, hi)
+///
+#[cfg(any(
+    all(
+        not(feature="no-xmul"),
+        target_arch="x86_64",
+        target_feature="pclmulqdq"
+    ),
+    all(
+        not(feature="no-xmul"),
+        target_arch="aarch64",
+        target_feature="neon"
+    )
+))]
+#[inline]
+pub fn xmul128_acc(acc: (u128, u128), a: u128, b: u128) -> (u128, u128) {
+    let (lo, hi) = xmul128(a, b);
+    (acc.0 ^ lo, acc.1 ^ hi)
+}
+
+/// Batched widening carry-less multiplication, if hardware instructions
+/// are available
+///
+/// This performs four independent multiplications, which on x86_64 with
+/// `vpclmulqdq` and `avx512f` can be done with a single instruction.
+/// Otherwise this falls back to four calls to [`xmul64`].
+///
+/// Result is a tuple of arrays (lo, hi)
+///
+#[cfg(any(
+    all(
+        not(feature="no-xmul"),
+        target_arch="x86_64",
+        target_feature="pclmulqdq"
+    ),
+    all(
+        not(feature="no-xmul"),
+        target_arch="aarch64",
+        target_feature="neon"
+    )
+))]
+#[inline]
+pub fn xmul64x4(a: [u64; 4], b: [u64; 4]) -> ([u64; 4], [u64; 4]) {
+    cfg_if! {
+        if #[cfg(all(
+            not(feature="no-xmul"),
+            target_arch="x86_64",
+            target_feature="vpclmulqdq",
+            target_feature="avx512f"
+        ))] {
+            // x86_64 with avx512 provides 4x 64-bit xmul via the vpclmulqdq
+            // instruction, one per 128-bit lane
+            use core::arch::x86_64::*;
+            unsafe {
+                let a = _mm512_set_epi64(
+                    0, a[3] as i64, 0, a[2] as i64,
+                    0, a[1] as i64, 0, a[0] as i64
+                );
+                let b = _mm512_set_epi64(
+                    0, b[3] as i64, 0, b[2] as i64,
+                    0, b[1] as i64, 0, b[0] as i64
+                );
+                let x = _mm512_clmulepi64_epi128::<0x00>(a, b);
+                let mut buf = [0u64; 8];
+                _mm512_storeu_si512(buf.as_mut_ptr() as *mut _, x);
+                (
+                    [buf[0], buf[2], buf[4], buf[6]],
+                    [buf[1], buf[3], buf[5], buf[7]],
+                )
+            }
+        } else {
+            // fall back to 4 separate xmuls
+            let (lo0, hi0) = xmul64(a[0], b[0]);
+            let (lo1, hi1) = xmul64(a[1], b[1]);
+            let (lo2, hi2) = xmul64(a[2], b[2]);
+            let (lo3, hi3) = xmul64(a[3], b[3]);
+            ([lo0, lo1, lo2, lo3], [hi0, hi1, hi2, hi3])
+        }
+    }
+}
+
+/// Batched widening carry-less multiplication, if hardware instructions
+/// are available
+///
+/// Result is a tuple of arrays (lo, hi)
+///
+#[cfg(any(
+    all(
+        not(feature="no-xmul"),
+        target_arch="x86_64",
+        target_feature="pclmulqdq"
+    ),
+    all(
+        not(feature="no-xmul"),
+        target_arch="aarch64",
+        target_feature="neon"
+    )
+))]
+#[inline]
+pub fn xmul8x4(a: [u8; 4], b: [u8; 4]) -> ([u8; 4], [u8; 4]) {
+    // the full product always fits in the low 64-bits
+    let (x, _) = xmul64x4(
+        [a[0] as u64, a[1] as u64, a[2] as u64, a[3] as u64],
+        [b[0] as u64, b[1] as u64, b[2] as u64, b[3] as u64]
+    );
+    (
+        [x[0] as u8, x[1] as u8, x[2] as u8, x[3] as u8],
+        [(x[0] >> 8) as u8, (x[1] >> 8) as u8, (x[2] >> 8) as u8, (x[3] >> 8) as u8],
+    )
+}
+
+/// Batched widening carry-less multiplication, if hardware instructions
+/// are available
+///
+/// Result is a tuple of arrays (lo, hi)
+///
+#[cfg(any(
+    all(
+        not(feature="no-xmul"),
+        target_arch="x86_64",
+        target_feature="pclmulqdq"
+    ),
+    all(
+        not(feature="no-xmul"),
+        target_arch="aarch64",
+        target_feature="neon"
+    )
+))]
+#[inline]
+pub fn xmul16x4(a: [u16; 4], b: [u16; 4]) -> ([u16; 4], [u16; 4]) {
+    // the full product always fits in the low 64-bits
+    let (x, _) = xmul64x4(
+        [a[0] as u64, a[1] as u64, a[2] as u64, a[3] as u64],
+        [b[0] as u64, b[1] as u64, b[2] as u64, b[3] as u64]
+    );
+    (
+        [x[0] as u16, x[1] as u16, x[2] as u16, x[3] as u16],
+        [(x[0] >> 16) as u16, (x[1] >> 16) as u16, (x[2] >> 16) as u16, (x[3] >> 16) as u16],
+    )
+}
+
+/// Batched widening carry-less multiplication, if hardware instructions
+/// are available
+///
+/// Result is a tuple of arrays (lo, hi)
+///
+#[cfg(any(
+    all(
+        not(feature="no-xmul"),
+        target_arch="x86_64",
+        target_feature="pclmulqdq"
+    ),
+    all(
+        not(feature="no-xmul"),
+        target_arch="aarch64",
+        target_feature="neon"
+    )
+))]
+#[inline]
+pub fn xmul32x4(a: [u32; 4], b: [u32; 4]) -> ([u32; 4], [u32; 4]) {
+    // the full product always fits in the low 64-bits
+    let (x, _) = xmul64x4(
+        [a[0] as u64, a[1] as u64, a[2] as u64, a[3] as u64],
+        [b[0] as u64, b[1] as u64, b[2] as u64, b[3] as u64]
+    );
+    (
+        [x[0] as u32, x[1] as u32, x[2] as u32, x[3] as u32],
+        [(x[0] >> 32) as u32, (x[1] >> 32) as u32, (x[2] >> 32) as u32, (x[3] >> 32) as u32],
+    )
+}
+
+/// Batched widening carry-less multiplication, if hardware instructions
+/// are available
+///
+/// Result is a tuple of arrays (lo, hi)
+///
+#[cfg(any(
+    all(
+        not(feature="no-xmul"),
+        target_arch="x86_64",
+        target_feature="pclmulqdq"
+    ),
+    all(
+        not(feature="no-xmul"),
+        target_arch="aarch64",
+        target_feature="neon"
+    )
+))]
+#[inline]
+pub fn xmul128x4(a: [u128; 4], b: [u128; 4]) -> ([u128; 4], [u128; 4]) {
+    let (lo0, hi0) = xmul128(a[0], b[0]);
+    let (lo1, hi1) = xmul128(a[1], b[1]);
+    let (lo2, hi2) = xmul128(a[2], b[2]);
+    let (lo3, hi3) = xmul128(a[3], b[3]);
+    ([lo0, lo1, lo2, lo3], [hi0, hi1, hi2, hi3])
+}
+
+
+/// Widening carry-less multiplication in software
+///
+/// This is used when hardware carry-less multiplication isn't available,
+/// and is a significant improvement over a naive bit-by-bit implementation
+/// on targets such as wasm32 or older x86_64 without `pclmulqdq`.
+///
+/// This works by multiplying 4-bits at a time, using a small 16-element
+/// table of multiples of `b`. The top 3 bits of `b` would overflow the
+/// table's entries, so they are handled separately with masks to avoid
+/// branches.
+///
+/// Note the table is indexed by `a`, so this is only "constant-time-ish",
+/// it may be susceptible to cache-timing attacks on some hardware. If
+/// this is a concern, the feature `no-tables` forces a naive bitwise
+/// implementation.
+///
+/// Result is a tuple (lo, hi)
+///
+#[inline]
+pub fn soft_xmul8(a: u8, b: u8) -> (u8, u8) {
+    // build a table of b*i for i in 0..16, clearing the top 3 bits
+    // of b so our table's entries can't overflow
+    let b_ = b & (u8::MAX >> 3);
+    let mut table = [0; 16];
+    let mut i = 1;
+    while i < 16 {
+        table[i] = table[i & (i-1)] ^ (b_ << i.trailing_zeros());
+        i += 1;
+    }
+
+    // multiply 4-bits at a time
+    let mut lo: u8 = 0;
+    let mut hi: u8 = 0;
+    let mut i = 8;
+    while i > 0 {
+        i -= 4;
+        hi = (hi << 4) | (lo >> (8-4));
+        lo = (lo << 4) ^ table[((a >> i) & 0xf) as usize];
+    }
+
+    // handle the top 3 bits of b, using masks to avoid branches
+    let mut i = 8-3;
+    while i < 8 {
+        let mask = ((b >> i) & 1).wrapping_neg();
+        lo ^= mask & (a << i);
+        hi ^= mask & (a >> (8-i));
+        i += 1;
+    }
+
+    (lo, hi)
+}
+
+/// Widening carry-less multiplication in software
+///
+/// See [`soft_xmul8`] for more info.
+///
+/// Result is a tuple (lo, hi)
+///
+#[inline]
+pub fn soft_xmul16(a: u16, b: u16) -> (u16, u16) {
+    // build a table of b*i for i in 0..16, clearing the top 3 bits
+    // of b so our table's entries can't overflow
+    let b_ = b & (u16::MAX >> 3);
+    let mut table = [0; 16];
+    let mut i = 1;
+    while i < 16 {
+        table[i] = table[i & (i-1)] ^ (b_ << i.trailing_zeros());
+        i += 1;
+    }
+
+    // multiply 4-bits at a time
+    let mut lo: u16 = 0;
+    let mut hi: u16 = 0;
+    let mut i = 16;
+    while i > 0 {
+        i -= 4;
+        hi = (hi << 4) | (lo >> (16-4));
+        lo = (lo << 4) ^ table[((a >> i) & 0xf) as usize];
+    }
+
+    // handle the top 3 bits of b, using masks to avoid branches
+    let mut i = 16-3;
+    while i < 16 {
+        let mask = ((b >> i) & 1).wrapping_neg();
+        lo ^= mask & (a << i);
+        hi ^= mask & (a >> (16-i));
+        i += 1;
+    }
+
+    (lo, hi)
+}
+
+/// Widening carry-less multiplication in software
+///
+/// See [`soft_xmul8`] for more info.
+///
+/// Result is a tuple (lo, hi)
+///
+#[inline]
+pub fn soft_xmul32(a: u32, b: u32) -> (u32, u32) {
+    // build a table of b*i for i in 0..16, clearing the top 3 bits
+    // of b so our table's entries can't overflow
+    let b_ = b & (u32::MAX >> 3);
+    let mut table = [0; 16];
+    let mut i = 1;
+    while i < 16 {
+        table[i] = table[i & (i-1)] ^ (b_ << i.trailing_zeros());
+        i += 1;
+    }
+
+    // multiply 4-bits at a time
+    let mut lo: u32 = 0;
+    let mut hi: u32 = 0;
+    let mut i = 32;
+    while i > 0 {
+        i -= 4;
+        hi = (hi << 4) | (lo >> (32-4));
+        lo = (lo << 4) ^ table[((a >> i) & 0xf) as usize];
+    }
+
+    // handle the top 3 bits of b, using masks to avoid branches
+    let mut i = 32-3;
+    while i < 32 {
+        let mask = ((b >> i) & 1).wrapping_neg();
+        lo ^= mask & (a << i);
+        hi ^= mask & (a >> (32-i));
+        i += 1;
+    }
+
+    (lo, hi)
+}
+
+/// Widening carry-less multiplication in software
+///
+/// See [`soft_xmul8`] for more info.
+///
+/// Result is a tuple (lo, hi)
+///
+#[inline]
+pub fn soft_xmul64(a: u64, b: u64) -> (u64, u64) {
+    // build a table of b*i for i in 0..16, clearing the top 3 bits
+    // of b so our table's entries can't overflow
+    let b_ = b & (u64::MAX >> 3);
+    let mut table = [0; 16];
+    let mut i = 1;
+    while i < 16 {
+        table[i] = table[i & (i-1)] ^ (b_ << i.trailing_zeros());
+        i += 1;
+    }
+
+    // multiply 4-bits at a time
+    let mut lo: u64 = 0;
+    let mut hi: u64 = 0;
+    let mut i = 64;
+    while i > 0 {
+        i -= 4;
+        hi = (hi << 4) | (lo >> (64-4));
+        lo = (lo << 4) ^ table[((a >> i) & 0xf) as usize];
+    }
+
+    // handle the top 3 bits of b, using masks to avoid branches
+    let mut i = 64-3;
+    while i < 64 {
+        let mask = ((b >> i) & 1).wrapping_neg();
+        lo ^= mask & (a << i);
+        hi ^= mask & (a >> (64-i));
+        i += 1;
+    }
+
+    (lo, hi)
+}
+
+/// Widening carry-less multiplication in software
+///
+/// See [`soft_xmul8`] for more info.
+///
+/// Result is a tuple (lo, hi)
+///
+#[inline]
+pub fn soft_xmul128(a: u128, b: u128) -> (u128, u128) {
+    // build a table of b*i for i in 0..16, clearing the top 3 bits
+    // of b so our table's entries can't overflow
+    let b_ = b & (u128::MAX >> 3);
+    let mut table = [0; 16];
+    let mut i = 1;
+    while i < 16 {
+        table[i] = table[i & (i-1)] ^ (b_ << i.trailing_zeros());
+        i += 1;
+    }
+
+    // multiply 4-bits at a time
+    let mut lo: u128 = 0;
+    let mut hi: u128 = 0;
+    let mut i = 128;
+    while i > 0 {
+        i -= 4;
+        hi = (hi << 4) | (lo >> (128-4));
+        lo = (lo << 4) ^ table[((a >> i) & 0xf) as usize];
+    }
+
+    // handle the top 3 bits of b, using masks to avoid branches
+    let mut i = 128-3;
+    while i < 128 {
+        let mask = ((b >> i) & 1).wrapping_neg();
+        lo ^= mask & (a << i);
+        hi ^= mask & (a >> (128-i));
+        i += 1;
+    }
+
+    (lo, hi)
+}
+
+/// Check if hardware carry-less multiplication instructions are available
+/// on the current CPU.
+///
+/// With the `std` feature, this probes the CPU at runtime, so it may find
+/// instructions that weren't enabled at compile time. std caches the result,
+/// so this is only an atomic load after the first call. Without `std`, this
+/// is the same as [`HAS_XMUL`].
+///
+#[inline]
+pub fn detect_xmul() -> bool {
+    cfg_if! {
+        if #[cfg(feature="no-xmul")] {
+            false
+        } else if #[cfg(all(target_arch="x86_64", feature="std"))] {
+            HAS_XMUL || std::is_x86_feature_detected!("pclmulqdq")
+        } else if #[cfg(all(target_arch="aarch64", feature="std"))] {
+            HAS_XMUL || (
+                std::arch::is_aarch64_feature_detected!("neon")
+                    && std::arch::is_aarch64_feature_detected!("aes")
+            )
+        } else {
+            HAS_XMUL
+        }
+    }
+}
+
+/// Widening carry-less multiplication, using hardware instructions found
+/// at runtime
+///
+/// This is only used by adaptive CRCs, which wrap this in a private
+/// polynomial type that is only used in functions compiled with the
+/// relevant target_features, and only after [`detect_xmul`] returns true.
+///
+/// Result is a tuple (lo, hi)
+///
+/// # Safety
+///
+/// The current CPU must support carry-less multiplication, see
+/// [`detect_xmul`].
+///
+#[cfg(all(
+    feature="std",
+    not(feature="no-xmul"),
+    any(
+        all(target_arch="x86_64", not(target_feature="pclmulqdq")),
+        all(target_arch="aarch64", not(target_feature="neon"))
+    )
+))]
+#[cfg_attr(target_arch="x86_64", target_feature(enable="pclmulqdq"))]
+#[cfg_attr(target_arch="aarch64", target_feature(enable="neon,aes"))]
+#[inline]
+pub unsafe fn runtime_xmul64(a: u64, b: u64) -> (u64, u64) {
+    cfg_if! {
+        if #[cfg(target_arch="x86_64")] {
+            // x86_64 provides 64-bit xmul via the pclmulqdq instruction,
+            // note we stick to sse2 to extract the result
+            use core::arch::x86_64::*;
+            let a = _mm_cvtsi64_si128(a as i64);
+            let b = _mm_cvtsi64_si128(b as i64);
+            let x = _mm_clmulepi64_si128::<0>(a, b);
+            let lo = _mm_cvtsi128_si64(x) as u64;
+            let hi = _mm_cvtsi128_si64(_mm_unpackhi_epi64(x, x)) as u64;
+            (lo, hi)
+        } else {
+            // aarch64 provides 64-bit xmul via the pmull instruction
+            use core::arch::aarch64::*;
+            let x = vmull_p64(a, b);
+            (x as u64, (x >> 64) as u64)
+        }
+    }
+}
+
+/// Widening carry-less multiplication, using hardware instructions found
+/// at runtime
+///
+/// See [`runtime_xmul64`] for more info.
+///
+/// Result is a tuple (lo, hi)
+///
+/// # Safety
+///
+/// The current CPU must support carry-less multiplication, see
+/// [`detect_xmul`].
+///
+#[cfg(all(
+    feature="std",
+    not(feature="no-xmul"),
+    any(
+        all(target_arch="x86_64", not(target_feature="pclmulqdq")),
+        all(target_arch="aarch64", not(target_feature="neon"))
+    )
+))]
+#[cfg_attr(target_arch="x86_64", target_feature(enable="pclmulqdq"))]
+#[cfg_attr(target_arch="aarch64", target_feature(enable="neon,aes"))]
+#[inline]
+pub unsafe fn runtime_xmul8(a: u8, b: u8) -> (u8, u8) {
+    let (x, _) = runtime_xmul64(u64::from(a), u64::from(b));
+    (x as u8, (x >> 8) as u8)
+}
+
+/// Widening carry-less multiplication, using hardware instructions found
+/// at runtime
+///
+/// See [`runtime_xmul64`] for more info.
+///
+/// Result is a tuple (lo, hi)
+///
+/// # Safety
+///
+/// The current CPU must support carry-less multiplication, see
+/// [`detect_xmul`].
+///
+#[cfg(all(
+    feature="std",
+    not(feature="no-xmul"),
+    any(
+        all(target_arch="x86_64", not(target_feature="pclmulqdq")),
+        all(target_arch="aarch64", not(target_feature="neon"))
+    )
+))]
+#[cfg_attr(target_arch="x86_64", target_feature(enable="pclmulqdq"))]
+#[cfg_attr(target_arch="aarch64", target_feature(enable="neon,aes"))]
+#[inline]
+pub unsafe fn runtime_xmul16(a: u16, b: u16) -> (u16, u16) {
+    let (x, _) = runtime_xmul64(u64::from(a), u64::from(b));
+    (x as u16, (x >> 16) as u16)
+}
+
+/// Widening carry-less multiplication, using hardware instructions found
+/// at runtime
+///
+/// See [`runtime_xmul64`] for more info.
+///
+/// Result is a tuple (lo, hi)
+///
+/// # Safety
+///
+/// The current CPU must support carry-less multiplication, see
+/// [`detect_xmul`].
+///
+#[cfg(all(
+    feature="std",
+    not(feature="no-xmul"),
+    any(
+        all(target_arch="x86_64", not(target_feature="pclmulqdq")),
+        all(target_arch="aarch64", not(target_feature="neon"))
+    )
+))]
+#[cfg_attr(target_arch="x86_64", target_feature(enable="pclmulqdq"))]
+#[cfg_attr(target_arch="aarch64", target_feature(enable="neon,aes"))]
+#[inline]
+pub unsafe fn runtime_xmul32(a: u32, b: u32) -> (u32, u32) {
+    let (x, _) = runtime_xmul64(u64::from(a), u64::from(b));
+    (x as u32, (x >> 32) as u32)
+}
+
 #[cfg(test)]
 mod test {
     #[allow(unused)]
@@ -329,4 +1147,102 @@ mod test {
         assert_eq!(xmul64(0x123456789abcdef1, 0x123456789abcdef1), (0x4144455051545501, 0x0104051011141540));
         assert_eq!(xmul128(0x123456789abcdef123456789abcdef12, 0x123456789abcdef123456789abcdef12), (0x04051011141540414445505154550104, 0x01040510111415404144455051545501));
     }
+
+    #[cfg(any(
+        all(
+            not(feature="no-xmul"),
+            target_arch="x86_64",
+            target_feature="pclmulqdq"
+        ),
+        all(
+            not(feature="no-xmul"),
+            target_arch="aarch64",
+            target_feature="neon"
+        )
+    ))]
+    #[test]
+    fn xmul_acc() {
+        assert_eq!(xmul8_acc((0x00, 0x00), 0x12, 0x12), (0x04, 0x01));
+        assert_eq!(xmul8_acc((0x05, 0xf1), 0x12, 0x12), (0x01, 0xf0));
+        assert_eq!(xmul16_acc((0x0510, 0x0104), 0x1234, 0x1234), (0x0000, 0x0000));
+        assert_eq!(xmul32_acc((0x11141541, 0x01040511), 0x12345678, 0x12345678), (0x00000001, 0x00000001));
+        assert_eq!(xmul64_acc((0xffffffffffffffff, 0), 0x123456789abcdef1, 0x123456789abcdef1), (0xbebbbaafaeabaafe, 0x0104051011141540));
+        assert_eq!(xmul128_acc((1, 2), 0x123456789abcdef123456789abcdef12, 0x123456789abcdef123456789abcdef12), (0x04051011141540414445505154550105, 0x01040510111415404144455051545503));
+    }
+
+    #[test]
+    fn soft_xmul() {
+        assert_eq!(soft_xmul8(0x12, 0x12), (0x04, 0x01));
+        assert_eq!(soft_xmul16(0x1234, 0x1234), (0x0510, 0x0104));
+        assert_eq!(soft_xmul32(0x12345678, 0x12345678), (0x11141540, 0x01040510));
+        assert_eq!(soft_xmul64(0x123456789abcdef1, 0x123456789abcdef1), (0x4144455051545501, 0x0104051011141540));
+        assert_eq!(soft_xmul128(0x123456789abcdef123456789abcdef12, 0x123456789abcdef123456789abcdef12), (0x04051011141540414445505154550104, 0x01040510111415404144455051545501));
+
+        // top bits of b are handled separately
+        assert_eq!(soft_xmul8(0xff, 0xff), (0x55, 0x55));
+        assert_eq!(soft_xmul8(0x01, 0xe0), (0xe0, 0x00));
+        assert_eq!(soft_xmul8(0x80, 0xe0), (0x00, 0x70));
+        assert_eq!(soft_xmul64(u64::MAX, u64::MAX), (0x5555555555555555, 0x5555555555555555));
+        assert_eq!(soft_xmul128(u128::MAX, u128::MAX), (0x55555555555555555555555555555555, 0x55555555555555555555555555555555));
+
+        // compare against naive bitwise multiplication
+        fn naive_xmul(a: u128, b: u128) -> (u128, u128) {
+            let mut lo = 0;
+            let mut hi = 0;
+            for i in 0..128 {
+                if b & (1 << i) != 0 {
+                    lo ^= a << i;
+                    hi ^= a.checked_shr(128-i).unwrap_or(0);
+                }
+            }
+            (lo, hi)
+        }
+
+        let mut x = 0x123456789abcdef123456789abcdef12u128;
+        for _ in 0..1000 {
+            // xorshift128
+            x ^= x << 35; x ^= x >> 49; x ^= x << 61;
+            let a = x;
+            x ^= x << 35; x ^= x >> 49; x ^= x << 61;
+            let b = x;
+
+            let (lo, _) = naive_xmul(a as u8 as u128, b as u8 as u128);
+            assert_eq!(soft_xmul8(a as u8, b as u8), (lo as u8, (lo >> 8) as u8));
+            let (lo, _) = naive_xmul(a as u16 as u128, b as u16 as u128);
+            assert_eq!(soft_xmul16(a as u16, b as u16), (lo as u16, (lo >> 16) as u16));
+            let (lo, _) = naive_xmul(a as u32 as u128, b as u32 as u128);
+            assert_eq!(soft_xmul32(a as u32, b as u32), (lo as u32, (lo >> 32) as u32));
+            let (lo, _) = naive_xmul(a as u64 as u128, b as u64 as u128);
+            assert_eq!(soft_xmul64(a as u64, b as u64), (lo as u64, (lo >> 64) as u64));
+            assert_eq!(soft_xmul128(a, b), naive_xmul(a, b));
+        }
+    }
+
+    #[cfg(any(
+        all(
+            not(feature="no-xmul"),
+            target_arch="x86_64",
+            target_feature="pclmulqdq"
+        ),
+        all(
+            not(feature="no-xmul"),
+            target_arch="aarch64",
+            target_feature="neon"
+        )
+    ))]
+    #[test]
+    fn xmul_x4() {
+        assert_eq!(xmul8x4([0x12, 0x02, 0x12, 0xff], [0x12, 0x34, 0x34, 0xff]), ([0x04, 0x68, 0x28, 0x55], [0x01, 0x00, 0x03, 0x55]));
+        assert_eq!(xmul64x4([0x123456789abcdef1, 1, 2, 3], [0x123456789abcdef1, 1, 2, 3]), ([0x4144455051545501, 1, 4, 5], [0x0104051011141540, 0, 0, 0]));
+        for (a, b) in [(0x1234u16, 0x5678u16), (0xffff, 0xffff), (0, 0x1234)] {
+            let (lo, hi) = xmul16x4([a; 4], [b; 4]);
+            assert_eq!((lo[3], hi[3]), xmul16(a, b));
+        }
+        for (a, b) in [(0x12345678u32, 0x9abcdef1u32), (0xffffffff, 0xffffffff)] {
+            let (lo, hi) = xmul32x4([a; 4], [b; 4]);
+            assert_eq!((lo[1], hi[1]), xmul32(a, b));
+        }
+        let (lo, hi) = xmul128x4([0x123456789abcdef123456789abcdef12; 4], [0x123456789abcdef123456789abcdef12; 4]);
+        assert_eq!((lo[2], hi[2]), xmul128(0x123456789abcdef123456789abcdef12, 0x123456789abcdef123456789abcdef12));
+    }
 }
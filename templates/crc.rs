@@ -27,232 +27,683 @@ use core::mem::size_of;
 /// assert_eq!(crc32c(b"Hello World!", 0), 0xfe6cf1dc);
 /// ```
 ///
+/// Note this only works if the CRC's `init` and `xor` are the same, which is
+/// the default. Otherwise the output of the CRC can't be used as the next
+/// input.
+///
 /// See the [module-level documentation](../crc) for more info.
 ///
 pub fn __crc(data: &[u8], crc: __u) -> __u {
     cfg_if! {
-        if #[cfg(__if(__naive))] {
-            let mut crc = __p(crc ^ __xor);
-
-            cfg_if! {
-                if #[cfg(__if(__reflected))] {
-                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
-                }
+        if #[cfg(__if(__adaptive))] {
+            // short_crc and long_crc are generated alongside this template
+            if data.len() < __threshold {
+                short_crc(data, crc)
+            } else {
+                long_crc(data, crc)
             }
+        } else {
+            // crc_kernel is generated alongside this template, see
+            // templates/crc_kernel.rs
+            crc_kernel(data, crc)
+        }
+    }
+}
 
-            crc = crc << 8*size_of::<__u>()-__width;
 
-            // iterate over words
-            let mut words = data.chunks_exact(size_of::<__u>());
-            for word in &mut words {
-                let word = <[u8; size_of::<__u>()]>::try_from(word).unwrap();
-                cfg_if! {
-                    if #[cfg(__if(__reflected))] {
-                        crc = crc + __p::from_le_bytes(word).reverse_bits();
+
+/// Calculate the CRC for a piece of data split into multiple chunks.
+///
+/// This is useful for scatter-gather buffers, where the data is not
+/// contiguous in memory:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let chunks: [&[u8]; 3] = [b"Hell", b"o Wo", b"rld!"];
+/// assert_eq!(crc32c_vectored(chunks, 0), 0xfe6cf1dc);
+/// ```
+///
+/// Unlike passing the previous CRC back into the CRC function, this works
+/// for any CRC, including CRCs where `init` and `xor` differ.
+///
+pub fn __crc_vectored<'a, I>(data: I, crc: __u) -> __u
+where
+    I: IntoIterator<Item=&'a [u8]>
+{
+    // keep track of our state before the final xor, same as our digest
+    let mut crc = crc ^ __init;
+    for data in data {
+        crc = __crc(data, crc ^ __init) ^ __xor;
+    }
+    crc ^ __xor
+}
+
+/// Calculate the CRC for a piece of data at compile time.
+///
+/// This is a simple bitwise implementation that can be evaluated in const
+/// contexts, which is useful for computing constants that embed CRCs:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// const HEADER_CRC: u32 = crc32c_const(b"Hello World!", 0);
+/// assert_eq!(HEADER_CRC, 0xfe6cf1dc);
+/// assert_eq!(HEADER_CRC, crc32c(b"Hello World!", 0));
+/// ```
+///
+/// Note this is much slower than the other CRC functions at runtime.
+///
+pub const fn __crc_const(data: &[u8], crc: __u) -> __u {
+    cfg_if! {
+        if #[cfg(__if(__reflected))] {
+            // reflected CRCs shift right, so we need a reflected polynomial
+            const POLYNOMIAL: __u = (((__polynomial as u128) & __nonzeros) as __u).reverse_bits()
+                >> (8*size_of::<__u>()-__width);
+
+            let mut crc = crc ^ __init;
+            let mut i = 0;
+            while i < data.len() {
+                crc ^= data[i] as __u;
+                let mut j = 0;
+                while j < 8 {
+                    crc = if crc & 1 != 0 {
+                        (crc >> 1) ^ POLYNOMIAL
                     } else {
-                        crc = crc + __p::from_be_bytes(word);
-                    }
+                        crc >> 1
+                    };
+                    j += 1;
                 }
-                crc = __p::try_from(
-                    (__p2::from(crc) << 8*size_of::<__u>()) % __p2(__polynomial << (8*size_of::<__u>()-__width))
-                ).unwrap();
+                i += 1;
             }
 
-            // handle remainder
-            for b in words.remainder() {
-                cfg_if! {
-                    if #[cfg(__if(__reflected))] {
-                        crc = crc + (__p::from(b.reverse_bits()) << (8*size_of::<__u>()-8));
+            crc ^ __xor
+        } else {
+            // unreflected CRCs shift left, so we align our CRC with the
+            // most-significant bit of the word
+            const POLYNOMIAL: __u = (((__polynomial as u128) & __nonzeros) as __u)
+                << (8*size_of::<__u>()-__width);
+
+            let mut crc = (crc ^ __init) << (8*size_of::<__u>()-__width);
+            let mut i = 0;
+            while i < data.len() {
+                crc ^= (data[i] as __u) << (8*size_of::<__u>()-8);
+                let mut j = 0;
+                while j < 8 {
+                    crc = if crc >> (8*size_of::<__u>()-1) != 0 {
+                        (crc << 1) ^ POLYNOMIAL
                     } else {
-                        crc = crc + (__p::from(*b) << (8*size_of::<__u>()-8));
-                    }
+                        crc << 1
+                    };
+                    j += 1;
                 }
-                crc = __p::try_from(
-                    (__p2::from(crc) << 8) % __p2(__polynomial << (8*size_of::<__u>()-__width))
-                ).unwrap();
+                i += 1;
             }
 
-            // our division is always 8-bit aligned, so we need to do some
-            // finagling if our crc is not 8-bit aligned
-            crc = crc >> 8*size_of::<__u>()-__width;
+            (crc >> (8*size_of::<__u>()-__width)) ^ __xor
+        }
+    }
+}
 
-            cfg_if! {
-                if #[cfg(__if(__reflected))] {
-                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
-                }
-            }
 
-            __u::from(crc) ^ __xor
-        } else if #[cfg(__if(__table))] {
-            const CRC_TABLE: [__u; 256] = {
-                let mut table = [0; 256];
-                let mut i = 0;
-                while i < table.len() {
-                    cfg_if! {
-                        if #[cfg(__if(__reflected))] {
-                            let x = ((i as u8).reverse_bits() as __u) << (8*size_of::<__u>()-8);
-                            let x = __p2((x as __u2) << 8)
-                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
-                            table[i] = x.reverse_bits();
-                            i += 1;
-                        } else {
-                            let x = (i as __u) << (8*size_of::<__u>()-8);
-                            let x = __p2((x as __u2) << 8)
-                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
-                            table[i] = x;
-                            i += 1;
-                        }
-                    }
-                }
-                table
-            };
+/// Combine the CRCs of two pieces of data into the CRC of their
+/// concatenation.
+///
+/// This only needs the length of the second piece of data, so CRCs can be
+/// computed in parallel and merged afterwards:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let a = crc32c(b"Hello ", 0);
+/// let b = crc32c(b"World!", 0);
+/// assert_eq!(crc32c_combine(a, b, 6), 0xfe6cf1dc);
+/// assert_eq!(crc32c(b"Hello World!", 0), 0xfe6cf1dc);
+/// ```
+///
+/// This runs in `O(log n)` multiplications, where n is the length of the
+/// second piece of data.
+///
+pub fn __combine(crc_a: __u, crc_b: __u, len_b: usize) -> __u {
+    // CRCs are linear, so the state after a || b is the state after b,
+    // starting from zero, plus the state after a shifted by len(b) bytes.
+    // Adjusting for init and xor:
+    //
+    // crc(a || b) = crc(b) + (crc(a) + xor + init)*x^(8*len(b)) mod p
+    //
+    crc_b ^ shift(crc_a ^ __xor ^ __init, len_b)
+}
 
-            cfg_if! {
-                if #[cfg(__if(__reflected))] {
-                    let mut crc = crc ^ __xor;
-                } else {
-                    let mut crc = (crc ^ __xor) << (8*size_of::<__u>()-__width);
-                }
-            }
+/// Recover the CRC of the first piece of data, given the CRC of the
+/// concatenation and the CRC of the second piece of data.
+///
+/// This is the inverse of the `combine` function, and only needs the length
+/// of the second piece of data:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let ab = crc32c(b"Hello World!", 0);
+/// let b = crc32c(b"World!", 0);
+/// assert_eq!(crc32c_uncombine(ab, b, 6), crc32c(b"Hello ", 0));
+/// ```
+///
+/// Note that to remove the first piece of data instead, `combine` can
+/// be used directly, since the CRCs are combined with xor:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let ab = crc32c(b"Hello World!", 0);
+/// let a = crc32c(b"Hello ", 0);
+/// assert_eq!(crc32c_combine(a, ab, 6), crc32c(b"World!", 0));
+/// ```
+///
+/// This runs in `O(log n)` multiplications, where n is the length of the
+/// second piece of data.
+///
+pub fn __uncombine(crc_ab: __u, crc_b: __u, len_b: usize) -> __u {
+    // solving our combine equation for crc(a):
+    //
+    // crc(a) = (crc(a || b) + crc(b))*x^-(8*len(b)) + xor + init mod p
+    //
+    unshift(crc_ab ^ crc_b, len_b) ^ __xor ^ __init
+}
 
-            for b in data {
-                cfg_if! {
-                    if #[cfg(__if(__width <= 8))] {
-                        crc = CRC_TABLE[usize::from((crc as u8) ^ b)];
-                    } else if #[cfg(__if(__reflected))] {
-                        crc = (crc >> 8) ^ CRC_TABLE[usize::from((crc as u8) ^ b)];
-                    } else {
-                        crc = (crc << 8) ^ CRC_TABLE[usize::from(((crc >> (8*size_of::<__u>()-8)) as u8) ^ b)];
-                    }
-                }
-            }
+// Multiply a CRC's state by a^len mod p
+fn mul_pow(x: __u, a: __p2, len: usize) -> __u {
+    let mul = |a: __p2, b: __p2| -> __p2 {
+        a.wrapping_mul(b) % __p2(__polynomial)
+    };
 
-            // our division is always 8-bit aligned, so we need to do some
-            // finagling if our crc is not 8-bit aligned
-            cfg_if! {
-                if #[cfg(__if(__reflected))] {
-                    crc = crc & __nonzeros;
-                } else {
-                    crc = crc >> (8*size_of::<__u>()-__width);
-                }
-            }
+    // Binary exponentiation to find a^len mod p
+    let mut a = a;
+    let mut len = len;
+    let mut g = __p2(1);
+    while len != 0 {
+        if len & 1 != 0 {
+            g = mul(g, a);
+        }
 
-            crc ^ __xor
-        } else if #[cfg(__if(__small_table))] {
-            const CRC_TABLE: [__u; 16] = {
-                let mut table = [0; 16];
-                let mut i = 0;
-                while i < table.len() {
-                    cfg_if! {
-                        if #[cfg(__if(__reflected))] {
-                            let x = ((i as u8).reverse_bits() as __u) << (8*size_of::<__u>()-8);
-                            let x = __p2((x as __u2) << 4)
-                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
-                            table[i] = x.reverse_bits();
-                            i += 1;
-                        } else {
-                            let x = (i as __u) << (8*size_of::<__u>()-4);
-                            let x = __p2((x as __u2) << 4)
-                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
-                            table[i] = x;
-                            i += 1;
-                        }
-                    }
-                }
-                table
-            };
+        len >>= 1;
+        if len == 0 {
+            break;
+        }
+        a = mul(a, a);
+    }
+
+    let mut x = x;
+    cfg_if! {
+        if #[cfg(__if(__reflected))] {
+            x = x.reverse_bits() >> (8*size_of::<__u>()-__width);
+        }
+    }
 
+    x = mul(__p2(x as __u2), g).0 as __u;
+    cfg_if! {
+        if #[cfg(__if(__reflected))] {
+            x = x.reverse_bits() >> (8*size_of::<__u>()-__width);
+        }
+    }
+
+    x
+}
+
+// Multiply a CRC's state by x^(8*len) mod p, this is the same as appending
+// len zero bytes to the message, ignoring init and xor
+fn shift(x: __u, len: usize) -> __u {
+    mul_pow(x, __p2(0x100) % __p2(__polynomial), len)
+}
+
+// Multiply a CRC's state by x^-(8*len) mod p, undoing shift
+fn unshift(x: __u, len: usize) -> __u {
+    // x is only invertible if our polynomial has a constant term, which
+    // is true for any useful CRC polynomial
+    assert!(__polynomial & 1 != 0);
+
+    // x*(p >> 1) = p - 1 = 1 mod p, so x^-1 = p >> 1
+    let mut a = __p2(__polynomial >> 1);
+    for _ in 0..3 {
+        a = a.wrapping_mul(a) % __p2(__polynomial);
+    }
+
+    mul_pow(x, a, len)
+}
+
+/// Update the CRC of a piece of data after a range of the data changes.
+///
+/// Given the old CRC, the old and new bytes, the offset of the change, and
+/// the total length of the data, this finds the new CRC without needing
+/// to reread the rest of the data:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let crc = crc32c(b"Hello World!", 0);
+/// assert_eq!(crc32c_patch(crc, b"World", b"Earth", 6, 12), crc32c(b"Hello Earth!", 0));
+/// ```
+///
+/// This runs in `O(n + log m)` time, where n is the length of the change,
+/// and m is the number of bytes after the change.
+///
+pub fn __patch(crc: __u, old: &[u8], new: &[u8], offset: usize, len: usize) -> __u {
+    assert_eq!(old.len(), new.len());
+    assert!(offset + old.len() <= len);
+
+    // CRCs are linear, so changing the data changes the CRC by the CRC of
+    // the difference, shifted by any bytes that follow. Note init and xor
+    // cancel out here, so we compute the difference starting from zero
+    let mut delta = 0;
+    let mut buf = [0u8; 64];
+    for (old, new) in old.chunks(buf.len()).zip(new.chunks(buf.len())) {
+        for i in 0..old.len() {
+            buf[i] = old[i] ^ new[i];
+        }
+        delta = __crc(&buf[..old.len()], delta ^ __init) ^ __xor;
+    }
+
+    crc ^ shift(delta, len - offset - old.len())
+}
+
+/// Calculate the CRCs of many pieces of data at once.
+///
+/// This takes a slice of messages and a slice of CRCs, one for each message,
+/// which are used as the previous state of each CRC, and updated with the
+/// resulting CRCs:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let data: [&[u8]; 3] = [b"Hello World!", b"Hello", b"World!"];
+/// let mut crcs = [0; 3];
+/// crc32c_batch(&data, &mut crcs);
+/// assert_eq!(crcs, [crc32c(b"Hello World!", 0), crc32c(b"Hello", 0), crc32c(b"World!", 0)]);
+/// ```
+///
+/// Messages are processed in groups of 8, with the words each group has in
+/// common interleaved using Barret-reduction. Independent CRCs don't depend
+/// on each other, so this keeps several multiplications in flight at once,
+/// which can be faster than computing each CRC separately when there are
+/// many small messages. Any remaining bytes are passed to the CRC function.
+///
+pub fn __batch(data: &[&[u8]], crcs: &mut [__u]) {
+    assert_eq!(data.len(), crcs.len());
+
+    const LANES: usize = 8;
+    const BARRET_CONSTANT: __p = {
+        __p(
+            __p2((__polynomial & __nonzeros) << ((8*size_of::<__u>()-__width) + 8*size_of::<__u>()))
+                .naive_div(__p2(__polynomial << (8*size_of::<__u>()-__width)))
+                .0 as __u
+        )
+    };
+
+    for (data, crcs) in data.chunks(LANES).zip(crcs.chunks_mut(LANES)) {
+        let lanes = data.len();
+        let mut state = [__p(0); LANES];
+        for i in 0..lanes {
+            let mut crc = __p(crcs[i] ^ __init);
             cfg_if! {
                 if #[cfg(__if(__reflected))] {
-                    let mut crc = crc ^ __xor;
-                } else {
-                    let mut crc = (crc ^ __xor) << (8*size_of::<__u>()-__width);
+                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
                 }
             }
+            state[i] = crc << 8*size_of::<__u>()-__width;
+        }
 
-            for b in data {
+        // interleave the words all messages in this group have in common
+        let words = data.iter()
+            .map(|data| data.len() / size_of::<__u>())
+            .min()
+            .unwrap_or(0);
+        for j in 0..words {
+            for i in 0..lanes {
+                let word = <[u8; size_of::<__u>()]>::try_from(
+                    &data[i][j*size_of::<__u>() .. (j+1)*size_of::<__u>()]
+                ).unwrap();
+                let mut crc = state[i];
                 cfg_if! {
                     if #[cfg(__if(__reflected))] {
-                        crc = (crc >> 4) ^ CRC_TABLE[usize::from((crc as u8) ^ (b >> 0)) & 0xf];
-                        crc = (crc >> 4) ^ CRC_TABLE[usize::from((crc as u8) ^ (b >> 4)) & 0xf];
+                        crc += __p::from_le_bytes(word).reverse_bits();
                     } else {
-                        crc = (crc << 4) ^ CRC_TABLE[usize::from(((crc >> (8*size_of::<__u>()-4)) as u8) ^ (b >> 4)) & 0xf];
-                        crc = (crc << 4) ^ CRC_TABLE[usize::from(((crc >> (8*size_of::<__u>()-4)) as u8) ^ (b >> 0)) & 0xf];
+                        crc += __p::from_be_bytes(word);
                     }
                 }
+                state[i] = crc.widening_mul_acc(BARRET_CONSTANT, (__p(0), crc)).1
+                    .wrapping_mul(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
             }
+        }
 
-            // our division is always 8-bit aligned, so we need to do some
-            // finagling if our crc is not 8-bit aligned
+        // finish each message separately, note the CRC function expects
+        // its previous state with init applied
+        for i in 0..lanes {
+            let mut crc = state[i] >> (8*size_of::<__u>()-__width);
             cfg_if! {
                 if #[cfg(__if(__reflected))] {
-                    crc = crc & __nonzeros;
-                } else {
-                    crc = crc >> (8*size_of::<__u>()-__width);
+                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
                 }
             }
+            crcs[i] = __crc(&data[i][words*size_of::<__u>()..], __u::from(crc) ^ __init);
+        }
+    }
+}
 
-            crc ^ __xor
-        } else if #[cfg(__if(__barret))] {
-            const BARRET_CONSTANT: __p = {
-                __p(
-                    __p2((__polynomial & __nonzeros) << ((8*size_of::<__u>()-__width) + 8*size_of::<__u>()))
-                        .naive_div(__p2(__polynomial << (8*size_of::<__u>()-__width)))
-                        .0 as __u
-                )
-            };
+/// Calculate the CRC for a piece of data in parallel.
+///
+/// This splits the data into chunks, computes the CRC of each chunk on
+/// rayon's thread pool, and merges the results with the `combine`
+/// function:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let data = vec![0x55u8; 1024*1024];
+/// assert_eq!(par_crc32c(&data, 0), crc32c(&data, 0));
+/// ```
+///
+/// Like the CRC function, this takes the previous state of the CRC as an
+/// argument. Small inputs are not worth splitting, so these are passed
+/// directly to the CRC function.
+///
+/// Note this requires the `rayon` feature.
+///
+#[cfg(__if(__rayon))]
+pub fn __par_crc(data: &[u8], crc: __u) -> __u {
+    use __crate::internal::rayon::prelude::*;
 
-            let mut crc = __p(crc ^ __xor);
+    // chunks need to be large enough to amortize the cost of combining,
+    // but we want a few chunks per thread to balance the load
+    const MIN_CHUNK: usize = 64*1024;
+    let threads = __crate::internal::rayon::current_num_threads();
+    let chunk = core::cmp::max(data.len().div_ceil(4*threads), MIN_CHUNK);
+    if data.len() <= chunk {
+        return __crc(data, crc);
+    }
 
-            cfg_if! {
-                if #[cfg(__if(__reflected))] {
-                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
-                }
+    // only the first chunk depends on the previous state, the rest start
+    // from zero and are shifted into place by combine, note the CRC of
+    // an empty message acts as an identity for combine
+    data.par_chunks(chunk)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let crc = __crc(chunk, if i == 0 { crc } else { 0 });
+            (crc, chunk.len())
+        })
+        .reduce(
+            || (__crc(&[], 0), 0),
+            |(crc_a, len_a), (crc_b, len_b)| {
+                (__combine(crc_a, crc_b, len_b), len_a+len_b)
             }
+        )
+        .0
+}
 
-            crc = crc << 8*size_of::<__u>()-__width;
+// The residue is the CRC of any message with its CRC appended, appending
+// the CRC cancels out all dependence on the message, leaving only the xor
+// shifted by the size of the trailing CRC
+const RESIDUE: __u = {
+    cfg_if! {
+        if #[cfg(__if(__reflected))] {
+            let xor = (__xor as __u).reverse_bits() >> (8*size_of::<__u>()-__width);
+        } else {
+            let xor = __xor as __u;
+        }
+    }
 
-            // iterate over words
-            let mut words = data.chunks_exact(size_of::<__u>());
-            for word in &mut words {
-                let word = <[u8; size_of::<__u>()]>::try_from(word).unwrap();
-                cfg_if! {
-                    if #[cfg(__if(__reflected))] {
-                        crc = crc + __p::from_le_bytes(word).reverse_bits();
-                    } else {
-                        crc = crc + __p::from_be_bytes(word);
-                    }
-                }
-                crc = (crc.widening_mul(BARRET_CONSTANT).1 + crc)
-                        .wrapping_mul(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
-            }
+    let residue = __p2((xor as __u2) << (8*usize::div_ceil(__width, 8)))
+        .naive_rem(__p2(__polynomial)).0 as __u;
 
-            // handle remainder
-            for b in words.remainder() {
-                cfg_if! {
-                    if #[cfg(__if(__reflected))] {
-                        crc = crc + (__p::from(b.reverse_bits()) << (8*size_of::<__u>()-8));
-                    } else {
-                        crc = crc + (__p::from(*b) << (8*size_of::<__u>()-8));
-                    }
-                }
-                crc = (crc << 8)
-                    + ((crc >> (8*size_of::<__u>()-8)).widening_mul(BARRET_CONSTANT).1 + (crc >> (8*size_of::<__u>()-8)))
-                        .wrapping_mul(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
-            }
+    cfg_if! {
+        if #[cfg(__if(__reflected))] {
+            let residue = residue.reverse_bits() >> (8*size_of::<__u>()-__width);
+        }
+    }
 
-            // our division is always 8-bit aligned, so we need to do some
-            // finagling if our crc is not 8-bit aligned
-            crc = crc >> (8*size_of::<__u>()-__width);
+    residue ^ __xor
+};
+
+/// Verify a piece of data with a trailing CRC.
+///
+/// Rather than recomputing the CRC and comparing, this computes the CRC
+/// over both the data and the trailing CRC, and compares the result against
+/// the CRC's residue, a constant that only depends on the CRC's parameters.
+///
+/// The CRC is expected to be appended in the natural byte order for the CRC,
+/// little-endian for reflected CRCs, big-endian for unreflected CRCs:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let mut data = [0u8; 16];
+/// data[..12].copy_from_slice(b"Hello World!");
+/// let crc = crc32c(&data[..12], 0);
+/// data[12..].copy_from_slice(&crc.to_le_bytes());
+/// assert!(crc32c_verify(&data));
+///
+/// data[1] ^= 0x04;
+/// assert!(!crc32c_verify(&data));
+/// ```
+///
+/// For CRCs whose width isn't a multiple of 8, the CRC is expected to be
+/// packed into the first bits of the trailing bytes, that is, the
+/// low bits for reflected CRCs, and the high bits for unreflected CRCs.
+///
+pub fn __verify(data: &[u8]) -> bool {
+    data.len() >= usize::div_ceil(__width, 8) && __crc(data, 0) == RESIDUE
+}
 
+/// Correct errors in a piece of data with a trailing CRC.
+///
+/// This is only generated if the `correct` option is provided, which limits
+/// the size of data that can be corrected. Bit errors, or bursts of errors
+/// up to the `burst` option, are located by looking up the syndrome in a
+/// table built at compile time.
+///
+/// The CRC is expected to be appended the same way as in the
+/// `verify` function.
+///
+/// Returns the number of bits corrected, `Error::TooManyErrors` if the data
+/// can not be corrected, or `Error::UnsupportedLength` if the data is shorter
+/// than the CRC or longer than the `correct` option.
+///
+/// ``` rust,ignore
+/// # use ::gf256::crc::*;
+/// #[crc(polynomial=0x11edc6f41, correct=64)]
+/// pub fn my_crc32() {}
+///
+/// let mut data = [0u8; 16];
+/// data[..12].copy_from_slice(b"Hello World!");
+/// let crc = my_crc32(&data[..12], 0);
+/// data[12..].copy_from_slice(&crc.to_le_bytes());
+///
+/// data[1] ^= 0x04;
+/// assert_eq!(my_crc32_correct(&mut data), Ok(1));
+/// assert_eq!(&data[..12], b"Hello World!");
+/// ```
+///
+/// Note a CRC can only reliably detect a limited number of errors, and using
+/// a CRC for correction uses up some of this capacity. Data with more errors
+/// than expected may be miscorrected.
+///
+#[cfg(__if(__has_correct))]
+pub fn __correct(data: &mut [u8]) -> Result<usize, __crate::crc::Error> {
+    // sorted (syndrome, position, pattern), positions count bits from the
+    // last bit of the data
+    const CORRECT_TABLE: &[(__u, usize, __u)] = &__correct_table;
+
+    if data.len() < usize::div_ceil(__width, 8) || data.len() > __correct_len {
+        return Err(__crate::crc::Error::UnsupportedLength);
+    }
+
+    // no errors?
+    let syndrome = __crc(data, 0) ^ RESIDUE;
+    if syndrome == 0 {
+        return Ok(0);
+    }
+
+    // find the error pattern
+    let (pos, pattern) = match CORRECT_TABLE.binary_search_by_key(&syndrome, |&(s, _, _)| s) {
+        Ok(i) => (CORRECT_TABLE[i].1, CORRECT_TABLE[i].2),
+        Err(_) => return Err(__crate::crc::Error::TooManyErrors),
+    };
+    if pos + ((8*size_of::<__u>()) - pattern.leading_zeros() as usize) > 8*data.len() {
+        return Err(__crate::crc::Error::TooManyErrors);
+    }
+
+    // correct the errors
+    let mut count = 0;
+    for j in 0..8*size_of::<__u>() {
+        if pattern & (1 << j) != 0 {
+            let k = 8*data.len()-1 - (pos+j);
             cfg_if! {
                 if #[cfg(__if(__reflected))] {
-                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
+                    data[k/8] ^= 1 << (k%8);
+                } else {
+                    data[k/8] ^= 0x80 >> (k%8);
                 }
             }
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// A stateful CRC digest, for computing a CRC over data that arrives in
+/// chunks.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let mut digest = Crc32cDigest::new();
+/// digest.update(b"Hell");
+/// digest.update(b"o Wo");
+/// digest.update(b"rld!");
+/// assert_eq!(digest.finalize(), 0xfe6cf1dc);
+/// ```
+///
+/// Unlike passing the previous CRC back into the CRC function, this keeps
+/// track of the CRC's state before the final xor, so this works for any
+/// CRC, including CRCs where `init` and `xor` differ:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let mut digest = Crc16CcittFalseDigest::new();
+/// digest.update(b"1234");
+/// digest.update(b"56789");
+/// assert_eq!(digest.finalize(), 0x29b1);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct __digest(__u);
+
+impl __digest {
+    /// The CRC of the ASCII string "123456789", the standard check value
+    /// used to identify CRCs in the catalogue of parametrised CRC algorithms.
+    pub const CHECK: __u = __crc_const(b"123456789", 0);
+
+    /// Create a new digest.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(__init)
+    }
+
+    /// Feed more data into the digest.
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        // the CRC function undoes init and applies xor, so we need to
+        // cancel these out to get back the internal state
+        self.0 = __crc(data, self.0 ^ __init) ^ __xor;
+    }
 
-            __u::from(crc) ^ __xor
+    /// Feed data split into multiple chunks into the digest.
+    ///
+    /// This is useful for scatter-gather buffers, where the data is not
+    /// contiguous in memory.
+    ///
+    #[inline]
+    pub fn update_vectored<'a, I>(&mut self, data: I)
+    where
+        I: IntoIterator<Item=&'a [u8]>
+    {
+        for data in data {
+            self.update(data);
         }
     }
+
+    /// Get the CRC of all data fed into the digest so far.
+    ///
+    /// This does not modify the digest, so more data can still be fed
+    /// into the digest afterwards.
+    ///
+    #[inline]
+    pub const fn finalize(&self) -> __u {
+        self.0 ^ __xor
+    }
+
+    /// Reset the digest to its initial state.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.0 = __init;
+    }
 }
 
+impl Default for __digest {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+// Digest trait implementation
+
+impl __crate::crc::Digest for __digest {
+    type U = __u;
+
+    #[inline]
+    fn update(&mut self, data: &[u8]) {
+        __digest::update(self, data)
+    }
+
+    #[inline]
+    fn finalize(&self) -> __u {
+        __digest::finalize(self)
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        __digest::reset(self)
+    }
+}
+
+
+// Check our check value at compile time, if requested
+
+#[cfg(__if(__has_check))]
+const _: () = assert!(
+    __digest::CHECK == __check,
+    "crc check value does not match, are the crc's parameters correct?"
+);
+
+
+// Checksum trait implementation
+
+impl __crate::crc::Checksum for __digest {
+    const WIDTH: usize = __width;
+    const POLYNOMIAL: u128 = __polynomial;
+    const REFLECTED: bool = __reflected;
+    const INIT: __u = __init_param;
+    const XOR: __u = __xor;
+    const CHECK: __u = __digest::CHECK;
+}
+
+
+// std::io::Write implementation, if enabled
+
+#[cfg(__if(__std))]
+extern crate std;
+
+#[cfg(__if(__std))]
+impl std::io::Write for __digest {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
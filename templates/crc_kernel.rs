@@ -0,0 +1,526 @@
+// Template for the CRC calculation itself
+//
+// This is compiled alongside templates/crc.rs, once for most CRCs, or once
+// for each implementation an adaptive CRC chooses between at runtime, so
+// each implementation only carries the CRC calculation and its tables.
+//
+// See examples/crc.rs for a more detailed explanation of
+// where these implementations come from
+
+fn __crc_kernel(data: &[u8], crc: __u) -> __u {
+    cfg_if! {
+        if #[cfg(__if(__naive))] {
+            let mut crc = __p(crc ^ __init);
+
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
+                }
+            }
+
+            crc <<= 8*size_of::<__u>()-__width;
+
+            // iterate over words
+            let mut words = data.chunks_exact(size_of::<__u>());
+            for word in &mut words {
+                let word = <[u8; size_of::<__u>()]>::try_from(word).unwrap();
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        crc += __p::from_le_bytes(word).reverse_bits();
+                    } else {
+                        crc += __p::from_be_bytes(word);
+                    }
+                }
+                crc = __p::try_from(
+                    (__p2::from(crc) << 8*size_of::<__u>()) % __p2(__polynomial << (8*size_of::<__u>()-__width))
+                ).unwrap();
+            }
+
+            // handle remainder
+            for b in words.remainder() {
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        crc += (__p::from(b.reverse_bits()) << (8*size_of::<__u>()-8));
+                    } else {
+                        crc += (__p::from(*b) << (8*size_of::<__u>()-8));
+                    }
+                }
+                crc = __p::try_from(
+                    (__p2::from(crc) << 8) % __p2(__polynomial << (8*size_of::<__u>()-__width))
+                ).unwrap();
+            }
+
+            // our division is always 8-bit aligned, so we need to do some
+            // finagling if our crc is not 8-bit aligned
+            crc >>= 8*size_of::<__u>()-__width;
+
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
+                }
+            }
+
+            __u::from(crc) ^ __xor
+        } else if #[cfg(__if(__table))] {
+            // remainder of the byte i, this is what fills our table
+            const fn crc_table_entry(i: u8) -> __u {
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        let x = (i.reverse_bits() as __u) << (8*size_of::<__u>()-8);
+                        let x = __p2((x as __u2) << 8)
+                            .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
+                        x.reverse_bits()
+                    } else {
+                        let x = (i as __u) << (8*size_of::<__u>()-8);
+                        let x = __p2((x as __u2) << 8)
+                            .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
+                        x
+                    }
+                }
+            }
+
+            cfg_if! {
+                if #[cfg(__if(__lazy))] {
+                    use core::cell::UnsafeCell;
+                    use core::sync::atomic::AtomicU8;
+                    use core::sync::atomic::Ordering;
+
+                    // build our table in RAM on first use, rather than
+                    // storing it in flash
+                    struct LazyCrcTable {
+                        state: AtomicU8,
+                        table: UnsafeCell<[__u; 256]>,
+                    }
+
+                    const UNINIT: u8 = 0;
+                    const BUSY: u8 = 1;
+                    const READY: u8 = 2;
+
+                    // the table is only written once, by whoever wins the
+                    // UNINIT -> BUSY transition, and only read after READY
+                    unsafe impl Sync for LazyCrcTable {}
+
+                    impl LazyCrcTable {
+                        #[inline]
+                        fn get(&self) -> Option<&[__u; 256]> {
+                            if self.state.load(Ordering::Acquire) == READY {
+                                return Some(unsafe { &*self.table.get() });
+                            }
+
+                            match self.state.compare_exchange(
+                                UNINIT, BUSY,
+                                Ordering::Acquire, Ordering::Acquire
+                            ) {
+                                Ok(_) => {
+                                    let table = unsafe { &mut *self.table.get() };
+                                    for i in 0..256 {
+                                        table[i] = crc_table_entry(i as u8);
+                                    }
+                                    self.state.store(READY, Ordering::Release);
+                                    Some(&*table)
+                                }
+                                // someone else is building the table, rather
+                                // than waiting (and risking deadlock in an
+                                // interrupt), fall back to computing entries
+                                // as we need them
+                                Err(_) => None,
+                            }
+                        }
+                    }
+
+                    static CRC_TABLE: LazyCrcTable = LazyCrcTable {
+                        state: AtomicU8::new(UNINIT),
+                        table: UnsafeCell::new([0; 256]),
+                    };
+
+                    let crc_table = CRC_TABLE.get();
+                } else {
+                    const CRC_TABLE: [__u; 256] = {
+                        let mut table = [0; 256];
+                        let mut i = 0;
+                        while i < table.len() {
+                            table[i] = crc_table_entry(i as u8);
+                            i += 1;
+                        }
+                        table
+                    };
+
+                    let crc_table = Some(&CRC_TABLE);
+                }
+            }
+
+            let lookup = |i: u8| -> __u {
+                match crc_table {
+                    Some(crc_table) => crc_table[usize::from(i)],
+                    None => crc_table_entry(i),
+                }
+            };
+
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    let mut crc = crc ^ __init;
+                } else {
+                    let mut crc = (crc ^ __init) << (8*size_of::<__u>()-__width);
+                }
+            }
+
+            for b in data {
+                cfg_if! {
+                    if #[cfg(__if(__width <= 8))] {
+                        crc = lookup((crc as u8) ^ b);
+                    } else if #[cfg(__if(__reflected))] {
+                        crc = (crc >> 8) ^ lookup((crc as u8) ^ b);
+                    } else {
+                        crc = (crc << 8) ^ lookup(((crc >> (8*size_of::<__u>()-8)) as u8) ^ b);
+                    }
+                }
+            }
+
+            // our division is always 8-bit aligned, so we need to do some
+            // finagling if our crc is not 8-bit aligned
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    crc &= __nonzeros;
+                } else {
+                    crc >>= (8*size_of::<__u>()-__width);
+                }
+            }
+
+            crc ^ __xor
+        } else if #[cfg(__if(__slice_by > 0))] {
+            // CRC_TABLES[k][i] contains the remainder of the byte i followed
+            // by k zero bytes, which lets us process __slice_by bytes with
+            // independent lookups
+            const CRC_TABLES: [[__u; 256]; __slice_by] = {
+                let mut tables = [[0; 256]; __slice_by];
+                let mut i = 0;
+                while i < 256 {
+                    cfg_if! {
+                        if #[cfg(__if(__reflected))] {
+                            let x = ((i as u8).reverse_bits() as __u) << (8*size_of::<__u>()-8);
+                            let x = __p2((x as __u2) << 8)
+                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
+                            tables[0][i] = x.reverse_bits();
+                        } else {
+                            let x = (i as __u) << (8*size_of::<__u>()-8);
+                            let x = __p2((x as __u2) << 8)
+                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
+                            tables[0][i] = x;
+                        }
+                    }
+                    i += 1;
+                }
+
+                let mut k = 1;
+                while k < __slice_by {
+                    let mut i = 0;
+                    while i < 256 {
+                        let x = tables[k-1][i];
+                        cfg_if! {
+                            if #[cfg(__if(__reflected))] {
+                                tables[k][i] = match x.checked_shr(8) { Some(x) => x, None => 0 }
+                                    ^ tables[0][(x as u8) as usize];
+                            } else {
+                                tables[k][i] = match x.checked_shl(8) { Some(x) => x, None => 0 }
+                                    ^ tables[0][((x >> (8*size_of::<__u>()-8)) as u8) as usize];
+                            }
+                        }
+                        i += 1;
+                    }
+                    k += 1;
+                }
+                tables
+            };
+
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    let mut crc = crc ^ __init;
+                } else {
+                    let mut crc = (crc ^ __init) << (8*size_of::<__u>()-__width);
+                }
+            }
+
+            // iterate over slices
+            let mut slices = data.chunks_exact(__slice_by);
+            for slice in &mut slices {
+                let mut x = 0;
+                for i in 0..__slice_by {
+                    let mut b = slice[i];
+                    if i < size_of::<__u>() {
+                        cfg_if! {
+                            if #[cfg(__if(__reflected))] {
+                                b ^= (crc >> (8*i)) as u8;
+                            } else {
+                                b ^= (crc >> (8*size_of::<__u>()-8-8*i)) as u8;
+                            }
+                        }
+                    }
+                    x ^= CRC_TABLES[__slice_by-1-i][usize::from(b)];
+                }
+
+                // any bytes of our crc that don't fit in the slice carry over
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        crc = x ^ crc.checked_shr(8*__slice_by).unwrap_or(0);
+                    } else {
+                        crc = x ^ crc.checked_shl(8*__slice_by).unwrap_or(0);
+                    }
+                }
+            }
+
+            // handle remainder
+            for b in slices.remainder() {
+                cfg_if! {
+                    if #[cfg(__if(__width <= 8))] {
+                        crc = CRC_TABLES[0][usize::from((crc as u8) ^ b)];
+                    } else if #[cfg(__if(__reflected))] {
+                        crc = (crc >> 8) ^ CRC_TABLES[0][usize::from((crc as u8) ^ b)];
+                    } else {
+                        crc = (crc << 8) ^ CRC_TABLES[0][usize::from(((crc >> (8*size_of::<__u>()-8)) as u8) ^ b)];
+                    }
+                }
+            }
+
+            // our division is always 8-bit aligned, so we need to do some
+            // finagling if our crc is not 8-bit aligned
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    crc &= __nonzeros;
+                } else {
+                    crc >>= (8*size_of::<__u>()-__width);
+                }
+            }
+
+            crc ^ __xor
+        } else if #[cfg(__if(__small_table))] {
+            const CRC_TABLE: [__u; 16] = {
+                let mut table = [0; 16];
+                let mut i = 0;
+                while i < table.len() {
+                    cfg_if! {
+                        if #[cfg(__if(__reflected))] {
+                            let x = ((i as u8).reverse_bits() as __u) << (8*size_of::<__u>()-8);
+                            let x = __p2((x as __u2) << 4)
+                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
+                            table[i] = x.reverse_bits();
+                            i += 1;
+                        } else {
+                            let x = (i as __u) << (8*size_of::<__u>()-4);
+                            let x = __p2((x as __u2) << 4)
+                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
+                            table[i] = x;
+                            i += 1;
+                        }
+                    }
+                }
+                table
+            };
+
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    let mut crc = crc ^ __init;
+                } else {
+                    let mut crc = (crc ^ __init) << (8*size_of::<__u>()-__width);
+                }
+            }
+
+            for b in data {
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        crc = (crc >> 4) ^ CRC_TABLE[usize::from((crc as u8) ^ (b >> 0)) & 0xf];
+                        crc = (crc >> 4) ^ CRC_TABLE[usize::from((crc as u8) ^ (b >> 4)) & 0xf];
+                    } else {
+                        crc = (crc << 4) ^ CRC_TABLE[usize::from(((crc >> (8*size_of::<__u>()-4)) as u8) ^ (b >> 4)) & 0xf];
+                        crc = (crc << 4) ^ CRC_TABLE[usize::from(((crc >> (8*size_of::<__u>()-4)) as u8) ^ (b >> 0)) & 0xf];
+                    }
+                }
+            }
+
+            // our division is always 8-bit aligned, so we need to do some
+            // finagling if our crc is not 8-bit aligned
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    crc &= __nonzeros;
+                } else {
+                    crc >>= (8*size_of::<__u>()-__width);
+                }
+            }
+
+            crc ^ __xor
+        } else if #[cfg(__if(__barret || __folding))] {
+            const BARRET_CONSTANT: __p = {
+                __p(
+                    __p2((__polynomial & __nonzeros) << ((8*size_of::<__u>()-__width) + 8*size_of::<__u>()))
+                        .naive_div(__p2(__polynomial << (8*size_of::<__u>()-__width)))
+                        .0 as __u
+                )
+            };
+
+            let mut crc = __p(crc ^ __init);
+
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
+                }
+            }
+
+            crc <<= 8*size_of::<__u>()-__width;
+
+            cfg_if! {
+                if #[cfg(__if(__folding))] {
+                    // x^n mod our polynomial, shifted to be word-aligned
+                    const fn xpow(n: usize) -> __p {
+                        let mut x: __u = 1;
+                        let mut i = 0;
+                        while i < n {
+                            let carry = x >> (8*size_of::<__u>()-1);
+                            x = (x << 1) ^ (carry * ((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
+                            i += 1;
+                        }
+                        __p(x)
+                    }
+
+                    // folding constants for moving a lane of two words forward
+                    // by n words, (x^(n+1 words) mod p, x^(n words) mod p)
+                    const fn fold_constants(n: usize) -> (__p, __p) {
+                        (xpow((n+1)*8*size_of::<__u>()), xpow(n*8*size_of::<__u>()))
+                    }
+                    const FOLD_8: (__p, __p) = fold_constants(8);
+                    const FOLD_6: (__p, __p) = fold_constants(6);
+                    const FOLD_4: (__p, __p) = fold_constants(4);
+                    const FOLD_2: (__p, __p) = fold_constants(2);
+
+                    // lanes are stored as (lo, hi) pairs of words, folding a
+                    // lane multiplies each word by its folding constant, the
+                    // result is congruent to the original lane, shifted forward,
+                    // but still fits in two words
+                    //
+                    // we always fold 4 lanes at once, which maps onto batched
+                    // carry-less multiplication if it is a single instruction,
+                    // note narrower words are zero-extended to 64-bits, so this
+                    // only pays off for 64-bit words
+                    //
+                    #[inline]
+                    fn fold_x4(
+                        lanes: [(__p, __p); 4],
+                        k: [(__p, __p); 4]
+                    ) -> ([__p; 4], [__p; 4]) {
+                        if __crate::internal::xmul::HAS_XMUL4 && size_of::<__u>() == 8 {
+                            let (lo0, hi0) = __p::widening_mul_x4(
+                                [lanes[0].1, lanes[1].1, lanes[2].1, lanes[3].1],
+                                [k[0].0, k[1].0, k[2].0, k[3].0],
+                            );
+                            let (lo1, hi1) = __p::widening_mul_x4(
+                                [lanes[0].0, lanes[1].0, lanes[2].0, lanes[3].0],
+                                [k[0].1, k[1].1, k[2].1, k[3].1],
+                            );
+                            (
+                                [lo0[0]+lo1[0], lo0[1]+lo1[1], lo0[2]+lo1[2], lo0[3]+lo1[3]],
+                                [hi0[0]+hi1[0], hi0[1]+hi1[1], hi0[2]+hi1[2], hi0[3]+hi1[3]],
+                            )
+                        } else {
+                            let mut lo = [__p(0); 4];
+                            let mut hi = [__p(0); 4];
+                            for i in 0..4 {
+                                let (lo0, hi0) = lanes[i].1.widening_mul(k[i].0);
+                                let (lo1, hi1) = lanes[i].0.widening_mul(k[i].1);
+                                lo[i] = lo0 + lo1;
+                                hi[i] = hi0 + hi1;
+                            }
+                            (lo, hi)
+                        }
+                    }
+
+                    #[inline]
+                    fn load(block: &[u8], i: usize) -> __p {
+                        let word = <[u8; size_of::<__u>()]>::try_from(
+                            &block[i*size_of::<__u>() .. (i+1)*size_of::<__u>()]
+                        ).unwrap();
+                        cfg_if! {
+                            if #[cfg(__if(__reflected))] {
+                                __p::from_le_bytes(word).reverse_bits()
+                            } else {
+                                __p::from_be_bytes(word)
+                            }
+                        }
+                    }
+
+                    // fold 4 independent lanes of 2 words at a time, this keeps
+                    // multiple carry-less multiplications in flight
+                    let mut blocks = data.chunks_exact(8*size_of::<__u>());
+                    if let Some(block) = blocks.next() {
+                        let mut lanes = [(__p(0), __p(0)); 4];
+                        for i in 0..4 {
+                            lanes[i] = (load(block, 2*i+1), load(block, 2*i));
+                        }
+                        lanes[0].1 += crc;
+
+                        for block in &mut blocks {
+                            let (lo, hi) = fold_x4(lanes, [FOLD_8; 4]);
+                            for i in 0..4 {
+                                lanes[i] = (lo[i] + load(block, 2*i+1), hi[i] + load(block, 2*i));
+                            }
+                        }
+
+                        // fold our lanes into a single lane
+                        let (lo, hi) = fold_x4(lanes, [FOLD_6, FOLD_4, FOLD_2, (__p(0), __p(0))]);
+                        let lane = (
+                            lanes[3].0 + lo[0] + lo[1] + lo[2],
+                            lanes[3].1 + hi[0] + hi[1] + hi[2],
+                        );
+
+                        // and reduce with Barret reduction
+                        crc = lane.1;
+                        crc = crc.widening_mul_acc(BARRET_CONSTANT, (__p(0), crc)).1
+                                .wrapping_mul(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
+                        crc += lane.0;
+                        crc = crc.widening_mul_acc(BARRET_CONSTANT, (__p(0), crc)).1
+                                .wrapping_mul(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
+                    }
+                    let data = blocks.remainder();
+                }
+            }
+
+            // iterate over words
+            let mut words = data.chunks_exact(size_of::<__u>());
+            for word in &mut words {
+                let word = <[u8; size_of::<__u>()]>::try_from(word).unwrap();
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        crc += __p::from_le_bytes(word).reverse_bits();
+                    } else {
+                        crc += __p::from_be_bytes(word);
+                    }
+                }
+                crc = crc.widening_mul_acc(BARRET_CONSTANT, (__p(0), crc)).1
+                        .wrapping_mul(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
+            }
+
+            // handle remainder
+            for b in words.remainder() {
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        crc += (__p::from(b.reverse_bits()) << (8*size_of::<__u>()-8));
+                    } else {
+                        crc += (__p::from(*b) << (8*size_of::<__u>()-8));
+                    }
+                }
+                crc = (crc << 8)
+                    + ((crc >> (8*size_of::<__u>()-8)).widening_mul(BARRET_CONSTANT).1 + (crc >> (8*size_of::<__u>()-8)))
+                        .wrapping_mul(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
+            }
+
+            // our division is always 8-bit aligned, so we need to do some
+            // finagling if our crc is not 8-bit aligned
+            crc >>= (8*size_of::<__u>()-__width);
+
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
+                }
+            }
+
+            __u::from(crc) ^ __xor
+        }
+    }
+}
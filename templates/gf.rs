@@ -499,8 +499,8 @@ impl __gf {
                 //
                 let (lo, hi) = __p(self.0 << (8*size_of::<__u>()-__width))
                     .widening_mul(__p(other.0));
-                let x = lo + (hi.widening_mul(Self::BARRET_CONSTANT).1 + hi)
-                    .wrapping_mul(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
+                let x = hi.widening_mul_acc(Self::BARRET_CONSTANT, (__p(0), hi)).1
+                    .wrapping_mul_acc(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)), lo);
                 __gf(x.0 >> (8*size_of::<__u>()-__width))
             } else {
                 // fallback to naive multiplication
@@ -789,6 +789,122 @@ impl __gf {
             )
         }
     }
+
+    /// Xor a slice of finite-field elements into another slice of
+    /// finite-field elements.
+    ///
+    /// This is equivalent to finite-field addition over each element, but
+    /// uses explicit wide registers when available. See
+    /// [`slice_xor`](../p/fn.slice_xor.html) for more info.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let mut x = [gf256(0x12), gf256(0x34), gf256(0x56)];
+    /// gf256::slice_xor(&mut x, &[gf256(0x34), gf256(0x34), gf256(0x34)]);
+    /// assert_eq!(x, [gf256(0x26), gf256(0x00), gf256(0x62)]);
+    /// ```
+    ///
+    #[inline]
+    pub fn slice_xor(dst: &mut [__gf], src: &[__gf]) {
+        assert_eq!(dst.len(), src.len());
+        let dst = unsafe {
+            slice::from_raw_parts_mut(
+                dst.as_mut_ptr() as *mut u8,
+                dst.len()*size_of::<__gf>()
+            )
+        };
+        let src = unsafe {
+            slice::from_raw_parts(
+                src.as_ptr() as *const u8,
+                src.len()*size_of::<__gf>()
+            )
+        };
+        __crate::p::slice_xor(dst, src);
+    }
+
+    /// Multiply a slice of finite-field elements by a constant and add it
+    /// to another slice of finite-field elements, `dst[i] += src[i]*c`.
+    ///
+    /// This is the core operation of RAID-parity and other erasure codes.
+    /// For fields with <=8 bits, this uses explicit SIMD instructions when
+    /// available (`gfni`/`avx2`/`ssse3` on x86_64, `neon` on aarch64),
+    /// otherwise falling back to 4-bit lookup tables. For 64-bit fields in
+    /// Barret mode, this uses batched carry-less multiplication when it is
+    /// a single instruction (`vpclmulqdq` with `avx512f` on x86_64):
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let mut x = [gf256(0x12), gf256(0x34), gf256(0x56)];
+    /// gf256::slice_mul_acc(&mut x, &[gf256(0x01), gf256(0x02), gf256(0x03)], gf256(0x34));
+    /// assert_eq!(x, [
+    ///     gf256(0x12) + gf256(0x01)*gf256(0x34),
+    ///     gf256(0x34) + gf256(0x02)*gf256(0x34),
+    ///     gf256(0x56) + gf256(0x03)*gf256(0x34),
+    /// ]);
+    /// ```
+    ///
+    #[inline]
+    pub fn slice_mul_acc(dst: &mut [__gf], src: &[__gf], c: __gf) {
+        assert_eq!(dst.len(), src.len());
+        cfg_if! {
+            if #[cfg(__if(__width <= 8))] {
+                if size_of::<__gf>() == 1 {
+                    // multiplication by a constant is linear, so all we need
+                    // is the constant multiplied by each bit
+                    let mut basis = [0u8; 8];
+                    for (j, b) in basis.iter_mut().enumerate().take(__width) {
+                        *b = (c * __gf::from_lossy(1u8 << j)).get() as u8;
+                    }
+
+                    let dst = unsafe {
+                        slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u8, dst.len())
+                    };
+                    let src = unsafe {
+                        slice::from_raw_parts(src.as_ptr() as *const u8, src.len())
+                    };
+                    __crate::internal::simd::mul_acc8(dst, src, basis);
+                    return;
+                }
+            } else if #[cfg(__if(__barret))] {
+                // in Barret mode, multiply 4 elements at a time if batched
+                // carry-less multiplication is a single instruction, note
+                // narrower words are zero-extended to 64-bits, so this only
+                // pays off for 64-bit words
+                if __crate::internal::xmul::HAS_XMUL4 && size_of::<__u>() == 8 {
+                    let mut dst_chunks = dst.chunks_exact_mut(4);
+                    let mut src_chunks = src.chunks_exact(4);
+                    for (d, s) in (&mut dst_chunks).zip(&mut src_chunks) {
+                        let (lo, hi) = __p::widening_mul_x4(
+                            [
+                                __p(s[0].0 << (8*size_of::<__u>()-__width)),
+                                __p(s[1].0 << (8*size_of::<__u>()-__width)),
+                                __p(s[2].0 << (8*size_of::<__u>()-__width)),
+                                __p(s[3].0 << (8*size_of::<__u>()-__width)),
+                            ],
+                            [__p(c.0); 4],
+                        );
+                        let (_, q) = __p::widening_mul_x4(hi, [Self::BARRET_CONSTANT; 4]);
+                        let (x, _) = __p::widening_mul_x4(
+                            [q[0]+hi[0], q[1]+hi[1], q[2]+hi[2], q[3]+hi[3]],
+                            [__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)); 4],
+                        );
+                        for i in 0..4 {
+                            d[i] += __gf((x[i]+lo[i]).0 >> (8*size_of::<__u>()-__width));
+                        }
+                    }
+
+                    for (d, s) in dst_chunks.into_remainder().iter_mut().zip(src_chunks.remainder()) {
+                        *d += *s*c;
+                    }
+                    return;
+                }
+            }
+        }
+
+        for i in 0..dst.len() {
+            dst[i] += src[i]*c;
+        }
+    }
 }
 
 
@@ -3872,6 +3988,55 @@ impl fmt::UpperHex for __gf {
     }
 }
 
+impl __crate::traits::Field for __gf {
+    type U = __u;
+
+    const WIDTH: usize = __width;
+    const NONZEROS: __u = __nonzeros;
+    const GENERATOR: __gf = __gf(__generator);
+    const ZERO: __gf = __gf(0);
+    const ONE: __gf = __gf(1);
+
+    #[inline]
+    fn try_from_u(x: __u) -> Option<__gf> {
+        cfg_if! {
+            if #[cfg(__if(__is_pw2ge8))] {
+                Some(__gf(x))
+            } else {
+                if x < __nonzeros+1 {
+                    Some(__gf(x))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn to_u(self) -> __u {
+        self.0
+    }
+
+    #[inline]
+    fn pow(self, exp: __u) -> __gf {
+        __gf::pow(self, exp)
+    }
+
+    #[inline]
+    fn checked_recip(self) -> Option<__gf> {
+        __gf::checked_recip(self)
+    }
+
+    #[inline]
+    fn checked_div(self, other: __gf) -> Option<__gf> {
+        __gf::checked_div(self, other)
+    }
+}
+
+// zeroize::Zeroize implementation, if enabled
+#[cfg(__if(__zeroize))]
+impl __crate::internal::zeroize::DefaultIsZeroes for __gf {}
+
 impl FromStr for __gf {
     type Err = ParseIntError;
 
@@ -3879,8 +4044,8 @@ impl FromStr for __gf {
     /// hexadecimal strings starting with `0x`. If you need a different radix
     /// there is [`from_str_radix`](#method.from_str_radix).
     fn from_str(s: &str) -> Result<__gf, ParseIntError> {
-        if s.starts_with("0x") {
-            Ok(__gf(__u::from_str_radix(&s[2..], 16)?))
+        if let Some(s) = s.strip_prefix("0x") {
+            Ok(__gf(__u::from_str_radix(s, 16)?))
         } else {
             "".parse::<__u>()?;
             unreachable!()
@@ -3,8 +3,9 @@
 //! See examples/lfsr.rs for a more detailed explanation of
 //! where these implementations come from
 
-use __crate::internal::rand::RngCore;
-use __crate::internal::rand::SeedableRng;
+use __crate::internal::rand_core::RngCore;
+use __crate::internal::rand_core::SeedableRng;
+use __crate::internal::rand_core::block::BlockRngCore;
 use __crate::internal::cfg_if::cfg_if;
 use __crate::traits::FromLossy;
 use __crate::traits::TryFrom;
@@ -33,7 +34,11 @@ use core::cmp::min;
 /// See the [module-level documentation](../lfsr) for more info.
 ///
 #[derive(Debug, Clone)]
-pub struct __lfsr(__nzu);
+pub struct __lfsr {
+    state: __nzu,
+    seed: __nzu,
+    position: __u,
+}
 
 impl __lfsr {
     /// The irreducible polynomial that defines the LFSR.
@@ -43,6 +48,21 @@ impl __lfsr {
     /// the maximum cycle-length of the LFSR.
     pub const NONZEROS: __u = __nonzeros;
 
+    /// The period of the LFSR, `2^width-1`.
+    ///
+    /// Note this assumes the polynomial is primitive, otherwise the LFSR
+    /// will cycle early, see `verify_maximal` and the `maximal` option.
+    ///
+    pub const PERIOD: __u = __nonzeros;
+
+    // prime factors of the period, found by the lfsr macro
+    #[cfg(__if(__maximal))]
+    const PERIOD_FACTORS: &'static [__u] = &__period_factors;
+
+    // terms of the polynomial, excluding x^width, if sparse
+    #[cfg(__if(__sparse))]
+    const SPARSE_TAPS: [usize; __sparse_taps.len()] = __sparse_taps;
+
     // div/rem tables, if required
     #[cfg(__if(__table || __table_barret))]
     const DIV_TABLE: [u8; 256] = {
@@ -188,10 +208,216 @@ impl __lfsr {
         )
     };
 
+    // find the Galois state whose next width-bits of output are q,
+    // in the non-reflected orientation, see from_output for more info
+    #[inline]
+    const fn state_from_output(q: __u) -> __u {
+        // s = (q*p) / x^width, note p's leading term contributes q itself
+        q ^ (
+            __p2(q as __u2).naive_wrapping_mul(__p2(__polynomial & __nonzeros)).0
+                >> __width
+        ) as __u
+    }
+
+    // find the next width-bits of output of a Galois state, in the
+    // non-reflected orientation
+    #[cfg(__if(__fibonacci))]
+    #[inline]
+    const fn output_from_state(s: __u) -> __u {
+        // q = (s*x^width) / p
+        __p2((s as __u2) << __width)
+            .naive_div(__p2(__polynomial))
+            .0 as __u
+    }
+
+    // reduce n-bits modulo the period, note the period is 1 for
+    // 1-bit LFSRs, which clippy doesn't like
+    #[inline]
+    #[allow(clippy::modulo_one)]
+    const fn position_rem(bits: __u) -> __u {
+        bits % __nonzeros
+    }
+
+    // add/subtract n-bits from a position modulo the period, note
+    // n must be <= the period
+    #[inline]
+    const fn position_add(position: __u, bits: __u) -> __u {
+        if bits >= __nonzeros - position {
+            bits - (__nonzeros - position)
+        } else {
+            position + bits
+        }
+    }
+
+    #[inline]
+    const fn position_sub(position: __u, bits: __u) -> __u {
+        if bits > position {
+            position + (__nonzeros - bits)
+        } else {
+            position - bits
+        }
+    }
+
+    // compute x^e mod the polynomial
+    const fn naive_pow_x(mut e: __u) -> __u {
+        let p = __p2(__polynomial);
+        let mut a = __p2(2).naive_rem(p);
+        let mut x = __p2(1);
+        while e != 0 {
+            if e & 1 != 0 {
+                x = x.naive_wrapping_mul(a).naive_rem(p);
+            }
+            a = a.naive_wrapping_mul(a).naive_rem(p);
+            e >>= 1;
+        }
+        x.0 as __u
+    }
+
+    /// Check that the LFSR is maximal-length, that is, it cycles through
+    /// all `2^width-1` non-zero states before repeating.
+    ///
+    /// This is true if the polynomial is primitive, which we can check by
+    /// testing that the order of `x` is exactly `2^width-1`, using the
+    /// prime factors of `2^width-1` found by the `lfsr` macro.
+    ///
+    /// Factoring `2^width-1` can be expensive, so this is only generated if
+    /// the `maximal` option is provided.
+    ///
+    /// Since this is a const fn, this can be checked at compile time:
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// # use ::gf256::lfsr::*;
+    /// #[lfsr(polynomial=0x11d, maximal)]
+    /// pub struct MyLfsr8 {}
+    ///
+    /// #[lfsr(polynomial=0x11b, maximal)]
+    /// pub struct NotMaximal {}
+    ///
+    /// # fn main() {
+    /// const _: () = assert!(MyLfsr8::verify_maximal());
+    /// assert!(!NotMaximal::verify_maximal());
+    /// # }
+    /// ```
+    ///
+    #[cfg(__if(__maximal))]
+    pub const fn verify_maximal() -> bool {
+        if Self::naive_pow_x(__nonzeros) != 1 {
+            return false;
+        }
+
+        let mut i = 0;
+        while i < Self::PERIOD_FACTORS.len() {
+            if Self::naive_pow_x(__nonzeros / Self::PERIOD_FACTORS[i]) == 1 {
+                return false;
+            }
+            i += 1;
+        }
+
+        true
+    }
+
+    /// Generate the next n-bits of pseudo-random data, in a const fn.
+    ///
+    /// This is a naive, bitwise implementation of [`next`](Self::next),
+    /// which takes and returns the LFSR by value so it can be used to
+    /// compute tables at compile time:
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// const WHITENING: [u16; 4] = {
+    ///     let mut table = [0; 4];
+    ///     let mut lfsr = Lfsr16::new(1);
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (lfsr_, x) = lfsr.naive_next(16);
+    ///         lfsr = lfsr_;
+    ///         table[i] = x;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    ///
+    /// assert_eq!(WHITENING, [0x0001, 0x002d, 0x0451, 0xbdad]);
+    /// ```
+    ///
+    #[inline]
+    pub const fn naive_next(self, bits: __u) -> (Self, __u) {
+        debug_assert!(bits <= __width);
+        let bits = bits as usize;
+        let mut x = self.state.get();
+        let mut q = 0;
+        let mut i = 0;
+        while i < bits {
+            let msb = x >> (__width-1);
+            q = (q << 1) | msb;
+            x = (x << 1) ^ if msb != 0 {
+                __polynomial as __u
+            } else {
+                0
+            };
+            i += 1;
+        }
+
+        cfg_if! {
+            if #[cfg(__if(__lsb_first))] {
+                if bits > 0 {
+                    q = q.reverse_bits() >> (8*size_of::<__u>() - bits);
+                }
+            }
+        }
+
+        (
+            Self {
+                state: unsafe { __nzu::new_unchecked(x) },
+                seed: self.seed,
+                position: Self::position_add(self.position, bits as __u),
+            },
+            q
+        )
+    }
+
+    /// Skip n-bits of pseudo-random data, in a const fn.
+    ///
+    /// This is a naive implementation of [`skip`](Self::skip), which takes
+    /// and returns the LFSR by value so it can be used at compile time:
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// const LFSR: Lfsr16 = Lfsr16::new(1).naive_skip(48);
+    ///
+    /// let mut lfsr = LFSR;
+    /// assert_eq!(lfsr.next(16), 0xbdad);
+    /// ```
+    ///
+    #[inline]
+    pub const fn naive_skip(self, bits: __u) -> Self {
+        // lfsr' = lfsr * 2^skip
+        let x = __p2(self.state.get() as __u2)
+            .naive_wrapping_mul(__p2(Self::naive_pow_x(bits) as __u2))
+            .naive_rem(__p2(__polynomial));
+        Self {
+            state: unsafe { __nzu::new_unchecked(x.0 as __u) },
+            seed: self.seed,
+            position: Self::position_add(self.position, Self::position_rem(bits)),
+        }
+    }
+
     /// Create an LFSR with the given seed.
     ///
-    /// The seed can't be `0`, so if `0` is provided, the seed `1` is used
-    /// instead.
+    /// Any bits outside of the LFSR's width are ignored. The seed also can't
+    /// be `0`, since zero is a fixed point of the LFSR, so if the seed is `0`
+    /// after ignoring these bits, the seed `1` is used instead.
+    ///
+    /// This never panics, so untrusted seeds are always accepted. See
+    /// [`try_new`](Self::try_new) for a version that reports a zero seed as
+    /// an error:
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// assert_eq!(Lfsr16::new(0).state(), 1);
+    /// assert_eq!(Lfsr16::try_new(0).unwrap_err(), Error::ZeroSeed);
+    /// ```
     ///
     #[inline]
     pub const fn new(mut seed: __u) -> Self {
@@ -209,7 +435,257 @@ impl __lfsr {
             }
         }
 
-        Self(unsafe { __nzu::new_unchecked(seed) })
+        // a Fibonacci LFSR's state is its next width-bits of output
+        cfg_if! {
+            if #[cfg(__if(__fibonacci))] {
+                seed = Self::state_from_output(seed);
+            }
+        }
+
+        let seed = unsafe { __nzu::new_unchecked(seed) };
+        Self { state: seed, seed: seed, position: 0 }
+    }
+
+    /// Create an LFSR with the given seed, returning an error if the seed
+    /// is zero.
+    ///
+    /// Like [`new`](Self::new), any bits outside of the LFSR's width are
+    /// ignored, but a seed that is `0` after ignoring these bits returns
+    /// `Error::ZeroSeed` instead of
+    /// silently being replaced:
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// assert!(Lfsr16::try_new(0x1234).is_ok());
+    /// assert_eq!(Lfsr16::try_new(0).unwrap_err(), Error::ZeroSeed);
+    /// ```
+    ///
+    #[inline]
+    pub const fn try_new(seed: __u) -> Result<Self, __crate::lfsr::Error> {
+        if seed & __nonzeros == 0 {
+            return Err(__crate::lfsr::Error::ZeroSeed);
+        }
+
+        Ok(Self::new(seed))
+    }
+
+    /// Get the current state of the LFSR.
+    ///
+    /// This is the seed that recreates the LFSR at its current position,
+    /// so a stream can be checkpointed and resumed exactly:
+    ///
+    /// ``` rust
+    /// use gf256::lfsr::Lfsr16;
+    ///
+    /// let mut lfsr = Lfsr16::new(1);
+    /// assert_eq!(lfsr.next(16), 0x0001);
+    /// let state = lfsr.state();
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    ///
+    /// let mut lfsr = Lfsr16::new(state);
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    /// ```
+    ///
+    #[inline]
+    pub const fn state(&self) -> __u {
+        Self::state_to_external(self.state.get())
+    }
+
+    // convert an internal state into the representation returned by state,
+    // this is the inverse of the conversions in new
+    #[inline]
+    const fn state_to_external(mut state: __u) -> __u {
+        // a Fibonacci LFSR's state is its next width-bits of output
+        cfg_if! {
+            if #[cfg(__if(__fibonacci))] {
+                state = Self::output_from_state(state);
+            }
+        }
+
+        cfg_if! {
+            if #[cfg(__if(__reflected))] {
+                state = state.reverse_bits() >> (8*size_of::<__u>()-__width);
+            }
+        }
+
+        state
+    }
+
+    /// Set the current state of the LFSR, keeping its seed and position.
+    ///
+    /// Since the state alone doesn't say where it is in the stream, the
+    /// caller provides the seed and position the state corresponds to. This
+    /// follows the same rules as [`new`](Self::new), so a state or seed of
+    /// `0` is replaced with `1`, and the position is reduced modulo
+    /// [`PERIOD`](Self::PERIOD):
+    ///
+    /// ``` rust
+    /// use gf256::lfsr::Lfsr16;
+    ///
+    /// let mut lfsr = Lfsr16::new(1);
+    /// assert_eq!(lfsr.next(16), 0x0001);
+    /// let (state, seed, position) = (lfsr.state(), lfsr.seed(), lfsr.position());
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    ///
+    /// let mut lfsr = Lfsr16::new(0x1234);
+    /// lfsr.set_state(state, seed, position);
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    /// lfsr.seek(0);
+    /// assert_eq!(lfsr.next(16), 0x0001);
+    /// ```
+    ///
+    /// Note this trusts that `seed` at `position` actually leads to `state`.
+    /// To start a new stream at `state`, use [`new`](Self::new).
+    ///
+    #[inline]
+    pub fn set_state(&mut self, state: __u, seed: __u, position: __u) {
+        *self = Self::new(state);
+        self.seed = Self::new(seed).state;
+        self.position = Self::position_rem(position);
+    }
+
+    /// Get the seed of the LFSR, the state it was created with.
+    ///
+    /// This is the state at position 0, see [`seek`](Self::seek).
+    ///
+    #[inline]
+    pub const fn seed(&self) -> __u {
+        Self::state_to_external(self.seed.get())
+    }
+
+    /// Get the current state, seed, and position of the LFSR as little-endian
+    /// bytes, in that order.
+    ///
+    /// This includes everything needed to resume the LFSR, including
+    /// [`seek`](Self::seek) and [`position`](Self::position):
+    ///
+    /// ``` rust
+    /// use gf256::lfsr::Lfsr16;
+    ///
+    /// let mut lfsr = Lfsr16::new(1);
+    /// assert_eq!(lfsr.next(16), 0x0001);
+    /// let bytes = lfsr.to_bytes();
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    ///
+    /// let mut lfsr = Lfsr16::from_bytes(bytes);
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    /// lfsr.seek(0);
+    /// assert_eq!(lfsr.next(16), 0x0001);
+    /// ```
+    ///
+    #[inline]
+    pub const fn to_bytes(&self) -> [u8; 3*size_of::<__u>()] {
+        let state = self.state().to_le_bytes();
+        let seed = self.seed().to_le_bytes();
+        let position = self.position.to_le_bytes();
+        let mut bytes = [0; 3*size_of::<__u>()];
+        let mut i = 0;
+        while i < size_of::<__u>() {
+            bytes[i] = state[i];
+            bytes[size_of::<__u>()+i] = seed[i];
+            bytes[2*size_of::<__u>()+i] = position[i];
+            i += 1;
+        }
+        bytes
+    }
+
+    /// Create an LFSR from its state, seed, and position in little-endian
+    /// bytes, see [`to_bytes`](Self::to_bytes).
+    ///
+    /// This follows the same rules as [`set_state`](Self::set_state).
+    ///
+    #[inline]
+    pub const fn from_bytes(bytes: [u8; 3*size_of::<__u>()]) -> Self {
+        let mut state = [0; size_of::<__u>()];
+        let mut seed = [0; size_of::<__u>()];
+        let mut position = [0; size_of::<__u>()];
+        let mut i = 0;
+        while i < size_of::<__u>() {
+            state[i] = bytes[i];
+            seed[i] = bytes[size_of::<__u>()+i];
+            position[i] = bytes[2*size_of::<__u>()+i];
+            i += 1;
+        }
+
+        let mut lfsr = Self::new(__u::from_le_bytes(state));
+        lfsr.seed = Self::new(__u::from_le_bytes(seed)).state;
+        lfsr.position = Self::position_rem(__u::from_le_bytes(position));
+        lfsr
+    }
+
+    /// Recover an LFSR from its output.
+    ///
+    /// The output of an LFSR is a linear function of its state, so the
+    /// state can be recovered from any width-bits of consecutive output.
+    ///
+    /// With this LFSR's representation, the next width-bits of output are
+    /// the quotient `q = (s*x^width) / p`, for state `s` and polynomial `p`.
+    /// Since `s*x^width` has no low bits, we can solve for `s` by dropping
+    /// the remainder: `s = (q*p) / x^width`.
+    ///
+    /// The returned LFSR is positioned right after the given output, so
+    /// it continues the same stream:
+    ///
+    /// ``` rust
+    /// use gf256::lfsr::Lfsr16;
+    ///
+    /// let mut lfsr = Lfsr16::new(0x1234);
+    /// lfsr.next(5);
+    /// let output = lfsr.next(16);
+    ///
+    /// let mut recovered = Lfsr16::from_output(output).unwrap();
+    /// assert_eq!(recovered.next(16), lfsr.next(16));
+    /// ```
+    ///
+    /// Returns [`None`] if the output could not have come from this LFSR,
+    /// that is, if it is zero or contains bits outside the LFSR's width.
+    ///
+    #[inline]
+    pub fn from_output(output: __u) -> Option<Self> {
+        if output == 0 || output & !__nonzeros != 0 {
+            return None;
+        }
+
+        let mut q = output;
+
+        cfg_if! {
+            if #[cfg(__if(__lsb_first))] {
+                q = q.reverse_bits() >> (8*size_of::<__u>()-__width);
+            }
+        }
+
+        let s = Self::state_from_output(q);
+        let s = unsafe { __nzu::new_unchecked(s) };
+        let mut lfsr = Self { state: s, seed: s, position: 0 };
+        lfsr.next(__width);
+        Some(lfsr)
+    }
+
+    /// Synchronize an LFSR with observed output.
+    ///
+    /// This is the same as [`from_output`](Self::from_output), but updates
+    /// an existing LFSR in-place, which is useful for synchronizing
+    /// descramblers. Returns `false`, leaving the LFSR unchanged, if the
+    /// output could not have come from this LFSR.
+    ///
+    /// ``` rust
+    /// use gf256::lfsr::Lfsr16;
+    ///
+    /// let mut scrambler = Lfsr16::new(0x1234);
+    /// let mut descrambler = Lfsr16::new(1);
+    /// assert!(descrambler.synchronize(scrambler.next(16)));
+    /// assert_eq!(descrambler.next(16), scrambler.next(16));
+    /// ```
+    ///
+    #[inline]
+    pub fn synchronize(&mut self, output: __u) -> bool {
+        match Self::from_output(output) {
+            Some(lfsr) => {
+                *self = lfsr;
+                true
+            }
+            None => false,
+        }
     }
 
     /// Generate the next n-bits of pseudo-random data.
@@ -230,7 +706,7 @@ impl __lfsr {
         cfg_if! {
             if #[cfg(__if(__naive))] {
                 // naive lfsr using bitshifts and xors
-                let mut x = __u::from(self.0);
+                let mut x = __u::from(self.state);
                 let mut q = 0;
                 for _ in 0..bits {
                     let msb = x >> (__width-1);
@@ -242,10 +718,29 @@ impl __lfsr {
                     };
                 }
                 // update state
-                self.0 = __nzu::try_from(x).unwrap();
+                self.state = __nzu::try_from(x).unwrap();
+            } else if #[cfg(__if(__sparse))] {
+                // lfsr using a shift and xor per term of a sparse polynomial,
+                // as long as n <= width-deg(p-x^width), the top n bits are the
+                // quotient, and we can apply the remainder all at once
+                let mut x = __u::from(self.state);
+                let mut q = 0;
+                let mut i = 0;
+                while i < bits {
+                    let n = min(min(__sparse_chunk, 8*size_of::<__u>()-1), bits-i);
+                    let hi = x >> (__width-n);
+                    q = (q << n) | hi;
+                    x = (x << n) & (__nonzeros as __u);
+                    for t in Self::SPARSE_TAPS {
+                        x ^= hi << t;
+                    }
+                    i += n;
+                }
+                // update state
+                self.state = __nzu::try_from(x).unwrap();
             } else if #[cfg(__if(__table))] {
                 // lfsr with a per-byte division and remainder table
-                let mut x = __u::from(self.0) << (8*size_of::<__u>()-__width);
+                let mut x = __u::from(self.state) << (8*size_of::<__u>()-__width);
                 let mut q = 0;
                 for i in (0..(bits+7)/8).rev() {
                     let n = min(8, bits-8*i);
@@ -264,10 +759,10 @@ impl __lfsr {
                 // adjust for alignment
                 x = x >> (8*size_of::<__u>()-__width);
                 // update state
-                self.0 = __nzu::try_from(x).unwrap();
+                self.state = __nzu::try_from(x).unwrap();
             } else if #[cfg(__if(__small_table))] {
                 // lfsr with a per-nibble division and remainder table
-                let mut x = __u::from(self.0) << (8*size_of::<__u>()-__width);
+                let mut x = __u::from(self.state) << (8*size_of::<__u>()-__width);
                 let mut q = 0;
                 for i in (0..(bits+3)/4).rev() {
                     let n = min(4, bits-4*i);
@@ -279,10 +774,10 @@ impl __lfsr {
                 // adjust for alignment
                 x = x >> (8*size_of::<__u>()-__width);
                 // update state
-                self.0 = __nzu::try_from(x).unwrap();
+                self.state = __nzu::try_from(x).unwrap();
             } else if #[cfg(__if(__barret))] {
                 // lfsr using naive division with Barret-reduction
-                let x = __p2::from(__u::from(self.0)) << (bits + (8*size_of::<__u>()-__width));
+                let x = __p2::from(__u::from(self.state)) << (bits + (8*size_of::<__u>()-__width));
                 let q = x / __p2(__polynomial << (8*size_of::<__u>()-__width));
                 let lo = __p::from_lossy(x);
                 let hi = __p::try_from(x >> (8*size_of::<__u>())).unwrap();
@@ -291,11 +786,11 @@ impl __lfsr {
                 // adjust for alignment
                 x = x >> (8*size_of::<__u>()-__width);
                 // update state
-                self.0 = __nzu::try_from(__u::from(x)).unwrap();
+                self.state = __nzu::try_from(__u::from(x)).unwrap();
                 let q = __u::try_from(q.0).unwrap();
             } else if #[cfg(__if(__table_barret))] {
                 // lfsr using a per-byte division table with Barret-reduction
-                let mut x = __p::from(__u::from(self.0)) << (8*size_of::<__u>()-__width);
+                let mut x = __p::from(__u::from(self.state)) << (8*size_of::<__u>()-__width);
                 let mut q = 0;
                 for i in (0..(bits+7)/8).rev() {
                     let n = min(8, bits-8*i);
@@ -317,10 +812,10 @@ impl __lfsr {
                 // adjust for alignment
                 x = x >> (8*size_of::<__u>()-__width);
                 // update state
-                self.0 = __nzu::try_from(__u::from(x)).unwrap();
+                self.state = __nzu::try_from(__u::from(x)).unwrap();
             } else if #[cfg(__if(__small_table_barret))] {
                 // lfsr using a per-nibble division table with Barret-reduction
-                let mut x = __p::from(__u::from(self.0)) << (8*size_of::<__u>()-__width);
+                let mut x = __p::from(__u::from(self.state)) << (8*size_of::<__u>()-__width);
                 let mut q = 0;
                 for i in (0..(bits+3)/4).rev() {
                     let n = min(4, bits-4*i);
@@ -334,12 +829,15 @@ impl __lfsr {
                 // adjust for alignment
                 x = x >> (8*size_of::<__u>()-__width);
                 // update state
-                self.0 = __nzu::try_from(__u::from(x)).unwrap();
+                self.state = __nzu::try_from(__u::from(x)).unwrap();
             }
         }
 
+        // update position
+        self.position = Self::position_add(self.position, bits as __u);
+
         cfg_if! {
-            if #[cfg(__if(__reflected))] {
+            if #[cfg(__if(__lsb_first))] {
                 q.reverse_bits() >> (8*size_of::<__u>() - bits)
             } else {
                 q
@@ -366,7 +864,7 @@ impl __lfsr {
         cfg_if! {
             if #[cfg(__if(__naive))] {
                 // naive lfsr using bitshifts and xors
-                let mut x = __u::from(self.0);
+                let mut x = __u::from(self.state);
                 let mut q = 0;
                 for _ in 0..bits {
                     let lsb = x & 1;
@@ -378,10 +876,31 @@ impl __lfsr {
                     };
                 }
                 // update state
-                self.0 = __nzu::try_from(x).unwrap();
+                self.state = __nzu::try_from(x).unwrap();
+            } else if #[cfg(__if(__sparse))] {
+                // lfsr using a shift and xor per term of a sparse polynomial,
+                // as long as n <= the smallest non-constant term, the low n bits
+                // are the quotient, and we can apply the remainder all at once
+                let mut x = __u::from(self.state);
+                let mut q = 0;
+                let mut i = 0;
+                while i < bits {
+                    let n = min(min(__sparse_back_chunk, 8*size_of::<__u>()-1), bits-i);
+                    let lo = x & ((1 << n) - 1);
+                    q |= lo << i;
+                    x = (x >> n) ^ (lo << (__width-n));
+                    for t in Self::SPARSE_TAPS {
+                        if t > 0 {
+                            x ^= lo << (t-n);
+                        }
+                    }
+                    i += n;
+                }
+                // update state
+                self.state = __nzu::try_from(x).unwrap();
             } else if #[cfg(__if(__table))] {
                 // lfsr with a per-byte division and remainder table
-                let mut x = __u::from(self.0);
+                let mut x = __u::from(self.state);
                 let mut q = 0;
                 for i in (0..(bits+7)/8).rev() {
                     let n = min(8, bits-8*i);
@@ -398,11 +917,11 @@ impl __lfsr {
                     }
                 }
                 // update state
-                self.0 = __nzu::try_from(x).unwrap();
+                self.state = __nzu::try_from(x).unwrap();
                 let q = q >> (8*size_of::<__u>() - bits);
             } else if #[cfg(__if(__small_table))] {
                 // lfsr with a per-nibble division and remainder table
-                let mut x = __u::from(self.0);
+                let mut x = __u::from(self.state);
                 let mut q = 0;
                 for i in (0..(bits+3)/4).rev() {
                     let n = min(4, bits-4*i);
@@ -412,11 +931,11 @@ impl __lfsr {
                         (x << (4-n)) & 0xf).unwrap()];
                 }
                 // update state
-                self.0 = __nzu::try_from(x).unwrap();
+                self.state = __nzu::try_from(x).unwrap();
                 let q = q >> (8*size_of::<__u>() - bits);
             } else if #[cfg(__if(__barret))] {
                 // lfsr using naive division with Barret-reduction
-                let x = __p2::from(__u::from(self.0).reverse_bits()) << bits;
+                let x = __p2::from(__u::from(self.state).reverse_bits()) << bits;
                 let q = x / __p2(__inverse_polynomial << (8*size_of::<__u>()-__width));
                 let lo = __p::from_lossy(x);
                 let hi = __p::try_from(x >> (8*size_of::<__u>())).unwrap();
@@ -426,11 +945,11 @@ impl __lfsr {
                 x = x >> (8*size_of::<__u>()-__width);
                 x = x.reverse_bits() >> (8*size_of::<__u>()-__width);
                 // update state
-                self.0 = __nzu::try_from(__u::from(x)).unwrap();
+                self.state = __nzu::try_from(__u::from(x)).unwrap();
                 let q = __u::try_from(q.0).unwrap().reverse_bits() >> (8*size_of::<__u>() - bits);
             } else if #[cfg(__if(__table_barret))] {
                 // lfsr using a per-byte division table with Barret-reduction
-                let mut x = __p::from(__u::from(self.0).reverse_bits());
+                let mut x = __p::from(__u::from(self.state).reverse_bits());
                 let mut q = 0;
                 for i in (0..(bits+7)/8).rev() {
                     let n = min(8, bits-8*i);
@@ -453,11 +972,11 @@ impl __lfsr {
                 x = x >> (8*size_of::<__u>()-__width);
                 x = x.reverse_bits() >> (8*size_of::<__u>()-__width);
                 // update state
-                self.0 = __nzu::try_from(__u::from(x)).unwrap();
+                self.state = __nzu::try_from(__u::from(x)).unwrap();
                 let q = q >> (8*size_of::<__u>() - bits);
             } else if #[cfg(__if(__small_table_barret))] {
                 // lfsr using a per-nibble division table with Barret-reduction
-                let mut x = __p::from(__u::from(self.0).reverse_bits());
+                let mut x = __p::from(__u::from(self.state).reverse_bits());
                 let mut q = 0;
                 for i in (0..(bits+3)/4).rev() {
                     let n = min(4, bits-4*i);
@@ -472,13 +991,16 @@ impl __lfsr {
                 x = x >> (8*size_of::<__u>()-__width);
                 x = x.reverse_bits() >> (8*size_of::<__u>()-__width);
                 // update state
-                self.0 = __nzu::try_from(__u::from(x)).unwrap();
+                self.state = __nzu::try_from(__u::from(x)).unwrap();
                 let q = q >> (8*size_of::<__u>() - bits);
             }
         }
 
+        // update position
+        self.position = Self::position_sub(self.position, bits as __u);
+
         cfg_if! {
-            if #[cfg(__if(__reflected))] {
+            if #[cfg(__if(__lsb_first))] {
                 q.reverse_bits() >> (8*size_of::<__u>() - bits)
             } else {
                 q
@@ -558,6 +1080,9 @@ impl __lfsr {
             }
         };
 
+        // update position
+        self.position = Self::position_add(self.position, Self::position_rem(bits));
+
         // Binary exponentiation
         let mut a = __p(2);
         let mut bits = bits;
@@ -575,7 +1100,7 @@ impl __lfsr {
         };
 
         // Final multiplication
-        self.0 = __nzu::try_from(__u::from(mul(__p::from(__u::from(self.0)), g))).unwrap();
+        self.state = __nzu::try_from(__u::from(mul(__p::from(__u::from(self.state)), g))).unwrap();
     }
 
     /// Skip n-bits of pseudo-random data backwards.
@@ -601,7 +1126,240 @@ impl __lfsr {
         // cycle with 2^width-1 elements. Which means backwards skips are the
         // same as skipping 2^width-1-(skip % 2^width-1) elements
         //
-        self.skip(__nonzeros - (bits % __nonzeros))
+        self.skip(__nonzeros - Self::position_rem(bits))
+    }
+
+    /// Skip n-bits of pseudo-random data, where n may be larger than the
+    /// state's width.
+    ///
+    /// Assuming the LFSR is maximal-length, n is reduced modulo the LFSR's
+    /// period, `2^width-1`, so this is just as efficient as
+    /// [`skip`](Self::skip).
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut lfsr = Lfsr16::new(1);
+    /// lfsr.skip_u128(1 << 40);
+    /// assert_eq!(lfsr.next(16), 0x018a);
+    ///
+    /// let mut lfsr = Lfsr16::new(1);
+    /// lfsr.skip(((1u64 << 40) % 0xffff) as u16);
+    /// assert_eq!(lfsr.next(16), 0x018a);
+    /// ```
+    ///
+    #[inline]
+    pub fn skip_u128(&mut self, bits: u128) {
+        self.skip((bits % (__nonzeros as u128)) as __u)
+    }
+
+    /// Skip n-bits of pseudo-random data backwards, where n may be larger
+    /// than the state's width.
+    ///
+    /// Assuming the LFSR is maximal-length, n is reduced modulo the LFSR's
+    /// period, `2^width-1`, so this is just as efficient as
+    /// [`skip_backwards`](Self::skip_backwards).
+    ///
+    #[inline]
+    pub fn skip_backwards_u128(&mut self, bits: u128) {
+        self.skip_backwards((bits % (__nonzeros as u128)) as __u)
+    }
+
+    /// Get the current position of the LFSR in bits, relative to its seed.
+    ///
+    /// Since an LFSR repeats after its period, this is always less than
+    /// [`PERIOD`](Self::PERIOD):
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut lfsr = Lfsr16::new(1);
+    /// lfsr.next(16);
+    /// lfsr.skip(100);
+    /// assert_eq!(lfsr.position(), 116);
+    /// lfsr.prev(16);
+    /// assert_eq!(lfsr.position(), 100);
+    /// lfsr.skip_backwards(101);
+    /// assert_eq!(lfsr.position(), Lfsr16::PERIOD-1);
+    /// ```
+    ///
+    #[inline]
+    pub const fn position(&self) -> __u {
+        self.position
+    }
+
+    /// Seek to an absolute position in bits, relative to the LFSR's seed.
+    ///
+    /// Unlike [`skip`](Self::skip), this is independent of the LFSR's
+    /// current position, which is useful for replaying a stream with random
+    /// access:
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut lfsr = Lfsr16::new(1);
+    /// assert_eq!(lfsr.next(16), 0x0001);
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    /// assert_eq!(lfsr.next(16), 0x0451);
+    /// assert_eq!(lfsr.next(16), 0xbdad);
+    /// lfsr.seek(16);
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    /// lfsr.seek(48);
+    /// assert_eq!(lfsr.next(16), 0xbdad);
+    /// ```
+    ///
+    /// Note the seed is the state the LFSR was created with, see
+    /// [`new`](Self::new) and [`seed`](Self::seed).
+    ///
+    #[inline]
+    pub fn seek(&mut self, position: __u) {
+        self.state = self.seed;
+        self.position = 0;
+        self.skip(position);
+    }
+
+    // generate the next n-bits of pseudo-random data, where n may be
+    // larger than the LFSR's width
+    #[inline]
+    fn next_word(&mut self, bits: usize) -> u64 {
+        if __width >= bits {
+            return self.next(bits as __u) as u64;
+        }
+
+        let mut x = 0;
+        let mut i = 0;
+        while i < bits {
+            let n = min(__width, bits-i);
+            cfg_if! {
+                if #[cfg(__if(__lsb_first))] {
+                    x |= (self.next(n as __u) as u64) << i;
+                } else {
+                    x = (x << n) | (self.next(n as __u) as u64);
+                }
+            }
+            i += n;
+        }
+        x
+    }
+
+    /// Iterate over the pseudo-random data, 32-bits at a time.
+    ///
+    /// This generates the same bits, in the same order, as repeatedly
+    /// calling [`next`](Self::next), but works for any width of LFSR:
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut lfsr = Lfsr16::new(1);
+    /// let buf = lfsr.iter_u32().take(2).collect::<Vec<_>>();
+    /// assert_eq!(buf, &[0x0001002d, 0x0451bdad]);
+    /// ```
+    ///
+    /// This iterator never ends.
+    ///
+    #[inline]
+    pub fn iter_u32(&mut self) -> impl FusedIterator<Item=u32> + '_ {
+        core::iter::repeat_with(move || self.next_word(32) as u32)
+    }
+
+    /// Iterate over the pseudo-random data, 64-bits at a time.
+    ///
+    /// This generates the same bits, in the same order, as repeatedly
+    /// calling [`next`](Self::next), but works for any width of LFSR:
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut lfsr = Lfsr16::new(1);
+    /// let buf = lfsr.iter_u64().take(2).collect::<Vec<_>>();
+    /// assert_eq!(buf, &[0x0001002d0451bdad, 0x13d3b87794e7fcb8]);
+    /// ```
+    ///
+    /// This iterator never ends.
+    ///
+    #[inline]
+    pub fn iter_u64(&mut self) -> impl FusedIterator<Item=u64> + '_ {
+        core::iter::repeat_with(move || self.next_word(64))
+    }
+
+    /// Split the LFSR into n interleaved LFSRs.
+    ///
+    /// The k-th LFSR produces every n-th `bits`-sized output of the original
+    /// LFSR, starting with the k-th output. This is known as leapfrogging,
+    /// and allows a single pseudo-random stream to be divided between
+    /// multiple threads deterministically.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut lfsr = Lfsr16::new(1);
+    /// let mut split = lfsr.split(2, 16).collect::<Vec<_>>();
+    /// assert_eq!(lfsr.next(16), 0x0001);
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    /// assert_eq!(lfsr.next(16), 0x0451);
+    /// assert_eq!(lfsr.next(16), 0xbdad);
+    /// assert_eq!(split[0].next(), Some(0x0001));
+    /// assert_eq!(split[0].next(), Some(0x0451));
+    /// assert_eq!(split[1].next(), Some(0x002d));
+    /// assert_eq!(split[1].next(), Some(0xbdad));
+    /// ```
+    ///
+    #[inline]
+    pub fn split(&self, n: usize, bits: __u) -> impl Iterator<Item=__crate::lfsr::Leapfrog<Self>> {
+        __crate::lfsr::Leapfrog::split(self.clone(), n, bits)
+    }
+}
+
+
+// Lfsr trait implementation
+
+impl __crate::lfsr::Lfsr for __lfsr {
+    type U = __u;
+
+    const WIDTH: usize = __width;
+
+    #[inline]
+    fn try_new(seed: __u) -> Result<Self, __crate::lfsr::Error> {
+        __lfsr::try_new(seed)
+    }
+
+    #[inline]
+    fn next(&mut self, bits: __u) -> __u {
+        __lfsr::next(self, bits)
+    }
+
+    #[inline]
+    fn prev(&mut self, bits: __u) -> __u {
+        __lfsr::prev(self, bits)
+    }
+
+    #[inline]
+    fn skip(&mut self, bits: __u) {
+        __lfsr::skip(self, bits)
+    }
+
+    #[inline]
+    fn skip_backwards(&mut self, bits: __u) {
+        __lfsr::skip_backwards(self, bits)
+    }
+
+    #[inline]
+    fn skip_u128(&mut self, bits: u128) {
+        __lfsr::skip_u128(self, bits)
+    }
+
+    #[inline]
+    fn skip_backwards_u128(&mut self, bits: u128) {
+        __lfsr::skip_backwards_u128(self, bits)
+    }
+
+    #[inline]
+    fn position(&self) -> __u {
+        __lfsr::position(self)
+    }
+
+    #[inline]
+    fn seek(&mut self, position: __u) {
+        __lfsr::seek(self, position)
+    }
+
+    #[inline]
+    fn state(&self) -> __u {
+        __lfsr::state(self)
     }
 }
 
@@ -609,15 +1367,23 @@ impl __lfsr {
 // Rng implementation
 
 impl SeedableRng for __lfsr {
+    /// The seed is the full width of the LFSR's state, in little-endian
+    /// bytes.
     type Seed = [u8; size_of::<__u>()];
 
+    /// Create an LFSR from a seed in little-endian bytes.
+    ///
+    /// This follows the same normalization as [`new`](Self::new), bits
+    /// outside of the LFSR's width are ignored, and a zero seed is replaced
+    /// with `1`. See [`try_new`](Self::try_new) to reject zero seeds.
+    ///
     #[inline]
     fn from_seed(seed: Self::Seed) -> Self {
         Self::new(__u::from_le_bytes(seed))
     }
 
     #[inline]
-    fn from_rng<R: RngCore>(mut rng: R) -> Result<Self, rand::Error> {
+    fn from_rng<R: RngCore>(mut rng: R) -> Result<Self, __crate::internal::rand_core::Error> {
         // find the first non-zero seed
         let mut seed = [0; size_of::<__u>()];
         loop {
@@ -643,7 +1409,7 @@ impl RngCore for __lfsr {
                     for i in (0..8).step_by(__width) {
                         let n = min(__width, 8-i);
                         cfg_if! {
-                            if #[cfg(__if(__reflected))] {
+                            if #[cfg(__if(__lsb_first))] {
                                 bits = (bits >> n) | (self.next(n) << (8-n));
                             } else {
                                 bits = (bits << n) | self.next(n);
@@ -658,7 +1424,7 @@ impl RngCore for __lfsr {
                 for chunk in &mut chunks {
                     chunk.copy_from_slice({
                         cfg_if! {
-                            if #[cfg(__if(__reflected))] {
+                            if #[cfg(__if(__lsb_first))] {
                                 &self.next(8*(__width/8)).to_le_bytes()[..__width/8]
                             } else {
                                 &self.next(8*(__width/8)).to_be_bytes()[size_of::<__u>()-(__width/8)..]
@@ -671,7 +1437,7 @@ impl RngCore for __lfsr {
                 if remainder.len() > 0 {
                     remainder.copy_from_slice({
                         cfg_if! {
-                            if #[cfg(__if(__reflected))] {
+                            if #[cfg(__if(__lsb_first))] {
                                 &self.next(8*remainder.len() as __u).to_le_bytes()[..remainder.len()]
                             } else {
                                 &self.next(8*remainder.len() as __u).to_be_bytes()[size_of::<__u>()-remainder.len()..]
@@ -684,7 +1450,7 @@ impl RngCore for __lfsr {
     }
 
     #[inline]
-    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), __crate::internal::rand_core::Error> {
         Ok(self.fill_bytes(dest))
     }
 
@@ -693,7 +1459,7 @@ impl RngCore for __lfsr {
         cfg_if! {
             if #[cfg(__if(__width >= 32))] {
                 cfg_if! {
-                    if #[cfg(__if(__reflected))] {
+                    if #[cfg(__if(__lsb_first))] {
                         self.next(32) as u32
                     } else {
                         (self.next(32) as u32).swap_bytes()
@@ -712,7 +1478,7 @@ impl RngCore for __lfsr {
         cfg_if! {
             if #[cfg(__if(__width >= 64))] {
                 cfg_if! {
-                    if #[cfg(__if(__reflected))] {
+                    if #[cfg(__if(__lsb_first))] {
                         self.next(64) as u64
                     } else {
                         (self.next(64) as u64).swap_bytes()
@@ -727,3 +1493,98 @@ impl RngCore for __lfsr {
     }
 }
 
+impl BlockRngCore for __lfsr {
+    type Item = u32;
+    type Results = [u32; 16];
+
+    /// Generate a block of pseudo-random data.
+    ///
+    /// This generates the same stream of bytes as
+    /// [`fill_bytes`](RngCore::fill_bytes), so wrapping an LFSR in a
+    /// [`BlockRng`](__crate::internal::rand_core::block::BlockRng) only
+    /// changes how the stream is buffered. Note that a buffered LFSR can
+    /// no longer [`prev`](Self::prev) or [`skip`](Self::skip) precisely.
+    ///
+    #[inline]
+    fn generate(&mut self, results: &mut Self::Results) {
+        for x in results.iter_mut() {
+            cfg_if! {
+                if #[cfg(__if(__width >= 32))] {
+                    *x = self.next_u32();
+                } else {
+                    // build up words from smaller steps, in the same bit
+                    // order as fill_bytes
+                    let mut word = 0u32;
+                    for i in (0..32).step_by(__width) {
+                        let n = min(__width, 32-i);
+                        cfg_if! {
+                            if #[cfg(__if(__lsb_first))] {
+                                word |= (self.next(n) as u32) << i;
+                            } else {
+                                word = (word << n) | self.next(n) as u32;
+                            }
+                        }
+                    }
+                    cfg_if! {
+                        if #[cfg(__if(__lsb_first))] {
+                            *x = word;
+                        } else {
+                            *x = word.swap_bytes();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+
+// Serde implementation, if enabled
+
+#[cfg(__if(__serde))]
+impl __crate::internal::serde::Serialize for __lfsr {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: __crate::internal::serde::Serializer
+    {
+        __crate::internal::serde::Serialize::serialize(
+            &(self.state(), self.seed(), self.position),
+            serializer
+        )
+    }
+}
+
+#[cfg(__if(__serde))]
+impl<'de> __crate::internal::serde::Deserialize<'de> for __lfsr {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: __crate::internal::serde::Deserializer<'de>
+    {
+        let (state, seed, position) = <(__u, __u, __u) as __crate::internal::serde::Deserialize>
+            ::deserialize(deserializer)?;
+
+        // unlike new, reject states that can't come from a valid LFSR,
+        // these indicate the serialized data is corrupt
+        if state == 0 || state & !__nonzeros != 0
+            || seed == 0 || seed & !__nonzeros != 0
+            || position >= __nonzeros
+        {
+            return Err(<D::Error as __crate::internal::serde::de::Error>::custom(
+                "invalid lfsr state"
+            ));
+        }
+
+        // the seed at the position should lead to the state
+        let mut lfsr = Self::new(seed);
+        lfsr.skip(position);
+        if lfsr.state() != state {
+            return Err(<D::Error as __crate::internal::serde::de::Error>::custom(
+                "invalid lfsr state"
+            ));
+        }
+
+        Ok(lfsr)
+    }
+}
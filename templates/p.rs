@@ -262,6 +262,105 @@ impl __p {
         }
     }
 
+    /// Polynomial multiply-accumulate.
+    ///
+    /// This computes `acc + self*other`, where `acc` is a tuple containing
+    /// the low and high parts of a widened accumulator. When hardware
+    /// carry-less multiplication instructions are available, the accumulator
+    /// can stay in the same register as the multiplication result.
+    ///
+    /// This returns a tuple containing the low and high parts in that order.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let acc = (p8(0x01), p8(0x10));
+    /// assert_eq!(p8(0x02).widening_mul_acc(p8(0x34), acc), (p8(0x69), p8(0x10)));
+    /// assert_eq!(p8(0x12).widening_mul_acc(p8(0x34), acc), (p8(0x29), p8(0x13)));
+    /// ```
+    ///
+    #[inline]
+    pub fn widening_mul_acc(self, other: __p, acc: (__p, __p)) -> (__p, __p) {
+        cfg_if! {
+            if #[cfg(__if(__has_xmul_acc))] {
+                let (lo, hi) = __xmul_acc(
+                    (acc.0.0 as _, acc.1.0 as _),
+                    self.0 as _,
+                    other.0 as _
+                );
+                (__p(lo as __u), __p(hi as __u))
+            } else {
+                let (lo, hi) = self.widening_mul(other);
+                (acc.0 + lo, acc.1 + hi)
+            }
+        }
+    }
+
+    /// Polynomial multiply-accumulate.
+    ///
+    /// This computes `acc + self*other`. When hardware carry-less
+    /// multiplication instructions are available, the accumulator can stay
+    /// in the same register as the multiplication result.
+    ///
+    /// Note this wraps around the boundary of the type.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// assert_eq!(p8(0x02).wrapping_mul_acc(p8(0x34), p8(0x01)), p8(0x69));
+    /// assert_eq!(p8(0x12).wrapping_mul_acc(p8(0x34), p8(0x01)), p8(0x29));
+    /// ```
+    ///
+    #[inline]
+    pub fn wrapping_mul_acc(self, other: __p, acc: __p) -> __p {
+        cfg_if! {
+            if #[cfg(__if(__has_xmul_acc))] {
+                __p(__xmul_acc((acc.0 as _, 0), self.0 as _, other.0 as _).0 as __u)
+            } else {
+                acc + self.wrapping_mul(other)
+            }
+        }
+    }
+
+    /// Batched polynomial multiplication.
+    ///
+    /// This performs four independent widening multiplications at once,
+    /// which can be done with a single `vpclmulqdq` instruction on x86_64
+    /// with avx512. Otherwise this falls back to four separate
+    /// multiplications.
+    ///
+    /// This returns a tuple containing the low and high parts in that order.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let (lo, hi) = p8::widening_mul_x4(
+    ///     [p8(0x02), p8(0x12), p8(0x12), p8(0x00)],
+    ///     [p8(0x34), p8(0x34), p8(0x12), p8(0x34)],
+    /// );
+    /// assert_eq!(lo, [p8(0x68), p8(0x28), p8(0x04), p8(0x00)]);
+    /// assert_eq!(hi, [p8(0x00), p8(0x03), p8(0x01), p8(0x00)]);
+    /// ```
+    ///
+    #[inline]
+    pub fn widening_mul_x4(a: [__p; 4], b: [__p; 4]) -> ([__p; 4], [__p; 4]) {
+        cfg_if! {
+            if #[cfg(__if(__has_xmul4))] {
+                let (lo, hi) = __xmul4(
+                    [a[0].0 as _, a[1].0 as _, a[2].0 as _, a[3].0 as _],
+                    [b[0].0 as _, b[1].0 as _, b[2].0 as _, b[3].0 as _],
+                );
+                (
+                    [__p(lo[0] as __u), __p(lo[1] as __u), __p(lo[2] as __u), __p(lo[3] as __u)],
+                    [__p(hi[0] as __u), __p(hi[1] as __u), __p(hi[2] as __u), __p(hi[3] as __u)],
+                )
+            } else {
+                let (lo0, hi0) = a[0].widening_mul(b[0]);
+                let (lo1, hi1) = a[1].widening_mul(b[1]);
+                let (lo2, hi2) = a[2].widening_mul(b[2]);
+                let (lo3, hi3) = a[3].widening_mul(b[3]);
+                ([lo0, lo1, lo2, lo3], [hi0, hi1, hi2, hi3])
+            }
+        }
+    }
+
     /// Polynomial multiplication.
     ///
     /// This attempts to use carry-less multiplication instructions when
@@ -357,6 +456,181 @@ impl __p {
         }
     }
 
+    /// Naive bit-reversed polynomial multiplication.
+    ///
+    /// This multiplies two polynomials stored in a bit-reversed
+    /// representation, where the most-significant bit holds the coefficient
+    /// of `x^0`. This is the representation used by reflected CRCs.
+    ///
+    /// Polynomial multiplication has the odd property that
+    /// `brev(a) * brev(b) = brev((a * b) << 1)`, so this is just a normal
+    /// polynomial multiplication followed by a 1-bit shift.
+    ///
+    /// Naive versions are built out of simple bitwise operations,
+    /// these are more expensive, but also allowed in const contexts.
+    ///
+    /// This returns a tuple containing the low and high parts in that order.
+    /// Note that in a bit-reversed representation, the high part contains
+    /// the low-degree terms.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// const X: (p8, p8) = p8(0x40).naive_rev_widening_mul(p8(0x2c));
+    /// const Y: (p8, p8) = p8(0x48).naive_rev_widening_mul(p8(0x2c));
+    /// assert_eq!(X, (p8(0x00), p8(0x16)));
+    /// assert_eq!(Y, (p8(0xc0), p8(0x14)));
+    /// ```
+    ///
+    #[inline]
+    pub const fn naive_rev_widening_mul(self, other: __p) -> (__p, __p) {
+        let (lo, hi) = self.naive_widening_mul(other);
+        (__p(lo.0 << 1), __p((hi.0 << 1) | (lo.0 >> (__width-1))))
+    }
+
+    /// Naive bit-reversed polynomial multiplication.
+    ///
+    /// This multiplies two polynomials stored in a bit-reversed
+    /// representation, where the most-significant bit holds the coefficient
+    /// of `x^0`. This is the representation used by reflected CRCs.
+    ///
+    /// Naive versions are built out of simple bitwise operations,
+    /// these are more expensive, but also allowed in const contexts.
+    ///
+    /// Note this wraps around the boundary of the type, which in a
+    /// bit-reversed representation discards the high-degree terms.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// const X: p8 = p8(0x40).naive_rev_wrapping_mul(p8(0x2c));
+    /// const Y: p8 = p8(0x48).naive_rev_wrapping_mul(p8(0x2c));
+    /// assert_eq!(X, p8(0x16));
+    /// assert_eq!(Y, p8(0x14));
+    /// ```
+    ///
+    #[inline]
+    pub const fn naive_rev_wrapping_mul(self, other: __p) -> __p {
+        self.naive_rev_widening_mul(other).1
+    }
+
+    /// Naive bit-reversed polynomial multiplication.
+    ///
+    /// This multiplies two polynomials stored in a bit-reversed
+    /// representation, where the most-significant bit holds the coefficient
+    /// of `x^0`. This is the representation used by reflected CRCs.
+    ///
+    /// Naive versions are built out of simple bitwise operations,
+    /// these are more expensive, but also allowed in const contexts.
+    ///
+    /// Note this panics if an overflow occured and debug_assertions
+    /// are enabled.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// const X: p8 = p8(0x40).naive_rev_mul(p8(0x2c));
+    /// assert_eq!(X, p8(0x16));
+    /// ```
+    ///
+    #[inline]
+    pub const fn naive_rev_mul(self, other: __p) -> __p {
+        cfg_if! {
+            // TODO feature flag for overflow-checks?
+            if #[cfg(debug_assertions)] {
+                match self.naive_rev_widening_mul(other) {
+                    (lo, hi) if lo.0 == 0 => hi,
+                    _ => __p(self.0 / 0),
+                }
+            } else {
+                self.naive_rev_wrapping_mul(other)
+            }
+        }
+    }
+
+    /// Bit-reversed polynomial multiplication.
+    ///
+    /// This multiplies two polynomials stored in a bit-reversed
+    /// representation, where the most-significant bit holds the coefficient
+    /// of `x^0`. This is the representation used by reflected CRCs.
+    ///
+    /// Polynomial multiplication has the odd property that
+    /// `brev(a) * brev(b) = brev((a * b) << 1)`, so this is just a normal
+    /// polynomial multiplication followed by a 1-bit shift.
+    ///
+    /// This attempts to use carry-less multiplication instructions when
+    /// available (`pclmulqdq` on x86_64, `pmull` on aarch64), otherwise falls
+    /// back to the expensive naive implementation.
+    ///
+    /// This returns a tuple containing the low and high parts in that order.
+    /// Note that in a bit-reversed representation, the high part contains
+    /// the low-degree terms.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// assert_eq!(p8(0x40).rev_widening_mul(p8(0x2c)), (p8(0x00), p8(0x16)));
+    /// assert_eq!(p8(0x48).rev_widening_mul(p8(0x2c)), (p8(0xc0), p8(0x14)));
+    /// ```
+    ///
+    #[inline]
+    pub fn rev_widening_mul(self, other: __p) -> (__p, __p) {
+        let (lo, hi) = self.widening_mul(other);
+        (lo << 1, (hi << 1) | (lo >> (__width-1)))
+    }
+
+    /// Bit-reversed polynomial multiplication.
+    ///
+    /// This multiplies two polynomials stored in a bit-reversed
+    /// representation, where the most-significant bit holds the coefficient
+    /// of `x^0`. This is the representation used by reflected CRCs.
+    ///
+    /// This attempts to use carry-less multiplication instructions when
+    /// available (`pclmulqdq` on x86_64, `pmull` on aarch64), otherwise falls
+    /// back to the expensive naive implementation.
+    ///
+    /// Note this wraps around the boundary of the type, which in a
+    /// bit-reversed representation discards the high-degree terms.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// assert_eq!(p8(0x40).rev_wrapping_mul(p8(0x2c)), p8(0x16));
+    /// assert_eq!(p8(0x48).rev_wrapping_mul(p8(0x2c)), p8(0x14));
+    /// ```
+    ///
+    #[inline]
+    pub fn rev_wrapping_mul(self, other: __p) -> __p {
+        self.rev_widening_mul(other).1
+    }
+
+    /// Bit-reversed polynomial multiplication.
+    ///
+    /// This multiplies two polynomials stored in a bit-reversed
+    /// representation, where the most-significant bit holds the coefficient
+    /// of `x^0`. This is the representation used by reflected CRCs.
+    ///
+    /// This attempts to use carry-less multiplication instructions when
+    /// available (`pclmulqdq` on x86_64, `pmull` on aarch64), otherwise falls
+    /// back to the expensive naive implementation.
+    ///
+    /// Note this panics if an overflow occured and debug_assertions
+    /// are enabled.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// assert_eq!(p8(0x40).rev_mul(p8(0x2c)), p8(0x16));
+    /// ```
+    ///
+    #[inline]
+    pub fn rev_mul(self, other: __p) -> __p {
+        cfg_if! {
+            // TODO feature flag for overflow-checks?
+            if #[cfg(debug_assertions)] {
+                let (lo, hi) = self.rev_widening_mul(other);
+                assert!(lo.0 == 0, "overflow in polynomial multiply");
+                hi
+            } else {
+                self.rev_wrapping_mul(other)
+            }
+        }
+    }
+
     /// Naive polynomial exponentiation.
     ///
     /// Performs exponentiation by squaring, where polynomial exponentiation
@@ -818,6 +1092,37 @@ impl __p {
             )
         }
     }
+
+    /// Xor a slice of polynomials into another slice of polynomials.
+    ///
+    /// This is equivalent to polynomial addition over each element, but uses
+    /// explicit wide registers when available. See [`slice_xor`](../p/fn.slice_xor.html)
+    /// for more info.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let mut x = [p8(0x12), p8(0x34), p8(0x56)];
+    /// p8::slice_xor(&mut x, &[p8(0x34), p8(0x34), p8(0x34)]);
+    /// assert_eq!(x, [p8(0x26), p8(0x00), p8(0x62)]);
+    /// ```
+    ///
+    #[inline]
+    pub fn slice_xor(dst: &mut [__p], src: &[__p]) {
+        assert_eq!(dst.len(), src.len());
+        let dst = unsafe {
+            slice::from_raw_parts_mut(
+                dst.as_mut_ptr() as *mut u8,
+                dst.len()*size_of::<__p>()
+            )
+        };
+        let src = unsafe {
+            slice::from_raw_parts(
+                src.as_ptr() as *const u8,
+                src.len()*size_of::<__p>()
+            )
+        };
+        __crate::p::slice_xor(dst, src);
+    }
 }
 
 
@@ -2251,6 +2556,206 @@ impl __p {
     pub const fn from_ne_bytes(bytes: [u8; __width/8]) -> __p {
         __p(__u::from_ne_bytes(bytes))
     }
+
+    /// Read a polynomial from a little-endian byte slice, which may be
+    /// shorter than the polynomial's width. Missing bytes are treated as
+    /// zero.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// assert_eq!(p32::from_le_byte_slice(&[0x12, 0x34, 0x56]), p32(0x563412));
+    /// ```
+    ///
+    /// Panics if the byte slice is larger than the polynomial.
+    ///
+    #[inline]
+    pub const fn from_le_byte_slice(bytes: &[u8]) -> __p {
+        assert!(bytes.len() <= size_of::<__u>());
+        let mut x: __u = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            x |= (bytes[i] as __u) << (8*i);
+            i += 1;
+        }
+        __p(x)
+    }
+
+    /// Write the lower bytes of a polynomial into a little-endian byte
+    /// slice, which may be shorter than the polynomial's width. Any bytes
+    /// that don't fit are discarded.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let mut bytes = [0u8; 3];
+    /// p32(0x78563412).to_le_byte_slice(&mut bytes);
+    /// assert_eq!(bytes, [0x12, 0x34, 0x56]);
+    /// ```
+    ///
+    /// Panics if the byte slice is larger than the polynomial.
+    ///
+    #[inline]
+    pub fn to_le_byte_slice(self, bytes: &mut [u8]) {
+        assert!(bytes.len() <= size_of::<__u>());
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (self.0 >> (8*i)) as u8;
+        }
+    }
+
+    /// Read a polynomial from a big-endian byte slice, which may be
+    /// shorter than the polynomial's width. Missing bytes are treated as
+    /// zero.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// assert_eq!(p32::from_be_byte_slice(&[0x12, 0x34, 0x56]), p32(0x123456));
+    /// ```
+    ///
+    /// Panics if the byte slice is larger than the polynomial.
+    ///
+    #[inline]
+    pub const fn from_be_byte_slice(bytes: &[u8]) -> __p {
+        assert!(bytes.len() <= size_of::<__u>());
+        let mut x: __u = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            x |= (bytes[i] as __u) << (8*(bytes.len()-1-i));
+            i += 1;
+        }
+        __p(x)
+    }
+
+    /// Write the lower bytes of a polynomial into a big-endian byte
+    /// slice, which may be shorter than the polynomial's width. Any bytes
+    /// that don't fit are discarded.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let mut bytes = [0u8; 3];
+    /// p32(0x78563412).to_be_byte_slice(&mut bytes);
+    /// assert_eq!(bytes, [0x56, 0x34, 0x12]);
+    /// ```
+    ///
+    /// Panics if the byte slice is larger than the polynomial.
+    ///
+    #[inline]
+    pub fn to_be_byte_slice(self, bytes: &mut [u8]) {
+        assert!(bytes.len() <= size_of::<__u>());
+        let len = bytes.len();
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (self.0 >> (8*(len-1-i))) as u8;
+        }
+    }
+
+    /// Read a polynomial from an arbitrary range of bits in a byte slice,
+    /// where bits are numbered least-significant-bit first.
+    ///
+    /// Bit `bit_off` of the byte slice becomes the lowest coefficient of
+    /// the resulting polynomial, and `bit_len` bits are read in total:
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let bytes = [0x12, 0x34, 0x56, 0x78];
+    /// assert_eq!(p32::from_le_bit_slice(&bytes, 4, 23), p32(0x056341));
+    /// ```
+    ///
+    /// Panics if `bit_len` is larger than the polynomial's width, or if
+    /// the bits are out of bounds of the byte slice.
+    ///
+    #[inline]
+    pub const fn from_le_bit_slice(bytes: &[u8], bit_off: usize, bit_len: usize) -> __p {
+        assert!(bit_len <= __width);
+        assert!(bit_off + bit_len <= 8*bytes.len());
+        let mut x: __u = 0;
+        let mut i = 0;
+        while i < bit_len {
+            let bit = bit_off + i;
+            x |= (((bytes[bit/8] >> (bit%8)) & 1) as __u) << i;
+            i += 1;
+        }
+        __p(x)
+    }
+
+    /// Write the lower `bit_len` bits of a polynomial into an arbitrary
+    /// range of bits in a byte slice, where bits are numbered
+    /// least-significant-bit first. Other bits in the byte slice are
+    /// left unchanged.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let mut bytes = [0x12, 0x34, 0x56, 0x78];
+    /// p32(0x7abcde).to_le_bit_slice(&mut bytes, 4, 23);
+    /// assert_eq!(bytes, [0xe2, 0xcd, 0xab, 0x7f]);
+    /// ```
+    ///
+    /// Panics if `bit_len` is larger than the polynomial's width, or if
+    /// the bits are out of bounds of the byte slice.
+    ///
+    #[inline]
+    pub fn to_le_bit_slice(self, bytes: &mut [u8], bit_off: usize, bit_len: usize) {
+        assert!(bit_len <= __width);
+        assert!(bit_off + bit_len <= 8*bytes.len());
+        for i in 0..bit_len {
+            let bit = bit_off + i;
+            let x = ((self.0 >> i) & 1) as u8;
+            bytes[bit/8] = (bytes[bit/8] & !(1 << (bit%8))) | (x << (bit%8));
+        }
+    }
+
+    /// Read a polynomial from an arbitrary range of bits in a byte slice,
+    /// where bits are numbered most-significant-bit first, as is common
+    /// in network protocols.
+    ///
+    /// Bit `bit_off` of the byte slice becomes the highest coefficient of
+    /// the resulting polynomial, and `bit_len` bits are read in total:
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let bytes = [0x12, 0x34, 0x56, 0x78];
+    /// assert_eq!(p32::from_be_bit_slice(&bytes, 4, 23), p32(0x11a2b3));
+    /// ```
+    ///
+    /// Panics if `bit_len` is larger than the polynomial's width, or if
+    /// the bits are out of bounds of the byte slice.
+    ///
+    #[inline]
+    pub const fn from_be_bit_slice(bytes: &[u8], bit_off: usize, bit_len: usize) -> __p {
+        assert!(bit_len <= __width);
+        assert!(bit_off + bit_len <= 8*bytes.len());
+        let mut x: __u = 0;
+        let mut i = 0;
+        while i < bit_len {
+            let bit = bit_off + i;
+            x = (x << 1) | (((bytes[bit/8] >> (7-bit%8)) & 1) as __u);
+            i += 1;
+        }
+        __p(x)
+    }
+
+    /// Write the lower `bit_len` bits of a polynomial into an arbitrary
+    /// range of bits in a byte slice, where bits are numbered
+    /// most-significant-bit first. Other bits in the byte slice are
+    /// left unchanged.
+    ///
+    /// ``` rust
+    /// # use ::gf256::*;
+    /// let mut bytes = [0x12, 0x34, 0x56, 0x78];
+    /// p32(0x11a2b3).to_be_bit_slice(&mut bytes, 4, 23);
+    /// assert_eq!(bytes, [0x12, 0x34, 0x56, 0x78]);
+    /// ```
+    ///
+    /// Panics if `bit_len` is larger than the polynomial's width, or if
+    /// the bits are out of bounds of the byte slice.
+    ///
+    #[inline]
+    pub fn to_be_bit_slice(self, bytes: &mut [u8], bit_off: usize, bit_len: usize) {
+        assert!(bit_len <= __width);
+        assert!(bit_off + bit_len <= 8*bytes.len());
+        for i in 0..bit_len {
+            let bit = bit_off + i;
+            let x = ((self.0 >> (bit_len-1-i)) & 1) as u8;
+            bytes[bit/8] = (bytes[bit/8] & !(0x80 >> (bit%8))) | (x << (7-bit%8));
+        }
+    }
 }
 
 
@@ -3451,6 +3956,72 @@ impl ShrAssign<&isize> for __p {
 }
 
 
+//// Poly trait ////
+
+impl __crate::traits::Poly for __p {
+    type U = __u;
+
+    const WIDTH: usize = __width;
+    const ZERO: __p = __p(0);
+    const ONE: __p = __p(1);
+
+    #[inline]
+    fn from_u(x: __u) -> __p {
+        __p(x)
+    }
+
+    #[inline]
+    fn to_u(self) -> __u {
+        self.0
+    }
+
+    #[inline]
+    fn widening_mul(self, other: __p) -> (__p, __p) {
+        __p::widening_mul(self, other)
+    }
+
+    #[inline]
+    fn wrapping_mul(self, other: __p) -> __p {
+        __p::wrapping_mul(self, other)
+    }
+
+    #[inline]
+    fn wrapping_pow(self, exp: u32) -> __p {
+        __p::wrapping_pow(self, exp)
+    }
+
+    #[inline]
+    fn checked_div(self, other: __p) -> Option<__p> {
+        __p::naive_checked_div(self, other)
+    }
+
+    #[inline]
+    fn checked_rem(self, other: __p) -> Option<__p> {
+        __p::naive_checked_rem(self, other)
+    }
+
+    #[inline]
+    fn reverse_bits(self) -> __p {
+        __p::reverse_bits(self)
+    }
+
+    #[inline]
+    fn leading_zeros(self) -> u32 {
+        __p::leading_zeros(self)
+    }
+
+    #[inline]
+    fn trailing_zeros(self) -> u32 {
+        __p::trailing_zeros(self)
+    }
+
+    #[inline]
+    fn count_ones(self) -> u32 {
+        __p::count_ones(self)
+    }
+}
+
+
 //// To/from strings ////
 
 impl fmt::Debug for __p {
@@ -36,6 +36,24 @@ use core::slice;
 use core::cmp::min;
 use core::cmp::max;
 use core::fmt;
+use core::mem::size_of;
+use __crate::raid::BlockSource;
+use __crate::raid::BlockSink;
+use __crate::raid::BlockDevice;
+use __crate::raid::Progress;
+use __crate::raid::Backend;
+use __crate::raid::Kernel;
+#[cfg(__if(__crc))]
+use __crate::crc::Checksum;
+#[cfg(__if(__alloc))]
+use __crate::raid::Journal;
+#[cfg(__if(__alloc))]
+use __crate::raid::UpdateIntent;
+
+#[cfg(__if(__alloc))]
+extern crate alloc;
+#[cfg(__if(__alloc))]
+use alloc::vec;
 
 
 /// Error codes for RAID arrays
@@ -45,21 +63,272 @@ pub enum Error {
     /// than there are parity blocks
     ///
     TooManyBadBlocks,
+
+    /// A bad block's index is out of range, bad blocks must be less than
+    /// the number of data blocks plus the number of parity blocks
+    ///
+    BadBlockOutOfRange,
+
+    /// The number of blocks, or the length of the blocks, don't match,
+    /// parity blocks must all be the same length, and data blocks can't be
+    /// longer than the parity blocks
+    ///
+    LengthMismatch,
+
+    /// There are more data blocks than there are non-zero elements in the
+    /// field, so the parity blocks can't distinguish between them. With more
+    /// than 3 parity blocks, this limit is reduced by the number of parity
+    /// blocks minus one
+    ///
+    TooManyBlocks,
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::TooManyBadBlocks => write!(f, "Too many bad-blocks to repair"),
+            Error::BadBlockOutOfRange => write!(f, "Bad-block out of range"),
+            Error::LengthMismatch => write!(f, "Block lengths don't match"),
+            Error::TooManyBlocks => write!(f, "Too many blocks for the field"),
+        }
+    }
+}
+
+/// The maximum number of data blocks our field can support
+#[cfg(__if(__parity >= 2))]
+fn max_blocks() -> usize {
+    let nonzeros = usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX);
+    cfg_if! {
+        if #[cfg(__if(__parity >= 4))] {
+            // each data block needs a unique non-zero element, distinct from
+            // the elements of the other parity blocks, see coefficient
+            nonzeros.saturating_sub(__parity-1)
+        } else {
+            nonzeros
+        }
+    }
+}
+
+/// Check that the number of data blocks and bad blocks are valid, before
+/// we try to repair anything
+fn check_blocks(n: usize, bad_blocks: &[usize]) -> Result<(), Error> {
+    #[cfg(__if(__parity >= 2))] {
+        if n > max_blocks() {
+            return Err(Error::TooManyBlocks);
+        }
+    }
+
+    if bad_blocks.iter().any(|b| !(0..n+__parity).contains(b)) {
+        return Err(Error::BadBlockOutOfRange);
+    }
+
+    if bad_blocks.len() > __parity {
+        // can't repair
+        return Err(Error::TooManyBadBlocks);
+    }
+
+    Ok(())
+}
+
+/// Error codes for streaming RAID operations
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StreamError<E> {
+    /// The RAID operation itself failed
+    Raid(Error),
+
+    /// Reading or writing a block failed
+    Block(E),
+
+    /// The operation was cancelled by its progress callback
+    Cancelled,
+}
+
+impl<E> From<Error> for StreamError<E> {
+    fn from(err: Error) -> StreamError<E> {
+        StreamError::Raid(err)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for StreamError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Raid(err) => write!(f, "{}", err),
+            StreamError::Block(err) => write!(f, "Block error: {}", err),
+            StreamError::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+/// The result of scrubbing a RAID array, see [`scrub`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ScrubReport {
+    /// The number of symbols where the parity blocks did not match the
+    /// data blocks
+    pub mismatches: usize,
+
+    /// The index of the bad block, if all mismatches can be explained by a
+    /// single bad block. Parity blocks are indexed after the data blocks,
+    /// the same as in [`repair`].
+    pub bad_block: Option<usize>,
+}
+
+impl ScrubReport {
+    /// Returns true if the parity blocks matched the data blocks
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches == 0
+    }
+}
+
+/// How a set of bad blocks would be repaired, see [`plan`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RepairPlan {
+    /// The number of bad data blocks that need to be solved for
+    pub bad_datas: usize,
+
+    /// Which parity blocks' equations are used to solve for the bad data
+    /// blocks, indexed relative to the parity blocks, so `equations[0]` is
+    /// the first parity block
+    pub equations: [bool; __parity],
+
+    /// Which parity blocks are regenerated from the data blocks
+    pub regenerated: [bool; __parity],
+}
+
+/// Find the single bad block that explains a set of non-zero syndromes,
+/// where syndrome k is the difference between parity block k and the
+/// parity block we would expect from the data blocks
+///
+/// An error e in data block x results in syndromes e*coefficient(x, k),
+/// while an error in parity block k only results in syndrome k.
+///
+#[cfg(__if(__parity >= 2))]
+fn locate(n: usize, syndromes: &[__gf]) -> Option<usize> {
+    let mut nonzeros = syndromes.iter().enumerate()
+        .filter(|(_, s)| **s != __gf::new(0));
+    match (nonzeros.next(), nonzeros.next()) {
+        // all zero? nothing to locate
+        (None, _) => return None,
+        // only one non-zero syndrome, must be a parity block
+        (Some((k, _)), None) => return Some(n+k),
+        _ => {}
+    }
+
+    cfg_if! {
+        if #[cfg(__if(__parity >= 4))] {
+            // find x such that coefficient(x, 1) = s1/s0, note parity block 0
+            // is a plain xor, so s0 = e
+            let ratio = syndromes[1].checked_div(syndromes[0])?;
+            let x = (0..n).find(|x| coefficient(*x, 1) == ratio)?;
+
+            // check that the remaining syndromes agree
+            for (k, s) in syndromes.iter().enumerate() {
+                if *s != syndromes[0]*coefficient(x, k) {
+                    return None;
+                }
+            }
+
+            Some(x)
+        } else {
+            // find x such that g^x = s1/s0
+            let ratio = syndromes[1].checked_div(syndromes[0])?;
+            let mut g = __gf::new(1);
+            let mut x = None;
+            for j in 0..n {
+                if g == ratio {
+                    x = Some(j);
+                    break;
+                }
+                g *= __gf::GENERATOR;
+            }
+            let x = x?;
+
+            // check that the remaining syndromes agree
+            let mut expected = syndromes[0];
+            for s in syndromes.iter() {
+                if *s != expected {
+                    return None;
+                }
+                expected *= ratio;
+            }
+
+            Some(x)
+        }
+    }
+}
+
+
+// Multiply a block by a constant and add it to a parity block,
+// p[i] += b[i]*c for i in lo..min(hi, b.len())
+//
+// Note subtraction is also addition in GF(2^n), and for 8-bit symbols
+// this uses explicit SIMD instructions when available
+#[inline]
+fn mul_acc(p: &mut [__gf], b: &[__u], c: __gf, lo: usize, hi: usize) {
+    let hi = min(hi, b.len());
+    if lo >= hi {
+        return;
+    }
+
+    if size_of::<__u>() == 1 {
+        // slice_mul_acc ignores any unused bits, which is equivalent to
+        // from_lossy
+        let b = unsafe { __gf::slice_from_slice_unchecked(&b[lo..hi]) };
+        __gf::slice_mul_acc(&mut p[lo..hi], b, c);
+    } else {
+        for i in lo..hi {
+            p[i] += __gf::from_lossy(b[i]) * c;
         }
     }
 }
 
+// Multiply a block by a constant and add it to a parity block using a
+// specific SIMD kernel, see mul_acc
+//
+// The kernel only applies to 8-bit fields, other fields fall back to the
+// field's own multiplication
+#[inline]
+fn mul_acc_with(
+    kernel: Kernel,
+    p: &mut [__gf],
+    b: &[__u],
+    c: __gf,
+    lo: usize,
+    hi: usize,
+) {
+    let hi = min(hi, b.len());
+    if lo >= hi {
+        return;
+    }
+
+    if size_of::<__gf>() == 1 && size_of::<__u>() == 1 {
+        // multiplication by a constant is linear, so all we need is the
+        // constant multiplied by each bit, unused bits are left as zero
+        let mut basis = [__gf::new(0); 8];
+        let width = __gf::NONZEROS.count_ones() as usize;
+        for (j, x) in basis.iter_mut().enumerate().take(width) {
+            *x = c * __gf::from_lossy(__u::from(1u8 << j));
+        }
+
+        let basis = unsafe { *(basis.as_ptr() as *const [u8; 8]) };
+        let p = unsafe {
+            slice::from_raw_parts_mut(p[lo..hi].as_mut_ptr() as *mut u8, hi-lo)
+        };
+        let b = unsafe {
+            slice::from_raw_parts(b[lo..hi].as_ptr() as *const u8, hi-lo)
+        };
+        __crate::internal::simd::mul_acc8_with(kernel, p, b, basis);
+    } else {
+        for i in lo..hi {
+            p[i] += __gf::from_lossy(b[i]) * c;
+        }
+    }
+}
 
 /// Format blocks as a RAID array.
 ///
 /// This writes the parity data to the provided parity blocks based on the
-/// provided data blocks.
+/// provided data blocks. Data blocks may be shorter than the parity blocks,
+/// in which case the missing symbols are treated as zero.
 ///
 /// ``` rust
 /// # use ::gf256::raid::*;
@@ -78,6 +347,7 @@ impl fmt::Display for Error {
 /// assert_eq!(&parity3,  b"\x9a\x6b\x23\xe7");
 /// ```
 ///
+#[cfg(__if(__parity <= 3))]
 pub fn format<B: AsRef<[__u]>>(
     blocks: &[B],
     #[cfg(__if(__parity >= 1))] p: &mut [__u],
@@ -85,10 +355,11 @@ pub fn format<B: AsRef<[__u]>>(
     #[cfg(__if(__parity >= 3))] r: &mut [__u],
 ) {
     assert!(blocks.len() >= 1);
-    #[cfg(__if(__parity >= 2))] { assert!(blocks.len() <= usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX)); }
+    #[cfg(__if(__parity >= 2))] { assert!(blocks.len() <= max_blocks()); }
 
-    let len = blocks[0].as_ref().len();
-    assert!(blocks.iter().all(|b| b.as_ref().len() == len));
+    // blocks may be shorter than the parity blocks, missing symbols are
+    // treated as zero
+    let len = blocks.iter().map(|b| b.as_ref().len()).max().unwrap();
     #[cfg(__if(__parity >= 1))] { assert!(p.len() == len); }
     #[cfg(__if(__parity >= 1))] let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
     #[cfg(__if(__parity >= 2))] { assert!(q.len() == len); }
@@ -105,11 +376,9 @@ pub fn format<B: AsRef<[__u]>>(
     for (j, b) in blocks.iter().enumerate() {
         #[cfg(__if(__parity >= 2))] let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
         #[cfg(__if(__parity >= 3))] let h = g*g;
-        for i in 0..len {
-            #[cfg(__if(__parity >= 1))] { p[i] += __gf::from_lossy(b.as_ref()[i]); }
-            #[cfg(__if(__parity >= 2))] { q[i] += __gf::from_lossy(b.as_ref()[i]) * g; }
-            #[cfg(__if(__parity >= 3))] { r[i] += __gf::from_lossy(b.as_ref()[i]) * h; }
-        }
+        #[cfg(__if(__parity >= 1))] { mul_acc(p, b.as_ref(), __gf::new(1), 0, len); }
+        #[cfg(__if(__parity >= 2))] { mul_acc(q, b.as_ref(), g, 0, len); }
+        #[cfg(__if(__parity >= 3))] { mul_acc(r, b.as_ref(), h, 0, len); }
     }
 }
 
@@ -119,6 +388,9 @@ pub fn format<B: AsRef<[__u]>>(
 /// blocks themselves. `bad_blocks` must be an array of indices indicating
 /// which blocks are bad.
 ///
+/// Returns an [`Error`] instead of panicking if there are too many bad
+/// blocks, a bad block is out of range, or the blocks' lengths don't match.
+///
 /// ``` rust
 /// # use ::gf256::raid::*;
 /// let mut data = b"Hellxxxxxxxx".to_vec();
@@ -132,6 +404,7 @@ pub fn format<B: AsRef<[__u]>>(
 /// assert_eq!(&data, b"Hello World!");
 /// ```
 ///
+#[cfg(__if(__parity <= 3))]
 pub fn repair<B: AsMut<[__u]>>(
     blocks: &mut [B],
     #[cfg(__if(__parity >= 1))] p: &mut [__u],
@@ -139,16 +412,73 @@ pub fn repair<B: AsMut<[__u]>>(
     #[cfg(__if(__parity >= 3))] r: &mut [__u],
     bad_blocks: &[usize]
 ) -> Result<(), Error> {
-    let len = blocks[0].as_mut().len();
+    check_blocks(blocks.len(), bad_blocks)?;
+    cfg_if! {
+        if #[cfg(__if(__parity >= 1))] {
+            let len = p.len();
+            if blocks.iter_mut().any(|b| b.as_mut().len() > len) {
+                return Err(Error::LengthMismatch);
+            }
+        } else {
+            let len = blocks.iter_mut().map(|b| b.as_mut().len()).max().unwrap_or(0);
+        }
+    }
+    #[cfg(__if(__parity >= 2))] { if q.len() != len { return Err(Error::LengthMismatch); } }
+    #[cfg(__if(__parity >= 3))] { if r.len() != len { return Err(Error::LengthMismatch); } }
     #[cfg(__if(__parity >= 1))] let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
     #[cfg(__if(__parity >= 2))] let q = unsafe { __gf::slice_from_slice_mut_unchecked(q) };
     #[cfg(__if(__parity >= 3))] let r = unsafe { __gf::slice_from_slice_mut_unchecked(r) };
 
-    if bad_blocks.len() > __parity {
-        // can't repair
-        return Err(Error::TooManyBadBlocks);
+    // blocks may be shorter than the parity blocks, in which case the
+    // missing symbols are zero, so we repair in segments where the set
+    // of bad blocks that need repair is constant
+    let n = blocks.len();
+    let mut lo = 0;
+    while lo < len {
+        let hi = bad_blocks.iter()
+            .filter(|b| **b < n)
+            .map(|b| blocks[*b].as_mut().len())
+            .filter(|l| *l > lo)
+            .fold(len, min);
+
+        let mut segment_array = [0usize; 3];
+        let mut segment_count = 0;
+        for b in bad_blocks {
+            if *b >= blocks.len() || blocks[*b].as_mut().len() > lo {
+                segment_array[segment_count] = *b;
+                segment_count += 1;
+            }
+        }
+
+        let segment = &segment_array[..segment_count];
+        cfg_if! {
+            if #[cfg(__if(__parity >= 3))] {
+                repair_range(blocks, p, q, r, segment, lo, hi);
+            } else if #[cfg(__if(__parity >= 2))] {
+                repair_range(blocks, p, q, segment, lo, hi);
+            } else if #[cfg(__if(__parity >= 1))] {
+                repair_range(blocks, p, segment, lo, hi);
+            } else {
+                repair_range(blocks, segment, lo, hi);
+            }
+        }
+        lo = hi;
     }
 
+    Ok(())
+}
+
+/// Repair the symbols in lo..hi, assuming bad_blocks <= parity blocks
+#[cfg(__if(__parity <= 3))]
+fn repair_range<B: AsMut<[__u]>>(
+    blocks: &mut [B],
+    #[cfg(__if(__parity >= 1))] p: &mut [__gf],
+    #[cfg(__if(__parity >= 2))] q: &mut [__gf],
+    #[cfg(__if(__parity >= 3))] r: &mut [__gf],
+    bad_blocks: &[usize],
+    lo: usize,
+    hi: usize,
+) {
     // sort the data blocks without alloc, this is only so we can split
     // the mut blocks array safely
     let mut bad_blocks_array = [
@@ -168,14 +498,12 @@ pub fn repair<B: AsMut<[__u]>>(
             let (d, after) = after.split_first_mut().unwrap();
             let d = unsafe { __gf::slice_from_slice_mut_unchecked(d.as_mut()) };
 
-            for i in 0..len {
+            for i in lo..hi {
                 d[i] = p[i];
             }
 
             for b in before.iter_mut().chain(after.iter_mut()) {
-                for i in 0..len {
-                    d[i] -= __gf::from_lossy(b.as_mut()[i]);
-                }
+                mul_acc(d, b.as_mut(), __gf::new(1), lo, hi);
             }
 
             bad_blocks = &mut bad_blocks[1..];
@@ -191,7 +519,7 @@ pub fn repair<B: AsMut<[__u]>>(
             let (d, after) = after.split_first_mut().unwrap();
             let d = unsafe { __gf::slice_from_slice_mut_unchecked(d.as_mut()) };
 
-            for i in 0..len {
+            for i in lo..hi {
                 d[i] = q[i];
             }
 
@@ -199,13 +527,11 @@ pub fn repair<B: AsMut<[__u]>>(
                 .chain((bad_blocks[0]+1..).zip(after.iter_mut()))
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
-                for i in 0..len {
-                    d[i] -= __gf::from_lossy(b.as_mut()[i]) * g;
-                }
+                mul_acc(d, b.as_mut(), g, lo, hi);
             }
 
             let g = __gf::GENERATOR.pow(__u::try_from(bad_blocks[0]).unwrap());
-            for i in 0..len {
+            for i in lo..hi {
                 d[i] /= g;
             }
 
@@ -229,7 +555,7 @@ pub fn repair<B: AsMut<[__u]>>(
             // q - Σ di*g^i
             //   i!=x,y
             //
-            for i in 0..len {
+            for i in lo..hi {
                 dx[i] = p[i];
                 dy[i] = q[i];
             }
@@ -239,10 +565,8 @@ pub fn repair<B: AsMut<[__u]>>(
                 .chain((bad_blocks[1]+1..).zip(after.iter_mut()))
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
-                for i in 0..len {
-                    dx[i] -= __gf::from_lossy(b.as_mut()[i]);
-                    dy[i] -= __gf::from_lossy(b.as_mut()[i]) * g;
-                }
+                mul_acc(dx, b.as_mut(), __gf::new(1), lo, hi);
+                mul_acc(dy, b.as_mut(), g, lo, hi);
             }
 
             // find final dx/dy
@@ -257,7 +581,7 @@ pub fn repair<B: AsMut<[__u]>>(
             //
             let gx = __gf::GENERATOR.pow(__u::try_from(bad_blocks[0]).unwrap());
             let gy = __gf::GENERATOR.pow(__u::try_from(bad_blocks[1]).unwrap());
-            for i in 0..len {
+            for i in lo..hi {
                 let pdelta = dx[i];
                 let qdelta = dy[i];
                 dx[i] = (qdelta - pdelta*gy) / (gx - gy);
@@ -277,7 +601,7 @@ pub fn repair<B: AsMut<[__u]>>(
             let (d, after) = after.split_first_mut().unwrap();
             let d = unsafe { __gf::slice_from_slice_mut_unchecked(d.as_mut()) };
 
-            for i in 0..len {
+            for i in lo..hi {
                 d[i] = r[i];
             }
 
@@ -286,14 +610,12 @@ pub fn repair<B: AsMut<[__u]>>(
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
                 let h = g*g;
-                for i in 0..len {
-                    d[i] -= __gf::from_lossy(b.as_mut()[i]) * h;
-                }
+                mul_acc(d, b.as_mut(), h, lo, hi);
             }
 
             let g = __gf::GENERATOR.pow(__u::try_from(bad_blocks[0]).unwrap());
             let h = g*g;
-            for i in 0..len {
+            for i in lo..hi {
                 d[i] /= h;
             }
 
@@ -317,7 +639,7 @@ pub fn repair<B: AsMut<[__u]>>(
             // r - Σ di*h^i
             //   i!=x,y
             //
-            for i in 0..len {
+            for i in lo..hi {
                 dx[i] = q[i];
                 dy[i] = r[i];
             }
@@ -328,10 +650,8 @@ pub fn repair<B: AsMut<[__u]>>(
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
                 let h = g*g;
-                for i in 0..len {
-                    dx[i] -= __gf::from_lossy(b.as_mut()[i]) * g;
-                    dy[i] -= __gf::from_lossy(b.as_mut()[i]) * h;
-                }
+                mul_acc(dx, b.as_mut(), g, lo, hi);
+                mul_acc(dy, b.as_mut(), h, lo, hi);
             }
 
             // find final dx/dy
@@ -348,7 +668,7 @@ pub fn repair<B: AsMut<[__u]>>(
             //
             let gx = __gf::GENERATOR.pow(__u::try_from(bad_blocks[0]).unwrap());
             let gy = __gf::GENERATOR.pow(__u::try_from(bad_blocks[1]).unwrap());
-            for i in 0..len {
+            for i in lo..hi {
                 let qdelta = dx[i];
                 let rdelta = dy[i];
                 dx[i] = (rdelta - qdelta*gy) / (gx*(gx - gy));
@@ -375,7 +695,7 @@ pub fn repair<B: AsMut<[__u]>>(
             // r - Σ di*h^i
             //   i!=x,y
             //
-            for i in 0..len {
+            for i in lo..hi {
                 dx[i] = p[i];
                 dy[i] = r[i];
             }
@@ -386,10 +706,8 @@ pub fn repair<B: AsMut<[__u]>>(
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
                 let h = g*g;
-                for i in 0..len {
-                    dx[i] -= __gf::from_lossy(b.as_mut()[i]);
-                    dy[i] -= __gf::from_lossy(b.as_mut()[i]) * h;
-                }
+                mul_acc(dx, b.as_mut(), __gf::new(1), lo, hi);
+                mul_acc(dy, b.as_mut(), h, lo, hi);
             }
 
             // find final dx/dy
@@ -406,7 +724,7 @@ pub fn repair<B: AsMut<[__u]>>(
             let hx = gx*gx;
             let gy = __gf::GENERATOR.pow(__u::try_from(bad_blocks[1]).unwrap());
             let hy = gy*gy;
-            for i in 0..len {
+            for i in lo..hi {
                 let pdelta = dx[i];
                 let rdelta = dy[i];
                 dx[i] = (rdelta - pdelta*hy) / (hx - hy);
@@ -437,7 +755,7 @@ pub fn repair<B: AsMut<[__u]>>(
             // r - Σ di*h^i
             //  i!=x,y,z
             //
-            for i in 0..len {
+            for i in lo..hi {
                 dx[i] = p[i];
                 dy[i] = q[i];
                 dz[i] = r[i];
@@ -450,11 +768,9 @@ pub fn repair<B: AsMut<[__u]>>(
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
                 let h = g*g;
-                for i in 0..len {
-                    dx[i] -= __gf::from_lossy(b.as_mut()[i]);
-                    dy[i] -= __gf::from_lossy(b.as_mut()[i]) * g;
-                    dz[i] -= __gf::from_lossy(b.as_mut()[i]) * h;
-                }
+                mul_acc(dx, b.as_mut(), __gf::new(1), lo, hi);
+                mul_acc(dy, b.as_mut(), g, lo, hi);
+                mul_acc(dz, b.as_mut(), h, lo, hi);
             }
 
             // find final dx/dy/dz
@@ -475,7 +791,7 @@ pub fn repair<B: AsMut<[__u]>>(
             let gx = __gf::GENERATOR.pow(__u::try_from(bad_blocks[0]).unwrap());
             let gy = __gf::GENERATOR.pow(__u::try_from(bad_blocks[1]).unwrap());
             let gz = __gf::GENERATOR.pow(__u::try_from(bad_blocks[2]).unwrap());
-            for i in 0..len {
+            for i in lo..hi {
                 let pdelta = dx[i];
                 let qdelta = dy[i];
                 let rdelta = dz[i];
@@ -491,14 +807,12 @@ pub fn repair<B: AsMut<[__u]>>(
     #[cfg(__if(__parity >= 1))] {
         if bad_blocks.iter().any(|x| *x == blocks.len()) {
             // regenerate p
-            for i in 0..len {
+            for i in lo..hi {
                 p[i] = __gf::new(0);
             }
 
             for b in blocks.iter_mut() {
-                for i in 0..len {
-                    p[i] += __gf::from_lossy(b.as_mut()[i]);
-                }
+                mul_acc(p, b.as_mut(), __gf::new(1), lo, hi);
             }
         }
     }
@@ -506,15 +820,13 @@ pub fn repair<B: AsMut<[__u]>>(
     #[cfg(__if(__parity >= 2))] {
         if bad_blocks.iter().any(|x| *x == blocks.len()+1) {
             // regenerate q
-            for i in 0..len {
+            for i in lo..hi {
                 q[i] = __gf::new(0);
             }
 
             for (j, b) in blocks.iter_mut().enumerate() {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
-                for i in 0..len {
-                    q[i] += __gf::from_lossy(b.as_mut()[i]) * g;
-                }
+                mul_acc(q, b.as_mut(), g, lo, hi);
             }
         }
     }
@@ -522,21 +834,111 @@ pub fn repair<B: AsMut<[__u]>>(
     #[cfg(__if(__parity >= 3))] {
         if bad_blocks.iter().any(|x| *x == blocks.len()+2) {
             // regenerate r
-            for i in 0..len {
+            for i in lo..hi {
                 r[i] = __gf::new(0);
             }
 
             for (j, b) in blocks.iter_mut().enumerate() {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
                 let h = g.pow(2);
-                for i in 0..len {
-                    r[i] += __gf::from_lossy(b.as_mut()[i]) * h;
-                }
+                mul_acc(r, b.as_mut(), h, lo, hi);
             }
         }
     }
+}
 
-    Ok(())
+/// Scrub a RAID array, checking that the parity blocks match the data
+/// blocks.
+///
+/// If there are at least 2 parity blocks, this also tries to locate a single
+/// block that explains any mismatches. Unlike other RAID operations, this
+/// can find silent corruption without any external mechanism to detect
+/// block failures. The resulting bad block can then be passed to [`repair`]:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let mut data = b"Hello World!".to_vec();
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// raid6::format(&data.chunks(4).collect::<Vec<_>>(), &mut parity1, &mut parity2);
+///
+/// // oh no, bit rot
+/// data[6] ^= 0x20;
+///
+/// // scrub
+/// let report = raid6::scrub(&data.chunks(4).collect::<Vec<_>>(), &parity1, &parity2);
+/// assert_eq!(report.mismatches, 1);
+/// assert_eq!(report.bad_block, Some(1));
+///
+/// // repair
+/// let mut datas = data.chunks_mut(4).collect::<Vec<_>>();
+/// raid6::repair(&mut datas, &mut parity1, &mut parity2, &[1]).unwrap();
+/// assert_eq!(&data, b"Hello World!");
+/// ```
+///
+/// Note that with 2 parity blocks, multiple bad blocks can be mistaken for
+/// a single bad block. With 3 or more parity blocks the extra parity is used
+/// to check the result.
+///
+#[cfg(__if(__parity <= 3))]
+pub fn scrub<B: AsRef<[__u]>>(
+    blocks: &[B],
+    #[cfg(__if(__parity >= 1))] p: &[__u],
+    #[cfg(__if(__parity >= 2))] q: &[__u],
+    #[cfg(__if(__parity >= 3))] r: &[__u],
+) -> ScrubReport {
+    // blocks may be shorter than the parity blocks, missing symbols are
+    // treated as zero
+    let len = blocks.iter().map(|b| b.as_ref().len()).max().unwrap_or(0);
+    #[cfg(__if(__parity >= 1))] { assert!(p.len() == len); }
+    #[cfg(__if(__parity >= 2))] { assert!(q.len() == len); }
+    #[cfg(__if(__parity >= 3))] { assert!(r.len() == len); }
+
+    let mut report = ScrubReport {
+        mismatches: 0,
+        bad_block: None,
+    };
+    let mut locatable = true;
+    for i in 0..len {
+        // find syndromes
+        let mut syndromes = [__gf::new(0); __parity];
+        #[cfg(__if(__parity >= 1))] { syndromes[0] = __gf::from_lossy(p[i]); }
+        #[cfg(__if(__parity >= 2))] { syndromes[1] = __gf::from_lossy(q[i]); }
+        #[cfg(__if(__parity >= 3))] { syndromes[2] = __gf::from_lossy(r[i]); }
+
+        #[cfg(__if(__parity >= 2))] let mut g = __gf::new(1);
+        for b in blocks.iter() {
+            let d = b.as_ref().get(i).copied().map(__gf::from_lossy).unwrap_or_default();
+            #[cfg(__if(__parity >= 1))] { syndromes[0] -= d; }
+            #[cfg(__if(__parity >= 2))] { syndromes[1] -= d * g; }
+            #[cfg(__if(__parity >= 3))] { syndromes[2] -= d * (g*g); }
+            #[cfg(__if(__parity >= 2))] { g *= __gf::GENERATOR; }
+        }
+
+        if syndromes.iter().all(|s| *s == __gf::new(0)) {
+            continue;
+        }
+        report.mismatches += 1;
+
+        // can we locate the bad block?
+        cfg_if! {
+            if #[cfg(__if(__parity >= 2))] {
+                let bad_block = locate(blocks.len(), &syndromes);
+            } else {
+                let bad_block = None;
+            }
+        }
+        match (report.mismatches, bad_block) {
+            (1, Some(x)) => report.bad_block = Some(x),
+            (_, Some(x)) if report.bad_block == Some(x) => {},
+            _ => locatable = false,
+        }
+    }
+
+    if !locatable {
+        report.bad_block = None;
+    }
+    report
 }
 
 /// Add a block to a RAID array.
@@ -565,6 +967,7 @@ pub fn repair<B: AsMut<[__u]>>(
 /// assert_eq!(&parity3,  b"\x98\x6b\x23\xe7");
 /// ```
 ///
+#[cfg(__if(__parity <= 3))]
 pub fn add(
     j: usize,
     new: &[__u],
@@ -579,12 +982,10 @@ pub fn add(
 
     #[cfg(__if(__parity >= 2))] let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
     #[cfg(__if(__parity >= 3))] let h = g*g;
-    for i in 0..len {
-        // calculate new parity
-        #[cfg(__if(__parity >= 1))] { p[i] += __gf::from_lossy(new[i]); }
-        #[cfg(__if(__parity >= 2))] { q[i] += __gf::from_lossy(new[i]) * g; }
-        #[cfg(__if(__parity >= 3))] { r[i] += __gf::from_lossy(new[i]) * h; }
-    }
+    // calculate new parity
+    #[cfg(__if(__parity >= 1))] { mul_acc(p, new, __gf::new(1), 0, len); }
+    #[cfg(__if(__parity >= 2))] { mul_acc(q, new, g, 0, len); }
+    #[cfg(__if(__parity >= 3))] { mul_acc(r, new, h, 0, len); }
 }
 
 /// Remove a block from a RAID array.
@@ -610,6 +1011,7 @@ pub fn add(
 /// assert_eq!(&parity3,  b"\xd2\x0e\x4f\x8b");
 /// ```
 ///
+#[cfg(__if(__parity <= 3))]
 pub fn remove(
     j: usize,
     old: &[__u],
@@ -624,12 +1026,10 @@ pub fn remove(
 
     #[cfg(__if(__parity >= 2))] let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
     #[cfg(__if(__parity >= 3))] let h = g*g;
-    for i in 0..len {
-        // calculate new parity
-        #[cfg(__if(__parity >= 1))] { p[i] -= __gf::from_lossy(old[i]); }
-        #[cfg(__if(__parity >= 2))] { q[i] -= __gf::from_lossy(old[i]) * g; }
-        #[cfg(__if(__parity >= 3))] { r[i] -= __gf::from_lossy(old[i]) * h; }
-    }
+    // calculate new parity
+    #[cfg(__if(__parity >= 1))] { mul_acc(p, old, __gf::new(1), 0, len); }
+    #[cfg(__if(__parity >= 2))] { mul_acc(q, old, g, 0, len); }
+    #[cfg(__if(__parity >= 3))] { mul_acc(r, old, h, 0, len); }
 }
 
 /// Update a block in a RAID array.
@@ -655,6 +1055,7 @@ pub fn remove(
 /// assert_eq!(&parity3,  b"\x98\x6b\x23\xe7");
 /// ```
 ///
+#[cfg(__if(__parity <= 3))]
 pub fn update(
     j: usize,
     old: &[__u],
@@ -679,3 +1080,2007 @@ pub fn update(
     }
 }
 
+
+/// Update part of a block in a RAID array using only the difference between
+/// the old and new data.
+///
+/// `delta` is the difference, `new - old`, which in a binary-extension field
+/// is just `old ^ new`, and `off` is the offset of `delta` in the block. Only
+/// the affected range of each parity block is modified, which makes small
+/// writes cheap:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let mut data = b"Hello World!".to_vec();
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// let mut parity3 = vec![0u8; 4];
+/// raid7::format(&data.chunks(4).collect::<Vec<_>>(), &mut parity1, &mut parity2, &mut parity3);
+///
+/// // a small write, "W" => "w" in block 1
+/// let delta = [b'W' ^ b'w'];
+/// raid7::update_delta(1, 2, &delta, &mut parity1, &mut parity2, &mut parity3);
+/// data[6] = b'w';
+///
+/// // parity matches the new data
+/// let mut new_parity1 = vec![0u8; 4];
+/// let mut new_parity2 = vec![0u8; 4];
+/// let mut new_parity3 = vec![0u8; 4];
+/// raid7::format(&data.chunks(4).collect::<Vec<_>>(), &mut new_parity1, &mut new_parity2, &mut new_parity3);
+/// assert_eq!(parity1, new_parity1);
+/// assert_eq!(parity2, new_parity2);
+/// assert_eq!(parity3, new_parity3);
+/// ```
+///
+#[cfg(__if(__parity <= 3))]
+pub fn update_delta(
+    j: usize,
+    off: usize,
+    delta: &[__u],
+    #[cfg(__if(__parity >= 1))] p: &mut [__u],
+    #[cfg(__if(__parity >= 2))] q: &mut [__u],
+    #[cfg(__if(__parity >= 3))] r: &mut [__u],
+) {
+    let len = delta.len();
+    #[cfg(__if(__parity >= 1))] let p = unsafe { __gf::slice_from_slice_mut_unchecked(&mut p[off..off+len]) };
+    #[cfg(__if(__parity >= 2))] let q = unsafe { __gf::slice_from_slice_mut_unchecked(&mut q[off..off+len]) };
+    #[cfg(__if(__parity >= 3))] let r = unsafe { __gf::slice_from_slice_mut_unchecked(&mut r[off..off+len]) };
+
+    #[cfg(__if(__parity >= 2))] let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
+    #[cfg(__if(__parity >= 3))] let h = g*g;
+    // calculate new parity
+    #[cfg(__if(__parity >= 1))] { mul_acc(p, delta, __gf::new(1), 0, len); }
+    #[cfg(__if(__parity >= 2))] { mul_acc(q, delta, g, 0, len); }
+    #[cfg(__if(__parity >= 3))] { mul_acc(r, delta, h, 0, len); }
+}
+
+// N-parity implementations, for more than 3 parity blocks
+//
+// Beyond 3 parity blocks, g^(j*k) no longer guarantees that any subset of
+// equations is linearly independent, so instead parity block k is the sum
+// of each data block j multiplied by the Cauchy matrix entry 1/(x_k + y_j),
+// scaled so parity block 0 is still a plain xor. Every square submatrix of
+// a Cauchy matrix is non-singular, so any combination of bad blocks can be
+// repaired. Since we can no longer write out the solutions by hand, we
+// instead solve the resulting system of equations with Gauss-Jordan
+// elimination.
+//
+
+/// Find the coefficient of data block j in parity block k
+///
+/// With x_k = k and y_j = parity+j, this is y_j/(x_k + y_j), note this
+/// requires j < max_blocks(). With 3 or fewer parity blocks this is the
+/// familiar g^(j*k).
+///
+fn coefficient(j: usize, k: usize) -> __gf {
+    cfg_if! {
+        if #[cfg(__if(__parity >= 4))] {
+            let x = __gf::new(__u::try_from(k).unwrap());
+            let y = __gf::new(__u::try_from(__parity+j).unwrap());
+            y / (x + y)
+        } else {
+            __gf::GENERATOR
+                .pow(__u::try_from(j).unwrap())
+                .pow(__u::try_from(k).unwrap())
+        }
+    }
+}
+
+/// Invert the top-left m×m corner of a matrix using Gauss-Jordan
+/// elimination, the matrix must be non-singular
+fn invert(
+    mut a: [[__gf; __parity]; __parity],
+    m: usize
+) -> [[__gf; __parity]; __parity] {
+    let mut inv = [[__gf::new(0); __parity]; __parity];
+    for (i, row) in inv.iter_mut().enumerate() {
+        row[i] = __gf::new(1);
+    }
+
+    for c in 0..m {
+        // find a pivot
+        let r = (c..m).find(|r| a[*r][c] != __gf::new(0)).unwrap();
+        a.swap(c, r);
+        inv.swap(c, r);
+
+        // normalize
+        let f = a[c][c];
+        for u in 0..m {
+            a[c][u] /= f;
+            inv[c][u] /= f;
+        }
+
+        // eliminate
+        let (a_c, inv_c) = (a[c], inv[c]);
+        for r in 0..m {
+            let f = a[r][c];
+            if r != c && f != __gf::new(0) {
+                for u in 0..m {
+                    a[r][u] -= f*a_c[u];
+                    inv[r][u] -= f*inv_c[u];
+                }
+            }
+        }
+    }
+
+    inv
+}
+
+/// Format blocks as a RAID array.
+///
+/// This writes the parity data to the provided parity blocks based on the
+/// provided data blocks. `parity` must contain exactly one block for each
+/// parity block in the array. Data blocks may be shorter than the parity
+/// blocks, in which case the missing symbols are treated as zero.
+///
+#[cfg(__if(__parity >= 4))]
+pub fn format<B: AsRef<[__u]>, P: AsMut<[__u]>>(
+    blocks: &[B],
+    parity: &mut [P],
+) {
+    assert!(blocks.len() >= 1);
+    assert!(blocks.len() <= max_blocks());
+    assert!(parity.len() == __parity);
+
+    // blocks may be shorter than the parity blocks, missing symbols are
+    // treated as zero
+    let len = blocks.iter().map(|b| b.as_ref().len()).max().unwrap();
+    assert!(parity.iter_mut().all(|p| p.as_mut().len() == len));
+
+    for (k, p) in parity.iter_mut().enumerate() {
+        let p = unsafe { __gf::slice_from_slice_mut_unchecked(p.as_mut()) };
+        for i in 0..len {
+            p[i] = __gf::new(0);
+        }
+
+        for (j, b) in blocks.iter().enumerate() {
+            let g = coefficient(j, k);
+            mul_acc(p, b.as_ref(), g, 0, len);
+        }
+    }
+}
+
+/// Repair up to `n` bad blocks.
+///
+/// Where `n` <= the number of parity blocks. This can include the parity
+/// blocks themselves, which are indexed after the data blocks. `bad_blocks`
+/// must be an array of indices indicating which blocks are bad.
+///
+/// Returns an [`Error`] instead of panicking if there are too many bad
+/// blocks, a bad block is out of range, or the blocks' lengths don't match.
+///
+/// Any combination of up to `n` bad data and parity blocks can be repaired.
+///
+#[cfg(__if(__parity >= 4))]
+pub fn repair<B: AsMut<[__u]>, P: AsMut<[__u]>>(
+    blocks: &mut [B],
+    parity: &mut [P],
+    bad_blocks: &[usize]
+) -> Result<(), Error> {
+    check_blocks(blocks.len(), bad_blocks)?;
+    if parity.len() != __parity {
+        return Err(Error::LengthMismatch);
+    }
+    let len = parity[0].as_mut().len();
+    if !parity.iter_mut().all(|p| p.as_mut().len() == len)
+        || blocks.iter_mut().any(|b| b.as_mut().len() > len)
+    {
+        return Err(Error::LengthMismatch);
+    }
+
+    // separate bad data blocks from bad parity blocks
+    let mut bad_datas_array = [0usize; __parity];
+    let mut bad_data_count = 0;
+    let mut bad_parity = [false; __parity];
+    for &b in bad_blocks {
+        if b < blocks.len() {
+            if !bad_datas_array[..bad_data_count].contains(&b) {
+                bad_datas_array[bad_data_count] = b;
+                bad_data_count += 1;
+            }
+        } else if let Some(bad) = bad_parity.get_mut(b-blocks.len()) {
+            *bad = true;
+        }
+    }
+
+    // blocks may be shorter than the parity blocks, in which case the
+    // missing symbols are zero, so we repair in segments where the set
+    // of bad blocks that need repair is constant
+    let mut lo = 0;
+    while lo < len {
+        let hi = bad_datas_array[..bad_data_count].iter()
+            .map(|b| blocks[*b].as_mut().len())
+            .filter(|l| *l > lo)
+            .fold(len, min);
+
+        let mut segment_array = [0usize; __parity];
+        let mut segment_count = 0;
+        for b in &bad_datas_array[..bad_data_count] {
+            if blocks[*b].as_mut().len() > lo {
+                segment_array[segment_count] = *b;
+                segment_count += 1;
+            }
+        }
+
+        solve_range(
+            Kernel::native(),
+            blocks,
+            parity,
+            &segment_array[..segment_count],
+            &bad_parity,
+            lo,
+            hi,
+        )?;
+        lo = hi;
+    }
+
+    Ok(())
+}
+
+/// Repair the symbols in lo..hi with any number of parity blocks, assuming
+/// bad blocks <= parity blocks
+fn solve_range<B: AsMut<[__u]>, P: AsMut<[__u]>>(
+    kernel: Kernel,
+    blocks: &mut [B],
+    parity: &mut [P],
+    bad_datas: &[usize],
+    bad_parity: &[bool; __parity],
+    lo: usize,
+    hi: usize,
+) -> Result<(), Error> {
+    let m = bad_datas.len();
+
+    if m > 0 {
+        // find m linearly independent equations from our good parity blocks
+        let rows = match equations(bad_datas, bad_parity) {
+            Some(rows) => rows,
+            // can't repair
+            None => return Err(Error::TooManyBadBlocks),
+        };
+
+        // invert our system of equations
+        let mut matrix = [[__gf::new(0); __parity]; __parity];
+        for (s, k) in rows[..m].iter().enumerate() {
+            for (u, x) in bad_datas.iter().enumerate() {
+                matrix[s][u] = coefficient(*x, *k);
+            }
+        }
+        let inv = invert(matrix, m);
+
+        // find intermediate values, storing them in our bad blocks
+        //
+        // p_k - Σ di*c(i,k)
+        //     i!=bad
+        //
+        for (s, k) in rows[..m].iter().enumerate() {
+            let (before, after) = blocks.split_at_mut(bad_datas[s]);
+            let (d, after) = after.split_first_mut().unwrap();
+            let d = unsafe { __gf::slice_from_slice_mut_unchecked(d.as_mut()) };
+            let p = unsafe { __gf::slice_from_slice_mut_unchecked(parity[*k].as_mut()) };
+
+            for i in lo..hi {
+                d[i] = p[i];
+            }
+
+            for (j, b) in before.iter_mut().enumerate()
+                .chain((bad_datas[s]+1..).zip(after.iter_mut()))
+            {
+                if bad_datas.contains(&j) {
+                    continue;
+                }
+
+                let g = coefficient(j, *k);
+                mul_acc_with(kernel, d, b.as_mut(), g, lo, hi);
+            }
+        }
+
+        // find final values by multiplying by the inverse
+        for i in lo..hi {
+            let mut deltas = [__gf::new(0); __parity];
+            for (u, x) in bad_datas.iter().enumerate() {
+                deltas[u] = __gf::from_lossy(blocks[*x].as_mut()[i]);
+            }
+
+            for (s, x) in bad_datas.iter().enumerate() {
+                let mut d = __gf::new(0);
+                for u in 0..m {
+                    d += inv[s][u] * deltas[u];
+                }
+                blocks[*x].as_mut()[i] = __u::from(d);
+            }
+        }
+    }
+
+    for (k, p) in parity.iter_mut().enumerate() {
+        if bad_parity[k] {
+            // regenerate parity block k
+            let p = unsafe { __gf::slice_from_slice_mut_unchecked(p.as_mut()) };
+            for i in lo..hi {
+                p[i] = __gf::new(0);
+            }
+
+            for (j, b) in blocks.iter_mut().enumerate() {
+                let g = coefficient(j, k);
+                mul_acc_with(kernel, p, b.as_mut(), g, lo, hi);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Scrub a RAID array, checking that the parity blocks match the data
+/// blocks.
+///
+/// This also tries to locate a single block that explains any mismatches,
+/// using the extra parity blocks to check the result. The resulting bad
+/// block can then be passed to [`repair`].
+///
+#[cfg(__if(__parity >= 4))]
+pub fn scrub<B: AsRef<[__u]>, P: AsRef<[__u]>>(
+    blocks: &[B],
+    parity: &[P],
+) -> ScrubReport {
+    assert!(parity.len() == __parity);
+    // blocks may be shorter than the parity blocks, missing symbols are
+    // treated as zero
+    let len = blocks.iter().map(|b| b.as_ref().len()).max().unwrap_or(0);
+    assert!(parity.iter().all(|p| p.as_ref().len() == len));
+
+    let mut report = ScrubReport {
+        mismatches: 0,
+        bad_block: None,
+    };
+    let mut locatable = true;
+    for i in 0..len {
+        // find syndromes
+        let mut syndromes = [__gf::new(0); __parity];
+        for (s, p) in syndromes.iter_mut().zip(parity) {
+            *s = __gf::from_lossy(p.as_ref()[i]);
+        }
+
+        for (j, b) in blocks.iter().enumerate() {
+            let d = b.as_ref().get(i).copied().map(__gf::from_lossy).unwrap_or_default();
+            for k in 0..__parity {
+                syndromes[k] -= d * coefficient(j, k);
+            }
+        }
+
+        if syndromes.iter().all(|s| *s == __gf::new(0)) {
+            continue;
+        }
+        report.mismatches += 1;
+
+        // can we locate the bad block?
+        match (report.mismatches, locate(blocks.len(), &syndromes)) {
+            (1, Some(x)) => report.bad_block = Some(x),
+            (_, Some(x)) if report.bad_block == Some(x) => {},
+            _ => locatable = false,
+        }
+    }
+
+    if !locatable {
+        report.bad_block = None;
+    }
+    report
+}
+
+/// Add a block to a RAID array.
+///
+/// Note the block index must be unique in the array, otherwise the array will
+/// become corrupted. This does not update other block indices.
+///
+#[cfg(__if(__parity >= 4))]
+pub fn add<P: AsMut<[__u]>>(
+    j: usize,
+    new: &[__u],
+    parity: &mut [P],
+) {
+    let len = new.len();
+    assert!(parity.len() == __parity);
+
+    for (k, p) in parity.iter_mut().enumerate() {
+        let p = unsafe { __gf::slice_from_slice_mut_unchecked(p.as_mut()) };
+        let g = coefficient(j, k);
+        // calculate new parity
+        mul_acc(p, new, g, 0, len);
+    }
+}
+
+/// Remove a block from a RAID array.
+///
+/// Note the block index must already exist in the array, otherwise the
+/// array will become corrupted. This does not update other block indices.
+///
+#[cfg(__if(__parity >= 4))]
+pub fn remove<P: AsMut<[__u]>>(
+    j: usize,
+    old: &[__u],
+    parity: &mut [P],
+) {
+    let len = old.len();
+    assert!(parity.len() == __parity);
+
+    for (k, p) in parity.iter_mut().enumerate() {
+        let p = unsafe { __gf::slice_from_slice_mut_unchecked(p.as_mut()) };
+        let g = coefficient(j, k);
+        // calculate new parity
+        mul_acc(p, old, g, 0, len);
+    }
+}
+
+/// Update a block in a RAID array.
+#[cfg(__if(__parity >= 4))]
+pub fn update<P: AsMut<[__u]>>(
+    j: usize,
+    old: &[__u],
+    new: &[__u],
+    parity: &mut [P],
+) {
+    let len = old.len();
+    assert!(new.len() == old.len());
+    assert!(parity.len() == __parity);
+
+    for (k, p) in parity.iter_mut().enumerate() {
+        let p = unsafe { __gf::slice_from_slice_mut_unchecked(p.as_mut()) };
+        let g = coefficient(j, k);
+        for i in 0..len {
+            // calculate new parity
+            p[i] += (__gf::from_lossy(new[i])-__gf::from_lossy(old[i])) * g;
+        }
+    }
+}
+
+/// Update part of a block in a RAID array using only the difference between
+/// the old and new data.
+///
+/// `delta` is the difference, `new - old`, which in a binary-extension field
+/// is just `old ^ new`, and `off` is the offset of `delta` in the block. Only
+/// the affected range of each parity block is modified.
+///
+#[cfg(__if(__parity >= 4))]
+pub fn update_delta<P: AsMut<[__u]>>(
+    j: usize,
+    off: usize,
+    delta: &[__u],
+    parity: &mut [P],
+) {
+    let len = delta.len();
+    assert!(parity.len() == __parity);
+
+    for (k, p) in parity.iter_mut().enumerate() {
+        let p = unsafe { __gf::slice_from_slice_mut_unchecked(&mut p.as_mut()[off..off+len]) };
+        let g = coefficient(j, k);
+        // calculate new parity
+        mul_acc(p, delta, g, 0, len);
+    }
+}
+
+/// Find linearly independent equations, one for each bad data block, from
+/// our good parity blocks, returning the indices of the parity blocks used
+///
+/// p_k - Σ di*c(i,k) = Σ dx*c(x,k)
+///     i!=bad          x=bad
+///
+/// Note this always picks the first good parity blocks that are linearly
+/// independent, which matches the hand-written solutions used for <= 3
+/// parity blocks.
+///
+fn equations(
+    bad_datas: &[usize],
+    bad_parity: &[bool; __parity],
+) -> Option<[usize; __parity]> {
+    let m = bad_datas.len();
+    let mut echelon = [[__gf::new(0); __parity]; __parity];
+    let mut pivots = [0usize; __parity];
+    let mut rows = [0usize; __parity];
+    let mut count = 0;
+    for k in (0..__parity).filter(|k| !bad_parity[*k]) {
+        if count == m {
+            break;
+        }
+
+        let mut row = [__gf::new(0); __parity];
+        for (u, x) in bad_datas.iter().enumerate() {
+            row[u] = coefficient(*x, k);
+        }
+
+        // reduce against our existing equations
+        for s in 0..count {
+            let f = row[pivots[s]];
+            if f != __gf::new(0) {
+                for u in 0..m {
+                    row[u] -= f*echelon[s][u];
+                }
+            }
+        }
+
+        // linearly independent?
+        if let Some(c) = (0..m).find(|u| row[*u] != __gf::new(0)) {
+            let f = row[c];
+            for u in 0..m {
+                row[u] /= f;
+            }
+
+            echelon[count] = row;
+            pivots[count] = c;
+            rows[count] = k;
+            count += 1;
+        }
+    }
+
+    if count < m {
+        return None;
+    }
+
+    Some(rows)
+}
+
+/// Find how a set of bad blocks would be repaired, without touching any
+/// blocks.
+///
+/// `n` is the number of data blocks, and `bad_blocks` uses the same indices
+/// as [`repair`], with parity blocks indexed after the data blocks. This
+/// returns [`Error::TooManyBadBlocks`] if [`repair`] would fail, otherwise
+/// it returns a [`RepairPlan`] describing which parity blocks' equations are
+/// used to solve for the bad data blocks, and which parity blocks are
+/// regenerated.
+///
+/// This is useful for checking the health of an array, or for testing which
+/// failures a given configuration can survive.
+///
+/// Note if the bad data blocks are shorter than the parity blocks, [`repair`]
+/// may use fewer equations for the tail of the array, where the missing
+/// symbols are known to be zero.
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// // with 3 data blocks, block 4 is q
+/// let plan = raid6::plan(3, &[0, 4]).unwrap();
+/// assert_eq!(plan.bad_datas, 1);
+/// assert_eq!(plan.equations, [true, false]);
+/// assert_eq!(plan.regenerated, [false, true]);
+///
+/// let plan = raid6::plan(3, &[0, 3]).unwrap();
+/// assert_eq!(plan.equations, [false, true]);
+///
+/// assert_eq!(raid6::plan(3, &[0, 1, 2]), Err(raid6::Error::TooManyBadBlocks));
+/// ```
+///
+pub fn plan(n: usize, bad_blocks: &[usize]) -> Result<RepairPlan, Error> {
+    #[cfg(__if(__parity >= 2))] {
+        if n > max_blocks() {
+            return Err(Error::TooManyBlocks);
+        }
+    }
+
+    if bad_blocks.iter().any(|b| !(0..n+__parity).contains(b)) {
+        return Err(Error::BadBlockOutOfRange);
+    }
+
+    // separate bad data blocks from bad parity blocks
+    let mut bad_datas_array = [0usize; __parity];
+    let mut bad_data_count = 0;
+    let mut bad_parity = [false; __parity];
+    let mut bad_parity_count = 0;
+    for &b in bad_blocks {
+        if b < n {
+            if !bad_datas_array[..bad_data_count].contains(&b) {
+                if bad_data_count == __parity {
+                    // can't repair
+                    return Err(Error::TooManyBadBlocks);
+                }
+
+                bad_datas_array[bad_data_count] = b;
+                bad_data_count += 1;
+            }
+        } else if let Some(bad) = bad_parity.get_mut(b-n) {
+            if !*bad {
+                *bad = true;
+                bad_parity_count += 1;
+            }
+        }
+    }
+
+    if bad_data_count + bad_parity_count > __parity {
+        // can't repair
+        return Err(Error::TooManyBadBlocks);
+    }
+
+    let rows = equations(&bad_datas_array[..bad_data_count], &bad_parity)
+        .ok_or(Error::TooManyBadBlocks)?;
+
+    let mut equations = [false; __parity];
+    for k in &rows[..bad_data_count] {
+        equations[*k] = true;
+    }
+
+    Ok(RepairPlan {
+        bad_datas: bad_data_count,
+        equations,
+        regenerated: bad_parity,
+    })
+}
+
+/// Number of symbols we reconstruct at a time when reconstructing into
+/// buffers, this is kept small since the windows are allocated on the stack
+const RECONSTRUCT_WINDOW: usize = 64;
+
+/// Reconstruct the bad blocks in `targets` into `outputs`, starting at
+/// offset `off`, without modifying any blocks
+///
+/// `bad_blocks` must include every bad block, including the targets, and
+/// must already be validated.
+///
+fn reconstruct<B: AsRef<[__u]>, O: AsMut<[__u]>>(
+    blocks: &[B],
+    bad_blocks: &[usize],
+    targets: &[usize],
+    off: usize,
+    outputs: &mut [O],
+) -> Result<(), Error> {
+    let n = blocks.len() - __parity;
+    let plan = plan(n, bad_blocks)?;
+
+    // find the bad data blocks and the equations used to solve for them,
+    // in the same order plan uses
+    let mut bad_datas = [0usize; __parity];
+    let mut m = 0;
+    for &b in bad_blocks {
+        if b < n && !bad_datas[..m].contains(&b) {
+            bad_datas[m] = b;
+            m += 1;
+        }
+    }
+    let mut rows = [0usize; __parity];
+    for (s, k) in (0..__parity).filter(|k| plan.equations[*k]).enumerate() {
+        rows[s] = k;
+    }
+
+    // invert our system of equations
+    let mut matrix = [[__gf::new(0); __parity]; __parity];
+    for (s, k) in rows[..m].iter().enumerate() {
+        for (u, x) in bad_datas[..m].iter().enumerate() {
+            matrix[s][u] = coefficient(*x, *k);
+        }
+    }
+    let inv = invert(matrix, m);
+
+    let len = outputs.iter_mut().map(|o| o.as_mut().len()).max().unwrap_or(0);
+    let mut i = 0;
+    while i < len {
+        let size = min(RECONSTRUCT_WINDOW, len-i);
+        let lo = off + i;
+
+        // find intermediate values for each equation
+        //
+        // p_k - Σ di*c(i,k)
+        //     i!=bad
+        //
+        let mut syndromes = [[__gf::new(0); RECONSTRUCT_WINDOW]; __parity];
+        for (s, k) in rows[..m].iter().enumerate() {
+            let p = blocks[n+*k].as_ref();
+            mul_acc(&mut syndromes[s], &p[min(lo, p.len())..], __gf::new(1), 0, size);
+
+            for (j, b) in blocks[..n].iter().enumerate() {
+                if !bad_datas[..m].contains(&j) {
+                    let b = b.as_ref();
+                    mul_acc(&mut syndromes[s], &b[min(lo, b.len())..], coefficient(j, *k), 0, size);
+                }
+            }
+        }
+
+        for (target, output) in targets.iter().zip(outputs.iter_mut()) {
+            let output = output.as_mut();
+            if i >= output.len() {
+                continue;
+            }
+
+            let mut window = [__gf::new(0); RECONSTRUCT_WINDOW];
+            if *target < n {
+                // solve for the data block using its row of the inverse
+                let t = bad_datas[..m].iter().position(|x| x == target).unwrap();
+                for s in 0..m {
+                    for x in 0..size {
+                        window[x] += inv[t][s]*syndromes[s][x];
+                    }
+                }
+            } else {
+                // regenerate the parity block, solving for any bad data blocks
+                let k = *target - n;
+                for (j, b) in blocks[..n].iter().enumerate() {
+                    if !bad_datas[..m].contains(&j) {
+                        let b = b.as_ref();
+                        mul_acc(&mut window, &b[min(lo, b.len())..], coefficient(j, k), 0, size);
+                    }
+                }
+
+                for t in 0..m {
+                    let g = coefficient(bad_datas[t], k);
+                    for s in 0..m {
+                        let f = g*inv[t][s];
+                        for x in 0..size {
+                            window[x] += f*syndromes[s][x];
+                        }
+                    }
+                }
+            }
+
+            for x in 0..min(size, output.len()-i) {
+                output[i+x] = __u::from(window[x]);
+            }
+        }
+
+        i += size;
+    }
+
+    Ok(())
+}
+
+/// Reconstruct bad blocks into caller-provided buffers, without modifying
+/// any blocks.
+///
+/// `blocks` contains the data blocks followed by the parity blocks, and
+/// `outputs` contains one buffer for each bad block, in the same order as
+/// `bad_blocks`. Bad blocks are never read, and each buffer's length
+/// determines how much of the bad block is reconstructed.
+///
+/// Unlike [`repair`], this doesn't need mutable access to the surviving
+/// blocks, and unlike [`repair_stream`], this never allocates, reconstructing
+/// the bad blocks directly into the buffers a small window at a time:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let data = b"Hello World!";
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// let blocks = data.chunks(4).collect::<Vec<_>>();
+/// raid6::format(&blocks, &mut parity1, &mut parity2);
+///
+/// // rebuild two lost blocks
+/// let blocks = [&b"Hell"[..], &b"xxxx"[..], &b"rld!"[..], &b"xxxx"[..], &parity2[..]];
+/// let mut rebuilt = [[0u8; 4]; 2];
+/// raid6::repair_into(&blocks, &[1, 3], &mut rebuilt).unwrap();
+/// assert_eq!(&rebuilt[0], b"o Wo");
+/// assert_eq!(&rebuilt[1], &parity1[..]);
+/// ```
+///
+pub fn repair_into<B: AsRef<[__u]>, O: AsMut<[__u]>>(
+    blocks: &[B],
+    bad_blocks: &[usize],
+    outputs: &mut [O],
+) -> Result<(), Error> {
+    if blocks.len() <= __parity || outputs.len() != bad_blocks.len() {
+        return Err(Error::LengthMismatch);
+    }
+    check_blocks(blocks.len() - __parity, bad_blocks)?;
+
+    reconstruct(blocks, bad_blocks, bad_blocks, 0, outputs)
+}
+
+/// Reconstruct part of a single bad block into a buffer, without modifying
+/// any blocks.
+///
+/// `blocks` contains the data blocks followed by the parity blocks, and
+/// `bad_blocks` contains any other blocks that are bad, `index` is always
+/// treated as bad. This reads the symbols in `off..off+buf.len()` of the
+/// reconstructed block into `buf`.
+///
+/// Unlike [`repair`], this only reconstructs the requested symbols of the
+/// requested block, which is useful for serving reads while a rebuild is
+/// pending:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let data = b"Hello World!";
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// let blocks = data.chunks(4).collect::<Vec<_>>();
+/// raid6::format(&blocks, &mut parity1, &mut parity2);
+///
+/// // read from two lost blocks
+/// let blocks = [&b"Hell"[..], &b"xxxx"[..], &b"xxxx"[..], &parity1[..], &parity2[..]];
+/// let mut buf = [0u8; 2];
+/// raid6::read_degraded_into(&blocks, &[1], 2, 1, &mut buf).unwrap();
+/// assert_eq!(&buf, b"ld");
+/// ```
+///
+pub fn read_degraded_into<B: AsRef<[__u]>>(
+    blocks: &[B],
+    bad_blocks: &[usize],
+    index: usize,
+    off: usize,
+    buf: &mut [__u],
+) -> Result<(), Error> {
+    if blocks.len() <= __parity {
+        return Err(Error::LengthMismatch);
+    }
+    check_blocks(blocks.len() - __parity, bad_blocks)?;
+    if index >= blocks.len() {
+        return Err(Error::BadBlockOutOfRange);
+    }
+
+    // include the block we're reading in our bad blocks
+    let mut bad_blocks_array = [0usize; __parity+1];
+    bad_blocks_array[..bad_blocks.len()].copy_from_slice(bad_blocks);
+    let mut bad_count = bad_blocks.len();
+    if !bad_blocks.contains(&index) {
+        bad_blocks_array[bad_count] = index;
+        bad_count += 1;
+    }
+
+    reconstruct(blocks, &bad_blocks_array[..bad_count], &[index], off, &mut [buf])
+}
+
+/// Reconstruct a single bad block, without modifying any blocks.
+///
+/// This is the same as [`read_degraded_into`], but allocates and returns
+/// the entire block. Like [`repair_stream`], bad blocks are never read, but
+/// the length of `blocks[index]` determines the length of the returned
+/// block.
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let data = b"Hello World!";
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// let blocks = data.chunks(4).collect::<Vec<_>>();
+/// raid6::format(&blocks, &mut parity1, &mut parity2);
+///
+/// // read a lost block
+/// let blocks = [&b"Hell"[..], &b"xxxx"[..], &b"rld!"[..], &parity1[..], &parity2[..]];
+/// assert_eq!(&raid6::read_degraded(&blocks, &[], 1).unwrap(), b"o Wo");
+/// ```
+///
+/// Note this requires alloc.
+///
+#[cfg(__if(__alloc))]
+pub fn read_degraded<B: AsRef<[__u]>>(
+    blocks: &[B],
+    bad_blocks: &[usize],
+    index: usize,
+) -> Result<vec::Vec<__u>, Error> {
+    let len = blocks.get(index)
+        .map(|b| b.as_ref().len())
+        .ok_or(Error::BadBlockOutOfRange)?;
+    let mut buf = vec![__u::default(); len];
+    read_degraded_into(blocks, bad_blocks, index, 0, &mut buf)?;
+    Ok(buf)
+}
+
+/// View a block's symbols as bytes for checksumming
+#[cfg(__if(__crc))]
+fn block_bytes(b: &[__u]) -> &[u8] {
+    unsafe {
+        slice::from_raw_parts(
+            b.as_ptr() as *const u8,
+            b.len()*size_of::<__u>()
+        )
+    }
+}
+
+/// Calculate the checksum of each block, using a CRC from the
+/// [`crc`](__crate::crc) module.
+///
+/// `blocks` contains the data blocks followed by the parity blocks, and
+/// `checksums` must have one checksum for each block. These checksums can
+/// later be passed to [`repair_checksummed`] to locate bad blocks.
+///
+/// Note the checksums are calculated over the native-endian bytes of each
+/// block's symbols.
+///
+#[cfg(__if(__crc))]
+pub fn checksum<C: Checksum, B: AsRef<[__u]>>(
+    blocks: &[B],
+    checksums: &mut [C::U],
+) {
+    assert!(checksums.len() == blocks.len());
+    for (b, checksum) in blocks.iter().zip(checksums.iter_mut()) {
+        *checksum = C::checksum(block_bytes(b.as_ref()));
+    }
+}
+
+/// Repair any blocks that don't match their checksums.
+///
+/// `blocks` contains the data blocks followed by the parity blocks, and
+/// `checksums` contains the checksums calculated by [`checksum`]. Blocks
+/// whose checksums don't match are treated as bad, which turns silent
+/// corruption, that [`scrub`] can detect but may not be able to locate,
+/// into a deterministic repair.
+///
+/// Returns the number of blocks that were repaired.
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// # use ::gf256::crc::Crc32cDigest;
+/// let mut blocks = [
+///     b"Hell".to_vec(),
+///     b"o Wo".to_vec(),
+///     b"rld!".to_vec(),
+///     vec![0u8; 4],
+///     vec![0u8; 4],
+/// ];
+/// let (datas, parity) = blocks.split_at_mut(3);
+/// let (p, q) = parity.split_at_mut(1);
+/// raid6::format(datas, &mut p[0], &mut q[0]);
+/// let mut checksums = [0u32; 5];
+/// raid6::checksum::<Crc32cDigest, _>(&blocks, &mut checksums);
+///
+/// // silently corrupt two blocks
+/// blocks[0][1] = b'a';
+/// blocks[4][2] ^= 0x80;
+///
+/// // repair
+/// let repaired = raid6::repair_checksummed::<Crc32cDigest, _>(&mut blocks, &checksums).unwrap();
+/// assert_eq!(repaired, 2);
+/// assert_eq!(&blocks[0], b"Hell");
+/// ```
+///
+#[cfg(__if(__crc))]
+pub fn repair_checksummed<C: Checksum, B: AsMut<[__u]>>(
+    blocks: &mut [B],
+    checksums: &[C::U],
+) -> Result<usize, Error>
+where
+    C::U: PartialEq
+{
+    if blocks.len() <= __parity || checksums.len() != blocks.len() {
+        return Err(Error::LengthMismatch);
+    }
+
+    // find the bad blocks
+    let mut bad_blocks_array = [0usize; __parity];
+    let mut bad_count = 0;
+    for (j, (b, checksum)) in blocks.iter_mut().zip(checksums).enumerate() {
+        if C::checksum(block_bytes(b.as_mut())) != *checksum {
+            if bad_count == __parity {
+                // can't repair
+                return Err(Error::TooManyBadBlocks);
+            }
+
+            bad_blocks_array[bad_count] = j;
+            bad_count += 1;
+        }
+    }
+
+    if bad_count > 0 {
+        let n = blocks.len() - __parity;
+        let (datas, parity) = blocks.split_at_mut(n);
+        repair_split(datas, parity, &bad_blocks_array[..bad_count])?;
+    }
+
+    Ok(bad_count)
+}
+
+
+// const-generic arrays, with no slices or alloc
+
+/// A RAID array stored in fixed-size arrays, with `N` data blocks of `L`
+/// symbols each.
+///
+/// The number of parity blocks is determined by the RAID module, so a
+/// `raid6::Raid<N, L>` has 2 parity blocks. Everything is stored inline,
+/// so this never allocates, which is useful for embedded systems such as
+/// flash translation layers, where the geometry is known at compile time:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let mut array = raid6::Raid::new([*b"Hell", *b"o Wo", *b"rld!"]);
+///
+/// // corrupt
+/// array.blocks_mut()[0] = *b"xxxx";
+/// array.parity_mut()[1] = *b"xxxx";
+///
+/// // repair
+/// array.repair(&[0, 4]).unwrap();
+/// assert_eq!(array.blocks(), &[*b"Hell", *b"o Wo", *b"rld!"]);
+/// ```
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Raid<const N: usize, const L: usize> {
+    blocks: [[__u; L]; N],
+    parity: [[__u; L]; __parity],
+}
+
+impl<const N: usize, const L: usize> Raid<N, L> {
+    /// Create a new RAID array, formatting the parity blocks.
+    pub fn new(blocks: [[__u; L]; N]) -> Self {
+        let mut parity = [[__u::default(); L]; __parity];
+        format_split(&blocks, &mut parity);
+        Self { blocks, parity }
+    }
+
+    /// Create a RAID array from existing data and parity blocks, without
+    /// formatting.
+    pub const fn from_parts(
+        blocks: [[__u; L]; N],
+        parity: [[__u; L]; __parity],
+    ) -> Self {
+        Self { blocks, parity }
+    }
+
+    /// Get the data blocks.
+    pub const fn blocks(&self) -> &[[__u; L]; N] {
+        &self.blocks
+    }
+
+    /// Get mutable access to the data blocks.
+    ///
+    /// Note that modifying the data blocks directly does not update the
+    /// parity blocks, see [`update`](Self::update) or
+    /// [`format`](Self::format).
+    ///
+    pub fn blocks_mut(&mut self) -> &mut [[__u; L]; N] {
+        &mut self.blocks
+    }
+
+    /// Get the parity blocks.
+    pub const fn parity(&self) -> &[[__u; L]; __parity] {
+        &self.parity
+    }
+
+    /// Get mutable access to the parity blocks.
+    pub fn parity_mut(&mut self) -> &mut [[__u; L]; __parity] {
+        &mut self.parity
+    }
+
+    /// Get the data and parity blocks.
+    pub fn into_parts(self) -> ([[__u; L]; N], [[__u; L]; __parity]) {
+        (self.blocks, self.parity)
+    }
+
+    /// Recalculate the parity blocks from the data blocks.
+    pub fn format(&mut self) {
+        format_split(&self.blocks, &mut self.parity);
+    }
+
+    /// Repair up to `n` bad blocks, where parity blocks are indexed after
+    /// the data blocks, the same as in [`repair`].
+    pub fn repair(&mut self, bad_blocks: &[usize]) -> Result<(), Error> {
+        repair_split(&mut self.blocks, &mut self.parity, bad_blocks)
+    }
+
+    /// Write `data` into data block `j` at offset `off`, updating the
+    /// parity blocks.
+    pub fn update(&mut self, j: usize, off: usize, data: &[__u]) {
+        let mut coefficients = [__gf::new(0); __parity];
+        for (k, c) in coefficients.iter_mut().enumerate() {
+            *c = coefficient(j, k);
+        }
+
+        let block = &mut self.blocks[j][off..off+data.len()];
+        for (i, (x, y)) in block.iter_mut().zip(data).enumerate() {
+            let delta = __gf::from_lossy(*x) - __gf::from_lossy(*y);
+            *x = *y;
+
+            for (p, c) in self.parity.iter_mut().zip(coefficients) {
+                let p = &mut p[off+i];
+                *p = __u::from(__gf::from_lossy(*p) - delta*c);
+            }
+        }
+    }
+}
+
+// streaming implementations, if alloc is available
+
+/// Number of symbols we process at a time when streaming
+#[cfg(__if(__alloc))]
+const STREAM_WINDOW: usize = 4096;
+
+/// Repair up to `n` bad blocks, streaming the blocks through fixed-size
+/// windows.
+///
+/// `blocks` contains the data blocks followed by the parity blocks, and
+/// `sinks` contains one [`BlockSink`] for each bad block, in the same order
+/// as `bad_blocks`. Bad blocks are never read, but their length is still
+/// used to determine how much to repair.
+///
+/// Unlike [`repair`], memory usage does not depend on the size of the
+/// blocks, so this can be used to rebuild blocks that don't fit in memory:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let data = b"Hello World!";
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// let blocks = data.chunks(4).collect::<Vec<_>>();
+/// raid6::format(&blocks, &mut parity1, &mut parity2);
+///
+/// // rebuild two lost blocks
+/// let mut blocks = [&b"Hell"[..], &b"xxxx"[..], &b"xxxx"[..], &parity1[..], &parity2[..]];
+/// let mut rebuilt = [vec![], vec![]];
+/// raid6::repair_stream(&mut blocks, &mut rebuilt, &[1, 2]).unwrap();
+/// assert_eq!(&rebuilt[0], b"o Wo");
+/// assert_eq!(&rebuilt[1], b"rld!");
+/// ```
+///
+/// Note this requires alloc.
+///
+#[cfg(__if(__alloc))]
+pub fn repair_stream<S, D>(
+    blocks: &mut [S],
+    sinks: &mut [D],
+    bad_blocks: &[usize],
+) -> Result<(), StreamError<S::Error>>
+where
+    S: BlockSource<__u>,
+    D: BlockSink<__u, Error=S::Error>
+{
+    repair_stream_with_progress(blocks, sinks, bad_blocks, |_| true)
+}
+
+/// Repair up to `n` bad blocks, streaming the blocks through fixed-size
+/// windows, and reporting progress.
+///
+/// This is the same as [`repair_stream`], but calls `progress` each time a
+/// window of a bad block is written. If `progress` returns false, the repair
+/// is cancelled and [`StreamError::Cancelled`] is returned. Note the bad
+/// blocks may be left partially repaired.
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let data = b"Hello World!";
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// let blocks = data.chunks(4).collect::<Vec<_>>();
+/// raid6::format(&blocks, &mut parity1, &mut parity2);
+///
+/// // rebuild a lost block, printing progress
+/// let mut blocks = [&b"Hell"[..], &b"xxxx"[..], &b"rld!"[..], &parity1[..], &parity2[..]];
+/// let mut rebuilt = [vec![]];
+/// raid6::repair_stream_with_progress(&mut blocks, &mut rebuilt, &[1], |progress| {
+///     println!("block {}: {}/{}", progress.block, progress.processed, progress.total);
+///     true
+/// }).unwrap();
+/// assert_eq!(&rebuilt[0], b"o Wo");
+///
+/// // or cancel the rebuild
+/// let mut rebuilt = [vec![]];
+/// assert_eq!(
+///     raid6::repair_stream_with_progress(&mut blocks, &mut rebuilt, &[1], |_| false),
+///     Err(raid6::StreamError::Cancelled)
+/// );
+/// ```
+///
+/// Note this requires alloc.
+///
+#[cfg(__if(__alloc))]
+pub fn repair_stream_with_progress<S, D, F>(
+    blocks: &mut [S],
+    sinks: &mut [D],
+    bad_blocks: &[usize],
+    mut progress: F,
+) -> Result<(), StreamError<S::Error>>
+where
+    S: BlockSource<__u>,
+    D: BlockSink<__u, Error=S::Error>,
+    F: FnMut(Progress) -> bool,
+{
+    if blocks.len() <= __parity || sinks.len() != bad_blocks.len() {
+        return Err(StreamError::Raid(Error::LengthMismatch));
+    }
+    let n = blocks.len() - __parity;
+    check_blocks(n, bad_blocks)?;
+
+    let len = blocks.iter().map(|b| b.len()).max().unwrap_or(0);
+    let mut windows = vec![vec![__u::default(); STREAM_WINDOW]; blocks.len()];
+    let mut off = 0;
+    while off < len {
+        // read the next window of each good block, blocks may be shorter
+        // than the parity blocks
+        for (j, (b, window)) in blocks.iter_mut().zip(windows.iter_mut()).enumerate() {
+            let size = min(STREAM_WINDOW, b.len().saturating_sub(off));
+            window.resize(size, __u::default());
+            if !bad_blocks.contains(&j) && !window.is_empty() {
+                b.read(off, window).map_err(StreamError::Block)?;
+            }
+        }
+
+        // repair
+        let (datas, parity) = windows.split_at_mut(n);
+        repair_split(datas, parity, bad_blocks)?;
+
+        // write out the repaired blocks
+        for (sink, j) in sinks.iter_mut().zip(bad_blocks) {
+            if !windows[*j].is_empty() {
+                sink.write(off, &windows[*j]).map_err(StreamError::Block)?;
+                if !progress(Progress {
+                    processed: off + windows[*j].len(),
+                    total: len,
+                    block: *j,
+                }) {
+                    return Err(StreamError::Cancelled);
+                }
+            }
+        }
+
+        off += STREAM_WINDOW;
+    }
+
+    Ok(())
+}
+
+// Dispatch repair with the parity blocks passed as a slice, regardless
+// of the number of parity blocks
+fn repair_split<B: AsMut<[__u]>, P: AsMut<[__u]>>(
+    datas: &mut [B],
+    parity: &mut [P],
+    bad_blocks: &[usize],
+) -> Result<(), Error> {
+    cfg_if! {
+        if #[cfg(__if(__parity >= 4))] {
+            repair(datas, parity, bad_blocks)
+        } else if #[cfg(__if(__parity >= 3))] {
+            let (p, parity) = parity.split_first_mut().unwrap();
+            let (q, parity) = parity.split_first_mut().unwrap();
+            let (r, _) = parity.split_first_mut().unwrap();
+            repair(datas, p.as_mut(), q.as_mut(), r.as_mut(), bad_blocks)
+        } else if #[cfg(__if(__parity >= 2))] {
+            let (p, parity) = parity.split_first_mut().unwrap();
+            let (q, _) = parity.split_first_mut().unwrap();
+            repair(datas, p.as_mut(), q.as_mut(), bad_blocks)
+        } else if #[cfg(__if(__parity >= 1))] {
+            let (p, _) = parity.split_first_mut().unwrap();
+            repair(datas, p.as_mut(), bad_blocks)
+        } else {
+            repair(datas, bad_blocks)
+        }
+    }
+}
+
+// Dispatch format with the parity blocks passed as a slice, regardless
+// of the number of parity blocks
+fn format_split<B: AsRef<[__u]>, P: AsMut<[__u]>>(
+    datas: &[B],
+    parity: &mut [P],
+) {
+    cfg_if! {
+        if #[cfg(__if(__parity >= 4))] {
+            format(datas, parity)
+        } else if #[cfg(__if(__parity >= 3))] {
+            let (p, parity) = parity.split_first_mut().unwrap();
+            let (q, parity) = parity.split_first_mut().unwrap();
+            let (r, _) = parity.split_first_mut().unwrap();
+            format(datas, p.as_mut(), q.as_mut(), r.as_mut())
+        } else if #[cfg(__if(__parity >= 2))] {
+            let (p, parity) = parity.split_first_mut().unwrap();
+            let (q, _) = parity.split_first_mut().unwrap();
+            format(datas, p.as_mut(), q.as_mut())
+        } else if #[cfg(__if(__parity >= 1))] {
+            let (p, _) = parity.split_first_mut().unwrap();
+            format(datas, p.as_mut())
+        } else {
+            format(datas)
+        }
+    }
+}
+
+/// Format the parity blocks of an array of [`BlockDevice`]s.
+///
+/// `devices` contains the data devices followed by the parity devices. Like
+/// [`format`], data devices may be shorter than the parity devices, but the
+/// parity devices must all be the length of the longest data device.
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let mut devices = [
+///     MemBlockDevice::new(b"Hell".to_vec()),
+///     MemBlockDevice::new(b"o Wo".to_vec()),
+///     MemBlockDevice::new(b"rld!".to_vec()),
+///     MemBlockDevice::new(vec![0u8; 4]),
+///     MemBlockDevice::new(vec![0u8; 4]),
+/// ];
+/// raid6::format_devices(&mut devices).unwrap();
+/// let parity1 = devices[3].get_ref().clone();
+///
+/// // lose two devices
+/// devices[0] = MemBlockDevice::new(b"xxxx".to_vec());
+/// devices[3] = MemBlockDevice::new(b"xxxx".to_vec());
+///
+/// // repair in place
+/// raid6::repair_devices(&mut devices, &[0, 3]).unwrap();
+/// assert_eq!(devices[0].get_ref(), b"Hell");
+/// assert_eq!(devices[3].get_ref(), &parity1);
+/// ```
+///
+/// Returns [`Error::LengthMismatch`] if the parity devices aren't the length
+/// of the longest data device.
+///
+/// Note this requires alloc.
+///
+#[cfg(__if(__alloc))]
+pub fn format_devices<D: BlockDevice<__u>>(
+    devices: &mut [D],
+) -> Result<(), StreamError<D::Error>> {
+    if devices.len() <= __parity {
+        return Err(StreamError::Raid(Error::LengthMismatch));
+    }
+    let n = devices.len() - __parity;
+    check_blocks(n, &[])?;
+
+    let len = devices[..n].iter().map(|d| d.len()).max().unwrap_or(0);
+    if devices[n..].iter().any(|d| d.len() != len) {
+        return Err(StreamError::Raid(Error::LengthMismatch));
+    }
+
+    let mut windows = vec![vec![__u::default(); STREAM_WINDOW]; devices.len()];
+    let mut off = 0;
+    while off < len {
+        // read the next window of each data device
+        for (d, window) in devices[..n].iter_mut().zip(windows.iter_mut()) {
+            let size = min(STREAM_WINDOW, d.len().saturating_sub(off));
+            window.resize(size, __u::default());
+            if !window.is_empty() {
+                d.read_block(off, window).map_err(StreamError::Block)?;
+            }
+        }
+        for window in windows[n..].iter_mut() {
+            window.resize(min(STREAM_WINDOW, len - off), __u::default());
+        }
+
+        // format and write out the parity
+        let (datas, parity) = windows.split_at_mut(n);
+        format_split(datas, parity);
+        for (d, window) in devices[n..].iter_mut().zip(windows[n..].iter()) {
+            d.write_block(off, window).map_err(StreamError::Block)?;
+        }
+
+        off += STREAM_WINDOW;
+    }
+
+    Ok(())
+}
+
+/// Repair up to `n` bad [`BlockDevice`]s in place.
+///
+/// `devices` contains the data devices followed by the parity devices, and
+/// `bad_blocks` indicates which devices are bad, the same as in [`repair`].
+/// Bad devices are never read, only written to. See [`format_devices`] for
+/// an example.
+///
+/// Note this requires alloc.
+///
+#[cfg(__if(__alloc))]
+pub fn repair_devices<D: BlockDevice<__u>>(
+    devices: &mut [D],
+    bad_blocks: &[usize],
+) -> Result<(), StreamError<D::Error>> {
+    repair_devices_with_progress(devices, bad_blocks, |_| true)
+}
+
+/// Repair up to `n` bad devices, reporting progress.
+///
+/// This is the same as [`repair_devices`], but calls `progress` each time a
+/// window of a bad device is written. If `progress` returns false, the repair
+/// is cancelled and [`StreamError::Cancelled`] is returned. Note the bad
+/// devices may be left partially repaired.
+///
+/// This can be used to provide a progress bar or a cancellation token:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// let mut devices = [
+///     MemBlockDevice::new(b"Hell".to_vec()),
+///     MemBlockDevice::new(b"o Wo".to_vec()),
+///     MemBlockDevice::new(b"rld!".to_vec()),
+///     MemBlockDevice::new(vec![0u8; 4]),
+///     MemBlockDevice::new(vec![0u8; 4]),
+/// ];
+/// raid6::format_devices(&mut devices).unwrap();
+///
+/// // lose a device
+/// devices[1] = MemBlockDevice::new(b"xxxx".to_vec());
+///
+/// // repair, unless cancelled
+/// let cancelled = AtomicBool::new(false);
+/// raid6::repair_devices_with_progress(&mut devices, &[1], |progress| {
+///     println!("device {}: {}/{}", progress.block, progress.processed, progress.total);
+///     !cancelled.load(Ordering::Relaxed)
+/// }).unwrap();
+/// assert_eq!(devices[1].get_ref(), b"o Wo");
+/// ```
+///
+/// Note this requires alloc.
+///
+#[cfg(__if(__alloc))]
+pub fn repair_devices_with_progress<D, F>(
+    devices: &mut [D],
+    bad_blocks: &[usize],
+    mut progress: F,
+) -> Result<(), StreamError<D::Error>>
+where
+    D: BlockDevice<__u>,
+    F: FnMut(Progress) -> bool,
+{
+    if devices.len() <= __parity {
+        return Err(StreamError::Raid(Error::LengthMismatch));
+    }
+    let n = devices.len() - __parity;
+    check_blocks(n, bad_blocks)?;
+
+    let len = devices.iter().map(|d| d.len()).max().unwrap_or(0);
+    let mut windows = vec![vec![__u::default(); STREAM_WINDOW]; devices.len()];
+    let mut off = 0;
+    while off < len {
+        // read the next window of each good device
+        for (j, (d, window)) in devices.iter_mut().zip(windows.iter_mut()).enumerate() {
+            let size = min(STREAM_WINDOW, d.len().saturating_sub(off));
+            window.resize(size, __u::default());
+            if !bad_blocks.contains(&j) && !window.is_empty() {
+                d.read_block(off, window).map_err(StreamError::Block)?;
+            }
+        }
+
+        // repair
+        let (datas, parity) = windows.split_at_mut(n);
+        repair_split(datas, parity, bad_blocks)?;
+
+        // write the repaired windows back to the bad devices
+        for &j in bad_blocks {
+            if !windows[j].is_empty() {
+                devices[j].write_block(off, &windows[j]).map_err(StreamError::Block)?;
+                if !progress(Progress {
+                    processed: off + windows[j].len(),
+                    total: len,
+                    block: j,
+                }) {
+                    return Err(StreamError::Cancelled);
+                }
+            }
+        }
+
+        off += STREAM_WINDOW;
+    }
+
+    Ok(())
+}
+
+// Chunks need to be large enough to amortize the cost of splitting up the
+// blocks, but we want a few chunks per thread to balance the load
+#[cfg(__if(__rayon && __alloc))]
+fn par_chunk(len: usize) -> usize {
+    const MIN_CHUNK: usize = 16*1024;
+    let threads = __crate::internal::rayon::current_num_threads();
+    max(len.div_ceil(4*threads), MIN_CHUNK)
+}
+
+#[cfg(__if(__rayon && __alloc))]
+fn par_format_split(datas: &[&[__u]], parity: &mut [&mut [__u]]) {
+    use __crate::internal::rayon::prelude::*;
+
+    let len = datas.iter().map(|b| b.len()).max().unwrap();
+    let chunk = par_chunk(len);
+    if len <= chunk {
+        return format_split(datas, parity);
+    }
+
+    // split each block into stripe chunks, blocks may be shorter than the
+    // parity blocks
+    let mut chunks = (0..len.div_ceil(chunk))
+        .map(|_| (vec::Vec::new(), vec::Vec::new()))
+        .collect::<vec::Vec<_>>();
+    for b in datas {
+        let mut b = b.chunks(chunk);
+        for (c, _) in chunks.iter_mut() {
+            c.push(b.next().unwrap_or(&[]));
+        }
+    }
+    for p in parity.iter_mut() {
+        assert!(p.len() == len);
+        for (c, (_, ps)) in p.chunks_mut(chunk).zip(chunks.iter_mut()) {
+            ps.push(c);
+        }
+    }
+
+    chunks.into_par_iter()
+        .for_each(|(datas, mut parity)| format_split(&datas, &mut parity));
+}
+
+#[cfg(__if(__rayon && __alloc))]
+fn par_repair_split(
+    datas: &mut [&mut [__u]],
+    parity: &mut [&mut [__u]],
+    bad_blocks: &[usize],
+) -> Result<(), Error> {
+    use __crate::internal::rayon::prelude::*;
+
+    check_blocks(datas.len(), bad_blocks)?;
+    if parity.len() != __parity {
+        return Err(Error::LengthMismatch);
+    }
+    let len = match parity.first() {
+        Some(p) => p.len(),
+        None => datas.iter().map(|b| b.len()).max().unwrap_or(0),
+    };
+    if !parity.iter().all(|p| p.len() == len)
+        || datas.iter().any(|b| b.len() > len)
+    {
+        return Err(Error::LengthMismatch);
+    }
+    let chunk = par_chunk(len);
+    if len <= chunk {
+        return repair_split(datas, parity, bad_blocks);
+    }
+
+    // split each block into stripe chunks, blocks may be shorter than the
+    // parity blocks
+    let mut chunks = (0..len.div_ceil(chunk))
+        .map(|_| (vec::Vec::new(), vec::Vec::new()))
+        .collect::<vec::Vec<_>>();
+    for b in datas.iter_mut() {
+        let mut b = b.chunks_mut(chunk);
+        for (c, _) in chunks.iter_mut() {
+            c.push(b.next().unwrap_or(&mut []));
+        }
+    }
+    for p in parity.iter_mut() {
+        for (c, (_, ps)) in p.chunks_mut(chunk).zip(chunks.iter_mut()) {
+            ps.push(c);
+        }
+    }
+
+    chunks.into_par_iter()
+        .try_for_each(|(mut datas, mut parity)| {
+            repair_split(&mut datas, &mut parity, bad_blocks)
+        })
+}
+
+/// Format blocks with RAID-parity in parallel.
+///
+/// This is equivalent to [`format`], but splits the blocks into stripe
+/// chunks and computes the parity of each chunk on rayon's thread pool:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let data = (0..1024*1024).map(|x| x as u8).collect::<Vec<u8>>();
+/// let blocks = data.chunks(256*1024).collect::<Vec<_>>();
+/// let mut parity1 = vec![0u8; 256*1024];
+/// let mut parity2 = vec![0u8; 256*1024];
+/// raid6::par_format(&blocks, &mut parity1, &mut parity2);
+///
+/// let mut expected1 = vec![0u8; 256*1024];
+/// let mut expected2 = vec![0u8; 256*1024];
+/// raid6::format(&blocks, &mut expected1, &mut expected2);
+/// assert_eq!(parity1, expected1);
+/// assert_eq!(parity2, expected2);
+/// ```
+///
+/// Small blocks are not worth splitting, so these are passed directly to
+/// [`format`].
+///
+/// Note this requires the `rayon` feature.
+///
+#[cfg(__if(__rayon && __alloc && __parity <= 3))]
+pub fn par_format<B: AsRef<[__u]>>(
+    blocks: &[B],
+    #[cfg(__if(__parity >= 1))] p: &mut [__u],
+    #[cfg(__if(__parity >= 2))] q: &mut [__u],
+    #[cfg(__if(__parity >= 3))] r: &mut [__u],
+) {
+    assert!(blocks.len() >= 1);
+    let datas = blocks.iter().map(|b| b.as_ref()).collect::<vec::Vec<_>>();
+    let mut parity = vec::Vec::with_capacity(__parity);
+    #[cfg(__if(__parity >= 1))] parity.push(p);
+    #[cfg(__if(__parity >= 2))] parity.push(q);
+    #[cfg(__if(__parity >= 3))] parity.push(r);
+    par_format_split(&datas, &mut parity);
+}
+
+/// Repair up to `n` bad blocks in parallel.
+///
+/// This is equivalent to [`repair`], but splits the blocks into stripe
+/// chunks and repairs each chunk on rayon's thread pool.
+///
+/// Small blocks are not worth splitting, so these are passed directly to
+/// [`repair`].
+///
+/// Returns an [`Error`] instead of panicking if there are too many bad
+/// blocks, a bad block is out of range, or the blocks' lengths don't match.
+///
+/// Note this requires the `rayon` feature.
+///
+#[cfg(__if(__rayon && __alloc && __parity <= 3))]
+pub fn par_repair<B: AsMut<[__u]>>(
+    blocks: &mut [B],
+    #[cfg(__if(__parity >= 1))] p: &mut [__u],
+    #[cfg(__if(__parity >= 2))] q: &mut [__u],
+    #[cfg(__if(__parity >= 3))] r: &mut [__u],
+    bad_blocks: &[usize],
+) -> Result<(), Error> {
+    let mut datas = blocks.iter_mut().map(|b| b.as_mut()).collect::<vec::Vec<_>>();
+    let mut parity = vec::Vec::with_capacity(__parity);
+    #[cfg(__if(__parity >= 1))] parity.push(p);
+    #[cfg(__if(__parity >= 2))] parity.push(q);
+    #[cfg(__if(__parity >= 3))] parity.push(r);
+    par_repair_split(&mut datas, &mut parity, bad_blocks)
+}
+
+/// Format blocks with RAID-parity in parallel.
+///
+/// This is equivalent to [`format`], but splits the blocks into stripe
+/// chunks and computes the parity of each chunk on rayon's thread pool.
+///
+/// Small blocks are not worth splitting, so these are passed directly to
+/// [`format`].
+///
+/// Note this requires the `rayon` feature.
+///
+#[cfg(__if(__rayon && __alloc && __parity >= 4))]
+pub fn par_format<B: AsRef<[__u]>, P: AsMut<[__u]>>(
+    blocks: &[B],
+    parity: &mut [P],
+) {
+    assert!(blocks.len() >= 1);
+    assert!(parity.len() == __parity);
+    let datas = blocks.iter().map(|b| b.as_ref()).collect::<vec::Vec<_>>();
+    let mut parity = parity.iter_mut().map(|p| p.as_mut()).collect::<vec::Vec<_>>();
+    par_format_split(&datas, &mut parity);
+}
+
+/// Repair up to `n` bad blocks in parallel.
+///
+/// This is equivalent to [`repair`], but splits the blocks into stripe
+/// chunks and repairs each chunk on rayon's thread pool.
+///
+/// Small blocks are not worth splitting, so these are passed directly to
+/// [`repair`].
+///
+/// Returns an [`Error`] instead of panicking if there are too many bad
+/// blocks, a bad block is out of range, or the blocks' lengths don't match.
+///
+/// Note this requires the `rayon` feature.
+///
+#[cfg(__if(__rayon && __alloc && __parity >= 4))]
+pub fn par_repair<B: AsMut<[__u]>, P: AsMut<[__u]>>(
+    blocks: &mut [B],
+    parity: &mut [P],
+    bad_blocks: &[usize],
+) -> Result<(), Error> {
+    let mut datas = blocks.iter_mut().map(|b| b.as_mut()).collect::<vec::Vec<_>>();
+    let mut parity = parity.iter_mut().map(|p| p.as_mut()).collect::<vec::Vec<_>>();
+    par_repair_split(&mut datas, &mut parity, bad_blocks)
+}
+
+/// Format blocks with RAID-parity using a [`Backend`] selected at runtime.
+///
+/// This is equivalent to [`format`], but uses the backend's SIMD kernel for
+/// 8-bit fields, and processes the blocks in chunks of the backend's chunk
+/// size. Regardless of the number of parity blocks, the parity blocks are
+/// passed as a slice, which must contain exactly one block for each parity
+/// block:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let backend = Backend::detect();
+///
+/// let data = b"Hello World!";
+/// let blocks = data.chunks(4).collect::<Vec<_>>();
+/// let mut parity = [[0u8; 4]; 3];
+/// raid7::format_with(&backend, &blocks, &mut parity);
+///
+/// assert_eq!(&parity[0], b"\x55\x29\x5f\x22");
+/// assert_eq!(&parity[1], b"\x43\x88\x4f\x36");
+/// assert_eq!(&parity[2], b"\x9a\x6b\x23\xe7");
+/// ```
+///
+pub fn format_with<B: AsRef<[__u]>, P: AsMut<[__u]>>(
+    backend: &Backend,
+    blocks: &[B],
+    parity: &mut [P],
+) {
+    assert!(blocks.len() >= 1);
+    #[cfg(__if(__parity >= 2))] { assert!(blocks.len() <= max_blocks()); }
+    assert!(parity.len() == __parity);
+
+    // blocks may be shorter than the parity blocks, missing symbols are
+    // treated as zero
+    let len = blocks.iter().map(|b| b.as_ref().len()).max().unwrap();
+    assert!(parity.iter_mut().all(|p| p.as_mut().len() == len));
+
+    // format one chunk at a time, keeping a chunk of each parity block in
+    // cache while we stream through the data blocks
+    let chunk = max(backend.chunk_size() / size_of::<__u>(), 1);
+    let mut lo = 0;
+    while lo < len {
+        let hi = min(lo+chunk, len);
+        for (k, p) in parity.iter_mut().enumerate() {
+            let p = unsafe { __gf::slice_from_slice_mut_unchecked(p.as_mut()) };
+            for i in lo..hi {
+                p[i] = __gf::new(0);
+            }
+
+            for (j, b) in blocks.iter().enumerate() {
+                mul_acc_with(backend.kernel(), p, b.as_ref(), coefficient(j, k), lo, hi);
+            }
+        }
+
+        lo = hi;
+    }
+}
+
+/// Repair up to `n` bad blocks using a [`Backend`] selected at runtime.
+///
+/// This is equivalent to [`repair`], but uses the backend's SIMD kernel for
+/// 8-bit fields, and processes the blocks in chunks of the backend's chunk
+/// size. Like [`format_with`], the parity blocks are passed as a slice:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let backend = Backend::detect();
+///
+/// let mut data = b"Hellxxxxxxxx".to_vec();
+/// let mut datas = data.chunks_mut(4).collect::<Vec<_>>();
+/// let mut parity = [*b"xxxx", *b"\x43\x88\x4f\x36", *b"\x9a\x6b\x23\xe7"];
+///
+/// // repair
+/// raid7::repair_with(&backend, &mut datas, &mut parity, &[1, 2, 3]).unwrap();
+/// assert_eq!(&data, b"Hello World!");
+/// assert_eq!(&parity[0], b"\x55\x29\x5f\x22");
+/// ```
+///
+/// Returns an [`Error`] instead of panicking if there are too many bad
+/// blocks, a bad block is out of range, or the blocks' lengths don't match.
+///
+pub fn repair_with<B: AsMut<[__u]>, P: AsMut<[__u]>>(
+    backend: &Backend,
+    blocks: &mut [B],
+    parity: &mut [P],
+    bad_blocks: &[usize],
+) -> Result<(), Error> {
+    check_blocks(blocks.len(), bad_blocks)?;
+    if parity.len() != __parity {
+        return Err(Error::LengthMismatch);
+    }
+    let len = match parity.first_mut() {
+        Some(p) => p.as_mut().len(),
+        None => blocks.iter_mut().map(|b| b.as_mut().len()).max().unwrap_or(0),
+    };
+    if !parity.iter_mut().all(|p| p.as_mut().len() == len)
+        || blocks.iter_mut().any(|b| b.as_mut().len() > len)
+    {
+        return Err(Error::LengthMismatch);
+    }
+
+    // separate bad data blocks from bad parity blocks
+    let mut bad_datas_array = [0usize; __parity];
+    let mut bad_data_count = 0;
+    let mut bad_parity = [false; __parity];
+    for &b in bad_blocks {
+        if b < blocks.len() {
+            if !bad_datas_array[..bad_data_count].contains(&b) {
+                bad_datas_array[bad_data_count] = b;
+                bad_data_count += 1;
+            }
+        } else if let Some(bad) = bad_parity.get_mut(b-blocks.len()) {
+            *bad = true;
+        }
+    }
+
+    // blocks may be shorter than the parity blocks, in which case the
+    // missing symbols are zero, so we repair in segments where the set
+    // of bad blocks that need repair is constant, and each segment one
+    // chunk at a time
+    let chunk = max(backend.chunk_size() / size_of::<__u>(), 1);
+    let mut lo = 0;
+    while lo < len {
+        let hi = bad_datas_array[..bad_data_count].iter()
+            .map(|b| blocks[*b].as_mut().len())
+            .filter(|l| *l > lo)
+            .fold(min(lo+chunk, len), min);
+
+        let mut segment_array = [0usize; __parity];
+        let mut segment_count = 0;
+        for b in &bad_datas_array[..bad_data_count] {
+            if blocks[*b].as_mut().len() > lo {
+                segment_array[segment_count] = *b;
+                segment_count += 1;
+            }
+        }
+
+        solve_range(
+            backend.kernel(),
+            blocks,
+            parity,
+            &segment_array[..segment_count],
+            &bad_parity,
+            lo,
+            hi,
+        )?;
+        lo = hi;
+    }
+
+    Ok(())
+}
+
+/// Check that an update of `size` words at `off` fits in data device `j`
+/// and in each of the parity devices
+#[cfg(__if(__alloc))]
+fn check_update_range<D: BlockDevice<__u>>(
+    devices: &[D],
+    j: usize,
+    off: usize,
+    size: usize,
+) -> Result<(), Error> {
+    let n = devices.len() - __parity;
+    let end = off.checked_add(size).ok_or(Error::LengthMismatch)?;
+    if end > devices[j].len() || devices[n..].iter().any(|d| end > d.len()) {
+        return Err(Error::LengthMismatch);
+    }
+
+    Ok(())
+}
+
+/// Prepare a journaled update of part of a data device.
+///
+/// This reads the old data and parity from `devices`, and returns an
+/// [`UpdateIntent`] containing the new data and the new parity over the
+/// affected range. Nothing is written.
+///
+/// `devices` contains the data devices followed by the parity devices, the
+/// same as in [`format_devices`]. See [`update_devices`] for more info.
+///
+/// Returns [`Error::BadBlockOutOfRange`] if `j` isn't a data device, and
+/// [`Error::LengthMismatch`] if the update doesn't fit in the devices.
+///
+/// Note this requires alloc.
+///
+#[cfg(__if(__alloc))]
+pub fn prepare_update_devices<D: BlockDevice<__u>>(
+    devices: &mut [D],
+    j: usize,
+    off: usize,
+    new: &[__u],
+) -> Result<UpdateIntent<__u>, StreamError<D::Error>> {
+    if devices.len() <= __parity {
+        return Err(StreamError::Raid(Error::LengthMismatch));
+    }
+    let n = devices.len() - __parity;
+    check_blocks(n, &[])?;
+    if j >= n {
+        return Err(StreamError::Raid(Error::BadBlockOutOfRange));
+    }
+    check_update_range(devices, j, off, new.len())?;
+
+    // find the delta, new - old, which in a binary-extension field is
+    // just xor
+    let mut delta = vec![__u::default(); new.len()];
+    devices[j].read_block(off, &mut delta).map_err(StreamError::Block)?;
+    for i in 0..new.len() {
+        delta[i] ^= new[i];
+    }
+
+    // calculate new parity
+    let mut parity = vec::Vec::with_capacity(__parity);
+    for (k, d) in devices[n..].iter_mut().enumerate() {
+        let mut p = vec![__u::default(); new.len()];
+        d.read_block(off, &mut p).map_err(StreamError::Block)?;
+        let p_ = unsafe { __gf::slice_from_slice_mut_unchecked(&mut p) };
+        mul_acc(p_, &delta, coefficient(j, k), 0, new.len());
+        parity.push(p);
+    }
+
+    Ok(UpdateIntent {
+        block: j,
+        off,
+        data: new.to_vec(),
+        parity,
+    })
+}
+
+/// Apply a journaled update to an array of devices.
+///
+/// This writes the new data and new parity in an [`UpdateIntent`]. Applying
+/// an intent is idempotent, so this can be used both to complete an update
+/// and to replay an update after a crash. See [`update_devices`] for more
+/// info.
+///
+/// Like [`prepare_update_devices`], this returns an [`Error`] if the intent
+/// doesn't match the devices.
+///
+/// Note this requires alloc.
+///
+#[cfg(__if(__alloc))]
+pub fn apply_update_devices<D: BlockDevice<__u>>(
+    devices: &mut [D],
+    intent: &UpdateIntent<__u>,
+) -> Result<(), StreamError<D::Error>> {
+    if devices.len() <= __parity {
+        return Err(StreamError::Raid(Error::LengthMismatch));
+    }
+    let n = devices.len() - __parity;
+    check_blocks(n, &[])?;
+    if intent.block >= n {
+        return Err(StreamError::Raid(Error::BadBlockOutOfRange));
+    }
+    if intent.parity.len() != __parity
+        || intent.parity.iter().any(|p| p.len() != intent.data.len())
+    {
+        return Err(StreamError::Raid(Error::LengthMismatch));
+    }
+    check_update_range(devices, intent.block, intent.off, intent.data.len())?;
+
+    devices[intent.block].write_block(intent.off, &intent.data)
+        .map_err(StreamError::Block)?;
+    for (d, p) in devices[n..].iter_mut().zip(&intent.parity) {
+        d.write_block(intent.off, p).map_err(StreamError::Block)?;
+    }
+
+    Ok(())
+}
+
+/// Update part of a data device, protecting against the RAID write hole.
+///
+/// Updating a block requires writing both the data and every parity block.
+/// If we lose power between these writes, the stripe is left silently
+/// inconsistent, and a later repair will reconstruct garbage. This is known
+/// as the "write hole".
+///
+/// To prevent this, this function first records the new data and parity in
+/// a [`Journal`], then writes to the devices, and only then clears the
+/// journal. After a crash, [`recover_devices`] replays any update left in
+/// the journal:
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let mut devices = b"Hello World!".chunks(4)
+///     .map(|b| MemBlockDevice::new(b.to_vec()))
+///     .chain([MemBlockDevice::new(vec![0u8; 4]), MemBlockDevice::new(vec![0u8; 4])])
+///     .collect::<Vec<_>>();
+/// raid6::format_devices(&mut devices).unwrap();
+///
+/// // update
+/// let mut journal = MemJournal::new();
+/// raid6::update_devices(&mut devices, &mut journal, 1, 1, b"__").unwrap();
+/// assert_eq!(devices[1].get_ref(), b"o__o");
+///
+/// // on mount, replay any interrupted updates
+/// assert_eq!(raid6::recover_devices(&mut devices, &mut journal), Ok(false));
+/// ```
+///
+/// Note this requires alloc.
+///
+#[cfg(__if(__alloc))]
+pub fn update_devices<D, J>(
+    devices: &mut [D],
+    journal: &mut J,
+    j: usize,
+    off: usize,
+    new: &[__u],
+) -> Result<(), StreamError<D::Error>>
+where
+    D: BlockDevice<__u>,
+    J: Journal<__u, Error=D::Error>
+{
+    let intent = prepare_update_devices(devices, j, off, new)?;
+    journal.record(&intent).map_err(StreamError::Block)?;
+    apply_update_devices(devices, &intent)?;
+    journal.clear().map_err(StreamError::Block)
+}
+
+/// Replay any update left in a [`Journal`] after a crash.
+///
+/// Returns true if an update was replayed. This should be called before
+/// any other operations on the devices. See [`update_devices`] for more
+/// info.
+///
+/// Note this requires alloc.
+///
+#[cfg(__if(__alloc))]
+pub fn recover_devices<D, J>(
+    devices: &mut [D],
+    journal: &mut J,
+) -> Result<bool, StreamError<D::Error>>
+where
+    D: BlockDevice<__u>,
+    J: Journal<__u, Error=D::Error>
+{
+    match journal.pending().map_err(StreamError::Block)? {
+        Some(intent) => {
+            apply_update_devices(devices, &intent)?;
+            journal.clear().map_err(StreamError::Block)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
@@ -28,10 +28,15 @@
 use __crate::traits::TryFrom;
 use core::slice;
 use core::fmt;
+use core::mem::size_of;
 
+#[cfg(__if(__alloc))]
 extern crate alloc;
+#[cfg(__if(__alloc))]
 use alloc::vec::Vec;
+#[cfg(__if(__alloc))]
 use alloc::vec;
+#[cfg(__if(__alloc))]
 use alloc::borrow::Cow;
 
 
@@ -39,7 +44,8 @@ use alloc::borrow::Cow;
 //
 // Reed-Solomon can correct ECC_SIZE known erasures and ECC_SIZE/2 unknown
 // erasures. DATA_SIZE is arbitrary, however the total size is limited to
-// 255 bytes in a GF(256) field.
+// the number of non-zero elements in the field, 255 bytes in a GF(256) field,
+// or 65535 symbols in a GF(2^16) field.
 //
 
 /// Maximum size of the original data in bytes.
@@ -51,6 +57,13 @@ pub const ECC_SIZE:   usize = __ecc_size;
 /// Size of the codeword, [`DATA_SIZE`] + [`ECC_SIZE`], in bytes.
 pub const BLOCK_SIZE: usize = DATA_SIZE + ECC_SIZE;
 
+// each symbol needs a unique non-zero error location, so make sure our
+// field is big enough at compile time
+const _: () = assert!(
+    BLOCK_SIZE as u128 <= __gf::NONZEROS as u128,
+    "rs block size must be <= the number of non-zero elements in the field"
+);
+
 // The generator polynomial in Reed-Solomon is a polynomial with roots (f(x) = 0)
 // at fixed points (g^i) in the finite-field.
 //
@@ -66,13 +79,17 @@ pub const BLOCK_SIZE: usize = DATA_SIZE + ECC_SIZE;
 // compile time. However, this has a tendency to hit the limit of
 // const_eval_limit for large values of ECC_SIZE.
 //
-// The only current workaround for this is nightly + #![feature(const_eval_limit="0")].
+// On older versions of Rust, the only workaround for this is nightly +
+// #![feature(const_eval_limit="0")]. Newer versions of Rust replace the
+// limit with the long_running_const_eval lint, which we can allow, which
+// is needed for larger fields such as GF(2^16) with large ECC_SIZEs.
 //
 // See:
 // https://github.com/rust-lang/rust/issues/67217
 //
 
 /// The generator polynomial for this error-correction code.
+#[allow(unknown_lints, long_running_const_eval)]
 pub const GENERATOR_POLY: [__gf; ECC_SIZE+1] = {
     let mut g = [__gf::new(0); ECC_SIZE+1];
     g[ECC_SIZE] = __gf::new(1);
@@ -84,34 +101,62 @@ pub const GENERATOR_POLY: [__gf; ECC_SIZE+1] = {
     //        i
     //
     let mut i = 0usize;
+    let mut root = __gf::new(1);
     while i < ECC_SIZE {
-        // x - g^i
-        let root = [
-            __gf::new(1),
-            __gf::GENERATOR.naive_pow(i as __u),
-        ];
-
         // G(x)*(x - g^i)
-        let mut product = [__gf::new(0); ECC_SIZE+1];
-        let mut j = 0usize;
-        while j < i+1 {
-            let mut k = 0usize;
-            while k < root.len() {
-                product[product.len()-1-(j+k)] = product[product.len()-1-(j+k)].naive_add(
-                    g[g.len()-1-j].naive_mul(root[root.len()-1-k])
-                );
-                k += 1;
-            }
+        //
+        // note G(x) only has i+1 non-zero coefficients at this point, and
+        // each coefficient in the product only depends on the coefficient
+        // and the coefficient below it, so we can do this in-place
+        //
+        let mut j = ECC_SIZE-(i+1);
+        while j < ECC_SIZE {
+            g[j] = g[j+1].naive_sub(g[j].naive_mul(root));
             j += 1;
         }
-        g = product;
+        g[ECC_SIZE] = __gf::new(0).naive_sub(g[ECC_SIZE].naive_mul(root));
 
+        root = root.naive_mul(__gf::GENERATOR);
         i += 1;
     }
 
     g
 };
 
+/// Products of G(x), without its leading term, with every 4-bit nibble
+///
+/// ``` text
+/// GENERATOR_NIBBLES[0][x][j] = x*G[j+1]
+/// GENERATOR_NIBBLES[1][x][j] = (x << 4)*G[j+1]
+/// ```
+///
+/// This is only used for fields with <=8 bits, where it turns multiplying
+/// G(x) by a symbol into two row lookups.
+///
+#[cfg(__if(!__no_tables))]
+const GENERATOR_NIBBLES: [[[__gf; ECC_SIZE]; 16]; 2] = {
+    let mut table = [[[__gf::new(0); ECC_SIZE]; 16]; 2];
+    let mut x = 0;
+    while x < 16 {
+        let mut j = 0;
+        while j < ECC_SIZE {
+            // skip nibbles that don't fit in smaller fields
+            if x <= __gf::NONZEROS as usize {
+                table[0][x][j] = __gf::new(x as __u)
+                    .naive_mul(GENERATOR_POLY[j+1]);
+            }
+            if x << 4 <= __gf::NONZEROS as usize {
+                table[1][x][j] = __gf::new((x << 4) as __u)
+                    .naive_mul(GENERATOR_POLY[j+1]);
+            }
+            j += 1;
+        }
+        x += 1;
+    }
+
+    table
+};
+
 
 /// Error codes for Reed-Solomon
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -122,16 +167,71 @@ pub enum Error {
     /// - 2*errors + erasures > ECC_SIZE
     ///
     TooManyErrors,
+
+    /// A stream of codewords was truncated or had an invalid header,
+    /// see [`Decoder`]
+    InvalidFrame,
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::TooManyErrors => write!(f, "Too many errors to correct"),
+            Error::InvalidFrame => write!(f, "Invalid codeword framing"),
         }
     }
 }
 
+/// The symbols corrected in a codeword, see [`correct_with_report`]
+#[cfg(__if(__alloc))]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Corrections {
+    /// Locations of errors found at unknown locations, in increasing order
+    pub errors: Vec<usize>,
+
+    /// Locations of known erasures, in increasing order. Note erasures are
+    /// reported even if they didn't actually need correcting.
+    pub erasures: Vec<usize>,
+}
+
+#[cfg(__if(__alloc))]
+impl Corrections {
+    /// Returns the total number of errors and erasures corrected
+    pub fn len(&self) -> usize {
+        self.errors.len() + self.erasures.len()
+    }
+
+    /// Returns true if the codeword had no errors/erasures
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.erasures.is_empty()
+    }
+
+    /// Returns how many more erasures could have been corrected, where
+    /// each error at an unknown location costs two erasures
+    ///
+    /// A margin of zero means the codeword was at the limit of what
+    /// Reed-Solomon can correct.
+    ///
+    pub fn margin(&self) -> usize {
+        ECC_SIZE - (2*self.errors.len() + self.erasures.len())
+    }
+}
+
+
+/// The state of a codeword, see [`check`]
+#[cfg(__if(__alloc))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Status {
+    /// The codeword has no errors
+    Clean,
+
+    /// The codeword has errors, but they can be corrected
+    Correctable,
+
+    /// The codeword has too many errors to correct
+    Uncorrectable,
+}
+
 
 /// Evaluate a polynomial at x using Horner's method
 ///
@@ -188,6 +288,7 @@ fn poly_mul(f: &mut [__gf], g: &[__gf]) {
 ///
 /// Note both the quotient and remainder are left in the dividend
 ///
+#[cfg(__if(__alloc))]
 fn poly_divrem(f: &mut [__gf], g: &[__gf]) {
     debug_assert!(f.len() >= g.len());
 
@@ -206,6 +307,60 @@ fn poly_divrem(f: &mut [__gf], g: &[__gf]) {
     }
 }
 
+/// Multiply G(x), without its leading term, by a symbol and add it to
+/// ECC_SIZE symbols, `f[j] += q*G[j+1]`
+///
+/// This is the inner loop of both encoding and finding syndromes. G(x) is
+/// fixed, so for fields with <=8 bits we can look up the products of G(x)
+/// with each nibble of q, leaving only two xors per symbol, which the
+/// compiler is happy to vectorize.
+///
+/// Note that subtraction is addition in our binary-extension fields.
+///
+#[cfg(__if(!__no_tables))]
+#[inline]
+fn generator_mul_acc(f: &mut [__gf], q: __gf) {
+    debug_assert_eq!(f.len(), ECC_SIZE);
+    if __gf::NONZEROS as usize <= 255 {
+        let lo = &GENERATOR_NIBBLES[0][(q.get() & 0xf) as usize];
+        let hi = &GENERATOR_NIBBLES[1][(q.get() >> 4) as usize];
+        for ((x, lo), hi) in f.iter_mut().zip(lo).zip(hi) {
+            *x += *lo + *hi;
+        }
+    } else {
+        for (x, g) in f.iter_mut().zip(&GENERATOR_POLY[1..]) {
+            *x += q * *g;
+        }
+    }
+}
+
+/// Multiply G(x), without its leading term, by a symbol and add it to
+/// ECC_SIZE symbols, `f[j] += q*G[j+1]`
+#[cfg(__if(__no_tables))]
+#[inline]
+fn generator_mul_acc(f: &mut [__gf], q: __gf) {
+    debug_assert_eq!(f.len(), ECC_SIZE);
+    for (x, g) in f.iter_mut().zip(&GENERATOR_POLY[1..]) {
+        *x += q * *g;
+    }
+}
+
+/// Divide a polynomial by G(x) via synthetic division
+///
+/// This is the same as [`poly_divrem`], but G(x) is monic, so we can skip
+/// normalization, and we can use [`generator_mul_acc`].
+///
+#[cfg(__if(__alloc))]
+fn generator_divrem(f: &mut [__gf]) {
+    debug_assert!(f.len() >= GENERATOR_POLY.len());
+    for i in 0 .. (f.len() - ECC_SIZE) {
+        let q = f[i];
+        if q != __gf::new(0) {
+            generator_mul_acc(&mut f[i+1 .. i+1+ECC_SIZE], q);
+        }
+    }
+}
+
 // Encode using Reed-Solomon error correction
 //
 // Much like in CRC, we want to make the message a multiple of G(x),
@@ -237,6 +392,7 @@ fn poly_divrem(f: &mut [__gf], g: &[__gf]) {
 ///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
 /// ```
 ///
+#[cfg(__if(__alloc))]
 pub fn encode(message: &mut [__u]) {
     assert!(message.len() <= BLOCK_SIZE);
     assert!(message.len() >= ECC_SIZE);
@@ -251,9 +407,8 @@ pub fn encode(message: &mut [__u]) {
     divrem[data_len..].fill(0);
 
     // divide by our generator polynomial
-    poly_divrem(
-        unsafe { __gf::slice_from_slice_mut_unchecked(&mut divrem) },
-        &GENERATOR_POLY
+    generator_divrem(
+        unsafe { __gf::slice_from_slice_mut_unchecked(&mut divrem) }
     );
 
     // return message + remainder, this new message is a polynomial
@@ -261,25 +416,142 @@ pub fn encode(message: &mut [__u]) {
     message[data_len..].copy_from_slice(&divrem[data_len..]);
 }
 
+// Update a single symbol in an encoded codeword
+//
+// Since Reed-Solomon is linear, changing a data symbol by some delta d
+// changes the error-correction by the remainder of d*x^i after division
+// by G(x), where i is the power of x the symbol is at:
+//
+// ``` text
+// c'(x) = c(x) + d*x^i - (d*x^i % G(x))
+// ```
+//
+// We can find this remainder with an LFSR, the same as in CRC, which only
+// needs to look at the symbols after the updated symbol.
+//
+
+/// Update a single data symbol in a codeword, updating the error-correction
+/// to match.
+///
+/// This is equivalent to modifying the data symbol and calling [`encode`]
+/// again, but only needs to look at the symbols after `index`, which can
+/// be much cheaper when codewords are modified in-place.
+///
+/// Panics if `index` is outside of the data symbols, the first
+/// `codeword.len()-ECC_SIZE` symbols.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+32, 0u8);
+/// rs255w223::encode(&mut codeword);
+///
+/// rs255w223::update_symbol(&mut codeword, 0, b'J');
+///
+/// let mut expected = b"Jello World!".to_vec();
+/// expected.resize(expected.len()+32, 0u8);
+/// rs255w223::encode(&mut expected);
+/// assert_eq!(codeword, expected);
+/// ```
+///
+#[cfg(__if(__alloc))]
+pub fn update_symbol(codeword: &mut [__u], index: usize, new_value: __u) {
+    assert!(codeword.len() <= BLOCK_SIZE);
+    assert!(codeword.len() >= ECC_SIZE);
+    let data_len = codeword.len() - ECC_SIZE;
+    assert!(index < data_len);
+    let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+
+    // find the delta
+    let new_value = unsafe { __gf::new_unchecked(new_value) };
+    let delta = new_value - codeword[index];
+    if delta == __gf::new(0) {
+        return;
+    }
+    codeword[index] = new_value;
+
+    // find (d*x^i) % G(x), feeding d followed by zeros for the remaining
+    // data symbols into an LFSR
+    let mut rem = vec![__gf::new(0); ECC_SIZE];
+    for j in index..data_len {
+        let feedback = if j == index { delta } else { __gf::new(0) } + rem[0];
+        rem.rotate_left(1);
+        rem[ECC_SIZE-1] = __gf::new(0);
+        generator_mul_acc(&mut rem, feedback);
+    }
+
+    // update the error-correction
+    for k in 0..ECC_SIZE {
+        codeword[data_len+k] += rem[k];
+    }
+}
+
 /// Find syndromes, which should be zero if there are no errors
 ///
 /// ``` text
 /// Si = c'(g^i)
 /// ```
 ///
-fn find_syndromes(f: &[__gf]) -> Vec<__gf> {
-    let mut S = vec![];
-    for i in 0..ECC_SIZE {
-        S.push(
-            poly_eval(f, __gf::GENERATOR.pow(__u::try_from(i).unwrap()))
-        );
+/// Evaluating c'(x) at each g^i directly requires a serial chain of
+/// multiplications per syndrome. Instead, we first find the remainder
+/// r(x) = c'(x) % G(x) with an LFSR, the same as in update_symbol, which
+/// uses [`generator_mul_acc`]. Since G(g^i) = 0, c'(g^i) = r(g^i), and
+/// r(x) only has ECC_SIZE terms:
+///
+/// ``` text
+/// Si = c'(g^i) = (c'(x) % G(x))(g^i)
+/// ```
+///
+/// Note we don't use xmul_acc (`widening_mul_acc`) here. It accumulates
+/// unreduced polynomial products, but the division above is only table
+/// lookups and xors, and each step of Horner's method needs a reduced
+/// product before the next multiply. Our fields may also be table-based,
+/// where a fused carry-less multiply would only add work.
+///
+/// This uses `rem` as scratch space for the remainder, both `rem` and `S`
+/// must be ECC_SIZE symbols.
+///
+fn find_syndromes_in(f: &[__gf], rem: &mut [__gf], S: &mut [__gf]) {
+    if ECC_SIZE == 0 {
+        return;
+    }
+
+    // find the remainder
+    rem.fill(__gf::new(0));
+    for &x in f {
+        let feedback = rem[0];
+        rem.rotate_left(1);
+        rem[ECC_SIZE-1] = x;
+        generator_mul_acc(rem, feedback);
+    }
+
+    // no remainder? no errors
+    if rem.iter().all(|x| *x == __gf::new(0)) {
+        S.fill(__gf::new(0));
+        return;
+    }
+
+    for (i, s) in S.iter_mut().enumerate() {
+        *s = poly_eval(rem, __gf::GENERATOR.pow(__u::try_from(i).unwrap()));
     }
+}
+
+/// Find syndromes, which should be zero if there are no errors
+///
+/// See [`find_syndromes_in`] for more info.
+///
+#[cfg(__if(__alloc))]
+fn find_syndromes(f: &[__gf]) -> Vec<__gf> {
+    let mut rem = vec![__gf::new(0); ECC_SIZE];
+    let mut S = vec![__gf::new(0); ECC_SIZE];
+    find_syndromes_in(f, &mut rem, &mut S);
     S
 }
 
 /// Find Forney syndromes, these hide known erasures from the original syndromes
 /// so error detection doesn't try (and possibly fail) to find known erasures
 ///
+#[cfg(__if(__alloc))]
 fn find_forney_syndromes(
     codeword: &[__gf],
     S: &[__gf],
@@ -306,6 +578,7 @@ fn find_forney_syndromes(
 ///        k
 /// ```
 ///
+#[cfg(__if(__alloc))]
 fn find_erasure_locator(codeword: &[__gf], erasures: &[usize]) -> Vec<__gf> {
     let mut Λ = vec![__gf::new(0); erasures.len()+1];
     let Λ_len = Λ.len();
@@ -324,14 +597,23 @@ fn find_erasure_locator(codeword: &[__gf], erasures: &[usize]) -> Vec<__gf> {
 /// Iteratively find the error locator polynomial using the
 /// Berlekamp-Massey algorithm when we don't know the location of errors
 ///
-fn find_error_locator(S: &[__gf]) -> Vec<__gf> {
+/// This writes Λ into a caller-provided buffer, using `prev_Λ` and `delta_Λ`
+/// as scratch space, all of which must be S.len()+1 symbols. Returns the
+/// number of leading zeros in Λ.
+///
+fn find_error_locator_in(
+    S: &[__gf],
+    Λ: &mut [__gf],
+    prev_Λ: &mut [__gf],
+    delta_Λ: &mut [__gf],
+) -> usize {
     // the current estimate for the error locator polynomial
-    let mut Λ = vec![__gf::new(0); S.len()+1];
+    Λ.fill(__gf::new(0));
     let Λ_len = Λ.len();
     Λ[Λ_len-1] = __gf::new(1);
 
-    let mut prev_Λ = Λ.clone();
-    let mut delta_Λ = Λ.clone();
+    prev_Λ.copy_from_slice(Λ);
+    delta_Λ.copy_from_slice(Λ);
 
     // the current estimate for the number of errors
     let mut v = 0;
@@ -346,31 +628,128 @@ fn find_error_locator(S: &[__gf]) -> Vec<__gf> {
 
         if delta != __gf::new(0) {
             if 2*v <= i {
-                core::mem::swap(&mut Λ, &mut prev_Λ);
-                poly_scale(&mut Λ, delta);
-                poly_scale(&mut prev_Λ, delta.recip());
+                Λ.swap_with_slice(prev_Λ);
+                poly_scale(Λ, delta);
+                poly_scale(prev_Λ, delta.recip());
                 v = i+1-v;
             }
 
-            delta_Λ.copy_from_slice(&prev_Λ);
-            poly_scale(&mut delta_Λ, delta);
-            poly_add(&mut Λ, &delta_Λ);
+            delta_Λ.copy_from_slice(prev_Λ);
+            poly_scale(delta_Λ, delta);
+            poly_add(Λ, delta_Λ);
         }
     }
 
+    // count leading zeros
+    Λ.iter().take_while(|x| **x == __gf::new(0)).count()
+}
+
+/// Find the error locator polynomial using the Berlekamp-Massey algorithm,
+/// see [`find_error_locator_in`]
+///
+#[cfg(__if(!__euclidean && __alloc))]
+fn find_error_locator(S: &[__gf]) -> Vec<__gf> {
+    let mut Λ = vec![__gf::new(0); S.len()+1];
+    let mut prev_Λ = Λ.clone();
+    let mut delta_Λ = Λ.clone();
+    let zeros = find_error_locator_in(S, &mut Λ, &mut prev_Λ, &mut delta_Λ);
+
     // trim leading zeros
-    let zeros = Λ.iter().take_while(|x| **x == __gf::new(0)).count();
     Λ.drain(0..zeros);
 
     Λ
 }
 
+/// Find the error locator polynomial using the extended Euclidean
+/// algorithm, also called Sugiyama's algorithm, when we don't know the
+/// location of errors
+///
+/// This solves the key equation:
+///
+/// ``` text
+/// Λ(x)*S(x) = Ω(x) mod x^2v
+/// ```
+///
+/// By running the extended Euclidean algorithm on x^2v and S(x), stopping
+/// when the remainder's degree drops below v. At this point the Bézout
+/// coefficient of S(x) is a scalar multiple of Λ(x).
+///
+/// This finds the same error locator as Berlekamp-Massey, but is a bit
+/// more straightforward to verify.
+///
+#[cfg(__if(__euclidean && __alloc))]
+fn find_error_locator(S: &[__gf]) -> Vec<__gf> {
+    // note polynomials here are little-endian, smallest coefficient first,
+    // which is the opposite of the rest of this file, the extended
+    // Euclidean algorithm is easier to follow this way
+    fn degree(f: &[__gf]) -> Option<usize> {
+        f.iter().rposition(|c| *c != __gf::new(0))
+    }
+
+    // r_0 = x^2v, r_1 = S(x)
+    let mut r_prev = vec![__gf::new(0); S.len()+1];
+    r_prev[S.len()] = __gf::new(1);
+    let mut r = S.to_vec();
+
+    // t_0 = 0, t_1 = 1
+    let mut t_prev = vec![__gf::new(0)];
+    let mut t = vec![__gf::new(1)];
+
+    while let Some(r_degree) = degree(&r) {
+        // stop when deg(r_i) < v
+        if 2*r_degree < S.len() {
+            break;
+        }
+
+        // divide r_i-1 by r_i, q_i = r_i-1 / r_i, r_i+1 = r_i-1 % r_i
+        let mut q = vec![__gf::new(0); r_prev.len()];
+        let mut rem = r_prev;
+        let r_lead = r[r_degree].recip();
+        while let Some(rem_degree) = degree(&rem).filter(|d| *d >= r_degree) {
+            let shift = rem_degree - r_degree;
+            let c = rem[rem_degree] * r_lead;
+            q[shift] = c;
+            for i in 0..r_degree+1 {
+                rem[i+shift] -= c * r[i];
+            }
+        }
+
+        // t_i+1 = t_i-1 - q_i*t_i
+        let mut t_next = vec![__gf::new(0); q.len()+t.len()];
+        t_next[..t_prev.len()].copy_from_slice(&t_prev);
+        for i in 0..q.len() {
+            for j in 0..t.len() {
+                t_next[i+j] -= q[i] * t[j];
+            }
+        }
+
+        r_prev = r;
+        r = rem;
+        t_prev = t;
+        t = t_next;
+    }
+
+    // normalize so Λ(0) = 1, if Λ(0) = 0 we've failed to find a valid
+    // error locator, but this will be caught when searching for roots
+    if let Some(c) = t[0].checked_recip() {
+        poly_scale(&mut t, c);
+    }
+
+    // trim leading zeros and convert to big-endian
+    let Λ_len = degree(&t).map(|d| d+1).unwrap_or(1);
+    t.truncate(Λ_len);
+    t.reverse();
+
+    t
+}
+
 /// Find roots of the error locator polynomial by brute force
 ///
 /// This just means we evaluate Λ(x) for all x locations in our
 /// message, if they equal 0, aka are a root, then we found the
 /// error location in our message.
 ///
+#[cfg(__if(__alloc))]
 fn find_error_locations(codeword: &[__gf], Λ: &[__gf]) -> Vec<usize> {
     let mut error_locations = vec![];
     for j in 0..codeword.len() {
@@ -415,6 +794,7 @@ fn find_error_locations(codeword: &[__gf], Λ: &[__gf]) -> Vec<usize> {
 ///        i=1
 /// ```
 ///
+#[cfg(__if(__alloc))]
 fn find_error_magnitudes(
     codeword: &[__gf],
     S: &[__gf],
@@ -468,6 +848,43 @@ fn find_error_magnitudes(
     error_magnitudes
 }
 
+/// Find the locations of errors at unknown locations, given a sorted
+/// set of known erasures
+///
+/// This returns [`Error::TooManyErrors`] if we can't find a root for every
+/// error, which means there are more errors than we can correct.
+///
+#[cfg(__if(__alloc))]
+fn find_errors(
+    codeword: &[__gf],
+    S: &[__gf],
+    erasures: &[usize]
+) -> Result<Vec<usize>, Error> {
+    // find Forney syndromes, hiding known erasures from the syndromes
+    let forney_S = find_forney_syndromes(codeword, S, erasures);
+
+    // find error locator polynomial
+    let Λ = find_error_locator(&forney_S);
+
+    // too many errors/erasures?
+    let error_count = Λ.len() - 1;
+    let erasure_count = erasures.len();
+    if error_count*2 + erasure_count > ECC_SIZE {
+        return Err(Error::TooManyErrors);
+    }
+
+    // find all error locations, if we don't find a root for every error
+    // there are more errors than we can correct
+    let errors = find_error_locations(codeword, &Λ);
+    if errors.len() != error_count
+        || errors.iter().any(|j| erasures.binary_search(j).is_ok())
+    {
+        return Err(Error::TooManyErrors);
+    }
+
+    Ok(errors)
+}
+
 /// Determine if codeword is correct and has no errors/erasures.
 ///
 /// This is quite a bit faster than actually finding the errors/erasures.
@@ -480,6 +897,7 @@ fn find_error_magnitudes(
 /// assert!(rs255w223::is_correct(&codeword));
 /// ```
 ///
+#[cfg(__if(__alloc))]
 pub fn is_correct(codeword: &[__u]) -> bool {
     let codeword = unsafe { __gf::slice_from_slice_unchecked(codeword) };
 
@@ -488,6 +906,57 @@ pub fn is_correct(codeword: &[__u]) -> bool {
     syndromes.iter().all(|s| *s == __gf::new(0))
 }
 
+/// Check if a codeword is clean, correctable, or uncorrectable, without
+/// correcting it.
+///
+/// This finds the locations of any errors, but stops before finding their
+/// magnitudes, and never modifies the codeword. This is cheaper than
+/// correcting a copy of the codeword, and useful for deciding if a more
+/// expensive repair is needed. Erasures are treated the same as in
+/// [`correct_errors_and_erasures`], pass an empty slice if there are no
+/// known erasures.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let codeword = b"xexlx xoxlx!\
+///     x\xa6x\xf8x\x15x\x6ex\xb6x\x12x\xbdx\xd3\
+///     x\x14x\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35".to_vec();
+/// assert_eq!(rs255w223::check(&codeword, &[]), rs255w223::Status::Correctable);
+///
+/// let codeword = b"xxxxxxxxxxxxxxxxxxxxxxxxx\
+///     x\x14x\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35".to_vec();
+/// assert_eq!(rs255w223::check(&codeword, &[]), rs255w223::Status::Uncorrectable);
+/// ```
+///
+#[cfg(__if(__alloc))]
+pub fn check(codeword: &[__u], erasures: &[usize]) -> Status {
+    let codeword = unsafe { __gf::slice_from_slice_unchecked(codeword) };
+    assert!(erasures.iter().all(|j| *j < codeword.len()));
+
+    // find syndromes, syndromes of all zero means there are no errors
+    let S = find_syndromes(codeword);
+    if S.iter().all(|s| *s == __gf::new(0)) {
+        return Status::Clean;
+    }
+
+    // duplicate erasures would give us a repeated root in our erasure
+    // locator, so remove them
+    let mut erasures = erasures.to_vec();
+    erasures.sort_unstable();
+    erasures.dedup();
+
+    // too many erasures?
+    if erasures.len() > ECC_SIZE {
+        return Status::Uncorrectable;
+    }
+
+    // can we find all of the errors?
+    match find_errors(codeword, &S, &erasures) {
+        Ok(_) => Status::Correctable,
+        Err(_) => Status::Uncorrectable,
+    }
+}
+
 /// Correct up to [`ECC_SIZE`] erasures at known locations.
 ///
 /// Returns the number of erasures, or [`Error::TooManyErrors`] if the codeword
@@ -506,6 +975,7 @@ pub fn is_correct(codeword: &[__u]) -> bool {
 ///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
 /// ```
 ///
+#[cfg(__if(__alloc))]
 pub fn correct_erasures(
     codeword: &mut [__u],
     erasures: &[usize]
@@ -565,6 +1035,7 @@ pub fn correct_erasures(
 ///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
 /// ```
 ///
+#[cfg(__if(__alloc))]
 pub fn correct_errors(codeword: &mut [__u]) -> Result<usize, Error> {
     let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
 
@@ -611,8 +1082,13 @@ pub fn correct_errors(codeword: &mut [__u]) -> Result<usize, Error> {
 /// Correct a mixture of errors and erasures, up to `2*errors+erasures <= ECC_SIZE`.
 ///
 /// Where erasures are at known locations and errors are at unknown locations.
-/// Errors must be <= [`ECC_SIZE`], erasures must be <= [`ECC_SIZE/2`](ECC_SIZE),
-/// and `2*errors+erasures` must be <= [`ECC_SIZE`].
+/// Knowing where the bad symbols are makes them half as expensive to correct,
+/// so erasures must be <= [`ECC_SIZE`], errors must be <= [`ECC_SIZE/2`](ECC_SIZE),
+/// and `2*errors+erasures` must be <= [`ECC_SIZE`]. This is useful for storage
+/// and packet-loss, where the locations of missing symbols are usually known.
+///
+/// Duplicate erasures are ignored, and erasures don't need to actually be
+/// corrupted. Panics if an erasure is outside of the codeword.
 ///
 /// Returns the number of errors and erasures, or [`Error::TooManyErrors`] if the
 /// codeword can not be corrected.
@@ -624,17 +1100,61 @@ pub fn correct_errors(codeword: &mut [__u]) -> Result<usize, Error> {
 ///     \x34x\xa7x\xd6x\xfdx\xc2x\x81x\x8ax\xc9x".to_vec();
 ///
 /// let erasures = (0..16).collect::<Vec<_>>();
-/// assert_eq!(rs255w223::correct(&mut codeword, &erasures), Ok(24));
+/// assert_eq!(rs255w223::correct_errors_and_erasures(&mut codeword, &erasures), Ok(24));
 /// assert_eq!(&codeword, b"Hello World!\
 ///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
 ///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
 /// ```
 ///
-pub fn correct(
+#[cfg(__if(__alloc))]
+pub fn correct_errors_and_erasures(
     codeword: &mut [__u],
     erasures: &[usize]
 ) -> Result<usize, Error> {
+    correct_with_report(codeword, erasures).map(|corrections| corrections.len())
+}
+
+/// Correct a mixture of errors and erasures, reporting where corrections
+/// were made.
+///
+/// This is the same as [`correct_errors_and_erasures`], but returns the
+/// locations of any errors and erasures as [`Corrections`]. This can be
+/// useful for monitoring the quality of a channel, or noticing when
+/// codewords are getting close to the limit of what can be corrected.
+///
+/// Returns the corrected errors and erasures, or [`Error::TooManyErrors`] if
+/// the codeword can not be corrected.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codeword = b"xxxxxxxxxxxx\
+///     xxxx\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34x\xa7x\xd6x\xfdx\xc2x\x81x\x8ax\xc9x".to_vec();
+///
+/// let erasures = (0..16).collect::<Vec<_>>();
+/// let corrections = rs255w223::correct_with_report(&mut codeword, &erasures)?;
+/// assert_eq!(corrections.errors, (29..44).step_by(2).collect::<Vec<_>>());
+/// assert_eq!(corrections.erasures, erasures);
+/// assert_eq!(corrections.margin(), 0);
+/// assert_eq!(&codeword, b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
+/// # Ok::<(), rs255w223::Error>(())
+/// ```
+///
+#[cfg(__if(__alloc))]
+pub fn correct_with_report(
+    codeword: &mut [__u],
+    erasures: &[usize]
+) -> Result<Corrections, Error> {
     let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+    assert!(erasures.iter().all(|j| *j < codeword.len()));
+
+    // duplicate erasures would give us a repeated root in our erasure
+    // locator, so remove them
+    let mut erasures = erasures.to_vec();
+    erasures.sort_unstable();
+    erasures.dedup();
 
     // too many erasures?
     if erasures.len() > ECC_SIZE {
@@ -644,24 +1164,15 @@ pub fn correct(
     // find syndromes, syndromes of all zero means there are no errors
     let S = find_syndromes(codeword);
     if S.iter().all(|s| *s == __gf::new(0)) {
-        return Ok(0);
-    }
-
-    // find Forney syndromes, hiding known erasures from the syndromes
-    let forney_S = find_forney_syndromes(codeword, &S, &erasures);
-
-    // find error locator polynomial
-    let Λ = find_error_locator(&forney_S);
-
-    // too many errors/erasures?
-    let error_count = Λ.len() - 1;
-    let erasure_count = erasures.len();
-    if error_count*2 + erasure_count > ECC_SIZE {
-        return Err(Error::TooManyErrors);
+        return Ok(Corrections {
+            errors: vec![],
+            erasures: vec![],
+        });
     }
 
-    // find all error locations
-    let mut error_locations = find_error_locations(codeword, &Λ);
+    // find the locations of any errors at unknown locations
+    let errors = find_errors(codeword, &S, &erasures)?;
+    let mut error_locations = errors.clone();
     error_locations.extend_from_slice(&erasures);
 
     // re-find error locator polynomial, this time including both 
@@ -687,6 +1198,1414 @@ pub fn correct(
         return Err(Error::TooManyErrors);
     }
 
-    Ok(error_locations.len())
+    Ok(Corrections {
+        errors,
+        erasures,
+    })
 }
 
+/// Correct a mixture of errors and erasures, up to `2*errors+erasures <= ECC_SIZE`.
+///
+/// This is the same as [`correct_errors_and_erasures`].
+///
+/// Returns the number of errors and erasures, or [`Error::TooManyErrors`] if the
+/// codeword can not be corrected.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codeword = b"xxxxxxxxxxxx\
+///     xxxx\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34x\xa7x\xd6x\xfdx\xc2x\x81x\x8ax\xc9x".to_vec();
+///
+/// let erasures = (0..16).collect::<Vec<_>>();
+/// assert_eq!(rs255w223::correct(&mut codeword, &erasures), Ok(24));
+/// assert_eq!(&codeword, b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
+/// ```
+///
+#[cfg(__if(__alloc))]
+pub fn correct(
+    codeword: &mut [__u],
+    erasures: &[usize]
+) -> Result<usize, Error> {
+    correct_errors_and_erasures(codeword, erasures)
+}
+
+
+// Decoding with caller-provided scratch space
+//
+// The above decoding functions allocate the syndromes, locator, and evaluator
+// polynomials on the heap. For devices without a heap, these variants instead
+// take a caller-provided scratch buffer. The size of this buffer only depends
+// on ECC_SIZE, n-k:
+//
+// ``` text
+// SCRATCH_SIZE = 5*(n-k) + 3
+// ```
+//
+// To avoid needing space for the error locations, we find the errata
+// locator polynomial for both errors and erasures up front, by multiplying
+// the error locator found with Berlekamp-Massey by the erasure locator:
+//
+// ``` text
+// Λ(x) = σ(x) * ∏ (1 - Xk*x)
+//               k
+// ```
+//
+// And correct each symbol as we find it during the Chien search. Though we
+// do need to search twice, once to make sure we can find every root before
+// modifying the codeword.
+//
+
+/// Size of the scratch buffer, in symbols, needed by
+/// [`correct_errors_with_scratch`] and [`correct_with_scratch`].
+///
+/// This is `5*ECC_SIZE + 3`.
+///
+pub const SCRATCH_SIZE: usize = 5*ECC_SIZE + 3;
+
+/// Correct up to [`ECC_SIZE/2`](ECC_SIZE) errors at unknown locations,
+/// using caller-provided scratch space instead of allocating.
+///
+/// This is the same as `correct_errors`, but takes a scratch buffer of at
+/// least [`SCRATCH_SIZE`] symbols.
+///
+/// Returns the number of errors, or [`Error::TooManyErrors`] if the codeword
+/// can not be corrected.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codeword = b"xexlx xoxlx!\
+///     x\xa6x\xf8x\x15x\x6ex\xb6x\x12x\xbdx\xd3\
+///     x\x14x\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35".to_vec();
+///
+/// let mut scratch = [0u8; rs255w223::SCRATCH_SIZE];
+/// assert_eq!(rs255w223::correct_errors_with_scratch(&mut codeword, &mut scratch), Ok(16));
+/// assert_eq!(&codeword, b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
+/// ```
+///
+pub fn correct_errors_with_scratch(
+    codeword: &mut [__u],
+    scratch: &mut [__u]
+) -> Result<usize, Error> {
+    correct_with_scratch(codeword, &[], scratch)
+}
+
+/// Correct a mixture of errors and erasures, up to `2*errors+erasures <= ECC_SIZE`,
+/// using caller-provided scratch space instead of allocating.
+///
+/// This is the same as `correct_errors_and_erasures`, but takes a scratch
+/// buffer of at least [`SCRATCH_SIZE`] symbols.
+///
+/// Duplicate erasures are ignored, and erasures don't need to actually be
+/// corrupted. Panics if an erasure is outside of the codeword, or if the
+/// scratch buffer is too small.
+///
+/// Returns the number of errors and erasures, or [`Error::TooManyErrors`] if the
+/// codeword can not be corrected.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codeword = b"xxxxxxxxxxxx\
+///     xxxx\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34x\xa7x\xd6x\xfdx\xc2x\x81x\x8ax\xc9x".to_vec();
+///
+/// let erasures = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+/// let mut scratch = [0u8; rs255w223::SCRATCH_SIZE];
+/// assert_eq!(rs255w223::correct_with_scratch(&mut codeword, &erasures, &mut scratch), Ok(24));
+/// assert_eq!(&codeword, b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
+/// ```
+///
+pub fn correct_with_scratch(
+    codeword: &mut [__u],
+    erasures: &[usize],
+    scratch: &mut [__u]
+) -> Result<usize, Error> {
+    let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+    assert!(erasures.iter().all(|j| *j < codeword.len()));
+    assert!(scratch.len() >= SCRATCH_SIZE);
+
+    // split up our scratch space
+    let scratch = unsafe {
+        __gf::slice_from_slice_mut_unchecked(&mut scratch[..SCRATCH_SIZE])
+    };
+    let (S, scratch) = scratch.split_at_mut(ECC_SIZE);
+    let (T, scratch) = scratch.split_at_mut(ECC_SIZE);
+    let (Λ, scratch) = scratch.split_at_mut(ECC_SIZE+1);
+    let (B, C) = scratch.split_at_mut(ECC_SIZE+1);
+
+    // find syndromes, syndromes of all zero means there are no errors
+    find_syndromes_in(codeword, T, S);
+    if S.iter().all(|s| *s == __gf::new(0)) {
+        return Ok(0);
+    }
+
+    // duplicate erasures would give us a repeated root in our erasure
+    // locator, we can't sort/dedup without allocating, so skip any
+    // erasures we've already seen
+    let unique_erasures = || {
+        erasures.iter()
+            .enumerate()
+            .filter(|(i, j)| !erasures[..*i].contains(j))
+            .map(|(_, j)| *j)
+    };
+    let erasure_count = unique_erasures().count();
+
+    // too many erasures?
+    if erasure_count > ECC_SIZE {
+        return Err(Error::TooManyErrors);
+    }
+
+    // find Forney syndromes, hiding known erasures from the syndromes
+    T.copy_from_slice(S);
+    for j in unique_erasures() {
+        let Xj = __gf::GENERATOR.pow(__u::try_from(codeword.len()-1-j).unwrap());
+        for i in 0 .. T.len()-1 {
+            T[i] = T[i+1] - T[i]*Xj;
+        }
+    }
+    let forney_len = ECC_SIZE - erasure_count;
+
+    // find error locator polynomial
+    let zeros = find_error_locator_in(
+        &T[..forney_len],
+        &mut Λ[..forney_len+1],
+        &mut B[..forney_len+1],
+        &mut C[..forney_len+1],
+    );
+
+    // too many errors/erasures?
+    let error_count = forney_len - zeros;
+    if error_count*2 + erasure_count > ECC_SIZE {
+        return Err(Error::TooManyErrors);
+    }
+
+    // find errata locator polynomial by multiplying in the erasures
+    //
+    // Λ(x) = σ(x) * ∏ (1 - Xk*x)
+    //               k
+    //
+    let Λ_len = error_count + erasure_count + 1;
+    Λ.copy_within(zeros..forney_len+1, Λ_len-(error_count+1));
+    let Λ = &mut Λ[..Λ_len];
+    Λ[..erasure_count].fill(__gf::new(0));
+    for j in unique_erasures() {
+        poly_mul(Λ, &[
+            -__gf::GENERATOR.pow(__u::try_from(codeword.len()-1-j).unwrap()),
+            __gf::new(1)
+        ]);
+    }
+
+    // find the errata evaluator polynomial
+    //
+    // Ω(x) = S(x)*Λ(x) mod x^2v
+    //
+    let Ω = T;
+    for k in 0..ECC_SIZE {
+        let mut sum = __gf::new(0);
+        for j in 0..Λ_len.min(k+1) {
+            sum += S[k-j] * Λ[Λ_len-1-j];
+        }
+        Ω[ECC_SIZE-1-k] = sum;
+    }
+
+    // find the formal derivative of Λ
+    //
+    // Λ'(x) = Σ i*Λi*x^(i-1)
+    //        i=1
+    //
+    let Λ_prime = &mut B[..Λ_len-1];
+    for i in 1..Λ_len {
+        let mut sum = __gf::new(0);
+        for _ in 0..i {
+            sum += Λ[Λ_len-1-i];
+        }
+        Λ_prime[Λ_len-2-(i-1)] = sum;
+    }
+
+    // find all errata locations, if we don't find a root for every
+    // error/erasure there are more errors than we can correct
+    let roots = (0..codeword.len())
+        .filter(|j| {
+            let Xj = __gf::GENERATOR.pow(__u::try_from(codeword.len()-1-j).unwrap());
+            poly_eval(Λ, Xj.recip()) == __gf::new(0)
+        })
+        .count();
+    if roots != Λ_len-1 {
+        return Err(Error::TooManyErrors);
+    }
+
+    // correct the errata using Forney's algorithm
+    //
+    //        Xj*Ω(Xj^-1)
+    // Yj = - -----------
+    //         Λ'(Xj^-1)
+    //
+    for j in 0..codeword.len() {
+        let Xj = __gf::GENERATOR.pow(__u::try_from(codeword.len()-1-j).unwrap());
+        if poly_eval(Λ, Xj.recip()) == __gf::new(0) {
+            let Yj = (-Xj*poly_eval(Ω, Xj.recip()))
+                .checked_div(poly_eval(Λ_prime, Xj.recip()))
+                .unwrap_or(__gf::new(0));
+            codeword[j] += Yj;
+        }
+    }
+
+    // re-find the syndromes to check if we were able to find all errors
+    find_syndromes_in(codeword, &mut C[..ECC_SIZE], S);
+    if !S.iter().all(|s| *s == __gf::new(0)) {
+        return Err(Error::TooManyErrors);
+    }
+
+    Ok(error_count + erasure_count)
+}
+
+
+// Streaming encoder/decoder
+//
+// For messages larger than a single codeword, we split the message into
+// codewords, each prefixed with a one symbol header containing the number
+// of data symbols in the codeword. Since the header is part of the
+// codeword's data, it is protected by the error-correction.
+//
+// Every codeword is BLOCK_SIZE symbols except the last, which is always
+// shortened, even if this means it contains no data. This lets the decoder
+// find codeword boundaries without trusting the headers, and notice if the
+// stream was truncated.
+//
+// Note the header always fits in one symbol, since BLOCK_SIZE is limited
+// to the number of non-zero elements in the field.
+//
+
+/// Size of the header prefixed to each codeword when streaming, in symbols.
+#[cfg(__if(__alloc))]
+pub const HEADER_SIZE: usize = 1;
+
+/// Maximum number of data symbols in each codeword when streaming.
+#[cfg(__if(__alloc))]
+pub const FRAME_SIZE: usize = DATA_SIZE - HEADER_SIZE;
+
+/// A streaming Reed-Solomon encoder.
+///
+/// This accepts data incrementally, splitting it into codewords of at most
+/// [`BLOCK_SIZE`] symbols. Each codeword is prefixed with a small header,
+/// [`HEADER_SIZE`] symbols, so the stream can be decoded with a [`Decoder`]
+/// without knowing how the data was split up.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut encoder = rs255w223::Encoder::new();
+/// let mut codewords = vec![];
+/// for _ in 0..100 {
+///     codewords.extend(encoder.update(b"Hello World!"));
+/// }
+/// codewords.extend(encoder.finish());
+///
+/// // corrupt
+/// for i in (0..codewords.len()).step_by(32) {
+///     codewords[i] = b'x';
+/// }
+///
+/// // decode
+/// let mut decoder = rs255w223::Decoder::new();
+/// let mut data = decoder.update(&codewords)?;
+/// data.extend(decoder.finish()?);
+/// assert_eq!(data, b"Hello World!".repeat(100));
+/// # Ok::<(), rs255w223::Error>(())
+/// ```
+///
+#[cfg(__if(__alloc))]
+#[derive(Debug, Clone)]
+pub struct Encoder {
+    buffer: Vec<__u>,
+}
+
+#[cfg(__if(__alloc))]
+impl Encoder {
+    /// Create a new streaming encoder.
+    pub fn new() -> Self {
+        assert!(FRAME_SIZE > 0);
+        Self {
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Prefix a frame of data with its header, leaving space for the
+    /// error-correction
+    fn push_frame(data: &[__u], codewords: &mut Vec<__u>) {
+        debug_assert!(data.len() <= FRAME_SIZE);
+        let off = codewords.len();
+        codewords.push(__u::try_from(data.len()).unwrap());
+        codewords.extend_from_slice(data);
+        codewords.resize(off+HEADER_SIZE+data.len()+ECC_SIZE, __u::default());
+    }
+
+    /// Feed data into the encoder, returning any complete codewords.
+    ///
+    /// Data that doesn't fill a full codeword is buffered until the next
+    /// call to [`update`](Encoder::update) or [`finish`](Encoder::finish).
+    ///
+    pub fn update(&mut self, data: &[__u]) -> Vec<__u> {
+        self.buffer.extend_from_slice(data);
+
+        // only encode full codewords here, the last codeword must always
+        // be shortened
+        let frames = self.buffer.len() / FRAME_SIZE;
+        let mut codewords = Vec::with_capacity(frames*BLOCK_SIZE);
+        for frame in self.buffer.chunks_exact(FRAME_SIZE) {
+            Self::push_frame(frame, &mut codewords);
+        }
+        self.buffer.drain(..frames*FRAME_SIZE);
+
+        // full codewords are all the same size, so we can encode them
+        // side-by-side
+        encode_blocks(&mut codewords.chunks_exact_mut(BLOCK_SIZE).collect::<Vec<_>>());
+        codewords
+    }
+
+    /// Finish encoding, returning the last, shortened, codeword.
+    pub fn finish(self) -> Vec<__u> {
+        let mut codewords = Vec::with_capacity(BLOCK_SIZE);
+        Self::push_frame(&self.buffer, &mut codewords);
+        encode(&mut codewords);
+        codewords
+    }
+}
+
+#[cfg(__if(__alloc))]
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A streaming Reed-Solomon decoder.
+///
+/// This accepts codewords generated by an [`Encoder`] incrementally,
+/// correcting up to [`ECC_SIZE/2`](ECC_SIZE) errors in each codeword, and
+/// returning the original data.
+///
+/// See [`Encoder`] for an example.
+///
+#[cfg(__if(__alloc))]
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    buffer: Vec<__u>,
+}
+
+#[cfg(__if(__alloc))]
+impl Decoder {
+    /// Create a new streaming decoder.
+    pub fn new() -> Self {
+        assert!(FRAME_SIZE > 0);
+        Self {
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Decode a codeword, checking that its header matches its size
+    ///
+    /// If we already know the codeword is correct, we can skip correcting it.
+    ///
+    fn decode_frame(
+        codeword: &mut [__u],
+        correct: bool,
+        data: &mut Vec<__u>
+    ) -> Result<(), Error> {
+        if codeword.len() < HEADER_SIZE+ECC_SIZE {
+            return Err(Error::InvalidFrame);
+        }
+
+        if !correct {
+            correct_errors(codeword)?;
+        }
+        let len = codeword.len() - HEADER_SIZE - ECC_SIZE;
+        if usize::try_from(codeword[0]).ok() != Some(len) {
+            return Err(Error::InvalidFrame);
+        }
+
+        data.extend_from_slice(&codeword[HEADER_SIZE..HEADER_SIZE+len]);
+        Ok(())
+    }
+
+    /// Feed codewords into the decoder, returning the data from any
+    /// complete codewords.
+    ///
+    /// Returns [`Error::TooManyErrors`] if a codeword can not be corrected,
+    /// or [`Error::InvalidFrame`] if a codeword's header is invalid.
+    ///
+    pub fn update(&mut self, codewords: &[__u]) -> Result<Vec<__u>, Error> {
+        self.buffer.extend_from_slice(codewords);
+
+        // only decode full codewords here, the last codeword is always
+        // shortened, so we can't decode it until we know the stream
+        // has ended
+        let blocks = self.buffer.len() / BLOCK_SIZE;
+        let mut data = Vec::with_capacity(blocks*FRAME_SIZE);
+
+        // most codewords should be correct, and we can check these
+        // side-by-side, so only fall back to correct_errors when needed
+        let corrects = is_correct_blocks(
+            &self.buffer.chunks_exact(BLOCK_SIZE).collect::<Vec<_>>()
+        );
+        for (codeword, correct) in self.buffer.chunks_exact_mut(BLOCK_SIZE)
+            .zip(corrects)
+        {
+            Self::decode_frame(codeword, correct, &mut data)?;
+        }
+        self.buffer.drain(..blocks*BLOCK_SIZE);
+
+        Ok(data)
+    }
+
+    /// Finish decoding, returning the data from the last, shortened,
+    /// codeword.
+    ///
+    /// Returns [`Error::InvalidFrame`] if the stream was truncated.
+    ///
+    pub fn finish(mut self) -> Result<Vec<__u>, Error> {
+        let mut data = Vec::with_capacity(FRAME_SIZE);
+        Self::decode_frame(&mut self.buffer, false, &mut data)?;
+        Ok(data)
+    }
+}
+
+#[cfg(__if(__alloc))]
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+// Batched encoding/checking
+//
+// The LFSR in encode and find_syndromes multiplies a feedback symbol by
+// every coefficient of G(x), but the feedback symbol changes every step. So
+// for a single codeword, there is never a long run of symbols multiplied by
+// the same constant for our SIMD multiply-accumulate kernels (pshufb/GFNI)
+// to work on.
+//
+// But if we run the LFSRs of many codewords side-by-side, storing the
+// remainders column-wise, each step multiplies a row of feedback symbols,
+// one from each codeword, by the same coefficient of G(x):
+//
+// ``` text
+// rem[j][k] += G[j+1]*feedback[k]
+// ```
+//
+// Which is exactly what our SIMD kernels do. And since G(x) is fixed, we
+// only need to prepare the kernels' tables once for each coefficient.
+//
+// To check a codeword, c(x) = d(x)*x^ECC_SIZE + e(x), we can use the same
+// LFSR. c(x) % G(x) = 0 only if (d(x)*x^ECC_SIZE) % G(x) = e(x), which is
+// what encode would have written.
+//
+// Note this only works for fields with byte-sized symbols, other fields
+// fall back to processing each codeword separately.
+//
+
+/// Number of codewords to process side-by-side, this keeps the remainders,
+/// ECC_SIZE*BLOCKS_BATCH bytes, small enough to stay in cache
+#[cfg(__if(__alloc))]
+const BLOCKS_BATCH: usize = 256;
+
+/// Number of symbols to transpose from each codeword at a time
+#[cfg(__if(__alloc))]
+const BLOCKS_ROWS: usize = 8;
+
+/// Can we use our SIMD kernels? This requires byte-sized symbols
+#[cfg(__if(__alloc))]
+const BLOCKS_SIMD: bool = size_of::<__u>() == 1
+    && __gf::NONZEROS as usize <= 255;
+
+/// Prepare the coefficients of G(x), without its leading term, for our
+/// SIMD kernels
+#[cfg(__if(__alloc))]
+fn generator_prepared() -> Vec<__crate::internal::simd::Prepared8> {
+    let kernel = __crate::internal::simd::Kernel::detect();
+    GENERATOR_POLY[1..].iter()
+        .map(|g| {
+            // multiplication by a constant is linear, so all we need
+            // is the constant multiplied by each bit, skipping bits that
+            // don't fit in smaller fields
+            let mut basis = [0u8; 8];
+            for (j, b) in basis.iter_mut().enumerate() {
+                if 1usize << j <= __gf::NONZEROS as usize {
+                    *b = (*g * __gf::try_from(1u8 << j).unwrap()).get() as u8;
+                }
+            }
+            __crate::internal::simd::Prepared8::new(kernel, basis)
+        })
+        .collect()
+}
+
+/// Find the remainders of many codewords' data after division by G(x),
+/// side-by-side
+///
+/// This is the same LFSR as in update_symbol, finding
+/// (d(x)*x^ECC_SIZE) % G(x), except rem is stored column-wise, rem[j*n+k]
+/// is the j-th symbol of the k-th codeword's remainder. Shorter data is
+/// treated as though it were prefixed with zeros, the same as in encode.
+///
+#[cfg(__if(__alloc))]
+fn generator_rem_blocks(
+    prepared: &[__crate::internal::simd::Prepared8],
+    datas: &[&[u8]],
+    rem: &mut [u8]
+) {
+    let n = datas.len();
+    debug_assert_eq!(prepared.len(), ECC_SIZE);
+    debug_assert_eq!(rem.len(), ECC_SIZE*n);
+    rem.fill(0);
+    if ECC_SIZE == 0 || n == 0 {
+        return;
+    }
+
+    // rem is a ring buffer of rows, where row h is the first row, this
+    // avoids moving ECC_SIZE rows every step
+    let len = datas.iter().map(|data| data.len()).max().unwrap();
+    let mut cols = vec![0u8; BLOCKS_ROWS*n];
+    let mut feedback = vec![0u8; n];
+    let mut h = 0;
+    for i in (0..len).step_by(BLOCKS_ROWS) {
+        // transpose the next few symbols of each codeword, so each step can
+        // read a row of symbols, one from each codeword, note shorter data
+        // is aligned to the end
+        for (k, data) in datas.iter().enumerate() {
+            let off = len - data.len();
+            let x = if i >= off && i+BLOCKS_ROWS <= len {
+                <[u8; BLOCKS_ROWS]>::try_from(&data[i-off..i-off+BLOCKS_ROWS]).unwrap()
+            } else {
+                let mut x = [0u8; BLOCKS_ROWS];
+                for (j, x) in x.iter_mut().enumerate() {
+                    if i+j >= off && i+j < len {
+                        *x = data[i+j-off];
+                    }
+                }
+                x
+            };
+
+            for (j, x) in x.iter().enumerate() {
+                cols[j*n+k] = *x;
+            }
+        }
+
+        for col in cols.chunks_exact(n).take(len-i) {
+            // shift, the first row becomes the last row
+            let first = &mut rem[h*n..(h+1)*n];
+            for ((f, r), x) in feedback.iter_mut().zip(first.iter_mut()).zip(col) {
+                *f = *r ^ *x;
+                *r = 0;
+            }
+            h = (h+1) % ECC_SIZE;
+
+            // rem += feedback*G(x)
+            let (before, after) = rem.split_at_mut(h*n);
+            let (prepared_after, prepared_before) = prepared.split_at(ECC_SIZE-h);
+            __crate::internal::simd::mul_acc8_rows(after, &feedback, prepared_after);
+            __crate::internal::simd::mul_acc8_rows(before, &feedback, prepared_before);
+        }
+    }
+
+    // rotate rows back into order
+    rem.rotate_left(h*n);
+}
+
+/// Encode many codewords.
+///
+/// This is equivalent to calling [`encode`] on each codeword, but for
+/// fields with byte-sized symbols, such as GF(256), this encodes the
+/// codewords side-by-side. This lets us use SIMD instructions when available
+/// (`gfni`/`avx2`/`ssse3` on x86_64, `neon` on aarch64), which is much
+/// faster than encoding each codeword separately. Each codeword may be a
+/// different length, up to [`BLOCK_SIZE`] bytes.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codewords = b"Hello World!".chunks(4)
+///     .map(|chunk| {
+///         let mut codeword = chunk.to_vec();
+///         codeword.resize(codeword.len()+32, 0u8);
+///         codeword
+///     })
+///     .collect::<Vec<_>>();
+/// rs255w223::encode_blocks(&mut codewords);
+///
+/// for codeword in &codewords {
+///     assert!(rs255w223::is_correct(codeword));
+/// }
+/// ```
+///
+#[cfg(__if(__alloc))]
+pub fn encode_blocks<B: AsMut<[__u]>>(codewords: &mut [B]) {
+    if !BLOCKS_SIMD {
+        for codeword in codewords {
+            encode(codeword.as_mut());
+        }
+        return;
+    }
+
+    let prepared = generator_prepared();
+    let mut rem = vec![0u8; ECC_SIZE*BLOCKS_BATCH];
+    for batch in codewords.chunks_mut(BLOCKS_BATCH) {
+        let n = batch.len();
+        let mut datas = Vec::with_capacity(n);
+        let mut eccs = Vec::with_capacity(n);
+        for codeword in batch.iter_mut() {
+            let codeword = codeword.as_mut();
+            assert!(codeword.len() <= BLOCK_SIZE);
+            assert!(codeword.len() >= ECC_SIZE);
+            let codeword = unsafe {
+                slice::from_raw_parts_mut(codeword.as_mut_ptr() as *mut u8, codeword.len())
+            };
+            let (data, ecc) = codeword.split_at_mut(codeword.len()-ECC_SIZE);
+            datas.push(&*data);
+            eccs.push(ecc);
+        }
+
+        let rem = &mut rem[..ECC_SIZE*n];
+        generator_rem_blocks(&prepared, &datas, rem);
+        for (k, ecc) in eccs.iter_mut().enumerate() {
+            for (j, x) in ecc.iter_mut().enumerate() {
+                *x = rem[j*n+k];
+            }
+        }
+    }
+}
+
+/// Determine if many codewords are correct and have no errors/erasures.
+///
+/// This is equivalent to calling [`is_correct`] on each codeword, but for
+/// fields with byte-sized symbols, such as GF(256), this checks the
+/// codewords side-by-side, the same as [`encode_blocks`].
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codewords = b"Hello World!".chunks(4)
+///     .map(|chunk| {
+///         let mut codeword = chunk.to_vec();
+///         codeword.resize(codeword.len()+32, 0u8);
+///         codeword
+///     })
+///     .collect::<Vec<_>>();
+/// rs255w223::encode_blocks(&mut codewords);
+///
+/// // corrupt
+/// codewords[1][0] = b'x';
+///
+/// assert_eq!(rs255w223::is_correct_blocks(&codewords), [true, false, true]);
+/// ```
+///
+#[cfg(__if(__alloc))]
+pub fn is_correct_blocks<B: AsRef<[__u]>>(codewords: &[B]) -> Vec<bool> {
+    if !BLOCKS_SIMD {
+        return codewords.iter()
+            .map(|codeword| is_correct(codeword.as_ref()))
+            .collect();
+    }
+
+    let prepared = generator_prepared();
+    let mut rem = vec![0u8; ECC_SIZE*BLOCKS_BATCH];
+    let mut corrects = Vec::with_capacity(codewords.len());
+    for batch in codewords.chunks(BLOCKS_BATCH) {
+        let n = batch.len();
+        let mut datas = Vec::with_capacity(n);
+        let mut eccs = Vec::with_capacity(n);
+        for codeword in batch.iter() {
+            let codeword = codeword.as_ref();
+            let codeword = unsafe {
+                slice::from_raw_parts(codeword.as_ptr() as *const u8, codeword.len())
+            };
+            let (data, ecc) = codeword.split_at(codeword.len().saturating_sub(ECC_SIZE));
+            datas.push(data);
+            eccs.push(ecc);
+        }
+
+        // compare against the error-correction, note codewords smaller
+        // than ECC_SIZE are entirely error-correction
+        let rem = &mut rem[..ECC_SIZE*n];
+        generator_rem_blocks(&prepared, &datas, rem);
+        corrects.extend(eccs.iter().enumerate().map(|(k, ecc)| {
+            let off = ECC_SIZE - ecc.len();
+            (0..ECC_SIZE).all(|j| {
+                rem[j*n+k] == if j < off { 0 } else { ecc[j-off] }
+            })
+        }));
+    }
+
+    corrects
+}
+
+// Rayon-parallel encoding/correction
+//
+// Codewords are independent, so when protecting large files or objects,
+// which are split into many codewords, the natural parallelism is to
+// encode/correct each codeword on a separate thread.
+//
+
+/// Encode many codewords in parallel.
+///
+/// This is equivalent to calling [`encode_blocks`], but processes batches
+/// of codewords on rayon's thread pool. Each codeword may be a different
+/// length, up to [`BLOCK_SIZE`] bytes.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codewords = b"Hello World!".chunks(4)
+///     .map(|chunk| {
+///         let mut codeword = chunk.to_vec();
+///         codeword.resize(codeword.len()+32, 0u8);
+///         codeword
+///     })
+///     .collect::<Vec<_>>();
+/// rs255w223::par_encode_blocks(&mut codewords);
+///
+/// for codeword in &codewords {
+///     assert!(rs255w223::is_correct(codeword));
+/// }
+/// ```
+///
+/// Note this requires the `rayon` feature.
+///
+#[cfg(__if(__rayon && __alloc))]
+pub fn par_encode_blocks<B: AsMut<[__u]> + Send>(codewords: &mut [B]) {
+    use __crate::internal::rayon::prelude::*;
+
+    codewords.par_chunks_mut(BLOCKS_BATCH)
+        .for_each(encode_blocks);
+}
+
+/// Correct many codewords in parallel.
+///
+/// This is equivalent to calling [`correct_errors`] on each codeword, but
+/// processes batches of codewords on rayon's thread pool. Each batch is
+/// first checked with [`is_correct_blocks`], so only codewords with errors
+/// need to be corrected.
+///
+/// Returns the result of each codeword, either the number of errors, or
+/// [`Error::TooManyErrors`] if that codeword can not be corrected. A
+/// codeword that can't be corrected doesn't prevent correcting the other
+/// codewords.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codewords = b"Hello World!".chunks(4)
+///     .map(|chunk| {
+///         let mut codeword = chunk.to_vec();
+///         codeword.resize(codeword.len()+32, 0u8);
+///         codeword
+///     })
+///     .collect::<Vec<_>>();
+/// rs255w223::par_encode_blocks(&mut codewords);
+///
+/// // corrupt
+/// for codeword in codewords.iter_mut() {
+///     codeword[0..4].fill(b'x');
+/// }
+///
+/// // correct
+/// let results = rs255w223::par_correct_blocks(&mut codewords);
+/// assert!(results.iter().all(|res| *res == Ok(4)));
+/// let message = codewords.iter()
+///     .flat_map(|codeword| &codeword[..codeword.len()-32])
+///     .copied()
+///     .collect::<Vec<_>>();
+/// assert_eq!(&message, b"Hello World!");
+/// ```
+///
+/// Note this requires the `rayon` feature.
+///
+#[cfg(__if(__rayon && __alloc))]
+pub fn par_correct_blocks<B: AsMut<[__u]> + Send>(
+    codewords: &mut [B]
+) -> Vec<Result<usize, Error>> {
+    use __crate::internal::rayon::prelude::*;
+
+    codewords.par_chunks_mut(BLOCKS_BATCH)
+        .flat_map_iter(|batch| {
+            let corrects = is_correct_blocks(
+                &batch.iter_mut()
+                    .map(|codeword| &*codeword.as_mut())
+                    .collect::<Vec<_>>()
+            );
+            batch.iter_mut()
+                .zip(corrects)
+                .map(|(codeword, correct)| {
+                    if correct {
+                        Ok(0)
+                    } else {
+                        correct_errors(codeword.as_mut())
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// Shard-oriented erasure coding
+//
+// Object stores often split data into k data shards and m parity shards,
+// where any k shards are enough to reconstruct the original data. We can
+// provide this by viewing the shards as columns of codewords, where the
+// i-th symbol of each shard forms a codeword.
+//
+// Rather than encoding/decoding each codeword one at a time, we can take
+// advantage of Reed-Solomon being linear. Each parity symbol is a linear
+// combination of the data symbols, so we can find a parity matrix P once,
+// where P[i][e] is the contribution of data shard i to parity shard e:
+//
+// ``` text
+// P[i][e] = (x^(n-1-i) % G(x))[e]
+// ```
+//
+// And to reconstruct, we just need to invert the rows of the generator
+// matrix [I|P] for any k remaining shards.
+//
+// Note the shards end up compatible with encode/correct_erasures, so any
+// column of shards is a valid codeword.
+//
+
+/// Find the parity matrix for k data shards, P[i][e] is the contribution
+/// of data shard i to parity shard e
+#[cfg(__if(__alloc))]
+fn find_parity_matrix(k: usize) -> Vec<Vec<__gf>> {
+    let mut P = vec![];
+    for i in 0..k {
+        // find x^(n-1-i) % G(x)
+        let mut divrem = vec![__gf::new(0); k-i+ECC_SIZE];
+        divrem[0] = __gf::new(1);
+        poly_divrem(&mut divrem, &GENERATOR_POLY);
+        P.push(divrem[k-i..].to_vec());
+    }
+    P
+}
+
+/// Invert a square matrix using Gauss-Jordan elimination
+///
+/// Returns None if the matrix is singular, which shouldn't happen for
+/// the rows of a Reed-Solomon generator matrix.
+///
+#[cfg(__if(__alloc))]
+fn matrix_invert(mut M: Vec<Vec<__gf>>) -> Option<Vec<Vec<__gf>>> {
+    let n = M.len();
+    let mut M_inv = (0..n)
+        .map(|i| {
+            let mut row = vec![__gf::new(0); n];
+            row[i] = __gf::new(1);
+            row
+        })
+        .collect::<Vec<_>>();
+
+    for col in 0..n {
+        let pivot = (col..n).find(|i| M[*i][col] != __gf::new(0))?;
+        M.swap(col, pivot);
+        M_inv.swap(col, pivot);
+
+        let c = M[col][col].recip();
+        poly_scale(&mut M[col], c);
+        poly_scale(&mut M_inv[col], c);
+
+        for i in 0..n {
+            let c = M[i][col];
+            if i != col && c != __gf::new(0) {
+                for j in 0..n {
+                    let x = M[col][j];
+                    M[i][j] -= c*x;
+                    let x = M_inv[col][j];
+                    M_inv[i][j] -= c*x;
+                }
+            }
+        }
+    }
+
+    Some(M_inv)
+}
+
+/// Encode parity shards from data shards.
+///
+/// The first `shards.len()-ECC_SIZE` shards are data shards, and the last
+/// [`ECC_SIZE`] shards are parity shards, which are overwritten. Any
+/// `shards.len()-ECC_SIZE` shards are enough to reconstruct all shards with
+/// [`reconstruct`].
+///
+/// The total number of shards is limited to at most [`BLOCK_SIZE`], and
+/// all shards must be the same length. The i-th symbol of each shard form
+/// a codeword, as would be generated by [`encode`].
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut shards = vec![
+///     b"Hello".to_vec(),
+///     b"World".to_vec(),
+/// ];
+/// shards.resize(2+32, vec![0u8; 5]);
+/// rs255w223::encode_shards(&mut shards);
+///
+/// // lose some shards
+/// let mut shards = shards.into_iter().map(Some).collect::<Vec<_>>();
+/// shards[0] = None;
+/// shards[1] = None;
+/// shards[2] = None;
+///
+/// // reconstruct
+/// rs255w223::reconstruct(&mut shards)?;
+/// assert_eq!(shards[0].as_deref(), Some(&b"Hello"[..]));
+/// assert_eq!(shards[1].as_deref(), Some(&b"World"[..]));
+/// # Ok::<(), rs255w223::Error>(())
+/// ```
+///
+#[cfg(__if(__alloc))]
+pub fn encode_shards<S: AsRef<[__u]> + AsMut<[__u]>>(shards: &mut [S]) {
+    assert!(shards.len() <= BLOCK_SIZE);
+    assert!(shards.len() >= ECC_SIZE);
+    let k = shards.len() - ECC_SIZE;
+    let len = shards.first().map(|s| s.as_ref().len()).unwrap_or(0);
+    assert!(shards.iter().all(|s| s.as_ref().len() == len));
+
+    let P = find_parity_matrix(k);
+    let (datas, paritys) = shards.split_at_mut(k);
+    for (e, parity) in paritys.iter_mut().enumerate() {
+        let parity = unsafe { __gf::slice_from_slice_mut_unchecked(parity.as_mut()) };
+        parity.fill(__gf::new(0));
+        for (i, data) in datas.iter().enumerate() {
+            let data = unsafe { __gf::slice_from_slice_unchecked(data.as_ref()) };
+            __gf::slice_mul_acc(parity, data, P[i][e]);
+        }
+    }
+}
+
+/// Reconstruct any missing shards, data or parity.
+///
+/// Missing shards are represented by `None`. The first
+/// `shards.len()-ECC_SIZE` shards are data shards, and the last
+/// [`ECC_SIZE`] shards are parity shards, as generated by
+/// [`encode_shards`]. Up to [`ECC_SIZE`] shards can be reconstructed.
+///
+/// Returns [`Error::TooManyErrors`] if too many shards are missing.
+///
+/// See [`encode_shards`] for an example.
+///
+#[cfg(__if(__alloc))]
+pub fn reconstruct(shards: &mut [Option<Vec<__u>>]) -> Result<(), Error> {
+    assert!(shards.len() <= BLOCK_SIZE);
+    assert!(shards.len() >= ECC_SIZE);
+    let k = shards.len() - ECC_SIZE;
+
+    // nothing to do?
+    let missing = shards.iter()
+        .enumerate()
+        .filter(|(_, s)| s.is_none())
+        .map(|(j, _)| j)
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    // too many missing shards?
+    if missing.len() > ECC_SIZE {
+        return Err(Error::TooManyErrors);
+    }
+
+    let len = shards.iter().flatten().next().map(|s| s.len()).unwrap_or(0);
+    assert!(shards.iter().flatten().all(|s| s.len() == len));
+
+    // choose any k remaining shards, and invert their rows of the
+    // generator matrix [I|P]
+    let P = find_parity_matrix(k);
+    let present = shards.iter()
+        .enumerate()
+        .filter(|(_, s)| s.is_some())
+        .map(|(j, _)| j)
+        .take(k)
+        .collect::<Vec<_>>();
+    let M = present.iter()
+        .map(|&j| {
+            (0..k)
+                .map(|i| {
+                    if j < k {
+                        __gf::from(i == j)
+                    } else {
+                        P[i][j-k]
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let M_inv = matrix_invert(M).ok_or(Error::TooManyErrors)?;
+
+    // reconstruct missing data shards
+    for &i in missing.iter().filter(|i| **i < k) {
+        let mut data = vec![__gf::new(0); len];
+        for (r, &j) in present.iter().enumerate() {
+            let shard = unsafe {
+                __gf::slice_from_slice_unchecked(shards[j].as_deref().unwrap())
+            };
+            __gf::slice_mul_acc(&mut data, shard, M_inv[i][r]);
+        }
+        shards[i] = Some(data.into_iter().map(|x| x.get()).collect());
+    }
+
+    // reconstruct missing parity shards
+    for &j in missing.iter().filter(|j| **j >= k) {
+        let mut parity = vec![__gf::new(0); len];
+        for i in 0..k {
+            let shard = unsafe {
+                __gf::slice_from_slice_unchecked(shards[i].as_deref().unwrap())
+            };
+            __gf::slice_mul_acc(&mut parity, shard, P[i][j-k]);
+        }
+        shards[j] = Some(parity.into_iter().map(|x| x.get()).collect());
+    }
+
+    Ok(())
+}
+
+// Experimental list decoding
+//
+// Beyond ECC_SIZE/2 errors, there may be more than one codeword that is
+// "closest" to our received codeword. A list decoder returns all of them,
+// letting the caller decide which is correct with some other means, such
+// as a checksum.
+//
+// This is an implementation of the Guruswami-Sudan algorithm, which works
+// on the evaluation view of Reed-Solomon. It turns out our codewords are
+// exactly the codewords of a generalized Reed-Solomon code, where each
+// symbol c_j is a polynomial f(x) with degree < DATA_SIZE evaluated at
+// X_j, and scaled by a column multiplier v_j:
+//
+// ``` text
+// c_j = v_j*f(X_j)
+//
+// X_j = g^(n-1-j)
+//
+//               1
+// v_j = ---------------
+//       ∏ (X_j - X_i)
+//      i≠j
+// ```
+//
+// Given a received codeword, we first find a bivariate polynomial Q(x,y),
+// with a bounded weighted degree, which has a zero of some multiplicity
+// at each (X_j, r_j/v_j). This is just a large system of linear equations.
+//
+// Any f(x) close enough to our received codeword must then be a y-root of
+// Q(x,y), Q(x,f(x)) = 0, which we can find with the Roth-Ruckenstein
+// algorithm.
+//
+// Note this is very slow! This is intended as a reference implementation,
+// and for recovering data that would otherwise be lost. It also uses a
+// brute-force search for roots, so it's limited to fields with <= 2^16
+// elements.
+//
+
+#[cfg(__if(__list && __alloc))]
+const _: () = assert!(
+    __gf::NONZEROS as u128 <= 0xffff,
+    "rs list decoding requires a field with <= 2^16 elements"
+);
+
+/// Find x^e, note e may be larger than our __u type
+#[cfg(__if(__list && __alloc))]
+fn list_pow(mut x: __gf, mut e: usize) -> __gf {
+    let mut y = __gf::new(1);
+    while e > 0 {
+        if e & 1 != 0 {
+            y *= x;
+        }
+        x *= x;
+        e >>= 1;
+    }
+    y
+}
+
+/// Find if the binomial coefficient C(a, b) is odd
+///
+/// In a field with characteristic 2, this is all we need to know, and
+/// thanks to Lucas's theorem, C(a, b) is odd iff the bits of b are a
+/// subset of the bits of a.
+///
+#[cfg(__if(__list && __alloc))]
+fn list_binomial_is_odd(a: usize, b: usize) -> bool {
+    b <= a && (a & b) == b
+}
+
+/// Find a non-zero bivariate polynomial Q(x,y) with (1,w)-weighted degree
+/// <= D, with a zero of multiplicity m at each point (xs[j], ys[j])
+///
+/// Q(x,y) is returned as a list of polynomials in x, little-endian, so
+/// Q[b][a] is the coefficient of x^a*y^b.
+///
+/// A point being a zero of multiplicity m means all of the Hasse
+/// derivatives with u+v < m are zero at that point:
+///
+/// ``` text
+///  Σ  C(a,u)*C(b,v)*Q[b][a]*x^(a-u)*y^(b-v) = 0
+/// a,b
+/// ```
+///
+/// Which gives us n*m*(m+1)/2 linear equations. If we have more unknown
+/// coefficients than equations, there must be a non-zero solution.
+///
+#[cfg(__if(__list && __alloc))]
+fn find_interpolation(
+    xs: &[__gf],
+    ys: &[__gf],
+    w: usize,
+    D: usize,
+    m: usize,
+) -> Vec<Vec<__gf>> {
+    // enumerate our monomials x^a*y^b with a+w*b <= D
+    let mut monomials = vec![];
+    for b in 0..D/w+1 {
+        for a in 0..D-w*b+1 {
+            monomials.push((a, b));
+        }
+    }
+
+    // build our linear equations
+    let mut rows = vec![];
+    for (&x, &y) in xs.iter().zip(ys) {
+        for u in 0..m {
+            for v in 0..m-u {
+                rows.push(monomials.iter()
+                    .map(|&(a, b)| {
+                        if list_binomial_is_odd(a, u) && list_binomial_is_odd(b, v) {
+                            list_pow(x, a-u) * list_pow(y, b-v)
+                        } else {
+                            __gf::new(0)
+                        }
+                    })
+                    .collect::<Vec<_>>());
+            }
+        }
+    }
+    debug_assert!(monomials.len() > rows.len());
+
+    // reduce to reduced row echelon form with Gauss-Jordan elimination
+    let mut pivots = vec![];
+    for col in 0..monomials.len() {
+        let row = pivots.len();
+        let pivot = match (row..rows.len()).find(|i| rows[*i][col] != __gf::new(0)) {
+            Some(pivot) => pivot,
+            None => continue,
+        };
+        rows.swap(row, pivot);
+
+        let c = rows[row][col].recip();
+        for x in rows[row].iter_mut() {
+            *x *= c;
+        }
+
+        for i in 0..rows.len() {
+            let c = rows[i][col];
+            if i != row && c != __gf::new(0) {
+                for j in col..monomials.len() {
+                    let x = rows[row][j];
+                    rows[i][j] -= c*x;
+                }
+            }
+        }
+
+        pivots.push(col);
+        if pivots.len() == rows.len() {
+            break;
+        }
+    }
+
+    // choose a free variable, and solve for the pivots
+    let free = (0..monomials.len())
+        .find(|col| pivots.binary_search(col).is_err())
+        .unwrap();
+    let mut Q = vec![vec![__gf::new(0); D+1]; D/w+1];
+    let (a, b) = monomials[free];
+    Q[b][a] = __gf::new(1);
+    for (row, &col) in pivots.iter().enumerate() {
+        let (a, b) = monomials[col];
+        Q[b][a] = -rows[row][free];
+    }
+
+    Q
+}
+
+/// Divide out any common powers of x in Q(x,y)
+#[cfg(__if(__list && __alloc))]
+fn list_reduce(Q: &mut [Vec<__gf>]) {
+    let shift = Q.iter()
+        .filter_map(|q| q.iter().position(|c| *c != __gf::new(0)))
+        .min()
+        .unwrap_or(0);
+    for q in Q.iter_mut() {
+        q.drain(..shift.min(q.len()));
+    }
+}
+
+/// Find all y-roots f(x) of Q(x,y) with degree < k using the
+/// Roth-Ruckenstein algorithm
+///
+/// For each root γ of Q(0,y), we know f(x) = γ + x*f'(x), where f'(x) is
+/// a y-root of Q'(x,y) = Q(x,x*y+γ)/x^s, so we can find f(x) one
+/// coefficient at a time.
+///
+#[cfg(__if(__list && __alloc))]
+fn find_y_roots(
+    Q: &[Vec<__gf>],
+    k: usize,
+    f: &mut Vec<__gf>,
+    roots: &mut Vec<Vec<__gf>>,
+) {
+    // find roots of Q(0,y) by brute force
+    let nonzeros = usize::try_from(__gf::NONZEROS).unwrap();
+    for γ in 0..nonzeros+1 {
+        let γ = unsafe { __gf::new_unchecked(__u::try_from(γ).unwrap()) };
+        let zero = Q.iter().rev()
+            .fold(__gf::new(0), |y, q| {
+                y*γ + q.first().copied().unwrap_or(__gf::new(0))
+            });
+        if zero != __gf::new(0) {
+            continue;
+        }
+
+        // find Q'(x,y) = Q(x,x*y+γ)/x^s
+        //
+        //          b
+        // (x*y+γ)^b = Σ C(b,j)*γ^(b-j)*x^j*y^j
+        //          j
+        //
+        let mut Q_ = vec![vec![]; Q.len()];
+        for (b, q) in Q.iter().enumerate() {
+            for j in 0..b+1 {
+                if !list_binomial_is_odd(b, j) {
+                    continue;
+                }
+
+                let c = list_pow(γ, b-j);
+                if Q_[j].len() < q.len()+j {
+                    Q_[j].resize(q.len()+j, __gf::new(0));
+                }
+                for (a, x) in q.iter().enumerate() {
+                    Q_[j][a+j] += c * x;
+                }
+            }
+        }
+        list_reduce(&mut Q_);
+
+        f.push(γ);
+        if Q_[0].iter().all(|c| *c == __gf::new(0)) {
+            // y divides Q'(x,y), so f(x) is a root
+            roots.push(f.clone());
+        }
+        if f.len() < k && Q_.iter().any(|q| q.iter().any(|c| *c != __gf::new(0))) {
+            find_y_roots(&Q_, k, f, roots);
+        }
+        f.pop();
+    }
+}
+
+/// Find all codewords that could be the original codeword, even beyond
+/// [`ECC_SIZE/2`](ECC_SIZE) errors.
+///
+/// This is an experimental list decoder using the Guruswami-Sudan
+/// algorithm. It returns a list of candidate codewords, sorted by the
+/// number of symbols that differ from the received codeword. The list
+/// may be empty if there are too many errors, and may contain more than
+/// one codeword, in which case some other means, such as a checksum, is
+/// needed to decide which is correct.
+///
+/// `multiplicity` controls how hard the list decoder tries, larger values
+/// can find codewords with more errors, but are much slower. A
+/// `multiplicity` of 1 is equivalent to Sudan's algorithm.
+///
+/// Note this is very slow, and only able to correct more than
+/// [`ECC_SIZE/2`](ECC_SIZE) errors for low-rate codes, where `ECC_SIZE`
+/// is large relative to the codeword size. This is also limited to
+/// fields with <= 2^16 elements.
+///
+/// Requires the `list` option.
+///
+#[cfg(__if(__list && __alloc))]
+pub fn correct_list(codeword: &[__u], multiplicity: usize) -> Vec<Vec<__u>> {
+    assert!(codeword.len() <= BLOCK_SIZE);
+    assert!(codeword.len() >= ECC_SIZE);
+    assert!(multiplicity > 0);
+    let codeword = unsafe { __gf::slice_from_slice_unchecked(codeword) };
+    let n = codeword.len();
+    let k = n - ECC_SIZE;
+    if k == 0 {
+        // only the zero codeword is valid
+        return vec![vec![__u::default(); n]];
+    }
+
+    // find our evaluation points and column multipliers
+    //
+    // X_j = g^(n-1-j)
+    //
+    //               1
+    // v_j = ---------------
+    //       ∏ (X_j - X_i)
+    //      i≠j
+    //
+    let xs = (0..n)
+        .map(|j| __gf::GENERATOR.pow(__u::try_from(n-1-j).unwrap()))
+        .collect::<Vec<_>>();
+    let vs = (0..n)
+        .map(|j| {
+            (0..n)
+                .filter(|i| *i != j)
+                .fold(__gf::new(1), |v, i| v * (xs[j] - xs[i]))
+                .recip()
+        })
+        .collect::<Vec<_>>();
+    let ys = codeword.iter().zip(&vs)
+        .map(|(r, v)| *r / *v)
+        .collect::<Vec<_>>();
+
+    // find the smallest weighted degree D that gives us more unknowns than
+    // equations, note we need w >= 1 for this to terminate
+    let m = multiplicity;
+    let w = core::cmp::max(k-1, 1);
+    let equations = n*m*(m+1)/2;
+    let mut D = 0;
+    while (0..D/w+1).map(|b| D-w*b+1).sum::<usize>() <= equations {
+        D += 1;
+    }
+
+    // find Q(x,y)
+    let mut Q = find_interpolation(&xs, &ys, w, D, m);
+    list_reduce(&mut Q);
+
+    // find y-roots of Q(x,y)
+    let mut roots = vec![];
+    find_y_roots(&Q, k, &mut vec![], &mut roots);
+
+    // map back to codewords, c_j = v_j*f(X_j)
+    let mut candidates = vec![];
+    for f in roots {
+        let candidate = xs.iter().zip(&vs)
+            .map(|(x, v)| *v * f.iter().rev().fold(__gf::new(0), |y, c| y*x + c))
+            .collect::<Vec<_>>();
+
+        // sanity check that this is actually a valid codeword, and that
+        // we haven't already found it
+        if !find_syndromes(&candidate).iter().all(|s| *s == __gf::new(0))
+            || candidates.iter().any(|(_, c)| *c == candidate)
+        {
+            continue;
+        }
+
+        let distance = candidate.iter().zip(codeword)
+            .filter(|(a, b)| a != b)
+            .count();
+        candidates.push((distance, candidate));
+    }
+
+    candidates.sort_by_key(|(distance, _)| *distance);
+    candidates.into_iter()
+        .map(|(_, candidate)| candidate.into_iter().map(|c| c.get()).collect())
+        .collect()
+}
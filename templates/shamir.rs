@@ -24,51 +24,104 @@
 //! See the [module-level documentation](../../shamir) for more info.
 //!
 
+use core::fmt;
 use __crate::internal::cfg_if::cfg_if;
 use __crate::internal::rand::Rng;
 use __crate::traits::TryFrom;
 use __crate::traits::FromLossy;
 
+#[cfg(__if(__alloc))]
 extern crate alloc;
+#[cfg(__if(__alloc))]
 use alloc::vec;
+#[cfg(__if(__alloc))]
 use alloc::vec::Vec;
 
+#[cfg(__if(__zeroize))]
+use __crate::internal::zeroize::Zeroize;
+#[cfg(__if(__zeroize))]
+use __crate::internal::zeroize::Zeroizing;
 
-/// Generate a random polynomial of a given degree, fixing f(0) = secret
-fn poly_random<R: Rng>(rng: &mut R, secret: __gf, degree: usize) -> Vec<__gf> {
-    let mut f = vec![secret];
-    for _ in 0..degree {
-        f.push(__gf::from_lossy(rng.gen_range(1..=__gf::NONZEROS)));
-    }
-    f
+
+/// Wrap buffers containing secret material so they are wiped on drop,
+/// if zeroize is enabled
+#[cfg(__if(__zeroize && __alloc))]
+fn secret_buf<T: Zeroize>(buf: Vec<T>) -> Zeroizing<Vec<T>> {
+    Zeroizing::new(buf)
 }
 
-/// Evaluate a polynomial at x using Horner's method
-fn poly_eval(f: &[__gf], x: __gf) -> __gf {
-    let mut y = __gf::new(0);
-    for c in f.iter().rev() {
-        y = y*x + c;
-    }
-    y
+#[cfg(__if(!__zeroize && __alloc))]
+fn secret_buf<T>(buf: Vec<T>) -> Vec<T> {
+    buf
 }
 
-/// Find f(0) using Lagrange interpolation
-fn poly_interpolate(xs: &[__gf], ys: &[__gf]) -> __gf {
-    assert!(xs.len() == ys.len());
 
-    let mut y = __gf::new(0);
-    for (i, (x0, y0)) in xs.iter().zip(ys).enumerate() {
-        let mut li = __gf::new(1);
-        for (j, (x1, _y1)) in xs.iter().zip(ys).enumerate() {
-            if i != j {
-                li *= x1 / (x1-x0);
-            }
-        }
+/// Check if a length in bytes is a multiple of the symbol size
+///
+/// Note the symbol size is often 1, which clippy doesn't like
+///
+#[cfg(__if(__alloc))]
+#[allow(clippy::modulo_one)]
+fn is_symbol_aligned(len: usize) -> bool {
+    len % core::mem::size_of::<__u>() == 0
+}
+
+
+/// Error codes for Shamir's secret-sharing
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// A share is invalid, such as failing to verify against its integrity
+    /// tag or having an x-coordinate of zero, this contains the index of the
+    /// share
+    InvalidShare(usize),
 
-        y += li*y0;
+    /// An encoded share is malformed or truncated
+    InvalidEncoding,
+
+    /// An encoded share uses an unsupported version of the encoding
+    UnsupportedVersion(u8),
+
+    /// An encoded share was generated over a different finite-field
+    MismatchedField,
+
+    /// Shares disagree on their threshold, chunk, label, or length
+    MismatchedShares,
+
+    /// Fewer shares than the threshold were provided
+    TooFewShares,
+
+    /// Two shares have the same x-coordinate, this contains the index of
+    /// the second share
+    DuplicateShare(usize),
+
+    /// Robust reconstruction can fail if more than (n-k)/2 shares are
+    /// invalid
+    TooManyErrors,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidShare(i) => write!(f, "Share {} is invalid", i),
+            Error::InvalidEncoding => write!(f, "Invalid share encoding"),
+            Error::UnsupportedVersion(v) => write!(f, "Unsupported share encoding version {}", v),
+            Error::MismatchedField => write!(f, "Share is from a different finite-field"),
+            Error::MismatchedShares => write!(f, "Shares do not match"),
+            Error::TooFewShares => write!(f, "Too few shares to reconstruct"),
+            Error::DuplicateShare(i) => write!(f, "Share {} is a duplicate", i),
+            Error::TooManyErrors => write!(f, "Too many invalid shares to correct"),
+        }
     }
+}
+
+#[cfg(__if(__std))]
+impl std::error::Error for Error {}
 
-    y
+
+/// Find x^d, note x^d = x^(d mod NONZEROS) for non-zero x
+fn pow(x: __gf, d: usize) -> __gf {
+    let nonzeros = usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX);
+    x.pow(__u::try_from(d % nonzeros).unwrap())
 }
 
 /// Generate `n` shares requiring `k` shares to reconstruct.
@@ -76,34 +129,90 @@ fn poly_interpolate(xs: &[__gf], ys: &[__gf]) -> __gf {
 /// This scheme is limited to to the number of shares <= the number of
 /// non-zero elements in the field.
 ///
+#[cfg(__if(__alloc))]
 pub fn generate(secret: &[__u], n: usize, k: usize) -> Vec<Vec<__u>> {
-    // we only support up to 255 shares
+    let mut shares = vec![vec![__u::default(); secret.len()+1]; n];
+    generate_into(secret, &mut shares, k);
+    shares
+}
+
+/// Generate shares requiring `k` shares to reconstruct into caller-provided
+/// buffers.
+///
+/// The number of shares generated is determined by `shares.len()`, and each
+/// share must be exactly `secret.len()+1` in length. This does not allocate,
+/// so it can be used with fixed-size arrays:
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// let mut shares = [[0u8; 1+21]; 5];
+/// shamir::generate_into(b"secret secret secret!", &mut shares, 4);
+///
+/// let mut secret = [0u8; 21];
+/// shamir::reconstruct_into(&shares[..4], &mut secret);
+/// assert_eq!(&secret, b"secret secret secret!");
+/// ```
+///
+/// This scheme is limited to to the number of shares <= the number of
+/// non-zero elements in the field.
+///
+pub fn generate_into<S: AsMut<[__u]>>(secret: &[__u], shares: &mut [S], k: usize) {
+    // we only support up to NONZEROS shares, 255 for gf256
     assert!(
-        n <= usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX),
+        shares.len() <= usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX),
         "exceeded {} shares",
         __gf::NONZEROS
     );
-    let mut shares = vec![vec![]; n];
+    assert!(k > 0, "need at least 1 share to reconstruct");
+    assert!(
+        shares.iter_mut().all(|s| s.as_mut().len() == secret.len()+1),
+        "mismatched share length?"
+    );
     let mut rng = __rng();
 
     // we need to store the x coord somewhere, so just prepend the share with it
-    for i in 0..n {
-        shares[i].push(__u::try_from(i+1).unwrap());
+    for (i, share) in shares.iter_mut().enumerate() {
+        share.as_mut()[0] = __u::try_from(i+1).unwrap();
     }
 
-    for x in secret {
-        // generate a random polynomial for each byte
-        let f = poly_random(&mut rng, __gf::from_lossy(*x), k-1);
+    generate_ys(&mut rng, secret, shares, 1, k);
+}
 
-        // assign each share with a point at f(i)
-        for i in 0..n {
-            shares[i].push(__u::from(
-                poly_eval(&f, __gf::from_lossy(i+1))
-            ));
+/// Generate the y-coordinates of each share at `off`, assuming share `i`
+/// is at x-coordinate `i+1`
+fn generate_ys<R: Rng, S: AsMut<[__u]>>(
+    rng: &mut R,
+    secret: &[__u],
+    shares: &mut [S],
+    off: usize,
+    k: usize
+) {
+    for (j, y) in secret.iter().enumerate() {
+        // f(0) = secret
+        for share in shares.iter_mut() {
+            share.as_mut()[off+j] = __u::from(__gf::from_lossy(*y));
         }
-    }
 
-    shares
+        // generate a random polynomial for each symbol, accumulating each
+        // term into each share's point at f(i) as we go, this avoids needing
+        // to store the polynomial
+        for d in 1..k {
+            #[allow(unused_mut)]
+            let mut c = __gf::from_lossy(rng.gen_range(1..=__gf::NONZEROS));
+            for (i, share) in shares.iter_mut().enumerate() {
+                let share = share.as_mut();
+                let x = __gf::from_lossy(i+1);
+                share[off+j] = __u::from(
+                    __gf::from_lossy(share[off+j])
+                        + c*pow(x, d)
+                );
+            }
+
+            // wipe our coefficient, knowing the polynomial reveals the secret
+            #[cfg(__if(__zeroize))]
+            c.zeroize();
+        }
+    }
 }
 
 /// Attempt to reconstruct a secret from at least `k` shares.
@@ -111,26 +220,1226 @@ pub fn generate(secret: &[__u], n: usize, k: usize) -> Vec<Vec<__u>> {
 /// All shares must be the same length. If insufficient or invalid shares are
 /// provided, the result will be garbage.
 ///
+#[cfg(__if(__alloc))]
 pub fn reconstruct<S: AsRef<[__u]>>(shares: &[S]) -> Vec<__u> {
+    let len = shares.get(0).map(|s| s.as_ref().len()).unwrap_or(0);
+    if len == 0 {
+        return vec![];
+    }
+
+    let mut secret = vec![__u::default(); len-1];
+    reconstruct_into(shares, &mut secret);
+    secret
+}
+
+/// Generate `n` shares requiring `k` shares to reconstruct, interpreting
+/// a byte secret as a slice of field symbols.
+///
+/// Each symbol is read/written as little-endian bytes, so in wider fields
+/// each field evaluation covers multiple bytes of the secret:
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir32;
+/// let shares = shamir32::generate_bytes(b"secret secret secret", 5, 4);
+/// assert_eq!(shares[0].len(), 4+20);
+/// assert_eq!(shamir32::reconstruct_bytes(&shares[..4]), b"secret secret secret");
+/// ```
+///
+/// The length of the secret must be a multiple of the symbol size. The
+/// resulting shares match the format of `generate_stream`.
+///
+#[cfg(__if(__alloc))]
+pub fn generate_bytes(secret: &[u8], n: usize, k: usize) -> Vec<Vec<u8>> {
+    const SIZE: usize = core::mem::size_of::<__u>();
+    assert!(
+        is_symbol_aligned(secret.len()),
+        "secret not a multiple of the symbol size"
+    );
+
+    let symbols = secret_buf(
+        secret.chunks(SIZE)
+            .map(|chunk| __u::from_le_bytes(<_>::try_from(chunk).unwrap()))
+            .collect::<Vec<_>>()
+    );
+    generate(&symbols, n, k).into_iter()
+        .map(|share| {
+            share.iter()
+                .flat_map(|y| y.to_le_bytes())
+                .collect()
+        })
+        .collect()
+}
+
+/// Attempt to reconstruct a byte secret from at least `k` shares, where
+/// each share is a slice of field symbols stored as little-endian bytes.
+///
+/// All shares must be the same length, and a multiple of the symbol size.
+/// If insufficient or invalid shares are provided, the result will be
+/// garbage.
+///
+#[cfg(__if(__alloc))]
+pub fn reconstruct_bytes<S: AsRef<[u8]>>(shares: &[S]) -> Vec<u8> {
+    const SIZE: usize = core::mem::size_of::<__u>();
+    assert!(
+        shares.iter().all(|s| is_symbol_aligned(s.as_ref().len())),
+        "share not a multiple of the symbol size"
+    );
+
+    let shares = shares.iter()
+        .map(|share| {
+            share.as_ref().chunks(SIZE)
+                .map(|chunk| __u::from_le_bytes(<_>::try_from(chunk).unwrap()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let secret = secret_buf(reconstruct(&shares));
+    secret.iter()
+        .flat_map(|y| y.to_le_bytes())
+        .collect()
+}
+
+/// Generate `n` shares requiring `k` shares to reconstruct, wrapped in
+/// `Zeroizing` so they are wiped on drop.
+///
+/// Note this requires the `zeroize` feature.
+///
+#[cfg(__if(__zeroize && __alloc))]
+pub fn generate_zeroizing(
+    secret: &[__u],
+    n: usize,
+    k: usize
+) -> Vec<Zeroizing<Vec<__u>>> {
+    generate(secret, n, k).into_iter()
+        .map(Zeroizing::new)
+        .collect()
+}
+
+/// Attempt to reconstruct a secret from at least `k` shares, wrapped in
+/// `Zeroizing` so it is wiped on drop.
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// let shares = shamir::generate_zeroizing(b"secret secret secret!", 5, 4);
+/// let secret = shamir::reconstruct_zeroizing(&shares[..4]);
+/// assert_eq!(&secret[..], b"secret secret secret!");
+/// ```
+///
+/// Note this requires the `zeroize` feature.
+///
+#[cfg(__if(__zeroize && __alloc))]
+pub fn reconstruct_zeroizing<S: AsRef<[__u]>>(shares: &[S]) -> Zeroizing<Vec<__u>> {
+    Zeroizing::new(reconstruct(shares))
+}
+
+/// Attempt to reconstruct a secret from at least `k` shares into a
+/// caller-provided buffer.
+///
+/// All shares must be the same length, and `secret` must be exactly one
+/// less than the length of the shares. This does not allocate.
+///
+/// If insufficient or invalid shares are provided, the result will be
+/// garbage.
+///
+pub fn reconstruct_into<S: AsRef<[__u]>>(shares: &[S], secret: &mut [__u]) {
+    // find f(0)
+    interpolate_into(shares, __gf::new(0), secret);
+}
+
+/// Attempt to reconstruct a secret from at least `k` shares, checking the
+/// shares for common mistakes.
+///
+/// Unlike [`reconstruct`], this returns an error if no shares are provided,
+/// if the shares have different lengths, or if any shares are invalid or
+/// duplicates:
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// let shares = shamir::generate(b"secret secret secret!", 5, 4);
+///
+/// // oops, entered the same share twice
+/// assert_eq!(
+///     shamir::try_reconstruct(&[&shares[0], &shares[1], &shares[1], &shares[2]]),
+///     Err(shamir::Error::DuplicateShare(2))
+/// );
+/// assert_eq!(shamir::try_reconstruct(&shares[..4])?, b"secret secret secret!");
+/// # Ok::<(), shamir::Error>(())
+/// ```
+///
+/// Note this can't detect if fewer than `k` shares are provided, the
+/// result will still be garbage. See [`reconstruct_shares`] for shares
+/// that carry their threshold.
+///
+#[cfg(__if(__alloc))]
+pub fn try_reconstruct<S: AsRef<[__u]>>(shares: &[S]) -> Result<Vec<__u>, Error> {
+    let len = shares.first().map(|s| s.as_ref().len()).unwrap_or(0);
+    let mut secret = vec![__u::default(); len.saturating_sub(1)];
+    try_reconstruct_into(shares, &mut secret)?;
+    Ok(secret)
+}
+
+/// Attempt to reconstruct a secret from at least `k` shares into a
+/// caller-provided buffer, checking the shares for common mistakes.
+///
+/// Unlike [`reconstruct_into`], this returns an error if no shares are
+/// provided, if the shares do not match the length of `secret`, or if any
+/// shares are invalid or duplicates. This does not allocate.
+///
+pub fn try_reconstruct_into<S: AsRef<[__u]>>(
+    shares: &[S],
+    secret: &mut [__u]
+) -> Result<(), Error> {
+    check_shares(shares, secret.len()+1)?;
+    reconstruct_into(shares, secret);
+    Ok(())
+}
+
+/// Check that shares are the right length, non-zero, and unique
+fn check_shares<S: AsRef<[__u]>>(shares: &[S], len: usize) -> Result<(), Error> {
+    if shares.is_empty() {
+        return Err(Error::TooFewShares);
+    }
+
+    for (i, s0) in shares.iter().enumerate() {
+        let s0 = s0.as_ref();
+        if s0.len() != len {
+            return Err(Error::MismatchedShares);
+        }
+
+        // x=0 would be the secret itself, this is never a valid share
+        if s0[0] == __u::default() {
+            return Err(Error::InvalidShare(i));
+        }
+
+        // duplicate x-coordinates make interpolation divide by zero
+        if shares[..i].iter().any(|s1| s1.as_ref()[0] == s0[0]) {
+            return Err(Error::DuplicateShare(i));
+        }
+    }
+
+    Ok(())
+}
+
+/// Find f(x) for each symbol using Lagrange interpolation
+fn interpolate_into<S: AsRef<[__u]>>(shares: &[S], x: __gf, ys: &mut [__u]) {
     // matching lengths?
     assert!(
-        shares.windows(2).all(|ss| ss[0].as_ref().len() == ss[1].as_ref().len()),
+        shares.iter().all(|s| s.as_ref().len() == ys.len()+1),
         "mismatched share length?"
     );
 
-    let mut secret = vec![];
-    let len = shares.get(0).map(|s| s.as_ref().len()).unwrap_or(0);
-    if len == 0 {
-        return secret;
+    for y in ys.iter_mut() {
+        *y = __u::default();
     }
 
-    // x is prepended to each share
-    let xs = shares.iter().map(|s| __gf::from_lossy(s.as_ref()[0])).collect::<Vec<_>>();
-    for i in 1..len {
-        let ys = shares.iter().map(|s| __gf::from_lossy(s.as_ref()[i])).collect::<Vec<_>>();
-        secret.push(__u::from(poly_interpolate(&xs, &ys)));
+    // f(x) = sum l_i*y_i, where the Lagrange basis l_i only depends on the
+    // x-coords, so we find each l_i once and accumulate its contribution to
+    // every symbol, note the x coord is prepended to each share
+    for (i, s0) in shares.iter().enumerate() {
+        let x0 = __gf::from_lossy(s0.as_ref()[0]);
+        let mut num = __gf::new(1);
+        let mut den = __gf::new(1);
+        for (i_, s1) in shares.iter().enumerate() {
+            if i != i_ {
+                let x1 = __gf::from_lossy(s1.as_ref()[0]);
+                num *= x-x1;
+                den *= x0-x1;
+            }
+        }
+        let li = num / den;
+
+        for (y, y_) in ys.iter_mut().zip(&s0.as_ref()[1..]) {
+            *y = __u::from(__gf::from_lossy(*y) + li*__gf::from_lossy(*y_));
+        }
     }
+}
 
-    secret
+/// Find the Lagrange basis for f(0) given a set of x-coordinates.
+///
+/// The secret is a linear combination of the y-coordinates of the shares,
+/// f(0) = sum l_i*y_i, where the coefficients l_i, the Lagrange basis, only
+/// depend on the x-coordinates. This is the core of [`reconstruct`], and is
+/// exposed for building custom reconstruction pipelines on top of the
+/// field math:
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// # use ::gf256::gf::gf256;
+/// let shares = shamir::generate(b"secret", 5, 4);
+///
+/// // find the secret as a linear combination of the y-coordinates
+/// let basis = shamir::lagrange_basis_at_zero(&[1, 3, 4, 5]);
+/// let secret = (1..7)
+///     .map(|j| {
+///         [&shares[0], &shares[2], &shares[3], &shares[4]].iter()
+///             .zip(&basis)
+///             .map(|(share, l)| gf256(*l)*gf256(share[j]))
+///             .fold(gf256(0), |a, b| a+b)
+///     })
+///     .map(u8::from)
+///     .collect::<Vec<_>>();
+/// assert_eq!(secret, b"secret");
+/// ```
+///
+/// The x-coordinates must be non-zero and unique, otherwise the result
+/// will be garbage.
+///
+#[cfg(__if(__alloc))]
+pub fn lagrange_basis_at_zero(xs: &[__u]) -> Vec<__u> {
+    let mut basis = vec![__u::default(); xs.len()];
+    lagrange_basis_into(xs, &mut basis);
+    basis
+}
+
+/// Find the Lagrange basis for f(0) given a set of x-coordinates, writing
+/// the basis into a caller-provided buffer.
+///
+/// `basis` must be the same length as `xs`. This does not allocate.
+///
+/// The x-coordinates must be non-zero and unique, otherwise the result
+/// will be garbage.
+///
+pub fn lagrange_basis_into(xs: &[__u], basis: &mut [__u]) {
+    assert!(basis.len() == xs.len(), "mismatched basis length?");
+
+    for (i, l) in basis.iter_mut().enumerate() {
+        let x0 = __gf::from_lossy(xs[i]);
+        let mut li = __gf::new(1);
+        for (i_, x1) in xs.iter().enumerate() {
+            if i != i_ {
+                let x1 = __gf::from_lossy(*x1);
+                li *= x1 / (x1-x0);
+            }
+        }
+        *l = __u::from(li);
+    }
+}
+
+/// A reconstructor for a fixed set of x-coordinates.
+///
+/// When reconstructing many chunks of a secret from the same set of share
+/// holders, the Lagrange basis is the same every time. A `Reconstructor`
+/// finds the basis once, reducing reconstruction to a cheap matrix-vector
+/// product:
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// let shares = shamir::generate(b"secret secret secret!", 5, 4);
+///
+/// // reconstruct in chunks
+/// let reconstructor = shamir::Reconstructor::new(&[1, 2, 3, 4]);
+/// let mut secret = vec![];
+/// for j in (1..22).step_by(8) {
+///     let chunks = shares[..4].iter()
+///         .map(|share| &share[j..(j+8).min(22)])
+///         .collect::<Vec<_>>();
+///     secret.extend(reconstructor.reconstruct(&chunks));
+/// }
+/// assert_eq!(secret, b"secret secret secret!");
+/// ```
+///
+/// Note that unlike [`reconstruct`], the chunks passed to a `Reconstructor`
+/// do not contain the x-coordinate, and must be in the same order as the
+/// x-coordinates.
+///
+#[cfg(__if(__alloc))]
+#[derive(Debug, Clone)]
+pub struct Reconstructor {
+    xs: Vec<__u>,
+    basis: Vec<__gf>,
+}
+
+#[cfg(__if(__alloc))]
+impl Reconstructor {
+    /// Create a reconstructor for the given x-coordinates.
+    pub fn new(xs: &[__u]) -> Reconstructor {
+        let basis = lagrange_basis_at_zero(xs).into_iter()
+            .map(__gf::from_lossy)
+            .collect();
+        Reconstructor {
+            xs: xs.to_vec(),
+            basis,
+        }
+    }
+
+    /// Create a reconstructor for the x-coordinates of the given shares.
+    pub fn from_shares<S: AsRef<[__u]>>(shares: &[S]) -> Reconstructor {
+        Self::new(
+            &shares.iter().map(|s| s.as_ref()[0]).collect::<Vec<_>>()
+        )
+    }
+
+    /// The x-coordinates this reconstructor was created for.
+    pub fn xs(&self) -> &[__u] {
+        &self.xs
+    }
+
+    /// Reconstruct a chunk of the secret.
+    ///
+    /// `chunks` must contain one chunk of y-coordinates for each
+    /// x-coordinate, in the same order, and all chunks must be the same
+    /// length.
+    ///
+    pub fn reconstruct<S: AsRef<[__u]>>(&self, chunks: &[S]) -> Vec<__u> {
+        let len = chunks.first().map(|c| c.as_ref().len()).unwrap_or(0);
+        let mut secret = vec![__u::default(); len];
+        self.reconstruct_into(chunks, &mut secret);
+        secret
+    }
+
+    /// Reconstruct a chunk of the secret into a caller-provided buffer.
+    ///
+    /// `chunks` must contain one chunk of y-coordinates for each
+    /// x-coordinate, in the same order, and all chunks must be the same
+    /// length as `secret`. This does not allocate.
+    ///
+    pub fn reconstruct_into<S: AsRef<[__u]>>(&self, chunks: &[S], secret: &mut [__u]) {
+        assert!(chunks.len() == self.xs.len(), "mismatched number of chunks?");
+        // matching lengths?
+        assert!(
+            chunks.iter().all(|c| c.as_ref().len() == secret.len()),
+            "mismatched chunk length?"
+        );
+
+        for y in secret.iter_mut() {
+            *y = __u::default();
+        }
+
+        // f(0) = sum l_i*y_i, we iterate over each chunk in the outer loop
+        // to keep our memory accesses sequential
+        for (li, chunk) in self.basis.iter().zip(chunks) {
+            for (y, y_) in secret.iter_mut().zip(chunk.as_ref()) {
+                *y = __u::from(
+                    __gf::from_lossy(*y) + li*__gf::from_lossy(*y_)
+                );
+            }
+        }
+    }
+}
+
+/// Issue an additional share at the x-coordinate `x`, given at least `k`
+/// existing shares.
+///
+/// This finds the new share directly, without ever reconstructing the
+/// secret. This is useful for adding new share holders after the initial
+/// shares have been distributed:
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// let shares = shamir::generate(b"secret secret secret!", 5, 4);
+///
+/// // a new share holder joins
+/// let share = shamir::issue_share(&shares[..4], 6);
+/// let shares = [&shares[0], &shares[2], &shares[4], &share];
+/// assert_eq!(shamir::reconstruct(&shares), b"secret secret secret!");
+/// ```
+///
+/// Note that `x` must be non-zero, and should be different from any
+/// existing shares. If insufficient or invalid shares are provided, the
+/// resulting share will be garbage.
+///
+#[cfg(__if(__alloc))]
+pub fn issue_share<S: AsRef<[__u]>>(shares: &[S], x: __u) -> Vec<__u> {
+    let len = shares.first().map(|s| s.as_ref().len()).unwrap_or(1);
+    let mut share = vec![__u::default(); len];
+    issue_share_into(shares, x, &mut share);
+    share
+}
+
+/// Issue an additional share at the x-coordinate `x`, given at least `k`
+/// existing shares, into a caller-provided buffer.
+///
+/// `share` must be the same length as the existing shares. This does not
+/// allocate.
+///
+/// Note that `x` must be non-zero, and should be different from any
+/// existing shares. If insufficient or invalid shares are provided, the
+/// resulting share will be garbage.
+///
+pub fn issue_share_into<S: AsRef<[__u]>>(shares: &[S], x: __u, share: &mut [__u]) {
+    // f(0) is the secret!
+    assert!(x != __u::default(), "x-coordinate 0 would reveal the secret");
+    assert!(!share.is_empty(), "mismatched share length?");
+
+    share[0] = x;
+    interpolate_into(shares, __gf::new(x), &mut share[1..]);
 }
 
+
+/// Generate `n` shares of multiple secrets requiring `k` shares to
+/// reconstruct.
+///
+/// Each secret is still shared with independent polynomials, but with the
+/// same x-coordinates, so each share holder only needs a single share for
+/// a bundle of secrets:
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// let key = b"some key";
+/// let iv = b"an iv";
+/// let shares = shamir::generate_multi(&[&key[..], &iv[..]], 5, 4);
+///
+/// let secrets = shamir::reconstruct_multi(&shares[1..])?;
+/// assert_eq!(secrets, vec![key.to_vec(), iv.to_vec()]);
+/// # Ok::<(), shamir::Error>(())
+/// ```
+///
+/// After the x-coordinate, each share contains a header with the number of
+/// secrets and their lengths, each as a little-endian u32 stored one byte per
+/// symbol, followed by the y-coordinates of each secret in order.
+///
+#[cfg(__if(__alloc))]
+pub fn generate_multi<T: AsRef<[__u]>>(secrets: &[T], n: usize, k: usize) -> Vec<Vec<__u>> {
+    // header containing the number of secrets and their lengths
+    let mut header = vec![];
+    let mut push_u32 = |x: usize| {
+        let x = u32::try_from(x).expect("secret too large");
+        header.extend(x.to_le_bytes().iter().map(|b| __u::from(*b)));
+    };
+    push_u32(secrets.len());
+    for secret in secrets {
+        push_u32(secret.as_ref().len());
+    }
+
+    // each symbol gets an independent polynomial anyways, so we can
+    // just share the concatenated secrets
+    let mut secret = secret_buf(vec![]);
+    for secret_ in secrets {
+        secret.extend_from_slice(secret_.as_ref());
+    }
+
+    generate(&secret, n, k).into_iter()
+        .map(|share| {
+            let mut share_ = Vec::with_capacity(share.len() + header.len());
+            share_.push(share[0]);
+            share_.extend_from_slice(&header);
+            share_.extend_from_slice(&share[1..]);
+            share_
+        })
+        .collect()
+}
+
+/// Attempt to reconstruct multiple secrets from at least `k` shares
+/// generated by [`generate_multi`].
+///
+/// Returns [`Error::InvalidEncoding`] if the shares' header is malformed,
+/// or [`Error::MismatchedShares`] if the shares' headers don't match. If
+/// insufficient or invalid shares are provided, the result will be garbage.
+///
+#[cfg(__if(__alloc))]
+pub fn reconstruct_multi<S: AsRef<[__u]>>(shares: &[S]) -> Result<Vec<Vec<__u>>, Error> {
+    let first = match shares.first() {
+        Some(first) => first.as_ref(),
+        None => return Ok(vec![]),
+    };
+
+    // parse the header
+    let mut off = 1;
+    let mut take_u32 = || -> Result<usize, Error> {
+        let mut bytes = [0u8; 4];
+        for b in bytes.iter_mut() {
+            let symbol = *first.get(off).ok_or(Error::InvalidEncoding)?;
+            *b = u8::try_from(symbol).map_err(|_| Error::InvalidEncoding)?;
+            off += 1;
+        }
+        usize::try_from(u32::from_le_bytes(bytes)).map_err(|_| Error::InvalidEncoding)
+    };
+    let count = take_u32()?;
+    let lens = (0..count).map(|_| take_u32()).collect::<Result<Vec<_>, _>>()?;
+    let header = 1..off;
+    if lens.iter().try_fold(0usize, |a, b| a.checked_add(*b)) != Some(first.len()-off) {
+        return Err(Error::InvalidEncoding);
+    }
+
+    // all shares must agree
+    if !shares.iter().all(|share| {
+        let share = share.as_ref();
+        share.len() == first.len() && share[header.clone()] == first[header.clone()]
+    }) {
+        return Err(Error::MismatchedShares);
+    }
+
+    // strip the header and reconstruct
+    let shares = shares.iter()
+        .map(|share| {
+            let share = share.as_ref();
+            let mut share_ = Vec::with_capacity(share.len() - header.len());
+            share_.push(share[0]);
+            share_.extend_from_slice(&share[header.end..]);
+            share_
+        })
+        .collect::<Vec<_>>();
+    let secret = secret_buf(reconstruct(&shares));
+
+    let mut secrets = Vec::with_capacity(count);
+    let mut off = 0;
+    for len in lens {
+        secrets.push(secret[off..off+len].to_vec());
+        off += len;
+    }
+    Ok(secrets)
+}
+
+/// Solve a system of linear equations, stored as an augmented matrix with
+/// `cols+1` columns, using Gaussian elimination
+///
+/// If the system is underdetermined, free variables are set to zero.
+///
+#[cfg(__if(__alloc))]
+fn solve(matrix: &mut [__gf], rows: usize, cols: usize) -> Option<Vec<__gf>> {
+    let width = cols+1;
+    let mut pivots = vec![];
+    for c in 0..cols {
+        // find a pivot
+        let r = pivots.len();
+        let p = match (r..rows).find(|&p| matrix[p*width+c] != __gf::new(0)) {
+            Some(p) => p,
+            None => continue,
+        };
+        for c_ in 0..width {
+            matrix.swap(r*width+c_, p*width+c_);
+        }
+
+        // normalize and eliminate this column from all other rows
+        let scale = matrix[r*width+c].recip();
+        for c_ in 0..width {
+            matrix[r*width+c_] *= scale;
+        }
+        for r_ in 0..rows {
+            let factor = matrix[r_*width+c];
+            if r_ != r && factor != __gf::new(0) {
+                for c_ in 0..width {
+                    let x = matrix[r*width+c_];
+                    matrix[r_*width+c_] -= factor*x;
+                }
+            }
+        }
+
+        pivots.push(c);
+    }
+
+    // any remaining rows must be consistent
+    if (pivots.len()..rows).any(|r| matrix[r*width+cols] != __gf::new(0)) {
+        return None;
+    }
+
+    let mut solution = vec![__gf::new(0); cols];
+    for (r, c) in pivots.into_iter().enumerate() {
+        solution[c] = matrix[r*width+cols];
+    }
+    Some(solution)
+}
+
+/// Attempt to reconstruct a secret from at least `k` shares, correcting
+/// any invalid shares.
+///
+/// Each symbol across the shares forms a Reed-Solomon codeword, so given
+/// `n` shares, we can correct up to `(n-k)/2` invalid shares using the
+/// Berlekamp-Welch algorithm. This returns the secret and the indices of
+/// any invalid shares:
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// let mut shares = shamir::generate(b"secret secret secret!", 8, 4);
+/// shares[1][3] ^= 0x20;
+/// shares[5][9] ^= 0x10;
+///
+/// let (secret, invalid) = shamir::reconstruct_robust(&shares, 4)?;
+/// assert_eq!(secret, b"secret secret secret!");
+/// assert_eq!(invalid, vec![1, 5]);
+/// # Ok::<(), shamir::Error>(())
+/// ```
+///
+/// Returns [`Error::TooManyErrors`] if the invalid shares can't be
+/// corrected, though note that with enough invalid shares, the result
+/// may be garbage.
+///
+/// This is much more expensive than [`reconstruct`], `O(n^3)` per symbol.
+///
+#[cfg(__if(__alloc))]
+pub fn reconstruct_robust<S: AsRef<[__u]>>(
+    shares: &[S],
+    k: usize
+) -> Result<(Vec<__u>, Vec<usize>), Error> {
+    let len = shares.first().map(|s| s.as_ref().len()).unwrap_or(0);
+    // matching lengths?
+    assert!(
+        shares.iter().all(|s| s.as_ref().len() == len),
+        "mismatched share length?"
+    );
+    if k == 0 || shares.len() < k {
+        return Err(Error::TooFewShares);
+    }
+
+    // how many errors can we correct?
+    let n = shares.len();
+    let e = (n-k)/2;
+
+    // x coord is prepended to each share
+    let xs = shares.iter()
+        .map(|s| __gf::from_lossy(s.as_ref()[0]))
+        .collect::<Vec<_>>();
+
+    let mut secret = Vec::with_capacity(len.saturating_sub(1));
+    let mut invalid = vec![false; n];
+    let mut matrix = vec![__gf::new(0); n*(k+2*e+1)];
+    for j in 1..len {
+        // Berlekamp-Welch, find Q(x) with degree < k+e and E(x) with degree
+        // e, where E(x) is monic, such that Q(x_i) = y_i*E(x_i)
+        //
+        // each share gives us one linear equation:
+        //
+        // sum q_t*x_i^t - y_i*(sum e_t*x_i^t) = y_i*x_i^e
+        //
+        for (i, (x, s)) in xs.iter().zip(shares).enumerate() {
+            let y = __gf::from_lossy(s.as_ref()[j]);
+            let row = &mut matrix[i*(k+2*e+1)..(i+1)*(k+2*e+1)];
+            let mut p = __gf::new(1);
+            for t in 0..k+e {
+                row[t] = p;
+                p *= x;
+            }
+            let mut p = __gf::new(1);
+            for t in 0..e {
+                row[k+e+t] = y*p;
+                p *= x;
+            }
+            row[k+2*e] = y*p;
+        }
+
+        let solution = solve(&mut matrix, n, k+2*e).ok_or(Error::TooManyErrors)?;
+        let mut q = solution[..k+e].to_vec();
+        let mut e_ = solution[k+e..].to_vec();
+        e_.push(__gf::new(1));
+
+        // P(x) = Q(x)/E(x), which should have no remainder
+        let mut p = vec![__gf::new(0); k];
+        for t in (e..k+e).rev() {
+            let c = q[t];
+            p[t-e] = c;
+            for (s, e_s) in e_.iter().enumerate() {
+                q[t-e+s] -= c*e_s;
+            }
+        }
+        if q[..e].iter().any(|c| *c != __gf::new(0)) {
+            return Err(Error::TooManyErrors);
+        }
+
+        // f(0) = secret
+        secret.push(__u::from(p[0]));
+
+        // any shares that don't match P(x) are invalid
+        for (i, (x, s)) in xs.iter().zip(shares).enumerate() {
+            let mut y = __gf::new(0);
+            for c in p.iter().rev() {
+                y = y*x + c;
+            }
+            if y != __gf::from_lossy(s.as_ref()[j]) {
+                invalid[i] = true;
+            }
+        }
+    }
+
+    Ok((
+        secret,
+        invalid.into_iter().enumerate()
+            .filter(|(_, invalid)| *invalid)
+            .map(|(i, _)| i)
+            .collect()
+    ))
+}
+
+
+/// Number of symbols in a share's integrity tag.
+///
+/// This is chosen so the tag is at least 32 bits.
+///
+pub const TAG_SIZE: usize = {
+    let width = <__gf as __crate::traits::Field>::WIDTH;
+    32usize.div_ceil(width)
+};
+
+/// Find the integrity tag of a share.
+///
+/// The tag is made of the evaluations of the share, as a polynomial, at
+/// successive powers of the generator, similar to Reed-Solomon syndromes.
+/// This detects any single-symbol corruption, and most other corruption.
+///
+/// Note this is a checksum, not a MAC. It detects accidental corruption, but
+/// anyone can compute a valid tag for a tampered share.
+///
+pub fn tag(share: &[__u]) -> [__u; TAG_SIZE] {
+    let mut tag = [__u::default(); TAG_SIZE];
+    let mut g = __gf::new(1);
+    for t in tag.iter_mut() {
+        g *= __gf::GENERATOR;
+        let mut y = __gf::new(0);
+        for s in share {
+            y = y*g + __gf::from_lossy(*s);
+        }
+        *t = __u::from(y);
+    }
+    tag
+}
+
+/// Check if a tagged share matches its integrity tag.
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// let mut shares = shamir::generate_tagged(b"secret secret secret!", 5, 4);
+/// assert!(shares.iter().all(|share| shamir::verify(share)));
+///
+/// shares[1][3] ^= 0x20;
+/// assert!(!shamir::verify(&shares[1]));
+/// ```
+///
+pub fn verify(share: &[__u]) -> bool {
+    if share.len() < TAG_SIZE {
+        return false;
+    }
+
+    let (share, tag_) = share.split_at(share.len()-TAG_SIZE);
+    tag(share).iter().zip(tag_)
+        .fold(true, |valid, (a, b)| valid & (a == b))
+}
+
+/// Find the indices of any tagged shares that don't match their integrity
+/// tags.
+pub fn invalid_shares<'a, S: AsRef<[__u]>>(
+    shares: &'a [S]
+) -> impl Iterator<Item=usize> + 'a {
+    shares.iter().enumerate()
+        .filter(|(_, share)| !verify(share.as_ref()))
+        .map(|(i, _)| i)
+}
+
+/// Generate `n` shares requiring `k` shares to reconstruct, with an
+/// integrity tag appended to each share.
+///
+/// See [`tag`] for more info.
+///
+#[cfg(__if(__alloc))]
+pub fn generate_tagged(secret: &[__u], n: usize, k: usize) -> Vec<Vec<__u>> {
+    let mut shares = generate(secret, n, k);
+    for share in shares.iter_mut() {
+        let tag_ = tag(share);
+        share.extend_from_slice(&tag_);
+    }
+    shares
+}
+
+/// Attempt to reconstruct a secret from at least `k` tagged shares.
+///
+/// Returns [`Error::InvalidShare`] with the index of the first share that
+/// doesn't match its integrity tag, [`invalid_shares`] can find all of
+/// them. Note insufficient shares will still result in garbage.
+///
+#[cfg(__if(__alloc))]
+pub fn reconstruct_tagged<S: AsRef<[__u]>>(shares: &[S]) -> Result<Vec<__u>, Error> {
+    if let Some(i) = invalid_shares(shares).next() {
+        return Err(Error::InvalidShare(i));
+    }
+
+    let shares = shares.iter()
+        .map(|share| {
+            let share = share.as_ref();
+            &share[..share.len()-TAG_SIZE]
+        })
+        .collect::<Vec<_>>();
+    Ok(reconstruct(&shares))
+}
+
+
+/// A share with the metadata needed to reconstruct it later, see
+/// [`Share::to_bytes`] for the encoding.
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// let shares = shamir::generate_shares(b"secret secret secret!", 5, 4);
+/// let bytes = shares.iter().map(|share| share.to_bytes()).collect::<Vec<_>>();
+///
+/// // ...some time later...
+///
+/// let shares = bytes[1..].iter()
+///     .map(|bytes| shamir::Share::from_bytes(bytes))
+///     .collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(shares[0].threshold, 4);
+/// assert_eq!(shamir::reconstruct_shares(&shares)?, b"secret secret secret!");
+/// # Ok::<(), shamir::Error>(())
+/// ```
+///
+#[cfg(__if(__alloc))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    /// Number of shares needed to reconstruct the secret
+    pub threshold: usize,
+    /// Index of the chunk, for secrets that are shared in multiple chunks
+    pub chunk: u32,
+    /// An arbitrary label, may be empty
+    pub label: Vec<u8>,
+    /// The x-coordinate of the share
+    pub x: __u,
+    /// The y-coordinates of the share
+    pub ys: Vec<__u>,
+}
+
+#[cfg(__if(__alloc))]
+impl Share {
+    /// The current version of the share encoding
+    pub const VERSION: u8 = 1;
+
+    /// Magic bytes at the start of every encoded share
+    pub const MAGIC: [u8; 4] = *b"gfsh";
+
+    /// Create a share from a raw share, as returned by [`generate`], with
+    /// the x-coordinate prepended.
+    pub fn from_raw(raw: &[__u], threshold: usize) -> Share {
+        assert!(!raw.is_empty(), "empty share?");
+        Share {
+            threshold,
+            chunk: 0,
+            label: vec![],
+            x: raw[0],
+            ys: raw[1..].to_vec(),
+        }
+    }
+
+    /// Convert to a raw share, as accepted by [`reconstruct`], with the
+    /// x-coordinate prepended.
+    pub fn to_raw(&self) -> Vec<__u> {
+        let mut raw = Vec::with_capacity(1+self.ys.len());
+        raw.push(self.x);
+        raw.extend_from_slice(&self.ys);
+        raw
+    }
+
+    /// Encode a share into bytes.
+    ///
+    /// The encoding, with all integers little-endian:
+    ///
+    /// ``` text
+    /// offset      size        field
+    /// 0           4           magic, "gfsh"
+    /// 4           1           version, currently 1
+    /// 5           1           width of the finite-field in bits
+    /// 6           16          irreducible polynomial of the finite-field
+    /// 22          4           threshold
+    /// 26          4           chunk index
+    /// 30          2           label length, l
+    /// 32          l           label
+    /// 32+l        s           x-coordinate, s = size of a symbol in bytes
+    /// 32+l+s      n*s         y-coordinates
+    /// ```
+    ///
+    /// The width and polynomial identify the finite-field, so shares are
+    /// never reconstructed with the wrong field.
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        const SIZE: usize = core::mem::size_of::<__u>();
+        let mut bytes = Vec::with_capacity(32 + self.label.len() + SIZE*(1+self.ys.len()));
+        bytes.extend_from_slice(&Self::MAGIC);
+        bytes.push(Self::VERSION);
+        bytes.push(u8::try_from(<__gf as __crate::traits::Field>::WIDTH).unwrap());
+        bytes.extend_from_slice(&u128::from(__gf::POLYNOMIAL.0).to_le_bytes());
+        bytes.extend_from_slice(
+            &u32::try_from(self.threshold).expect("threshold too large").to_le_bytes()
+        );
+        bytes.extend_from_slice(&self.chunk.to_le_bytes());
+        bytes.extend_from_slice(
+            &u16::try_from(self.label.len()).expect("label too large").to_le_bytes()
+        );
+        bytes.extend_from_slice(&self.label);
+        bytes.extend_from_slice(&self.x.to_le_bytes());
+        for y in &self.ys {
+            bytes.extend_from_slice(&y.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decode a share from bytes, see [`Share::to_bytes`] for the encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Share, Error> {
+        const SIZE: usize = core::mem::size_of::<__u>();
+
+        // a small helper for taking bytes
+        fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Result<&'a [u8], Error> {
+            if bytes.len() < n {
+                return Err(Error::InvalidEncoding);
+            }
+            let (a, b) = bytes.split_at(n);
+            *bytes = b;
+            Ok(a)
+        }
+        fn symbol(bytes: &[u8]) -> __u {
+            let mut symbol = [0u8; SIZE];
+            symbol.copy_from_slice(bytes);
+            __u::from_le_bytes(symbol)
+        }
+
+        let mut bytes = bytes;
+        if take(&mut bytes, 4)? != Self::MAGIC {
+            return Err(Error::InvalidEncoding);
+        }
+        let version = take(&mut bytes, 1)?[0];
+        if version != Self::VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let width = take(&mut bytes, 1)?[0];
+        let polynomial = u128::from_le_bytes(<_>::try_from(take(&mut bytes, 16)?).unwrap());
+        if usize::from(width) != <__gf as __crate::traits::Field>::WIDTH
+            || polynomial != u128::from(__gf::POLYNOMIAL.0)
+        {
+            return Err(Error::MismatchedField);
+        }
+        let threshold = u32::from_le_bytes(<_>::try_from(take(&mut bytes, 4)?).unwrap());
+        let chunk = u32::from_le_bytes(<_>::try_from(take(&mut bytes, 4)?).unwrap());
+        let label_len = u16::from_le_bytes(<_>::try_from(take(&mut bytes, 2)?).unwrap());
+        let label = take(&mut bytes, usize::from(label_len))?.to_vec();
+        let x = symbol(take(&mut bytes, SIZE)?);
+        if !is_symbol_aligned(bytes.len()) {
+            return Err(Error::InvalidEncoding);
+        }
+        let ys = bytes.chunks(SIZE).map(symbol).collect();
+
+        Ok(Share {
+            threshold: usize::try_from(threshold).map_err(|_| Error::InvalidEncoding)?,
+            chunk,
+            label,
+            x,
+            ys,
+        })
+    }
+}
+
+/// Generate `n` shares requiring `k` shares to reconstruct, with the
+/// metadata needed to reconstruct them later.
+///
+/// See [`Share`] for more info.
+///
+#[cfg(__if(__alloc))]
+pub fn generate_shares(secret: &[__u], n: usize, k: usize) -> Vec<Share> {
+    generate(secret, n, k).iter()
+        .map(|raw| Share::from_raw(raw, k))
+        .collect()
+}
+
+/// Attempt to reconstruct a secret from at least `threshold` shares.
+///
+/// Unlike [`reconstruct`], this uses the share's metadata to check that
+/// the shares are from the same secret and that there are enough shares.
+///
+#[cfg(__if(__alloc))]
+pub fn reconstruct_shares(shares: &[Share]) -> Result<Vec<__u>, Error> {
+    let first = match shares.first() {
+        Some(first) => first,
+        None => return Err(Error::TooFewShares),
+    };
+    if !shares.iter().all(|share| {
+        share.threshold == first.threshold
+            && share.chunk == first.chunk
+            && share.label == first.label
+            && share.ys.len() == first.ys.len()
+    }) {
+        return Err(Error::MismatchedShares);
+    }
+    if shares.len() < first.threshold {
+        return Err(Error::TooFewShares);
+    }
+
+    let raw = shares.iter()
+        .map(|share| share.to_raw())
+        .collect::<Vec<_>>();
+    try_reconstruct(&raw)
+}
+
+
+// streaming std::io implementations, if enabled
+
+#[cfg(__if(__std))]
+extern crate std;
+
+/// Number of symbols we process at a time when streaming
+#[cfg(__if(__std && __alloc))]
+const STREAM_CHUNK: usize = 1024;
+
+/// Read until buf is full or we hit EOF, returning the number of bytes read
+#[cfg(__if(__std && __alloc))]
+fn read_full<R: std::io::Read>(r: &mut R, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut off = 0;
+    while off < buf.len() {
+        match r.read(&mut buf[off..]) {
+            Ok(0) => break,
+            Ok(diff) => off += diff,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {},
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(off)
+}
+
+/// Generate shares requiring `k` shares to reconstruct, streaming the
+/// secret from a [`Read`](std::io::Read) into a [`Write`](std::io::Write)
+/// for each share.
+///
+/// The number of shares generated is determined by `shares.len()`. Unlike
+/// [`generate`], this processes the secret in fixed-size chunks, so memory
+/// usage does not depend on the size of the secret:
+///
+/// ``` rust
+/// # use ::gf256::shamir::shamir;
+/// let mut shares = vec![vec![]; 5];
+/// shamir::generate_stream(&b"secret secret secret!"[..], &mut shares, 4)?;
+///
+/// let mut secret = vec![];
+/// let mut readers = shares.iter().map(|s| &s[..]).collect::<Vec<_>>();
+/// shamir::reconstruct_stream(&mut readers[..4], &mut secret)?;
+/// assert_eq!(secret, b"secret secret secret!");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// Each symbol is read/written as little-endian bytes, and the length of the
+/// secret must be a multiple of the symbol size.
+///
+/// Note this requires the `std` feature.
+///
+#[cfg(__if(__std && __alloc))]
+pub fn generate_stream<R: std::io::Read, W: std::io::Write>(
+    mut secret: R,
+    shares: &mut [W],
+    k: usize
+) -> std::io::Result<()> {
+    const SIZE: usize = core::mem::size_of::<__u>();
+
+    // we only support up to NONZEROS shares, 255 for gf256
+    assert!(
+        shares.len() <= usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX),
+        "exceeded {} shares",
+        __gf::NONZEROS
+    );
+    assert!(k > 0, "need at least 1 share to reconstruct");
+    let mut rng = __rng();
+
+    // x coord is prepended to each share
+    for (i, share) in shares.iter_mut().enumerate() {
+        share.write_all(&__u::try_from(i+1).unwrap().to_le_bytes())?;
+    }
+
+    let mut buf = secret_buf(vec![0u8; STREAM_CHUNK*SIZE]);
+    let mut symbols = secret_buf(vec![__u::default(); STREAM_CHUNK]);
+    let mut ys = secret_buf(vec![vec![__u::default(); STREAM_CHUNK]; shares.len()]);
+    loop {
+        let len = read_full(&mut secret, &mut buf)?;
+        if !is_symbol_aligned(len) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "secret not a multiple of the symbol size"
+            ));
+        }
+        if len == 0 {
+            break;
+        }
+
+        let count = len / SIZE;
+        for (j, symbol) in symbols[..count].iter_mut().enumerate() {
+            let mut bytes = [0u8; SIZE];
+            bytes.copy_from_slice(&buf[j*SIZE..(j+1)*SIZE]);
+            *symbol = __u::from_le_bytes(bytes);
+        }
+
+        generate_ys(&mut rng, &symbols[..count], &mut ys[..], 0, k);
+
+        for (share, ys) in shares.iter_mut().zip(ys.iter()) {
+            for (j, y) in ys[..count].iter().enumerate() {
+                buf[j*SIZE..(j+1)*SIZE].copy_from_slice(&y.to_le_bytes());
+            }
+            share.write_all(&buf[..len])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Attempt to reconstruct a secret from at least `k` shares, streaming
+/// the shares from a [`Read`](std::io::Read) for each share into a
+/// [`Write`](std::io::Write).
+///
+/// This processes the shares in fixed-size chunks, so memory usage does not
+/// depend on the size of the secret. All shares must be the same length. If
+/// insufficient or invalid shares are provided, the result will be garbage.
+///
+/// Note this requires the `std` feature.
+///
+#[cfg(__if(__std && __alloc))]
+pub fn reconstruct_stream<R: std::io::Read, W: std::io::Write>(
+    shares: &mut [R],
+    mut secret: W
+) -> std::io::Result<()> {
+    const SIZE: usize = core::mem::size_of::<__u>();
+
+    // x coord is prepended to each share
+    let mut xs = vec![];
+    let mut empty = 0;
+    for share in shares.iter_mut() {
+        let mut bytes = [0u8; SIZE];
+        match read_full(share, &mut bytes)? {
+            0 => empty += 1,
+            len if len == SIZE => {},
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "mismatched share length?"
+                ));
+            }
+        }
+        xs.push(__u::from_le_bytes(bytes));
+    }
+
+    // empty shares, empty secret
+    if empty == shares.len() {
+        return Ok(());
+    } else if empty > 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "mismatched share length?"
+        ));
+    }
+
+    // the Lagrange basis only depends on the x coords, so we only need
+    // to find it once
+    let ls = lagrange_basis_at_zero(&xs).into_iter()
+        .map(__gf::from_lossy)
+        .collect::<Vec<_>>();
+
+    let mut buf = secret_buf(vec![0u8; STREAM_CHUNK*SIZE]);
+    let mut ys = secret_buf(vec![__gf::new(0); STREAM_CHUNK]);
+    loop {
+        let mut len = None;
+        ys.fill(__gf::new(0));
+        for (share, li) in shares.iter_mut().zip(&ls) {
+            let len_ = read_full(share, &mut buf)?;
+            if len.unwrap_or(len_) != len_ || !is_symbol_aligned(len_) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "mismatched share length?"
+                ));
+            }
+            len = Some(len_);
+
+            for (j, y) in ys[..len_/SIZE].iter_mut().enumerate() {
+                let mut bytes = [0u8; SIZE];
+                bytes.copy_from_slice(&buf[j*SIZE..(j+1)*SIZE]);
+                *y += li*__gf::from_lossy(__u::from_le_bytes(bytes));
+            }
+        }
+
+        let len = len.unwrap_or(0);
+        if len == 0 {
+            break;
+        }
+
+        for (j, y) in ys[..len/SIZE].iter().enumerate() {
+            buf[j*SIZE..(j+1)*SIZE].copy_from_slice(&__u::from(*y).to_le_bytes());
+        }
+        secret.write_all(&buf[..len])?;
+    }
+
+    Ok(())
+}